@@ -1,40 +1,51 @@
-use std::{io::Write, process::Stdio};
-
 use anyhow::{anyhow, bail, Context, Result};
 
-/// Note that this is a quick implementation of commit signature verification that ignores a lot of what
-/// git does and can do, while focussing on the gist of it.
-/// For this to go into `gix`, one will have to implement many more options and various validation programs.
-pub fn verify(repo: gix::Repository, rev_spec: Option<&str>) -> Result<()> {
+/// Verify the signature of the commit at `rev_spec` or `HEAD`, and fail if it isn't good, similar to `git verify-commit`.
+pub fn verify(
+    repo: gix::Repository,
+    rev_spec: Option<&str>,
+    out: impl std::io::Write,
+    err: impl std::io::Write,
+) -> Result<()> {
     let rev_spec = rev_spec.unwrap_or("HEAD");
     let commit = repo
         .rev_parse_single(format!("{rev_spec}^{{commit}}").as_str())?
         .object()?
         .into_commit();
-    let (signature, signed_data) = commit
-        .signature()
-        .context("Could not parse commit to obtain signature")?
+    let verification = commit
+        .verify_signature()
+        .context("Could not verify the signature of the commit")?
         .ok_or_else(|| anyhow!("Commit at {rev_spec} is not signed"))?;
+    write_verification(&repo, &verification, rev_spec, out, err)
+}
 
-    let mut signature_storage = tempfile::NamedTempFile::new()?;
-    signature_storage.write_all(signature.as_ref())?;
-    let signed_storage = signature_storage.into_temp_path();
-
-    let mut cmd: std::process::Command = gix::command::prepare("gpg").into();
-    cmd.args(["--keyid-format=long", "--status-fd=1", "--verify"])
-        .arg(&signed_storage)
-        .arg("-")
-        .stdin(Stdio::piped());
-    gix::trace::debug!("About to execute {cmd:?}");
-    let mut child = cmd.spawn()?;
-    child
-        .stdin
-        .take()
-        .expect("configured")
-        .write_all(signed_data.to_bstring().as_ref())?;
-
-    if !child.wait()?.success() {
-        bail!("Command {cmd:?} failed");
+/// Write the result of a signature `verification` of the object at `rev_spec` and fail if it isn't good.
+pub(crate) fn write_verification(
+    repo: &gix::Repository,
+    verification: &gix::gpg::Verification,
+    rev_spec: &str,
+    mut out: impl std::io::Write,
+    mut err: impl std::io::Write,
+) -> Result<()> {
+    err.write_all(&verification.output)?;
+    writeln!(
+        out,
+        "{status} {format} signature by {signer} with key {key} (trust: {trust})",
+        status = verification.status.as_char(),
+        format = verification.format.as_str(),
+        signer = verification
+            .signer
+            .as_ref()
+            .map_or("<unknown>".into(), ToString::to_string),
+        key = verification
+            .key
+            .as_ref()
+            .map_or("<unknown>".into(), ToString::to_string),
+        trust = verification.trust.as_str(),
+    )?;
+    let min_trust = repo.signature_min_trust_level()?;
+    if !verification.is_good(min_trust) {
+        bail!("The signature of {rev_spec} is not good");
     }
     Ok(())
 }
@@ -3,3 +3,6 @@ pub use list::function::list;
 
 pub mod verify;
 pub use verify::function::verify;
+
+pub mod write;
+pub use write::function::write;
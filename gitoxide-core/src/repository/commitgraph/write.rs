@@ -0,0 +1,58 @@
+pub(crate) mod function {
+    use anyhow::{bail, Context};
+    use gix::bstr::BString;
+
+    use crate::OutputFormat;
+
+    /// Write a commit-graph with all commits reachable from `commits` in hexadecimal form, or from all references
+    /// if `None`, into a single file or a new layer of a split chain if `split` is set.
+    /// `changed_paths` controls whether changed-path Bloom filters are written, keeping them if they exist if `None`.
+    pub fn write(
+        repo: gix::Repository,
+        commits: Option<impl Iterator<Item = BString>>,
+        split: Option<gix::commitgraph::write::Split>,
+        changed_paths: Option<bool>,
+        mut out: impl std::io::Write,
+        mut err: impl std::io::Write,
+        format: OutputFormat,
+    ) -> anyhow::Result<()> {
+        if format != OutputFormat::Human {
+            bail!("Only human output is currently supported");
+        }
+        let tips: Vec<_> = match commits {
+            Some(commits) => commits
+                .map(|hex| -> anyhow::Result<_> {
+                    let id = gix::ObjectId::from_hex(hex.as_ref())
+                        .with_context(|| format!("Invalid commit object id '{hex}'"))?;
+                    Ok(repo.find_object(id)?.peel_to_kind(gix::object::Kind::Commit)?.id)
+                })
+                .collect::<Result<_, _>>()?,
+            None => repo
+                .references()?
+                .all()?
+                .filter_map(Result::ok)
+                .filter_map(|mut r| r.peel_to_id_in_place().ok())
+                .filter_map(|id| {
+                    id.object()
+                        .ok()?
+                        .peel_to_kind(gix::object::Kind::Commit)
+                        .ok()
+                        .map(|c| c.id)
+                })
+                .collect(),
+        };
+
+        let outcome = repo.write_commit_graph(tips, split, changed_paths)?;
+        for checksum in &outcome.checksums {
+            writeln!(out, "{checksum}")?;
+        }
+        writeln!(
+            err,
+            "Wrote {} commits, the commit-graph contains {} commits in {} file(s)",
+            outcome.num_written_commits,
+            outcome.num_commits,
+            outcome.checksums.len()
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+use anyhow::{anyhow, Context, Result};
+
+/// Verify the signature of the tag object at `rev_spec`, and fail if it isn't good, similar to `git verify-tag`.
+pub fn verify(repo: gix::Repository, rev_spec: &str, out: impl std::io::Write, err: impl std::io::Write) -> Result<()> {
+    let tag = repo
+        .rev_parse_single(rev_spec)?
+        .object()?
+        .try_into_tag()
+        .with_context(|| format!("{rev_spec} is not a tag object"))?;
+    let verification = tag
+        .verify_signature()
+        .context("Could not verify the signature of the tag")?
+        .ok_or_else(|| anyhow!("Tag at {rev_spec} is not signed"))?;
+    super::commit::write_verification(&repo, &verification, rev_spec, out, err)
+}
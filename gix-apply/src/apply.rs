@@ -0,0 +1,329 @@
+use std::borrow::Cow;
+
+use bstr::ByteSlice;
+
+use crate::{
+    whitespace,
+    whitespace::{is_blank, Problem},
+    BinaryHunk, Hunk, LineKind,
+};
+
+/// Options for use in [`text()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If set, like `-C<n>`, the amount of context lines that must match at least before and after each change.
+    ///
+    /// If a hunk doesn't apply, context lines are removed from its beginning and end one by one until it applies
+    /// or this limit is reached.
+    /// If `None`, all context lines must match, which is the default of `git apply`.
+    pub min_context: Option<usize>,
+    /// If `true`, like `--unidiff-zero`, don't expect hunks without context to be anchored at the beginning or end of the file.
+    pub unidiff_zero: bool,
+    /// If `true`, like `--ignore-whitespace`, ignore changes in the amount of whitespace when matching context and removed lines.
+    pub ignore_whitespace: bool,
+    /// The whitespace errors to look for in added lines.
+    pub whitespace: whitespace::Rules,
+    /// What to do with whitespace errors.
+    pub whitespace_action: whitespace::Action,
+}
+
+/// The result of [`text()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The new content.
+    pub data: Vec<u8>,
+    /// Information about where each hunk was applied, in order of the hunks.
+    pub hunks: Vec<HunkOutcome>,
+    /// All whitespace errors that were found, or fixed, in added lines.
+    pub whitespace_problems: Vec<Problem>,
+}
+
+/// Information about how a hunk was applied.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HunkOutcome {
+    /// The zero-based line in the new content at which the hunk was applied.
+    pub line: usize,
+    /// The amount of lines by which the position of the hunk differs from the position the patch expected.
+    pub offset: isize,
+    /// The amount of context lines that had to be ignored to apply the hunk.
+    pub reduced_context: usize,
+}
+
+///
+pub mod text {
+    use crate::whitespace::Problem;
+
+    /// The error returned by [`text()`](super::text()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Hunk #{} starting at line {old_start} does not apply", hunk + 1)]
+        HunkMismatch { hunk: usize, old_start: u32 },
+        #[error("{} line(s) add whitespace errors", problems.len())]
+        Whitespace { problems: Vec<Problem> },
+    }
+}
+
+///
+pub mod binary {
+    /// The error returned by [`binary()`](super::binary()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The binary delta could not be applied to the old version of the file")]
+        Delta,
+    }
+}
+
+/// Apply `hunks` to the text in `old` and return the new content, similar to `git apply`.
+///
+/// Each hunk is searched for at its expected position first, and then alternately after and before it. Hunks that
+/// start at the beginning or end of the file must match there unless `options` allow to remove context lines.
+pub fn text(old: &[u8], hunks: &[Hunk], options: Options) -> Result<Outcome, text::Error> {
+    let mut image: Vec<Cow<'_, [u8]>> = old.lines_with_terminator().map(Cow::Borrowed).collect();
+    let mut outcomes = Vec::with_capacity(hunks.len());
+    let mut problems = Vec::new();
+    let action = options.whitespace_action;
+
+    for (hunk_index, hunk) in hunks.iter().enumerate() {
+        let mut preimage: Vec<&[u8]> = Vec::new();
+        let mut postimage: Vec<PostLine<'_>> = Vec::new();
+        let (mut leading, mut trailing, mut seen_change) = (0, 0, false);
+        let (mut new_blank_lines_at_end, mut first_blank_line_at_end) = (0, 0);
+        for (line_index, line) in hunk.lines.iter().enumerate() {
+            let text = line.text.as_bytes();
+            match line.kind {
+                LineKind::Context => {
+                    postimage.push(PostLine::Context(preimage.len()));
+                    preimage.push(text);
+                    if seen_change {
+                        trailing += 1;
+                    } else {
+                        leading += 1;
+                    }
+                    if !is_blank(text) {
+                        new_blank_lines_at_end = 0;
+                    }
+                }
+                LineKind::Removed => {
+                    preimage.push(text);
+                    seen_change = true;
+                    trailing = 0;
+                    new_blank_lines_at_end = 0;
+                }
+                LineKind::Added => {
+                    seen_change = true;
+                    trailing = 0;
+                    let mut text = Cow::Borrowed(text);
+                    if action.is_checking() {
+                        let check = options.whitespace.check_line(&text);
+                        if !check.is_empty() {
+                            let fixed = match action {
+                                whitespace::Action::Fix => options.whitespace.fix_line(&text),
+                                _ => None,
+                            };
+                            problems.push(Problem {
+                                hunk: hunk_index,
+                                line: line_index,
+                                check,
+                                fixed: fixed.is_some(),
+                            });
+                            if let Some(fixed) = fixed {
+                                text = Cow::Owned(fixed.into());
+                            }
+                        }
+                    }
+                    if is_blank(&text) {
+                        if new_blank_lines_at_end == 0 {
+                            first_blank_line_at_end = line_index;
+                        }
+                        new_blank_lines_at_end += 1;
+                    } else {
+                        new_blank_lines_at_end = 0;
+                    }
+                    postimage.push(PostLine::Added(text));
+                }
+            }
+        }
+
+        let expected_pos = (hunk.new_start.saturating_sub(1) as usize).min(image.len());
+        let mut pos = expected_pos;
+        let mut match_beginning = hunk.old_start == 0 || (hunk.old_start == 1 && !options.unidiff_zero);
+        let mut match_end = !options.unidiff_zero && trailing == 0;
+        let (original_leading, original_trailing) = (leading, trailing);
+        let min_context = options.min_context.unwrap_or(usize::MAX);
+        let (mut pre, mut post) = (0..preimage.len(), 0..postimage.len());
+        let applied_pos = loop {
+            if let Some(found) = find_pos(
+                &image,
+                &preimage[pre.clone()],
+                pos,
+                match_beginning,
+                match_end,
+                &options,
+            ) {
+                break found;
+            }
+            if leading <= min_context && trailing <= min_context {
+                return Err(text::Error::HunkMismatch {
+                    hunk: hunk_index,
+                    old_start: hunk.old_start,
+                });
+            }
+            if match_beginning || match_end {
+                match_beginning = false;
+                match_end = false;
+                continue;
+            }
+            // Reduce the context on the side that has more of it, or on both sides if they are equal.
+            if leading >= trailing {
+                pre.start += 1;
+                post.start += 1;
+                pos = pos.saturating_sub(1);
+                leading -= 1;
+            }
+            if trailing > leading {
+                pre.end -= 1;
+                post.end -= 1;
+                trailing -= 1;
+            }
+        };
+
+        if new_blank_lines_at_end != 0
+            && pre.len() + applied_pos >= image.len()
+            && options.whitespace.check.contains(whitespace::Check::BLANK_AT_EOF)
+            && action.is_checking()
+        {
+            let fix = action == whitespace::Action::Fix;
+            problems.push(Problem {
+                hunk: hunk_index,
+                line: first_blank_line_at_end,
+                check: whitespace::Check::BLANK_AT_EOF,
+                fixed: fix,
+            });
+            if fix {
+                post.end = post.end.saturating_sub(new_blank_lines_at_end).max(post.start);
+            }
+        }
+
+        // Context lines are taken from the image as they may differ from the patch if whitespace is ignored.
+        let new_lines: Vec<_> = postimage[post]
+            .iter()
+            .map(|line| match line {
+                PostLine::Context(index) => image[applied_pos + index - pre.start].clone(),
+                PostLine::Added(text) => text.clone(),
+            })
+            .collect();
+        image.splice(applied_pos..applied_pos + pre.len(), new_lines);
+        outcomes.push(HunkOutcome {
+            line: applied_pos,
+            offset: applied_pos as isize - expected_pos as isize,
+            reduced_context: original_leading - leading + original_trailing - trailing,
+        });
+    }
+
+    if action.is_error() && !problems.is_empty() {
+        return Err(text::Error::Whitespace { problems });
+    }
+    Ok(Outcome {
+        data: image.concat(),
+        hunks: outcomes,
+        whitespace_problems: problems,
+    })
+}
+
+/// Apply the binary `patch` to `old` and return the new content.
+pub fn binary(old: &[u8], patch: &BinaryHunk) -> Result<Vec<u8>, binary::Error> {
+    match patch {
+        BinaryHunk::Literal(data) => Ok(data.clone()),
+        BinaryHunk::Delta(delta) => crate::delta::apply(old, delta).ok_or(binary::Error::Delta),
+    }
+}
+
+/// A line of the new version of a hunk.
+enum PostLine<'a> {
+    /// A context line, referring to the line with the given index in the preimage.
+    Context(usize),
+    /// An added line.
+    Added(Cow<'a, [u8]>),
+}
+
+/// Find the line at which `preimage` matches in `image`, starting at `pos` and then trying alternately after and before it,
+/// similar to `find_pos()` in `git`.
+fn find_pos(
+    image: &[Cow<'_, [u8]>],
+    preimage: &[&[u8]],
+    pos: usize,
+    match_beginning: bool,
+    match_end: bool,
+    options: &Options,
+) -> Option<usize> {
+    if preimage.len() > image.len() {
+        return None;
+    }
+    let matches = |at: usize| {
+        at + preimage.len() <= image.len()
+            && (!match_beginning || at == 0)
+            && (!match_end || at + preimage.len() == image.len())
+            && preimage
+                .iter()
+                .zip(&image[at..])
+                .all(|(expected, actual)| lines_match(expected, actual, options))
+    };
+
+    let (mut backwards, mut forwards) = (pos, pos);
+    let mut try_pos = pos;
+    let mut i = 0usize;
+    loop {
+        if matches(try_pos) {
+            return Some(try_pos);
+        }
+        loop {
+            if backwards == 0 && forwards == image.len() {
+                return None;
+            }
+            if i % 2 == 1 {
+                if backwards == 0 {
+                    i += 1;
+                    continue;
+                }
+                backwards -= 1;
+                try_pos = backwards;
+            } else {
+                if forwards == image.len() {
+                    i += 1;
+                    continue;
+                }
+                forwards += 1;
+                try_pos = forwards;
+            }
+            break;
+        }
+        i += 1;
+    }
+}
+
+fn lines_match(expected: &[u8], actual: &[u8], options: &Options) -> bool {
+    if expected == actual {
+        return true;
+    }
+    if options.ignore_whitespace && whitespace_insensitive_eq(expected, actual) {
+        return true;
+    }
+    if options.whitespace_action == whitespace::Action::Fix {
+        let fixed_expected = options.whitespace.fix_line(expected);
+        let fixed_actual = options.whitespace.fix_line(actual);
+        return fixed_expected.as_ref().map_or(expected, |line| line.as_bytes())
+            == fixed_actual.as_ref().map_or(actual, |line| line.as_bytes());
+    }
+    false
+}
+
+/// Compare lines while ignoring the amount of whitespace, but not whether there is whitespace, similar to
+/// `fuzzy_matchlines()` in `git`.
+fn whitespace_insensitive_eq(a: &[u8], b: &[u8]) -> bool {
+    let starts_with_whitespace = |line: &[u8]| line.trim_end().first().map_or(false, u8::is_ascii_whitespace);
+    starts_with_whitespace(a) == starts_with_whitespace(b)
+        && a.fields_with(|c| c.is_ascii_whitespace())
+            .eq(b.fields_with(|c| c.is_ascii_whitespace()))
+}
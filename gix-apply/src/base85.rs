@@ -0,0 +1,56 @@
+//! The base85 encoding as used by `git` in binary patches, which is different from the one defined in RFC 1924.
+
+const ALPHABET: &[u8; 85] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+fn value_of(byte: u8) -> Option<u32> {
+    ALPHABET.iter().position(|b| *b == byte).map(|pos| pos as u32)
+}
+
+/// Decode `input` whose length must be a multiple of 5 and append the decoded bytes to `out`, 4 bytes for each 5 input bytes.
+///
+/// Return `None` if `input` contains invalid characters or isn't of the correct length.
+pub(crate) fn decode(input: &[u8], out: &mut Vec<u8>) -> Option<()> {
+    if input.len() % 5 != 0 {
+        return None;
+    }
+    for chunk in input.chunks(5) {
+        let mut acc = 0u32;
+        for byte in chunk {
+            acc = acc.checked_mul(85)?.checked_add(value_of(*byte)?)?;
+        }
+        out.extend_from_slice(&acc.to_be_bytes());
+    }
+    Some(())
+}
+
+/// Return the amount of bytes encoded in a line of a binary patch, as noted by its first character.
+pub(crate) fn line_length(byte: u8) -> Option<usize> {
+    match byte {
+        b'A'..=b'Z' => Some((byte - b'A') as usize + 1),
+        b'a'..=b'z' => Some((byte - b'a') as usize + 27),
+        _ => None,
+    }
+}
+
+/// Encode `input` and append the result to `out`, producing 5 bytes for each 4 input bytes with the last chunk padded with zeroes.
+pub(crate) fn encode(input: &[u8], out: &mut Vec<u8>) {
+    for chunk in input.chunks(4) {
+        let mut bytes = [0u8; 4];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        let mut acc = u32::from_be_bytes(bytes);
+        let mut encoded = [0u8; 5];
+        for byte in encoded.iter_mut().rev() {
+            *byte = ALPHABET[(acc % 85) as usize];
+            acc /= 85;
+        }
+        out.extend_from_slice(&encoded);
+    }
+}
+
+/// Return the character that notes the amount of bytes encoded in a line of a binary patch, which must be between 1 and 52.
+pub(crate) fn line_length_char(len: usize) -> u8 {
+    match len {
+        1..=26 => b'A' + (len - 1) as u8,
+        _ => b'a' + (len - 27) as u8,
+    }
+}
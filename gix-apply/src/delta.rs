@@ -0,0 +1,63 @@
+//! Apply deltas in the format used in packs, which is also used in binary patches.
+
+/// Decode a little-endian variable-length size as it's found in the header of deltas.
+fn decode_size(data: &[u8]) -> Option<(u64, usize)> {
+    let mut size = 0u64;
+    let mut shift = 0;
+    for (consumed, byte) in data.iter().enumerate() {
+        if shift > 63 {
+            return None;
+        }
+        size |= u64::from(byte & 0x7f) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Some((size, consumed + 1));
+        }
+    }
+    None
+}
+
+/// Apply `delta` to `base` and return the result, or `None` if the delta is malformed or doesn't fit `base`.
+pub(crate) fn apply(base: &[u8], delta: &[u8]) -> Option<Vec<u8>> {
+    let (base_size, consumed) = decode_size(delta)?;
+    let mut delta = &delta[consumed..];
+    if base_size != base.len() as u64 {
+        return None;
+    }
+    let (result_size, consumed) = decode_size(delta)?;
+    delta = &delta[consumed..];
+
+    let mut out = Vec::with_capacity(usize::try_from(result_size).ok()?);
+    while let Some((&cmd, rest)) = delta.split_first() {
+        delta = rest;
+        if cmd & 0x80 != 0 {
+            let mut ofs = 0usize;
+            let mut size = 0usize;
+            for (bit, shift) in (0..4).map(|i| (1 << i, i * 8)) {
+                if cmd & bit != 0 {
+                    let (&byte, rest) = delta.split_first()?;
+                    delta = rest;
+                    ofs |= (byte as usize) << shift;
+                }
+            }
+            for (bit, shift) in (0..3).map(|i| (0x10 << i, i * 8)) {
+                if cmd & bit != 0 {
+                    let (&byte, rest) = delta.split_first()?;
+                    delta = rest;
+                    size |= (byte as usize) << shift;
+                }
+            }
+            if size == 0 {
+                size = 0x10000;
+            }
+            out.extend_from_slice(base.get(ofs..ofs.checked_add(size)?)?);
+        } else if cmd != 0 {
+            let len = cmd as usize;
+            out.extend_from_slice(delta.get(..len)?);
+            delta = &delta[len..];
+        } else {
+            return None;
+        }
+    }
+    (out.len() as u64 == result_size).then_some(out)
+}
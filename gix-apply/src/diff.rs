@@ -0,0 +1,105 @@
+use std::ops::Range;
+
+use bstr::BString;
+use imara_diff::intern::InternedInput;
+pub use imara_diff::Algorithm;
+
+use crate::{Hunk, Line, LineKind};
+
+/// Diff `old` and `new` line by line using `algorithm` and return the hunks of a unified diff with `context_lines` lines of
+/// context around each change, similar to `git diff -U<context_lines>`.
+///
+/// Changes whose context would overlap or touch are merged into the same hunk.
+/// Lines are compared including their line terminator, so a missing newline at the end of the file is a change as well.
+pub fn hunks(algorithm: Algorithm, old: &[u8], new: &[u8], context_lines: u32) -> Vec<Hunk> {
+    let old = lines(old);
+    let new = lines(new);
+    let mut input = InternedInput::default();
+    input.update_before(old.iter().copied());
+    input.update_after(new.iter().copied());
+    let mut changes: Vec<(Range<u32>, Range<u32>)> = Vec::new();
+    imara_diff::diff(algorithm, &input, |before: Range<u32>, after: Range<u32>| {
+        changes.push((before, after));
+    });
+
+    let mut out = Vec::new();
+    let mut changes = changes.into_iter().peekable();
+    while let Some((before, after)) = changes.next() {
+        let old_start = before.start.saturating_sub(context_lines);
+        let new_start = after.start - (before.start - old_start);
+        let mut lines = Vec::new();
+        push_lines(
+            &mut lines,
+            LineKind::Context,
+            &old[old_start as usize..before.start as usize],
+        );
+        push_lines(
+            &mut lines,
+            LineKind::Removed,
+            &old[before.start as usize..before.end as usize],
+        );
+        push_lines(
+            &mut lines,
+            LineKind::Added,
+            &new[after.start as usize..after.end as usize],
+        );
+        let mut old_end = before.end;
+        while let Some((before, after)) =
+            changes.next_if(|(before, _)| before.start <= old_end.saturating_add(context_lines.saturating_mul(2)))
+        {
+            push_lines(
+                &mut lines,
+                LineKind::Context,
+                &old[old_end as usize..before.start as usize],
+            );
+            push_lines(
+                &mut lines,
+                LineKind::Removed,
+                &old[before.start as usize..before.end as usize],
+            );
+            push_lines(
+                &mut lines,
+                LineKind::Added,
+                &new[after.start as usize..after.end as usize],
+            );
+            old_end = before.end;
+        }
+        let context_end = old_end.saturating_add(context_lines).min(old.len() as u32);
+        push_lines(
+            &mut lines,
+            LineKind::Context,
+            &old[old_end as usize..context_end as usize],
+        );
+
+        let old_lines = lines.iter().filter(|l| l.kind != LineKind::Added).count() as u32;
+        let new_lines = lines.iter().filter(|l| l.kind != LineKind::Removed).count() as u32;
+        out.push(Hunk {
+            old_start: if old_lines == 0 { old_start } else { old_start + 1 },
+            old_lines,
+            new_start: if new_lines == 0 { new_start } else { new_start + 1 },
+            new_lines,
+            section: BString::default(),
+            lines,
+        });
+    }
+    out
+}
+
+fn push_lines(out: &mut Vec<Line>, kind: LineKind, lines: &[&[u8]]) {
+    out.extend(lines.iter().map(|text| Line {
+        kind,
+        text: (*text).into(),
+    }));
+}
+
+/// Split `data` into lines that keep their line terminator.
+fn lines(data: &[u8]) -> Vec<&[u8]> {
+    let mut out = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let end = rest.iter().position(|b| *b == b'\n').map_or(rest.len(), |nl| nl + 1);
+        out.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    out
+}
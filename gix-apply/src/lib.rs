@@ -0,0 +1,155 @@
+//! Parse patches in the unified diff format, as produced by `git diff` or `git format-patch`, and apply them to buffers
+//! similar to `git apply`.
+//!
+//! Applying a [`File`] patch to a repository, i.e. the worktree, the index or a tree, is left to the caller
+//! which can use [`apply::text()`] and [`apply::binary()`] to compute the new content of each file.
+//!
+//! Patches can also be produced with [`diff::hunks()`] and written with [`File::write_to()`], and the [`mail`] module
+//! extracts patches from emails and mailboxes, or writes commits as emails.
+#![deny(rust_2018_idioms, missing_docs)]
+#![forbid(unsafe_code)]
+
+use bstr::BString;
+use gix_object::tree::EntryMode;
+
+///
+pub mod parse;
+pub use parse::function::parse;
+
+///
+pub mod apply;
+
+///
+pub mod whitespace;
+
+///
+pub mod diff;
+
+pub mod mail;
+
+mod base85;
+mod delta;
+mod write;
+
+/// The patch of a single file, as parsed from one `diff --git` section or a pair of `---` and `+++` lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    /// The path of the file before the change, or `None` if the file is created by this patch.
+    ///
+    /// It's relative to the root of the worktree after removing the leading path components as configured during parsing.
+    pub old_path: Option<BString>,
+    /// The path of the file after the change, or `None` if the file is deleted by this patch.
+    pub new_path: Option<BString>,
+    /// The mode of the file before the change, if it was mentioned.
+    pub old_mode: Option<EntryMode>,
+    /// The mode of the file after the change, if it was mentioned.
+    pub new_mode: Option<EntryMode>,
+    /// The possibly abbreviated hexadecimal object id of the file before the change, as mentioned in the `index` line.
+    pub old_id: Option<BString>,
+    /// The possibly abbreviated hexadecimal object id of the file after the change, as mentioned in the `index` line.
+    pub new_id: Option<BString>,
+    /// What happens to the file.
+    pub operation: Operation,
+    /// The similarity index in percent for renames and copies, or the dissimilarity index for rewrites.
+    pub similarity: Option<u8>,
+    /// The changes to the content of the file.
+    pub content: Content,
+}
+
+impl File {
+    /// Return the path at which the file is located after applying the patch, or the previous path if it was deleted.
+    pub fn path(&self) -> &BString {
+        self.new_path
+            .as_ref()
+            .or(self.old_path.as_ref())
+            .expect("parser assures at least one path is set")
+    }
+
+    /// Return `true` if this patch doesn't change the content of the file, like pure renames or mode changes.
+    pub fn is_content_unchanged(&self) -> bool {
+        matches!(&self.content, Content::Text(hunks) if hunks.is_empty())
+    }
+}
+
+/// Describe what happens to a [`File`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// The file exists before and after the change.
+    Modify,
+    /// The file is created.
+    Create,
+    /// The file is deleted.
+    Delete,
+    /// The file is moved from [`File::old_path`] to [`File::new_path`].
+    Rename,
+    /// The file at [`File::old_path`] is copied to [`File::new_path`], which is where the changes are applied.
+    Copy,
+}
+
+/// The way the content of a [`File`] changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Content {
+    /// Text is changed by the given hunks, which are sorted by their position.
+    ///
+    /// No hunks means the content doesn't change.
+    Text(Vec<Hunk>),
+    /// Binary content is changed, as described in a `GIT binary patch` section.
+    Binary(Binary),
+    /// Binary content changed, but the patch only notes that with `Binary files … differ`, so it can't be applied.
+    BinaryWithoutData,
+}
+
+/// A hunk of a text patch, starting with `@@ -old_start,old_lines +new_start,new_lines @@`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// The one-based line at which the hunk starts in the old version of the file, or `0` if it's empty.
+    pub old_start: u32,
+    /// The amount of context and removed lines.
+    pub old_lines: u32,
+    /// The one-based line at which the hunk starts in the new version of the file, or `0` if it's empty.
+    pub new_start: u32,
+    /// The amount of context and added lines.
+    pub new_lines: u32,
+    /// The text after the closing `@@`, without leading space, which is typically the function the hunk is in.
+    pub section: BString,
+    /// All lines of the hunk in order.
+    pub lines: Vec<Line>,
+}
+
+/// A line within a [`Hunk`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// The kind of line.
+    pub kind: LineKind,
+    /// The text of the line including its newline, which is missing only if the patch marks it with `\ No newline at end of file`.
+    pub text: BString,
+}
+
+/// Describe the role of a [`Line`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LineKind {
+    /// The line is part of the old and new version, prefixed with ` `.
+    Context,
+    /// The line is only part of the old version, prefixed with `-`.
+    Removed,
+    /// The line is only part of the new version, prefixed with `+`.
+    Added,
+}
+
+/// The data of a `GIT binary patch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binary {
+    /// The data to turn the old version into the new version.
+    pub forward: BinaryHunk,
+    /// The data to turn the new version into the old version, if present.
+    pub reverse: Option<BinaryHunk>,
+}
+
+/// The decompressed data of one direction of a [`Binary`] patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryHunk {
+    /// The data is the complete new version of the file.
+    Literal(Vec<u8>),
+    /// The data is a delta in the format used in packs, to be applied to the old version of the file.
+    Delta(Vec<u8>),
+}
@@ -0,0 +1,192 @@
+//! Decoding and encoding of header values and message bodies as used in emails.
+use bstr::{BStr, BString, ByteSlice};
+use encoding_rs::Encoding;
+
+use super::parse::Error;
+
+/// Convert `data` from `charset` to `target`, or return it unchanged if there is no `target` or no `charset`.
+pub(crate) fn convert(
+    data: &[u8],
+    charset: Option<&[u8]>,
+    target: Option<&'static Encoding>,
+) -> Result<BString, Error> {
+    let (Some(target), Some(charset)) = (target, charset) else {
+        return Ok(data.into());
+    };
+    let source = Encoding::for_label(charset).ok_or_else(|| Error::UnknownCharset {
+        charset: charset.into(),
+    })?;
+    if source == target {
+        return Ok(data.into());
+    }
+    let decoded = source
+        .decode_without_bom_handling_and_without_replacement(data)
+        .ok_or_else(|| Error::Charset {
+            charset: charset.into(),
+        })?;
+    let (encoded, _, had_errors) = target.encode(&decoded);
+    if had_errors {
+        return Err(Error::Charset {
+            charset: target.name().into(),
+        });
+    }
+    Ok(encoded.into_owned().into())
+}
+
+/// Decode all RFC 2047 encoded words in the header `value` and convert them to `target`.
+///
+/// Whitespace between adjacent encoded words is removed.
+pub(crate) fn decode_header(value: &[u8], target: Option<&'static Encoding>) -> Result<BString, Error> {
+    let mut out = BString::default();
+    let mut rest = value;
+    let mut last_was_encoded = false;
+    while !rest.is_empty() {
+        match rest
+            .find("=?")
+            .and_then(|start| Some((start, encoded_word(&rest[start..])?)))
+        {
+            Some((start, (charset, decoded, consumed))) => {
+                let before = &rest[..start];
+                if !(last_was_encoded && before.iter().all(u8::is_ascii_whitespace)) {
+                    out.extend_from_slice(before);
+                }
+                out.extend_from_slice(&convert(&decoded, Some(charset), target)?);
+                last_was_encoded = true;
+                rest = &rest[start + consumed..];
+            }
+            None => {
+                let end = rest.find("=?").map_or(rest.len(), |pos| pos + 2);
+                out.extend_from_slice(&rest[..end]);
+                last_was_encoded = false;
+                rest = &rest[end..];
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Parse the encoded word `=?charset?encoding?text?=` at the beginning of `input` and return the charset, the decoded bytes
+/// and the amount of consumed bytes.
+fn encoded_word(input: &[u8]) -> Option<(&[u8], Vec<u8>, usize)> {
+    let inner = input.strip_prefix(b"=?")?;
+    let charset_end = inner.find_byte(b'?')?;
+    let charset = &inner[..charset_end];
+    let encoding = *inner.get(charset_end + 1)?;
+    if inner.get(charset_end + 2) != Some(&b'?') {
+        return None;
+    }
+    let text_start = charset_end + 3;
+    let text_len = inner[text_start..].find("?=")?;
+    let text = &inner[text_start..text_start + text_len];
+    if text.iter().any(u8::is_ascii_whitespace) {
+        return None;
+    }
+    let decoded = match encoding.to_ascii_lowercase() {
+        b'q' => decode_quoted_printable(text, true),
+        b'b' => decode_base64(text)?,
+        _ => return None,
+    };
+    // Ignore the language of RFC 2231, as in `utf-8*en`.
+    let charset = charset.split_str("*").next().unwrap_or(charset);
+    Some((charset, decoded, 2 + text_start + text_len + 2))
+}
+
+/// Decode the quoted-printable `input`, turning `_` into spaces if `is_header` is set.
+pub(crate) fn decode_quoted_printable(input: &[u8], is_header: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut bytes = input.iter().copied().enumerate();
+    while let Some((pos, byte)) = bytes.next() {
+        match byte {
+            b'=' => {
+                let hex = input.get(pos + 1..pos + 3);
+                if let Some(value) = hex.and_then(|hex| u8::from_str_radix(hex.to_str().ok()?, 16).ok()) {
+                    out.push(value);
+                    bytes.nth(1);
+                } else if input.get(pos + 1..pos + 3) == Some(b"\r\n") {
+                    // A soft line break.
+                    bytes.nth(1);
+                } else if input.get(pos + 1) == Some(&b'\n') {
+                    bytes.next();
+                } else {
+                    out.push(byte);
+                }
+            }
+            b'_' if is_header => out.push(b' '),
+            byte => out.push(byte),
+        }
+    }
+    out
+}
+
+/// Decode the base64 `input`, ignoring whitespace, or return `None` if it contains invalid characters.
+pub(crate) fn decode_base64(input: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() / 4 * 3);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for byte in input.iter().copied().filter(|b| !b.is_ascii_whitespace()) {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => return None,
+        };
+        acc = (acc << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// The kind of header that is encoded, which affects which characters need to be encoded.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum HeaderKind {
+    Subject,
+    Address,
+}
+
+/// Return `true` if `value` needs to be encoded according to RFC 2047 to be used in a header.
+pub(crate) fn needs_encoding(value: &BStr) -> bool {
+    value.iter().any(|b| !b.is_ascii() || b.is_ascii_control()) || value.contains_str("=?")
+}
+
+/// Encode `value` as a series of UTF-8 encoded words using the `Q` encoding, folding lines so they stay below 76 characters,
+/// with `line_len` being the amount of characters already on the current line.
+///
+/// This is `add_rfc2047()` in `git`.
+pub(crate) fn encode_header(out: &mut BString, value: &BStr, kind: HeaderKind, mut line_len: usize) {
+    const PREFIX: &[u8] = b"=?UTF-8?q?";
+    const MAX_LEN: usize = 76;
+    out.extend_from_slice(PREFIX);
+    line_len += PREFIX.len();
+    for (start, end, _) in value.char_indices() {
+        let char_bytes = &value[start..end];
+        let is_special = |b: u8| match kind {
+            HeaderKind::Subject => !b.is_ascii() || b.is_ascii_control() || b"=?_ ".contains(&b),
+            HeaderKind::Address => !(b.is_ascii_alphanumeric() || b"!*+-/".contains(&b)),
+        };
+        let encoded_len = char_bytes
+            .iter()
+            .map(|b| if is_special(*b) { 3 } else { 1 })
+            .sum::<usize>();
+        if line_len + encoded_len + 2 > MAX_LEN {
+            out.extend_from_slice(b"?=\n ");
+            out.extend_from_slice(PREFIX);
+            line_len = 1 + PREFIX.len();
+        }
+        for byte in char_bytes.iter().copied() {
+            if is_special(byte) {
+                out.extend_from_slice(format!("={byte:02X}").as_bytes());
+            } else {
+                out.push(byte);
+            }
+        }
+        line_len += encoded_len;
+    }
+    out.extend_from_slice(b"?=");
+}
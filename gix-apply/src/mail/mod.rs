@@ -0,0 +1,103 @@
+//! Split mailboxes, extract commit information and patches from emails similar to `git mailinfo`, and write commits as emails
+//! similar to `git format-patch`.
+use bstr::{BString, ByteSlice};
+
+mod encoding;
+
+///
+pub mod parse;
+pub use parse::function::parse;
+
+///
+pub mod write;
+pub use write::Email;
+
+/// The information extracted from an email by [`parse()`].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Info {
+    /// The author of the patch, taken from the `From` header or an in-body `From:` line.
+    ///
+    /// The name is the email if no usable name was found, and both are empty if there was no `From` header.
+    pub author: gix_actor::Identity,
+    /// The date as found in the `Date` header or an in-body `Date:` line, if present.
+    pub date: Option<BString>,
+    /// The subject with the `[PATCH]` prefix and similar removed, unless configured otherwise.
+    pub subject: BString,
+    /// The commit message without the subject, which is empty or ends with a newline.
+    pub message: BString,
+    /// Everything after the commit message, starting with the `---` line or the first patch.
+    pub patch: BString,
+    /// The value of the `Message-Id` header, if present.
+    pub message_id: Option<BString>,
+}
+
+impl Info {
+    /// Return the message of the commit to create for this email, which is the subject followed by the message if it's not empty.
+    pub fn commit_message(&self) -> BString {
+        let mut out = self.subject.clone();
+        out.push(b'\n');
+        if !self.message.is_empty() {
+            out.push(b'\n');
+            out.extend_from_slice(&self.message);
+        }
+        out
+    }
+
+    /// Parse the date of the email into a time, or return `None` if there is no date or if it couldn't be parsed.
+    pub fn time(&self) -> Option<gix_date::Time> {
+        let date = self.date.as_ref()?.to_str().ok()?;
+        gix_date::parse(date.trim(), None).ok()
+    }
+}
+
+/// Return an iterator over all messages in `mbox`, each without its `From ` separator line.
+///
+/// If `mbox` doesn't start with a `From ` line, it is considered a single message.
+pub fn split(mbox: &[u8]) -> Split<'_> {
+    Split { data: mbox }
+}
+
+/// An iterator over the messages in a mailbox, as created by [`split()`].
+pub struct Split<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        if is_from_line(self.data) {
+            let end = self.data.find_byte(b'\n').map_or(self.data.len(), |pos| pos + 1);
+            self.data = &self.data[end..];
+        }
+        let mut pos = 0;
+        while pos < self.data.len() {
+            let line_end = self.data[pos..]
+                .find_byte(b'\n')
+                .map_or(self.data.len(), |end| pos + end + 1);
+            if pos != 0 && is_from_line(&self.data[pos..]) {
+                break;
+            }
+            pos = line_end;
+        }
+        let (message, rest) = self.data.split_at(pos);
+        self.data = rest;
+        Some(message)
+    }
+}
+
+/// Return `true` if `data` starts with a line that separates messages in a mailbox, like `From <id> <date>`,
+/// similar to `is_from_line()` in `git`.
+fn is_from_line(data: &[u8]) -> bool {
+    let line = data.lines().next().unwrap_or_default();
+    let Some(rest) = line.strip_prefix(b"From ") else {
+        return false;
+    };
+    // The line must end with a date, which contains a time like `00:00:00`.
+    rest.rfind_byte(b':').map_or(false, |colon| {
+        colon >= 2 && rest[colon - 2..colon].iter().all(u8::is_ascii_digit)
+    })
+}
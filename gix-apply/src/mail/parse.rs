@@ -0,0 +1,410 @@
+use bstr::BString;
+
+/// Options for use in [`parse()`](crate::mail::parse()).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// If `true`, like `-k`, keep the subject as is instead of removing `Re:` and bracketed prefixes like `[PATCH 1/2]`.
+    pub keep_subject: bool,
+    /// If `true`, like `-b`, only remove bracketed prefixes of the subject that contain `PATCH`.
+    pub keep_non_patch_brackets: bool,
+    /// If `true`, like `--scissors`, remove everything in the body before a scissors line like `-- >8 --`.
+    pub scissors: bool,
+    /// If `true`, like `--keep-cr`, keep carriage-returns at the end of lines.
+    pub keep_cr: bool,
+    /// The encoding to convert the subject, message and author to, or `None` to keep them as they are, like `-n`.
+    ///
+    /// It defaults to UTF-8, which is the default of `i18n.commitEncoding`.
+    pub encoding: Option<&'static encoding_rs::Encoding>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            keep_subject: false,
+            keep_non_patch_brackets: false,
+            scissors: false,
+            keep_cr: false,
+            encoding: Some(encoding_rs::UTF_8),
+        }
+    }
+}
+
+/// The error returned by [`parse()`](crate::mail::parse()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The charset '{charset}' is unknown")]
+    UnknownCharset { charset: BString },
+    #[error("Text could not be converted from or to '{charset}'")]
+    Charset { charset: BString },
+    #[error("The base64 encoded body could not be decoded")]
+    Base64,
+}
+
+pub(crate) mod function {
+    use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+    use super::{Error, Options};
+    use crate::mail::{encoding, Info};
+
+    /// Extract the author, subject, commit message and patch from the email `message`, similar to `git mailinfo`.
+    ///
+    /// The body is decoded according to its `Content-Transfer-Encoding`, and all text is converted from its charset to the
+    /// encoding configured in `options`. In multi-part messages, all parts are used in order.
+    /// `From:`, `Subject:` and `Date:` lines at the beginning of the body override the respective headers.
+    pub fn parse(message: &[u8], options: Options) -> Result<Info, Error> {
+        let message = if options.keep_cr {
+            message.into()
+        } else {
+            message.replace(b"\r\n", b"\n")
+        };
+        let (headers, body) = split_headers(&message);
+
+        let mut info = Info::default();
+        let mut from = None;
+        let mut subject = None;
+        for (name, value) in &headers {
+            let value = || encoding::decode_header(value, options.encoding);
+            match name.as_slice() {
+                b"from" => from = Some(value()?),
+                b"subject" => subject = Some(value()?),
+                b"date" => info.date = Some(value()?),
+                b"message-id" => info.message_id = Some(value()?),
+                _ => {}
+            }
+        }
+
+        let body = decode_body(&headers, body, options)?;
+        let mut lines = body.lines_with_terminator().peekable();
+        let mut message = BString::default();
+        let mut in_body_headers = true;
+        while let Some(line) = lines.peek().copied() {
+            if is_patch_break(line) {
+                break;
+            }
+            lines.next();
+            if options.scissors && is_scissors_line(line) {
+                message.clear();
+                in_body_headers = true;
+                continue;
+            }
+            if in_body_headers {
+                if message.is_empty() && line.trim().is_empty() {
+                    continue;
+                }
+                let header = [("from", &mut from), ("subject", &mut subject), ("date", &mut info.date)]
+                    .into_iter()
+                    .find_map(|(name, target)| in_body_header(line, name).map(|value| (value, target)));
+                if let Some((value, target)) = header {
+                    let mut value: BString = value.trim().into();
+                    while let Some(continuation) =
+                        lines.next_if(|line| line.first().map_or(false, |b| *b == b' ' || *b == b'\t'))
+                    {
+                        value.push_str(continuation.trim_end());
+                    }
+                    *target = Some(value);
+                    continue;
+                }
+                in_body_headers = false;
+                if line.trim().is_empty() {
+                    continue;
+                }
+            }
+            message.extend_from_slice(line);
+        }
+        info.patch = lines.flat_map(|line| line.iter().copied()).collect::<Vec<_>>().into();
+
+        let trimmed_len = message
+            .lines_with_terminator()
+            .rev()
+            .skip_while(|line| line.trim().is_empty())
+            .map(<[u8]>::len)
+            .sum();
+        message.truncate(trimmed_len);
+        if let Some(last) = message.lines_with_terminator().next_back() {
+            let trimmed = last.trim_end().len();
+            let end = message.len() - last.len() + trimmed;
+            message.truncate(end);
+            message.push(b'\n');
+        }
+        info.message = message;
+
+        info.subject = subject.unwrap_or_default();
+        if !options.keep_subject {
+            info.subject = cleanup_subject(info.subject.as_ref(), options.keep_non_patch_brackets);
+        } else {
+            info.subject = info.subject.trim().into();
+        }
+        if let Some(from) = from {
+            info.author = parse_from(from.as_ref());
+        }
+        info.date = info.date.map(|date| date.trim().into());
+        info.message_id = info.message_id.map(|id| id.trim().into());
+        Ok(info)
+    }
+
+    type Headers = Vec<(Vec<u8>, BString)>;
+
+    /// Split `message` into its unfolded headers, with lower-case names, and the body.
+    fn split_headers(message: &[u8]) -> (Headers, &[u8]) {
+        let mut headers: Headers = Vec::new();
+        let mut rest = message;
+        while !rest.is_empty() {
+            let end = rest.find_byte(b'\n').map_or(rest.len(), |pos| pos + 1);
+            let line = &rest[..end];
+            let content = line.trim_end_with(|c| c == '\n' || c == '\r');
+            if content.is_empty() {
+                rest = &rest[end..];
+                break;
+            }
+            if content[0] == b' ' || content[0] == b'\t' {
+                if let Some((_, value)) = headers.last_mut() {
+                    value.extend_from_slice(content);
+                }
+            } else if let Some(colon) = content.find_byte(b':') {
+                let name = content[..colon].trim().to_ascii_lowercase();
+                headers.push((name, content[colon + 1..].trim_start().into()));
+            } else {
+                // Not a header, so there are no headers at all.
+                break;
+            }
+            rest = &rest[end..];
+        }
+        (headers, rest)
+    }
+
+    fn header<'a>(headers: &'a Headers, name: &str) -> Option<&'a BStr> {
+        headers
+            .iter()
+            .find(|(n, _)| n.as_slice() == name.as_bytes())
+            .map(|(_, value)| value.as_bstr())
+    }
+
+    /// Return the value of the parameter `name` in a header `value` like `text/plain; charset="utf-8"`.
+    fn parameter<'a>(value: &'a BStr, name: &str) -> Option<&'a [u8]> {
+        value.split_str(";").skip(1).find_map(|param| {
+            let (key, value) = param.split_once_str("=")?;
+            key.trim()
+                .eq_ignore_ascii_case(name.as_bytes())
+                .then(|| value.trim().trim_with(|c| c == '"'))
+        })
+    }
+
+    /// Decode the `body` according to the `headers`, and convert it to the configured encoding.
+    fn decode_body(headers: &Headers, body: &[u8], options: Options) -> Result<BString, Error> {
+        let content_type = header(headers, "content-type");
+        if let Some(boundary) = content_type
+            .filter(|ct| ct.trim_start().to_ascii_lowercase().starts_with(b"multipart/"))
+            .and_then(|ct| parameter(ct, "boundary"))
+        {
+            let mut delimiter = BString::from("--");
+            delimiter.extend_from_slice(boundary);
+            let mut out = BString::default();
+            let mut part = None::<Vec<u8>>;
+            for line in body.lines_with_terminator() {
+                let content = line.trim_end();
+                if content.starts_with(&delimiter) {
+                    if let Some(part) = part.take() {
+                        let (headers, body) = split_headers(&part);
+                        out.extend_from_slice(&decode_body(&headers, body, options)?);
+                    }
+                    if content[delimiter.len()..].starts_with(b"--") {
+                        break;
+                    }
+                    part = Some(Vec::new());
+                } else if let Some(part) = part.as_mut() {
+                    part.extend_from_slice(line);
+                }
+            }
+            return Ok(out);
+        }
+
+        let transfer_encoding = header(headers, "content-transfer-encoding").map(|te| te.trim().to_ascii_lowercase());
+        let decoded = match transfer_encoding.as_deref() {
+            Some(b"base64") => encoding::decode_base64(body).ok_or(Error::Base64)?,
+            Some(b"quoted-printable") => encoding::decode_quoted_printable(body, false),
+            _ => body.to_owned(),
+        };
+        let charset = content_type.and_then(|ct| parameter(ct, "charset"));
+        encoding::convert(&decoded, charset, options.encoding)
+    }
+
+    /// Return the value of the in-body header `name` if `line` is such a header.
+    fn in_body_header<'a>(line: &'a [u8], name: &str) -> Option<&'a [u8]> {
+        let colon = line.find_byte(b':')?;
+        line[..colon]
+            .eq_ignore_ascii_case(name.as_bytes())
+            .then(|| &line[colon + 1..])
+    }
+
+    /// Return `true` if `line` starts a patch, similar to `patchbreak()` in `git`.
+    fn is_patch_break(line: &[u8]) -> bool {
+        if line.starts_with(b"diff -") || line.starts_with(b"Index: ") {
+            return true;
+        }
+        match line.strip_prefix(b"---") {
+            Some(rest) if line.len() >= 4 => {
+                (rest[0] == b' ' && rest.get(1).map_or(false, |b| !b.is_ascii_whitespace()))
+                    || rest.iter().all(u8::is_ascii_whitespace)
+            }
+            _ => false,
+        }
+    }
+
+    /// Return `true` if `line` is a line like `-- >8 --` or `--8<-- cut here --8<--`, similar to `is_scissors_line()` in `git`.
+    fn is_scissors_line(line: &[u8]) -> bool {
+        let (mut scissors, mut gap, mut perforation) = (0, 0, 0);
+        let (mut first_nonblank, mut last_nonblank) = (None, None);
+        let mut in_perforation = false;
+        let mut pos = 0;
+        while pos < line.len() {
+            let byte = line[pos];
+            if byte.is_ascii_whitespace() {
+                if in_perforation {
+                    perforation += 1;
+                    gap += 1;
+                }
+                pos += 1;
+                continue;
+            }
+            last_nonblank = Some(pos);
+            first_nonblank.get_or_insert(pos);
+            if byte == b'-' {
+                in_perforation = true;
+                perforation += 1;
+                pos += 1;
+                continue;
+            }
+            let next = &line[pos..];
+            if next.starts_with(b">8") || next.starts_with(b"8<") || next.starts_with(b">%") || next.starts_with(b"%<")
+            {
+                in_perforation = true;
+                perforation += 2;
+                scissors += 2;
+                last_nonblank = Some(pos + 1);
+                pos += 2;
+                continue;
+            }
+            in_perforation = false;
+            pos += 1;
+        }
+        let visible = match (first_nonblank, last_nonblank) {
+            (Some(first), Some(last)) => last - first + 1,
+            _ => 0,
+        };
+        scissors != 0 && 8 <= visible && visible < perforation * 3 && gap * 2 < perforation
+    }
+
+    /// Remove `Re:` and bracketed prefixes from `subject`, similar to `cleanup_subject()` in `git`.
+    fn cleanup_subject(subject: &BStr, keep_non_patch_brackets: bool) -> BString {
+        let mut subject: BString = subject.into();
+        let mut at = 0;
+        while at < subject.len() {
+            match subject[at] {
+                b'r' | b'R'
+                    if subject.len() > at + 3
+                        && subject[at + 1].eq_ignore_ascii_case(&b'e')
+                        && subject[at + 2] == b':' =>
+                {
+                    subject.drain(at..at + 3);
+                    continue;
+                }
+                b' ' | b'\t' | b':' => {
+                    subject.remove(at);
+                    continue;
+                }
+                b'[' => {
+                    if let Some(len) = subject[at..].find_byte(b']').map(|pos| pos + 1) {
+                        if !keep_non_patch_brackets || (len >= 7 && subject[at..at + len].contains_str("PATCH")) {
+                            subject.drain(at..at + len);
+                        } else {
+                            at += len;
+                            if subject.get(at).map_or(false, u8::is_ascii_whitespace) {
+                                at += 1;
+                            }
+                        }
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            break;
+        }
+        subject.trim().into()
+    }
+
+    /// Parse the author from the value of a `From` header, similar to `handle_from()` in `git`.
+    fn parse_from(from: &BStr) -> gix_actor::Identity {
+        let from = unquote_quoted_pairs(from);
+        let Some(at) = from.find_byte(b'@') else {
+            return parse_bogus_from(from.as_ref());
+        };
+        let mut start = at;
+        let mut remove_start = at;
+        while start > 0 {
+            let byte = from[start - 1];
+            if byte.is_ascii_whitespace() {
+                break;
+            }
+            if byte == b'<' {
+                remove_start = start - 1;
+                break;
+            }
+            start -= 1;
+            remove_start = start;
+        }
+        let len = from[start..]
+            .iter()
+            .position(|b| b.is_ascii_whitespace() || *b == b'>')
+            .unwrap_or(from.len() - start);
+        let email: BString = from[start..start + len].into();
+        let remove_end = (start + len + 1).min(from.len());
+
+        let mut name = BString::from(&from[..remove_start]);
+        name.push(b' ');
+        name.extend_from_slice(&from[remove_end..]);
+        let mut name: BString = name.fields().collect::<Vec<_>>().join(&b' ').into();
+        if name.starts_with(b"(") && name.ends_with(b")") {
+            name = name[1..name.len() - 1].into();
+        }
+        sane_identity(name, email)
+    }
+
+    /// Handle a `From` header without `@`, like `Name <email>`.
+    fn parse_bogus_from(from: &BStr) -> gix_actor::Identity {
+        let (Some(open), Some(close)) = (from.find_byte(b'<'), from.rfind_byte(b'>')) else {
+            return sane_identity(from.trim().into(), BString::default());
+        };
+        if close < open {
+            return sane_identity(from.trim().into(), BString::default());
+        }
+        let email: BString = from[open + 1..close].trim().into();
+        let name = from[..open].trim().trim_with(|c| c == '"');
+        sane_identity(name.into(), email)
+    }
+
+    /// Use the email as name if the name is empty, too long or looks like an email itself.
+    fn sane_identity(name: BString, email: BString) -> gix_actor::Identity {
+        let name = if name.is_empty() || name.len() > 60 || name.iter().any(|b| b"@<>".contains(b)) {
+            email.clone()
+        } else {
+            name
+        };
+        gix_actor::Identity { name, email }
+    }
+
+    /// Remove the quotes of quoted strings and unescape their quoted pairs.
+    fn unquote_quoted_pairs(input: &BStr) -> BString {
+        let mut out = BString::default();
+        let mut in_quotes = false;
+        let mut bytes = input.iter().copied();
+        while let Some(byte) = bytes.next() {
+            match byte {
+                b'"' => in_quotes = !in_quotes,
+                b'\\' if in_quotes => out.extend(bytes.next()),
+                byte => out.push(byte),
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,121 @@
+use std::io::{self, Write};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use super::encoding::{encode_header, needs_encoding, HeaderKind};
+
+/// The headers and message of an email for a single commit, similar to what `git format-patch` produces.
+///
+/// Patches are expected to follow a `---` line written right after it, followed by an optional [signature](write_signature()).
+#[derive(Debug, Clone, Copy)]
+pub struct Email<'a> {
+    /// The id of the commit, used in the `From <id>` line that starts each message in a mailbox.
+    pub id: &'a gix_hash::oid,
+    /// The author of the commit, which is used for the `From` and `Date` headers.
+    pub author: gix_actor::SignatureRef<'a>,
+    /// The text in brackets that is put in front of the subject, like `PATCH 1/2`, or `None` to not use brackets.
+    pub subject_prefix: Option<&'a BStr>,
+    /// The subject, which is the first paragraph of the commit message with line breaks replaced by spaces.
+    pub subject: &'a BStr,
+    /// The commit message without the subject, which is empty or ends with a newline.
+    pub body: &'a BStr,
+}
+
+impl Email<'_> {
+    /// Write the email headers and the message.
+    ///
+    /// Headers with non-ASCII characters are encoded as described in RFC 2047, assuming UTF-8.
+    pub fn write_to(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "From {} Mon Sep 17 00:00:00 2001", self.id)?;
+
+        let mut header = BString::from("From: ");
+        write_address(&mut header, self.author.name, self.author.email);
+        header.push(b'\n');
+        out.write_all(&header)?;
+
+        writeln!(
+            out,
+            "Date: {}",
+            self.author.time.format(gix_date::time::format::GIT_RFC2822)
+        )?;
+
+        let mut subject = BString::default();
+        if let Some(prefix) = self.subject_prefix {
+            subject.push(b'[');
+            subject.extend_from_slice(prefix);
+            subject.extend_from_slice(b"] ");
+        }
+        subject.extend_from_slice(self.subject);
+        let mut header = BString::from("Subject: ");
+        if needs_encoding(subject.as_ref()) {
+            let len = header.len();
+            encode_header(&mut header, subject.as_ref(), HeaderKind::Subject, len);
+        } else {
+            wrap_header(&mut header, subject.as_ref());
+        }
+        header.push(b'\n');
+        out.write_all(&header)?;
+
+        if !self.body.is_ascii() || !self.subject.is_ascii() {
+            out.write_all(
+                b"MIME-Version: 1.0\nContent-Type: text/plain; charset=UTF-8\nContent-Transfer-Encoding: 8bit\n",
+            )?;
+        }
+        out.write_all(b"\n")?;
+        if !self.body.is_empty() {
+            out.write_all(self.body)?;
+            if !self.body.ends_with(b"\n") {
+                out.write_all(b"\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write the `signature` that ends each email, which is typically the version of the program that produced it.
+pub fn write_signature(out: &mut dyn Write, signature: &BStr) -> io::Result<()> {
+    out.write_all(b"-- \n")?;
+    out.write_all(signature)?;
+    out.write_all(b"\n\n")
+}
+
+/// Write `name <email>` into `out`, encoding or quoting the name if needed.
+fn write_address(out: &mut BString, name: &BStr, email: &BStr) {
+    if needs_encoding(name) {
+        let len = out.len();
+        encode_header(out, name, HeaderKind::Address, len);
+    } else if name.iter().any(|b| b"()<>[]:;@\\,.\"".contains(b)) {
+        out.push(b'"');
+        for byte in name.iter().copied() {
+            if byte == b'"' || byte == b'\\' {
+                out.push(b'\\');
+            }
+            out.push(byte);
+        }
+        out.push(b'"');
+    } else {
+        out.extend_from_slice(name);
+    }
+    out.extend_from_slice(b" <");
+    out.extend_from_slice(email);
+    out.push(b'>');
+}
+
+/// Append `value` to the header in `out`, folding it at spaces to keep lines at no more than 78 characters.
+fn wrap_header(out: &mut BString, value: &BStr) {
+    const MAX_LEN: usize = 78;
+    let mut line_len = out.len();
+    for (index, word) in value.split_str(" ").enumerate() {
+        if index > 0 {
+            if line_len + 1 + word.len() > MAX_LEN {
+                out.extend_from_slice(b"\n ");
+                line_len = 1;
+            } else {
+                out.push(b' ');
+                line_len += 1;
+            }
+        }
+        out.extend_from_slice(word);
+        line_len += word.len();
+    }
+}
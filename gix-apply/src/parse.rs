@@ -0,0 +1,470 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// Options for use in [`parse()`](crate::parse()).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The amount of leading path components to remove from the paths in the patch, like `-p<n>` in `git apply`.
+    ///
+    /// It defaults to `1`, which removes the `a/` and `b/` prefixes produced by `git diff`.
+    /// Paths in `rename` and `copy` headers are never stripped as they are always relative to the root of the worktree.
+    pub strip_components: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { strip_components: 1 }
+    }
+}
+
+/// The error returned by [`parse()`](crate::parse()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Corrupt patch at line {line}: {message}")]
+    Corrupt { line: usize, message: &'static str },
+    #[error("Could not remove {components} leading path component(s) from '{path}' at line {line}")]
+    StripComponents {
+        line: usize,
+        components: usize,
+        path: BString,
+    },
+    #[error("Could not unquote path at line {line}")]
+    Unquote {
+        line: usize,
+        source: gix_quote::ansi_c::undo::Error,
+    },
+    #[error("The patch at line {line} lacks a file name")]
+    MissingPath { line: usize },
+}
+
+pub(crate) mod function {
+    use std::borrow::Cow;
+
+    use bstr::{BStr, BString, ByteSlice};
+    use gix_object::tree::EntryMode;
+
+    use super::{strip_components, Error, Lines, Options};
+    use crate::{base85, Binary, BinaryHunk, Content, File, Hunk, Line, LineKind, Operation};
+
+    /// Parse all file patches in `input`, which may contain arbitrary text between them, like a commit message
+    /// as produced by `git format-patch`.
+    ///
+    /// Patches in the format produced by `git diff` are recognized by their `diff --git` line, while other unified diffs
+    /// are recognized by their `---` and `+++` lines that are followed by a hunk.
+    /// If no patch is found, the returned list is empty.
+    pub fn parse(input: &[u8], options: Options) -> Result<Vec<File>, Error> {
+        let mut lines = Lines { data: input, line: 1 };
+        let mut out = Vec::new();
+        while let Some(line) = lines.peek(0) {
+            if let Some(names) = line.strip_prefix(b"diff --git ") {
+                let header_line = lines.line;
+                lines.next();
+                out.push(git_patch(&mut lines, names, header_line, options)?);
+            } else if line.starts_with(b"--- ")
+                && lines.peek(1).map_or(false, |l| l.starts_with(b"+++ "))
+                && lines.peek(2).map_or(false, |l| l.starts_with(b"@@ -"))
+            {
+                out.push(traditional_patch(&mut lines, options)?);
+            } else {
+                lines.next();
+            }
+        }
+        Ok(out)
+    }
+
+    fn git_patch(lines: &mut Lines<'_>, names: &[u8], header_line: usize, options: Options) -> Result<File, Error> {
+        let name = header_name(without_newline(names).as_bstr(), options.strip_components);
+        let mut file = File {
+            old_path: name.clone(),
+            new_path: name,
+            old_mode: None,
+            new_mode: None,
+            old_id: None,
+            new_id: None,
+            operation: Operation::Modify,
+            similarity: None,
+            content: Content::Text(Vec::new()),
+        };
+        while let Some(line) = lines.peek(0).map(without_newline) {
+            let line_number = lines.line;
+            if let Some(mode) = line.strip_prefix(b"old mode ") {
+                file.old_mode = Some(parse_mode(mode, line_number)?);
+            } else if let Some(mode) = line.strip_prefix(b"new mode ") {
+                file.new_mode = Some(parse_mode(mode, line_number)?);
+            } else if let Some(mode) = line.strip_prefix(b"deleted file mode ") {
+                file.old_mode = Some(parse_mode(mode, line_number)?);
+                file.operation = Operation::Delete;
+            } else if let Some(mode) = line.strip_prefix(b"new file mode ") {
+                file.new_mode = Some(parse_mode(mode, line_number)?);
+                file.operation = Operation::Create;
+            } else if let Some(path) = line
+                .strip_prefix(b"rename from ")
+                .or_else(|| line.strip_prefix(b"rename old "))
+            {
+                file.old_path = Some(unquote(path, line_number)?.into_owned());
+                file.operation = Operation::Rename;
+            } else if let Some(path) = line
+                .strip_prefix(b"rename to ")
+                .or_else(|| line.strip_prefix(b"rename new "))
+            {
+                file.new_path = Some(unquote(path, line_number)?.into_owned());
+                file.operation = Operation::Rename;
+            } else if let Some(path) = line.strip_prefix(b"copy from ") {
+                file.old_path = Some(unquote(path, line_number)?.into_owned());
+                file.operation = Operation::Copy;
+            } else if let Some(path) = line.strip_prefix(b"copy to ") {
+                file.new_path = Some(unquote(path, line_number)?.into_owned());
+                file.operation = Operation::Copy;
+            } else if let Some(percent) = line
+                .strip_prefix(b"similarity index ")
+                .or_else(|| line.strip_prefix(b"dissimilarity index "))
+            {
+                file.similarity = percent
+                    .strip_suffix(b"%")
+                    .and_then(|p| p.to_str().ok())
+                    .and_then(|p| p.parse().ok());
+            } else if let Some(index) = line.strip_prefix(b"index ") {
+                let (ids, mode) = match index.find_byte(b' ') {
+                    Some(pos) => (&index[..pos], Some(parse_mode(&index[pos + 1..], line_number)?)),
+                    None => (index, None),
+                };
+                let (old, new) = ids.split_once_str("..").ok_or(Error::Corrupt {
+                    line: line_number,
+                    message: "index line lacks '..'",
+                })?;
+                file.old_id = Some(old.into());
+                file.new_id = Some(new.into());
+                if let Some(mode) = mode {
+                    file.old_mode.get_or_insert(mode);
+                    file.new_mode.get_or_insert(mode);
+                }
+            } else {
+                break;
+            }
+            lines.next();
+        }
+
+        if lines.peek(0).map_or(false, |l| l.starts_with(b"--- "))
+            && lines.peek(1).map_or(false, |l| l.starts_with(b"+++ "))
+        {
+            let (old, new) = marker_names(lines, options)?;
+            if file.old_path.is_none() {
+                file.old_path = old;
+            }
+            if file.new_path.is_none() {
+                file.new_path = new;
+            }
+        }
+        if lines.peek(0) == Some(b"GIT binary patch\n") {
+            lines.next();
+            file.content = Content::Binary(binary(lines)?);
+        } else if lines.peek(0).map_or(false, |l| l.starts_with(b"Binary files ")) {
+            lines.next();
+            file.content = Content::BinaryWithoutData;
+        } else {
+            file.content = Content::Text(hunks(lines)?);
+        }
+
+        match file.operation {
+            Operation::Create => file.old_path = None,
+            Operation::Delete => file.new_path = None,
+            Operation::Modify | Operation::Rename | Operation::Copy => {
+                if file.old_path.is_none() || file.new_path.is_none() {
+                    return Err(Error::MissingPath { line: header_line });
+                }
+            }
+        }
+        Ok(file)
+    }
+
+    fn traditional_patch(lines: &mut Lines<'_>, options: Options) -> Result<File, Error> {
+        let line = lines.line;
+        let (old_path, new_path) = marker_names(lines, options)?;
+        let operation = match (&old_path, &new_path) {
+            (None, Some(_)) => Operation::Create,
+            (Some(_), None) => Operation::Delete,
+            (Some(_), Some(_)) => Operation::Modify,
+            (None, None) => return Err(Error::MissingPath { line }),
+        };
+        Ok(File {
+            old_path,
+            new_path,
+            old_mode: None,
+            new_mode: None,
+            old_id: None,
+            new_id: None,
+            operation,
+            similarity: None,
+            content: Content::Text(hunks(lines)?),
+        })
+    }
+
+    /// Parse the `---` and `+++` lines, returning `None` for each name that is `/dev/null`.
+    fn marker_names(lines: &mut Lines<'_>, options: Options) -> Result<(Option<BString>, Option<BString>), Error> {
+        let mut parse = || -> Result<Option<BString>, Error> {
+            let line_number = lines.line;
+            let line = without_newline(&lines.next().expect("checked by caller")[4..]);
+            let name = if line.starts_with(b"\"") {
+                unquote(line, line_number)?
+            } else {
+                Cow::Borrowed(line.split_str("\t").next().unwrap_or_default().as_bstr())
+            };
+            if name.as_ref() == "/dev/null" {
+                return Ok(None);
+            }
+            strip_components(name.as_ref(), options.strip_components)
+                .map(|name| Some(name.to_owned()))
+                .ok_or_else(|| Error::StripComponents {
+                    line: line_number,
+                    components: options.strip_components,
+                    path: name.into_owned(),
+                })
+        };
+        Ok((parse()?, parse()?))
+    }
+
+    fn hunks(lines: &mut Lines<'_>) -> Result<Vec<Hunk>, Error> {
+        let mut out = Vec::new();
+        while let Some(header) = lines.peek(0).and_then(|l| l.strip_prefix(b"@@ -")) {
+            let line_number = lines.line;
+            lines.next();
+            let corrupt = |message| Error::Corrupt {
+                line: line_number,
+                message,
+            };
+            let header = without_newline(header);
+            let (old, rest) = header.split_once_str(" +").ok_or(corrupt("malformed hunk header"))?;
+            let (new, section) = rest.split_once_str(" @@").ok_or(corrupt("malformed hunk header"))?;
+            let (old_start, old_lines) = parse_range(old).ok_or(corrupt("malformed hunk header"))?;
+            let (new_start, new_lines) = parse_range(new).ok_or(corrupt("malformed hunk header"))?;
+
+            let mut hunk = Hunk {
+                old_start,
+                old_lines,
+                new_start,
+                new_lines,
+                section: section.strip_prefix(b" ").unwrap_or(section).into(),
+                lines: Vec::new(),
+            };
+            let (mut old_left, mut new_left) = (old_lines, new_lines);
+            while old_left > 0 || new_left > 0 {
+                let line_number = lines.line;
+                let corrupt = |message| Error::Corrupt {
+                    line: line_number,
+                    message,
+                };
+                let line = lines.next().ok_or(corrupt("unexpected end of hunk"))?;
+                let (kind, text) = match line[0] {
+                    b' ' => (LineKind::Context, &line[1..]),
+                    // Some editors remove the trailing space of empty context lines, which is why `git` accepts this as well.
+                    b'\n' => (LineKind::Context, line),
+                    b'-' => (LineKind::Removed, &line[1..]),
+                    b'+' => (LineKind::Added, &line[1..]),
+                    b'\\' => {
+                        remove_trailing_newline(&mut hunk);
+                        continue;
+                    }
+                    _ => return Err(corrupt("unexpected line in hunk")),
+                };
+                match kind {
+                    LineKind::Context if old_left > 0 && new_left > 0 => {
+                        old_left -= 1;
+                        new_left -= 1;
+                    }
+                    LineKind::Removed if old_left > 0 => old_left -= 1,
+                    LineKind::Added if new_left > 0 => new_left -= 1,
+                    _ => return Err(corrupt("hunk has more lines than its header announces")),
+                }
+                let mut text = BString::from(text);
+                if !text.ends_with(b"\n") {
+                    text.push(b'\n');
+                }
+                hunk.lines.push(Line { kind, text });
+            }
+            if lines.peek(0).map_or(false, |l| l.starts_with(b"\\")) {
+                lines.next();
+                remove_trailing_newline(&mut hunk);
+            }
+            out.push(hunk);
+        }
+        Ok(out)
+    }
+
+    fn remove_trailing_newline(hunk: &mut Hunk) {
+        if let Some(line) = hunk.lines.last_mut() {
+            if line.text.ends_with(b"\n") {
+                line.text.pop();
+            }
+        }
+    }
+
+    /// Parse `start[,count]`, with `count` defaulting to 1.
+    fn parse_range(range: &[u8]) -> Option<(u32, u32)> {
+        let parse = |n: &[u8]| n.to_str().ok()?.parse().ok();
+        match range.split_once_str(",") {
+            Some((start, count)) => Some((parse(start)?, parse(count)?)),
+            None => Some((parse(range)?, 1)),
+        }
+    }
+
+    fn binary(lines: &mut Lines<'_>) -> Result<Binary, Error> {
+        let line = lines.line;
+        let forward = binary_hunk(lines)?.ok_or(Error::Corrupt {
+            line,
+            message: "binary patch lacks 'literal' or 'delta' data",
+        })?;
+        let reverse = binary_hunk(lines)?;
+        Ok(Binary { forward, reverse })
+    }
+
+    fn binary_hunk(lines: &mut Lines<'_>) -> Result<Option<BinaryHunk>, Error> {
+        let header_line = lines.line;
+        let Some(header) = lines.peek(0).map(without_newline) else {
+            return Ok(None);
+        };
+        let (is_literal, size) = if let Some(size) = header.strip_prefix(b"literal ") {
+            (true, size)
+        } else if let Some(size) = header.strip_prefix(b"delta ") {
+            (false, size)
+        } else {
+            return Ok(None);
+        };
+        let size: usize = size.to_str().ok().and_then(|s| s.parse().ok()).ok_or(Error::Corrupt {
+            line: header_line,
+            message: "invalid size of binary data",
+        })?;
+        lines.next();
+
+        let mut compressed = Vec::new();
+        while let Some(line) = lines.peek(0).map(without_newline) {
+            let line_number = lines.line;
+            lines.next();
+            if line.is_empty() {
+                break;
+            }
+            let corrupt = Error::Corrupt {
+                line: line_number,
+                message: "invalid line in binary patch",
+            };
+            let len = base85::line_length(line[0]).ok_or(corrupt)?;
+            let encoded = &line[1..];
+            let max_len = encoded.len() / 5 * 4;
+            if encoded.len() % 5 != 0 || max_len < len || len + 4 <= max_len {
+                return Err(Error::Corrupt {
+                    line: line_number,
+                    message: "invalid line length in binary patch",
+                });
+            }
+            let start = compressed.len();
+            base85::decode(encoded, &mut compressed).ok_or(Error::Corrupt {
+                line: line_number,
+                message: "invalid characters in binary patch",
+            })?;
+            compressed.truncate(start + len);
+        }
+
+        let mut data = vec![0; size + 1];
+        let inflated = gix_features::zlib::Inflate::default()
+            .once(&compressed, &mut data)
+            .ok()
+            .and_then(|(status, _, written)| (status == gix_features::zlib::Status::StreamEnd).then_some(written));
+        if inflated != Some(size) {
+            return Err(Error::Corrupt {
+                line: header_line,
+                message: "binary data doesn't inflate to the announced size",
+            });
+        }
+        data.truncate(size);
+        Ok(Some(if is_literal {
+            BinaryHunk::Literal(data)
+        } else {
+            BinaryHunk::Delta(data)
+        }))
+    }
+
+    /// Parse the names of the `diff --git` line, which are only used if they are the same as they are ambiguous otherwise.
+    /// Renames and copies provide the names in their own headers.
+    fn header_name(names: &BStr, strip: usize) -> Option<BString> {
+        let same_name = |a: &BStr, b: &BStr| -> Option<BString> {
+            let a = strip_components(a, strip)?;
+            (a == strip_components(b, strip)?).then(|| a.to_owned())
+        };
+        if names.starts_with(b"\"") {
+            let (first, consumed) = gix_quote::ansi_c::undo(names).ok()?;
+            let second = names[consumed..].strip_prefix(b" ")?.as_bstr();
+            let (second, _) = gix_quote::ansi_c::undo(second).ok()?;
+            return same_name(first.as_ref(), second.as_ref());
+        }
+        if let Some(pos) = names.find(" \"") {
+            let (second, _) = gix_quote::ansi_c::undo(names[pos + 1..].as_bstr()).ok()?;
+            return same_name(names[..pos].as_bstr(), second.as_ref());
+        }
+        names
+            .find_iter(" ")
+            .find_map(|pos| same_name(names[..pos].as_bstr(), names[pos + 1..].as_bstr()))
+    }
+
+    fn unquote(path: &[u8], line: usize) -> Result<Cow<'_, BStr>, Error> {
+        gix_quote::ansi_c::undo(path.as_bstr())
+            .map(|(path, _)| path)
+            .map_err(|source| Error::Unquote { line, source })
+    }
+
+    fn parse_mode(mode: &[u8], line: usize) -> Result<EntryMode, Error> {
+        mode.to_str()
+            .ok()
+            .and_then(|mode| u16::from_str_radix(mode, 8).ok())
+            .map(EntryMode)
+            .ok_or(Error::Corrupt {
+                line,
+                message: "invalid file mode",
+            })
+    }
+
+    fn without_newline(line: &[u8]) -> &[u8] {
+        line.strip_suffix(b"\n").unwrap_or(line)
+    }
+}
+
+/// Remove `count` leading components from `path`, or return `None` if there are not enough of them.
+fn strip_components(mut path: &BStr, count: usize) -> Option<&BStr> {
+    for _ in 0..count {
+        let slash = path.find_byte(b'/')?;
+        path = path[slash + 1..].as_bstr();
+        while path.starts_with(b"/") {
+            path = path[1..].as_bstr();
+        }
+    }
+    (!path.is_empty()).then_some(path)
+}
+
+/// An iterator over lines that keep their newline, along with the one-based number of the next line.
+struct Lines<'a> {
+    data: &'a [u8],
+    line: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn peek(&self, nth: usize) -> Option<&'a [u8]> {
+        let mut data = self.data;
+        for _ in 0..nth {
+            data = &data[Self::line_end(data)?..];
+        }
+        Self::line_end(data).map(|end| &data[..end])
+    }
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let end = Self::line_end(self.data)?;
+        let (line, rest) = self.data.split_at(end);
+        self.data = rest;
+        self.line += 1;
+        Some(line)
+    }
+
+    fn line_end(data: &[u8]) -> Option<usize> {
+        if data.is_empty() {
+            return None;
+        }
+        Some(data.find_byte(b'\n').map_or(data.len(), |pos| pos + 1))
+    }
+}
@@ -0,0 +1,296 @@
+//! Detect and fix whitespace errors in added lines according to `core.whitespace`.
+use bstr::{BStr, BString, ByteSlice};
+
+bitflags::bitflags! {
+    /// The kinds of whitespace errors to detect, as configured with `core.whitespace`.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    pub struct Check: u16 {
+        /// Whitespace at the end of a line.
+        const BLANK_AT_EOL = 1 << 0;
+        /// A space immediately before a tab in the indentation of a line.
+        const SPACE_BEFORE_TAB = 1 << 1;
+        /// A line indented with at least `tabwidth` spaces instead of tabs.
+        const INDENT_WITH_NON_TAB = 1 << 2;
+        /// A carriage-return at the end of a line isn't considered whitespace at the end of a line.
+        const CR_AT_EOL = 1 << 3;
+        /// Blank lines added at the end of the file.
+        const BLANK_AT_EOF = 1 << 4;
+        /// A tab in the indentation of a line.
+        const TAB_IN_INDENT = 1 << 5;
+    }
+}
+
+/// The rules to check for, as configured with `core.whitespace`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rules {
+    /// The errors to detect.
+    pub check: Check,
+    /// The width of a tab, defaulting to `8`.
+    pub tab_width: u8,
+}
+
+impl Default for Rules {
+    /// The rules `git` uses if `core.whitespace` isn't set.
+    fn default() -> Self {
+        Rules {
+            check: Check::BLANK_AT_EOL | Check::SPACE_BEFORE_TAB | Check::BLANK_AT_EOF,
+            tab_width: 8,
+        }
+    }
+}
+
+///
+pub mod from_config {
+    use bstr::BString;
+
+    /// The error returned by [Rules::from_config()](super::Rules::from_config()).
+    #[derive(Debug, thiserror::Error)]
+    #[error("Invalid tab width in '{value}', it must be between 1 and 63")]
+    pub struct Error {
+        /// The value that contained the invalid tab width.
+        pub value: BString,
+    }
+}
+
+impl Rules {
+    /// Parse the comma-separated `value` of `core.whitespace`, where each rule can be prefixed with `-` to disable it.
+    ///
+    /// Unknown rules are ignored, just like `git` does.
+    pub fn from_config(value: &BStr) -> Result<Self, from_config::Error> {
+        let mut rules = Rules::default();
+        for token in value.split_str(",").map(ByteSlice::trim).filter(|t| !t.is_empty()) {
+            let (negated, token) = match token.strip_prefix(b"-") {
+                Some(token) => (true, token),
+                None => (false, token),
+            };
+            if let Some(width) = token.strip_prefix(b"tabwidth=") {
+                rules.tab_width = width
+                    .to_str()
+                    .ok()
+                    .and_then(|w| w.parse().ok())
+                    .filter(|w| (1..64).contains(w))
+                    .ok_or_else(|| from_config::Error {
+                        value: value.to_owned(),
+                    })?;
+                continue;
+            }
+            let check = match token {
+                b"trailing-space" => Check::BLANK_AT_EOL | Check::BLANK_AT_EOF,
+                b"blank-at-eol" => Check::BLANK_AT_EOL,
+                b"blank-at-eof" => Check::BLANK_AT_EOF,
+                b"space-before-tab" => Check::SPACE_BEFORE_TAB,
+                b"indent-with-non-tab" => Check::INDENT_WITH_NON_TAB,
+                b"cr-at-eol" => Check::CR_AT_EOL,
+                b"tab-in-indent" => Check::TAB_IN_INDENT,
+                _ => continue,
+            };
+            rules.check.set(check, !negated);
+        }
+        Ok(rules)
+    }
+
+    /// Return all whitespace errors in the single `line`, which may end in a newline.
+    ///
+    /// [`Check::BLANK_AT_EOF`] can't be detected by looking at a single line, and is never returned.
+    /// This is `ws_check()` in `git`.
+    pub fn check_line(&self, line: &[u8]) -> Check {
+        let mut line = line.strip_suffix(b"\n").unwrap_or(line);
+        if self.check.contains(Check::CR_AT_EOL) {
+            line = line.strip_suffix(b"\r").unwrap_or(line);
+        }
+        let mut result = Check::empty();
+        let mut trailing_whitespace = line.len();
+        if self.check.contains(Check::BLANK_AT_EOL) {
+            let content_end = line
+                .iter()
+                .rposition(|b| !b.is_ascii_whitespace())
+                .map_or(0, |pos| pos + 1);
+            if content_end != line.len() {
+                trailing_whitespace = content_end;
+                result |= Check::BLANK_AT_EOL;
+            }
+        }
+
+        let mut written = 0;
+        let mut indent_end = trailing_whitespace;
+        for (i, byte) in line[..trailing_whitespace].iter().enumerate() {
+            match byte {
+                b' ' => {}
+                b'\t' => {
+                    if self.check.contains(Check::SPACE_BEFORE_TAB) && written < i {
+                        result |= Check::SPACE_BEFORE_TAB;
+                    } else if self.check.contains(Check::TAB_IN_INDENT) {
+                        result |= Check::TAB_IN_INDENT;
+                    }
+                    written = i + 1;
+                }
+                _ => {
+                    indent_end = i;
+                    break;
+                }
+            }
+        }
+        if self.check.contains(Check::INDENT_WITH_NON_TAB) && indent_end - written >= usize::from(self.tab_width) {
+            result |= Check::INDENT_WITH_NON_TAB;
+        }
+        result
+    }
+
+    /// Return `line` with all of its whitespace errors fixed, or `None` if there was nothing to fix.
+    ///
+    /// This is `ws_fix_copy()` in `git`.
+    pub fn fix_line(&self, line: &[u8]) -> Option<BString> {
+        let tab_width = usize::from(self.tab_width);
+        let (mut src, newline) = match line.strip_suffix(b"\n") {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        let mut fixed = false;
+        let mut tail: &[u8] = if newline { b"\n" } else { b"" };
+        if self.check.contains(Check::BLANK_AT_EOL) {
+            let (content, cr) = match src.strip_suffix(b"\r") {
+                Some(content) if self.check.contains(Check::CR_AT_EOL) && newline => (content, true),
+                _ => (src, false),
+            };
+            let content_end = content
+                .iter()
+                .rposition(|b| !b.is_ascii_whitespace())
+                .map_or(0, |pos| pos + 1);
+            if content_end != content.len() {
+                src = &content[..content_end];
+                if cr {
+                    tail = b"\r\n";
+                }
+                fixed = true;
+            }
+        }
+
+        let mut last_tab_in_indent = None;
+        let mut last_space_in_indent = None;
+        let mut need_fix_leading_space = false;
+        for (i, byte) in src.iter().enumerate() {
+            match byte {
+                b'\t' => {
+                    last_tab_in_indent = Some(i);
+                    if self.check.contains(Check::SPACE_BEFORE_TAB) && last_space_in_indent.is_some() {
+                        need_fix_leading_space = true;
+                    }
+                }
+                b' ' => {
+                    last_space_in_indent = Some(i);
+                    if self.check.contains(Check::INDENT_WITH_NON_TAB)
+                        && last_tab_in_indent.is_none()
+                        && tab_width <= i + 1
+                    {
+                        need_fix_leading_space = true;
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        let mut out = BString::default();
+        if need_fix_leading_space {
+            let last = if self.check.contains(Check::INDENT_WITH_NON_TAB) {
+                last_tab_in_indent.max(last_space_in_indent)
+            } else {
+                last_tab_in_indent
+            }
+            .map_or(0, |pos| pos + 1);
+            let mut consecutive_spaces = 0;
+            for byte in &src[..last] {
+                if *byte == b' ' {
+                    consecutive_spaces += 1;
+                    if consecutive_spaces == tab_width {
+                        out.push(b'\t');
+                        consecutive_spaces = 0;
+                    }
+                } else {
+                    consecutive_spaces = 0;
+                    out.push(*byte);
+                }
+            }
+            out.extend(std::iter::repeat(b' ').take(consecutive_spaces));
+            src = &src[last..];
+            fixed = true;
+        } else if let Some(last_tab) = last_tab_in_indent.filter(|_| self.check.contains(Check::TAB_IN_INDENT)) {
+            for byte in &src[..=last_tab] {
+                if *byte == b'\t' {
+                    out.push(b' ');
+                    while out.len() % tab_width != 0 {
+                        out.push(b' ');
+                    }
+                } else {
+                    out.push(*byte);
+                }
+            }
+            src = &src[last_tab + 1..];
+            fixed = true;
+        }
+
+        if !fixed {
+            return None;
+        }
+        out.extend_from_slice(src);
+        out.extend_from_slice(tail);
+        Some(out)
+    }
+}
+
+/// Return `true` if `line` consists only of whitespace.
+pub(crate) fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
+
+/// Determine what to do with whitespace errors, as configured with `apply.whitespace` or `--whitespace=<action>`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Don't check for whitespace errors.
+    NoWarn,
+    /// Report whitespace errors, but apply the patch as is.
+    #[default]
+    Warn,
+    /// Fix whitespace errors in added lines and report them, like `fix` or `strip`.
+    Fix,
+    /// Refuse to apply a patch with whitespace errors.
+    Error,
+    /// Like [`Action::Error`], which is the same here as all errors are always reported.
+    ErrorAll,
+}
+
+impl Action {
+    /// Parse the value of `apply.whitespace`, returning `None` if it's unknown.
+    pub fn from_config(value: &BStr) -> Option<Self> {
+        Some(match value.as_bytes() {
+            b"nowarn" => Action::NoWarn,
+            b"warn" => Action::Warn,
+            b"fix" | b"strip" => Action::Fix,
+            b"error" => Action::Error,
+            b"error-all" => Action::ErrorAll,
+            _ => return None,
+        })
+    }
+
+    /// Return `true` if whitespace errors should be detected.
+    pub fn is_checking(&self) -> bool {
+        !matches!(self, Action::NoWarn)
+    }
+
+    /// Return `true` if whitespace errors prevent the patch from being applied.
+    pub fn is_error(&self) -> bool {
+        matches!(self, Action::Error | Action::ErrorAll)
+    }
+}
+
+/// A whitespace error found in an added line.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Problem {
+    /// The zero-based index of the hunk the line is in.
+    pub hunk: usize,
+    /// The zero-based index of the line within the hunk.
+    pub line: usize,
+    /// The errors that were found.
+    pub check: Check,
+    /// `true` if the errors were fixed, i.e. [`Action::Fix`] was used.
+    pub fixed: bool,
+}
@@ -0,0 +1,224 @@
+use std::io::{self, Write};
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_object::tree::EntryMode;
+
+use crate::{base85, Binary, BinaryHunk, Content, File, Hunk, LineKind, Operation};
+
+impl File {
+    /// Write this patch in the format produced by `git diff`, with paths prefixed with `a/` and `b/`.
+    ///
+    /// Paths are quoted if they contain special or non-ASCII characters, just like `git` does by default.
+    pub fn write_to(&self, out: &mut dyn Write) -> io::Result<()> {
+        let old_path = self.old_path.as_ref().or(self.new_path.as_ref()).map(|p| p.as_bstr());
+        let new_path = self.new_path.as_ref().or(self.old_path.as_ref()).map(|p| p.as_bstr());
+        out.write_all(b"diff --git ")?;
+        write_path(out, b"a/", old_path)?;
+        out.write_all(b" ")?;
+        write_path(out, b"b/", new_path)?;
+        out.write_all(b"\n")?;
+
+        match self.operation {
+            Operation::Create => {
+                if let Some(mode) = self.new_mode {
+                    writeln!(out, "new file mode {}", mode_str(mode))?;
+                }
+            }
+            Operation::Delete => {
+                if let Some(mode) = self.old_mode {
+                    writeln!(out, "deleted file mode {}", mode_str(mode))?;
+                }
+            }
+            Operation::Modify | Operation::Rename | Operation::Copy => {
+                if let (Some(old), Some(new)) = (self.old_mode, self.new_mode) {
+                    if old != new {
+                        writeln!(out, "old mode {}", mode_str(old))?;
+                        writeln!(out, "new mode {}", mode_str(new))?;
+                    }
+                }
+                if let Some(similarity) = self.similarity.filter(|_| self.operation != Operation::Modify) {
+                    writeln!(out, "similarity index {similarity}%")?;
+                }
+                let kind: &[u8] = match self.operation {
+                    Operation::Rename => b"rename",
+                    Operation::Copy => b"copy",
+                    _ => b"",
+                };
+                if !kind.is_empty() {
+                    out.write_all(kind)?;
+                    out.write_all(b" from ")?;
+                    write_path(out, b"", old_path)?;
+                    out.write_all(b"\n")?;
+                    out.write_all(kind)?;
+                    out.write_all(b" to ")?;
+                    write_path(out, b"", new_path)?;
+                    out.write_all(b"\n")?;
+                }
+            }
+        }
+
+        let has_content = !matches!(&self.content, Content::Text(hunks) if hunks.is_empty());
+        if let (Some(old_id), Some(new_id)) = (&self.old_id, &self.new_id) {
+            if has_content || matches!(self.operation, Operation::Create | Operation::Delete) {
+                write!(out, "index {old_id}..{new_id}")?;
+                match (self.old_mode, self.new_mode) {
+                    (Some(old), Some(new)) if old == new && self.operation == Operation::Modify => {
+                        write!(out, " {}", mode_str(old))?;
+                    }
+                    _ => {}
+                }
+                out.write_all(b"\n")?;
+            }
+        }
+
+        match &self.content {
+            Content::Text(hunks) => {
+                if !hunks.is_empty() {
+                    write_markers(out, self)?;
+                }
+                for hunk in hunks {
+                    write_hunk(out, hunk)?;
+                }
+            }
+            Content::Binary(binary) => {
+                out.write_all(b"GIT binary patch\n")?;
+                write_binary(out, binary)?;
+            }
+            Content::BinaryWithoutData => {
+                out.write_all(b"Binary files ")?;
+                match self.old_path.as_ref().filter(|_| self.operation != Operation::Create) {
+                    Some(path) => write_path(out, b"a/", Some(path.as_ref()))?,
+                    None => out.write_all(b"/dev/null")?,
+                }
+                out.write_all(b" and ")?;
+                match self.new_path.as_ref().filter(|_| self.operation != Operation::Delete) {
+                    Some(path) => write_path(out, b"b/", Some(path.as_ref()))?,
+                    None => out.write_all(b"/dev/null")?,
+                }
+                out.write_all(b" differ\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Hunk {
+    /// Write this hunk including its header, marking lines without newline with `\ No newline at end of file`.
+    pub fn write_to(&self, out: &mut dyn Write) -> io::Result<()> {
+        write_hunk(out, self)
+    }
+}
+
+fn write_markers(out: &mut dyn Write, file: &File) -> io::Result<()> {
+    out.write_all(b"--- ")?;
+    match file.old_path.as_ref().filter(|_| file.operation != Operation::Create) {
+        Some(path) => write_path(out, b"a/", Some(path.as_ref()))?,
+        None => out.write_all(b"/dev/null")?,
+    }
+    out.write_all(b"\n+++ ")?;
+    match file.new_path.as_ref().filter(|_| file.operation != Operation::Delete) {
+        Some(path) => write_path(out, b"b/", Some(path.as_ref()))?,
+        None => out.write_all(b"/dev/null")?,
+    }
+    out.write_all(b"\n")
+}
+
+fn write_hunk(out: &mut dyn Write, hunk: &Hunk) -> io::Result<()> {
+    out.write_all(b"@@ -")?;
+    write_range(out, hunk.old_start, hunk.old_lines)?;
+    out.write_all(b" +")?;
+    write_range(out, hunk.new_start, hunk.new_lines)?;
+    out.write_all(b" @@")?;
+    if !hunk.section.is_empty() {
+        out.write_all(b" ")?;
+        out.write_all(&hunk.section)?;
+    }
+    out.write_all(b"\n")?;
+    for line in &hunk.lines {
+        out.write_all(match line.kind {
+            LineKind::Context => b" ",
+            LineKind::Removed => b"-",
+            LineKind::Added => b"+",
+        })?;
+        out.write_all(&line.text)?;
+        if !line.text.ends_with(b"\n") {
+            out.write_all(b"\n\\ No newline at end of file\n")?;
+        }
+    }
+    Ok(())
+}
+
+fn write_range(out: &mut dyn Write, start: u32, lines: u32) -> io::Result<()> {
+    if lines == 1 {
+        write!(out, "{start}")
+    } else {
+        write!(out, "{start},{lines}")
+    }
+}
+
+fn write_binary(out: &mut dyn Write, binary: &Binary) -> io::Result<()> {
+    write_binary_hunk(out, &binary.forward)?;
+    if let Some(reverse) = &binary.reverse {
+        write_binary_hunk(out, reverse)?;
+    }
+    Ok(())
+}
+
+fn write_binary_hunk(out: &mut dyn Write, hunk: &BinaryHunk) -> io::Result<()> {
+    let (kind, data) = match hunk {
+        BinaryHunk::Literal(data) => ("literal", data),
+        BinaryHunk::Delta(data) => ("delta", data),
+    };
+    writeln!(out, "{kind} {}", data.len())?;
+    let mut deflate = gix_features::zlib::stream::deflate::Write::new(Vec::new());
+    deflate.write_all(data)?;
+    deflate.flush()?;
+    let compressed = deflate.into_inner();
+
+    let mut line = Vec::with_capacity(70);
+    for chunk in compressed.chunks(52) {
+        line.clear();
+        line.push(base85::line_length_char(chunk.len()));
+        base85::encode(chunk, &mut line);
+        line.push(b'\n');
+        out.write_all(&line)?;
+    }
+    out.write_all(b"\n")
+}
+
+/// Write `path` with `prefix`, quoting it like `git` does if necessary.
+fn write_path(out: &mut dyn Write, prefix: &[u8], path: Option<&BStr>) -> io::Result<()> {
+    let path = path.unwrap_or_default();
+    if !path.iter().any(|b| needs_quoting(*b)) {
+        out.write_all(prefix)?;
+        return out.write_all(path);
+    }
+    let mut quoted = BString::from("\"");
+    quoted.extend_from_slice(prefix);
+    for byte in path.iter().copied() {
+        match byte {
+            b'\x07' => quoted.extend_from_slice(b"\\a"),
+            b'\x08' => quoted.extend_from_slice(b"\\b"),
+            b'\t' => quoted.extend_from_slice(b"\\t"),
+            b'\n' => quoted.extend_from_slice(b"\\n"),
+            b'\x0b' => quoted.extend_from_slice(b"\\v"),
+            b'\x0c' => quoted.extend_from_slice(b"\\f"),
+            b'\r' => quoted.extend_from_slice(b"\\r"),
+            b'"' => quoted.extend_from_slice(b"\\\""),
+            b'\\' => quoted.extend_from_slice(b"\\\\"),
+            byte if needs_quoting(byte) => quoted.extend_from_slice(format!("\\{byte:03o}").as_bytes()),
+            byte => quoted.push(byte),
+        }
+    }
+    quoted.push(b'"');
+    out.write_all(&quoted)
+}
+
+fn needs_quoting(byte: u8) -> bool {
+    byte < 0x20 || byte == b'"' || byte == b'\\' || byte >= 0x7f
+}
+
+fn mode_str(mode: EntryMode) -> BString {
+    let mut buf = Default::default();
+    mode.as_bytes(&mut buf).into()
+}
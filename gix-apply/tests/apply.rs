@@ -0,0 +1,9 @@
+mod diff;
+mod mail;
+mod parse;
+mod text;
+mod whitespace;
+
+fn parse(patch: &str) -> Vec<gix_apply::File> {
+    gix_apply::parse(patch.as_bytes(), Default::default()).expect("valid patch")
+}
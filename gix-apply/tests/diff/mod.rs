@@ -0,0 +1,87 @@
+use bstr::ByteSlice;
+use gix_apply::{Content, File, LineKind, Operation};
+use gix_object::tree::EntryKind;
+
+#[test]
+fn hunks_with_context_are_merged_and_written_like_git() {
+    let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+    let new = "1\nzwei\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\nthirteen";
+    let hunks = gix_apply::diff::hunks(gix_apply::diff::Algorithm::Myers, old.as_bytes(), new.as_bytes(), 3);
+    assert_eq!(hunks.len(), 2, "the changes are too far apart to share a hunk");
+    assert_eq!((hunks[0].old_start, hunks[0].old_lines), (1, 5));
+    assert_eq!((hunks[0].new_start, hunks[0].new_lines), (1, 5));
+    assert_eq!((hunks[1].old_start, hunks[1].old_lines), (10, 3));
+    assert_eq!((hunks[1].new_start, hunks[1].new_lines), (10, 4));
+
+    let merged = gix_apply::diff::hunks(gix_apply::diff::Algorithm::Myers, old.as_bytes(), new.as_bytes(), 5);
+    assert_eq!(merged.len(), 1, "with more context, both changes end up in one hunk");
+    assert_eq!(merged[0].lines.iter().filter(|l| l.kind == LineKind::Added).count(), 2);
+
+    let file = File {
+        old_path: Some("a.txt".into()),
+        new_path: Some("a.txt".into()),
+        old_mode: Some(EntryKind::Blob.into()),
+        new_mode: Some(EntryKind::Blob.into()),
+        old_id: Some("1234567".into()),
+        new_id: Some("89abcde".into()),
+        operation: Operation::Modify,
+        similarity: None,
+        content: Content::Text(hunks),
+    };
+    let mut buf = Vec::new();
+    file.write_to(&mut buf).unwrap();
+    assert_eq!(
+        buf.as_bstr(),
+        "diff --git a/a.txt b/a.txt
+index 1234567..89abcde 100644
+--- a/a.txt
++++ b/a.txt
+@@ -1,5 +1,5 @@
+ 1
+-2
++zwei
+ 3
+ 4
+ 5
+@@ -10,3 +10,4 @@
+ 10
+ 11
+ 12
++thirteen
+\\ No newline at end of file
+"
+    );
+
+    let parsed = gix_apply::parse(&buf, Default::default()).expect("written patches can be parsed");
+    assert_eq!(parsed, vec![file]);
+    let Content::Text(hunks) = &parsed[0].content else {
+        unreachable!("it's a text patch")
+    };
+    let applied = gix_apply::apply::text(old.as_bytes(), hunks, Default::default()).expect("applies cleanly");
+    assert_eq!(applied.data.as_bstr(), new);
+}
+
+#[test]
+fn binary_patches_and_quoted_paths_round_trip() {
+    let file = File {
+        old_path: None,
+        new_path: Some("dir/\u{e4}.bin".into()),
+        old_mode: None,
+        new_mode: Some(EntryKind::Blob.into()),
+        old_id: Some("0000000".into()),
+        new_id: Some("89abcde".into()),
+        operation: Operation::Create,
+        similarity: None,
+        content: Content::Binary(gix_apply::Binary {
+            forward: gix_apply::BinaryHunk::Literal((0..200u8).collect()),
+            reverse: Some(gix_apply::BinaryHunk::Literal(Vec::new())),
+        }),
+    };
+    let mut buf = Vec::new();
+    file.write_to(&mut buf).unwrap();
+    assert!(buf.starts_with(
+        b"diff --git \"a/dir/\\303\\244.bin\" \"b/dir/\\303\\244.bin\"\nnew file mode 100644\nindex 0000000..89abcde\nGIT binary patch\nliteral 200\n"
+    ));
+    let parsed = gix_apply::parse(&buf, Default::default()).expect("written patches can be parsed");
+    assert_eq!(parsed, vec![file]);
+}
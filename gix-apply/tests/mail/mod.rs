@@ -0,0 +1,210 @@
+use bstr::ByteSlice;
+use gix_apply::mail;
+
+#[test]
+fn split_mailbox_and_parse_with_rfc2047_headers() {
+    let mbox = "From 1234567890abcdef1234567890abcdef12345678 Mon Sep 17 00:00:00 2001
+From: =?UTF-8?q?J=C3=B6rg=20M=C3=BCller?= <joerg@example.com>
+Date: Tue, 3 Sep 2024 10:00:00 +0200
+Subject: [PATCH 1/2] Re: [RFC] fix the
+ frobnicator
+Message-Id: <1@example.com>
+
+Explain what happened.
+
+
+---
+ a | 2 +-
+diff --git a/a b/a
+--- a/a
++++ b/a
+@@ -1 +1 @@
+-a
++b
+From 234567890abcdef1234567890abcdef123456789 Mon Sep 17 00:00:00 2001
+From: \"Doe, Jane\" <jane@example.com>
+Subject: =?ISO-8859-1?Q?caf=E9?=
+ =?ISO-8859-1?Q?_au_lait?=
+
+diff --git a/b b/b
+";
+    let messages: Vec<_> = mail::split(mbox.as_bytes()).collect();
+    assert_eq!(messages.len(), 2);
+    assert!(messages[1].starts_with(b"From: \"Doe, Jane\""));
+
+    let info = mail::parse(messages[0], Default::default()).expect("valid");
+    assert_eq!(info.author.name, "Jörg Müller");
+    assert_eq!(info.author.email, "joerg@example.com");
+    assert_eq!(info.subject, "fix the frobnicator", "prefixes are removed");
+    assert_eq!(info.message, "Explain what happened.\n");
+    assert_eq!(info.message_id.as_ref().expect("set"), "<1@example.com>");
+    assert!(info.patch.starts_with(b"---\n a | 2 +-\ndiff --git"));
+    assert_eq!(
+        info.time().expect("valid date"),
+        gix_date::parse("Tue, 3 Sep 2024 10:00:00 +0200", None).unwrap()
+    );
+    assert_eq!(info.commit_message(), "fix the frobnicator\n\nExplain what happened.\n");
+
+    let info = mail::parse(messages[1], Default::default()).expect("valid");
+    assert_eq!(info.author.name, "Doe, Jane");
+    assert_eq!(
+        info.subject, "café au lait",
+        "whitespace between encoded words is dropped"
+    );
+    assert_eq!(info.message, "");
+    assert_eq!(info.commit_message(), "café au lait\n");
+    assert_eq!(info.date, None);
+}
+
+#[test]
+fn subject_cleanup_can_be_configured() {
+    let message = b"Subject: [PATCH v2] [topic] Re: subject\n\nbody\n";
+    let parse = |options| mail::parse(message, options).expect("valid").subject;
+    assert_eq!(parse(mail::parse::Options::default()), "subject");
+    assert_eq!(
+        parse(mail::parse::Options {
+            keep_non_patch_brackets: true,
+            ..Default::default()
+        }),
+        "[topic] subject"
+    );
+    assert_eq!(
+        parse(mail::parse::Options {
+            keep_subject: true,
+            ..Default::default()
+        }),
+        "[PATCH v2] [topic] Re: subject"
+    );
+}
+
+#[test]
+fn in_body_headers_and_scissors() {
+    let message = "From: Sender <sender@example.com>
+Subject: [PATCH] wrong subject
+Content-Type: text/plain; charset=ISO-8859-1
+Content-Transfer-Encoding: quoted-printable
+
+Some discussion which isn't part of the commit message.
+
+-- >8 --
+From: Author Name <author@example.com>
+Subject: the real subject
+
+The real caf=E9 message,
+which continues here.=20
+---
+diff --git a/a b/a
+";
+    let info = mail::parse(
+        message.as_bytes(),
+        mail::parse::Options {
+            scissors: true,
+            ..Default::default()
+        },
+    )
+    .expect("valid");
+    assert_eq!(info.author.name, "Author Name");
+    assert_eq!(info.author.email, "author@example.com");
+    assert_eq!(info.subject, "the real subject");
+    assert_eq!(info.message, "The real café message,\nwhich continues here.\n");
+
+    let info = mail::parse(message.as_bytes(), Default::default()).expect("valid");
+    assert_eq!(
+        info.author.name, "Sender",
+        "in-body headers are only used at the beginning"
+    );
+    assert_eq!(info.subject, "wrong subject");
+    assert!(info.message.starts_with(b"Some discussion"));
+    assert!(info.message.contains_str("-- >8 --"));
+}
+
+#[test]
+fn unusual_from_headers() {
+    for (from, name, email) in [
+        ("jane@example.com", "jane@example.com", "jane@example.com"),
+        ("jane@example.com (Jane Doe)", "Jane Doe", "jane@example.com"),
+        (
+            "\"Jane \\\"JD\\\" Doe\" <jane@example.com>",
+            "Jane \"JD\" Doe",
+            "jane@example.com",
+        ),
+        ("Jane Doe <jane>", "Jane Doe", "jane"),
+    ] {
+        let message = format!("From: {from}\nSubject: s\n\n");
+        let info = mail::parse(message.as_bytes(), Default::default()).expect("valid");
+        assert_eq!(info.author.name, name, "{from}");
+        assert_eq!(info.author.email, email, "{from}");
+    }
+}
+
+#[test]
+fn base64_multipart_messages_are_decoded() {
+    let message = "From: A <a@example.com>
+Subject: multipart
+Content-Type: multipart/mixed; boundary=\"XYZ\"
+
+preamble
+--XYZ
+Content-Type: text/plain; charset=utf-8
+Content-Transfer-Encoding: base64
+
+bWVzc2FnZQo=
+--XYZ
+Content-Type: text/x-patch
+
+---
+diff --git a/a b/a
+--XYZ--
+";
+    let info = mail::parse(message.as_bytes(), Default::default()).expect("valid");
+    assert_eq!(info.message, "message\n");
+    assert_eq!(info.patch, "---\ndiff --git a/a b/a\n");
+}
+
+#[test]
+fn write_email() {
+    let id = gix_hash::ObjectId::empty_tree(gix_hash::Kind::Sha1);
+    let author = gix_actor::SignatureRef {
+        name: "Jörg, the author".into(),
+        email: "joerg@example.com".into(),
+        time: gix_date::parse("Tue, 3 Sep 2024 10:00:00 +0200", None).unwrap(),
+    };
+    let mut buf = Vec::new();
+    mail::Email {
+        id: &id,
+        author,
+        subject_prefix: Some("PATCH 1/2".into()),
+        subject: "a subject that is so long that it needs to be wrapped onto the next line of the header".into(),
+        body: "Bödy\n".into(),
+    }
+    .write_to(&mut buf)
+    .unwrap();
+    buf.extend_from_slice(b"---\n");
+    mail::write::write_signature(&mut buf, "2.46.0".into()).unwrap();
+    assert_eq!(
+        buf.as_bstr(),
+        "From 4b825dc642cb6eb9a060e54bf8d69288fbee4904 Mon Sep 17 00:00:00 2001
+From: =?UTF-8?q?J=C3=B6rg=2C=20the=20author?= <joerg@example.com>
+Date: Tue, 3 Sep 2024 10:00:00 +0200
+Subject: [PATCH 1/2] a subject that is so long that it needs to be wrapped
+ onto the next line of the header
+MIME-Version: 1.0
+Content-Type: text/plain; charset=UTF-8
+Content-Transfer-Encoding: 8bit
+
+Bödy
+---
+-- 
+2.46.0
+
+"
+    );
+
+    let info = mail::parse(&buf, Default::default()).expect("written emails can be parsed");
+    assert_eq!(info.author.name, "Jörg, the author");
+    assert_eq!(
+        info.subject,
+        "a subject that is so long that it needs to be wrapped onto the next line of the header"
+    );
+    assert_eq!(info.message, "Bödy\n");
+}
@@ -0,0 +1,217 @@
+use gix_apply::{BinaryHunk, Content, LineKind, Operation};
+use gix_object::tree::{EntryKind, EntryMode};
+
+use crate::parse;
+
+#[test]
+fn modification_with_extended_headers_and_missing_newline() {
+    let files = parse(
+        "commit message, which is ignored
+---
+diff --git a/src/old.rs b/src/new.rs
+old mode 100644
+new mode 100755
+similarity index 90%
+rename from src/old.rs
+rename to src/new.rs
+index 1234567..89abcde
+--- a/src/old.rs
++++ b/src/new.rs
+@@ -1,3 +1,3 @@ fn main() {
+ a
+-b
++B
+ c
+\\ No newline at end of file
+",
+    );
+    assert_eq!(files.len(), 1);
+    let file = &files[0];
+    assert_eq!(file.old_path.as_ref().expect("set"), "src/old.rs");
+    assert_eq!(file.new_path.as_ref().expect("set"), "src/new.rs");
+    assert_eq!(file.path(), "src/new.rs");
+    assert_eq!(file.operation, Operation::Rename);
+    assert_eq!(file.similarity, Some(90));
+    assert_eq!(file.old_mode, Some(EntryKind::Blob.into()));
+    assert_eq!(file.new_mode, Some(EntryKind::BlobExecutable.into()));
+    assert_eq!(file.old_id.as_ref().expect("set"), "1234567");
+    assert_eq!(file.new_id.as_ref().expect("set"), "89abcde");
+
+    let Content::Text(hunks) = &file.content else {
+        unreachable!("text patch")
+    };
+    assert_eq!(hunks.len(), 1);
+    let hunk = &hunks[0];
+    assert_eq!(
+        (hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines),
+        (1, 3, 1, 3)
+    );
+    assert_eq!(hunk.section, "fn main() {");
+    let lines: Vec<_> = hunk.lines.iter().map(|l| (l.kind, l.text.to_string())).collect();
+    assert_eq!(
+        lines,
+        [
+            (LineKind::Context, "a\n".into()),
+            (LineKind::Removed, "b\n".into()),
+            (LineKind::Added, "B\n".into()),
+            (LineKind::Context, "c".into()),
+        ]
+    );
+}
+
+#[test]
+fn creation_deletion_and_mode_changes() {
+    let files = parse(
+        "diff --git a/new b/new
+new file mode 100644
+index 0000000..e69de29
+diff --git a/gone b/gone
+deleted file mode 120000
+index 1234567..0000000
+--- a/gone
++++ /dev/null
+@@ -1 +0,0 @@
+-target
+\\ No newline at end of file
+diff --git a/dir with space/file b/dir with space/file
+old mode 100644
+new mode 100755
+",
+    );
+    assert_eq!(files.len(), 3);
+
+    assert_eq!(files[0].operation, Operation::Create);
+    assert_eq!(files[0].old_path, None);
+    assert_eq!(files[0].new_path.as_ref().expect("set"), "new");
+    assert!(files[0].is_content_unchanged());
+
+    assert_eq!(files[1].operation, Operation::Delete);
+    assert_eq!(files[1].old_path.as_ref().expect("set"), "gone");
+    assert_eq!(files[1].new_path, None);
+    assert_eq!(files[1].old_mode, Some(EntryMode(0o120000)));
+
+    assert_eq!(files[2].operation, Operation::Modify);
+    assert_eq!(files[2].path(), "dir with space/file");
+    assert!(files[2].new_mode.expect("set").is_executable());
+}
+
+#[test]
+fn quoted_paths() {
+    let files = parse(
+        "diff --git \"a/t\\303\\244st\" \"b/t\\303\\244st\"
+index 1234567..89abcde 100644
+--- \"a/t\\303\\244st\"
++++ \"b/t\\303\\244st\"
+@@ -1 +1 @@
+-a
++b
+",
+    );
+    assert_eq!(files[0].path(), "täst");
+    assert_eq!(files[0].old_mode, Some(EntryKind::Blob.into()));
+}
+
+#[test]
+fn traditional_diff_with_custom_strip() {
+    let files = gix_apply::parse(
+        b"--- file.orig\t2024-01-01 00:00:00
++++ file\t2024-01-01 00:00:00
+@@ -1,2 +1,2 @@
+-a
++b
+ c
+",
+        gix_apply::parse::Options { strip_components: 0 },
+    )
+    .expect("valid");
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].operation, Operation::Modify);
+    assert_eq!(files[0].old_path.as_ref().expect("set"), "file.orig");
+    assert_eq!(files[0].new_path.as_ref().expect("set"), "file");
+}
+
+#[test]
+fn binary_literal_and_delta() {
+    let files = parse(
+        "diff --git a/small b/small
+new file mode 100644
+index 0000000000000000000000000000000000000000..d5d0b8b4c4c9e936890870f6799cfbb5ba984470
+GIT binary patch
+literal 3
+Kcmb<ms0083<N)#j
+
+literal 0
+HcmV?d00001
+
+diff --git a/bin b/bin
+index 6033d2837bc4dea17330b234b0b05289d9fab798..c60adb99658e540edbd6a33c16c2b9c73d89f777 100644
+GIT binary patch
+delta 28
+ecmX@Ya)f1q1dFeKkn3heMrFpy7Z^*RSQY?>2??42
+
+delta 24
+RcmX@Ya)f2_1;!F|3;>6^3dsNf
+
+",
+    );
+    assert_eq!(files.len(), 2);
+    let Content::Binary(binary) = &files[0].content else {
+        unreachable!("binary patch")
+    };
+    assert_eq!(binary.forward, BinaryHunk::Literal(b"x\0y".to_vec()));
+    assert_eq!(binary.reverse, Some(BinaryHunk::Literal(Vec::new())));
+
+    let Content::Binary(binary) = &files[1].content else {
+        unreachable!("binary patch")
+    };
+    let old: Vec<u8> = std::iter::repeat(&b"\x00\x01\x02binary-data-here-and-more\x00"[..])
+        .take(20)
+        .flatten()
+        .copied()
+        .collect();
+    let new = gix_apply::apply::binary(&old, &binary.forward).expect("delta applies");
+    let mut expected = old.clone();
+    expected[24..28].copy_from_slice(b"MORE");
+    assert_eq!(new, expected);
+    let reverse = binary.reverse.as_ref().expect("present");
+    assert_eq!(
+        gix_apply::apply::binary(&new, reverse).expect("reverse delta applies"),
+        old
+    );
+    assert!(
+        gix_apply::apply::binary(b"other", &binary.forward).is_err(),
+        "deltas must fit their base"
+    );
+}
+
+#[test]
+fn binary_files_differ() {
+    let files = parse(
+        "diff --git a/bin b/bin
+index 1234567..89abcde 100644
+Binary files a/bin and b/bin differ
+",
+    );
+    assert_eq!(files[0].content, Content::BinaryWithoutData);
+}
+
+#[test]
+fn corrupt_patches_are_rejected() {
+    let err = gix_apply::parse(
+        b"diff --git a/f b/f
+--- a/f
++++ b/f
+@@ -1,2 +1,2 @@
+-a
++b
+",
+        Default::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Corrupt patch at line 7: unexpected end of hunk");
+
+    assert!(
+        gix_apply::parse(b"--- f\n+++ f\n@@ -1 +1 @@\n-a\n+b\n", Default::default()).is_err(),
+        "cannot strip a component from 'f'"
+    );
+}
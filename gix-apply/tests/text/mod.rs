@@ -0,0 +1,140 @@
+use bstr::ByteSlice;
+use gix_apply::{apply, Content, Hunk};
+
+use crate::parse;
+
+fn hunks(patch: &str) -> Vec<Hunk> {
+    let mut files = parse(patch);
+    match files.pop().expect("one file").content {
+        Content::Text(hunks) => hunks,
+        _ => unreachable!("text patch"),
+    }
+}
+
+fn apply(old: &str, patch: &str, options: apply::Options) -> Result<apply::Outcome, apply::text::Error> {
+    apply::text(old.as_bytes(), &hunks(patch), options)
+}
+
+const PATCH: &str = "--- a/f
++++ b/f
+@@ -2,3 +2,3 @@
+ b
+-c
++C
+ d
+@@ -7,2 +7,3 @@
+ g
+ h
++i
+";
+
+#[test]
+fn hunks_apply_at_their_position() {
+    let out = apply("a\nb\nc\nd\ne\nf\ng\nh\n", PATCH, Default::default()).expect("applies");
+    assert_eq!(out.data.as_bstr(), "a\nb\nC\nd\ne\nf\ng\nh\ni\n");
+    assert_eq!(out.hunks.len(), 2);
+    assert!(out.hunks.iter().all(|h| h.offset == 0 && h.reduced_context == 0));
+}
+
+#[test]
+fn hunks_are_found_if_lines_moved() {
+    let out = apply("0\n1\na\nb\nc\nd\ne\nf\ng\nh\n", PATCH, Default::default()).expect("applies");
+    assert_eq!(out.data.as_bstr(), "0\n1\na\nb\nC\nd\ne\nf\ng\nh\ni\n");
+    assert_eq!(out.hunks[0].offset, 2);
+    assert_eq!(out.hunks[0].line, 3);
+    assert_eq!(out.hunks[1].offset, 2);
+}
+
+#[test]
+fn hunks_without_trailing_context_must_match_at_the_end() {
+    let err = apply("a\nb\nc\nd\ne\nf\ng\nh\nmore\n", PATCH, Default::default()).unwrap_err();
+    assert_eq!(err.to_string(), "Hunk #2 starting at line 7 does not apply");
+}
+
+#[test]
+fn context_can_be_reduced_to_apply_hunks() {
+    let old = "a\nX\nc\nd\ne\nf\ng\nh\n";
+    assert!(
+        apply(old, PATCH, Default::default()).is_err(),
+        "by default, all context must match"
+    );
+    assert!(apply(
+        old,
+        PATCH,
+        apply::Options {
+            min_context: Some(1),
+            ..Default::default()
+        }
+    )
+    .is_err());
+
+    let out = apply(
+        old,
+        PATCH,
+        apply::Options {
+            min_context: Some(0),
+            ..Default::default()
+        },
+    )
+    .expect("applies with less context");
+    assert_eq!(out.data.as_bstr(), "a\nX\nC\nd\ne\nf\ng\nh\ni\n");
+    assert_eq!(out.hunks[0].reduced_context, 2, "both sides are reduced equally");
+}
+
+#[test]
+fn whitespace_changes_can_be_ignored() {
+    let old = "a\nb\n  c   x\nd\ne\nf\ng\nh\n";
+    let patch = "--- a/f
++++ b/f
+@@ -2,3 +2,3 @@
+ b
+-  c x
++C
+ d
+";
+    assert!(apply(old, patch, Default::default()).is_err());
+    let out = apply(
+        old,
+        patch,
+        apply::Options {
+            ignore_whitespace: true,
+            ..Default::default()
+        },
+    )
+    .expect("applies");
+    assert_eq!(out.data.as_bstr(), "a\nb\nC\nd\ne\nf\ng\nh\n");
+}
+
+#[test]
+fn missing_newlines_at_end_of_file() {
+    let patch = "--- a/f
++++ b/f
+@@ -1,2 +1,2 @@
+ a
+-b
+\\ No newline at end of file
++b
+";
+    let out = apply("a\nb", patch, Default::default()).expect("applies");
+    assert_eq!(out.data.as_bstr(), "a\nb\n");
+    assert!(
+        apply("a\nb\n", patch, Default::default()).is_err(),
+        "the newline is part of the line"
+    );
+}
+
+#[test]
+fn file_creation() {
+    let patch = "--- /dev/null
++++ b/f
+@@ -0,0 +1,2 @@
++a
++b
+";
+    let out = apply("", patch, Default::default()).expect("applies");
+    assert_eq!(out.data.as_bstr(), "a\nb\n");
+    assert!(
+        apply("existing\n", patch, Default::default()).is_err(),
+        "it must match at the beginning and the end"
+    );
+}
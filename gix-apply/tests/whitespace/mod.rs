@@ -0,0 +1,93 @@
+use bstr::ByteSlice;
+use gix_apply::{
+    apply,
+    whitespace::{Action, Check, Rules},
+    Content,
+};
+
+use crate::parse;
+
+#[test]
+fn rules_from_config() {
+    assert_eq!(Rules::from_config("".into()).expect("valid"), Rules::default());
+    let rules =
+        Rules::from_config("-trailing-space, tab-in-indent,cr-at-eol,tabwidth=4,unknown".into()).expect("valid");
+    assert_eq!(
+        rules.check,
+        Check::SPACE_BEFORE_TAB | Check::TAB_IN_INDENT | Check::CR_AT_EOL
+    );
+    assert_eq!(rules.tab_width, 4);
+    assert!(Rules::from_config("tabwidth=0".into()).is_err());
+}
+
+#[test]
+fn check_and_fix_lines() {
+    let rules = Rules::default();
+    assert_eq!(rules.check_line(b"a  \n"), Check::BLANK_AT_EOL);
+    assert_eq!(rules.check_line(b" \ta\n"), Check::SPACE_BEFORE_TAB);
+    assert_eq!(rules.check_line(b"\ta\n"), Check::empty());
+    assert_eq!(rules.check_line(b"a\r\n"), Check::BLANK_AT_EOL);
+    assert_eq!(rules.fix_line(b"a \t\n").expect("fixed"), "a\n");
+    assert_eq!(rules.fix_line(b" \ta\n").expect("fixed"), "\ta\n");
+    assert_eq!(rules.fix_line(b"\ta\n"), None);
+
+    let rules = Rules::from_config("indent-with-non-tab,cr-at-eol".into()).expect("valid");
+    assert_eq!(rules.check_line(b"a\r\n"), Check::empty());
+    assert_eq!(rules.check_line(b"        a\n"), Check::INDENT_WITH_NON_TAB);
+    assert_eq!(rules.fix_line(b"          a \r\n").expect("fixed"), "\t  a\r\n");
+
+    let rules = Rules::from_config("-space-before-tab,tab-in-indent,tabwidth=4".into()).expect("valid");
+    assert_eq!(rules.check_line(b"\t a\n"), Check::TAB_IN_INDENT);
+    assert_eq!(rules.fix_line(b" \ta\n").expect("fixed"), "    a\n");
+}
+
+fn hunks(patch: &str) -> Vec<gix_apply::Hunk> {
+    match parse(patch).pop().expect("one file").content {
+        Content::Text(hunks) => hunks,
+        _ => unreachable!("text patch"),
+    }
+}
+
+const PATCH: &str = "--- a/f
++++ b/f
+@@ -1,2 +1,4 @@
+ a
+ b
++c  
++
+";
+
+#[test]
+fn actions() {
+    let hunks = hunks(PATCH);
+    let with_action = |whitespace_action| apply::Options {
+        whitespace_action,
+        ..Default::default()
+    };
+
+    let out = apply::text(b"a\nb\n", &hunks, with_action(Action::NoWarn)).expect("applies");
+    assert!(out.whitespace_problems.is_empty());
+
+    let out = apply::text(b"a\nb\n", &hunks, with_action(Action::Warn)).expect("applies");
+    assert_eq!(out.data.as_bstr(), "a\nb\nc  \n\n");
+    let checks: Vec<_> = out
+        .whitespace_problems
+        .iter()
+        .map(|p| (p.line, p.check, p.fixed))
+        .collect();
+    assert_eq!(
+        checks,
+        [(2, Check::BLANK_AT_EOL, false), (3, Check::BLANK_AT_EOF, false)]
+    );
+
+    let out = apply::text(b"a\nb\n", &hunks, with_action(Action::Fix)).expect("applies");
+    assert_eq!(
+        out.data.as_bstr(),
+        "a\nb\nc\n",
+        "trailing whitespace and blank lines at the end are removed"
+    );
+    assert!(out.whitespace_problems.iter().all(|p| p.fixed));
+
+    let err = apply::text(b"a\nb\n", &hunks, with_action(Action::Error)).unwrap_err();
+    assert_eq!(err.to_string(), "2 line(s) add whitespace errors");
+}
@@ -50,7 +50,7 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
 }
 
 mod access {
-    use super::Vec;
+    use super::{rlw, Vec};
 
     impl Vec {
         /// Call `f(index)` for each bit that is true, given the index of the bit that identifies it uniquely within the bit array.
@@ -94,30 +94,209 @@ mod access {
 
     #[inline]
     fn rlw_running_len_bits(w: &u64) -> u64 {
-        rlw_running_len(w) * 64
+        rlw::running_len(*w) * 64
     }
 
     #[inline]
-    fn rlw_running_len(w: &u64) -> u64 {
-        (w >> 1) & RLW_LARGEST_RUNNING_COUNT
+    fn rlw_literal_words(w: &u64) -> u64 {
+        rlw::literal_words(*w)
     }
 
     #[inline]
-    fn rlw_literal_words(w: &u64) -> u64 {
-        w >> (1 + RLW_RUNNING_BITS)
+    fn rlw_runbit_is_set(w: &u64) -> bool {
+        rlw::run_bit(*w)
+    }
+}
+
+mod mutation {
+    use super::{rlw, Vec, BITS_PER_WORD};
+
+    impl Default for Vec {
+        fn default() -> Self {
+            Vec {
+                num_bits: 0,
+                bits: vec![0],
+                rlw: 0,
+            }
+        }
+    }
+
+    impl Vec {
+        /// Set the bit at `index` to true, with all bits between the previously set bit and `index` being false.
+        ///
+        /// ### Panics
+        ///
+        /// If `index` isn't larger than the index of the previously set bit, as bits can only be appended.
+        pub fn set(&mut self, index: usize) {
+            let num_bits = self.num_bits();
+            assert!(index >= num_bits, "bits must be set in ascending order");
+            let distance =
+                (index + 1 + BITS_PER_WORD - 1) / BITS_PER_WORD - (num_bits + BITS_PER_WORD - 1) / BITS_PER_WORD;
+            self.num_bits = (index + 1).try_into().expect("we don't store more than 4 billion bits");
+            let bit = 1 << (index % BITS_PER_WORD);
+            if distance > 0 {
+                if distance > 1 {
+                    self.add_empty_words(false, distance - 1);
+                }
+                self.add_literal(bit);
+                return;
+            }
+
+            let rlw = self.rlw();
+            if rlw::literal_words(self.bits[rlw]) == 0 {
+                let running_len = rlw::running_len(self.bits[rlw]);
+                rlw::set_running_len(&mut self.bits[rlw], running_len - 1);
+                self.add_literal(bit);
+                return;
+            }
+
+            let last = self.bits.last_mut().expect("at least one rlw");
+            *last |= bit;
+            if *last == u64::MAX {
+                self.bits.pop();
+                let rlw = self.rlw();
+                let literal_words = rlw::literal_words(self.bits[rlw]);
+                rlw::set_literal_words(&mut self.bits[rlw], literal_words - 1);
+                self.add_empty_word(true);
+            }
+        }
+
+        fn rlw(&self) -> usize {
+            self.rlw as usize
+        }
+
+        fn push_rlw(&mut self) {
+            self.bits.push(0);
+            self.rlw = (self.bits.len() - 1) as u64;
+        }
+
+        fn add_literal(&mut self, word: u64) {
+            let rlw = self.rlw();
+            let literal_words = rlw::literal_words(self.bits[rlw]);
+            if literal_words >= rlw::LARGEST_LITERAL_COUNT {
+                self.push_rlw();
+                let rlw = self.rlw();
+                rlw::set_literal_words(&mut self.bits[rlw], 1);
+            } else {
+                rlw::set_literal_words(&mut self.bits[rlw], literal_words + 1);
+            }
+            self.bits.push(word);
+        }
+
+        fn add_empty_word(&mut self, value: bool) {
+            let rlw = self.rlw();
+            let no_literal = rlw::literal_words(self.bits[rlw]) == 0;
+            let running_len = rlw::running_len(self.bits[rlw]);
+            if no_literal && running_len == 0 {
+                rlw::set_run_bit(&mut self.bits[rlw], value);
+            }
+            if no_literal && rlw::run_bit(self.bits[rlw]) == value && running_len < rlw::LARGEST_RUNNING_COUNT {
+                rlw::set_running_len(&mut self.bits[rlw], running_len + 1);
+            } else {
+                self.push_rlw();
+                let rlw = self.rlw();
+                rlw::set_run_bit(&mut self.bits[rlw], value);
+                rlw::set_running_len(&mut self.bits[rlw], 1);
+            }
+        }
+
+        fn add_empty_words(&mut self, value: bool, mut count: usize) {
+            let rlw = self.rlw();
+            if rlw::run_bit(self.bits[rlw]) != value && rlw::size(self.bits[rlw]) == 0 {
+                rlw::set_run_bit(&mut self.bits[rlw], value);
+            } else if rlw::literal_words(self.bits[rlw]) != 0 || rlw::run_bit(self.bits[rlw]) != value {
+                self.push_rlw();
+                let rlw = self.rlw();
+                rlw::set_run_bit(&mut self.bits[rlw], value);
+            }
+            let rlw = self.rlw();
+            let running_len = rlw::running_len(self.bits[rlw]);
+            let can_add = (count as u64).min(rlw::LARGEST_RUNNING_COUNT - running_len);
+            rlw::set_running_len(&mut self.bits[rlw], running_len + can_add);
+            count -= can_add as usize;
+
+            while count > 0 {
+                let run = (count as u64).min(rlw::LARGEST_RUNNING_COUNT);
+                self.push_rlw();
+                let rlw = self.rlw();
+                rlw::set_run_bit(&mut self.bits[rlw], value);
+                rlw::set_running_len(&mut self.bits[rlw], run);
+                count -= run as usize;
+            }
+        }
     }
+}
+
+mod write {
+    use super::Vec;
+
+    impl Vec {
+        /// Serialize this bitmap into `out` in the format understood by [`decode()`][super::decode()].
+        pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            let len: u32 = self.bits.len().try_into().expect("less than 4 billion words");
+            out.write_all(&len.to_be_bytes())?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            let rlw: u32 = self.rlw.try_into().expect("rlw offset is within the bits");
+            out.write_all(&rlw.to_be_bytes())
+        }
+    }
+}
+
+/// Utilities for dealing with a run-length word, which is followed by the amount of literal words it specifies.
+mod rlw {
+    const RUNNING_BITS: u64 = 4 * 8;
+    const LITERAL_BITS: u64 = 64 - 1 - RUNNING_BITS;
+    pub(super) const LARGEST_RUNNING_COUNT: u64 = (1 << RUNNING_BITS) - 1;
+    pub(super) const LARGEST_LITERAL_COUNT: u64 = (1 << LITERAL_BITS) - 1;
+    const RUNNING_LEN_PLUS_BIT: u64 = (1 << (RUNNING_BITS + 1)) - 1;
 
     #[inline]
-    fn rlw_runbit_is_set(w: &u64) -> bool {
+    pub(super) fn run_bit(w: u64) -> bool {
         w & 1 == 1
     }
 
-    const RLW_RUNNING_BITS: u64 = 4 * 8;
-    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    #[inline]
+    pub(super) fn running_len(w: u64) -> u64 {
+        (w >> 1) & LARGEST_RUNNING_COUNT
+    }
+
+    #[inline]
+    pub(super) fn literal_words(w: u64) -> u64 {
+        w >> (1 + RUNNING_BITS)
+    }
+
+    #[inline]
+    pub(super) fn size(w: u64) -> u64 {
+        running_len(w) + literal_words(w)
+    }
+
+    pub(super) fn set_run_bit(w: &mut u64, value: bool) {
+        if value {
+            *w |= 1;
+        } else {
+            *w &= !1;
+        }
+    }
+
+    pub(super) fn set_running_len(w: &mut u64, len: u64) {
+        *w |= LARGEST_RUNNING_COUNT << 1;
+        *w &= (len << 1) | !(LARGEST_RUNNING_COUNT << 1);
+    }
+
+    pub(super) fn set_literal_words(w: &mut u64, count: u64) {
+        *w |= !RUNNING_LEN_PLUS_BIT;
+        *w &= (count << (RUNNING_BITS + 1)) | RUNNING_LEN_PLUS_BIT;
+    }
 }
 
+const BITS_PER_WORD: usize = 64;
+
 /// A growable collection of u64 that are seen as stream of individual bits.
-#[allow(dead_code)]
+///
+/// Use [`Vec::default()`] to create an empty instance to [set bits](Vec::set()) on.
 #[derive(Clone)]
 pub struct Vec {
     num_bits: u32,
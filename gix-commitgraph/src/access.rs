@@ -1,4 +1,4 @@
-use crate::{file, file::Commit, File, Graph, Position};
+use crate::{bloom, file, file::Commit, File, Graph, Position};
 
 /// Access
 impl Graph {
@@ -11,6 +11,14 @@ impl Graph {
         r.file.commit_at(r.pos)
     }
 
+    /// Returns the settings of the changed-path Bloom filters of the first file that has usable Bloom filters,
+    /// or `None` if there are none.
+    ///
+    /// Note that the settings of all files should be the same, but each [filter](Commit::bloom_filter()) also knows its own.
+    pub fn bloom_filter_settings(&self) -> Option<&bloom::Settings> {
+        self.files.iter().find_map(File::bloom_filter_settings)
+    }
+
     /// Returns the commit matching the given `id`.
     pub fn commit_by_id(&self, id: impl AsRef<gix_hash::oid>) -> Option<Commit<'_>> {
         let r = self.lookup_by_id(id.as_ref())?;
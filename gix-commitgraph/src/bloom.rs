@@ -0,0 +1,212 @@
+//! Changed-path Bloom filters as stored in the `BIDX` and `BDAT` chunks of commit-graph files.
+//!
+//! Each commit may have a Bloom filter containing all paths, including their leading directories, that changed compared
+//! to its first parent, or compared to the empty tree for root commits. This allows to quickly determine that a commit
+//! definitely didn't change a path, without having to diff its tree.
+use bstr::{BStr, ByteSlice};
+
+/// The largest amount of changed paths for which a Bloom filter is computed.
+///
+/// Commits with more changed paths receive a filter with all bits set, which [may contain](Filter::contains()) any path.
+pub const MAX_CHANGED_PATHS: usize = 512;
+
+const SEED0: u32 = 0x293a_e76f;
+const SEED1: u32 = 0x7e64_6e2c;
+const BITS_PER_WORD: usize = 8;
+
+/// The parameters used to compute the Bloom filters of a commit-graph file, as stored in the header of its `BDAT` chunk.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settings {
+    /// The version of the hash function used to hash paths.
+    ///
+    /// Version 1 is understood by all git versions, but hashes bytes of non-ASCII paths as signed values, while version 2
+    /// fixes this and requires git 2.46 or newer.
+    pub hash_version: u32,
+    /// The amount of hashes computed for each path, i.e. the amount of bits set per path.
+    pub num_hashes: u32,
+    /// The amount of bits in a filter per changed path.
+    pub bits_per_entry: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            hash_version: 1,
+            num_hashes: 7,
+            bits_per_entry: 10,
+        }
+    }
+}
+
+impl Settings {
+    /// Return `true` if we know how to compute and query filters with these settings.
+    pub fn is_supported(&self) -> bool {
+        matches!(self.hash_version, 1 | 2) && self.num_hashes != 0
+    }
+}
+
+/// The hashes of a path, as needed to add it to or look it up in a [`Filter`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    hashes: Vec<u32>,
+}
+
+impl Key {
+    /// Compute the key of `path`, which is relative to the root of the repository and uses slashes as separators,
+    /// using `settings`.
+    pub fn new(path: &BStr, settings: &Settings) -> Self {
+        let hash0 = murmur3_seeded(SEED0, path, settings.hash_version);
+        let hash1 = murmur3_seeded(SEED1, path, settings.hash_version);
+        Key {
+            hashes: (0..settings.num_hashes)
+                .map(|i| hash0.wrapping_add(i.wrapping_mul(hash1)))
+                .collect(),
+        }
+    }
+
+    /// Compute the keys of `path` and all of its leading directories, which are all contained in a filter
+    /// if `path` was changed.
+    ///
+    /// Looking up all of them is more selective than looking up `path` alone.
+    pub fn with_leading_directories(path: &BStr, settings: &Settings) -> Vec<Key> {
+        let mut path = path.as_bytes();
+        while let Some(stripped) = path.strip_suffix(b"/") {
+            path = stripped;
+        }
+        let mut keys = vec![Key::new(path.as_bstr(), settings)];
+        let mut prefix = path;
+        while let Some(pos) = prefix.rfind_byte(b'/') {
+            prefix = &prefix[..pos];
+            keys.push(Key::new(prefix.as_bstr(), settings));
+        }
+        keys
+    }
+}
+
+/// A changed-path Bloom filter of a single commit, as stored in a commit-graph [File][crate::File].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Filter<'a> {
+    data: &'a [u8],
+    settings: Settings,
+}
+
+impl<'a> Filter<'a> {
+    pub(crate) fn new(data: &'a [u8], settings: Settings) -> Self {
+        Filter { data, settings }
+    }
+
+    /// Return the raw bytes of the filter.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// The settings used to compute this filter, which must also be used to compute the [keys](Key) to look up.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Return `false` if the path of `key` definitely wasn't changed, or `true` if it may have been changed.
+    pub fn contains(&self, key: &Key) -> bool {
+        contains(self.data, key)
+    }
+
+    /// Return `false` if the path of `keys` as obtained by [`Key::with_leading_directories()`] definitely wasn't changed,
+    /// or `true` if it may have been changed.
+    pub fn contains_all(&self, keys: &[Key]) -> bool {
+        keys.iter().all(|key| self.contains(key))
+    }
+}
+
+/// Compute the data of a Bloom filter for the `changed_paths` of a commit compared to its first parent, using `settings`.
+///
+/// `changed_paths` are the paths of all changed files or submodules, without their leading directories which are added
+/// automatically. If there are more than [`MAX_CHANGED_PATHS`], the filter will contain all paths.
+/// Note that it suffices to pass one more than [`MAX_CHANGED_PATHS`] paths in that case.
+pub fn compute<'a>(changed_paths: impl IntoIterator<Item = &'a BStr>, settings: &Settings) -> Vec<u8> {
+    let mut paths = Vec::new();
+    for (num_changed_paths, path) in changed_paths.into_iter().enumerate() {
+        if num_changed_paths == MAX_CHANGED_PATHS {
+            return large_filter();
+        }
+        let mut path = path.as_bytes();
+        paths.push(path);
+        while let Some(pos) = path.rfind_byte(b'/') {
+            path = &path[..pos];
+            paths.push(path);
+        }
+    }
+    paths.sort_unstable();
+    paths.dedup();
+    if paths.len() > MAX_CHANGED_PATHS {
+        return large_filter();
+    }
+
+    let len = (paths.len() * settings.bits_per_entry as usize + BITS_PER_WORD - 1) / BITS_PER_WORD;
+    let mut data = vec![0; len.max(1)];
+    for path in paths {
+        add(&mut data, &Key::new(path.as_bstr(), settings));
+    }
+    data
+}
+
+/// The filter of commits with too many changed paths, which may contain any path.
+fn large_filter() -> Vec<u8> {
+    vec![0xff]
+}
+
+fn add(data: &mut [u8], key: &Key) {
+    let num_bits = (data.len() * BITS_PER_WORD) as u64;
+    for hash in &key.hashes {
+        let bit = u64::from(*hash) % num_bits;
+        data[(bit / BITS_PER_WORD as u64) as usize] |= 1 << (bit % BITS_PER_WORD as u64);
+    }
+}
+
+fn contains(data: &[u8], key: &Key) -> bool {
+    let num_bits = (data.len() * BITS_PER_WORD) as u64;
+    if num_bits == 0 {
+        return true;
+    }
+    key.hashes.iter().all(|hash| {
+        let bit = u64::from(*hash) % num_bits;
+        data[(bit / BITS_PER_WORD as u64) as usize] & (1 << (bit % BITS_PER_WORD as u64)) != 0
+    })
+}
+
+/// The 32 bit variant of murmur3 as implemented by git, which with `version` 1 interprets bytes as signed values.
+fn murmur3_seeded(mut seed: u32, data: &[u8], version: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    const R1: u32 = 15;
+    const R2: u32 = 13;
+    const M: u32 = 5;
+    const N: u32 = 0xe654_6b64;
+
+    let byte = |b: u8| if version == 1 { b as i8 as u32 } else { u32::from(b) };
+    let mut chunks = data.chunks_exact(4);
+    for chunk in chunks.by_ref() {
+        let mut k = byte(chunk[0]) | byte(chunk[1]) << 8 | byte(chunk[2]) << 16 | byte(chunk[3]) << 24;
+        k = k.wrapping_mul(C1).rotate_left(R1).wrapping_mul(C2);
+        seed ^= k;
+        seed = seed.rotate_left(R2).wrapping_mul(M).wrapping_add(N);
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0;
+        for (idx, b) in tail.iter().enumerate().rev() {
+            k ^= byte(*b) << (8 * idx);
+        }
+        k = k.wrapping_mul(C1).rotate_left(R1).wrapping_mul(C2);
+        seed ^= k;
+    }
+
+    seed ^= data.len() as u32;
+    seed ^= seed >> 16;
+    seed = seed.wrapping_mul(0x85eb_ca6b);
+    seed ^= seed >> 13;
+    seed = seed.wrapping_mul(0xc2b2_ae35);
+    seed ^= seed >> 16;
+    seed
+}
@@ -4,7 +4,8 @@ use std::{
 };
 
 use crate::{
-    file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH},
+    bloom,
+    file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH, GENERATION_DATA_OVERFLOW_MASK},
     File,
 };
 
@@ -15,6 +16,14 @@ impl File {
         self.base_graph_count
     }
 
+    /// Returns the settings used to compute the changed-path Bloom filters of this file, or `None` if it has no
+    /// usable Bloom filters.
+    ///
+    /// The filters themselves can be obtained with [`Commit::bloom_filter()`].
+    pub fn bloom_filter_settings(&self) -> Option<&bloom::Settings> {
+        self.bloom_filter_settings.as_ref()
+    }
+
     /// Returns the commit data for the commit located at the given lexicographical position.
     ///
     /// `pos` must range from 0 to `self.num_commits()`.
@@ -26,6 +35,12 @@ impl File {
         Commit::new(self, pos)
     }
 
+    /// Returns `true` if this file stores corrected commit dates, i.e. generation numbers v2,
+    /// which can be obtained with [`Commit::corrected_commit_date()`].
+    pub fn has_corrected_commit_dates(&self) -> bool {
+        self.generation_data_offset.is_some()
+    }
+
     /// The kind of hash used in this File.
     ///
     /// Note that it is always conforming to the hash used in the owning repository.
@@ -127,6 +142,41 @@ impl File {
         &self.data[start..][..entry_size]
     }
 
+    /// Returns the changed-path Bloom filter for the given commit as stored in this file's Bloom Filter Data (BDAT) chunk,
+    /// or `None` if there are no filters, if it wasn't computed or if the Bloom Filter Index (BIDX) chunk is corrupt.
+    pub(crate) fn bloom_filter(&self, pos: file::Position) -> Option<bloom::Filter<'_>> {
+        let index_offset = self.bloom_filter_index_offset?;
+        let read_end = |pos: usize| {
+            let bytes = &self.data[index_offset + pos * 4..][..4];
+            usize::try_from(u32::from_be_bytes(bytes.try_into().expect("4 bytes"))).ok()
+        };
+        let pos = usize::try_from(pos.0).ok()?;
+        let start = match pos.checked_sub(1) {
+            Some(previous) => read_end(previous)?,
+            None => 0,
+        };
+        let end = read_end(pos)?;
+        let data = self.data[self.bloom_filter_data_range.clone()?].get(start..end)?;
+        if data.is_empty() {
+            return None;
+        }
+        Some(bloom::Filter::new(data, *self.bloom_filter_settings.as_ref()?))
+    }
+
+    /// Returns the corrected commit date offset for the given commit as stored in this file's Generation Data (GDA2) chunk
+    /// and possibly its Generation Data Overflow (GDO2) chunk, or `None` if there is no generation data or it is corrupt.
+    pub(crate) fn corrected_commit_date_offset(&self, pos: file::Position) -> Option<u64> {
+        let start = self.generation_data_offset? + usize::try_from(pos.0).ok()? * 4;
+        let offset = u32::from_be_bytes(self.data[start..][..4].try_into().expect("4 bytes"));
+        if offset & GENERATION_DATA_OVERFLOW_MASK == 0 {
+            return Some(offset.into());
+        }
+        let overflow_index = usize::try_from(offset & !GENERATION_DATA_OVERFLOW_MASK).ok()?;
+        let overflow = &self.data[self.generation_data_overflow_range.clone()?];
+        let bytes = overflow.get(overflow_index * 8..)?.get(..8)?;
+        Some(u64::from_be_bytes(bytes.try_into().expect("8 bytes")))
+    }
+
     /// Returns the byte slice for this file's entire Extra Edge List (EDGE) chunk.
     pub(crate) fn extra_edges_data(&self) -> Option<&[u8]> {
         Some(&self.data[self.extra_edges_list_range.clone()?])
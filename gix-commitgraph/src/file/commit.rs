@@ -5,6 +5,7 @@ use std::{
 };
 
 use crate::{
+    bloom,
     file::{self, EXTENDED_EDGES_MASK, LAST_EXTENDED_EDGE_MASK, NO_PARENT},
     File, Position,
 };
@@ -74,6 +75,26 @@ impl<'a> Commit<'a> {
         self.generation
     }
 
+    /// Returns the corrected commit date of this commit, also known as generation number v2, or `None` if the owning
+    /// [File] doesn't store generation data.
+    ///
+    /// The corrected commit date is the commit's own committer timestamp, or the largest corrected commit date of its
+    /// parents plus one, whichever is larger.
+    pub fn corrected_commit_date(&self) -> Option<u64> {
+        self.file
+            .corrected_commit_date_offset(self.pos)
+            .map(|offset| self.commit_timestamp.saturating_add(offset))
+    }
+
+    /// Returns the changed-path Bloom filter of this commit, or `None` if the owning [File] doesn't store Bloom filters
+    /// or none was computed for this commit.
+    ///
+    /// The filter contains all paths that were changed compared to the first parent, or compared to the empty tree
+    /// if this is a root commit.
+    pub fn bloom_filter(&self) -> Option<bloom::Filter<'a>> {
+        self.file.bloom_filter(self.pos)
+    }
+
     /// Returns an iterator over the parent positions for lookup in the owning [Graph][crate::Graph].
     pub fn iter_parents(self) -> Parents<'a> {
         // I didn't find a combinator approach that a) was as strict as ParentIterator, b) supported
@@ -2,9 +2,12 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use crate::{
+    bloom,
     file::{
-        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
-        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, GENERATION_DATA_CHUNK_ID, GENERATION_DATA_OVERFLOW_CHUNK_ID, HEADER_LEN,
+        OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
     },
     File,
 };
@@ -184,6 +187,75 @@ impl File {
 
         let extra_edges_list_range = chunks.usize_offset_by_id(EXTENDED_EDGES_LIST_CHUNK_ID).ok();
 
+        let generation_data = chunks
+            .validated_usize_offset_by_id(GENERATION_DATA_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                if chunk_size % 4 != 0 {
+                    return Err(Error::InvalidChunkSize {
+                        id: GENERATION_DATA_CHUNK_ID,
+                        msg: format!("chunk size {chunk_size} is not a multiple of 4"),
+                    });
+                }
+                Ok((
+                    chunk_range.start,
+                    u32::try_from(chunk_size / 4).expect("number of commits in GDA2 chunk to fit in 32 bits"),
+                ))
+            })
+            .ok()
+            .transpose()?;
+
+        let generation_data_overflow_range = chunks
+            .validated_usize_offset_by_id(GENERATION_DATA_OVERFLOW_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                if chunk_size % 8 != 0 {
+                    return Err(Error::InvalidChunkSize {
+                        id: GENERATION_DATA_OVERFLOW_CHUNK_ID,
+                        msg: format!("chunk size {chunk_size} is not a multiple of 8"),
+                    });
+                }
+                Ok(chunk_range)
+            })
+            .ok()
+            .transpose()?;
+
+        let bloom_filter_index = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                if chunk_size % 4 != 0 {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_INDEX_CHUNK_ID,
+                        msg: format!("chunk size {chunk_size} is not a multiple of 4"),
+                    });
+                }
+                Ok((
+                    chunk_range.start,
+                    u32::try_from(chunk_size / 4).expect("number of commits in BIDX chunk to fit in 32 bits"),
+                ))
+            })
+            .ok()
+            .transpose()?;
+
+        let bloom_filter_data = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                if chunk_size < BLOOM_FILTER_DATA_HEADER_LEN {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_DATA_CHUNK_ID,
+                        msg: format!("chunk size {chunk_size} is smaller than its header of {BLOOM_FILTER_DATA_HEADER_LEN} bytes"),
+                    });
+                }
+                let header = &data[chunk_range.start..][..BLOOM_FILTER_DATA_HEADER_LEN];
+                let read_u32 = |ofs: usize| u32::from_be_bytes(header[ofs..][..4].try_into().expect("4 bytes"));
+                let settings = bloom::Settings {
+                    hash_version: read_u32(0),
+                    num_hashes: read_u32(4),
+                    bits_per_entry: read_u32(8),
+                };
+                Ok((settings, chunk_range.start + BLOOM_FILTER_DATA_HEADER_LEN..chunk_range.end))
+            })
+            .ok()
+            .transpose()?;
+
         let trailer = &data[chunks.highest_offset() as usize..];
         if trailer.len() != object_hash.len_in_bytes() {
             return Err(Error::Trailer(format!(
@@ -217,12 +289,45 @@ impl File {
                 chunk2_commits: commit_data_count,
             });
         }
+        if let Some((_, generation_data_count)) = generation_data {
+            if generation_data_count != fan[255] {
+                return Err(Error::CommitCountMismatch {
+                    chunk1_id: OID_FAN_CHUNK_ID,
+                    chunk1_commits: fan[255],
+                    chunk2_id: GENERATION_DATA_CHUNK_ID,
+                    chunk2_commits: generation_data_count,
+                });
+            }
+        }
+        if let Some((_, bloom_filter_index_count)) = bloom_filter_index {
+            if bloom_filter_index_count != fan[255] {
+                return Err(Error::CommitCountMismatch {
+                    chunk1_id: OID_FAN_CHUNK_ID,
+                    chunk1_commits: fan[255],
+                    chunk2_id: BLOOM_FILTER_INDEX_CHUNK_ID,
+                    chunk2_commits: bloom_filter_index_count,
+                });
+            }
+        }
+        // Like git, ignore filters we can't use instead of failing, as they are merely an optimization.
+        let (bloom_filter_index_offset, bloom_filter_data_range, bloom_filter_settings) =
+            match (bloom_filter_index, bloom_filter_data) {
+                (Some((index_offset, _)), Some((settings, data_range))) if settings.is_supported() => {
+                    (Some(index_offset), Some(data_range), Some(settings))
+                }
+                _ => (None, None, None),
+            };
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filter_index_offset,
+            bloom_filter_data_range,
+            bloom_filter_settings,
             commit_data_offset,
             data,
             extra_edges_list_range,
+            generation_data_offset: generation_data.map(|(offset, _)| offset),
+            generation_data_overflow_range,
             fan,
             oid_lookup_offset,
             path,
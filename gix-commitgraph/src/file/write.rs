@@ -0,0 +1,319 @@
+use std::io::Write;
+
+use crate::{
+    bloom,
+    file::{
+        BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, EXTENDED_EDGES_MASK, FAN_LEN, GENERATION_DATA_CHUNK_ID,
+        GENERATION_DATA_OVERFLOW_CHUNK_ID, GENERATION_DATA_OVERFLOW_MASK, HEADER_LEN, LAST_EXTENDED_EDGE_MASK,
+        NO_PARENT, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    write::{self, Commit, Error, GenerationVersion},
+    File, GENERATION_NUMBER_MAX, GENERATION_NUMBER_V2_OFFSET_MAX, MAX_COMMITS,
+};
+
+/// A parent of a commit to write, along with its generation data.
+#[derive(Clone, Copy)]
+enum Parent {
+    /// The index into the commits to write.
+    Local(usize),
+    /// A commit in one of the base graphs.
+    Base {
+        graph_pos: u32,
+        generation: u32,
+        corrected_commit_date: u64,
+    },
+}
+
+/// Writing
+impl File {
+    /// Write a commit-graph file with `commits` to `out` and return its checksum, with `base_files` being the layers of a split
+    /// commit-graph chain, base first, that the written file is supposed to be placed upon.
+    ///
+    /// Each parent of `commits` must either be contained in `commits` or in `base_files`, and commits already contained
+    /// in `base_files` must not be passed.
+    /// Corrected commit dates are only written with [`GenerationVersion::V2`] and if all `base_files` have them as well.
+    /// If `changed_paths` is set, the [Bloom filters](Commit::bloom_filter) of all commits are written, which must have been
+    /// computed with these settings.
+    pub fn write_to(
+        mut commits: Vec<Commit>,
+        base_files: &[File],
+        out: &mut dyn Write,
+        object_hash: gix_hash::Kind,
+        generation_version: GenerationVersion,
+        changed_paths: Option<bloom::Settings>,
+    ) -> Result<gix_hash::ObjectId, write::Error> {
+        commits.sort_by_key(|c| c.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+
+        if base_files.len() > usize::from(u8::MAX) {
+            return Err(Error::TooManyFiles(base_files.len() + 1));
+        }
+        let num_base_commits: u32 = base_files.iter().map(File::num_commits).sum();
+        let num_commits = u64::from(num_base_commits) + commits.len() as u64;
+        if num_commits > u64::from(MAX_COMMITS) {
+            return Err(Error::TooManyCommits(num_commits));
+        }
+        let write_generation_data =
+            generation_version == GenerationVersion::V2 && base_files.iter().all(File::has_corrected_commit_dates);
+
+        let parents = resolve_parents(&commits, base_files)?;
+        let (generations, corrected_commit_dates) = compute_generations(&commits, &parents)?;
+        let graph_pos = |parent: &Parent| match *parent {
+            Parent::Local(idx) => num_base_commits + idx as u32,
+            Parent::Base { graph_pos, .. } => graph_pos,
+        };
+
+        let num_extra_edges: usize = parents.iter().filter(|p| p.len() > 2).map(|p| p.len() - 1).sum();
+        let overflowing_offsets: Vec<u64> = if write_generation_data {
+            commits
+                .iter()
+                .zip(&corrected_commit_dates)
+                .map(|(c, date)| date - c.committer_timestamp)
+                .filter(|offset| *offset > u64::from(GENERATION_NUMBER_V2_OFFSET_MAX))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let hash_len = object_hash.len_in_bytes() as u64;
+        let mut cf = gix_chunk::file::Index::for_writing();
+        cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+        cf.plan_chunk(OID_LOOKUP_CHUNK_ID, hash_len * commits.len() as u64);
+        cf.plan_chunk(
+            COMMIT_DATA_CHUNK_ID,
+            (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH as u64) * commits.len() as u64,
+        );
+        if write_generation_data {
+            cf.plan_chunk(GENERATION_DATA_CHUNK_ID, 4 * commits.len() as u64);
+            if !overflowing_offsets.is_empty() {
+                cf.plan_chunk(GENERATION_DATA_OVERFLOW_CHUNK_ID, 8 * overflowing_offsets.len() as u64);
+            }
+        }
+        if num_extra_edges != 0 {
+            cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, 4 * num_extra_edges as u64);
+        }
+        if changed_paths.is_some() {
+            let num_bloom_bytes: usize = commits
+                .iter()
+                .filter_map(|c| c.bloom_filter.as_ref())
+                .map(Vec::len)
+                .sum();
+            cf.plan_chunk(BLOOM_FILTER_INDEX_CHUNK_ID, 4 * commits.len() as u64);
+            cf.plan_chunk(
+                BLOOM_FILTER_DATA_CHUNK_ID,
+                (BLOOM_FILTER_DATA_HEADER_LEN + num_bloom_bytes) as u64,
+            );
+        }
+        if !base_files.is_empty() {
+            cf.plan_chunk(BASE_GRAPHS_LIST_CHUNK_ID, hash_len * base_files.len() as u64);
+        }
+
+        let mut out = gix_features::hash::Write::new(out, object_hash);
+        out.write_all(SIGNATURE)?;
+        out.write_all(&[1 /* version */, object_hash as u8])?;
+        out.write_all(&[
+            cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks"),
+            base_files.len() as u8,
+        ])?;
+
+        let mut chunk_write = cf.into_write(&mut out, HEADER_LEN)?;
+        while let Some(chunk_to_write) = chunk_write.next_chunk() {
+            match chunk_to_write {
+                OID_FAN_CHUNK_ID => {
+                    let mut fan = [0u32; FAN_LEN];
+                    for c in &commits {
+                        fan[usize::from(c.id.first_byte())] += 1;
+                    }
+                    let mut count = 0;
+                    for entry in fan {
+                        count += entry;
+                        chunk_write.write_all(&count.to_be_bytes())?;
+                    }
+                }
+                OID_LOOKUP_CHUNK_ID => {
+                    for c in &commits {
+                        chunk_write.write_all(c.id.as_bytes())?;
+                    }
+                }
+                COMMIT_DATA_CHUNK_ID => {
+                    let mut extra_edge_index = 0;
+                    for ((c, parents), generation) in commits.iter().zip(&parents).zip(&generations) {
+                        chunk_write.write_all(c.tree.as_bytes())?;
+                        let parent1 = parents.first().map_or(NO_PARENT, graph_pos);
+                        let parent2 = match parents.len() {
+                            0 | 1 => NO_PARENT,
+                            2 => graph_pos(&parents[1]),
+                            num_parents => {
+                                let edge = EXTENDED_EDGES_MASK | extra_edge_index;
+                                extra_edge_index += num_parents as u32 - 1;
+                                edge
+                            }
+                        };
+                        chunk_write.write_all(&parent1.to_be_bytes())?;
+                        chunk_write.write_all(&parent2.to_be_bytes())?;
+                        let timestamp_high = ((c.committer_timestamp >> 32) & 0x3) as u32;
+                        chunk_write.write_all(&((generation << 2) | timestamp_high).to_be_bytes())?;
+                        chunk_write.write_all(&(c.committer_timestamp as u32).to_be_bytes())?;
+                    }
+                }
+                GENERATION_DATA_CHUNK_ID => {
+                    let mut num_overflows = 0;
+                    for (c, date) in commits.iter().zip(&corrected_commit_dates) {
+                        let offset = date - c.committer_timestamp;
+                        let offset = if offset > u64::from(GENERATION_NUMBER_V2_OFFSET_MAX) {
+                            num_overflows += 1;
+                            GENERATION_DATA_OVERFLOW_MASK | (num_overflows - 1)
+                        } else {
+                            offset as u32
+                        };
+                        chunk_write.write_all(&offset.to_be_bytes())?;
+                    }
+                }
+                GENERATION_DATA_OVERFLOW_CHUNK_ID => {
+                    for offset in &overflowing_offsets {
+                        chunk_write.write_all(&offset.to_be_bytes())?;
+                    }
+                }
+                EXTENDED_EDGES_LIST_CHUNK_ID => {
+                    for parents in parents.iter().filter(|p| p.len() > 2) {
+                        let (last, extra) = parents[1..].split_last().expect("at least two extra parents");
+                        for parent in extra {
+                            chunk_write.write_all(&graph_pos(parent).to_be_bytes())?;
+                        }
+                        chunk_write.write_all(&(graph_pos(last) | LAST_EXTENDED_EDGE_MASK).to_be_bytes())?;
+                    }
+                }
+                BLOOM_FILTER_INDEX_CHUNK_ID => {
+                    let mut end = 0u32;
+                    for c in &commits {
+                        end += c.bloom_filter.as_ref().map_or(0, Vec::len) as u32;
+                        chunk_write.write_all(&end.to_be_bytes())?;
+                    }
+                }
+                BLOOM_FILTER_DATA_CHUNK_ID => {
+                    let settings = changed_paths.expect("only planned with settings");
+                    for value in [settings.hash_version, settings.num_hashes, settings.bits_per_entry] {
+                        chunk_write.write_all(&value.to_be_bytes())?;
+                    }
+                    for filter in commits.iter().filter_map(|c| c.bloom_filter.as_ref()) {
+                        chunk_write.write_all(filter)?;
+                    }
+                }
+                BASE_GRAPHS_LIST_CHUNK_ID => {
+                    for file in base_files {
+                        chunk_write.write_all(file.checksum().as_bytes())?;
+                    }
+                }
+                unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+            }
+        }
+
+        let checksum: gix_hash::ObjectId = out.hash.digest().into();
+        out.inner.write_all(checksum.as_slice())?;
+        out.inner.flush()?;
+        Ok(checksum)
+    }
+}
+
+/// Resolve the parents of all `commits`, which are sorted by id, to either their index in `commits` or their position in `base_files`.
+fn resolve_parents(commits: &[Commit], base_files: &[File]) -> Result<Vec<Vec<Parent>>, Error> {
+    let lookup_base = |id: &gix_hash::oid| {
+        let mut graph_pos = 0;
+        for file in base_files {
+            if let Some(pos) = file.lookup(id) {
+                let commit = file.commit_at(pos);
+                return Some(Parent::Base {
+                    graph_pos: graph_pos + pos.0,
+                    generation: commit.generation(),
+                    corrected_commit_date: commit.corrected_commit_date().unwrap_or_default(),
+                });
+            }
+            graph_pos += file.num_commits();
+        }
+        None
+    };
+    commits
+        .iter()
+        .map(|c| {
+            c.parents
+                .iter()
+                .map(|parent| {
+                    commits
+                        .binary_search_by(|probe| probe.id.cmp(parent))
+                        .ok()
+                        .map(Parent::Local)
+                        .or_else(|| lookup_base(parent))
+                        .ok_or(Error::MissingParent {
+                            id: c.id,
+                            parent: *parent,
+                        })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Compute the topological level and corrected commit date of all `commits` with `parents`, visiting parents before their children.
+fn compute_generations(commits: &[Commit], parents: &[Vec<Parent>]) -> Result<(Vec<u32>, Vec<u64>), Error> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        InProgress,
+        Done,
+    }
+    let mut state = vec![State::Unvisited; commits.len()];
+    let mut generations = vec![0; commits.len()];
+    let mut dates = vec![0; commits.len()];
+    let mut stack = Vec::new();
+    for start in 0..commits.len() {
+        if state[start] != State::Unvisited {
+            continue;
+        }
+        stack.push(start);
+        while let Some(&idx) = stack.last() {
+            match state[idx] {
+                State::Done => {
+                    stack.pop();
+                }
+                State::Unvisited => {
+                    state[idx] = State::InProgress;
+                    for parent in &parents[idx] {
+                        if let Parent::Local(parent_idx) = *parent {
+                            match state[parent_idx] {
+                                State::Unvisited => stack.push(parent_idx),
+                                State::InProgress => return Err(Error::Cycle { id: commits[idx].id }),
+                                State::Done => {}
+                            }
+                        }
+                    }
+                }
+                State::InProgress => {
+                    let (mut max_generation, mut max_date) = (0, 0);
+                    for parent in &parents[idx] {
+                        let (generation, date) = match *parent {
+                            Parent::Local(parent_idx) => (generations[parent_idx], dates[parent_idx]),
+                            Parent::Base {
+                                generation,
+                                corrected_commit_date,
+                                ..
+                            } => (generation, corrected_commit_date),
+                        };
+                        max_generation = max_generation.max(generation);
+                        max_date = max_date.max(date);
+                    }
+                    generations[idx] = max_generation.min(GENERATION_NUMBER_MAX - 1) + 1;
+                    let timestamp = commits[idx].committer_timestamp;
+                    if timestamp > max_date {
+                        max_date = timestamp - 1;
+                    }
+                    dates[idx] = max_date + 1;
+                    state[idx] = State::Done;
+                    stack.pop();
+                }
+            }
+        }
+    }
+    Ok((generations, dates))
+}
@@ -0,0 +1,413 @@
+//! Write commit-graph files, either as single monolithic file or as layer of a split commit-graph chain.
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{bloom, File, Graph, Position};
+
+/// The error returned by [`File::write_to()`] and [`Graph::write_to_info_dir()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Commit {id} has parent {parent} which is neither part of the commits to write nor of the base graphs")]
+    MissingParent {
+        id: gix_hash::ObjectId,
+        parent: gix_hash::ObjectId,
+    },
+    #[error("Commit {id} is its own ancestor")]
+    Cycle { id: gix_hash::ObjectId },
+    #[error(
+        "Commit-graph would contain {0} commits, but only {} commits are allowed",
+        crate::MAX_COMMITS
+    )]
+    TooManyCommits(u64),
+    #[error("Commit-graph chain would consist of {0} files, but at most 256 files are allowed")]
+    TooManyFiles(usize),
+    #[error(transparent)]
+    Write(#[from] std::io::Error),
+    #[error("Could not write commit-graph data at '{}'", .path.display())]
+    Io {
+        #[source]
+        err: std::io::Error,
+        path: PathBuf,
+    },
+    #[error(transparent)]
+    Init(#[from] crate::init::Error),
+    #[error(transparent)]
+    Commit(#[from] crate::file::commit::Error),
+}
+
+/// A commit along with all information needed to write it into a commit-graph file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Commit {
+    /// The id of the commit itself.
+    pub id: gix_hash::ObjectId,
+    /// The id of the commit's root tree.
+    pub tree: gix_hash::ObjectId,
+    /// The ids of all parents of the commit, in order.
+    pub parents: Vec<gix_hash::ObjectId>,
+    /// The committer timestamp in seconds since the unix epoch.
+    pub committer_timestamp: u64,
+    /// The changed-path Bloom filter of the commit as computed by [`bloom::compute()`] with [`Options::changed_paths`],
+    /// or `None` if it wasn't computed.
+    pub bloom_filter: Option<Vec<u8>>,
+}
+
+impl Commit {
+    /// Create an instance from `commit` as stored in a graph file, using `graph` to resolve its parent positions.
+    /// Its Bloom filter is kept if it was computed with `bloom_settings`.
+    fn from_graph(
+        graph: &Graph,
+        commit: &crate::file::Commit<'_>,
+        bloom_settings: Option<&bloom::Settings>,
+    ) -> Result<Self, crate::file::commit::Error> {
+        Ok(Commit {
+            id: commit.id().into(),
+            tree: commit.root_tree_id().into(),
+            parents: commit
+                .iter_parents()
+                .map(|pos| pos.map(|pos| graph.id_at(pos).to_owned()))
+                .collect::<Result<_, _>>()?,
+            committer_timestamp: commit.committer_timestamp(),
+            bloom_filter: commit
+                .bloom_filter()
+                .filter(|filter| Some(filter.settings()) == bloom_settings)
+                .map(|filter| filter.as_bytes().to_owned()),
+        })
+    }
+}
+
+/// The version of generation numbers to write.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GenerationVersion {
+    /// Write topological levels only, as understood by all git versions.
+    V1,
+    /// Write corrected commit dates in addition to topological levels, using the `GDA2` and `GDO2` chunks.
+    ///
+    /// Note that these will only be written if all base graphs of a split chain also provide them.
+    #[default]
+    V2,
+}
+
+/// Determine how layers of a split commit-graph chain are merged, similar to `git commit-graph write --split[=<strategy>]`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SplitStrategy {
+    /// Write new commits into a new layer, and merge existing layers into it according to [`Split::size_multiple`]
+    /// and [`Split::max_commits`].
+    #[default]
+    Merge,
+    /// Write new commits into a new layer without ever merging existing layers.
+    NoMerge,
+    /// Merge all existing layers and new commits into a single layer.
+    Replace,
+}
+
+/// Options for writing a split commit-graph chain.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Split {
+    /// How to merge existing layers.
+    pub strategy: SplitStrategy,
+    /// Merge the topmost layer into the new one if it isn't at least `size_multiple` times bigger than the new layer,
+    /// and repeat with the next layer. Defaults to 2, like `--size-multiple`.
+    pub size_multiple: u32,
+    /// If set, keep merging layers into the new one while it contains more than `max_commits` commits,
+    /// like `--max-commits`.
+    pub max_commits: Option<u32>,
+}
+
+impl Default for Split {
+    fn default() -> Self {
+        Split {
+            strategy: SplitStrategy::default(),
+            size_multiple: 2,
+            max_commits: None,
+        }
+    }
+}
+
+/// Options for use in [`Graph::write_to_info_dir()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The kind of hash used for all commits.
+    pub object_hash: gix_hash::Kind,
+    /// The kind of generation numbers to write.
+    pub generation_version: GenerationVersion,
+    /// If set, write a new layer of a split commit-graph chain into `commit-graphs/`. Otherwise, write a single
+    /// `commit-graph` file and remove any previous chain.
+    pub split: Option<Split>,
+    /// If set, write the [Bloom filters](Commit::bloom_filter) of all commits which were computed with these settings,
+    /// like `--changed-paths`.
+    ///
+    /// Filters of commits in merged layers are kept if they were computed with the same settings.
+    pub changed_paths: Option<bloom::Settings>,
+}
+
+/// The result of [`Graph::write_to_info_dir()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The checksums of all files the commit-graph consists of after writing, base layers first.
+    ///
+    /// This is a single checksum if no split chain was written.
+    pub checksums: Vec<gix_hash::ObjectId>,
+    /// The amount of commits in the newly written file, or 0 if no file had to be written.
+    pub num_written_commits: u32,
+    /// The total amount of commits in the commit-graph after writing.
+    pub num_commits: u32,
+}
+
+const COMMIT_GRAPH_FILE: &str = "commit-graph";
+const COMMIT_GRAPHS_DIR: &str = "commit-graphs";
+const CHAIN_FILE: &str = "commit-graph-chain";
+
+/// Writing
+impl Graph {
+    /// Write all `commits` into the commit-graph at `info_dir`, typically `.git/objects/info`, configured by `options`.
+    ///
+    /// `commits` must contain all ancestors of each commit, unless these are already part of an existing commit-graph
+    /// and a split chain is written. Without [`Options::split`], a single `commit-graph` file with only `commits` is written
+    /// and all split chain files are removed. Otherwise, commits which aren't yet in the existing commit-graph are written
+    /// into a new layer, which existing layers may be merged into according to [`Split`].
+    /// Files of the chain that aren't referenced anymore are removed.
+    pub fn write_to_info_dir(
+        info_dir: &Path,
+        commits: impl IntoIterator<Item = Commit>,
+        Options {
+            object_hash,
+            generation_version,
+            split,
+            changed_paths,
+        }: Options,
+    ) -> Result<Outcome, Error> {
+        let graph_path = info_dir.join(COMMIT_GRAPH_FILE);
+        let graphs_dir = info_dir.join(COMMIT_GRAPHS_DIR);
+        let chain_path = graphs_dir.join(CHAIN_FILE);
+        let mut commits: Vec<_> = commits.into_iter().collect();
+        commits.sort_by_key(|c| c.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+
+        let Some(split) = split else {
+            let num_commits = commits.len() as u32;
+            let checksum = write_file_atomically(&graph_path, |out| {
+                File::write_to(commits, &[], out, object_hash, generation_version, changed_paths)
+            })?;
+            remove_file_if_present(&chain_path)?;
+            remove_unreferenced_graph_files(&graphs_dir, &[])?;
+            return Ok(Outcome {
+                checksums: vec![checksum],
+                num_written_commits: num_commits,
+                num_commits,
+            });
+        };
+
+        let graph = if graph_path.is_file() || chain_path.is_file() {
+            Graph::from_info_dir(info_dir)?
+        } else {
+            let num_commits = commits.len() as u32;
+            let checksum = write_layer(
+                &graphs_dir,
+                commits,
+                &[],
+                object_hash,
+                generation_version,
+                changed_paths,
+            )?;
+            write_chain(&chain_path, &[checksum])?;
+            remove_unreferenced_graph_files(&graphs_dir, &[checksum])?;
+            return Ok(Outcome {
+                checksums: vec![checksum],
+                num_written_commits: num_commits,
+                num_commits,
+            });
+        };
+        commits.retain(|c| graph.lookup(c.id).is_none());
+
+        if commits.is_empty() && split.strategy != SplitStrategy::Replace {
+            return Ok(Outcome {
+                checksums: graph.files.iter().map(|f| f.checksum().to_owned()).collect(),
+                num_written_commits: 0,
+                num_commits: graph.num_commits(),
+            });
+        }
+
+        let num_kept_files = num_kept_files(&graph.files, commits.len() as u64, split);
+        let first_merged_pos: u32 = graph.files[..num_kept_files].iter().map(File::num_commits).sum();
+        for pos in first_merged_pos..graph.num_commits() {
+            commits.push(Commit::from_graph(
+                &graph,
+                &graph.commit_at(Position(pos)),
+                changed_paths.as_ref(),
+            )?);
+        }
+        let num_written_commits = commits.len();
+
+        let mut files = graph.files;
+        files.truncate(num_kept_files);
+        let checksum = write_layer(
+            &graphs_dir,
+            commits,
+            &files,
+            object_hash,
+            generation_version,
+            changed_paths,
+        )?;
+        let mut checksums: Vec<_> = files.iter().map(|f| f.checksum().to_owned()).collect();
+        let monolithic_base = files.first().map_or(false, |f| f.path() == graph_path);
+        let num_kept_commits: u32 = files.iter().map(File::num_commits).sum();
+        drop(files);
+
+        if monolithic_base {
+            let new_path = graphs_dir.join(graph_file_name(&checksums[0]));
+            std::fs::rename(&graph_path, &new_path).map_err(|err| Error::Io { err, path: new_path })?;
+        }
+        checksums.push(checksum);
+        write_chain(&chain_path, &checksums)?;
+        remove_file_if_present(&graph_path)?;
+        remove_unreferenced_graph_files(&graphs_dir, &checksums)?;
+
+        Ok(Outcome {
+            checksums,
+            num_written_commits: num_written_commits as u32,
+            num_commits: num_kept_commits + num_written_commits as u32,
+        })
+    }
+}
+
+/// Return the amount of layers in `files`, base first, to keep when adding a layer with `num_new_commits`, with all others
+/// being merged into the new layer.
+fn num_kept_files(files: &[File], num_new_commits: u64, split: Split) -> usize {
+    match split.strategy {
+        SplitStrategy::Replace => 0,
+        SplitStrategy::NoMerge => files.len(),
+        SplitStrategy::Merge => {
+            let mut num_commits = num_new_commits;
+            let mut num_kept = files.len();
+            while let Some(top) = num_kept.checked_sub(1).map(|idx| &files[idx]) {
+                let top_commits = u64::from(top.num_commits());
+                let too_small = top_commits <= u64::from(split.size_multiple) * num_commits;
+                let too_many = split.max_commits.map_or(false, |max| num_commits > u64::from(max));
+                if !(too_small || too_many) {
+                    break;
+                }
+                num_commits += top_commits;
+                num_kept -= 1;
+            }
+            num_kept
+        }
+    }
+}
+
+fn graph_file_name(checksum: &gix_hash::oid) -> String {
+    format!("graph-{checksum}.graph")
+}
+
+/// Write a new layer with `commits` on top of `base_files` into `graphs_dir` and return its checksum.
+fn write_layer(
+    graphs_dir: &Path,
+    commits: Vec<Commit>,
+    base_files: &[File],
+    object_hash: gix_hash::Kind,
+    generation_version: GenerationVersion,
+    changed_paths: Option<bloom::Settings>,
+) -> Result<gix_hash::ObjectId, Error> {
+    std::fs::create_dir_all(graphs_dir).map_err(|err| Error::Io {
+        err,
+        path: graphs_dir.to_owned(),
+    })?;
+    let tmp_path = graphs_dir.join(format!("tmp_graph_{}", std::process::id()));
+    let checksum = write_file_atomically(&tmp_path, |out| {
+        File::write_to(commits, base_files, out, object_hash, generation_version, changed_paths)
+    })?;
+    let path = graphs_dir.join(graph_file_name(&checksum));
+    std::fs::rename(&tmp_path, &path).map_err(|err| Error::Io { err, path })?;
+    Ok(checksum)
+}
+
+fn write_chain(chain_path: &Path, checksums: &[gix_hash::ObjectId]) -> Result<(), Error> {
+    write_file_atomically(chain_path, |out| {
+        for checksum in checksums {
+            writeln!(out, "{checksum}")?;
+        }
+        Ok(())
+    })
+}
+
+/// Write to a lock file next to `path` using `write`, and move it into place on success.
+fn write_file_atomically<T>(path: &Path, write: impl FnOnce(&mut dyn Write) -> Result<T, Error>) -> Result<T, Error> {
+    let mut lock_path = path.to_owned().into_os_string();
+    lock_path.push(".lock");
+    let lock_path = PathBuf::from(lock_path);
+    let io_err = |err| Error::Io {
+        err,
+        path: lock_path.clone(),
+    };
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&lock_path)
+        .map_err(io_err)?;
+    let res = (|| {
+        let mut out = std::io::BufWriter::new(file);
+        let res = write(&mut out)?;
+        out.into_inner()
+            .map_err(std::io::IntoInnerError::into_error)?
+            .sync_all()?;
+        Ok(res)
+    })();
+    match res {
+        Ok(res) => {
+            std::fs::rename(&lock_path, path).map_err(|err| Error::Io {
+                err,
+                path: path.to_owned(),
+            })?;
+            Ok(res)
+        }
+        Err(err) => {
+            std::fs::remove_file(&lock_path).ok();
+            Err(err)
+        }
+    }
+}
+
+fn remove_file_if_present(path: &Path) -> Result<(), Error> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(Error::Io {
+            err,
+            path: path.to_owned(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Remove all graph files in `graphs_dir` that aren't referenced by `checksums`.
+fn remove_unreferenced_graph_files(graphs_dir: &Path, checksums: &[gix_hash::ObjectId]) -> Result<(), Error> {
+    let entries = match std::fs::read_dir(graphs_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(Error::Io {
+                err,
+                path: graphs_dir.to_owned(),
+            })
+        }
+    };
+    for entry in entries {
+        let entry = entry.map_err(|err| Error::Io {
+            err,
+            path: graphs_dir.to_owned(),
+        })?;
+        let file_name = entry.file_name();
+        let Some(hex) = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix("graph-"))
+            .and_then(|name| name.strip_suffix(".graph"))
+        else {
+            continue;
+        };
+        if checksums.iter().any(|checksum| checksum.to_hex().to_string() == hex) {
+            continue;
+        }
+        remove_file_if_present(&entry.path())?;
+    }
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use gix_commitgraph::bloom;
+
 use crate::{check_common, graph_and_expected, graph_and_expected_named};
 
 #[test]
@@ -69,6 +71,23 @@ fn generation_numbers_overflow_is_handled_in_chained_graph() {
     }
 }
 
+#[test]
+fn corrected_commit_dates_are_read_from_generation_data_and_its_overflow() {
+    let names = ["extra", "old-2", "future-2", "old-1", "future-1"];
+    let (cg, refs) = graph_and_expected("generation_number_overflow.sh", &names);
+    for (name, expected) in names.iter().zip((4147483646..=4147483650).rev()) {
+        let commit = cg.commit_by_id(refs[*name].id()).expect("present");
+        assert_eq!(commit.corrected_commit_date(), Some(expected), "{name}");
+    }
+    assert_eq!(
+        cg.commit_by_id(refs["old-1"].id())
+            .expect("present")
+            .committer_timestamp(),
+        0,
+        "the offset to the corrected commit date overflows and is stored separately"
+    );
+}
+
 #[test]
 fn octupus_merges() {
     let (cg, refs) = graph_and_expected(
@@ -111,3 +130,50 @@ fn two_parents() {
     assert_eq!(cg.commit_at(refs["parent2"].pos()).generation(), 1);
     assert_eq!(cg.commit_at(refs["child"].pos()).generation(), 2);
 }
+
+#[test]
+fn changed_path_bloom_filters() {
+    let names = [
+        "root",
+        "side",
+        "empty",
+        "file-becomes-directory",
+        "too-many-changes",
+        "tip",
+    ];
+    let (cg, refs) = graph_and_expected("changed_paths.sh", &names);
+    let settings = *cg.bloom_filter_settings().expect("written with --changed-paths");
+    assert_eq!(settings, bloom::Settings::default());
+    let maybe_changed = |name: &str, path: &str| {
+        let filter = cg
+            .commit_by_id(refs[name].id())
+            .expect("present")
+            .bloom_filter()
+            .expect("computed for all commits");
+        filter.contains_all(&bloom::Key::with_leading_directories(path.into(), &settings))
+    };
+
+    for path in ["a", "dir", "dir/b", "dir/sub", "dir/sub/c"] {
+        assert!(maybe_changed("root", path), "{path} was added");
+    }
+    assert!(maybe_changed("side", "dir/sub/ümlaut"));
+    assert!(maybe_changed("side", "é"));
+    assert!(
+        maybe_changed("file-becomes-directory", "dir/b/e"),
+        "directories are added as well"
+    );
+
+    for path in ["a", "dir", "dir/sub/c", "many"] {
+        assert!(
+            !maybe_changed("empty", path),
+            "{path}: the empty commit changes nothing"
+        );
+    }
+    for path in ["does-not-exist", "dir/b/e"] {
+        assert!(
+            maybe_changed("too-many-changes", path),
+            "{path}: too many changes, so everything may have changed"
+        );
+    }
+    assert!(maybe_changed("tip", "many/1"));
+}
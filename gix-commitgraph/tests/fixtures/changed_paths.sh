@@ -0,0 +1,48 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir -p dir/sub other
+echo a > a
+echo b > dir/b
+echo c > dir/sub/c
+git add . && git commit -q -m root
+git branch root
+
+echo a2 > a
+git commit -q -am change-a
+
+echo c2 > dir/sub/c
+echo d > other/d
+git add . && git commit -q -m change-c-add-d
+
+git checkout -q -b side root
+echo ä > "dir/sub/ümlaut"
+echo é > "é"
+git add . && git commit -q -m non-ascii
+
+git checkout -q main
+git merge -q -m merge --no-ff side
+
+git commit -q --allow-empty -m empty
+git tag empty
+
+git rm -q dir/b
+mkdir dir/b
+echo e > dir/b/e
+git add . && git commit -q -m file-becomes-directory
+git tag file-becomes-directory
+
+mkdir many
+for i in $(seq 1 513); do echo $i > many/$i; done
+git add . && git commit -q -m too-many-changes
+git tag too-many-changes
+
+git rm -rq many
+git commit -q -m remove-many
+
+git tag tip
+git commit-graph write --no-progress --reachable --changed-paths
+git repack -adq
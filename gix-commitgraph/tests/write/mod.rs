@@ -0,0 +1,321 @@
+use std::{
+    io::{BufRead, Cursor},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use bstr::BString;
+use gix_commitgraph::{
+    bloom,
+    write::{self, Split, SplitStrategy},
+    Graph,
+};
+use gix_testtools::{scripted_fixture_read_only, scripted_fixture_writable};
+
+use crate::{check_common, inspect_refs};
+
+#[test]
+fn single_file_is_identical_to_the_one_written_by_git() -> gix_testtools::Result {
+    let expected = scripted_fixture_read_only("octopus_merges.sh")?;
+    let tmp = scripted_fixture_writable("octopus_merges.sh")?;
+    std::fs::remove_file(info_dir(tmp.path()).join("commit-graph"))?;
+
+    let outcome = Graph::write_to_info_dir(&info_dir(tmp.path()), commits(tmp.path(), &["--all"]), options(None))?;
+    assert_eq!(outcome.num_written_commits, 7);
+    assert_eq!(outcome.num_commits, 7);
+    assert_eq!(
+        std::fs::read(info_dir(tmp.path()).join("commit-graph"))?,
+        std::fs::read(info_dir(&expected).join("commit-graph"))?,
+        "extra edges are written just like git does"
+    );
+    verify_with_git(tmp.path());
+    Ok(())
+}
+
+#[test]
+fn split_chain_with_generation_data_overflow_is_identical_to_the_one_written_by_git() -> gix_testtools::Result {
+    let expected = scripted_fixture_read_only("generation_number_overflow.sh")?;
+    let tmp = scripted_fixture_writable("generation_number_overflow.sh")?;
+    let info = info_dir(tmp.path());
+    std::fs::remove_dir_all(info.join("commit-graphs"))?;
+
+    Graph::write_to_info_dir(&info, commits(tmp.path(), &["old-1"]), options(None))?;
+    let no_merge = Some(Split {
+        strategy: SplitStrategy::NoMerge,
+        ..Default::default()
+    });
+    let outcome = Graph::write_to_info_dir(&info, commits(tmp.path(), &["old-2"]), options(no_merge))?;
+    assert_eq!(outcome.checksums.len(), 2);
+    assert!(
+        !info.join("commit-graph").exists(),
+        "the monolithic graph file was moved into the chain"
+    );
+    let outcome = Graph::write_to_info_dir(&info, commits(tmp.path(), &["extra"]), options(no_merge))?;
+    assert_eq!(outcome.checksums.len(), 3);
+    assert_eq!(outcome.num_written_commits, 1);
+    assert_eq!(outcome.num_commits, 5);
+
+    assert_eq!(
+        std::fs::read(info.join("commit-graphs/commit-graph-chain"))?,
+        std::fs::read(info_dir(&expected).join("commit-graphs/commit-graph-chain"))?,
+        "all layers, including their generation data and its overflow, are the same as the ones written by git"
+    );
+    verify_with_git(tmp.path());
+    Ok(())
+}
+
+#[test]
+fn changed_path_bloom_filters_are_identical_to_the_ones_written_by_git() -> gix_testtools::Result {
+    let expected = scripted_fixture_read_only("changed_paths.sh")?;
+    let tmp = scripted_fixture_writable("changed_paths.sh")?;
+    let info = info_dir(tmp.path());
+    std::fs::remove_file(info.join("commit-graph"))?;
+
+    let settings = bloom::Settings::default();
+    let commits = commits(tmp.path(), &["--all"]).into_iter().map(|mut c| {
+        let paths = changed_paths(tmp.path(), &c);
+        c.bloom_filter = Some(bloom::compute(paths.iter().map(AsRef::as_ref), &settings));
+        c
+    });
+    Graph::write_to_info_dir(
+        &info,
+        commits,
+        write::Options {
+            changed_paths: Some(settings),
+            ..options(None)
+        },
+    )?;
+    assert_eq!(
+        std::fs::read(info.join("commit-graph"))?,
+        std::fs::read(info_dir(&expected).join("commit-graph"))?,
+        "filters of empty commits, non-ASCII paths and too many changes are the same as the ones written by git"
+    );
+    verify_with_git(tmp.path());
+    Ok(())
+}
+
+#[test]
+fn changed_path_bloom_filters_are_kept_when_merging_layers() -> gix_testtools::Result {
+    let tmp = scripted_fixture_writable("changed_paths.sh")?;
+    let info = info_dir(tmp.path());
+    let settings = bloom::Settings::default();
+    let split = write::Options {
+        changed_paths: Some(settings),
+        ..options(Some(Split {
+            strategy: SplitStrategy::Replace,
+            ..Default::default()
+        }))
+    };
+
+    let outcome = Graph::write_to_info_dir(&info, None, split)?;
+    assert_eq!(outcome.num_written_commits, 9, "the monolithic graph was rewritten");
+    assert_eq!(outcome.checksums.len(), 1);
+    let graph = Graph::from_info_dir(&info)?;
+    assert_eq!(graph.bloom_filter_settings(), Some(&settings));
+    assert_eq!(
+        graph.iter_commits().filter(|c| c.bloom_filter().is_some()).count(),
+        9,
+        "all filters were copied"
+    );
+
+    Graph::write_to_info_dir(
+        &info,
+        None,
+        write::Options {
+            changed_paths: None,
+            ..split
+        },
+    )?;
+    let graph = Graph::from_info_dir(&info)?;
+    assert_eq!(
+        graph.bloom_filter_settings(),
+        None,
+        "filters are dropped if not requested"
+    );
+    verify_with_git(tmp.path());
+    Ok(())
+}
+
+#[test]
+fn split_strategies() -> gix_testtools::Result {
+    let tmp = scripted_fixture_writable("split_chain.sh")?;
+    let info = info_dir(tmp.path());
+    std::fs::remove_dir_all(info.join("commit-graphs"))?;
+    let split = |strategy, max_commits| {
+        options(Some(Split {
+            strategy,
+            max_commits,
+            ..Default::default()
+        }))
+    };
+
+    let outcome = Graph::write_to_info_dir(
+        &info,
+        commits(tmp.path(), &["commit1"]),
+        split(SplitStrategy::Merge, None),
+    )?;
+    assert_eq!(outcome.checksums.len(), 1);
+    let outcome = Graph::write_to_info_dir(
+        &info,
+        commits(tmp.path(), &["commit2"]),
+        split(SplitStrategy::NoMerge, None),
+    )?;
+    assert_eq!(outcome.checksums.len(), 2, "layers are never merged");
+    assert_eq!(num_graph_files(&info)?, 2);
+
+    let outcome = Graph::write_to_info_dir(
+        &info,
+        commits(tmp.path(), &["commit2"]),
+        split(SplitStrategy::Merge, None),
+    )?;
+    assert_eq!(
+        outcome.num_written_commits, 0,
+        "there is nothing to do without new commits"
+    );
+    assert_eq!(outcome.checksums.len(), 2);
+
+    let outcome = Graph::write_to_info_dir(
+        &info,
+        commits(tmp.path(), &["commit3"]),
+        split(SplitStrategy::Merge, None),
+    )?;
+    assert_eq!(
+        outcome.checksums.len(),
+        1,
+        "each layer has less than twice as many commits as the new layer and is merged"
+    );
+    assert_eq!(outcome.num_written_commits, 3);
+    assert_eq!(num_graph_files(&info)?, 1, "unreferenced files are removed");
+    verify_with_git(tmp.path());
+
+    std::fs::remove_dir_all(info.join("commit-graphs"))?;
+    let by_max_commits = options(Some(Split {
+        strategy: SplitStrategy::Merge,
+        size_multiple: 0,
+        max_commits: Some(1),
+    }));
+    Graph::write_to_info_dir(&info, commits(tmp.path(), &["commit1"]), by_max_commits)?;
+    let outcome = Graph::write_to_info_dir(&info, commits(tmp.path(), &["commit3"]), by_max_commits)?;
+    assert_eq!(
+        outcome.checksums.len(),
+        1,
+        "layers are merged while the new layer has more than `max_commits` commits"
+    );
+    assert_eq!(outcome.num_written_commits, 3);
+
+    let outcome = Graph::write_to_info_dir(&info, None, split(SplitStrategy::Replace, None))?;
+    assert_eq!(outcome.checksums.len(), 1);
+    assert_eq!(outcome.num_written_commits, 3, "existing layers are rewritten");
+    let graph = Graph::from_info_dir(&info)?;
+    check_common(&graph, &inspect_refs(tmp.path(), &["commit1", "commit2", "commit3"]));
+    verify_with_git(tmp.path());
+
+    Graph::write_to_info_dir(&info, commits(tmp.path(), &["commit3"]), options(None))?;
+    assert!(info.join("commit-graph").is_file());
+    assert!(
+        !info.join("commit-graphs/commit-graph-chain").exists(),
+        "the chain is removed when writing a single file"
+    );
+    assert_eq!(num_graph_files(&info)?, 0);
+    verify_with_git(tmp.path());
+    Ok(())
+}
+
+#[test]
+fn missing_parents_are_an_error() -> gix_testtools::Result {
+    let tmp = scripted_fixture_writable("split_chain.sh")?;
+    let mut commits = commits(tmp.path(), &["commit3"]);
+    commits.retain(|c| !c.parents.is_empty());
+    let err = Graph::write_to_info_dir(&info_dir(tmp.path()), commits, options(None)).unwrap_err();
+    assert!(matches!(err, write::Error::MissingParent { .. }));
+    Ok(())
+}
+
+fn info_dir(repo_dir: &Path) -> PathBuf {
+    repo_dir.join(".git").join("objects").join("info")
+}
+
+fn options(split: Option<Split>) -> write::Options {
+    write::Options {
+        object_hash: gix_hash::Kind::Sha1,
+        generation_version: write::GenerationVersion::V2,
+        split,
+        changed_paths: None,
+    }
+}
+
+fn num_graph_files(info_dir: &Path) -> std::io::Result<usize> {
+    Ok(std::fs::read_dir(info_dir.join("commit-graphs"))?
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().map_or(false, |ext| ext == "graph"))
+        .count())
+}
+
+/// Return all commits reachable from `revs`, like `git commit-graph write --reachable` would collect them.
+fn commits(repo_dir: &Path, revs: &[&str]) -> Vec<write::Commit> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(["log", "--format=%H %T %ct %P"])
+        .args(revs)
+        .arg("--")
+        .env_remove("GIT_DIR")
+        .output()
+        .expect("failed to execute `git log`");
+    assert!(output.status.success(), "`git log` failed");
+    Cursor::new(output.stdout)
+        .lines()
+        .map(|line| {
+            let line = line.expect("valid output");
+            let parts = line.trim_end().split(' ').collect::<Vec<_>>();
+            let id = |hex: &str| gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("40 bytes hex");
+            write::Commit {
+                id: id(parts[0]),
+                tree: id(parts[1]),
+                committer_timestamp: parts[2].parse().expect("valid timestamp"),
+                parents: parts[3..].iter().map(|hex| id(hex)).collect(),
+                bloom_filter: None,
+            }
+        })
+        .collect()
+}
+
+/// Return the paths of all files changed by `commit` compared to its first parent, like git computes them for Bloom filters.
+fn changed_paths(repo_dir: &Path, commit: &write::Commit) -> Vec<BString> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(repo_dir).args([
+        "diff-tree",
+        "-r",
+        "-z",
+        "--no-renames",
+        "--name-only",
+        "--no-commit-id",
+        "--root",
+    ]);
+    if let Some(parent) = commit.parents.first() {
+        cmd.arg(parent.to_string());
+    }
+    let output = cmd
+        .arg(commit.id.to_string())
+        .env_remove("GIT_DIR")
+        .output()
+        .expect("failed to execute `git diff-tree`");
+    assert!(output.status.success(), "`git diff-tree` failed");
+    output
+        .stdout
+        .split(|b| *b == 0)
+        .filter(|path| !path.is_empty())
+        .map(Into::into)
+        .collect()
+}
+
+fn verify_with_git(repo_dir: &Path) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(["commit-graph", "verify", "--no-progress"])
+        .env_remove("GIT_DIR")
+        .status()
+        .expect("git can be executed");
+    assert!(status.success(), "git considers the commit-graph valid");
+}
@@ -40,6 +40,9 @@ pub fn invoke(helper: &mut crate::Program, action: &Action) -> Result {
 }
 
 pub(crate) fn raw(helper: &mut crate::Program, action: &Action) -> std::result::Result<Option<Vec<u8>>, Error> {
+    if let crate::program::Kind::Native { helper, .. } = &helper.kind {
+        return native(helper, action);
+    }
     let (mut stdin, stdout) = helper.start(action)?;
     if let (Action::Get(_), None) = (&action, &stdout) {
         panic!("BUG: `Helper` impls must return an output handle to read output from if Action::Get is provided")
@@ -66,3 +69,19 @@ pub(crate) fn raw(helper: &mut crate::Program, action: &Action) -> std::result::
         Some(stdout) => Ok(Some(stdout)),
     }
 }
+
+fn native(helper: &crate::native::Helper, action: &Action) -> std::result::Result<Option<Vec<u8>>, Error> {
+    use crate::program::main;
+    let mut input = Vec::new();
+    action.send(&mut input)?;
+    let ctx = Context::from_bytes(&input)?;
+    let kind = match action {
+        Action::Get(_) => main::Action::Get,
+        Action::Store(_) => main::Action::Store,
+        Action::Erase(_) => main::Action::Erase,
+    };
+    let found = helper.invoke(kind, ctx).map_err(|err| Error::CredentialsHelperFailed {
+        source: std::io::Error::new(std::io::ErrorKind::Other, err),
+    })?;
+    Ok(matches!(action, Action::Get(_)).then(|| found.map(|ctx| ctx.to_bstring().into()).unwrap_or_default()))
+}
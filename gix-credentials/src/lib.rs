@@ -23,6 +23,9 @@ pub struct Program {
 ///
 pub mod helper;
 
+///
+pub mod native;
+
 ///
 pub mod program;
 
@@ -0,0 +1,240 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{native::Error, program::main::Action, protocol::Context};
+
+/// A helper to keep credentials in the memory of a daemon which forgets them after a timeout, which is compatible with
+/// `git credential-cache` so that either one can talk to the daemon of the other.
+///
+/// If no daemon is running when credentials are stored, one is started on a thread of the current process, so credentials
+/// are kept only as long as the current process runs. Use [`serve()`] to run a daemon in a process of its own instead.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cache {
+    /// The socket to communicate with the daemon, or `None` to use the default location like `git` does.
+    pub socket: Option<PathBuf>,
+    /// The time for which stored credentials are kept.
+    pub timeout: Duration,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Cache {
+            socket: None,
+            timeout: Duration::from_secs(900),
+        }
+    }
+}
+
+impl Cache {
+    /// Return the path to the socket of the daemon, which is `~/.git-credential-cache/socket` if its directory exists,
+    /// or `$XDG_CACHE_HOME/git/credential/socket` otherwise.
+    pub fn socket_path(&self) -> Result<PathBuf, Error> {
+        if let Some(socket) = &self.socket {
+            return Ok(socket.clone());
+        }
+        let home = gix_path::env::home_dir();
+        if let Some(dir) = home
+            .as_ref()
+            .map(|home| home.join(".git-credential-cache"))
+            .filter(|dir| dir.is_dir())
+        {
+            return Ok(dir.join("socket"));
+        }
+        std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home.map(|home| home.join(".cache")))
+            .map(|dir| dir.join("git").join("credential").join("socket"))
+            .ok_or(Error::HomeMissing {
+                name: "credential cache socket",
+            })
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn invoke(&self, _action: Action, _ctx: &Context) -> Result<Option<Context>, Error> {
+        Err(Error::CacheUnsupported)
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn invoke(&self, action: Action, ctx: &Context) -> Result<Option<Context>, Error> {
+        let socket = self.socket_path()?;
+        let cache_err = |source| Error::Cache {
+            path: socket.clone(),
+            source,
+        };
+        if matches!(action, Action::Store) && (ctx.username.is_none() || ctx.password.is_none()) {
+            return Ok(None);
+        }
+        let mut request = format!("action={}\ntimeout={}\n", action.as_str(), self.timeout.as_secs()).into_bytes();
+        Context {
+            url: None,
+            quit: None,
+            ..ctx.clone()
+        }
+        .write_to(&mut request)
+        .map_err(cache_err)?;
+
+        let response = match daemon::send(&socket, &request) {
+            Ok(response) => response,
+            Err(err) if daemon::is_not_running(&err) => match action {
+                Action::Store => {
+                    daemon::spawn(&socket).map_err(cache_err)?;
+                    daemon::send(&socket, &request).map_err(cache_err)?
+                }
+                Action::Get | Action::Erase => return Ok(None),
+            },
+            Err(err) => return Err(cache_err(err)),
+        };
+        if !matches!(action, Action::Get) {
+            return Ok(None);
+        }
+        let found = Context::from_bytes(&response)
+            .map_err(|err| cache_err(std::io::Error::new(std::io::ErrorKind::InvalidData, err)))?;
+        Ok((found.username.is_some() || found.password.is_some()).then_some(found))
+    }
+}
+
+#[cfg(unix)]
+pub use daemon::serve;
+
+#[cfg(unix)]
+mod daemon {
+    use std::{
+        io::{Read, Write},
+        os::unix::{
+            fs::DirBuilderExt,
+            net::{UnixListener, UnixStream},
+        },
+        path::Path,
+        time::{Duration, Instant},
+    };
+
+    use bstr::ByteSlice;
+
+    use crate::{
+        native::{identity_of, matches},
+        protocol::Context,
+    };
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// Run a credential cache daemon listening on `socket` on the current thread like `git credential-cache--daemon` does,
+    /// until it's asked to exit or has no credentials left.
+    ///
+    /// It waits 30 seconds for the first credentials to be stored, and removes `socket` when done.
+    pub fn serve(socket: &Path) -> std::io::Result<()> {
+        serve_listener(bind(socket)?, socket)
+    }
+
+    pub(super) fn send(socket: &Path, request: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut stream = UnixStream::connect(socket)?;
+        stream.write_all(request)?;
+        stream.shutdown(std::net::Shutdown::Write)?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        Ok(response)
+    }
+
+    pub(super) fn is_not_running(err: &std::io::Error) -> bool {
+        matches!(
+            err.kind(),
+            std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused
+        )
+    }
+
+    pub(super) fn spawn(socket: &Path) -> std::io::Result<()> {
+        let listener = bind(socket)?;
+        let socket = socket.to_owned();
+        std::thread::Builder::new()
+            .name("gix-credentials::cache daemon".into())
+            .spawn(move || {
+                if let Err(_err) = serve_listener(listener, &socket) {
+                    gix_trace::warn!("credential cache daemon failed: {_err}");
+                }
+            })?;
+        Ok(())
+    }
+
+    fn bind(socket: &Path) -> std::io::Result<UnixListener> {
+        if let Some(dir) = socket.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+        }
+        match std::fs::remove_file(socket) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+        UnixListener::bind(socket)
+    }
+
+    fn serve_listener(listener: UnixListener, socket: &Path) -> std::io::Result<()> {
+        listener.set_nonblocking(true)?;
+        let wait_for_entry_until = Instant::now() + Duration::from_secs(30);
+        let mut entries = Vec::new();
+        let res = loop {
+            let now = Instant::now();
+            entries.retain(|(_, expires_at): &(Context, Instant)| *expires_at > now);
+            if entries.is_empty() && now >= wait_for_entry_until {
+                break Ok(());
+            }
+            match listener.accept() {
+                Ok((stream, _)) => match handle(stream, &mut entries) {
+                    Ok(Continue::No) => break Ok(()),
+                    Ok(Continue::Yes) => {}
+                    Err(_err) => {
+                        gix_trace::debug!("ignoring invalid credential cache request: {_err}");
+                    }
+                },
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(POLL_INTERVAL),
+                Err(err) => break Err(err),
+            }
+        };
+        std::fs::remove_file(socket).ok();
+        res
+    }
+
+    enum Continue {
+        Yes,
+        No,
+    }
+
+    fn handle(mut stream: UnixStream, entries: &mut Vec<(Context, Instant)>) -> std::io::Result<Continue> {
+        let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg.to_owned());
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(Duration::from_secs(1)))?;
+        let mut request = Vec::new();
+        stream.read_to_end(&mut request)?;
+
+        let mut parts = request.splitn(3, |b| *b == b'\n');
+        let action = parts
+            .next()
+            .and_then(|line| line.strip_prefix(b"action="))
+            .ok_or_else(|| invalid("missing action"))?;
+        let timeout = parts
+            .next()
+            .and_then(|line| line.strip_prefix(b"timeout="))
+            .and_then(|timeout| timeout.to_str().ok()?.parse().ok())
+            .map(Duration::from_secs)
+            .ok_or_else(|| invalid("missing timeout"))?;
+        let ctx = Context::from_bytes(parts.next().unwrap_or_default())
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        match action {
+            b"get" => {
+                if let Some((found, _)) = entries.iter().find(|(entry, _)| matches(&ctx, entry, false)) {
+                    identity_of(found).write_to(&mut stream)?;
+                }
+            }
+            b"store" => {
+                if ctx.username.is_none() || ctx.password.is_none() {
+                    return Err(invalid(
+                        "cache daemon can't store credentials without username or password",
+                    ));
+                }
+                entries.retain(|(entry, _)| !matches(&ctx, entry, false));
+                entries.push((ctx, Instant::now() + timeout));
+            }
+            b"erase" => entries.retain(|(entry, _)| !matches(&ctx, entry, true)),
+            b"exit" => return Ok(Continue::No),
+            _ => return Err(invalid("unknown action")),
+        }
+        Ok(Continue::Yes)
+    }
+}
@@ -0,0 +1,118 @@
+use std::{num::NonZeroU32, path::PathBuf};
+
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+
+use crate::{
+    native::{identity_of, lock, read_if_exists, store, write_locked, Error},
+    program::main::Action,
+    protocol::Context,
+};
+
+/// A helper to store credentials like [`Store`](super::Store) does, but in a file that is encrypted with a key derived
+/// from a passphrase.
+///
+/// The file starts with a header of `GIXCRED1`, the number of key derivation iterations as big-endian 32 bit integer,
+/// a 16 byte salt and a 12 byte nonce, followed by the contents of a [`Store`](super::Store) file encrypted with
+/// `ChaCha20-Poly1305` using the header as associated data. The key is derived with `PBKDF2-HMAC-SHA256`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EncryptedStore {
+    /// The encrypted file to keep credentials in.
+    pub file: PathBuf,
+    /// The file with the passphrase to derive the key from, of which a trailing newline is ignored.
+    pub key_file: PathBuf,
+}
+
+const MAGIC: &[u8] = b"GIXCRED1";
+const SALT_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 4 + SALT_LEN + NONCE_LEN;
+const ITERATIONS: u32 = 100_000;
+
+impl EncryptedStore {
+    pub(crate) fn invoke(&self, action: Action, ctx: &Context) -> Result<Option<Context>, Error> {
+        if let Action::Get = action {
+            return Ok(self
+                .read()?
+                .and_then(|content| store::find(&content, ctx))
+                .map(|found| identity_of(&found)));
+        }
+        let lock = lock(&self.file)?;
+        let content = match self.read()? {
+            Some(content) => content,
+            None if matches!(action, Action::Erase) => return Ok(None),
+            None => Vec::new(),
+        };
+        if let Some(content) = store::update(&content, action, ctx) {
+            write_locked(lock, &self.file, &self.encrypt(content)?)?;
+        }
+        Ok(None)
+    }
+
+    fn read(&self) -> Result<Option<Vec<u8>>, Error> {
+        read_if_exists(&self.file)?.map(|data| self.decrypt(data)).transpose()
+    }
+
+    fn decrypt(&self, mut data: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let err = || Error::Decrypt {
+            path: self.file.clone(),
+        };
+        let header: [u8; HEADER_LEN] = data.get(..HEADER_LEN).ok_or_else(err)?.try_into().expect("right size");
+        let (magic, rest) = header.split_at(MAGIC.len());
+        let (iterations, rest) = rest.split_at(4);
+        let (salt, nonce) = rest.split_at(SALT_LEN);
+        if magic != MAGIC {
+            return Err(err());
+        }
+        let iterations =
+            NonZeroU32::new(u32::from_be_bytes(iterations.try_into().expect("4 bytes"))).ok_or_else(err)?;
+        let nonce = Nonce::try_assume_unique_for_key(nonce).expect("right size");
+        let plaintext_len = self
+            .key(iterations, salt)?
+            .open_in_place(nonce, Aad::from(&header), &mut data[HEADER_LEN..])
+            .map_err(|_| err())?
+            .len();
+        data.drain(..HEADER_LEN);
+        data.truncate(plaintext_len);
+        Ok(data)
+    }
+
+    fn encrypt(&self, mut content: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let err = |_| Error::Write {
+            path: self.file.clone(),
+            source: std::io::Error::new(std::io::ErrorKind::Other, "could not encrypt credentials"),
+        };
+        let rng = SystemRandom::new();
+        let mut salt = [0; SALT_LEN];
+        let mut nonce = [0; NONCE_LEN];
+        rng.fill(&mut salt).map_err(err)?;
+        rng.fill(&mut nonce).map_err(err)?;
+
+        let mut out = Vec::with_capacity(HEADER_LEN + content.len() + CHACHA20_POLY1305.tag_len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&ITERATIONS.to_be_bytes());
+        out.extend_from_slice(&salt);
+        out.extend_from_slice(&nonce);
+        self.key(NonZeroU32::new(ITERATIONS).expect("non-zero"), &salt)?
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(&out), &mut content)
+            .map_err(err)?;
+        out.extend_from_slice(&content);
+        Ok(out)
+    }
+
+    fn key(&self, iterations: NonZeroU32, salt: &[u8]) -> Result<LessSafeKey, Error> {
+        let passphrase = std::fs::read(&self.key_file).map_err(|err| Error::KeyFile {
+            path: self.key_file.clone(),
+            source: err,
+        })?;
+        let passphrase = passphrase.strip_suffix(b"\n").unwrap_or(&passphrase);
+        let passphrase = passphrase.strip_suffix(b"\r").unwrap_or(passphrase);
+        let mut key = [0; 32];
+        pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase, &mut key);
+        Ok(LessSafeKey::new(
+            UnboundKey::new(&CHACHA20_POLY1305, &key).expect("key has the right length"),
+        ))
+    }
+}
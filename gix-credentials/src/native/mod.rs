@@ -0,0 +1,216 @@
+//! Credential helpers implemented natively, which are run in-process instead of launching `git credential-<name>`.
+//!
+//! They are compatible with their `git` counterparts and can be used interchangeably with them, and are used by programs
+//! created with [`Program::from_native()`](crate::Program::from_native()), while
+//! [`Program::from_custom_definition()`](crate::Program::from_custom_definition()) always runs the external helper.
+//! Use [`Helper::invoke()`] with [`program::main()`](crate::program::main()) to implement a helper program with them.
+#![allow(clippy::result_large_err)]
+use std::path::{Path, PathBuf};
+
+use bstr::{BStr, ByteSlice};
+
+use crate::{program::main::Action, protocol::Context};
+
+///
+pub mod store;
+pub use store::Store;
+
+///
+pub mod cache;
+pub use cache::Cache;
+
+///
+#[cfg(feature = "encrypted-store")]
+pub mod encrypted_store;
+#[cfg(feature = "encrypted-store")]
+pub use encrypted_store::EncryptedStore;
+
+/// A credential helper that runs in-process.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Helper {
+    /// Store credentials in plain text, like `git credential-store`.
+    Store(Store),
+    /// Keep credentials in memory of a daemon for a limited time, like `git credential-cache`.
+    Cache(Cache),
+    /// Store credentials in a file encrypted with a key, named `gix-encrypted-store`, which has no `git` counterpart.
+    #[cfg(feature = "encrypted-store")]
+    EncryptedStore(EncryptedStore),
+}
+
+/// The error returned by [`Helper::invoke()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read credentials from '{}'", path.display())]
+    Read { path: PathBuf, source: std::io::Error },
+    #[error("Could not write credentials to '{}'", path.display())]
+    Write { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    Lock(#[from] gix_lock::acquire::Error),
+    #[error("Could not communicate with the credential cache daemon at '{}'", path.display())]
+    Cache { path: PathBuf, source: std::io::Error },
+    #[error("The credential cache needs Unix domain sockets, which aren't available on this platform")]
+    CacheUnsupported,
+    #[error("The home directory could not be determined to find the default location of '{name}'")]
+    HomeMissing { name: &'static str },
+    #[error(transparent)]
+    Url(#[from] crate::protocol::Error),
+    #[cfg(feature = "encrypted-store")]
+    #[error("Could not read the key from '{}'", path.display())]
+    KeyFile { path: PathBuf, source: std::io::Error },
+    #[cfg(feature = "encrypted-store")]
+    #[error("Could not decrypt credentials at '{}' as the key is wrong or the file is corrupt", path.display())]
+    Decrypt { path: PathBuf },
+}
+
+/// Initialization
+impl Helper {
+    /// Parse `name_and_args` like `store --file ~/.my-credentials`, and return the native helper it names,
+    /// or `None` if there is none or if the arguments aren't understood.
+    ///
+    /// Arguments are split on whitespace as quoting isn't supported, but a leading `~/` in paths is expanded to the home directory.
+    pub fn from_definition(name_and_args: &BStr) -> Option<Self> {
+        let input = name_and_args.to_str().ok()?;
+        if input.contains(['"', '\'', '\\', '$', '`']) {
+            return None;
+        }
+        let mut tokens = input.split_whitespace();
+        let name = tokens.next()?;
+        let mut args = Vec::new();
+        for token in tokens {
+            match token.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    args.push(flag);
+                    args.push(value);
+                }
+                _ => args.push(token),
+            }
+        }
+        let mut args = args.into_iter();
+        let next_path = |args: &mut std::vec::IntoIter<&str>| args.next().map(expand_home);
+        Some(match name {
+            "store" => {
+                let mut store = Store::default();
+                while let Some(flag) = args.next() {
+                    match flag {
+                        "--file" => store.file = Some(next_path(&mut args)?),
+                        _ => return None,
+                    }
+                }
+                Helper::Store(store)
+            }
+            "cache" => {
+                let mut cache = Cache::default();
+                while let Some(flag) = args.next() {
+                    match flag {
+                        "--socket" => cache.socket = Some(next_path(&mut args)?),
+                        "--timeout" => cache.timeout = std::time::Duration::from_secs(args.next()?.parse().ok()?),
+                        _ => return None,
+                    }
+                }
+                Helper::Cache(cache)
+            }
+            #[cfg(feature = "encrypted-store")]
+            "gix-encrypted-store" => {
+                let (mut file, mut key_file) = (None, None);
+                while let Some(flag) = args.next() {
+                    match flag {
+                        "--file" => file = Some(next_path(&mut args)?),
+                        "--key-file" => key_file = Some(next_path(&mut args)?),
+                        _ => return None,
+                    }
+                }
+                Helper::EncryptedStore(EncryptedStore {
+                    file: file?,
+                    key_file: key_file?,
+                })
+            }
+            _ => return None,
+        })
+    }
+}
+
+/// Access
+impl Helper {
+    /// Perform `action` with `ctx`, and return credentials if `action` is [`Action::Get`] and credentials were found,
+    /// in the format expected by [`program::main()`](crate::program::main()).
+    ///
+    /// If `ctx` only has the `url` field set, it's used to fill in all other fields.
+    pub fn invoke(&self, action: Action, mut ctx: Context) -> Result<Option<Context>, Error> {
+        if ctx.protocol.is_none() && ctx.url.is_some() {
+            ctx.destructure_url_in_place(true)?;
+        }
+        match self {
+            Helper::Store(store) => store.invoke(action, &ctx),
+            Helper::Cache(cache) => cache.invoke(action, &ctx),
+            #[cfg(feature = "encrypted-store")]
+            Helper::EncryptedStore(store) => store.invoke(action, &ctx),
+        }
+    }
+}
+
+/// Return `true` if `have` has all fields that are set in `want`, ignoring the password unless `match_password` is set,
+/// just like `git` does.
+pub(crate) fn matches(want: &Context, have: &Context, match_password: bool) -> bool {
+    fn check<T: PartialEq + ?Sized>(want: Option<&T>, have: Option<&T>) -> bool {
+        want.map_or(true, |want| have == Some(want))
+    }
+    check(want.protocol.as_deref(), have.protocol.as_deref())
+        && check(want.host.as_deref(), have.host.as_deref())
+        && check(want.path.as_deref(), have.path.as_deref())
+        && check(want.username.as_deref(), have.username.as_deref())
+        && (!match_password || check(want.password.as_deref(), have.password.as_deref()))
+}
+
+/// Return a context with only the username and password of `ctx`, which is what helpers return for [`Action::Get`].
+pub(crate) fn identity_of(ctx: &Context) -> Context {
+    Context {
+        username: ctx.username.clone(),
+        password: ctx.password.clone(),
+        ..Default::default()
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(relative) => gix_path::env::home_dir().map_or_else(|| path.into(), |home| home.join(relative)),
+        None => path.into(),
+    }
+}
+
+/// Write `data` to the file at `path` which was locked with `lock`, replacing it.
+pub(crate) fn write_locked(mut lock: gix_lock::File, path: &Path, data: &[u8]) -> Result<(), Error> {
+    use std::io::Write;
+    let write_err = |source| Error::Write {
+        path: path.to_owned(),
+        source,
+    };
+    lock.write_all(data).map_err(write_err)?;
+    lock.commit().map_err(|err| write_err(err.error))?;
+    Ok(())
+}
+
+/// Lock the file at `path` for updating it, waiting a little if it's already locked, and create it so that only the
+/// current user can read it.
+pub(crate) fn lock(path: &Path) -> Result<gix_lock::File, Error> {
+    let mode = gix_lock::acquire::Fail::AfterDurationWithBackoff(std::time::Duration::from_secs(1));
+    #[cfg(unix)]
+    let lock = gix_lock::File::acquire_to_update_resource_with_permissions(path, mode, None, || {
+        std::os::unix::fs::PermissionsExt::from_mode(0o600)
+    })?;
+    #[cfg(not(unix))]
+    let lock = gix_lock::File::acquire_to_update_resource(path, mode, None)?;
+    Ok(lock)
+}
+
+/// Read the file at `path`, or return `None` if it doesn't exist.
+pub(crate) fn read_if_exists(path: &Path) -> Result<Option<Vec<u8>>, Error> {
+    match std::fs::read(path) {
+        Ok(data) => Ok(Some(data)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(Error::Read {
+            path: path.to_owned(),
+            source: err,
+        }),
+    }
+}
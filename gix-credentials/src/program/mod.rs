@@ -24,6 +24,14 @@ pub enum Kind {
     },
     /// A script to execute with `sh`.
     ExternalShellScript(BString),
+    /// A helper like `store` or `cache` which is implemented natively and runs in-process, but which would be invoked like
+    /// [`ExternalName`](Kind::ExternalName) when turned into a command, as created by [`Program::from_native()`].
+    Native {
+        /// The name like `store` along with optional args, like `store --file ~/.my-credentials`.
+        name_and_args: BString,
+        /// The helper implementation.
+        helper: crate::native::Helper,
+    },
 }
 
 /// Initialization
@@ -66,6 +74,18 @@ impl Program {
         from_custom_definition_inner(input.into())
     }
 
+    /// Parse `name_and_args` like `store --file ~/.my-credentials`, the definition of a named helper, and return a program
+    /// that runs its [native](crate::native) implementation in-process instead of `git credential-<name>`, or `None` if
+    /// there is none or its arguments aren't understood.
+    ///
+    /// This has to be opted into as the native [`Cache`](crate::native::Cache) keeps credentials only as long as the
+    /// current process runs if it has to start the daemon itself.
+    pub fn from_native(name_and_args: impl Into<BString>) -> Option<Self> {
+        let name_and_args = name_and_args.into();
+        let helper = crate::native::Helper::from_definition(name_and_args.as_bstr())?;
+        Some(Program::from_kind(Kind::Native { name_and_args, helper }))
+    }
+
     /// Convert the program into the respective command, suitable to invoke `action`.
     pub fn to_command(&self, action: &helper::Action) -> std::process::Command {
         let git_program = gix_path::env::exe_invocation();
@@ -75,7 +95,7 @@ impl Program {
                 cmd.arg("credential").arg(action.as_arg(false));
                 cmd
             }
-            Kind::ExternalName { name_and_args } => {
+            Kind::ExternalName { name_and_args } | Kind::Native { name_and_args, .. } => {
                 let mut args = name_and_args.clone();
                 args.insert_str(0, "credential-");
                 args.insert_str(0, " ");
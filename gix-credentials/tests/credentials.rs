@@ -1,5 +1,6 @@
 pub use gix_testtools::Result;
 
 mod helper;
+mod native;
 mod program;
 mod protocol;
@@ -0,0 +1,25 @@
+#[cfg(unix)]
+mod cache;
+#[cfg(feature = "encrypted-store")]
+mod encrypted_store;
+mod store;
+
+use gix_credentials::protocol::Context;
+
+fn ctx(url: &str) -> Context {
+    let mut ctx = Context {
+        url: Some(url.into()),
+        ..Default::default()
+    };
+    ctx.destructure_url_in_place(true).expect("valid url");
+    ctx.url = None;
+    ctx
+}
+
+fn identity(username: &str, password: &str) -> Context {
+    Context {
+        username: Some(username.into()),
+        password: Some(password.into()),
+        ..Default::default()
+    }
+}
@@ -1,4 +1,4 @@
-use gix_credentials::{helper, program::Kind, Program};
+use gix_credentials::{helper, native, program::Kind, Program};
 
 static GIT: once_cell::sync::Lazy<&'static str> =
     once_cell::sync::Lazy::new(|| gix_path::env::exe_invocation().to_str().expect("not illformed"));
@@ -110,3 +110,56 @@ fn path_with_simple_args() {
         "a shell is used as there are arguments, and it's generally more flexible, but on windows we split ourselves"
     );
 }
+
+#[test]
+fn helpers_with_native_implementation_are_external_by_default() {
+    let git = *GIT;
+    for input in ["store", "cache --timeout 60"] {
+        let prog = Program::from_custom_definition(input);
+        assert!(
+            matches!(&prog.kind, Kind::ExternalName{name_and_args} if name_and_args == input),
+            "{input}: native helpers have to be opted into"
+        );
+    }
+    let prog = Program::from_custom_definition("store");
+    assert_eq!(
+        format!("{:?}", prog.to_command(&helper::Action::Store("egal".into()))),
+        format!(r#""{git}" "credential-store" "store""#)
+    );
+}
+
+#[test]
+fn native_helpers_with_known_arguments() {
+    let input = "store --file=/abs/credentials";
+    let prog = Program::from_native(input).expect("understood");
+    let git = *GIT;
+    assert!(matches!(
+        &prog.kind,
+        Kind::Native { name_and_args, helper: native::Helper::Store(store) }
+            if name_and_args == input && store.file.as_deref() == Some(std::path::Path::new("/abs/credentials"))
+    ));
+    assert_eq!(
+        format!("{:?}", prog.to_command(&helper::Action::Store("egal".into()))),
+        format!(r#""{git}" "credential-store" "--file=/abs/credentials" "store""#),
+        "they can still be invoked like any other named helper"
+    );
+
+    let prog = Program::from_native("cache --timeout 60").expect("understood");
+    assert!(matches!(
+        &prog.kind,
+        Kind::Native { helper: native::Helper::Cache(cache), .. }
+            if cache.timeout == std::time::Duration::from_secs(60) && cache.socket.is_none()
+    ));
+
+    for input in [
+        "store --unknown",
+        "cache --timeout=\"60\"",
+        "cache --timeout",
+        "storage",
+    ] {
+        assert!(
+            Program::from_native(input).is_none(),
+            "{input}: anything we don't understand is left to the external program"
+        );
+    }
+}
@@ -111,14 +111,36 @@ mod relative {
 
     use crate::parse::Error;
 
+    /// Parse approxidate-style relative dates like `2 weeks ago`, `2.weeks.ago`, `1 year 3 months ago`, `yesterday` and `now`.
     fn parse_inner(input: &str) -> Option<Result<Span, Error>> {
-        let mut split = input.split_whitespace();
-        let units = i64::from_str(split.next()?).ok()?;
-        let period = split.next()?;
-        if split.next()? != "ago" {
+        let mut tokens = input
+            .split(|c: char| c.is_ascii_whitespace() || c == '.')
+            .filter(|token| !token.is_empty());
+        let mut token = tokens.next()?;
+        let mut span = match token {
+            "now" => Some(Span::new()),
+            "yesterday" => Some(Span::new().days(1)),
+            _ => None,
+        };
+        if span.is_none() {
+            let mut out = Span::new();
+            loop {
+                let units = i64::from_str(token).ok()?;
+                out = match add(out, tokens.next()?, units)? {
+                    Ok(span) => span,
+                    Err(err) => return Some(Err(err)),
+                };
+                token = tokens.next()?;
+                if token == "ago" {
+                    break;
+                }
+            }
+            span = Some(out);
+        }
+        if tokens.next().is_some() {
             return None;
         }
-        span(period, units)
+        span.map(Ok)
     }
 
     pub(crate) fn parse(input: &str, now: Option<SystemTime>) -> Option<Result<Zoned, Error>> {
@@ -145,19 +167,22 @@ mod relative {
         })
     }
 
-    fn span(period: &str, units: i64) -> Option<Result<Span, Error>> {
+    /// Add `units` of `period` to `span`, or return `None` if `period` isn't known.
+    fn add(span: Span, period: &str, units: i64) -> Option<Result<Span, Error>> {
         let period = period.strip_suffix('s').unwrap_or(period);
+        let sum = |current: i64| current.checked_add(units);
         let result = match period {
-            "second" => Span::new().try_seconds(units),
-            "minute" => Span::new().try_minutes(units),
-            "hour" => Span::new().try_hours(units),
-            "day" => Span::new().try_days(units),
-            "week" => Span::new().try_weeks(units),
-            // TODO months & years? YES
+            "second" => sum(span.get_seconds()).and_then(|n| span.try_seconds(n).ok()),
+            "minute" => sum(span.get_minutes()).and_then(|n| span.try_minutes(n).ok()),
+            "hour" => sum(span.get_hours().into()).and_then(|n| span.try_hours(n).ok()),
+            "day" => sum(span.get_days().into()).and_then(|n| span.try_days(n).ok()),
+            "week" => sum(span.get_weeks().into()).and_then(|n| span.try_weeks(n).ok()),
+            "month" => sum(span.get_months().into()).and_then(|n| span.try_months(n).ok()),
+            "year" => sum(span.get_years().into()).and_then(|n| span.try_years(n).ok()),
             // Ignore values you don't know, assume seconds then (so does git)
             _ => return None,
         };
-        Some(result.map_err(|_| Error::RelativeTimeConversion))
+        Some(result.ok_or(Error::RelativeTimeConversion))
     }
 
     #[cfg(test)]
@@ -167,6 +192,28 @@ mod relative {
         #[test]
         fn two_weeks_ago() {
             assert_eq!(parse_inner("2 weeks ago").unwrap().unwrap(), Span::new().weeks(2));
+            assert_eq!(parse_inner("2.weeks.ago").unwrap().unwrap(), Span::new().weeks(2));
+        }
+
+        #[test]
+        fn compound_units() {
+            assert_eq!(
+                parse_inner("1 year 2 months 3 days ago").unwrap().unwrap(),
+                Span::new().years(1).months(2).days(3)
+            );
+            assert_eq!(
+                parse_inner("1.week.1.week.ago").unwrap().unwrap(),
+                Span::new().weeks(2),
+                "units add up"
+            );
+        }
+
+        #[test]
+        fn special_words() {
+            assert_eq!(parse_inner("now").unwrap().unwrap(), Span::new());
+            assert_eq!(parse_inner("yesterday").unwrap().unwrap(), Span::new().days(1));
+            assert!(parse_inner("now ago").is_none());
+            assert!(parse_inner("2 weeks").is_none(), "'ago' is required");
         }
     }
 }
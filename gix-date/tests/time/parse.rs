@@ -176,6 +176,25 @@ mod relative {
         assert_eq!(date.seconds, -1);
     }
 
+    #[test]
+    fn approxidate_forms() {
+        let now: SystemTime = "2024-03-31T12:00:00Z".parse::<jiff::Timestamp>().unwrap().into();
+        for (input, expected) in [
+            ("1 month ago", "2024-02-29T12:00:00Z"),
+            ("1.year.ago", "2023-03-31T12:00:00Z"),
+            ("1 week 2 days ago", "2024-03-22T12:00:00Z"),
+            ("yesterday", "2024-03-30T12:00:00Z"),
+            ("now", "2024-03-31T12:00:00Z"),
+        ] {
+            let expected: jiff::Timestamp = expected.parse().unwrap();
+            assert_eq!(
+                gix_date::parse(input, Some(now)).unwrap().seconds,
+                expected.as_second(),
+                "{input}"
+            );
+        }
+    }
+
     #[test]
     fn various() {
         let now = SystemTime::now();
@@ -0,0 +1,220 @@
+//! A word diff like `git diff --word-diff`, which diffs a sequence of words instead of lines.
+//!
+//! It operates on the text of two buffers, which typically are the removed and added lines of a hunk or two entire blobs.
+use std::{io, ops::Range};
+
+use crate::blob::{intern::InternedInput, Algorithm};
+
+/// A way to split text into words.
+#[derive(Default, Debug, Clone)]
+pub enum Tokenizer {
+    /// Words are separated by whitespace, which is what `git` does without `diff.wordRegex`.
+    #[default]
+    Whitespace,
+    /// Words are non-empty matches of the given regular expression, like `diff.wordRegex` or `--word-diff-regex`.
+    ///
+    /// Text that doesn't match is ignored, and matches never span multiple lines.
+    /// Use [`Tokenizer::from_word_regex()`] to create it with the same semantics as `git`.
+    #[cfg(feature = "word-regex")]
+    Regex(regex::bytes::Regex),
+}
+
+#[cfg(feature = "word-regex")]
+impl Tokenizer {
+    /// Compile `pattern` as `git` would compile `diff.wordRegex`, where `^` and `$` match at line boundaries.
+    pub fn from_word_regex(pattern: &str) -> Result<Self, regex::Error> {
+        regex::bytes::RegexBuilder::new(pattern)
+            .multi_line(true)
+            .build()
+            .map(Tokenizer::Regex)
+    }
+}
+
+impl Tokenizer {
+    /// Return the byte-ranges of all words in `text`, in order.
+    pub fn words(&self, text: &[u8]) -> Vec<Range<usize>> {
+        let mut out = Vec::new();
+        let mut pos = 0;
+        while let Some(word) = self.next_word(text, pos) {
+            pos = word.end;
+            out.push(word);
+        }
+        out
+    }
+
+    /// Find the next word at or after `begin`, similar to `find_word_boundaries()` in `git`.
+    fn next_word(&self, text: &[u8], mut begin: usize) -> Option<Range<usize>> {
+        match self {
+            Tokenizer::Whitespace => {
+                while begin < text.len() && text[begin].is_ascii_whitespace() {
+                    begin += 1;
+                }
+                let mut end = begin;
+                while end < text.len() && !text[end].is_ascii_whitespace() {
+                    end += 1;
+                }
+                (begin < end).then_some(begin..end)
+            }
+            #[cfg(feature = "word-regex")]
+            Tokenizer::Regex(re) => {
+                while begin < text.len() {
+                    let m = re.find_at(text, begin)?;
+                    let end = text[m.start()..m.end()]
+                        .iter()
+                        .position(|b| *b == b'\n')
+                        .map_or(m.end(), |nl| m.start() + nl);
+                    if m.start() != end {
+                        return Some(m.start()..end);
+                    }
+                    begin = m.start() + 1;
+                }
+                None
+            }
+        }
+    }
+}
+
+/// The way the word diff is printed.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Like `--word-diff=plain`, enclose removed words in `[-…-]` and added words in `{+…+}`.
+    #[default]
+    Plain,
+    /// Like `--word-diff=porcelain`, print each run of text on its own line prefixed with ` `, `-` or `+`,
+    /// with newlines of the input shown as a line containing only `~`.
+    Porcelain,
+}
+
+/// A run of text as produced by [`diff()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// The given range of text in the *new* buffer is unchanged, including the whitespace between words.
+    Unchanged(Range<usize>),
+    /// The given range of text in the *old* buffer was removed.
+    Removed(Range<usize>),
+    /// The given range of text in the *new* buffer was added.
+    Added(Range<usize>),
+}
+
+/// Diff the words of `old` and `new` as determined by `tokenizer` using `algorithm`, and call `cb` for each run of text
+/// in the order `git` would print it.
+pub fn diff(algorithm: Algorithm, old: &[u8], new: &[u8], tokenizer: &Tokenizer, mut cb: impl FnMut(Change)) {
+    let old_words = tokenizer.words(old);
+    let new_words = tokenizer.words(new);
+    let mut input = InternedInput::default();
+    input.update_before(old_words.iter().map(|r| &old[r.clone()]));
+    input.update_after(new_words.iter().map(|r| &new[r.clone()]));
+
+    // The end of the word before `idx`, which is where empty ranges are anchored, just like `git` does it.
+    let end_before =
+        |words: &[Range<usize>], idx: u32| -> usize { (idx as usize).checked_sub(1).map_or(0, |prev| words[prev].end) };
+    let mut current_new = 0;
+    crate::blob::diff(algorithm, &input, |before: Range<u32>, after: Range<u32>| {
+        let removed = if before.is_empty() {
+            None
+        } else {
+            Some(old_words[before.start as usize].start..old_words[before.end as usize - 1].end)
+        };
+        let (new_begin, new_end) = if after.is_empty() {
+            let pos = end_before(&new_words, after.start);
+            (pos, pos)
+        } else {
+            (
+                new_words[after.start as usize].start,
+                new_words[after.end as usize - 1].end,
+            )
+        };
+        if current_new < new_begin {
+            cb(Change::Unchanged(current_new..new_begin));
+        }
+        if let Some(removed) = removed {
+            cb(Change::Removed(removed));
+        }
+        if new_begin != new_end {
+            cb(Change::Added(new_begin..new_end));
+        }
+        current_new = new_end;
+    });
+    if current_new < new.len() {
+        cb(Change::Unchanged(current_new..new.len()));
+    }
+}
+
+/// Perform a word diff [like `diff()`](diff()) and write the result to `out` in the given `mode`.
+pub fn write(
+    algorithm: Algorithm,
+    old: &[u8],
+    new: &[u8],
+    tokenizer: &Tokenizer,
+    mode: Mode,
+    mut out: impl io::Write,
+) -> io::Result<()> {
+    let mut res = Ok(());
+    diff(algorithm, old, new, tokenizer, |change| {
+        if res.is_err() {
+            return;
+        }
+        let (style, text) = match change {
+            Change::Unchanged(r) => (Style::context(mode), &new[r]),
+            Change::Removed(r) => (Style::removed(mode), &old[r]),
+            Change::Added(r) => (Style::added(mode), &new[r]),
+        };
+        res = style.write(text, &mut out);
+    });
+    res
+}
+
+/// How to decorate text, like `struct diff_words_style_elem` in `git`.
+struct Style {
+    prefix: &'static str,
+    suffix: &'static str,
+    newline: &'static str,
+}
+
+impl Style {
+    fn context(mode: Mode) -> Self {
+        match mode {
+            Mode::Plain => Style::new("", "", mode),
+            Mode::Porcelain => Style::new(" ", "\n", mode),
+        }
+    }
+    fn removed(mode: Mode) -> Self {
+        match mode {
+            Mode::Plain => Style::new("[-", "-]", mode),
+            Mode::Porcelain => Style::new("-", "\n", mode),
+        }
+    }
+    fn added(mode: Mode) -> Self {
+        match mode {
+            Mode::Plain => Style::new("{+", "+}", mode),
+            Mode::Porcelain => Style::new("+", "\n", mode),
+        }
+    }
+    fn new(prefix: &'static str, suffix: &'static str, mode: Mode) -> Self {
+        Style {
+            prefix,
+            suffix,
+            newline: match mode {
+                Mode::Plain => "\n",
+                Mode::Porcelain => "~\n",
+            },
+        }
+    }
+
+    /// Write `text` with each of its lines decorated, like `fn_out_diff_words_write_helper()` in `git`.
+    fn write(&self, mut text: &[u8], out: &mut impl io::Write) -> io::Result<()> {
+        while !text.is_empty() {
+            let newline = text.iter().position(|b| *b == b'\n');
+            let line = &text[..newline.unwrap_or(text.len())];
+            if !line.is_empty() {
+                out.write_all(self.prefix.as_bytes())?;
+                out.write_all(line)?;
+                out.write_all(self.suffix.as_bytes())?;
+            }
+            let Some(newline) = newline else { break };
+            out.write_all(self.newline.as_bytes())?;
+            text = &text[newline + 1..];
+        }
+        Ok(())
+    }
+}
@@ -46,6 +46,10 @@ pub mod rewrites;
 ///
 pub mod tree;
 
+/// Per-file statistics of changes, similar to `git diff --stat`.
+#[cfg(feature = "blob")]
+pub mod stat;
+
 ///
 #[cfg(feature = "blob")]
 pub mod blob;
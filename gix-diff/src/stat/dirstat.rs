@@ -0,0 +1,154 @@
+use std::io;
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::stat::{Counts, File};
+
+/// Determine how the amount of change of each file is measured.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Count added and removed lines, like `--dirstat=lines`.
+    ///
+    /// Binary files are accounted for with 64 bytes per line.
+    #[default]
+    Lines,
+    /// Count each changed file as one change, like `--dirstat=files`.
+    ///
+    /// Text files without added or removed lines are considered unchanged, like renames or mode changes.
+    Files,
+}
+
+/// Options for use in [`compute()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// How to measure changes.
+    pub mode: Mode,
+    /// The minimal amount of change in per-mille of the total a directory needs to be listed, defaulting to `30` (i.e. 3%).
+    pub permille: u32,
+    /// If `true`, like `--dirstat=cumulative`, changes that were already reported for a sub-directory are
+    /// counted towards the parent directory as well.
+    pub cumulative: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            mode: Mode::default(),
+            permille: 30,
+            cumulative: false,
+        }
+    }
+}
+
+/// A directory whose share of the changes was high enough to be listed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The directory, with a trailing slash.
+    pub directory: BString,
+    /// The share of the total changes in per-mille.
+    pub permille: u32,
+}
+
+impl std::fmt::Display for Entry {
+    /// Display the entry like `git diff --dirstat`, without the trailing newline.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:4}.{}% {}", self.permille / 10, self.permille % 10, self.directory)
+    }
+}
+
+/// Distribute the changes in `files` to their parent directories and return all directories that see more than
+/// the configured share of changes, in the order `git diff --dirstat` would print them.
+///
+/// Note that changes of files at the top-level count towards the total, but aren't listed.
+pub fn compute(files: &[File], options: Options) -> Vec<Entry> {
+    let mut changes: Vec<(&BStr, u64)> = files
+        .iter()
+        .map(|file| {
+            let damage = match (options.mode, file.counts) {
+                (
+                    Mode::Files,
+                    Counts::Lines {
+                        insertions: 0,
+                        removals: 0,
+                    },
+                ) => 0,
+                (Mode::Files, _) => 1,
+                (Mode::Lines, Counts::Lines { insertions, removals }) => u64::from(insertions) + u64::from(removals),
+                (Mode::Lines, Counts::Binary { old_size, new_size }) => (old_size + new_size + 63) / 64,
+            };
+            (file.location.as_bstr(), damage)
+        })
+        .filter(|(_, damage)| *damage != 0)
+        .collect();
+    changes.sort_by(|a, b| a.0.cmp(b.0));
+
+    let total: u64 = changes.iter().map(|(_, damage)| damage).sum();
+    let mut out = Vec::new();
+    if total == 0 {
+        return out;
+    }
+    let mut state = State {
+        files: &changes,
+        total,
+        options,
+        out: &mut out,
+    };
+    state.gather("".into());
+    out
+}
+
+/// Write all `entries` to `out` in the format of `git diff --dirstat`.
+pub fn write(entries: &[Entry], mut out: impl io::Write) -> io::Result<()> {
+    for entry in entries {
+        writeln!(out, "{entry}")?;
+    }
+    Ok(())
+}
+
+struct State<'a, 'b> {
+    files: &'a [(&'a BStr, u64)],
+    total: u64,
+    options: Options,
+    out: &'b mut Vec<Entry>,
+}
+
+impl<'a> State<'a, '_> {
+    /// Consume all files starting with `base` and return their accumulated changes, listing `base` if
+    /// it's significant enough. This is `gather_dirstat()` in `git`.
+    fn gather(&mut self, base: &'a BStr) -> u64 {
+        let mut sum_changes = 0;
+        let mut sources = 0;
+        while let Some((name, damage)) = self.files.first().copied() {
+            if !name.starts_with(base) {
+                break;
+            }
+            let changes = match name[base.len()..].find_byte(b'/') {
+                Some(slash) => {
+                    sources += 1;
+                    self.gather(name[..base.len() + slash + 1].as_bstr())
+                }
+                None => {
+                    self.files = &self.files[1..];
+                    sources += 2;
+                    damage
+                }
+            };
+            sum_changes += changes;
+        }
+
+        // Neither the top-level, nor directories whose changes all come from a single sub-directory are listed.
+        if !base.is_empty() && sources != 1 && sum_changes != 0 {
+            let permille = (sum_changes * 1000 / self.total) as u32;
+            if permille >= self.options.permille {
+                self.out.push(Entry {
+                    directory: base.to_owned(),
+                    permille,
+                });
+                if !self.options.cumulative {
+                    return 0;
+                }
+            }
+        }
+        sum_changes
+    }
+}
@@ -0,0 +1,360 @@
+use std::io;
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::blob::platform::{prepare_diff, resource};
+
+/// The changes recorded for a single file, as needed to produce `git diff --stat` and `git diff --numstat` output.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct File {
+    /// The repository-relative location of the file after the change, or the location of a deleted file.
+    pub location: BString,
+    /// If the change was a rename or copy, the location of the source of the rewrite.
+    pub source_location: Option<BString>,
+    /// The amount of change.
+    pub counts: Counts,
+}
+
+/// The amount of changes to a file, measured either in lines or in bytes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Counts {
+    /// The file could be diffed as text.
+    Lines {
+        /// The amount of lines added to the file.
+        insertions: u32,
+        /// The amount of lines removed from the file.
+        removals: u32,
+    },
+    /// At least one side of the diff was considered binary, so only the sizes are known.
+    Binary {
+        /// The size of the previous version in bytes, or `0` if it didn't exist.
+        old_size: u64,
+        /// The size of the new version in bytes, or `0` if it doesn't exist anymore.
+        new_size: u64,
+    },
+}
+
+impl Counts {
+    /// Compute the counts from a `prepared` diff, performing a line diff if both sides are textual.
+    ///
+    /// Return `None` if the diff must be performed by an external command, whose output is opaque to us.
+    pub fn from_prepared_diff(prepared: &prepare_diff::Outcome<'_>) -> Option<Self> {
+        Some(match prepared.operation {
+            prepare_diff::Operation::InternalDiff { algorithm } => {
+                let input = prepared.interned_input();
+                let counter = crate::blob::diff(algorithm, &input, crate::blob::sink::Counter::default());
+                Counts::Lines {
+                    insertions: counter.insertions,
+                    removals: counter.removals,
+                }
+            }
+            prepare_diff::Operation::SourceOrDestinationIsBinary => Counts::Binary {
+                old_size: size_of(prepared.old.data),
+                new_size: size_of(prepared.new.data),
+            },
+            prepare_diff::Operation::ExternalCommand { .. } => return None,
+        })
+    }
+
+    /// Return `(insertions, removals)`, which are byte sizes for binary files just like in `git`.
+    pub fn added_and_removed(&self) -> (u64, u64) {
+        match *self {
+            Counts::Lines { insertions, removals } => (insertions.into(), removals.into()),
+            Counts::Binary { old_size, new_size } => (new_size, old_size),
+        }
+    }
+
+    /// Return `true` if this file was considered binary.
+    pub fn is_binary(&self) -> bool {
+        matches!(self, Counts::Binary { .. })
+    }
+}
+
+fn size_of(data: resource::Data<'_>) -> u64 {
+    match data {
+        resource::Data::Missing => 0,
+        resource::Data::Buffer(buf) => buf.len() as u64,
+        resource::Data::Binary { size } => size,
+    }
+}
+
+impl File {
+    /// Return the name to display for this file, which is `location` unless it's a rewrite.
+    ///
+    /// Rewrites are displayed like `git` does, with the common prefix and suffix of both paths
+    /// factored out, like `dir/{old => new}/file`.
+    pub fn display_name(&self) -> BString {
+        match self.source_location.as_ref() {
+            Some(source) if source != &self.location => pretty_rename(source.as_ref(), self.location.as_ref()),
+            _ => self.location.clone(),
+        }
+    }
+}
+
+/// Produce `{a => b}` with common leading directories and trailing path components factored out,
+/// just like `pprint_rename()` in `git`.
+pub fn pretty_rename(a: &BStr, b: &BStr) -> BString {
+    let mut prefix_len = 0;
+    for (pos, (ca, cb)) in a.iter().zip(b.iter()).enumerate() {
+        if ca != cb {
+            break;
+        }
+        if *ca == b'/' {
+            prefix_len = pos + 1;
+        }
+    }
+
+    // If there is a common prefix, it must end in a slash, which we allow to be seen again
+    // by the suffix search. Positions at the end of each string act like a shared terminator.
+    let lower_bound = prefix_len as isize - isize::from(prefix_len != 0);
+    let mut suffix_len = 0;
+    let (mut pa, mut pb) = (a.len() as isize, b.len() as isize);
+    while lower_bound <= pa && lower_bound <= pb {
+        let ca = a.get(pa as usize).copied().unwrap_or(0);
+        let cb = b.get(pb as usize).copied().unwrap_or(0);
+        if ca != cb {
+            break;
+        }
+        if ca == b'/' {
+            suffix_len = a.len() - pa as usize;
+        }
+        pa -= 1;
+        pb -= 1;
+    }
+
+    let a_mid = a.len().saturating_sub(prefix_len + suffix_len);
+    let b_mid = b.len().saturating_sub(prefix_len + suffix_len);
+    let mut out = BString::default();
+    let has_common = prefix_len + suffix_len != 0;
+    if has_common {
+        out.push_str(&a[..prefix_len]);
+        out.push_byte(b'{');
+    }
+    out.push_str(&a[prefix_len..prefix_len + a_mid]);
+    out.push_str(" => ");
+    out.push_str(&b[prefix_len..prefix_len + b_mid]);
+    if has_common {
+        out.push_byte(b'}');
+        out.push_str(&a[a.len() - suffix_len..]);
+    }
+    out
+}
+
+/// Aggregated information over all files, as displayed by `git diff --shortstat`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Summary {
+    /// The amount of files with changes.
+    pub files_changed: usize,
+    /// The total amount of lines inserted in text files.
+    pub insertions: u64,
+    /// The total amount of lines removed in text files.
+    pub removals: u64,
+}
+
+impl Summary {
+    /// Aggregate the counts of all `files`, ignoring the byte-sizes of binary files.
+    pub fn from_files<'a>(files: impl IntoIterator<Item = &'a File>) -> Self {
+        files.into_iter().fold(Summary::default(), |mut acc, file| {
+            acc.files_changed += 1;
+            if let Counts::Lines { insertions, removals } = file.counts {
+                acc.insertions += u64::from(insertions);
+                acc.removals += u64::from(removals);
+            }
+            acc
+        })
+    }
+}
+
+impl std::fmt::Display for Summary {
+    /// Display ourselves like `git diff --shortstat`, without the trailing newline.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.files_changed == 0 {
+            return f.write_str(" 0 files changed");
+        }
+        let plural = |n: u64| if n == 1 { "" } else { "s" };
+        write!(
+            f,
+            " {} file{} changed",
+            self.files_changed,
+            plural(self.files_changed as u64)
+        )?;
+        if self.insertions != 0 || self.removals == 0 {
+            write!(f, ", {} insertion{}(+)", self.insertions, plural(self.insertions))?;
+        }
+        if self.removals != 0 || self.insertions == 0 {
+            write!(f, ", {} deletion{}(-)", self.removals, plural(self.removals))?;
+        }
+        Ok(())
+    }
+}
+
+/// Options for use in [`write_stat()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The total width of the output, like `--stat=<width>`, defaulting to 80 columns.
+    pub width: usize,
+    /// The maximum width of the name portion, like `--stat-name-width`.
+    pub name_width: Option<usize>,
+    /// The maximum width of the graph portion, like `--stat-graph-width` or `diff.statGraphWidth`.
+    pub graph_width: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            width: 80,
+            name_width: None,
+            graph_width: None,
+        }
+    }
+}
+
+/// Write `files` to `out` in the format of `git diff --stat`, followed by the summary line.
+///
+/// Names are not quoted, and their width is assumed to be their amount of characters.
+pub fn write_stat(files: &[File], options: Options, mut out: impl io::Write) -> io::Result<()> {
+    let names: Vec<_> = files.iter().map(File::display_name).collect();
+    let mut max_len = 0;
+    let mut max_change = 0;
+    let mut number_width = 0;
+    let mut bin_width = 0;
+    for (file, name) in files.iter().zip(&names) {
+        max_len = max_len.max(char_width(name.as_ref()));
+        match file.counts {
+            Counts::Binary { old_size, new_size } => {
+                bin_width = bin_width.max(14 + decimal_width(new_size) + decimal_width(old_size));
+                number_width = 3;
+            }
+            Counts::Lines { insertions, removals } => {
+                max_change = max_change.max(u64::from(insertions) + u64::from(removals));
+            }
+        }
+    }
+
+    // The layout algorithm is the one of `show_stats()` in `git`.
+    let mut width = options.width;
+    number_width = number_width.max(decimal_width(max_change));
+    if width < 16 + 6 + number_width {
+        width = 16 + 6 + number_width;
+    }
+    let mut graph_width = if max_change as usize + 4 > bin_width {
+        max_change as usize
+    } else {
+        bin_width - 4
+    };
+    if let Some(limit) = options.graph_width.filter(|w| *w != 0 && *w < graph_width) {
+        graph_width = limit;
+    }
+    let mut name_width = options.name_width.filter(|w| *w > 0 && *w < max_len).unwrap_or(max_len);
+    if name_width + number_width + 6 + graph_width > width {
+        let graph_limit = (width * 3 / 8).saturating_sub(number_width + 6);
+        if graph_width > graph_limit {
+            graph_width = graph_limit.max(6);
+        }
+        if let Some(limit) = options.graph_width.filter(|w| *w != 0 && graph_width > *w) {
+            graph_width = limit;
+        }
+        let name_limit = width.saturating_sub(number_width + 6 + graph_width);
+        if name_width > name_limit {
+            name_width = name_limit;
+        } else {
+            graph_width = width.saturating_sub(number_width + 6 + name_width);
+        }
+    }
+
+    for (file, name) in files.iter().zip(&names) {
+        let (prefix, name) = scale_name(name.as_ref(), name_width);
+        let padding = name_width.saturating_sub(prefix.len() + char_width(name));
+        write!(out, " {prefix}{name}{:padding$} | ", "", name = name.as_bstr())?;
+        match file.counts {
+            Counts::Binary { old_size, new_size } => {
+                write!(out, "{:>number_width$}", "Bin")?;
+                if old_size != 0 || new_size != 0 {
+                    write!(out, " {old_size} -> {new_size} bytes")?;
+                }
+            }
+            Counts::Lines { insertions, removals } => {
+                let (added, removed) = (u64::from(insertions), u64::from(removals));
+                let (mut add, mut del) = (added, removed);
+                if graph_width as u64 <= max_change {
+                    let mut total = scale_linear(add + del, graph_width as u64, max_change);
+                    if total < 2 && add != 0 && del != 0 {
+                        total = 2;
+                    }
+                    if add < del {
+                        add = scale_linear(add, graph_width as u64, max_change);
+                        del = total - add;
+                    } else {
+                        del = scale_linear(del, graph_width as u64, max_change);
+                        add = total - del;
+                    }
+                }
+                write!(out, "{:>number_width$}", added + removed)?;
+                if added + removed != 0 {
+                    out.write_all(b" ")?;
+                }
+                out.write_all("+".repeat(add as usize).as_bytes())?;
+                out.write_all("-".repeat(del as usize).as_bytes())?;
+            }
+        }
+        out.write_all(b"\n")?;
+    }
+    writeln!(out, "{}", Summary::from_files(files))
+}
+
+/// Write `files` to `out` in the format of `git diff --numstat`, where binary files have `-` instead of line counts.
+pub fn write_numstat(files: &[File], mut out: impl io::Write) -> io::Result<()> {
+    for file in files {
+        match file.counts {
+            Counts::Lines { insertions, removals } => write!(out, "{insertions}\t{removals}\t")?,
+            Counts::Binary { .. } => out.write_all(b"-\t-\t")?,
+        }
+        out.write_all(file.display_name().as_slice())?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+fn scale_linear(it: u64, width: u64, max_change: u64) -> u64 {
+    if it == 0 {
+        return 0;
+    }
+    // Make sure that at least one '+' or '-' is printed if there is any change by scaling
+    // as if the allotted width was one column shorter, and then adding one.
+    1 + (it * (width.saturating_sub(1)) / max_change)
+}
+
+/// Shorten `name` to fit into `width` by cutting it at the front and continuing at the next slash, returning
+/// the prefix to display and the remaining name.
+fn scale_name(name: &BStr, width: usize) -> (&'static str, &BStr) {
+    let name_len = char_width(name);
+    if name_len <= width {
+        return ("", name);
+    }
+    let len = width.saturating_sub(3);
+    let skip = name
+        .char_indices()
+        .nth(name_len - len)
+        .map_or(name.len(), |(start, _, _)| start);
+    let mut name = &name[skip..];
+    if let Some(slash) = name.find_byte(b'/') {
+        name = &name[slash..];
+    }
+    ("...", name.as_bstr())
+}
+
+fn char_width(name: &BStr) -> usize {
+    name.chars().count()
+}
+
+fn decimal_width(mut n: u64) -> usize {
+    let mut width = 1;
+    while n >= 10 {
+        n /= 10;
+        width += 1;
+    }
+    width
+}
+
+///
+pub mod dirstat;
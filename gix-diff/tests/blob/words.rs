@@ -0,0 +1,86 @@
+use gix_diff::blob::{
+    words::{diff, write, Change, Mode, Tokenizer},
+    Algorithm,
+};
+use gix_object::bstr::ByteSlice;
+
+fn word_diff(old: &str, new: &str, tokenizer: &Tokenizer, mode: Mode) -> crate::Result<String> {
+    let mut out = Vec::new();
+    write(
+        Algorithm::Histogram,
+        old.as_bytes(),
+        new.as_bytes(),
+        tokenizer,
+        mode,
+        &mut out,
+    )?;
+    Ok(out.to_str()?.to_owned())
+}
+
+#[test]
+fn plain_matches_git() -> crate::Result {
+    let actual = word_diff(
+        "a b c\nline two here\n",
+        "a c\nline 2 here now\n",
+        &Tokenizer::Whitespace,
+        Mode::Plain,
+    )?;
+    assert_eq!(actual, "a[-b-] c\nline [-two-]{+2+} here {+now+}\n");
+    Ok(())
+}
+
+#[test]
+fn porcelain_matches_git() -> crate::Result {
+    let actual = word_diff(
+        "a b c\nline two here\n",
+        "a c\nline 2 here now\n",
+        &Tokenizer::Whitespace,
+        Mode::Porcelain,
+    )?;
+    assert_eq!(actual, " a\n-b\n  c\n~\n line \n-two\n+2\n  here \n+now\n~\n");
+
+    let actual = word_diff(
+        "foo(bar, baz);\nkeep\n",
+        "foo(bar, qux);\nkeep\nadded line\n",
+        &Tokenizer::Whitespace,
+        Mode::Porcelain,
+    )?;
+    assert_eq!(actual, " foo(bar, \n-baz);\n+qux);\n~\n keep\n~\n+added line\n~\n");
+    Ok(())
+}
+
+#[test]
+fn changes_refer_to_old_and_new_buffers() {
+    let (old, new) = ("one two", "one three");
+    let mut changes = Vec::new();
+    diff(
+        Algorithm::Myers,
+        old.as_bytes(),
+        new.as_bytes(),
+        &Tokenizer::Whitespace,
+        |change| changes.push(change),
+    );
+    assert_eq!(
+        changes,
+        [Change::Unchanged(0..4), Change::Removed(4..7), Change::Added(4..9)]
+    );
+}
+
+#[test]
+fn word_regex_matches_git() -> crate::Result {
+    let tokenizer = Tokenizer::from_word_regex("[a-z]+|[^[:space:]]")?;
+    let actual = word_diff(
+        "foo(bar, baz);\nkeep\n",
+        "foo(bar, qux);\nkeep\nadded line\n",
+        &tokenizer,
+        Mode::Plain,
+    )?;
+    assert_eq!(actual, "foo(bar, [-baz-]{+qux+});\nkeep\n{+added line+}\n");
+
+    let actual = word_diff("a b c\n", "a c\n", &Tokenizer::from_word_regex(".")?, Mode::Plain)?;
+    assert_eq!(
+        actual, "a [-b -]c\n",
+        "whitespace matched by the regex is part of words"
+    );
+    Ok(())
+}
@@ -6,6 +6,7 @@ fn hex_to_id(hex: &str) -> gix_hash::ObjectId {
 
 mod blob;
 mod rewrites;
+mod stat;
 mod tree;
 
 mod util {
@@ -0,0 +1,168 @@
+use gix_diff::stat::{dirstat, pretty_rename, write_numstat, write_stat, Counts, File, Options, Summary};
+use gix_object::bstr::ByteSlice;
+
+fn lines(location: &str, insertions: u32, removals: u32) -> File {
+    File {
+        location: location.into(),
+        source_location: None,
+        counts: Counts::Lines { insertions, removals },
+    }
+}
+
+/// Mirrors a `git diff --stat` run on a repository with a binary file, a rename and changes in nested directories.
+fn fixture() -> Vec<File> {
+    vec![
+        File {
+            location: "b.bin".into(),
+            source_location: None,
+            counts: Counts::Binary {
+                old_size: 4,
+                new_size: 8,
+            },
+        },
+        lines("docs/long-file-name.md", 100, 29),
+        File {
+            source_location: Some("old/dir/file".into()),
+            ..lines("old/dir/renamed", 0, 0)
+        },
+        lines("src/a/one", 4, 4),
+        lines("top", 1, 0),
+    ]
+}
+
+#[test]
+fn stat_matches_git() -> crate::Result {
+    let mut out = Vec::new();
+    write_stat(&fixture(), Options::default(), &mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        " b.bin                     | Bin 4 -> 8 bytes
+ docs/long-file-name.md    | 129 +++++++++++++++++++++++++++++++++++-----------
+ old/dir/{file => renamed} |   0
+ src/a/one                 |   8 +--
+ top                       |   1 +
+ 5 files changed, 105 insertions(+), 33 deletions(-)
+"
+    );
+
+    out.clear();
+    write_stat(
+        &fixture(),
+        Options {
+            width: 40,
+            ..Default::default()
+        },
+        &mut out,
+    )?;
+    assert_eq!(
+        out.as_bstr(),
+        " b.bin                     | Bin 4 -> 8 bytes
+ docs/long-file-name.md    | 129 ++++--
+ old/dir/{file => renamed} |   0
+ src/a/one                 |   8 +-
+ top                       |   1 +
+ 5 files changed, 105 insertions(+), 33 deletions(-)
+",
+        "the graph is scaled down to make space for the name"
+    );
+    Ok(())
+}
+
+#[test]
+fn numstat_matches_git() -> crate::Result {
+    let mut out = Vec::new();
+    write_numstat(&fixture(), &mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        "-\t-\tb.bin
+100\t29\tdocs/long-file-name.md
+0\t0\told/dir/{file => renamed}
+4\t4\tsrc/a/one
+1\t0\ttop
+"
+    );
+    Ok(())
+}
+
+#[test]
+fn summary() {
+    assert_eq!(
+        Summary::from_files(&fixture()).to_string(),
+        " 5 files changed, 105 insertions(+), 33 deletions(-)"
+    );
+    assert_eq!(
+        Summary::from_files(&[lines("a", 1, 0)]).to_string(),
+        " 1 file changed, 1 insertion(+)"
+    );
+    assert_eq!(
+        Summary::from_files(&[lines("a", 0, 2)]).to_string(),
+        " 1 file changed, 2 deletions(-)"
+    );
+    assert_eq!(
+        Summary::from_files(&[lines("a", 0, 0)]).to_string(),
+        " 1 file changed, 0 insertions(+), 0 deletions(-)"
+    );
+    assert_eq!(Summary::default().to_string(), " 0 files changed");
+}
+
+#[test]
+fn rename_display() {
+    for (a, b, expected) in [
+        ("old/dir/file", "old/dir/renamed", "old/dir/{file => renamed}"),
+        ("a/b/c", "a/x/c", "a/{b => x}/c"),
+        ("a/b/c", "d/e/f", "a/b/c => d/e/f"),
+        ("dir/file", "file", "dir/file => file"),
+        ("file", "dir/file", "file => dir/file"),
+        ("a", "b", "a => b"),
+    ] {
+        assert_eq!(pretty_rename(a.into(), b.into()), expected, "{a} -> {b}");
+    }
+}
+
+#[test]
+fn dirstat_matches_git() -> crate::Result {
+    let entries = dirstat::compute(&fixture(), dirstat::Options::default());
+    let mut out = Vec::new();
+    dirstat::write(&entries, &mut out)?;
+    assert_eq!(out.as_bstr(), "  92.8% docs/\n   5.7% src/a/\n");
+
+    let entries = dirstat::compute(
+        &fixture(),
+        dirstat::Options {
+            mode: dirstat::Mode::Files,
+            permille: 0,
+            ..Default::default()
+        },
+    );
+    out.clear();
+    dirstat::write(&entries, &mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        "  25.0% docs/\n  25.0% src/a/\n",
+        "files without content changes are ignored"
+    );
+    Ok(())
+}
+
+#[test]
+fn dirstat_cumulative() {
+    let files = [lines("a/b/1", 5, 0), lines("a/b/2", 5, 0), lines("a/c", 10, 0)];
+    let entries = dirstat::compute(&files, dirstat::Options::default());
+    assert_eq!(
+        entries.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        ["  50.0% a/b/", "  50.0% a/"],
+        "changes reported for sub-directories don't count towards parents"
+    );
+
+    let entries = dirstat::compute(
+        &files,
+        dirstat::Options {
+            cumulative: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        entries.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        ["  50.0% a/b/", " 100.0% a/"]
+    );
+}
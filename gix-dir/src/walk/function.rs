@@ -3,7 +3,9 @@ use std::path::{Path, PathBuf};
 
 use bstr::{BStr, BString, ByteSlice};
 
-use crate::walk::{classify, readdir, Action, Context, Delegate, Error, ForDeletionMode, Options, Outcome};
+use crate::walk::{
+    classify, readdir, untracked_cache, Action, Context, Delegate, Error, ForDeletionMode, Options, Outcome,
+};
 use crate::{entry, EntryRef};
 
 /// A function to perform a git-style, unsorted, directory walk.
@@ -102,6 +104,7 @@ pub fn walk(
 
     let mut state = readdir::State::new(worktree_root, ctx.current_dir, options.for_deletion.is_some());
     let may_collapse = root != worktree_root && state.may_collapse(&current);
+    let cache_dir = untracked_cache::prepare(root == worktree_root, &mut ctx, options)?;
     let (action, _) = readdir::recursive(
         may_collapse,
         &mut current,
@@ -112,6 +115,7 @@ pub fn walk(
         delegate,
         &mut out,
         &mut state,
+        cache_dir,
     )?;
     if action != Action::Cancel {
         state.emit_remaining(may_collapse, options, &mut out, delegate);
@@ -263,6 +263,31 @@ pub struct Context<'a> {
     ///
     /// If the `traversal_root` is not in the `worktree_root` passed to [walk()](crate::walk()).
     pub explicit_traversal_root: Option<&'a std::path::Path>,
+    /// If not `None`, the untracked cache to consult for directories whose content didn't change since it was last recorded,
+    /// which avoids reading them, and to update with the content of all directories that had to be read.
+    ///
+    /// It's only used if the walk is compatible with what the cache stores, i.e. if it starts at the worktree root without
+    /// pathspecs, and if neither ignored, tracked, pruned nor empty directory entries are emitted, and if it's not for deletion.
+    /// If [`Options::emit_untracked`] doesn't match the `dir_flags` of the cache, it will be reset.
+    pub untracked_cache: Option<UntrackedCache<'a>>,
+}
+
+/// The untracked cache and all information needed to validate it, for use in [Context::untracked_cache].
+///
+/// Note that it's the responsibility of the caller to assure that [`identifier`](gix_index::extension::UntrackedCache::identifier)
+/// matches the current worktree, or to reset the cache otherwise.
+pub struct UntrackedCache<'a> {
+    /// The cache to use and update, typically obtained from the index.
+    pub cache: &'a mut gix_index::extension::UntrackedCache,
+    /// The path to the `$GIT_DIR/info/exclude` file, whose change invalidates the whole cache.
+    pub info_exclude: Option<&'a std::path::Path>,
+    /// The path to the file configured in `core.excludesFile`, whose change invalidates the whole cache.
+    pub excludes_file: Option<&'a std::path::Path>,
+    /// Control how the stat information of directories is compared to see if they changed.
+    pub stat_options: gix_index::entry::stat::Options,
+    /// If `true`, all changes reported by the filesystem monitor since the cache was last written were used to invalidate it,
+    /// so directories that are still valid can be trusted without checking them on disk.
+    pub use_fsmonitor: bool,
 }
 
 /// Additional information collected as outcome of [`walk()`](function::walk()).
@@ -306,8 +331,11 @@ pub enum Error {
     DirEntryFileType { path: PathBuf, source: std::io::Error },
     #[error("Could not obtain symlink metadata on '{}'", path.display())]
     SymlinkMetadata { path: PathBuf, source: std::io::Error },
+    #[error("Could not read '{}' to validate the untracked cache", path.display())]
+    UntrackedCacheValidation { path: PathBuf, source: std::io::Error },
 }
 
 mod classify;
 pub(crate) mod function;
 mod readdir;
+mod untracked_cache;
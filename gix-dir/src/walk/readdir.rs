@@ -7,7 +7,8 @@ use crate::entry::{PathspecMatch, Status};
 use crate::walk::function::{can_recurse, emit_entry};
 use crate::walk::EmissionMode::CollapseDirectory;
 use crate::walk::{
-    classify, Action, CollapsedEntriesEmissionMode, Context, Delegate, Error, ForDeletionMode, Options, Outcome,
+    classify, untracked_cache, Action, CollapsedEntriesEmissionMode, Context, Delegate, Error, ForDeletionMode,
+    Options, Outcome,
 };
 use crate::{entry, walk, Entry, EntryRef};
 
@@ -25,46 +26,141 @@ pub(super) fn recursive(
     delegate: &mut dyn Delegate,
     out: &mut Outcome,
     state: &mut State,
+    cache_dir: Option<usize>,
 ) -> Result<(Action, bool), Error> {
     if ctx.should_interrupt.map_or(false, |flag| flag.load(Ordering::Relaxed)) {
         return Err(Error::Interrupted);
     }
-    out.read_dir_calls += 1;
-    let entries = gix_fs::read_dir(current, opts.precompose_unicode).map_err(|err| Error::ReadDir {
-        path: current.to_owned(),
-        source: err,
-    })?;
+    let (cached_names, mut recorder) = match cache_dir {
+        Some(index) => match untracked_cache::lookup(index, current, current_bstr.as_bstr(), ctx)? {
+            untracked_cache::Lookup::Valid(names) => (Some(names), None),
+            untracked_cache::Lookup::Invalid(recorder) => (None, Some(recorder)),
+        },
+        None => (None, None),
+    };
 
     let mut num_entries = 0;
     let mark = state.mark(may_collapse);
     let mut prevent_collapse = false;
-    for entry in entries {
-        let entry = entry.map_err(|err| Error::DirEntry {
-            parent_directory: current.to_owned(),
-            source: err,
-        })?;
-        // Important to count right away, otherwise the directory could be seen as empty even though it's not.
-        // That is, this should be independent of the kind.
-        num_entries += 1;
+    let mut dir = Directory {
+        current,
+        current_bstr,
+        ctx,
+        opts,
+        delegate,
+        out,
+        state,
+        cache_dir,
+        recorder: recorder.as_mut(),
+        prevent_collapse: &mut prevent_collapse,
+    };
+    match cached_names {
+        Some(names) => {
+            for name in names {
+                // Important to count right away, otherwise the directory could be seen as empty even though it's not.
+                num_entries += 1;
+                let path = dir.current.join(gix_path::from_bstr(name.as_bstr()));
+                let action = dir.entry(name.as_bstr(), move || {
+                    path.symlink_metadata().ok().map(|meta| meta.file_type().into())
+                })?;
+                if action != Action::Continue {
+                    return Ok((action, prevent_collapse));
+                }
+            }
+        }
+        None => {
+            dir.out.read_dir_calls += 1;
+            let entries = gix_fs::read_dir(dir.current, opts.precompose_unicode).map_err(|err| Error::ReadDir {
+                path: dir.current.to_owned(),
+                source: err,
+            })?;
+            for entry in entries {
+                let entry = entry.map_err(|err| Error::DirEntry {
+                    parent_directory: dir.current.to_owned(),
+                    source: err,
+                })?;
+                // Important to count right away, otherwise the directory could be seen as empty even though it's not.
+                // That is, this should be independent of the kind.
+                num_entries += 1;
 
+                let file_name = entry.file_name();
+                let name =
+                    gix_path::try_os_str_into_bstr(Cow::Borrowed(file_name.as_ref())).expect("no illformed UTF-8");
+                let action = dir.entry(name.as_ref(), || entry.file_type().ok().map(Into::into))?;
+                if action != Action::Continue {
+                    return Ok((action, prevent_collapse));
+                }
+            }
+        }
+    }
+
+    if let Some(recorder) = recorder {
+        recorder.finish(ctx);
+    }
+    let res = mark.reduce_held_entries(
+        num_entries,
+        state,
+        &mut prevent_collapse,
+        current,
+        current_bstr.as_bstr(),
+        current_info,
+        opts,
+        out,
+        ctx,
+        delegate,
+    );
+    Ok((res, prevent_collapse))
+}
+
+/// Everything needed to handle the entries of a single directory.
+struct Directory<'a, 'b, 'c> {
+    current: &'a mut PathBuf,
+    current_bstr: &'a mut BString,
+    ctx: &'a mut Context<'b>,
+    opts: Options<'c>,
+    delegate: &'a mut dyn Delegate,
+    out: &'a mut Outcome,
+    state: &'a mut State,
+    cache_dir: Option<usize>,
+    recorder: Option<&'a mut untracked_cache::Recorder>,
+    prevent_collapse: &'a mut bool,
+}
+
+impl Directory<'_, '_, '_> {
+    /// Classify the entry `name` in this directory and recurse into it or emit it. Use `on_demand_disk_kind` to learn
+    /// its kind on disk if needed.
+    fn entry(
+        &mut self,
+        name: &BStr,
+        on_demand_disk_kind: impl FnOnce() -> Option<entry::Kind>,
+    ) -> Result<Action, Error> {
+        let Directory {
+            current,
+            current_bstr,
+            ctx,
+            opts,
+            delegate,
+            out,
+            state,
+            cache_dir,
+            recorder,
+            prevent_collapse,
+        } = self;
+        let opts = *opts;
         let prev_len = current_bstr.len();
         if prev_len != 0 {
             current_bstr.push(b'/');
         }
-        let file_name = entry.file_name();
-        current_bstr.extend_from_slice(
-            gix_path::try_os_str_into_bstr(Cow::Borrowed(file_name.as_ref()))
-                .expect("no illformed UTF-8")
-                .as_ref(),
-        );
-        current.push(file_name);
+        let filename_start_idx = if prev_len == 0 { 0 } else { prev_len + 1 };
+        current_bstr.extend_from_slice(name);
+        current.push(gix_path::from_bstr(name));
 
         let mut info = classify::path(
             current,
             current_bstr,
-            if prev_len == 0 { 0 } else { prev_len + 1 },
+            filename_start_idx,
             None,
-            || entry.file_type().ok().map(Into::into),
+            on_demand_disk_kind,
             opts,
             ctx,
         )?;
@@ -74,8 +170,9 @@ pub(super) fn recursive(
             info,
             opts.for_deletion,
             false, /* is root */
-            delegate,
+            &mut **delegate,
         ) {
+            let subdir_cache_dir = cache_dir.map(|parent| untracked_cache::sub_directory(parent, name, ctx));
             let subdir_may_collapse = state.may_collapse(current);
             let (action, subdir_prevent_collapse) = recursive(
                 subdir_may_collapse,
@@ -84,13 +181,26 @@ pub(super) fn recursive(
                 info,
                 ctx,
                 opts,
-                delegate,
+                &mut **delegate,
                 out,
                 state,
+                subdir_cache_dir,
             )?;
-            prevent_collapse |= subdir_prevent_collapse;
+            **prevent_collapse |= subdir_prevent_collapse;
             if action != Action::Continue {
-                return Ok((action, prevent_collapse));
+                return Ok(action);
+            }
+            if let Some((recorder, subdir_cache_dir)) = recorder.as_deref_mut().zip(subdir_cache_dir) {
+                recorder.push_sub_directory(subdir_cache_dir);
+                let collapsed_into_untracked = opts.emit_untracked == CollapseDirectory
+                    && state.on_hold.last().map_or(false, |e| {
+                        e.rela_path == **current_bstr
+                            && e.status == Status::Untracked
+                            && e.property != Some(entry::Property::EmptyDirectory)
+                    });
+                if collapsed_into_untracked {
+                    recorder.push_untracked(name, true);
+                }
             }
         } else {
             if opts.for_deletion == Some(ForDeletionMode::IgnoredDirectoriesCanHideNestedRepositories)
@@ -106,30 +216,33 @@ pub(super) fn recursive(
                     ctx.git_dir_realpath,
                 );
             }
+            if let Some(recorder) = recorder.as_deref_mut() {
+                match info.status {
+                    Status::Untracked => recorder.push_untracked(name, info.disk_kind.map_or(false, |k| k.is_dir())),
+                    Status::Tracked if ctx.index.entry_index_by_path(current_bstr.as_bstr()).is_err() => {
+                        recorder.mark_incomplete();
+                    }
+                    Status::Tracked | Status::Pruned | Status::Ignored(_) => {}
+                }
+            }
             if !state.held_for_directory_collapse(current_bstr.as_bstr(), info, &opts) {
-                let action = emit_entry(Cow::Borrowed(current_bstr.as_bstr()), info, None, opts, out, delegate);
+                let action = emit_entry(
+                    Cow::Borrowed(current_bstr.as_bstr()),
+                    info,
+                    None,
+                    opts,
+                    out,
+                    &mut **delegate,
+                );
                 if action != Action::Continue {
-                    return Ok((action, prevent_collapse));
+                    return Ok(action);
                 }
             }
         }
         current_bstr.truncate(prev_len);
         current.pop();
+        Ok(Action::Continue)
     }
-
-    let res = mark.reduce_held_entries(
-        num_entries,
-        state,
-        &mut prevent_collapse,
-        current,
-        current_bstr.as_bstr(),
-        current_info,
-        opts,
-        out,
-        ctx,
-        delegate,
-    );
-    Ok((res, prevent_collapse))
 }
 
 pub(super) struct State {
@@ -0,0 +1,256 @@
+use std::path::Path;
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_index::extension::untracked_cache::{dir_flags, Directory, OidStat};
+use gix_index::hash::ObjectId;
+
+use crate::walk::{Context, EmissionMode, Error, Options};
+
+/// The name of the per-directory exclude files we support.
+const EXCLUDE_FILENAME: &str = ".gitignore";
+
+/// Return the index of the root directory in the untracked cache of `ctx` if the cache can be used for a walk with `opts`,
+/// after resetting or invalidating it according to what changed since it was last written.
+///
+/// `is_worktree_root` is `true` if the traversal starts at the root of the worktree.
+pub(super) fn prepare(
+    is_worktree_root: bool,
+    ctx: &mut Context<'_>,
+    opts: Options<'_>,
+) -> Result<Option<usize>, Error> {
+    let Some(uc) = ctx.untracked_cache.as_mut() else {
+        return Ok(None);
+    };
+    if !is_worktree_root
+        || ctx.pathspec.patterns().len() != 0
+        || ctx.excludes.is_none()
+        || ctx.index.is_sparse()
+        || opts.for_deletion.is_some()
+        || opts.emit_ignored.is_some()
+        || opts.emit_tracked
+        || opts.emit_pruned
+        || opts.emit_empty_directories
+    {
+        return Ok(None);
+    }
+
+    let flags = match opts.emit_untracked {
+        EmissionMode::CollapseDirectory => dir_flags::SHOW_OTHER_DIRECTORIES | dir_flags::HIDE_EMPTY_DIRECTORIES,
+        EmissionMode::Matching => 0,
+    };
+    let cache = &mut *uc.cache;
+    if cache.dir_flags != flags || cache.exclude_filename_per_dir != EXCLUDE_FILENAME {
+        *cache = gix_index::extension::UntrackedCache::new(std::mem::take(&mut cache.identifier), flags);
+    }
+
+    let object_hash = ctx.index.object_hash();
+    let info_exclude = oid_stat(uc.info_exclude, object_hash)?;
+    let excludes_file = oid_stat(uc.excludes_file, object_hash)?;
+    let id = |oid_stat: &Option<OidStat>| oid_stat.as_ref().map(|s| s.id);
+    if !cache.directories.is_empty()
+        && (id(&cache.info_exclude) != id(&info_exclude) || id(&cache.excludes_file) != id(&excludes_file))
+    {
+        cache.invalidate_directory(0);
+    }
+    cache.info_exclude = info_exclude;
+    cache.excludes_file = excludes_file;
+
+    if cache.directories.is_empty() {
+        cache.directories.push(new_directory(BString::default()));
+    }
+    Ok(Some(0))
+}
+
+/// What we know about a directory from the untracked cache.
+pub(super) enum Lookup {
+    /// The directory didn't change since it was recorded, and these are the names of all entries that are relevant to the walk.
+    Valid(Vec<BString>),
+    /// The directory has to be read, and what's seen should be recorded.
+    Invalid(Recorder),
+}
+
+/// Collect everything we need to know about a directory to be able to skip reading it next time.
+pub(super) struct Recorder {
+    dir: usize,
+    stat: Option<gix_index::entry::Stat>,
+    exclude_file_oid: Option<ObjectId>,
+    untracked_entries: Vec<BString>,
+    sub_directories: Vec<usize>,
+    is_complete: bool,
+}
+
+impl Recorder {
+    /// Record the entry with `name` as untracked, as directory if `is_dir` is `true`.
+    pub(super) fn push_untracked(&mut self, name: &BStr, is_dir: bool) {
+        let mut name = name.to_owned();
+        if is_dir {
+            name.push(b'/');
+        }
+        self.untracked_entries.push(name);
+    }
+
+    /// Record the sub-directory at `index` in the cache as one we traversed into.
+    pub(super) fn push_sub_directory(&mut self, index: usize) {
+        self.sub_directories.push(index);
+    }
+
+    /// Indicate that we saw an entry that can't be recreated from the cache and the index, so the directory shouldn't be
+    /// marked as valid.
+    pub(super) fn mark_incomplete(&mut self) {
+        self.is_complete = false;
+    }
+
+    /// Write what we recorded back into the cache.
+    pub(super) fn finish(mut self, ctx: &mut Context<'_>) {
+        let cache = &mut *ctx
+            .untracked_cache
+            .as_mut()
+            .expect("BUG: recorders are only created with a cache")
+            .cache;
+        self.sub_directories
+            .sort_by(|a, b| cache.directories[*a].name.cmp(&cache.directories[*b].name));
+        let dir = &mut cache.directories[self.dir];
+        dir.stat = self.stat.filter(|_| self.is_complete);
+        dir.exclude_file_oid = self.exclude_file_oid;
+        dir.untracked_entries = self.untracked_entries;
+        dir.sub_directories = self.sub_directories;
+        dir.check_only = false;
+    }
+}
+
+/// Find out if the directory at `index` in the cache, located at `path` and `rela_path`, is still valid, and if so,
+/// return the names of its entries.
+pub(super) fn lookup(index: usize, path: &Path, rela_path: &BStr, ctx: &mut Context<'_>) -> Result<Lookup, Error> {
+    let uc = ctx
+        .untracked_cache
+        .as_mut()
+        .expect("BUG: directory indices are only passed with a cache");
+    let cached = &uc.cache.directories[index];
+    let is_valid_by_fsmonitor = uc.use_fsmonitor && !cached.check_only && cached.stat.is_some();
+    let (stat, exclude_file_oid) = if is_valid_by_fsmonitor {
+        (cached.stat, cached.exclude_file_oid)
+    } else {
+        let stat = gix_index::fs::Metadata::from_path_no_follow(path)
+            .ok()
+            .and_then(|meta| gix_index::entry::Stat::from_fs(&meta).ok());
+        let exclude_file = path.join(EXCLUDE_FILENAME);
+        let exclude_file_oid = oid_stat(Some(&exclude_file), ctx.index.object_hash())?.map(|s| s.id);
+        (stat, exclude_file_oid)
+    };
+
+    let cache = &mut *uc.cache;
+    if cache.directories[index].exclude_file_oid != exclude_file_oid {
+        cache.invalidate_directory(index);
+    }
+    let dir = &cache.directories[index];
+    let is_valid = is_valid_by_fsmonitor
+        || (!dir.check_only
+            && match (dir.stat, stat) {
+                (Some(cached), Some(current)) => {
+                    cached.matches(&current, uc.stat_options)
+                        && !current.is_racy(ctx.index.timestamp(), uc.stat_options)
+                }
+                _ => false,
+            });
+    if is_valid {
+        let mut names: Vec<BString> = dir
+            .sub_directories
+            .iter()
+            .map(|idx| cache.directories[*idx].name.clone())
+            .chain(
+                dir.untracked_entries
+                    .iter()
+                    .map(|name| name.strip_suffix(b"/").unwrap_or(name).into()),
+            )
+            .collect();
+        let mut prefix = rela_path.to_owned();
+        if !prefix.is_empty() {
+            prefix.push(b'/');
+        }
+        for entry in ctx.index.prefixed_entries(prefix.as_bstr()).unwrap_or_default() {
+            if entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE) {
+                continue;
+            }
+            let name = &entry.path(ctx.index)[prefix.len()..];
+            if !name.contains(&b'/') {
+                names.push(name.into());
+            }
+        }
+        names.sort();
+        names.dedup();
+        if !names.is_empty() {
+            return Ok(Lookup::Valid(names));
+        }
+    }
+
+    let dir = &mut cache.directories[index];
+    dir.stat = None;
+    dir.untracked_entries.clear();
+    Ok(Lookup::Invalid(Recorder {
+        dir: index,
+        stat,
+        exclude_file_oid,
+        untracked_entries: Vec::new(),
+        sub_directories: Vec::new(),
+        is_complete: true,
+    }))
+}
+
+/// Return the index of the sub-directory `name` of the directory at `parent` in the cache, creating it if needed.
+pub(super) fn sub_directory(parent: usize, name: &BStr, ctx: &mut Context<'_>) -> usize {
+    let cache = &mut *ctx
+        .untracked_cache
+        .as_mut()
+        .expect("BUG: directory indices are only passed with a cache")
+        .cache;
+    let pos = cache.directories[parent]
+        .sub_directories
+        .binary_search_by(|idx| cache.directories[*idx].name.as_bstr().cmp(name));
+    match pos {
+        Ok(pos) => cache.directories[parent].sub_directories[pos],
+        Err(pos) => {
+            let index = cache.directories.len();
+            cache.directories.push(new_directory(name.to_owned()));
+            cache.directories[parent].sub_directories.insert(pos, index);
+            index
+        }
+    }
+}
+
+fn new_directory(name: BString) -> Directory {
+    Directory {
+        name,
+        untracked_entries: Vec::new(),
+        sub_directories: Vec::new(),
+        stat: None,
+        exclude_file_oid: None,
+        check_only: false,
+    }
+}
+
+/// Hash the file at `path` like git would for the untracked cache, or return `None` if there is no such file.
+fn oid_stat(path: Option<&Path>, object_hash: gix_index::hash::Kind) -> Result<Option<OidStat>, Error> {
+    let Some(path) = path else { return Ok(None) };
+    let mut data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(Error::UntrackedCacheValidation {
+                path: path.to_owned(),
+                source: err,
+            })
+        }
+    };
+    // Like git, hash the file as if it ended with a newline.
+    if data.last().map_or(false, |b| *b != b'\n') {
+        data.push(b'\n');
+    }
+    let stat = gix_index::fs::Metadata::from_path_no_follow(path)
+        .ok()
+        .and_then(|meta| gix_index::entry::Stat::from_fs(&meta).ok())
+        .unwrap_or_default();
+    Ok(Some(OidStat {
+        stat,
+        id: gix_object::compute_hash(object_hash, gix_object::Kind::Blob, &data),
+    }))
+}
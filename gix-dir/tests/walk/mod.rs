@@ -6,7 +6,7 @@ use std::sync::atomic::AtomicBool;
 use crate::walk_utils::{
     collect, collect_filtered, collect_filtered_with_cwd, entry, entry_dirstat, entry_nokind, entry_nomatch, entryps,
     entryps_dirstat, fixture, fixture_in, options, options_emit_all, try_collect, try_collect_filtered_opts,
-    try_collect_filtered_opts_collect, try_collect_filtered_opts_collect_with_root, EntryExt, Options,
+    try_collect_filtered_opts_collect, try_collect_filtered_opts_collect_with_root, Entries, EntryExt, Options,
 };
 use gix_dir::entry;
 use gix_dir::entry::Kind::*;
@@ -4647,3 +4647,157 @@ fn in_repo_hidden_worktree() -> crate::Result {
     }
     Ok(())
 }
+
+#[test]
+fn untracked_cache_avoids_reading_unchanged_directories() -> crate::Result {
+    let mut saved_reads = 0;
+    for name in [
+        "dir-with-tracked-file",
+        "repo-with-submodule",
+        "ignored-dir-with-nested-repository",
+        "nested-repository",
+        "only-untracked",
+        "subdir-untracked",
+        "subdir-untracked-and-ignored",
+        "expendable-and-precious",
+        "empty-and-untracked-dir",
+        "complex-empty",
+        "star-in-subdir-and-negated",
+        "with-sub-repo",
+    ] {
+        let root = fixture(name);
+        for emit_untracked in [Matching, CollapseDirectory] {
+            let opts = walk::Options {
+                emit_untracked,
+                emit_collapsed: Some(OnStatusMismatch),
+                ..options()
+            };
+            let ((expected_out, _root), expected_entries) =
+                collect(&root, None, |keep, ctx| walk(&root, ctx, opts, keep));
+
+            let mut cache = gix_index::extension::UntrackedCache::new("test", 0xff);
+            let ((out, _root), entries) = collect_with_untracked_cache(&root, opts, &mut cache)?;
+            assert_eq!(
+                entries, expected_entries,
+                "{name}: filling the cache doesn't change the result"
+            );
+            assert_eq!(out.read_dir_calls, expected_out.read_dir_calls);
+            assert_ne!(cache.dir_flags, 0xff, "the flags are adjusted to the emission mode");
+
+            let ((out, _root), entries) = collect_with_untracked_cache(&root, opts, &mut cache)?;
+            assert_eq!(
+                entries, expected_entries,
+                "{name}: using the cache doesn't change the result"
+            );
+            assert_eq!(out.returned_entries, expected_out.returned_entries);
+            assert!(out.read_dir_calls <= expected_out.read_dir_calls);
+            saved_reads += expected_out.read_dir_calls - out.read_dir_calls;
+        }
+    }
+    assert!(
+        saved_reads > 20,
+        "unchanged directories aren't read again, got {saved_reads}"
+    );
+    Ok(())
+}
+
+#[test]
+fn untracked_cache_notices_changes() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("many.sh")?;
+    let root = tmp.path().join("subdir-untracked");
+    let opts = walk::Options {
+        emit_untracked: CollapseDirectory,
+        ..options()
+    };
+    let mut cache = gix_index::extension::UntrackedCache::new("test", 0);
+    let (_out, expected_entries) = collect_with_untracked_cache(&root, opts, &mut cache)?;
+    assert_eq!(
+        cache.dir_flags,
+        gix_index::extension::untracked_cache::dir_flags::SHOW_OTHER_DIRECTORIES
+            | gix_index::extension::untracked_cache::dir_flags::HIDE_EMPTY_DIRECTORIES
+    );
+    let ((out, _root), entries) = collect_with_untracked_cache(&root, opts, &mut cache)?;
+    assert_eq!(entries, expected_entries);
+    assert_eq!(out.read_dir_calls, 0, "nothing changed, so no directory is read");
+
+    std::fs::write(root.join("d/new-file"), [])?;
+    let ((out, _root), entries) = collect_with_untracked_cache(&root, opts, &mut cache)?;
+    assert_eq!(out.read_dir_calls, 1, "only the changed directory is read");
+    assert_eq!(
+        entries,
+        [expected_entries.as_slice(), &[entry("d/new-file", Untracked, File)]].concat(),
+        "the new file is picked up"
+    );
+
+    std::fs::write(root.join("d/.gitignore"), "new-file\n")?;
+    let ((out, _root), entries) = collect_with_untracked_cache(&root, opts, &mut cache)?;
+    assert_eq!(
+        out.read_dir_calls, 2,
+        "the changed directory and all of its subdirectories are read"
+    );
+    assert_eq!(
+        entries,
+        [&[entry("d/.gitignore", Untracked, File)], expected_entries.as_slice()].concat(),
+        "exclude files are picked up, and the newly ignored file disappears"
+    );
+    Ok(())
+}
+
+#[test]
+fn untracked_cache_trusts_fsmonitor() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("many.sh")?;
+    let root = tmp.path().join("subdir-untracked");
+    let opts = walk::Options {
+        emit_untracked: CollapseDirectory,
+        ..options()
+    };
+    let mut cache = gix_index::extension::UntrackedCache::new("test", 0);
+    let (_out, expected_entries) = collect_with_untracked_cache(&root, opts, &mut cache)?;
+
+    std::fs::write(root.join("d/new-file"), [])?;
+    let ((out, _root), entries) = collect_with_untracked_cache_opts(&root, opts, &mut cache, true)?;
+    assert_eq!(
+        out.read_dir_calls, 0,
+        "directories aren't checked on disk as the fsmonitor didn't report a change"
+    );
+    assert_eq!(entries, expected_entries, "hence the new file isn't seen");
+
+    cache.invalidate_path("d/new-file".into());
+    let ((out, _root), entries) = collect_with_untracked_cache_opts(&root, opts, &mut cache, true)?;
+    assert_eq!(
+        out.read_dir_calls, 2,
+        "only the reported directory and its parent are read, as untracked directories are collapsed"
+    );
+    assert_eq!(
+        entries,
+        [expected_entries.as_slice(), &[entry("d/new-file", Untracked, File)]].concat(),
+    );
+    Ok(())
+}
+
+fn collect_with_untracked_cache(
+    root: &std::path::Path,
+    opts: walk::Options<'_>,
+    cache: &mut gix_index::extension::UntrackedCache,
+) -> Result<((walk::Outcome, std::path::PathBuf), Entries), walk::Error> {
+    collect_with_untracked_cache_opts(root, opts, cache, false)
+}
+
+fn collect_with_untracked_cache_opts(
+    root: &std::path::Path,
+    opts: walk::Options<'_>,
+    cache: &mut gix_index::extension::UntrackedCache,
+    use_fsmonitor: bool,
+) -> Result<((walk::Outcome, std::path::PathBuf), Entries), walk::Error> {
+    try_collect_filtered_opts_collect(
+        root,
+        None,
+        |keep, ctx| walk(root, ctx, opts, keep),
+        None::<&str>,
+        Options {
+            untracked_cache: Some(cache),
+            use_fsmonitor,
+            ..Default::default()
+        },
+    )
+}
@@ -280,6 +280,8 @@ pub fn try_collect_filtered_opts(
         fresh_index,
         git_dir,
         should_interrupt,
+        untracked_cache,
+        use_fsmonitor,
     }: Options<'_>,
 ) -> Result<(walk::Outcome, PathBuf), walk::Error> {
     let git_dir = worktree_root.join(git_dir.unwrap_or(".git"));
@@ -297,6 +299,8 @@ pub fn try_collect_filtered_opts(
         },
     );
     if fresh_index {
+        // a refreshed index would also have been written, making its timestamp current.
+        index.set_timestamp(std::time::SystemTime::now().into());
         index
             .entries_mut()
             .iter_mut()
@@ -355,6 +359,18 @@ pub fn try_collect_filtered_opts(
             objects: &gix_object::find::Never,
             explicit_traversal_root,
             should_interrupt,
+            untracked_cache: untracked_cache.map(|cache| walk::UntrackedCache {
+                cache,
+                info_exclude: None,
+                excludes_file: None,
+                // Be precise to not consider directories racy that were just created.
+                stat_options: gix_index::entry::stat::Options {
+                    check_stat: true,
+                    use_nsec: true,
+                    ..Default::default()
+                },
+                use_fsmonitor,
+            }),
         },
     )
 }
@@ -363,6 +379,8 @@ pub struct Options<'a> {
     pub fresh_index: bool,
     pub git_dir: Option<&'a str>,
     pub should_interrupt: Option<&'a AtomicBool>,
+    pub untracked_cache: Option<&'a mut gix_index::extension::UntrackedCache>,
+    pub use_fsmonitor: bool,
 }
 
 impl<'a> Options<'a> {
@@ -380,8 +398,10 @@ impl<'a> Default for Options<'a> {
             fresh_index: true,
             git_dir: None,
             should_interrupt: None,
+            untracked_cache: None,
+            use_fsmonitor: false,
         }
     }
 }
 
-type Entries = Vec<(Entry, Option<entry::Status>)>;
+pub type Entries = Vec<(Entry, Option<entry::Status>)>;
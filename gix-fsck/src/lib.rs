@@ -1,6 +1,9 @@
 //! A library for performing object database integrity and connectivity checks
 #![deny(rust_2018_idioms, unsafe_code, missing_docs)]
 
+pub mod message;
+pub mod object;
+
 use std::collections::VecDeque;
 
 use gix_hash::ObjectId;
@@ -44,18 +47,22 @@ where
     ///
     /// Walk the trees and blobs referenced by the commit and verify they exist in the ODB.
     /// Any objects previously encountered by this instance will be skipped silently.
-    /// Any referenced blobs that are not present in the ODB will result in a call to the  `missing_cb`.
-    /// Missing commits or trees will cause an error to be returned.
-    ///     - TODO: consider how to handle a missing commit (invoke `missing_cb`, or possibly return a Result?)
+    /// Any referenced objects that are not present in the ODB, including the commit itself, will result in a call to
+    /// the `missing_cb`.
+    /// Commits that can't be decoded will cause an error to be returned.
     pub fn check_commit(&mut self, oid: &ObjectId) -> Result<(), gix_object::find::existing_object::Error> {
         // Attempt to insert the commit ID in the set, and if already present, return immediately
         if !self.seen.insert(*oid) {
             return Ok(());
         }
         // Obtain the commit's tree ID
-        let tree_id = {
-            let commit = self.db.find_commit(oid, &mut self.buf)?;
-            commit.tree()
+        let tree_id = match self.db.find_commit(oid, &mut self.buf) {
+            Ok(commit) => commit.tree(),
+            Err(gix_object::find::existing_object::Error::NotFound { .. }) => {
+                (self.missing_cb)(oid, Kind::Commit);
+                return Ok(());
+            }
+            Err(err) => return Err(err),
         };
 
         let mut tree_ids = VecDeque::from_iter(Some(tree_id));
@@ -68,6 +75,62 @@ where
         Ok(())
     }
 
+    /// Run the connectivity check on the object `oid`, which is expected to be of the given `kind`.
+    ///
+    /// ### Algorithm
+    ///
+    /// Unlike [`check_commit()`](Self::check_commit()), follow everything the object refers to, which includes
+    /// the parents of commits and the targets of tags. Any objects previously encountered by this instance are skipped,
+    /// while all referenced objects that are not present in the ODB result in a call to the `missing_cb`.
+    /// Objects that can't be decoded are not followed, as they are expected to be reported by [`object::check()`].
+    pub fn check_object(&mut self, oid: &ObjectId, kind: Kind) -> Result<(), gix_object::find::Error> {
+        let mut queue = vec![(*oid, kind)];
+        while let Some((id, kind)) = queue.pop() {
+            if !self.seen.insert(id) {
+                continue;
+            }
+            if kind == Kind::Blob {
+                check_blob(&self.db, &id, &mut self.missing_cb);
+                continue;
+            }
+            let Some(object) = self.db.try_find(&id, &mut self.buf)? else {
+                (self.missing_cb)(&id, kind);
+                continue;
+            };
+            match object.kind {
+                Kind::Commit => {
+                    if let Ok(commit) = gix_object::CommitRef::from_bytes(object.data) {
+                        queue.push((commit.tree(), Kind::Tree));
+                        queue.extend(commit.parents().map(|id| (id, Kind::Commit)));
+                    }
+                }
+                Kind::Tag => {
+                    if let Ok(tag) = gix_object::TagRef::from_bytes(object.data) {
+                        queue.push((tag.target(), tag.target_kind));
+                    }
+                }
+                Kind::Tree => {
+                    for entry in gix_object::TreeRefIter::from_bytes(object.data).map_while(Result::ok) {
+                        match entry.mode.kind() {
+                            EntryKind::Tree => queue.push((entry.oid.to_owned(), Kind::Tree)),
+                            EntryKind::Blob | EntryKind::BlobExecutable | EntryKind::Link => {
+                                queue.push((entry.oid.to_owned(), Kind::Blob));
+                            }
+                            EntryKind::Commit => {}
+                        }
+                    }
+                }
+                Kind::Blob => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the ids of all objects encountered so far, whether they are present in the ODB or not.
+    pub fn seen(&self) -> &HashSet {
+        &self.seen
+    }
+
     /// Blobs are checked right away, trees are stored in `tree_ids` for the parent to iterate them, and only
     /// if they have not been `seen` yet.
     fn check_tree(&mut self, oid: &ObjectId, tree_ids: &mut VecDeque<ObjectId>) {
@@ -0,0 +1,192 @@
+//! Identifiers and severities of the problems that can be found in objects, as known from `git fsck`.
+use std::collections::HashMap;
+
+use gix_object::bstr::{BStr, ByteSlice};
+
+/// How a problem found in an object is to be treated.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// The problem is an error, which means the object is considered invalid.
+    Error,
+    /// The problem is reported, but doesn't invalidate the object.
+    Warn,
+    /// The problem isn't reported at all.
+    Ignore,
+}
+
+impl Severity {
+    /// Parse `name` as one of `error`, `warn` or `ignore`, as used in `fsck.<msg-id>` configuration.
+    pub fn from_name(name: &BStr) -> Option<Self> {
+        Some(match name.as_bytes() {
+            b"error" => Severity::Error,
+            b"warn" => Severity::Warn,
+            b"ignore" => Severity::Ignore,
+            _ => return None,
+        })
+    }
+
+    /// Return the name of this instance as used in `fsck.<msg-id>` configuration.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warn => "warn",
+            Severity::Ignore => "ignore",
+        }
+    }
+}
+
+macro_rules! ids {
+    ($($(#[$doc:meta])* $variant:ident => $name:literal, $severity:ident;)*) => {
+        /// The identifier of a problem found in an object, named like its `git` counterpart.
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum Id {
+            $($(#[$doc])* $variant,)*
+        }
+
+        impl Id {
+            /// All known identifiers.
+            pub const ALL: &'static [Id] = &[$(Id::$variant),*];
+
+            /// Return the name of this identifier as used in `fsck.<msg-id>` configuration, like `badDate`.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Id::$variant => $name,)*
+                }
+            }
+
+            /// Return the severity of this identifier unless it's configured otherwise.
+            pub fn default_severity(&self) -> Severity {
+                match self {
+                    $(Id::$variant => Severity::$severity,)*
+                }
+            }
+        }
+    };
+}
+
+ids! {
+    /// The date of a signature could not be parsed.
+    BadDate => "badDate", Error;
+    /// The email of a signature contains invalid characters.
+    BadEmail => "badEmail", Error;
+    /// The name of a signature contains invalid characters.
+    BadName => "badName", Error;
+    /// The object a tag points to isn't a valid hash.
+    BadObjectSha1 => "badObjectSha1", Error;
+    /// A parent of a commit isn't a valid hash.
+    BadParentSha1 => "badParentSha1", Error;
+    /// The timezone of a signature could not be parsed.
+    BadTimezone => "badTimezone", Error;
+    /// A tree could not be parsed.
+    BadTree => "badTree", Error;
+    /// The tree of a commit isn't a valid hash.
+    BadTreeSha1 => "badTreeSha1", Error;
+    /// The type of the object a tag points to is unknown.
+    BadType => "badType", Error;
+    /// A tree contains the same name more than once.
+    DuplicateEntries => "duplicateEntries", Error;
+    /// A commit doesn't have an author.
+    MissingAuthor => "missingAuthor", Error;
+    /// A commit doesn't have a committer.
+    MissingCommitter => "missingCommitter", Error;
+    /// A signature doesn't have an email.
+    MissingEmail => "missingEmail", Error;
+    /// A signature doesn't have a name before its email.
+    MissingNameBeforeEmail => "missingNameBeforeEmail", Error;
+    /// A tag doesn't say which object it points to.
+    MissingObject => "missingObject", Error;
+    /// There is no space between the email and the date of a signature.
+    MissingSpaceBeforeDate => "missingSpaceBeforeDate", Error;
+    /// There is no space between the name and the email of a signature.
+    MissingSpaceBeforeEmail => "missingSpaceBeforeEmail", Error;
+    /// A tag doesn't have a name.
+    MissingTagEntry => "missingTagEntry", Error;
+    /// A commit doesn't have a tree.
+    MissingTree => "missingTree", Error;
+    /// A tag doesn't say which type of object it points to.
+    MissingTypeEntry => "missingTypeEntry", Error;
+    /// A commit has more than one author.
+    MultipleAuthors => "multipleAuthors", Error;
+    /// The headers of a commit or tag contain a null byte.
+    NulInHeader => "nulInHeader", Error;
+    /// The entries of a tree aren't sorted.
+    TreeNotSorted => "treeNotSorted", Error;
+    /// The headers of a commit or tag aren't followed by an empty line.
+    UnterminatedHeader => "unterminatedHeader", Error;
+    /// The date of a signature has leading zeroes.
+    ZeroPaddedDate => "zeroPaddedDate", Error;
+    /// A `.gitmodules` file contains a submodule name that could be used to escape the modules directory.
+    GitmodulesName => "gitmodulesName", Error;
+    /// A `.gitmodules` file contains a submodule path that could be mistaken for a command-line option.
+    GitmodulesPath => "gitmodulesPath", Error;
+    /// A tree contains a `.gitmodules` symbolic link.
+    GitmodulesSymlink => "gitmodulesSymlink", Error;
+    /// A `.gitmodules` file contains a submodule update command.
+    GitmodulesUpdate => "gitmodulesUpdate", Error;
+    /// A `.gitmodules` file contains a submodule URL that could be mistaken for a command-line option.
+    GitmodulesUrl => "gitmodulesUrl", Error;
+    /// A tree entry has an empty name.
+    EmptyName => "emptyName", Warn;
+    /// A tree entry name contains a slash.
+    FullPathname => "fullPathname", Warn;
+    /// A tree entry is named `.`.
+    HasDot => "hasDot", Warn;
+    /// A tree entry is named `..`.
+    HasDotdot => "hasDotdot", Warn;
+    /// A tree entry is named `.git`, or something a filesystem may consider equivalent.
+    HasDotgit => "hasDotgit", Warn;
+    /// A tree entry points to the null hash.
+    NullSha1 => "nullSha1", Warn;
+    /// The message of a commit contains a null byte.
+    NulInCommit => "nulInCommit", Warn;
+    /// The mode of a tree entry has leading zeroes.
+    ZeroPaddedFilemode => "zeroPaddedFilemode", Warn;
+    /// The mode of a tree entry is unusual.
+    BadFilemode => "badFilemode", Warn;
+    /// The name of a tag isn't valid as reference name.
+    BadTagName => "badTagName", Warn;
+    /// A `.gitmodules` file could not be parsed.
+    GitmodulesParse => "gitmodulesParse", Warn;
+    /// A tag doesn't have a tagger.
+    MissingTaggerEntry => "missingTaggerEntry", Warn;
+}
+
+impl Id {
+    /// Find the identifier with the given `name`, ignoring its case just like configuration keys do.
+    pub fn from_name(name: &BStr) -> Option<Self> {
+        Id::ALL
+            .iter()
+            .find(|id| id.as_str().as_bytes().eq_ignore_ascii_case(name))
+            .copied()
+    }
+}
+
+impl std::fmt::Display for Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Determine the [`Severity`] of each [`Id`], as configured with `fsck.<msg-id>`.
+#[derive(Default, Debug, Clone)]
+pub struct Severities {
+    /// If `true`, problems that would be warnings are treated as errors, like `git fsck --strict`.
+    pub strict: bool,
+    /// Severities that override the default severity of their identifier.
+    pub overrides: HashMap<Id, Severity>,
+}
+
+impl Severities {
+    /// Return the severity of `id`, taking overrides and strictness into account.
+    pub fn severity(&self, id: Id) -> Severity {
+        match self
+            .overrides
+            .get(&id)
+            .copied()
+            .unwrap_or_else(|| id.default_severity())
+        {
+            Severity::Warn if self.strict => Severity::Error,
+            severity => severity,
+        }
+    }
+}
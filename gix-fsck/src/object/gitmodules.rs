@@ -0,0 +1,59 @@
+use gix_object::bstr::{BStr, ByteSlice};
+
+use super::Problem;
+use crate::message::Id;
+
+pub(super) fn check(data: &[u8]) -> Vec<Problem> {
+    let mut out = Vec::new();
+    let events = match gix_config::parse::Events::from_bytes(data, None) {
+        Ok(events) => events,
+        Err(err) => {
+            out.push(Problem::new(
+                Id::GitmodulesParse,
+                format!("could not parse gitmodules blob: {err}"),
+            ));
+            return out;
+        }
+    };
+    let file = gix_config::File::from_parse_events_no_includes(events, gix_config::file::Metadata::api());
+    for section in file.sections_by_name("submodule").into_iter().flatten() {
+        let Some(name) = section.header().subsection_name() else {
+            continue;
+        };
+        if gix_validate::submodule::name(name).is_err() {
+            out.push(Problem::new(
+                Id::GitmodulesName,
+                format!("disallowed submodule name: {name}"),
+            ));
+        }
+        for url in section.values("url") {
+            if looks_like_option(url.as_ref()) || url.find_byte(b'\n').is_some() {
+                out.push(Problem::new(
+                    Id::GitmodulesUrl,
+                    format!("disallowed submodule url: {url}"),
+                ));
+            }
+        }
+        for path in section.values("path") {
+            if looks_like_option(path.as_ref()) {
+                out.push(Problem::new(
+                    Id::GitmodulesPath,
+                    format!("disallowed submodule path: {path}"),
+                ));
+            }
+        }
+        for update in section.values("update") {
+            if update.first() == Some(&b'!') {
+                out.push(Problem::new(
+                    Id::GitmodulesUpdate,
+                    format!("disallowed submodule update setting: {update}"),
+                ));
+            }
+        }
+    }
+    out
+}
+
+fn looks_like_option(value: &BStr) -> bool {
+    value.first() == Some(&b'-')
+}
@@ -0,0 +1,184 @@
+use gix_object::bstr::ByteSlice;
+
+use super::Problem;
+use crate::message::Id;
+
+pub(super) fn commit(data: &[u8], hash_len: usize, out: &mut Vec<Problem>) {
+    let Some((header, body)) = split_headers(data, out) else {
+        return;
+    };
+    let mut lines = header.split(|b| *b == b'\n').peekable();
+    match lines.peek().and_then(|line| line.strip_prefix(b"tree ")) {
+        Some(hex) => {
+            if !is_hash(hex, hash_len) {
+                out.push(Problem::new(Id::BadTreeSha1, "invalid 'tree' line format - bad sha1"));
+            }
+            lines.next();
+        }
+        None => out.push(Problem::new(Id::MissingTree, "invalid format - expected 'tree' line")),
+    }
+    while let Some(hex) = lines.peek().and_then(|line| line.strip_prefix(b"parent ")) {
+        if !is_hash(hex, hash_len) {
+            out.push(Problem::new(
+                Id::BadParentSha1,
+                "invalid 'parent' line format - bad sha1",
+            ));
+        }
+        lines.next();
+    }
+    let mut authors = 0;
+    while let Some(ident) = lines.peek().and_then(|line| line.strip_prefix(b"author ")) {
+        authors += 1;
+        check_ident(ident, out);
+        lines.next();
+    }
+    match authors {
+        0 => out.push(Problem::new(
+            Id::MissingAuthor,
+            "invalid format - expected 'author' line",
+        )),
+        1 => {}
+        _ => out.push(Problem::new(
+            Id::MultipleAuthors,
+            "invalid format - multiple 'author' lines",
+        )),
+    }
+    match lines.peek().and_then(|line| line.strip_prefix(b"committer ")) {
+        Some(ident) => check_ident(ident, out),
+        None => out.push(Problem::new(
+            Id::MissingCommitter,
+            "invalid format - expected 'committer' line",
+        )),
+    }
+    if body.find_byte(0).is_some() {
+        out.push(Problem::new(Id::NulInCommit, "NUL byte in the commit object body"));
+    }
+}
+
+pub(super) fn tag(data: &[u8], hash_len: usize, out: &mut Vec<Problem>) {
+    let Some((header, _body)) = split_headers(data, out) else {
+        return;
+    };
+    let mut lines = header.split(|b| *b == b'\n').peekable();
+    match lines.peek().and_then(|line| line.strip_prefix(b"object ")) {
+        Some(hex) => {
+            if !is_hash(hex, hash_len) {
+                out.push(Problem::new(
+                    Id::BadObjectSha1,
+                    "invalid 'object' line format - bad sha1",
+                ));
+            }
+            lines.next();
+        }
+        None => out.push(Problem::new(
+            Id::MissingObject,
+            "invalid format - expected 'object' line",
+        )),
+    }
+    match lines.peek().and_then(|line| line.strip_prefix(b"type ")) {
+        Some(kind) => {
+            if gix_object::Kind::from_bytes(kind).is_err() {
+                out.push(Problem::new(Id::BadType, "invalid 'type' value"));
+            }
+            lines.next();
+        }
+        None => out.push(Problem::new(
+            Id::MissingTypeEntry,
+            "invalid format - expected 'type' line",
+        )),
+    }
+    match lines.peek().and_then(|line| line.strip_prefix(b"tag ")) {
+        Some(name) => {
+            if gix_validate::tag::name(name.as_bstr()).is_err() {
+                out.push(Problem::new(
+                    Id::BadTagName,
+                    format!("invalid 'tag' name: {}", name.as_bstr()),
+                ));
+            }
+            lines.next();
+        }
+        None => out.push(Problem::new(
+            Id::MissingTagEntry,
+            "invalid format - expected 'tag' line",
+        )),
+    }
+    match lines.peek().and_then(|line| line.strip_prefix(b"tagger ")) {
+        Some(ident) => check_ident(ident, out),
+        None => out.push(Problem::new(
+            Id::MissingTaggerEntry,
+            "invalid format - expected 'tagger' line",
+        )),
+    }
+}
+
+/// Split `data` into headers, without their terminating newline, and body, or return `None` if the headers are invalid.
+fn split_headers<'a>(data: &'a [u8], out: &mut Vec<Problem>) -> Option<(&'a [u8], &'a [u8])> {
+    let (header, body) = match data.find(b"\n\n") {
+        Some(pos) => (&data[..pos], &data[pos + 2..]),
+        // Headers without a body are fine, as long as they are terminated.
+        None if data.last() == Some(&b'\n') => (&data[..data.len() - 1], &[][..]),
+        None => {
+            out.push(Problem::new(Id::UnterminatedHeader, "unterminated header"));
+            return None;
+        }
+    };
+    if let Some(pos) = header.find_byte(0) {
+        out.push(Problem::new(
+            Id::NulInHeader,
+            format!("unterminated header: NUL at offset {pos}"),
+        ));
+        return None;
+    }
+    Some((header, body))
+}
+
+fn is_hash(hex: &[u8], hash_len: usize) -> bool {
+    hex.len() == hash_len * 2 && hex.iter().all(u8::is_ascii_hexdigit)
+}
+
+/// Check `ident` like `Name <email> 1234567890 +0100` and report the first problem.
+fn check_ident(ident: &[u8], out: &mut Vec<Problem>) {
+    let mut report = |id, message: &str| {
+        out.push(Problem::new(id, format!("invalid author/committer line - {message}")));
+    };
+    if ident.first() == Some(&b'<') {
+        return report(Id::MissingNameBeforeEmail, "missing space before email");
+    }
+    let email_start = match ident.find_byteset(b"<>") {
+        Some(pos) if ident[pos] == b'<' => pos,
+        Some(_) => return report(Id::BadName, "bad name"),
+        None => return report(Id::MissingEmail, "missing email"),
+    };
+    if ident[email_start - 1] != b' ' {
+        return report(Id::MissingSpaceBeforeEmail, "missing space before email");
+    }
+    let rest = &ident[email_start + 1..];
+    let rest = match rest.find_byteset(b"<>") {
+        Some(pos) if rest[pos] == b'>' => &rest[pos + 1..],
+        _ => return report(Id::BadEmail, "bad email"),
+    };
+    let Some(rest) = rest.strip_prefix(b" ") else {
+        return report(Id::MissingSpaceBeforeDate, "missing space before date");
+    };
+    if rest.first() == Some(&b'0') && rest.get(1) != Some(&b' ') {
+        return report(Id::ZeroPaddedDate, "zero-padded date");
+    }
+    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    if digits == 0 || rest.get(digits) != Some(&b' ') {
+        return report(Id::BadDate, "bad date");
+    }
+    if rest[..digits]
+        .to_str()
+        .ok()
+        .and_then(|date| date.parse::<i64>().ok())
+        .is_none()
+    {
+        return report(Id::BadDate, "date causes integer overflow");
+    }
+    let timezone = &rest[digits + 1..];
+    let is_valid_timezone =
+        timezone.len() == 5 && matches!(timezone[0], b'+' | b'-') && timezone[1..].iter().all(u8::is_ascii_digit);
+    if !is_valid_timezone {
+        report(Id::BadTimezone, "bad time zone");
+    }
+}
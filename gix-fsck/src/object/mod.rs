@@ -0,0 +1,67 @@
+//! Validation of the syntax of individual objects, as performed by `git fsck`.
+use gix_hash::ObjectId;
+use gix_object::Kind;
+
+use crate::message::Id;
+
+mod gitmodules;
+mod headers;
+mod tree;
+
+/// A problem found in an object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// The identifier of the problem, which also determines its [severity](crate::message::Severities).
+    pub id: Id,
+    /// A description of the problem for display to humans.
+    pub message: String,
+}
+
+impl Problem {
+    fn new(id: Id, message: impl Into<String>) -> Self {
+        Problem {
+            id,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.id, self.message)
+    }
+}
+
+/// The result of [`check()`].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// All problems found in the object, in the order they were encountered.
+    pub problems: Vec<Problem>,
+    /// The ids of blobs that a checked tree stores as `.gitmodules` file, or under a name that filesystems may consider
+    /// equivalent. They should be validated with [`check_gitmodules()`].
+    pub gitmodules: Vec<ObjectId>,
+}
+
+/// Validate the syntax of `data`, the decompressed content of an object of the given `kind` whose hashes are of
+/// kind `object_hash`.
+///
+/// Commits and tags are checked for the presence and format of their headers, including signatures, while trees
+/// are checked for the format, order and uniqueness of their entries, along with names that are dangerous to check out.
+/// Blobs are always valid, unless they are used as `.gitmodules` file.
+pub fn check(kind: Kind, data: &[u8], object_hash: gix_hash::Kind) -> Outcome {
+    let mut out = Outcome::default();
+    let hash_len = object_hash.len_in_bytes();
+    match kind {
+        Kind::Commit => headers::commit(data, hash_len, &mut out.problems),
+        Kind::Tag => headers::tag(data, hash_len, &mut out.problems),
+        Kind::Tree => tree::check(data, object_hash, &mut out),
+        Kind::Blob => {}
+    }
+    out
+}
+
+/// Validate `data`, the content of a blob that is used as `.gitmodules` file, for submodule names, paths, URLs and
+/// update commands that could be used to trick `git` into writing outside of the repository or into running commands.
+pub fn check_gitmodules(data: &[u8]) -> Vec<Problem> {
+    gitmodules::check(data)
+}
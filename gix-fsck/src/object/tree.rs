@@ -0,0 +1,134 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashSet},
+};
+
+use gix_hash::ObjectId;
+use gix_object::bstr::ByteSlice;
+use gix_validate::path::component;
+
+use super::{Outcome, Problem};
+use crate::message::Id;
+
+const IFMT: u32 = 0o170000;
+const TREE: u32 = 0o040000;
+const LINK: u32 = 0o120000;
+const BLOB: u32 = 0o100000;
+
+pub(super) fn check(data: &[u8], object_hash: gix_hash::Kind, out: &mut Outcome) {
+    let hash_len = object_hash.len_in_bytes();
+    // Each problem is reported only once per tree, just like `git` does.
+    let mut found = BTreeSet::new();
+    let mut report = |id: Id, message: &str, out: &mut Outcome| {
+        if found.insert(id) {
+            out.problems.push(Problem::new(id, message));
+        }
+    };
+    let options = component::Options {
+        protect_windows: false,
+        protect_hfs: true,
+        protect_ntfs: true,
+    };
+
+    let mut names = HashSet::<&[u8]>::new();
+    let mut previous = None;
+    let mut rest = data;
+    while !rest.is_empty() {
+        let Some((Entry { mode, name, id }, remaining)) = parse_entry(rest, hash_len) else {
+            report(Id::BadTree, "cannot be parsed as a tree", out);
+            return;
+        };
+        rest = remaining;
+        let Some(mode_value) = parse_mode(mode) else {
+            report(Id::BadTree, "cannot be parsed as a tree", out);
+            return;
+        };
+        if mode[0] == b'0' {
+            report(Id::ZeroPaddedFilemode, "contains zero-padded file modes", out);
+        }
+        match mode_value {
+            0o100644 | 0o100755 | 0o100664 | LINK | TREE | 0o160000 => {}
+            _ => report(Id::BadFilemode, "contains bad file modes", out),
+        }
+        if id.iter().all(|b| *b == 0) {
+            report(Id::NullSha1, "contains entries pointing to null sha1", out);
+        }
+
+        if name.is_empty() {
+            report(Id::EmptyName, "contains empty pathname", out);
+        } else if name.find_byte(b'/').is_some() {
+            report(Id::FullPathname, "contains full pathnames", out);
+        } else if name == b"." {
+            report(Id::HasDot, "contains '.'", out);
+        } else if name == b".." {
+            report(Id::HasDotdot, "contains '..'", out);
+        } else {
+            if let Err(component::Error::DotGitDir) = gix_validate::path::component(name.as_bstr(), None, options) {
+                report(Id::HasDotgit, "contains '.git'", out);
+            }
+            let is_gitmodules = matches!(
+                gix_validate::path::component(name.as_bstr(), Some(component::Mode::Symlink), options),
+                Err(component::Error::SymlinkedGitModules)
+            );
+            if is_gitmodules {
+                match mode_value & IFMT {
+                    LINK => report(Id::GitmodulesSymlink, ".gitmodules is a symbolic link", out),
+                    BLOB => out.gitmodules.push(ObjectId::from_bytes_or_panic(id)),
+                    _ => {}
+                }
+            }
+        }
+
+        let is_tree = mode_value & IFMT == TREE;
+        if !names.insert(name) {
+            report(Id::DuplicateEntries, "contains duplicate file entries", out);
+        } else if let Some((previous_name, previous_is_tree)) = previous {
+            if compare(previous_name, previous_is_tree, name, is_tree) == Ordering::Greater {
+                report(Id::TreeNotSorted, "not properly sorted", out);
+            }
+        }
+        previous = Some((name, is_tree));
+    }
+}
+
+/// The unparsed fields of a tree entry.
+struct Entry<'a> {
+    mode: &'a [u8],
+    name: &'a [u8],
+    id: &'a [u8],
+}
+
+/// Return the entry at the beginning of `data`, along with the remaining data.
+fn parse_entry(data: &[u8], hash_len: usize) -> Option<(Entry<'_>, &[u8])> {
+    let space = data.find_byte(b' ')?;
+    let (mode, rest) = (&data[..space], &data[space + 1..]);
+    let nul = rest.find_byte(0)?;
+    let (name, rest) = (&rest[..nul], &rest[nul + 1..]);
+    if rest.len() < hash_len {
+        return None;
+    }
+    let entry = Entry {
+        mode,
+        name,
+        id: &rest[..hash_len],
+    };
+    Some((entry, &rest[hash_len..]))
+}
+
+fn parse_mode(mode: &[u8]) -> Option<u32> {
+    if mode.is_empty() || mode.len() > 7 {
+        return None;
+    }
+    mode.iter().try_fold(0u32, |acc, b| {
+        matches!(b, b'0'..=b'7').then(|| (acc << 3) + u32::from(b - b'0'))
+    })
+}
+
+/// Compare names like `git` sorts tree entries, with trees sorting as if their name ended in a slash.
+fn compare(lhs: &[u8], lhs_is_tree: bool, rhs: &[u8], rhs_is_tree: bool) -> Ordering {
+    let common = lhs.len().min(rhs.len());
+    lhs[..common].cmp(&rhs[..common]).then_with(|| {
+        let next = |name: &[u8], is_tree: bool| name.get(common).copied().or(is_tree.then_some(b'/'));
+        next(lhs, lhs_is_tree).cmp(&next(rhs, rhs_is_tree))
+    })
+}
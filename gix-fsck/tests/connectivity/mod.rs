@@ -56,6 +56,49 @@ fn no_missing() {
     assert_eq!(check_missing("base", all_commits()), HashMap::default());
 }
 
+#[test]
+fn missing_commits() {
+    let missing_commit = hex_to_id("0000000000000000000000000000000000000001");
+    assert_eq!(
+        check_missing("base", &[missing_commit]),
+        hex_to_objects(["0000000000000000000000000000000000000001"], Kind::Commit)
+    );
+}
+
+#[test]
+fn check_object_follows_parents() {
+    let fixture_path = gix_testtools::scripted_fixture_read_only("make_test_repos.sh")
+        .expect("fixture path")
+        .join("blobless")
+        .join(".git")
+        .join("objects");
+    let db = gix_odb::at(fixture_path).expect("valid odb");
+    let mut missing = HashMap::default();
+    let mut check = Connectivity::new(&db, |oid: &ObjectId, kind: Kind| {
+        missing.insert(*oid, kind);
+    });
+    check
+        .check_object(&all_commits()[0], Kind::Commit)
+        .expect("no error accessing the database");
+    assert_eq!(
+        check.seen().len(),
+        3 + 3 + 4,
+        "all commits, trees and blobs are reachable from the tip"
+    );
+    drop(check);
+    assert_eq!(
+        missing,
+        hex_to_objects(
+            [
+                "4cdeaab5b01f9a9fbbb2fb6c08404cf12b7bdab1",
+                "c18147dc648481eeb65dc5e66628429a64843327",
+            ],
+            Kind::Blob,
+        ),
+        "only blobs of previous commits are missing"
+    );
+}
+
 #[test]
 fn missing_blobs() {
     // The "blobless" repo is cloned with `--filter=blob:none`, and is missing one blob
@@ -0,0 +1,217 @@
+use gix_fsck::{
+    message::{Id, Severities, Severity},
+    object::{check, check_gitmodules},
+};
+use gix_object::Kind;
+
+use crate::hex_to_id;
+
+const TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+fn ids(kind: Kind, data: &[u8]) -> Vec<Id> {
+    check(kind, data, gix_hash::Kind::Sha1)
+        .problems
+        .into_iter()
+        .map(|problem| problem.id)
+        .collect()
+}
+
+fn tree(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (mode, name) in entries {
+        out.extend_from_slice(format!("{mode} {name}\0").as_bytes());
+        out.extend_from_slice(hex_to_id(TREE).as_slice());
+    }
+    out
+}
+
+#[test]
+fn valid_commit_and_tag() {
+    let commit = format!(
+        "tree {TREE}\nparent {TREE}\nauthor A U Thor <author@example.com> 946684800 +0000\ncommitter C O Mitter <committer@example.com> 946771200 -0130\n\nmessage\n"
+    );
+    assert_eq!(ids(Kind::Commit, commit.as_bytes()), []);
+    let tag = format!(
+        "object {TREE}\ntype commit\ntag v1.0\ntagger A U Thor <author@example.com> 946684800 +0000\n\nmessage\n"
+    );
+    assert_eq!(ids(Kind::Tag, tag.as_bytes()), []);
+    assert_eq!(ids(Kind::Blob, b"\0anything"), [], "blobs are always valid");
+}
+
+#[test]
+fn bad_commits() {
+    let author = "author A <a@example.com> 1 +0000\n";
+    let committer = "committer C <c@example.com> 1 +0000\n";
+    for (commit, expected) in [
+        (format!("{author}{committer}\n"), vec![Id::MissingTree]),
+        (format!("tree abc\n{author}{committer}\n"), vec![Id::BadTreeSha1]),
+        (
+            format!("tree {TREE}\nparent {TREE}x\n{author}{committer}\n"),
+            vec![Id::BadParentSha1],
+        ),
+        (format!("tree {TREE}\n{committer}\n"), vec![Id::MissingAuthor]),
+        (
+            format!("tree {TREE}\n{author}{author}{committer}\n"),
+            vec![Id::MultipleAuthors],
+        ),
+        (format!("tree {TREE}\n{author}\n"), vec![Id::MissingCommitter]),
+        (
+            format!("tree {TREE}\n{author}{committer}\nbody\0"),
+            vec![Id::NulInCommit],
+        ),
+        (format!("tree {TREE}\n{author}{committer}"), vec![]),
+        (format!("tree {TREE}\n{author}\0{committer}\n"), vec![Id::NulInHeader]),
+        (format!("tree {TREE}"), vec![Id::UnterminatedHeader]),
+    ] {
+        assert_eq!(ids(Kind::Commit, commit.as_bytes()), expected, "{commit:?}");
+    }
+}
+
+#[test]
+fn bad_signatures() {
+    for (ident, expected) in [
+        ("<a@example.com> 1 +0000", Id::MissingNameBeforeEmail),
+        ("A> <a@example.com> 1 +0000", Id::BadName),
+        ("A 1 +0000", Id::MissingEmail),
+        ("A<a@example.com> 1 +0000", Id::MissingSpaceBeforeEmail),
+        ("A <a@example.com 1 +0000", Id::BadEmail),
+        ("A <a@example.com>1 +0000", Id::MissingSpaceBeforeDate),
+        ("A <a@example.com> 01 +0000", Id::ZeroPaddedDate),
+        ("A <a@example.com> x +0000", Id::BadDate),
+        ("A <a@example.com> 99999999999999999999 +0000", Id::BadDate),
+        ("A <a@example.com> 1 0000", Id::BadTimezone),
+        ("A <a@example.com> 1 +00", Id::BadTimezone),
+    ] {
+        let commit = format!("tree {TREE}\nauthor {ident}\ncommitter C <c@example.com> 1 +0000\n\n");
+        assert_eq!(ids(Kind::Commit, commit.as_bytes()), [expected], "{ident:?}");
+    }
+}
+
+#[test]
+fn bad_tags() {
+    let tagger = "tagger T <t@example.com> 1 +0000\n";
+    for (tag, expected) in [
+        (format!("type commit\ntag v1\n{tagger}\n"), vec![Id::MissingObject]),
+        (
+            format!("object {TREE}x\ntype commit\ntag v1\n{tagger}\n"),
+            vec![Id::BadObjectSha1],
+        ),
+        (format!("object {TREE}\ntag v1\n{tagger}\n"), vec![Id::MissingTypeEntry]),
+        (
+            format!("object {TREE}\ntype bad\ntag v1\n{tagger}\n"),
+            vec![Id::BadType],
+        ),
+        (
+            format!("object {TREE}\ntype tree\n{tagger}\n"),
+            vec![Id::MissingTagEntry],
+        ),
+        (
+            format!("object {TREE}\ntype tree\ntag a..b\n{tagger}\n"),
+            vec![Id::BadTagName],
+        ),
+        (
+            format!("object {TREE}\ntype tree\ntag v1\n\n"),
+            vec![Id::MissingTaggerEntry],
+        ),
+    ] {
+        assert_eq!(ids(Kind::Tag, tag.as_bytes()), expected, "{tag:?}");
+    }
+}
+
+#[test]
+fn bad_trees() {
+    assert_eq!(
+        ids(Kind::Tree, &tree(&[("100644", "a"), ("40000", "a-"), ("40000", "b")])),
+        [],
+        "trees sort as if their name ended with a slash"
+    );
+    for (entries, expected) in [
+        (vec![("040000", "a")], vec![Id::ZeroPaddedFilemode]),
+        (vec![("100600", "a")], vec![Id::BadFilemode]),
+        (vec![("100644", "b"), ("100644", "a")], vec![Id::TreeNotSorted]),
+        (vec![("100644", "a"), ("100644", "a")], vec![Id::DuplicateEntries]),
+        (
+            vec![("100644", "a"), ("100644", "a-"), ("40000", "a")],
+            vec![Id::DuplicateEntries],
+        ),
+        (vec![("100644", "")], vec![Id::EmptyName]),
+        (vec![("100644", "a/b")], vec![Id::FullPathname]),
+        (vec![("40000", ".")], vec![Id::HasDot]),
+        (vec![("40000", "..")], vec![Id::HasDotdot]),
+        (vec![("40000", ".git")], vec![Id::HasDotgit]),
+        (vec![("40000", ".GIT")], vec![Id::HasDotgit]),
+        (vec![("40000", "git~1")], vec![Id::HasDotgit]),
+        (vec![("40000", ".git\u{200c}")], vec![Id::HasDotgit]),
+        (vec![("120000", ".gitmodules")], vec![Id::GitmodulesSymlink]),
+        (vec![("120000", "GITMOD~1")], vec![Id::GitmodulesSymlink]),
+    ] {
+        assert_eq!(ids(Kind::Tree, &tree(&entries)), expected, "{entries:?}");
+    }
+    assert_eq!(
+        ids(Kind::Tree, &tree(&[("40000", "a")])[..10]),
+        [Id::BadTree],
+        "truncated"
+    );
+    assert_eq!(ids(Kind::Tree, b"100644 a\0"), [Id::BadTree], "missing hash");
+    assert_eq!(ids(Kind::Tree, &tree(&[("1o0644", "a")])), [Id::BadTree], "bad octal");
+
+    let mut null_entry = b"100644 a\0".to_vec();
+    null_entry.extend_from_slice(&[0; 20]);
+    assert_eq!(ids(Kind::Tree, &null_entry), [Id::NullSha1]);
+}
+
+#[test]
+fn gitmodules() {
+    let out = check(
+        Kind::Tree,
+        &tree(&[("100644", ".gitmodules"), ("100644", "Gitmod~1")]),
+        gix_hash::Kind::Sha1,
+    );
+    assert_eq!(out.problems, []);
+    assert_eq!(
+        out.gitmodules,
+        [hex_to_id(TREE), hex_to_id(TREE)],
+        "blobs stored as .gitmodules are returned for validation, even if their name is only equivalent"
+    );
+
+    assert_eq!(
+        check_gitmodules(b"[submodule \"ok\"]\n\tpath = ok\n\turl = https://example.com/ok\n\tupdate = rebase\n"),
+        []
+    );
+    let problems: Vec<_> = check_gitmodules(
+        b"[submodule \"../escape\"]\n\tpath = -path\n\turl = --upload-pack=evil\n\tupdate = !rm -rf /\n",
+    )
+    .into_iter()
+    .map(|problem| problem.id)
+    .collect();
+    assert_eq!(
+        problems,
+        [
+            Id::GitmodulesName,
+            Id::GitmodulesUrl,
+            Id::GitmodulesPath,
+            Id::GitmodulesUpdate
+        ]
+    );
+    let problems = check_gitmodules(b"[submodule");
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].id, Id::GitmodulesParse);
+}
+
+#[test]
+fn severities() {
+    assert_eq!(Id::from_name("BADDATE".into()), Some(Id::BadDate));
+    assert_eq!(Id::from_name("unknown".into()), None);
+    assert_eq!(Severity::from_name("warn".into()), Some(Severity::Warn));
+    assert_eq!(Severity::from_name("fatal".into()), None);
+
+    let mut severities = Severities::default();
+    assert_eq!(severities.severity(Id::BadDate), Severity::Error);
+    assert_eq!(severities.severity(Id::MissingTaggerEntry), Severity::Warn);
+    severities.overrides.insert(Id::BadDate, Severity::Ignore);
+    assert_eq!(severities.severity(Id::BadDate), Severity::Ignore);
+
+    severities.strict = true;
+    assert_eq!(severities.severity(Id::MissingTaggerEntry), Severity::Error);
+    assert_eq!(severities.severity(Id::BadDate), Severity::Ignore, "overrides are kept");
+}
@@ -0,0 +1,210 @@
+//! Create and verify signatures of commits and tags similar to `git commit -S` and `git verify-commit`.
+//!
+//! Signatures are [created](Signer) by [running](program::sign()) `gpg`, `gpgsm` or `ssh-keygen`, or by a custom implementation.
+//! SSH signatures are verified [natively](ssh::verify()) against the keys of an [allowed signers file](ssh::allowed_signers)
+//! if the `ssh` feature is enabled, while OpenPGP and X.509 signatures are verified by [running](program::verify()) `gpg`,
+//! `gpgsm` or a compatible program.
+//! ## Feature Flags
+#![cfg_attr(
+    all(doc, feature = "document-features"),
+    doc = ::document_features::document_features!()
+)]
+#![cfg_attr(all(doc, feature = "document-features"), feature(doc_cfg, doc_auto_cfg))]
+#![deny(missing_docs, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use bstr::{BString, ByteSlice};
+
+///
+pub mod program;
+
+///
+#[cfg(feature = "ssh")]
+pub mod ssh;
+
+/// A way to create signatures, which allows to plug in in-process implementations like an HSM-backed service.
+pub trait Signer {
+    /// Create a detached signature of `data`, which is expected to be ASCII-armored and end with a newline,
+    /// like the output of `gpg -bsa`.
+    fn sign(&self, data: &[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>>;
+}
+
+impl<T: Signer + ?Sized> Signer for &T {
+    fn sign(&self, data: &[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        (*self).sign(data)
+    }
+}
+
+/// The kind of signature, as configured with `gpg.format`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// An OpenPGP signature as created by `gpg`.
+    #[default]
+    OpenPgp,
+    /// An X.509 signature as created by `gpgsm`.
+    X509,
+    /// An SSH signature as created by `ssh-keygen -Y sign`.
+    Ssh,
+}
+
+impl Format {
+    /// The markers at the beginning of a signature of this format.
+    pub fn markers(&self) -> &'static [&'static str] {
+        match self {
+            Format::OpenPgp => &["-----BEGIN PGP SIGNATURE-----", "-----BEGIN PGP MESSAGE-----"],
+            Format::X509 => &["-----BEGIN SIGNED MESSAGE-----"],
+            Format::Ssh => &["-----BEGIN SSH SIGNATURE-----"],
+        }
+    }
+
+    /// Determine the format of `signature` by the marker it starts with, or return `None` if it isn't known.
+    pub fn from_signature(signature: &[u8]) -> Option<Self> {
+        [Format::OpenPgp, Format::X509, Format::Ssh].into_iter().find(|format| {
+            format
+                .markers()
+                .iter()
+                .any(|marker| signature.starts_with(marker.as_bytes()))
+        })
+    }
+
+    /// The program that is used by `git` to create and verify signatures of this format if nothing else is configured.
+    pub fn default_program(&self) -> &'static str {
+        match self {
+            Format::OpenPgp => "gpg",
+            Format::X509 => "gpgsm",
+            Format::Ssh => "ssh-keygen",
+        }
+    }
+
+    /// The name of the format as used in `gpg.format`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Format::OpenPgp => "openpgp",
+            Format::X509 => "x509",
+            Format::Ssh => "ssh",
+        }
+    }
+}
+
+/// The level of trust into the key that created a signature, ordered from the least to the most trust.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Trust {
+    /// Nothing is known about the trust into the key.
+    #[default]
+    Undefined,
+    /// The key must never be trusted.
+    Never,
+    /// The key is trusted marginally.
+    Marginal,
+    /// The key is fully trusted.
+    Fully,
+    /// The key is trusted ultimately, typically because it's owned by the user.
+    Ultimate,
+}
+
+impl Trust {
+    /// Parse the trust level `name` as used in `gpg.minTrustLevel` and in the `TRUST_*` status lines of `gpg`,
+    /// ignoring its case.
+    pub fn from_bytes(name: &[u8]) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_slice() {
+            b"undefined" => Trust::Undefined,
+            b"never" => Trust::Never,
+            b"marginal" => Trust::Marginal,
+            b"fully" => Trust::Fully,
+            b"ultimate" => Trust::Ultimate,
+            _ => return None,
+        })
+    }
+
+    /// The name of the trust level as shown by `git log --format=%GT`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Trust::Undefined => "undefined",
+            Trust::Never => "never",
+            Trust::Marginal => "marginal",
+            Trust::Fully => "fully",
+            Trust::Ultimate => "ultimate",
+        }
+    }
+}
+
+/// The result of checking a signature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// The signature is good.
+    Good,
+    /// The signature is good, but the key that created it isn't known to be allowed to sign.
+    UnknownValidity,
+    /// The signature is good, but it has expired.
+    ExpiredSignature,
+    /// The signature is good, but it was made by a key that has expired.
+    ExpiredKey,
+    /// The signature is good, but it was made by a key that was revoked.
+    RevokedKey,
+    /// The signature doesn't match the signed data.
+    Bad,
+    /// The signature couldn't be checked, typically because the key is missing.
+    Unverifiable,
+}
+
+impl Status {
+    /// The character used by `git log --format=%G?` for this status.
+    pub fn as_char(&self) -> char {
+        match self {
+            Status::Good => 'G',
+            Status::UnknownValidity => 'U',
+            Status::ExpiredSignature => 'X',
+            Status::ExpiredKey => 'Y',
+            Status::RevokedKey => 'R',
+            Status::Bad => 'B',
+            Status::Unverifiable => 'E',
+        }
+    }
+}
+
+/// The outcome of verifying a signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    /// The format of the signature.
+    pub format: Format,
+    /// Whether or not the signature is good.
+    pub status: Status,
+    /// The identity of the signer, like `Name <email>` for OpenPGP or the principal for SSH signatures, if known.
+    pub signer: Option<BString>,
+    /// The id of the key that created the signature, if known.
+    pub key: Option<BString>,
+    /// The fingerprint of the key that created the signature, if known.
+    pub fingerprint: Option<BString>,
+    /// The fingerprint of the primary key if the signature was made by a sub-key, which is only known for OpenPGP signatures.
+    pub primary_key_fingerprint: Option<BString>,
+    /// The trust into the key that created the signature.
+    pub trust: Trust,
+    /// The human-readable output of the verification, as `git verify-commit` would show it.
+    pub output: BString,
+}
+
+impl Verification {
+    /// Return `true` if the signature is good and its key is trusted at least as much as `min_trust`.
+    ///
+    /// This is what `git verify-commit` and `git verify-tag` use to determine success, with `min_trust`
+    /// configured by `gpg.minTrustLevel`.
+    pub fn is_good(&self, min_trust: Trust) -> bool {
+        self.status == Status::Good && self.trust >= min_trust
+    }
+}
+
+/// Return the position at which the signature in `data` starts, which is the last line that starts with the marker
+/// of a known [`Format`], or `None` if there is no signature.
+///
+/// This is how signatures are found at the end of tag messages.
+pub fn signature_start(data: &[u8]) -> Option<usize> {
+    let mut start = None;
+    let mut pos = 0;
+    while pos < data.len() {
+        if Format::from_signature(&data[pos..]).is_some() {
+            start = Some(pos);
+        }
+        pos = data[pos..].find_byte(b'\n').map_or(data.len(), |end| pos + end + 1);
+    }
+    start
+}
@@ -0,0 +1,294 @@
+//! Create signatures and verify OpenPGP and X.509 signatures by running `gpg`, `gpgsm`, `ssh-keygen` or a compatible program,
+//! similar to what `git` does.
+use std::{
+    ffi::{OsStr, OsString},
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use bstr::{BString, ByteSlice};
+
+use crate::{Format, Status, Trust, Verification};
+
+///
+pub mod sign {
+    use bstr::BString;
+
+    /// The error returned by [`sign()`](super::sign()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not write the data to sign or the signing key to a temporary file")]
+        Tempfile(#[source] std::io::Error),
+        #[error("Could not run '{program}' to create a signature")]
+        Spawn { program: BString, source: std::io::Error },
+        #[error("'{program}' failed to sign the data: {stderr}")]
+        Failed { program: BString, stderr: BString },
+        #[error("ssh-keygen -Y sign is needed for ssh signing (available in openssh version 8.2p1+)")]
+        UnsupportedSshKeygen,
+        #[error("Could not read the signature written by '{program}'")]
+        ReadSignature { program: BString, source: std::io::Error },
+    }
+}
+
+///
+pub mod verify {
+    use bstr::BString;
+
+    /// The error returned by [`verify()`](super::verify()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Signatures in the {} format can't be verified with a program like gpg", format.as_str())]
+        UnsupportedFormat { format: crate::Format },
+        #[error("Could not write the signature to a temporary file")]
+        Tempfile(#[source] std::io::Error),
+        #[error("Could not run '{program}' to verify the signature")]
+        Spawn { program: BString, source: std::io::Error },
+    }
+}
+
+/// A [signer](crate::Signer) that creates signatures by running a program like `gpg`, `gpgsm` or `ssh-keygen`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signer {
+    /// The program to run, as configured with `gpg.<format>.program`.
+    pub program: OsString,
+    /// The format of the signatures to create.
+    pub format: Format,
+    /// The key to sign with, as configured with `user.signingKey`.
+    ///
+    /// For OpenPGP and X.509 signatures it's passed to the program as is, and it's typically the committer identity.
+    /// For SSH signatures it's the path to a private key, or a public key which is used with the `ssh-agent`
+    /// if it starts with `key::` or `ssh-`.
+    pub key: BString,
+}
+
+impl crate::Signer for Signer {
+    fn sign(&self, data: &[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(sign(&self.program, self.format, self.key.as_ref(), data)?)
+    }
+}
+
+/// Sign `data` with `key` by running `program` to create a detached signature in `format`, just like `git` does.
+///
+/// OpenPGP and X.509 signatures are created in the manner of `gpg --status-fd=2 -bsau <key>`, with `data` passed
+/// via `stdin` and the signature read from `stdout`. SSH signatures are created in the manner of
+/// `ssh-keygen -Y sign -n git -f <key-file> <data-file>` and read from the `.sig` file next to the data file.
+///
+/// The returned signature ends with a newline and has its carriage returns removed.
+pub fn sign(program: impl AsRef<OsStr>, format: Format, key: &[u8], data: &[u8]) -> Result<BString, sign::Error> {
+    let program = program.as_ref();
+    let program_name = || -> BString { program.to_string_lossy().into_owned().into() };
+    let spawn_err = |source| sign::Error::Spawn {
+        program: program_name(),
+        source,
+    };
+    let failed = |stderr: Vec<u8>| sign::Error::Failed {
+        program: program_name(),
+        stderr: stderr.trim_with(|c| c.is_ascii_whitespace()).into(),
+    };
+
+    let signature = match format {
+        Format::OpenPgp | Format::X509 => {
+            let mut child = Command::new(program)
+                .arg("--status-fd=2")
+                .arg("-bsau")
+                .arg(&*gix_path::from_bstr(key.as_bstr()))
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(spawn_err)?;
+            let mut stdin = child.stdin.take().expect("configured");
+            let output = std::thread::scope(|scope| {
+                scope.spawn(move || stdin.write_all(data).ok());
+                child.wait_with_output()
+            })
+            .map_err(spawn_err)?;
+            if !output.status.success()
+                || output.stdout.is_empty()
+                || output.stderr.find(b"\n[GNUPG:] SIG_CREATED ").is_none()
+            {
+                return Err(failed(output.stderr));
+            }
+            output.stdout
+        }
+        Format::Ssh => {
+            let mut key_file = None;
+            let literal_key = key
+                .strip_prefix(b"key::")
+                .or_else(|| key.starts_with(b"ssh-").then_some(key));
+            if let Some(literal_key) = literal_key {
+                let mut file = tempfile::Builder::new()
+                    .prefix(".git_signing_key_tmp")
+                    .tempfile()
+                    .map_err(sign::Error::Tempfile)?;
+                file.write_all(literal_key)
+                    .and_then(|_| file.flush())
+                    .map_err(sign::Error::Tempfile)?;
+                key_file = Some(file);
+            }
+            let mut data_file = tempfile::Builder::new()
+                .prefix(".git_signing_buffer_tmp")
+                .tempfile()
+                .map_err(sign::Error::Tempfile)?;
+            data_file
+                .write_all(data)
+                .and_then(|_| data_file.flush())
+                .map_err(sign::Error::Tempfile)?;
+
+            let mut cmd = Command::new(program);
+            cmd.args(["-Y", "sign", "-n", "git", "-f"]);
+            match &key_file {
+                Some(key_file) => cmd.arg(key_file.path()).arg("-U"),
+                None => cmd.arg(&*gix_path::from_bstr(key.as_bstr())),
+            };
+            let output = cmd
+                .arg(data_file.path())
+                .stdin(Stdio::null())
+                .output()
+                .map_err(spawn_err)?;
+            let mut signature_path = data_file.path().as_os_str().to_owned();
+            signature_path.push(".sig");
+            let signature_path = std::path::PathBuf::from(signature_path);
+            if !output.status.success() {
+                std::fs::remove_file(&signature_path).ok();
+                return Err(if output.stderr.find(b"usage:").is_some() {
+                    sign::Error::UnsupportedSshKeygen
+                } else {
+                    failed(output.stderr)
+                });
+            }
+            let signature = std::fs::read(&signature_path).map_err(|source| sign::Error::ReadSignature {
+                program: program_name(),
+                source,
+            });
+            std::fs::remove_file(&signature_path).ok();
+            signature?
+        }
+    };
+    Ok(remove_carriage_returns(signature))
+}
+
+/// Verify `signature` of `data` by running `program` in the manner of `gpg --status-fd=1 --verify <signature-file> -`,
+/// with `format` being either [`Format::OpenPgp`] or [`Format::X509`].
+///
+/// The program is expected to write `gpg` status lines to `stdout`, which are [parsed](parse_status()) into the result,
+/// and human-readable output to `stderr`, which becomes its [output](Verification::output).
+/// Note that a failing program isn't an error, but typically leads to a verification that [isn't good](Verification::is_good()).
+pub fn verify(
+    program: impl AsRef<OsStr>,
+    format: Format,
+    data: &[u8],
+    signature: &[u8],
+) -> Result<Verification, verify::Error> {
+    let program = program.as_ref();
+    if format == Format::Ssh {
+        return Err(verify::Error::UnsupportedFormat { format });
+    }
+    let mut signature_file = tempfile::Builder::new()
+        .prefix(".git_vtag_tmp")
+        .tempfile()
+        .map_err(verify::Error::Tempfile)?;
+    signature_file
+        .write_all(signature)
+        .and_then(|_| signature_file.flush())
+        .map_err(verify::Error::Tempfile)?;
+
+    let mut cmd = Command::new(program);
+    if format == Format::OpenPgp {
+        cmd.arg("--keyid-format=long");
+    }
+    cmd.arg("--status-fd=1")
+        .arg("--verify")
+        .arg(signature_file.path())
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let spawn_err = |source| verify::Error::Spawn {
+        program: program.to_string_lossy().into_owned().into(),
+        source,
+    };
+    let mut child = cmd.spawn().map_err(spawn_err)?;
+    let mut stdin = child.stdin.take().expect("configured");
+    let output = std::thread::scope(|scope| {
+        // The program may stop reading early, so failing to write everything is expected in that case.
+        scope.spawn(move || stdin.write_all(data).ok());
+        child.wait_with_output()
+    })
+    .map_err(spawn_err)?;
+
+    let mut verification = parse_status(format, &output.stdout);
+    verification.output = output.stderr.into();
+    Ok(verification)
+}
+
+/// Parse the `status` lines written by `gpg --status-fd` for a signature in `format`, similar to how `git` does it.
+///
+/// If there is no status that indicates the result, or if there is more than one signature, the signature is
+/// considered [unverifiable](Status::Unverifiable). The [output](Verification::output) is left empty.
+pub fn parse_status(format: Format, status: &[u8]) -> Verification {
+    let mut out = unverifiable(format);
+
+    let mut seen_result = false;
+    for line in status.lines() {
+        let Some(line) = line.strip_prefix(b"[GNUPG:] ") else {
+            continue;
+        };
+        let (keyword, rest) = line.split_once_str(" ").unwrap_or((line, b""));
+        if let Some(trust) = keyword.strip_prefix(b"TRUST_") {
+            match Trust::from_bytes(trust) {
+                Some(trust) => out.trust = trust,
+                None => return unverifiable(format),
+            }
+            continue;
+        }
+        let status = match keyword {
+            b"GOODSIG" => Status::Good,
+            b"BADSIG" => Status::Bad,
+            b"EXPSIG" => Status::ExpiredSignature,
+            b"EXPKEYSIG" => Status::ExpiredKey,
+            b"REVKEYSIG" => Status::RevokedKey,
+            b"ERRSIG" => Status::Unverifiable,
+            b"VALIDSIG" => {
+                let mut fields = rest.split_str(" ");
+                out.fingerprint = fields.next().map(Into::into);
+                // The primary key fingerprint is the 9th field after the fingerprint, and only present for OpenPGP.
+                out.primary_key_fingerprint = fields.nth(8).filter(|field| !field.is_empty()).map(Into::into);
+                continue;
+            }
+            _ => continue,
+        };
+        if seen_result {
+            // Each signature has only one result, so we are dealing with multiple signatures.
+            return unverifiable(format);
+        }
+        seen_result = true;
+        out.status = status;
+        let (key, signer) = rest.split_once_str(" ").unwrap_or((rest, b""));
+        out.key = Some(key.into());
+        if status != Status::Unverifiable && !signer.is_empty() {
+            out.signer = Some(signer.into());
+        }
+    }
+    out
+}
+
+fn unverifiable(format: Format) -> Verification {
+    Verification {
+        format,
+        status: Status::Unverifiable,
+        signer: None,
+        key: None,
+        fingerprint: None,
+        primary_key_fingerprint: None,
+        trust: Trust::Undefined,
+        output: BString::default(),
+    }
+}
+
+fn remove_carriage_returns(mut signature: Vec<u8>) -> BString {
+    signature.retain(|b| *b != b'\r');
+    signature.into()
+}
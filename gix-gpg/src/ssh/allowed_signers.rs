@@ -0,0 +1,196 @@
+//! Parse the allowed signers file configured in `gpg.ssh.allowedSignersFile`, as described in the
+//! `ALLOWED SIGNERS` section of the `ssh-keygen` manual.
+use bstr::{BString, ByteSlice};
+
+/// A line of an allowed signers file, which associates principals with a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The comma-separated principals, typically email addresses, which may contain `*` and `?` wildcards.
+    pub principals: BString,
+    /// If `true`, the key is a certificate authority whose certificates are trusted for the principals.
+    ///
+    /// These are parsed, but not used for verification.
+    pub cert_authority: bool,
+    /// The comma-separated namespaces that the key may be used for, or `None` if it may be used for all of them.
+    pub namespaces: Option<BString>,
+    /// The time in seconds since the Unix epoch at which the key becomes valid.
+    pub valid_after: Option<i64>,
+    /// The time in seconds since the Unix epoch at which the key stops being valid.
+    pub valid_before: Option<i64>,
+    /// The public key.
+    pub key: ssh_key::public::KeyData,
+}
+
+impl Entry {
+    /// Return `true` if this entry allows its key to sign in `namespace` at `time` in seconds since the Unix epoch,
+    /// with the time only being checked if it's known.
+    pub fn allows(&self, namespace: &str, time: Option<i64>) -> bool {
+        !self.cert_authority
+            && self.namespaces.as_ref().map_or(true, |namespaces| {
+                namespaces
+                    .split_str(",")
+                    .any(|candidate| candidate.trim_with(|c| c.is_ascii_whitespace()) == namespace.as_bytes())
+            })
+            && time.map_or(true, |time| {
+                self.valid_after.map_or(true, |after| time >= after)
+                    && self.valid_before.map_or(true, |before| time <= before)
+            })
+    }
+}
+
+/// The error returned by [`parse()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line_number} of the allowed signers file doesn't contain a key")]
+    MissingKey { line_number: usize },
+    #[error("Line {line_number} of the allowed signers file has an unknown option: '{option}'")]
+    UnknownOption { line_number: usize, option: BString },
+    #[error("Line {line_number} of the allowed signers file has an invalid time: '{time}'")]
+    InvalidTime { line_number: usize, time: BString },
+    #[error("Line {line_number} of the allowed signers file has an invalid key")]
+    Key { line_number: usize, source: ssh_key::Error },
+}
+
+/// Parse the contents of an allowed signers file, skipping empty lines and comments.
+///
+/// Times in `valid-after` and `valid-before` options are in the local time zone unless they end with `Z`.
+pub fn parse(data: &[u8]) -> Result<Vec<Entry>, Error> {
+    let mut out = Vec::new();
+    for (line_number, line) in data
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim_with(|c| c.is_ascii_whitespace())))
+    {
+        if line.is_empty() || line.starts_with(b"#") {
+            continue;
+        }
+        let (principals, rest) = split_field(line);
+        let mut entry = Options::default();
+        let key = match parse_key(rest) {
+            Some(key) => key,
+            None => {
+                let (options, key) = split_field(rest);
+                for option in split_options(options) {
+                    entry.apply(option, line_number)?;
+                }
+                if key.is_empty() {
+                    return Err(Error::MissingKey { line_number });
+                }
+                ssh_key::PublicKey::from_openssh(key.to_str_lossy().as_ref())
+                    .map_err(|source| Error::Key { line_number, source })?
+            }
+        };
+        out.push(Entry {
+            principals: unquote(principals),
+            cert_authority: entry.cert_authority,
+            namespaces: entry.namespaces,
+            valid_after: entry.valid_after,
+            valid_before: entry.valid_before,
+            key: key.key_data().clone(),
+        });
+    }
+    Ok(out)
+}
+
+#[derive(Default)]
+struct Options {
+    cert_authority: bool,
+    namespaces: Option<BString>,
+    valid_after: Option<i64>,
+    valid_before: Option<i64>,
+}
+
+impl Options {
+    fn apply(&mut self, option: &[u8], line_number: usize) -> Result<(), Error> {
+        let (name, value) = option.split_once_str("=").unwrap_or((option, b""));
+        let value = unquote(value);
+        let time = || {
+            parse_time(value.as_ref()).ok_or_else(|| Error::InvalidTime {
+                line_number,
+                time: value.clone(),
+            })
+        };
+        match name.to_ascii_lowercase().as_slice() {
+            b"cert-authority" => self.cert_authority = true,
+            b"namespaces" => self.namespaces = Some(value.clone()),
+            b"valid-after" => self.valid_after = Some(time()?),
+            b"valid-before" => self.valid_before = Some(time()?),
+            _ => {
+                return Err(Error::UnknownOption {
+                    line_number,
+                    option: option.into(),
+                })
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_key(input: &[u8]) -> Option<ssh_key::PublicKey> {
+    ssh_key::PublicKey::from_openssh(input.to_str().ok()?).ok()
+}
+
+/// Split the first whitespace-separated field off `input`, respecting double quotes, and return it along with the trimmed rest.
+fn split_field(input: &[u8]) -> (&[u8], &[u8]) {
+    let mut in_quotes = false;
+    for (pos, byte) in input.iter().enumerate() {
+        match byte {
+            b'"' => in_quotes = !in_quotes,
+            b' ' | b'\t' if !in_quotes => {
+                return (&input[..pos], input[pos..].trim_start_with(|c| c.is_ascii_whitespace()))
+            }
+            _ => {}
+        }
+    }
+    (input, &[])
+}
+
+/// Split comma-separated options, respecting double quotes.
+fn split_options(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut in_quotes = false;
+    input
+        .split(move |byte| {
+            if *byte == b'"' {
+                in_quotes = !in_quotes;
+            }
+            *byte == b',' && !in_quotes
+        })
+        .filter(|option| !option.is_empty())
+}
+
+fn unquote(value: &[u8]) -> BString {
+    value
+        .strip_prefix(b"\"")
+        .and_then(|value| value.strip_suffix(b"\""))
+        .unwrap_or(value)
+        .into()
+}
+
+/// Parse a time like `YYYYMMDD[HHMM[SS]][Z]` into seconds since the Unix epoch.
+fn parse_time(input: &[u8]) -> Option<i64> {
+    let (digits, is_utc) = match input.strip_suffix(b"Z").or_else(|| input.strip_suffix(b"z")) {
+        Some(digits) => (digits, true),
+        None => (input, false),
+    };
+    if !matches!(digits.len(), 8 | 12 | 14) || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let number = |range: std::ops::Range<usize>| -> Option<i64> { digits.get(range)?.to_str().ok()?.parse().ok() };
+    let datetime = jiff::civil::DateTime::new(
+        number(0..4)? as i16,
+        number(4..6)? as i8,
+        number(6..8)? as i8,
+        number(8..10).unwrap_or(0) as i8,
+        number(10..12).unwrap_or(0) as i8,
+        number(12..14).unwrap_or(0) as i8,
+        0,
+    )
+    .ok()?;
+    let tz = if is_utc {
+        jiff::tz::TimeZone::UTC
+    } else {
+        jiff::tz::TimeZone::system()
+    };
+    Some(datetime.to_zoned(tz).ok()?.timestamp().as_second())
+}
@@ -0,0 +1,119 @@
+//! Verify SSH signatures natively, with results similar to what `git` obtains by running `ssh-keygen -Y verify`.
+use bstr::{BString, ByteSlice};
+pub use ssh_key;
+use ssh_key::{public::KeyData, Algorithm, HashAlg, SshSig};
+
+use crate::{Format, Status, Trust, Verification};
+
+///
+pub mod allowed_signers;
+
+/// The namespace that `git` uses for its signatures.
+pub const NAMESPACE: &str = "git";
+
+/// The keys to check signatures against.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options<'a> {
+    /// The [parsed](allowed_signers::parse()) entries of the allowed signers file configured in `gpg.ssh.allowedSignersFile`.
+    pub allowed_signers: &'a [allowed_signers::Entry],
+    /// The [parsed](parse_revoked_keys()) keys of the file configured in `gpg.ssh.revocationFile`.
+    pub revoked_keys: &'a [KeyData],
+    /// The time at which the data was signed in seconds since the Unix epoch, typically the time of the committer or tagger.
+    ///
+    /// If set, the validity period of keys in the allowed signers file is checked against it.
+    pub time: Option<i64>,
+}
+
+///
+pub mod verify {
+    /// The error returned by [`verify()`](super::verify()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not decode the SSH signature")]
+        Decode(#[from] ssh_key::Error),
+    }
+}
+
+/// Verify the SSH `signature` of `data` and find the principal that signed it with the keys in `options`.
+///
+/// A signature that doesn't match the data is [bad](Status::Bad), while a matching signature of a key without a principal in the
+/// allowed signers file has [unknown validity](Status::UnknownValidity). Principals of matching keys are fully trusted.
+/// Only a signature that can't be decoded is an error.
+pub fn verify(data: &[u8], signature: &[u8], options: Options<'_>) -> Result<Verification, verify::Error> {
+    let signature = SshSig::from_pem(signature)?;
+    let key = signature.public_key();
+    let fingerprint: BString = key.fingerprint(HashAlg::Sha256).to_string().into();
+    let mut out = Verification {
+        format: Format::Ssh,
+        status: Status::Bad,
+        signer: None,
+        key: Some(fingerprint.clone()),
+        fingerprint: Some(fingerprint.clone()),
+        primary_key_fingerprint: None,
+        trust: Trust::Never,
+        output: "Could not verify signature.\n".into(),
+    };
+    if ssh_key::PublicKey::from(key.clone())
+        .verify(NAMESPACE, data, &signature)
+        .is_err()
+    {
+        return Ok(out);
+    }
+
+    let key_name = key_type_name(key.algorithm());
+    if options.revoked_keys.contains(key) {
+        out.status = Status::RevokedKey;
+        out.output = format!("Signature made with revoked {key_name} key {fingerprint}\n").into();
+        return Ok(out);
+    }
+    match options
+        .allowed_signers
+        .iter()
+        .find(|entry| &entry.key == key && entry.allows(NAMESPACE, options.time))
+    {
+        Some(entry) => {
+            out.status = Status::Good;
+            out.trust = Trust::Fully;
+            out.output = format!(
+                "Good \"{NAMESPACE}\" signature for {} with {key_name} key {fingerprint}\n",
+                entry.principals
+            )
+            .into();
+            out.signer = Some(entry.principals.clone());
+        }
+        None => {
+            out.status = Status::UnknownValidity;
+            out.trust = Trust::Undefined;
+            out.output =
+                format!("Good \"{NAMESPACE}\" signature with {key_name} key {fingerprint}\nNo principal matched.\n")
+                    .into();
+        }
+    }
+    Ok(out)
+}
+
+/// Parse the plain list of public keys in a revocation file as configured in `gpg.ssh.revocationFile`,
+/// skipping empty lines and comments.
+///
+/// Note that key revocation lists in the binary format of `ssh-keygen -k` aren't supported.
+pub fn parse_revoked_keys(data: &[u8]) -> Result<Vec<KeyData>, ssh_key::Error> {
+    data.lines()
+        .map(|line| line.trim_with(|c| c.is_ascii_whitespace()))
+        .filter(|line| !line.is_empty() && !line.starts_with(b"#"))
+        .map(|line| ssh_key::PublicKey::from_openssh(line.to_str_lossy().as_ref()).map(|key| key.key_data().clone()))
+        .collect()
+}
+
+/// The name of the key type as shown by `ssh-keygen`.
+fn key_type_name(algorithm: Algorithm) -> String {
+    match algorithm {
+        Algorithm::Dsa => "DSA".into(),
+        Algorithm::Ecdsa { .. } => "ECDSA".into(),
+        Algorithm::Ed25519 => "ED25519".into(),
+        Algorithm::Rsa { .. } => "RSA".into(),
+        Algorithm::SkEcdsaSha2NistP256 => "ECDSA-SK".into(),
+        Algorithm::SkEd25519 => "ED25519-SK".into(),
+        other => other.as_str().to_owned(),
+    }
+}
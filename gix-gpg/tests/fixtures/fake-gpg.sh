@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+echo "args: $*" >&2
+if [[ "$1" == --status-fd=2 && "$2" == -bsau ]]; then
+  # Pretend to be `gpg --status-fd=2 -bsau <key>` and sign with any key but 'unknown', using CRLF line endings.
+  if [[ "$3" == unknown ]]; then
+    echo "gpg: skipped \"unknown\": No secret key" >&2
+    exit 2
+  fi
+  echo "[GNUPG:] SIG_CREATED D 1 8 00 1234567890 0123456789ABCDEF" >&2
+  printf -- "-----BEGIN PGP SIGNATURE-----\r\n\r\n%s by %s\r\n-----END PGP SIGNATURE-----\r\n" "$(cat)" "$3"
+  exit 0
+fi
+
+# Pretend to be `gpg --status-fd=1 --verify <file> -` and accept only the signature 'good' of the data 'data'.
+signature_file="${@: -2:1}"
+if [[ "$(cat "$signature_file")" == good && "$(cat)" == data ]]; then
+  echo "[GNUPG:] GOODSIG 0123456789ABCDEF A U Thor <author@example.com>"
+  echo "[GNUPG:] TRUST_ULTIMATE 0 pgp"
+else
+  echo "[GNUPG:] BADSIG 0123456789ABCDEF A U Thor <author@example.com>"
+  exit 1
+fi
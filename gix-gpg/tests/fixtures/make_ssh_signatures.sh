@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+ssh-keygen -q -t ed25519 -N "" -C alice -f alice
+ssh-keygen -q -t ecdsa -b 256 -N "" -C bob -f bob
+ssh-keygen -q -t ed25519 -N "" -C mallory -f mallory
+
+printf 'signed data\n' > data
+for signer in alice bob mallory; do
+  ssh-keygen -q -Y sign -n git -f $signer data
+  mv data.sig $signer.sig
+done
+ssh-keygen -q -Y sign -n file -f alice data
+mv data.sig alice-file-namespace.sig
+
+{
+  echo "# trusted signers"
+  echo "alice@example.com namespaces=\"git\" $(cat alice.pub)"
+  echo "\"bob@example.com,robert@example.com\" valid-after=\"20000101\",valid-before=\"20000102Z\" $(cat bob.pub)"
+} > allowed_signers
+cp mallory.pub revoked_keys
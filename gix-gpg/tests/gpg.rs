@@ -0,0 +1,29 @@
+mod program;
+#[cfg(feature = "ssh")]
+mod ssh;
+
+#[test]
+fn format_from_signature() {
+    use gix_gpg::Format;
+    assert_eq!(
+        Format::from_signature(b"-----BEGIN PGP SIGNATURE-----\n"),
+        Some(Format::OpenPgp)
+    );
+    assert_eq!(
+        Format::from_signature(b"-----BEGIN SIGNED MESSAGE-----\n"),
+        Some(Format::X509)
+    );
+    assert_eq!(
+        Format::from_signature(b"-----BEGIN SSH SIGNATURE-----\n"),
+        Some(Format::Ssh)
+    );
+    assert_eq!(Format::from_signature(b"no signature"), None);
+}
+
+#[test]
+fn signature_start() {
+    let tag = b"message\n-----BEGIN PGP SIGNATURE-----\nold\n-----END PGP SIGNATURE-----\n-----BEGIN SSH SIGNATURE-----\nsig\n";
+    assert_eq!(gix_gpg::signature_start(tag), Some(70), "the last signature is used");
+    assert_eq!(gix_gpg::signature_start(b"message\n"), None);
+    assert_eq!(gix_gpg::signature_start(b"-----BEGIN PGP SIGNATURE-----"), Some(0));
+}
@@ -0,0 +1,175 @@
+use gix_gpg::{program, Format, Status, Trust};
+
+mod parse_status {
+    use super::*;
+
+    #[test]
+    fn good_signature() {
+        let status = b"[GNUPG:] NEWSIG\n[GNUPG:] KEY_CONSIDERED 1234567890ABCDEF1234567890ABCDEF12345678 0
+[GNUPG:] SIG_ID abc 2024-01-01 1704067200
+[GNUPG:] GOODSIG 90ABCDEF12345678 A U Thor <author@example.com>
+[GNUPG:] VALIDSIG AAAA1111AAAA1111AAAA1111AAAA1111AAAA1111 2024-01-01 1704067200 0 4 0 22 10 00 1234567890ABCDEF1234567890ABCDEF12345678
+[GNUPG:] TRUST_ULTIMATE 0 pgp\n";
+        let out = program::parse_status(Format::OpenPgp, status);
+        assert_eq!(out.status, Status::Good);
+        assert_eq!(out.key.as_ref().map(AsRef::as_ref), Some("90ABCDEF12345678".as_bytes()));
+        assert_eq!(
+            out.signer.as_ref().map(AsRef::as_ref),
+            Some("A U Thor <author@example.com>".as_bytes())
+        );
+        assert_eq!(
+            out.fingerprint.as_ref().map(AsRef::as_ref),
+            Some("AAAA1111AAAA1111AAAA1111AAAA1111AAAA1111".as_bytes())
+        );
+        assert_eq!(
+            out.primary_key_fingerprint.as_ref().map(AsRef::as_ref),
+            Some("1234567890ABCDEF1234567890ABCDEF12345678".as_bytes())
+        );
+        assert_eq!(out.trust, Trust::Ultimate);
+        assert!(out.is_good(Trust::Fully));
+    }
+
+    #[test]
+    fn untrusted_good_signature_does_not_meet_higher_trust_levels() {
+        let status =
+            b"[GNUPG:] GOODSIG 90ABCDEF12345678 A U Thor <author@example.com>\n[GNUPG:] TRUST_UNDEFINED 0 pgp\n";
+        let out = program::parse_status(Format::OpenPgp, status);
+        assert_eq!(out.status, Status::Good);
+        assert_eq!(out.trust, Trust::Undefined);
+        assert!(out.is_good(Trust::Undefined), "by default, no trust is required");
+        assert!(!out.is_good(Trust::Marginal));
+    }
+
+    #[test]
+    fn missing_key() {
+        let status = b"[GNUPG:] ERRSIG 90ABCDEF12345678 1 10 00 1704067200 9 -\n[GNUPG:] NO_PUBKEY 90ABCDEF12345678\n";
+        let out = program::parse_status(Format::OpenPgp, status);
+        assert_eq!(out.status, Status::Unverifiable);
+        assert_eq!(out.key.as_ref().map(AsRef::as_ref), Some("90ABCDEF12345678".as_bytes()));
+        assert_eq!(out.signer, None, "the signer isn't known");
+        assert!(!out.is_good(Trust::Undefined));
+    }
+
+    #[test]
+    fn expired_and_revoked_keys() {
+        for (keyword, expected) in [
+            ("EXPSIG", Status::ExpiredSignature),
+            ("EXPKEYSIG", Status::ExpiredKey),
+            ("REVKEYSIG", Status::RevokedKey),
+            ("BADSIG", Status::Bad),
+        ] {
+            let status = format!("[GNUPG:] {keyword} 90ABCDEF12345678 A U Thor <author@example.com>\n");
+            let out = program::parse_status(Format::X509, status.as_bytes());
+            assert_eq!(out.status, expected);
+            assert_eq!(out.format, Format::X509);
+            assert!(!out.is_good(Trust::Undefined));
+        }
+    }
+
+    #[test]
+    fn multiple_signatures_are_unverifiable() {
+        let status = b"[GNUPG:] GOODSIG 90ABCDEF12345678 A U Thor <author@example.com>
+[GNUPG:] GOODSIG 1111111111111111 C O Mitter <committer@example.com>\n";
+        let out = program::parse_status(Format::OpenPgp, status);
+        assert_eq!(out.status, Status::Unverifiable);
+        assert_eq!(out.signer, None);
+    }
+
+    #[test]
+    fn no_status_is_unverifiable() {
+        let out = program::parse_status(Format::OpenPgp, b"");
+        assert_eq!(out.status, Status::Unverifiable);
+    }
+}
+
+#[cfg(unix)]
+fn fake_gpg() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fake-gpg.sh")
+}
+
+#[cfg(unix)]
+mod sign {
+    use gix_gpg::{program, Format, Signer};
+
+    use super::fake_gpg;
+
+    #[test]
+    fn passes_data_and_key_to_the_program_and_removes_carriage_returns() -> Result<(), program::sign::Error> {
+        let signature = program::sign(fake_gpg(), Format::OpenPgp, b"A U Thor <author@example.com>", b"data")?;
+        assert_eq!(
+            signature,
+            "-----BEGIN PGP SIGNATURE-----\n\ndata by A U Thor <author@example.com>\n-----END PGP SIGNATURE-----\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn program_signer() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let signer = program::Signer {
+            program: fake_gpg().into(),
+            format: Format::X509,
+            key: "key".into(),
+        };
+        assert_eq!(
+            signer.sign(b"data")?,
+            "-----BEGIN PGP SIGNATURE-----\n\ndata by key\n-----END PGP SIGNATURE-----\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn failing_program() {
+        match program::sign(fake_gpg(), Format::OpenPgp, b"unknown", b"data") {
+            Err(program::sign::Error::Failed { stderr, .. }) => {
+                assert!(stderr.ends_with(b"gpg: skipped \"unknown\": No secret key"));
+            }
+            other => unreachable!("expected a failure, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_program() {
+        assert!(matches!(
+            program::sign("does-not-exist-gpg", Format::OpenPgp, b"key", b"data"),
+            Err(program::sign::Error::Spawn { .. })
+        ));
+    }
+}
+
+#[cfg(unix)]
+mod verify {
+    use super::*;
+
+    #[test]
+    fn passes_data_and_signature_to_the_program() -> Result<(), program::verify::Error> {
+        let out = program::verify(fake_gpg(), Format::OpenPgp, b"data", b"good")?;
+        assert_eq!(out.status, Status::Good);
+        assert_eq!(out.trust, Trust::Ultimate);
+        assert!(
+            out.output
+                .starts_with(b"args: --keyid-format=long --status-fd=1 --verify "),
+            "stderr becomes the output"
+        );
+
+        let out = program::verify(fake_gpg(), Format::X509, b"other data", b"good")?;
+        assert_eq!(out.status, Status::Bad, "a failing program isn't an error");
+        assert!(out.output.starts_with(b"args: --status-fd=1 --verify "));
+        Ok(())
+    }
+
+    #[test]
+    fn ssh_signatures_are_unsupported() {
+        assert!(matches!(
+            program::verify(fake_gpg(), Format::Ssh, b"data", b"good"),
+            Err(program::verify::Error::UnsupportedFormat { format: Format::Ssh })
+        ));
+    }
+
+    #[test]
+    fn missing_program() {
+        assert!(matches!(
+            program::verify("does-not-exist-gpg", Format::OpenPgp, b"data", b"good"),
+            Err(program::verify::Error::Spawn { .. })
+        ));
+    }
+}
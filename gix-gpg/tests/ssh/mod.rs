@@ -0,0 +1,209 @@
+use gix_gpg::{ssh, Status, Trust};
+
+use gix_testtools::Result;
+
+struct Fixture {
+    dir: std::path::PathBuf,
+    allowed_signers: Vec<ssh::allowed_signers::Entry>,
+    revoked_keys: Vec<ssh::ssh_key::public::KeyData>,
+}
+
+impl Fixture {
+    fn new() -> Result<Self> {
+        let dir = gix_testtools::scripted_fixture_read_only("make_ssh_signatures.sh")?;
+        Ok(Fixture {
+            allowed_signers: ssh::allowed_signers::parse(&std::fs::read(dir.join("allowed_signers"))?)?,
+            revoked_keys: ssh::parse_revoked_keys(&std::fs::read(dir.join("revoked_keys"))?)?,
+            dir,
+        })
+    }
+
+    fn read(&self, name: &str) -> Vec<u8> {
+        std::fs::read(self.dir.join(name)).expect("fixture file exists")
+    }
+
+    fn options(&self, time: Option<i64>) -> ssh::Options<'_> {
+        ssh::Options {
+            allowed_signers: &self.allowed_signers,
+            revoked_keys: &self.revoked_keys,
+            time,
+        }
+    }
+}
+
+#[test]
+fn good_signature_of_allowed_signer() -> Result {
+    let fixture = Fixture::new()?;
+    let out = ssh::verify(&fixture.read("data"), &fixture.read("alice.sig"), fixture.options(None))?;
+    assert_eq!(out.status, Status::Good);
+    assert_eq!(out.trust, Trust::Fully);
+    assert_eq!(
+        out.signer.as_ref().map(AsRef::as_ref),
+        Some("alice@example.com".as_bytes())
+    );
+    let fingerprint = out.fingerprint.clone().expect("always set");
+    assert!(fingerprint.starts_with(b"SHA256:"));
+    assert_eq!(out.key.as_ref(), Some(&fingerprint));
+    assert_eq!(
+        out.output,
+        format!("Good \"git\" signature for alice@example.com with ED25519 key {fingerprint}\n")
+    );
+    assert!(out.is_good(Trust::Fully));
+    Ok(())
+}
+
+#[test]
+fn signature_created_with_ssh_keygen_is_good() -> Result {
+    let fixture = Fixture::new()?;
+    let signature = gix_gpg::program::sign(
+        "ssh-keygen",
+        gix_gpg::Format::Ssh,
+        fixture.dir.join("alice").to_str().expect("valid UTF-8").as_bytes(),
+        b"data to sign\n",
+    )?;
+    assert!(signature.starts_with(b"-----BEGIN SSH SIGNATURE-----\n"));
+    assert!(signature.ends_with(b"-----END SSH SIGNATURE-----\n"));
+
+    let out = ssh::verify(b"data to sign\n", &signature, fixture.options(None))?;
+    assert_eq!(out.status, Status::Good);
+    assert_eq!(
+        out.signer.as_ref().map(AsRef::as_ref),
+        Some("alice@example.com".as_bytes())
+    );
+    Ok(())
+}
+
+#[test]
+fn modified_data_is_a_bad_signature() -> Result {
+    let fixture = Fixture::new()?;
+    let out = ssh::verify(
+        b"signed data, modified\n",
+        &fixture.read("alice.sig"),
+        fixture.options(None),
+    )?;
+    assert_eq!(out.status, Status::Bad);
+    assert_eq!(out.trust, Trust::Never);
+    assert_eq!(out.signer, None);
+    assert!(!out.is_good(Trust::Undefined));
+    Ok(())
+}
+
+#[test]
+fn signatures_in_other_namespaces_are_bad() -> Result {
+    let fixture = Fixture::new()?;
+    let out = ssh::verify(
+        &fixture.read("data"),
+        &fixture.read("alice-file-namespace.sig"),
+        fixture.options(None),
+    )?;
+    assert_eq!(out.status, Status::Bad);
+    Ok(())
+}
+
+#[test]
+fn validity_period_is_checked_against_the_signing_time() -> Result {
+    let fixture = Fixture::new()?;
+    let (data, signature) = (fixture.read("data"), fixture.read("bob.sig"));
+    let out = ssh::verify(&data, &signature, fixture.options(None))?;
+    assert_eq!(
+        out.status,
+        Status::Good,
+        "without time, the validity period isn't checked"
+    );
+    assert_eq!(
+        out.signer.as_ref().map(AsRef::as_ref),
+        Some("bob@example.com,robert@example.com".as_bytes())
+    );
+    assert!(out
+        .output
+        .starts_with(b"Good \"git\" signature for bob@example.com,robert@example.com with ECDSA key SHA256:"));
+
+    let out = ssh::verify(&data, &signature, fixture.options(Some(1_700_000_000)))?;
+    assert_eq!(
+        out.status,
+        Status::UnknownValidity,
+        "the key isn't valid anymore, so there is no principal"
+    );
+    assert_eq!(out.trust, Trust::Undefined);
+    assert_eq!(out.signer, None);
+    assert!(!out.is_good(Trust::Undefined));
+    Ok(())
+}
+
+#[test]
+fn unknown_and_revoked_keys() -> Result {
+    let fixture = Fixture::new()?;
+    let (data, signature) = (fixture.read("data"), fixture.read("mallory.sig"));
+    let out = ssh::verify(&data, &signature, ssh::Options::default())?;
+    assert_eq!(out.status, Status::UnknownValidity);
+    assert!(out.output.ends_with(b"No principal matched.\n"));
+
+    let out = ssh::verify(&data, &signature, fixture.options(None))?;
+    assert_eq!(out.status, Status::RevokedKey);
+    Ok(())
+}
+
+#[test]
+fn invalid_signatures_are_errors() {
+    assert!(ssh::verify(
+        b"data",
+        b"-----BEGIN SSH SIGNATURE-----\ninvalid\n-----END SSH SIGNATURE-----\n",
+        Default::default()
+    )
+    .is_err());
+}
+
+mod allowed_signers {
+    use gix_gpg::ssh::allowed_signers;
+
+    const KEY: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHQNGeKXNkXP4ZWWx3TRGfaoS9jVE8CXfDqDN5J24hBN";
+
+    #[test]
+    fn options() -> Result<(), allowed_signers::Error> {
+        let entries = allowed_signers::parse(
+            format!(
+                "# comment\n\n\"a@example.com,b@example.com\" cert-authority,namespaces=\"git,file\",valid-after=20240101Z,valid-before=\"202401021230Z\" {KEY} comment\nc@example.com {KEY}\n"
+            )
+            .as_bytes(),
+        )?;
+        assert_eq!(entries.len(), 2);
+        let first = &entries[0];
+        assert_eq!(first.principals, "a@example.com,b@example.com");
+        assert!(first.cert_authority);
+        assert_eq!(first.namespaces.as_ref().expect("set"), "git,file");
+        assert_eq!(first.valid_after, Some(1_704_067_200));
+        assert_eq!(first.valid_before, Some(1_704_198_600));
+        assert!(!first.allows("git", None), "certificate authorities aren't used");
+
+        let second = &entries[1];
+        assert_eq!(second.principals, "c@example.com");
+        assert_eq!(second.key, first.key);
+        assert!(second.allows("git", Some(0)));
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        for (input, expected) in [
+            (
+                "a@example.com\n",
+                "Line 1 of the allowed signers file doesn't contain a key",
+            ),
+            (
+                "\na@example.com unknown=1 ssh-ed25519 AAAA\n",
+                "Line 2 of the allowed signers file has an unknown option: 'unknown=1'",
+            ),
+            (
+                "a@example.com valid-after=2024 ssh-ed25519 AAAA\n",
+                "Line 1 of the allowed signers file has an invalid time: '2024'",
+            ),
+            (
+                "a@example.com namespaces=git ssh-ed25519 invalid\n",
+                "Line 1 of the allowed signers file has an invalid key",
+            ),
+        ] {
+            let err = allowed_signers::parse(input.as_bytes()).unwrap_err();
+            assert_eq!(err.to_string(), expected);
+        }
+    }
+}
@@ -6,10 +6,6 @@ use filetime::FileTime;
 use crate::entry::{Stage, StageRaw};
 use crate::{entry, extension, AccelerateLookup, Entry, PathStorage, PathStorageRef, State, Version};
 
-// TODO: integrate this somehow, somewhere, depending on later usage.
-#[allow(dead_code)]
-mod sparse;
-
 /// General information and entries
 impl State {
     /// Return the version used to store this state's information on disk.
@@ -473,6 +469,8 @@ impl State {
     ///
     /// Alternatively, make sure to call [`sort_entries()`][State::sort_entries()] before entry lookup by path to restore
     /// the invariant.
+    ///
+    /// The [untracked cache](State::untracked()) is invalidated for the directory containing `path`, if present.
     pub fn dangerously_push_entry(
         &mut self,
         stat: entry::Stat,
@@ -481,6 +479,9 @@ impl State {
         mode: entry::Mode,
         path: &BStr,
     ) {
+        if let Some(untracked) = self.untracked.as_mut() {
+            untracked.invalidate_path(path);
+        }
         let path = {
             let path_start = self.path_backing.len();
             self.path_backing.push_str(path);
@@ -519,6 +520,7 @@ impl State {
     /// Physically remove all entries for which `should_remove(idx, path, entry)` returns `true`, traversing them from first to last.
     ///
     /// Note that the memory used for the removed entries paths is not freed, as it's append-only.
+    /// The [untracked cache](State::untracked()) is invalidated for the directories containing removed entries, if present.
     ///
     /// ### Performance
     ///
@@ -527,10 +529,16 @@ impl State {
     pub fn remove_entries(&mut self, mut should_remove: impl FnMut(usize, &BStr, &mut Entry) -> bool) {
         let mut index = 0;
         let paths = &self.path_backing;
+        let untracked = &mut self.untracked;
         self.entries.retain_mut(|e| {
             let path = e.path_in(paths);
             let res = !should_remove(index, path, e);
             index += 1;
+            if !res {
+                if let Some(untracked) = untracked.as_mut() {
+                    untracked.invalidate_path(path);
+                }
+            }
             res
         });
     }
@@ -542,6 +550,12 @@ impl State {
     pub fn tree(&self) -> Option<&extension::Tree> {
         self.tree.as_ref()
     }
+    /// Set the `tree` extension to `tree`, or remove it if `None`, returning the previous one.
+    ///
+    /// Use this to remove the tree extension after changing entries in ways that would make it outdated.
+    pub fn set_tree(&mut self, tree: Option<extension::Tree>) -> Option<extension::Tree> {
+        std::mem::replace(&mut self.tree, tree)
+    }
     /// Access the `link` extension.
     pub fn link(&self) -> Option<&extension::Link> {
         self.link.as_ref()
@@ -554,10 +568,30 @@ impl State {
     pub fn untracked(&self) -> Option<&extension::UntrackedCache> {
         self.untracked.as_ref()
     }
+    /// Obtain the untracked extension for modification.
+    pub fn untracked_mut(&mut self) -> Option<&mut extension::UntrackedCache> {
+        self.untracked.as_mut()
+    }
+    /// Set the untracked extension to `untracked`, or remove it if `None`, returning the previous one.
+    pub fn set_untracked(&mut self, untracked: Option<extension::UntrackedCache>) -> Option<extension::UntrackedCache> {
+        std::mem::replace(&mut self.untracked, untracked)
+    }
     /// Obtain the fsmonitor extension.
     pub fn fs_monitor(&self) -> Option<&extension::FsMonitor> {
         self.fs_monitor.as_ref()
     }
+    /// Set the fsmonitor extension to `fs_monitor`, or remove it if `None`, returning the previous one.
+    ///
+    /// When removing it, all entries lose their [`FSMONITOR_VALID`](entry::Flags::FSMONITOR_VALID) flag as well
+    /// as nothing vouches for their validity anymore.
+    pub fn set_fs_monitor(&mut self, fs_monitor: Option<extension::FsMonitor>) -> Option<extension::FsMonitor> {
+        if fs_monitor.is_none() {
+            for entry in &mut self.entries {
+                entry.flags.remove(entry::Flags::FSMONITOR_VALID);
+            }
+        }
+        std::mem::replace(&mut self.fs_monitor, fs_monitor)
+    }
     /// Return `true` if the end-of-index extension was present when decoding this index.
     pub fn had_end_of_index_marker(&self) -> bool {
         self.end_of_index_at_decode_time
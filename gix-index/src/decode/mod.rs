@@ -225,7 +225,7 @@ impl State {
             }
         }
         let EntriesOutcome {
-            entries,
+            mut entries,
             path_backing,
             mut is_sparse,
         } = entries;
@@ -240,6 +240,9 @@ impl State {
             offset_table,
         } = ext;
         is_sparse |= is_sparse_from_ext;
+        if let Some(fs_monitor) = &fs_monitor {
+            extension::fs_monitor::apply_to_entries(fs_monitor, &mut entries);
+        }
 
         Ok((
             State {
@@ -311,13 +314,13 @@ pub(crate) fn stat(data: &[u8]) -> Option<(entry::Stat, &[u8])> {
     Some((
         entry::Stat {
             mtime: entry::stat::Time {
-                secs: ctime_secs,
-                nsecs: ctime_nsecs,
-            },
-            ctime: entry::stat::Time {
                 secs: mtime_secs,
                 nsecs: mtime_nsecs,
             },
+            ctime: entry::stat::Time {
+                secs: ctime_secs,
+                nsecs: ctime_nsecs,
+            },
             dev,
             ino,
             uid,
@@ -1,19 +1,31 @@
 use bstr::BString;
 
 use crate::{
+    entry,
     extension::{FsMonitor, Signature},
     util::{read_u32, read_u64, split_at_byte_exclusive},
+    Entry,
 };
 
-#[derive(Clone)]
-#[allow(dead_code)]
+/// The token to pass to the filesystem monitor to learn about all changes since it was obtained.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Token {
-    V1 { nanos_since_1970: u64 },
-    V2 { token: BString },
+    /// The time at which the filesystem monitor was last queried, as understood by version 1 of the hook protocol.
+    V1 {
+        /// The amount of nanoseconds since the unix epoch.
+        nanos_since_1970: u64,
+    },
+    /// An opaque token provided by the filesystem monitor, as used by version 2 of the hook protocol.
+    V2 {
+        /// The token itself.
+        token: BString,
+    },
 }
 
+/// Only used as an indicator
 pub const SIGNATURE: Signature = *b"FSMN";
 
+/// Decode a filesystem monitor extension from `data`.
 pub fn decode(data: &[u8]) -> Option<FsMonitor> {
     let (version, data) = read_u32(data)?;
     let (token, data) = match version {
@@ -37,3 +49,52 @@ pub fn decode(data: &[u8]) -> Option<FsMonitor> {
 
     FsMonitor { token, entry_dirty }.into()
 }
+
+/// Mark all `entries` as [valid](entry::Flags::FSMONITOR_VALID) unless they are marked dirty in `fs_monitor`.
+pub(crate) fn apply_to_entries(fs_monitor: &FsMonitor, entries: &mut [Entry]) {
+    for entry in entries.iter_mut() {
+        entry.flags.insert(entry::Flags::FSMONITOR_VALID);
+    }
+    fs_monitor.entry_dirty.for_each_set_bit(|index| {
+        entries.get_mut(index)?.flags.remove(entry::Flags::FSMONITOR_VALID);
+        Some(())
+    });
+}
+
+/// Serialize the filesystem monitor extension with `token` to `out`, marking all `entries` as dirty unless they
+/// are [valid](entry::Flags::FSMONITOR_VALID).
+///
+/// Entries marked for [removal](entry::Flags::REMOVE) are skipped as they won't be written.
+pub fn write_to(mut out: impl std::io::Write, token: &Token, entries: &[Entry]) -> Result<(), std::io::Error> {
+    let mut buf = Vec::new();
+    match token {
+        Token::V1 { nanos_since_1970 } => {
+            buf.extend_from_slice(&1_u32.to_be_bytes());
+            buf.extend_from_slice(&nanos_since_1970.to_be_bytes());
+        }
+        Token::V2 { token } => {
+            buf.extend_from_slice(&2_u32.to_be_bytes());
+            buf.extend_from_slice(token);
+            buf.push(0);
+        }
+    }
+
+    let mut entry_dirty = gix_bitmap::ewah::Vec::default();
+    for (index, entry) in entries
+        .iter()
+        .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+        .enumerate()
+    {
+        if !entry.flags.contains(entry::Flags::FSMONITOR_VALID) {
+            entry_dirty.set(index);
+        }
+    }
+    let mut bitmap = Vec::new();
+    entry_dirty.write_to(&mut bitmap)?;
+    buf.extend_from_slice(&(u32::try_from(bitmap.len()).expect("less than 4GB bitmap")).to_be_bytes());
+    buf.extend_from_slice(&bitmap);
+
+    out.write_all(&SIGNATURE)?;
+    out.write_all(&(u32::try_from(buf.len()).expect("less than 4GB fsmonitor extension")).to_be_bytes())?;
+    out.write_all(&buf)
+}
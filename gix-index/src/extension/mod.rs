@@ -42,36 +42,40 @@ pub struct Link {
 }
 
 /// The extension for untracked files.
-#[allow(dead_code)]
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UntrackedCache {
     /// Something identifying the location and machine that this cache is for.
     /// Should the repository be copied to a different machine, the entire cache can immediately be invalidated.
-    identifier: BString,
+    pub identifier: BString,
     /// Stat for the .git/info/exclude file
-    info_exclude: Option<untracked_cache::OidStat>,
+    pub info_exclude: Option<untracked_cache::OidStat>,
     /// Stat for the `core.excludesfile`
-    excludes_file: Option<untracked_cache::OidStat>,
+    pub excludes_file: Option<untracked_cache::OidStat>,
     /// Usually `.gitignore`
-    exclude_filename_per_dir: BString,
-    dir_flags: u32,
+    pub exclude_filename_per_dir: BString,
+    /// The flags of the directory walk that produced this cache, see [`untracked_cache::dir_flags`].
+    pub dir_flags: u32,
 
     /// A list of directories and sub-directories, with `directories[0]` being the root.
-    directories: Vec<untracked_cache::Directory>,
+    pub directories: Vec<untracked_cache::Directory>,
 }
 
 /// The extension for keeping state on recent information provided by the filesystem monitor.
-#[allow(dead_code)]
 #[derive(Clone)]
 pub struct FsMonitor {
-    token: fs_monitor::Token,
+    /// The token to use when querying the filesystem monitor for changes next time.
+    pub token: fs_monitor::Token,
     /// if a bit is true, the respective entry is NOT valid as per the fs monitor.
-    entry_dirty: gix_bitmap::ewah::Vec,
+    ///
+    /// Note that these bits are also reflected in the [`FSMONITOR_VALID`](crate::entry::Flags::FSMONITOR_VALID) flag of
+    /// each entry, which is authoritative when writing the index.
+    pub entry_dirty: gix_bitmap::ewah::Vec,
 }
 
 mod iter;
 
-pub(crate) mod fs_monitor;
+///
+pub mod fs_monitor;
 
 ///
 pub mod decode;
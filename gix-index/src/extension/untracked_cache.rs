@@ -1,14 +1,14 @@
-use bstr::BString;
+use bstr::{BStr, BString};
 use gix_hash::ObjectId;
 
 use crate::{
     entry,
     extension::{Signature, UntrackedCache},
-    util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int},
+    util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int, write_var_int},
 };
 
 /// A structure to track filesystem stat information along with an object id, linking a worktree file with what's in our ODB.
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OidStat {
     /// The file system stat information
     pub stat: entry::Stat,
@@ -17,27 +17,198 @@ pub struct OidStat {
 }
 
 /// A directory with information about its untracked files, and its sub-directories
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Directory {
     /// The directories name, or an empty string if this is the root directory.
     pub name: BString,
-    /// Untracked files and directory names
+    /// Untracked files and directory names, with the latter having a trailing slash.
     pub untracked_entries: Vec<BString>,
     /// indices for sub-directories similar to this one.
     pub sub_directories: Vec<usize>,
 
-    /// The directories stat data, if available or valid // TODO: or is it the exclude file?
+    /// The stat data of the directory at the time `untracked_entries` were obtained, or `None` if they are
+    /// invalid and the directory has to be read again.
     pub stat: Option<entry::Stat>,
-    /// The oid of a .gitignore file, if it exists
+    /// The id of the `.gitignore` file of this directory at the time `untracked_entries` were obtained,
+    /// or `None` if there was no such file.
     pub exclude_file_oid: Option<ObjectId>,
-    /// TODO: figure out what this really does
+    /// If `true`, `untracked_entries` only contain as many entries as needed to know that the directory
+    /// contains untracked files.
     pub check_only: bool,
 }
 
+/// The flags of a directory walk that affect which entries are stored in the cache, as seen in [`UntrackedCache::dir_flags`].
+pub mod dir_flags {
+    /// Untracked directories are listed as a whole, instead of listing all untracked files inside of them.
+    pub const SHOW_OTHER_DIRECTORIES: u32 = 1 << 1;
+    /// Untracked directories without untracked files aren't listed.
+    pub const HIDE_EMPTY_DIRECTORIES: u32 = 1 << 2;
+}
+
+impl UntrackedCache {
+    /// Create a new cache without any directory for use by directory walks with the given `dir_flags`
+    /// (see [`dir_flags`]), on the worktree and system described by `identifier`.
+    ///
+    /// Note that git stores the `identifier` with a trailing null-byte.
+    pub fn new(identifier: impl Into<BString>, dir_flags: u32) -> Self {
+        UntrackedCache {
+            identifier: identifier.into(),
+            info_exclude: None,
+            excludes_file: None,
+            exclude_filename_per_dir: ".gitignore".into(),
+            dir_flags,
+            directories: Vec::new(),
+        }
+    }
+
+    /// Invalidate the directory at `index` along with all of its sub-directories, so they have to be read again.
+    pub fn invalidate_directory(&mut self, index: usize) {
+        let mut stack = vec![index];
+        while let Some(index) = stack.pop() {
+            let dir = &mut self.directories[index];
+            dir.stat = None;
+            dir.untracked_entries.clear();
+            stack.extend_from_slice(&dir.sub_directories);
+        }
+    }
+
+    /// Invalidate the directory containing the worktree-relative `path` of an entry that was added to or removed from the index,
+    /// as its untracked entries may have changed.
+    ///
+    /// If untracked directories are [listed as a whole](dir_flags::SHOW_OTHER_DIRECTORIES), all of its parent directories
+    /// are invalidated as well.
+    pub fn invalidate_path(&mut self, path: &BStr) {
+        if self.directories.is_empty() {
+            return;
+        }
+        let mut parents = vec![0];
+        let mut components = path.split(|b| *b == b'/').peekable();
+        while let Some(component) = components.next() {
+            if components.peek().is_none() {
+                break;
+            }
+            let current = &self.directories[*parents.last().expect("at least root")];
+            match current
+                .sub_directories
+                .iter()
+                .find(|idx| self.directories[**idx].name == component)
+            {
+                Some(idx) => parents.push(*idx),
+                None => break,
+            }
+        }
+
+        let invalidate_parents = self.dir_flags & dir_flags::SHOW_OTHER_DIRECTORIES != 0;
+        for idx in parents.into_iter().rev() {
+            let dir = &mut self.directories[idx];
+            dir.stat = None;
+            dir.untracked_entries.clear();
+            if !invalidate_parents {
+                break;
+            }
+        }
+    }
+
+    /// Serialize this instance to `out`, assuming object hashes are of type `object_hash`.
+    pub fn write_to(&self, mut out: impl std::io::Write, object_hash: gix_hash::Kind) -> Result<(), std::io::Error> {
+        let mut buf = Vec::new();
+        write_var_int(&mut buf, self.identifier.len() as u64)?;
+        buf.extend_from_slice(&self.identifier);
+        let oid_stats = [&self.info_exclude, &self.excludes_file];
+        for oid_stat in oid_stats {
+            write_stat(&mut buf, &oid_stat.as_ref().map(|s| s.stat).unwrap_or_default())?;
+        }
+        buf.extend_from_slice(&self.dir_flags.to_be_bytes());
+        let null = object_hash.null();
+        for oid_stat in oid_stats {
+            buf.extend_from_slice(oid_stat.as_ref().map_or(null.as_bytes(), |s| s.id.as_bytes()));
+        }
+        buf.extend_from_slice(&self.exclude_filename_per_dir);
+        buf.push(0);
+
+        if self.directories.is_empty() {
+            write_var_int(&mut buf, 0)?;
+        } else {
+            let mut state = WriteState::default();
+            write_directory_block(&self.directories, 0, &mut state)?;
+            write_var_int(&mut buf, state.num_blocks as u64)?;
+            buf.extend_from_slice(&state.blocks);
+            state.valid.write_to(&mut buf)?;
+            state.check_only.write_to(&mut buf)?;
+            state.hash_valid.write_to(&mut buf)?;
+            buf.extend_from_slice(&state.stats);
+            buf.extend_from_slice(&state.hashes);
+            buf.push(0);
+        }
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(buf.len()).expect("less than 4GB untracked cache extension")).to_be_bytes())?;
+        out.write_all(&buf)
+    }
+}
+
+#[derive(Default)]
+struct WriteState {
+    num_blocks: usize,
+    blocks: Vec<u8>,
+    valid: gix_bitmap::ewah::Vec,
+    check_only: gix_bitmap::ewah::Vec,
+    hash_valid: gix_bitmap::ewah::Vec,
+    stats: Vec<u8>,
+    hashes: Vec<u8>,
+}
+
+fn write_directory_block(directories: &[Directory], index: usize, state: &mut WriteState) -> std::io::Result<()> {
+    let dir = &directories[index];
+    let block_index = state.num_blocks;
+    state.num_blocks += 1;
+    if let Some(stat) = &dir.stat {
+        state.valid.set(block_index);
+        write_stat(&mut state.stats, stat)?;
+    }
+    if dir.check_only {
+        state.check_only.set(block_index);
+    }
+    if let Some(id) = &dir.exclude_file_oid {
+        state.hash_valid.set(block_index);
+        state.hashes.extend_from_slice(id.as_bytes());
+    }
+
+    write_var_int(&mut state.blocks, dir.untracked_entries.len() as u64)?;
+    write_var_int(&mut state.blocks, dir.sub_directories.len() as u64)?;
+    state.blocks.extend_from_slice(&dir.name);
+    state.blocks.push(0);
+    for name in &dir.untracked_entries {
+        state.blocks.extend_from_slice(name);
+        state.blocks.push(0);
+    }
+    for sub_directory in &dir.sub_directories {
+        write_directory_block(directories, *sub_directory, state)?;
+    }
+    Ok(())
+}
+
+fn write_stat(out: &mut Vec<u8>, stat: &entry::Stat) -> std::io::Result<()> {
+    use std::io::Write;
+    for value in [
+        stat.ctime.secs,
+        stat.ctime.nsecs,
+        stat.mtime.secs,
+        stat.mtime.nsecs,
+        stat.dev,
+        stat.ino,
+        stat.uid,
+        stat.gid,
+        stat.size,
+    ] {
+        out.write_all(&value.to_be_bytes())?;
+    }
+    Ok(())
+}
+
 /// Only used as an indicator
 pub const SIGNATURE: Signature = *b"UNTR";
 
-// #[allow(unused)]
 /// Decode an untracked cache extension from `data`, assuming object hashes are of type `object_hash`.
 pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache> {
     if !data.last().map_or(false, |b| *b == 0) {
@@ -47,9 +218,11 @@ pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache
     let (identifier, data) = split_at_pos(data, identifier_len.try_into().ok()?)?;
 
     let hash_len = object_hash.len_in_bytes();
-    let (info_exclude, data) = decode_oid_stat(data, hash_len)?;
-    let (excludes_file, data) = decode_oid_stat(data, hash_len)?;
+    let (info_exclude_stat, data) = crate::decode::stat(data)?;
+    let (excludes_file_stat, data) = crate::decode::stat(data)?;
     let (dir_flags, data) = read_u32(data)?;
+    let (info_exclude, data) = decode_oid_stat(info_exclude_stat, data, hash_len)?;
+    let (excludes_file, data) = decode_oid_stat(excludes_file_stat, data, hash_len)?;
     let (exclude_filename_per_dir, data) = split_at_byte_exclusive(data, 0)?;
 
     let (num_directory_blocks, data) = var_int(data)?;
@@ -141,8 +314,7 @@ fn decode_directory_block<'a>(data: &'a [u8], directories: &mut Vec<Directory>)
     data.into()
 }
 
-fn decode_oid_stat(data: &[u8], hash_len: usize) -> Option<(OidStat, &[u8])> {
-    let (stat, data) = crate::decode::stat(data)?;
+fn decode_oid_stat(stat: entry::Stat, data: &[u8], hash_len: usize) -> Option<(OidStat, &[u8])> {
     let (hash, data) = split_at_pos(data, hash_len)?;
     Some((
         OidStat {
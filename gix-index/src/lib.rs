@@ -26,6 +26,9 @@ pub mod entry;
 
 mod access;
 
+///
+pub mod sparse;
+
 ///
 pub mod init;
 
@@ -197,6 +200,21 @@ pub(crate) mod util {
         (num, data).into()
     }
 
+    /// Write `value` in the variable-length integer encoding understood by [`var_int()`].
+    pub fn write_var_int(mut out: impl std::io::Write, mut value: u64) -> std::io::Result<()> {
+        let mut buf = [0u8; 10];
+        let mut pos = buf.len() - 1;
+        buf[pos] = (value & 0x7f) as u8;
+        value >>= 7;
+        while value != 0 {
+            value -= 1;
+            pos -= 1;
+            buf[pos] = 0x80 | (value & 0x7f) as u8;
+            value >>= 7;
+        }
+        out.write_all(&buf[pos..])
+    }
+
     #[inline]
     pub fn read_u32(data: &[u8]) -> Option<(u32, &[u8])> {
         split_at_pos(data, 4).map(|(num, data)| (u32::from_be_bytes(num.try_into().unwrap()), data))
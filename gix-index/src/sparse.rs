@@ -0,0 +1,275 @@
+//! Sparse indices and the handling of sparse directories, which represent entire directories outside of the
+//! sparse-checkout cone with a single entry.
+use std::{collections::HashSet, ops::Range};
+
+use bstr::{BStr, ByteSlice};
+use gix_object::WriteTo;
+
+use crate::{entry::Flags, Entry, State};
+
+/// Configuration related to sparse indexes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// If true, certain entries in the index will be excluded / skipped for certain operations,
+    /// based on the ignore patterns in the `.git/info/sparse-checkout` file. These entries will
+    /// carry the [`SKIP_WORKTREE`][crate::entry::Flags::SKIP_WORKTREE] flag.
+    ///
+    /// This typically is the value of `core.sparseCheckout` in the git configuration.
+    pub sparse_checkout: bool,
+
+    /// Interpret the `.git/info/sparse-checkout` file using _cone mode_.
+    ///
+    /// If true, _cone mode_ is active and entire directories will be included in the checkout, as well as files in the root
+    /// of the repository.
+    /// If false, non-cone mode is active and entries to _include_ will be matched with patterns like those found in `.gitignore` files.
+    ///
+    /// This typically is the value of `core.sparseCheckoutCone` in the git configuration.
+    pub directory_patterns_only: bool,
+
+    /// If true, will attempt to write a sparse index file which only works in cone mode.
+    ///
+    /// A sparse index has [`DIR` entries][crate::entry::Mode::DIR] that represent entire directories to be skipped
+    /// during checkout and other operations due to the added presence of
+    /// the [`SKIP_WORKTREE`][crate::entry::Flags::SKIP_WORKTREE] flag.
+    ///
+    /// This is typically the value of `index.sparse` in the git configuration.
+    pub write_sparse_index: bool,
+}
+
+impl Options {
+    /// Derive a valid mode from all parameters that affect the 'sparseness' of the index.
+    ///
+    /// Some combinations of them degenerate to one particular mode.
+    pub fn sparse_mode(&self) -> Mode {
+        match (
+            self.sparse_checkout,
+            self.directory_patterns_only,
+            self.write_sparse_index,
+        ) {
+            (true, true, true) => Mode::IncludeDirectoriesStoreIncludedEntriesAndExcludedDirs,
+            (true, true, false) => Mode::IncludeDirectoriesStoreAllEntriesSkipUnmatched,
+            (true, false, _) => Mode::IncludeByIgnorePatternStoreAllEntriesSkipUnmatched,
+            (false, _, _) => Mode::Disabled,
+        }
+    }
+}
+
+/// Describes the configuration how a sparse index should be written, or if one should be written at all.
+#[derive(Debug)]
+pub enum Mode {
+    /// index with DIR entries for exclusion and included entries, directory-only include patterns in `.git/info/sparse-checkout` file.
+    IncludeDirectoriesStoreIncludedEntriesAndExcludedDirs,
+    /// index with all file entries and skip worktree flags for exclusion, directory-only include patterns in `.git/info/sparse-checkout` file.
+    IncludeDirectoriesStoreAllEntriesSkipUnmatched,
+    /// index with all file entries and skip-worktree flags for exclusion, `ignore` patterns to include entries in `.git/info/sparse-checkout` file.
+    IncludeByIgnorePatternStoreAllEntriesSkipUnmatched,
+    /// index with all entries, non is excluded, `.git/info/sparse-checkout` file is not considered, a regular index.
+    Disabled,
+}
+
+///
+pub mod expand {
+    use bstr::BString;
+
+    /// The error returned by [State::expand_sparse_directories()](crate::State::expand_sparse_directories()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not obtain the entries of sparse directory '{path}'")]
+        FromTree {
+            path: BString,
+            source: crate::init::from_tree::Error,
+        },
+    }
+}
+
+/// Sparse directories
+impl State {
+    /// Replace all [sparse directory entries](crate::entry::Mode::DIR) with the entries of the trees they point to,
+    /// reading them from `objects`, and return the amount of replaced directories.
+    ///
+    /// All new entries inherit the [`SKIP_WORKTREE`](Flags::SKIP_WORKTREE) flag of their directory, and the index
+    /// [won't be sparse](State::is_sparse()) anymore.
+    /// Note that the [tree extension](State::tree()) is removed if directories were expanded, as it doesn't match the
+    /// entries anymore.
+    pub fn expand_sparse_directories(&mut self, objects: impl gix_object::Find) -> Result<usize, expand::Error> {
+        let _span = gix_features::trace::coarse!("gix_index::State::expand_sparse_directories()");
+        let mut expanded = 0;
+        if self.entries.iter().any(|e| e.mode.is_sparse()) {
+            let mut entries = Vec::with_capacity(self.entries.len());
+            let mut path_backing = Vec::with_capacity(self.path_backing.len());
+            for entry in &self.entries {
+                let path = entry.path(self);
+                if !entry.mode.is_sparse() {
+                    let start = path_backing.len();
+                    path_backing.extend_from_slice(path);
+                    entries.push(Entry {
+                        path: start..path_backing.len(),
+                        ..entry.clone()
+                    });
+                    continue;
+                }
+
+                let directory = State::from_tree(&entry.id, &objects, Default::default()).map_err(|err| {
+                    expand::Error::FromTree {
+                        path: path.to_owned(),
+                        source: err,
+                    }
+                })?;
+                for dir_entry in directory.entries() {
+                    let start = path_backing.len();
+                    // Sparse directory paths end with a slash already.
+                    path_backing.extend_from_slice(path);
+                    path_backing.extend_from_slice(dir_entry.path(&directory));
+                    entries.push(Entry {
+                        flags: dir_entry.flags | (entry.flags & (Flags::SKIP_WORKTREE | Flags::EXTENDED)),
+                        path: start..path_backing.len(),
+                        ..dir_entry.clone()
+                    });
+                }
+                expanded += 1;
+            }
+            self.entries = entries;
+            self.path_backing = path_backing;
+            self.tree = None;
+        }
+        self.is_sparse = false;
+        Ok(expanded)
+    }
+
+    /// Replace all entries of directories for which `is_excluded_directory(directory)` returns `true` with a single
+    /// [sparse directory entry](crate::entry::Mode::DIR) and return the amount of collapsed directories.
+    ///
+    /// `directory` is passed without trailing slash, starting with the top-level directories, so that only the
+    /// outermost excluded directories are collapsed.
+    /// A directory can only be collapsed if all of its entries are unconflicted, carry the
+    /// [`SKIP_WORKTREE`](Flags::SKIP_WORKTREE) flag and aren't submodules, and if the tree built from its entries
+    /// exists in `objects`. Otherwise, its subdirectories are tried instead.
+    ///
+    /// The index [is sparse](State::is_sparse()) afterwards if it contains at least one sparse directory.
+    /// Note that the [tree extension](State::tree()) is removed if directories were collapsed, as it doesn't match the
+    /// entries anymore.
+    pub fn collapse_sparse_directories(
+        &mut self,
+        mut is_excluded_directory: impl FnMut(&BStr) -> bool,
+        objects: impl gix_object::Exists,
+    ) -> usize {
+        let _span = gix_features::trace::coarse!("gix_index::State::collapse_sparse_directories()");
+        let mut entries = Vec::with_capacity(self.entries.len());
+        let mut path_backing = Vec::with_capacity(self.path_backing.len());
+        let mut non_collapsible = HashSet::<&BStr>::new();
+        let mut collapsed = 0;
+        let mut idx = 0;
+        while idx < self.entries.len() {
+            let entry = &self.entries[idx];
+            let path = entry.path(self);
+            let sparse_dir = path
+                .iter()
+                .enumerate()
+                // The trailing slash of sparse directories denotes the entry itself.
+                .filter(|(pos, b)| **b == b'/' && *pos + 1 < path.len())
+                .find_map(|(pos, _)| {
+                    let dir = path[..pos].as_bstr();
+                    if non_collapsible.contains(dir) || !is_excluded_directory(dir) {
+                        return None;
+                    }
+                    let res = self.collapsible_directory(path[..=pos].as_bstr(), &objects);
+                    if res.is_none() {
+                        non_collapsible.insert(dir);
+                    }
+                    res.map(|(range, id)| (pos, range, id))
+                });
+
+            let start = path_backing.len();
+            match sparse_dir {
+                Some((pos, range, id)) => {
+                    path_backing.extend_from_slice(&path[..=pos]);
+                    entries.push(Entry {
+                        stat: Default::default(),
+                        id,
+                        flags: Flags::SKIP_WORKTREE | Flags::EXTENDED,
+                        mode: crate::entry::Mode::DIR,
+                        path: start..path_backing.len(),
+                    });
+                    idx = range.end;
+                    collapsed += 1;
+                }
+                None => {
+                    path_backing.extend_from_slice(path);
+                    entries.push(Entry {
+                        path: start..path_backing.len(),
+                        ..entry.clone()
+                    });
+                    idx += 1;
+                }
+            }
+        }
+
+        if collapsed > 0 {
+            self.entries = entries;
+            self.path_backing = path_backing;
+            self.tree = None;
+        }
+        self.is_sparse = self.entries.iter().any(|e| e.mode.is_sparse());
+        collapsed
+    }
+
+    /// Return the range of entries in `prefix` (with trailing slash) along with the id of the tree they form,
+    /// if they can be represented by a sparse directory.
+    fn collapsible_directory(
+        &self,
+        prefix: &BStr,
+        objects: &impl gix_object::Exists,
+    ) -> Option<(Range<usize>, gix_hash::ObjectId)> {
+        let range = self.prefixed_entries_range(prefix)?;
+        let entries = &self.entries[range.clone()];
+        if !entries
+            .iter()
+            .all(|e| e.flags.contains(Flags::SKIP_WORKTREE) && e.stage_raw() == 0 && !e.mode.is_submodule())
+        {
+            return None;
+        }
+        let id = self.tree_id(entries, prefix.len())?;
+        objects.exists(&id).then_some((range, id))
+    }
+
+    /// Compute the id of the tree formed by `entries`, whose paths all share a prefix of `prefix_len` bytes.
+    fn tree_id(&self, entries: &[Entry], prefix_len: usize) -> Option<gix_hash::ObjectId> {
+        let mut tree = gix_object::Tree::empty();
+        let mut idx = 0;
+        while idx < entries.len() {
+            let path = entries[idx].path(self);
+            let relative_path = &path[prefix_len..];
+            match relative_path.find_byte(b'/') {
+                Some(pos) if pos + 1 < relative_path.len() => {
+                    let subdir = &path[..prefix_len + pos + 1];
+                    let end = idx
+                        + entries[idx..]
+                            .iter()
+                            .take_while(|e| e.path(self).starts_with(subdir))
+                            .count();
+                    tree.entries.push(gix_object::tree::Entry {
+                        mode: gix_object::tree::EntryKind::Tree.into(),
+                        filename: relative_path[..pos].into(),
+                        oid: self.tree_id(&entries[idx..end], subdir.len())?,
+                    });
+                    idx = end;
+                }
+                _ => {
+                    let entry = &entries[idx];
+                    tree.entries.push(gix_object::tree::Entry {
+                        mode: entry.mode.to_tree_entry_mode()?,
+                        filename: relative_path.strip_suffix(b"/").unwrap_or(relative_path).into(),
+                        oid: entry.id,
+                    });
+                    idx += 1;
+                }
+            }
+        }
+        tree.entries.sort();
+
+        let mut buf = Vec::new();
+        tree.write_to(&mut buf).expect("writing to memory never fails");
+        Some(gix_object::compute_hash(self.object_hash, gix_object::Kind::Tree, &buf))
+    }
+}
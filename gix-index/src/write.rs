@@ -17,6 +17,10 @@ pub enum Extensions {
     Given {
         /// Write the tree-cache extension, if present.
         tree_cache: bool,
+        /// Write the untracked-cache extension, if present.
+        untracked_cache: bool,
+        /// Write the filesystem-monitor extension, if present.
+        fs_monitor: bool,
         /// Write the end-of-index-entry extension.
         end_of_index_entry: bool,
     },
@@ -32,9 +36,13 @@ impl Extensions {
             Extensions::All => Some(signature),
             Extensions::Given {
                 tree_cache,
+                untracked_cache,
+                fs_monitor,
                 end_of_index_entry,
             } => match signature {
                 extension::tree::SIGNATURE => tree_cache,
+                extension::untracked_cache::SIGNATURE => untracked_cache,
+                extension::fs_monitor::SIGNATURE => fs_monitor,
                 extension::end_of_index_entry::SIGNATURE => end_of_index_entry,
                 _ => &false,
             }
@@ -117,6 +125,23 @@ impl State {
                     .should_write(extension::tree::SIGNATURE)
                     .and_then(|signature| self.tree().map(|tree| tree.write_to(write).map(|_| signature)))
             },
+            &|write| {
+                extensions
+                    .should_write(extension::untracked_cache::SIGNATURE)
+                    .and_then(|signature| {
+                        self.untracked()
+                            .map(|untracked| untracked.write_to(write, self.object_hash).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::fs_monitor::SIGNATURE)
+                    .and_then(|signature| {
+                        self.fs_monitor().map(|fs_monitor| {
+                            extension::fs_monitor::write_to(write, &fs_monitor.token, self.entries()).map(|_| signature)
+                        })
+                    })
+            },
             &|write| {
                 self.is_sparse()
                     .then(|| extension::sparse::write_to(write).map(|_| extension::sparse::SIGNATURE))
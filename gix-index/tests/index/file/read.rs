@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 use bstr::ByteSlice;
 use gix_index::{
     entry::{self, Flags, Mode},
+    extension::{fs_monitor, untracked_cache},
     Version,
 };
 
@@ -189,7 +190,30 @@ fn untr_extension() {
     let file = loose_file("UNTR");
     assert_eq!(file.version(), Version::V2);
 
-    assert!(file.untracked().is_some());
+    let untracked = file.untracked().expect("present");
+    assert!(untracked.identifier.ends_with(b", system Darwin\0"));
+    assert_eq!(
+        untracked.dir_flags,
+        untracked_cache::dir_flags::SHOW_OTHER_DIRECTORIES | untracked_cache::dir_flags::HIDE_EMPTY_DIRECTORIES
+    );
+    assert_eq!(
+        untracked.info_exclude.as_ref().map(|s| s.id),
+        Some(hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")),
+        "the stat and id of the info-exclude file are decoded correctly"
+    );
+    assert_eq!(untracked.excludes_file, None);
+    assert_eq!(untracked.exclude_filename_per_dir, ".gitignore");
+    let root = &untracked.directories[0];
+    assert_eq!(root.name, "");
+    assert_eq!(root.untracked_entries, ["three", "dtwo/", "dthree/"]);
+    assert_eq!(
+        root.sub_directories
+            .iter()
+            .map(|idx| untracked.directories[*idx].name.as_bstr())
+            .collect::<Vec<_>>(),
+        ["done", "dthree", "dtwo"]
+    );
+    assert!(root.stat.is_some(), "the root is valid");
 }
 
 #[test]
@@ -205,7 +229,17 @@ fn fsmn_v1() {
     let file = loose_file("FSMN");
     assert_eq!(file.version(), Version::V2);
 
-    assert!(file.fs_monitor().is_some());
+    let fs_monitor = file.fs_monitor().expect("present");
+    assert_eq!(
+        fs_monitor.token,
+        fs_monitor::Token::V2 {
+            token: "1642331326943378000".into()
+        }
+    );
+    assert!(
+        file.entries().iter().all(|e| !e.flags.contains(Flags::FSMONITOR_VALID)),
+        "all entries are marked dirty"
+    );
 }
 
 #[test]
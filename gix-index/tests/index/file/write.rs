@@ -14,9 +14,14 @@ fn roundtrips() -> crate::Result {
             Generated("v2"),
             options_with(write::Extensions::Given {
                 tree_cache: true,
+                untracked_cache: false,
+                fs_monitor: false,
                 end_of_index_entry: true,
             }),
         ),
+        (Loose("UNTR"), all_ext_but_eoie()),
+        (Loose("UNTR-with-oids"), all_ext_but_eoie()),
+        (Loose("FSMN"), all_ext_but_eoie()),
         (Generated("V2_empty"), only_tree_ext()),
         (Generated("v2_more_files"), only_tree_ext()),
         (Generated("v2_all_file_kinds"), only_tree_ext()),
@@ -148,10 +153,14 @@ fn state_comparisons_with_various_extension_configurations() {
             options_with(write::Extensions::All),
             options_with(write::Extensions::Given {
                 tree_cache: true,
+                untracked_cache: false,
+                fs_monitor: false,
                 end_of_index_entry: false,
             }),
             options_with(write::Extensions::Given {
                 tree_cache: false,
+                untracked_cache: true,
+                fs_monitor: true,
                 end_of_index_entry: true,
             }),
         ] {
@@ -243,6 +252,22 @@ fn compare_states(actual: &State, actual_version: Version, expected: &State, opt
             .and_then(|_| expected.tree()),
         "tree extension mismatch, actual vs option in {fixture:?}"
     );
+    assert_eq!(
+        actual.untracked(),
+        options
+            .extensions
+            .should_write(extension::untracked_cache::SIGNATURE)
+            .and_then(|_| expected.untracked()),
+        "untracked cache extension mismatch, actual vs option in {fixture:?}"
+    );
+    assert_eq!(
+        actual.fs_monitor().map(|fsm| &fsm.token),
+        options
+            .extensions
+            .should_write(extension::fs_monitor::SIGNATURE)
+            .and_then(|_| expected.fs_monitor().map(|fsm| &fsm.token)),
+        "fsmonitor extension mismatch, actual vs option in {fixture:?}"
+    );
 
     // As `write_to` does / should not mutate we can test those properties here.
     // Anything that can be configured has to be tested separately when comparing against baseline
@@ -293,6 +318,20 @@ fn only_tree_ext() -> Options {
         extensions: write::Extensions::Given {
             end_of_index_entry: false,
             tree_cache: true,
+            untracked_cache: false,
+            fs_monitor: false,
+        },
+        skip_hash: false,
+    }
+}
+
+fn all_ext_but_eoie() -> Options {
+    Options {
+        extensions: write::Extensions::Given {
+            end_of_index_entry: false,
+            tree_cache: true,
+            untracked_cache: true,
+            fs_monitor: true,
         },
         skip_hash: false,
     }
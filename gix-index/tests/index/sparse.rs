@@ -0,0 +1,80 @@
+use bstr::ByteSlice;
+use gix_index::entry::{Flags, Mode};
+
+use crate::index::Fixture;
+
+#[test]
+fn expand_and_collapse_sparse_directories_roundtrip() -> crate::Result {
+    let fixture = Fixture::Generated("v3_sparse_index");
+    let expected = fixture.open();
+    let odb = gix_odb::at(fixture.to_path().parent().expect("in .git").join("objects"))?;
+
+    let mut state = expected.clone().into();
+    let state: &mut gix_index::State = &mut state;
+    assert_eq!(state.expand_sparse_directories(&odb)?, 2);
+    assert!(!state.is_sparse());
+    assert!(state.tree().is_none(), "the tree extension doesn't match anymore");
+    let paths: Vec<_> = state
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(Flags::SKIP_WORKTREE))
+        .map(|e| {
+            assert_eq!(e.mode, Mode::FILE);
+            e.path(state).to_str_lossy().into_owned()
+        })
+        .collect();
+    assert_eq!(
+        paths,
+        ["c1/c3/a", "c1/c3/b", "d/a", "d/b", "d/c4/a", "d/c4/b", "d/c4/c5"],
+        "sparse directories are replaced by their skipped entries"
+    );
+    assert_eq!(state.expand_sparse_directories(&odb)?, 0, "nothing left to expand");
+
+    let mut queried_dirs = std::collections::BTreeSet::new();
+    let collapsed = state.collapse_sparse_directories(
+        |dir| {
+            queried_dirs.insert(dir.to_owned());
+            dir != "c1" && dir != "c1/c2"
+        },
+        &odb,
+    );
+    assert_eq!(collapsed, 2);
+    assert_eq!(
+        queried_dirs.into_iter().collect::<Vec<_>>(),
+        ["c1", "c1/c2", "c1/c3", "d"],
+        "subdirectories of collapsed directories aren't queried"
+    );
+    assert!(state.is_sparse());
+    assert_eq!(
+        state.entries(),
+        expected.entries(),
+        "the sparse directories are the same as the ones created by git"
+    );
+    Ok(())
+}
+
+#[test]
+fn collapse_only_skipped_directories_with_existing_trees() -> crate::Result {
+    let fixture = Fixture::Generated("v3_sparse_index");
+    let odb = gix_odb::at(fixture.to_path().parent().expect("in .git").join("objects"))?;
+    let mut file = fixture.open();
+    file.expand_sparse_directories(&odb)?;
+
+    let idx = file.entry_index_by_path("d/c4/a".into()).expect("present");
+    file.entries_mut()[idx].flags.remove(Flags::SKIP_WORKTREE);
+    assert_eq!(
+        file.collapse_sparse_directories(|dir| dir.starts_with(b"d"), &odb),
+        0,
+        "'d' and 'd/c4' contain an entry that is checked out"
+    );
+    assert!(!file.is_sparse());
+
+    file.entries_mut()[idx].flags.insert(Flags::SKIP_WORKTREE);
+    file.entries_mut()[idx].id = gix_hash::Kind::Sha1.null();
+    assert_eq!(
+        file.collapse_sparse_directories(|dir| dir.starts_with(b"d"), &odb),
+        0,
+        "trees that aren't in the object database can't be used"
+    );
+    Ok(())
+}
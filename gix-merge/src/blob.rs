@@ -0,0 +1,230 @@
+//! Merge the content of blobs line by line, similar to `git merge-file`.
+use std::ops::Range;
+
+use bstr::BStr;
+use imara_diff::{intern::InternedInput, Algorithm};
+
+/// Determine how conflicting regions are presented.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ConflictStyle {
+    /// Only show our and their version of a conflicting region, like `merge.conflictStyle=merge`.
+    ///
+    /// Lines at the beginning and end of a conflict that both sides agree on are moved out of the conflict.
+    #[default]
+    Merge,
+    /// Also show the original version of a conflicting region between `|||||||` and `=======`,
+    /// like `merge.conflictStyle=diff3`.
+    Diff3,
+}
+
+/// The names to print next to the conflict markers.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Labels<'a> {
+    /// The label of the common ancestor, shown after `|||||||` in [`ConflictStyle::Diff3`].
+    pub ancestor: Option<&'a BStr>,
+    /// The label of our side, shown after `<<<<<<<`.
+    pub current: Option<&'a BStr>,
+    /// The label of their side, shown after `>>>>>>>`.
+    pub other: Option<&'a BStr>,
+}
+
+/// Options for use in [`merge()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The diff algorithm to use for diffing the ancestor with each side.
+    pub algorithm: Algorithm,
+    /// How to present conflicts.
+    pub conflict_style: ConflictStyle,
+    /// The amount of characters of each conflict marker, defaulting to `7`.
+    pub marker_size: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            algorithm: Algorithm::Myers,
+            conflict_style: ConflictStyle::default(),
+            marker_size: 7,
+        }
+    }
+}
+
+/// The result of [`merge()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The amount of conflicts that were written, each enclosed in conflict markers.
+    pub conflicts: usize,
+}
+
+impl Outcome {
+    /// Return `true` if at least one conflict was written.
+    pub fn is_conflicted(&self) -> bool {
+        self.conflicts != 0
+    }
+}
+
+/// Merge the changes that lead from `base` to `ours` with the ones that lead from `base` to `theirs` and append the result to `out`.
+///
+/// Regions that were changed on only one side are taken from that side, and regions that were changed in the same way on both sides
+/// are taken once. Changes that overlap or touch each other, but aren't the same, are conflicts and are written enclosed
+/// in conflict markers labelled with `labels`.
+pub fn merge(
+    out: &mut Vec<u8>,
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+    labels: Labels<'_>,
+    options: Options,
+) -> Outcome {
+    let base = lines(base);
+    let ours = lines(ours);
+    let theirs = lines(theirs);
+    let mut ours_hunks = hunks(options.algorithm, &base, &ours).into_iter().peekable();
+    let mut theirs_hunks = hunks(options.algorithm, &base, &theirs).into_iter().peekable();
+
+    let mut conflicts = 0;
+    let mut base_pos = 0;
+    let (mut ours_delta, mut theirs_delta) = (0isize, 0isize);
+    loop {
+        let start = match (ours_hunks.peek(), theirs_hunks.peek()) {
+            (None, None) => break,
+            (Some(a), None) => a.base.start,
+            (None, Some(b)) => b.base.start,
+            (Some(a), Some(b)) => a.base.start.min(b.base.start),
+        };
+        write_lines(out, &base[base_pos..start]);
+
+        // Collect all changes that overlap or touch the region, which is what `git` considers a conflict.
+        let (ours_delta_before, theirs_delta_before) = (ours_delta, theirs_delta);
+        let (mut ours_changed, mut theirs_changed) = (false, false);
+        let mut end = start;
+        loop {
+            if let Some(hunk) = ours_hunks.next_if(|h| h.base.start <= end) {
+                end = end.max(hunk.base.end);
+                ours_delta += hunk.delta();
+                ours_changed = true;
+            } else if let Some(hunk) = theirs_hunks.next_if(|h| h.base.start <= end) {
+                end = end.max(hunk.base.end);
+                theirs_delta += hunk.delta();
+                theirs_changed = true;
+            } else {
+                break;
+            }
+        }
+        let ours_region = &ours[offset(start, ours_delta_before)..offset(end, ours_delta)];
+        let theirs_region = &theirs[offset(start, theirs_delta_before)..offset(end, theirs_delta)];
+        base_pos = end;
+
+        if !theirs_changed || ours_region == theirs_region {
+            write_lines(out, ours_region);
+            continue;
+        }
+        if !ours_changed {
+            write_lines(out, theirs_region);
+            continue;
+        }
+
+        conflicts += 1;
+        let base_region = &base[start..end];
+        let (ours_region, theirs_region, common_suffix) = match options.conflict_style {
+            ConflictStyle::Merge => {
+                let prefix = ours_region
+                    .iter()
+                    .zip(theirs_region)
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                let suffix = ours_region[prefix..]
+                    .iter()
+                    .rev()
+                    .zip(theirs_region[prefix..].iter().rev())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                write_lines(out, &ours_region[..prefix]);
+                (
+                    &ours_region[prefix..ours_region.len() - suffix],
+                    &theirs_region[prefix..theirs_region.len() - suffix],
+                    &ours_region[ours_region.len() - suffix..],
+                )
+            }
+            ConflictStyle::Diff3 => (ours_region, theirs_region, &[][..]),
+        };
+        write_marker(out, b'<', options.marker_size, labels.current);
+        write_conflict_lines(out, ours_region);
+        if options.conflict_style == ConflictStyle::Diff3 {
+            write_marker(out, b'|', options.marker_size, labels.ancestor);
+            write_conflict_lines(out, base_region);
+        }
+        write_marker(out, b'=', options.marker_size, None);
+        write_conflict_lines(out, theirs_region);
+        write_marker(out, b'>', options.marker_size, labels.other);
+        write_lines(out, common_suffix);
+    }
+    write_lines(out, &base[base_pos..]);
+    Outcome { conflicts }
+}
+
+/// A change in terms of line ranges in the common ancestor and one side.
+struct Hunk {
+    base: Range<usize>,
+    side: Range<usize>,
+}
+
+impl Hunk {
+    /// The amount of lines this hunk adds to, or removes from, the ancestor.
+    fn delta(&self) -> isize {
+        self.side.len() as isize - self.base.len() as isize
+    }
+}
+
+fn offset(pos: usize, delta: isize) -> usize {
+    (pos as isize + delta) as usize
+}
+
+fn hunks(algorithm: Algorithm, base: &[&[u8]], side: &[&[u8]]) -> Vec<Hunk> {
+    let mut input = InternedInput::default();
+    input.update_before(base.iter().copied());
+    input.update_after(side.iter().copied());
+    let mut out = Vec::new();
+    imara_diff::diff(algorithm, &input, |base: Range<u32>, side: Range<u32>| {
+        out.push(Hunk {
+            base: base.start as usize..base.end as usize,
+            side: side.start as usize..side.end as usize,
+        });
+    });
+    out
+}
+
+/// Split `data` into lines that keep their line terminator.
+fn lines(data: &[u8]) -> Vec<&[u8]> {
+    let mut out = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let end = rest.iter().position(|b| *b == b'\n').map_or(rest.len(), |nl| nl + 1);
+        out.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    out
+}
+
+fn write_lines(out: &mut Vec<u8>, lines: &[&[u8]]) {
+    for line in lines {
+        out.extend_from_slice(line);
+    }
+}
+
+/// Like [`write_lines()`], but assure the last line is terminated so the following marker starts on its own line.
+fn write_conflict_lines(out: &mut Vec<u8>, lines: &[&[u8]]) {
+    write_lines(out, lines);
+    if lines.last().map_or(false, |line| !line.ends_with(b"\n")) {
+        out.push(b'\n');
+    }
+}
+
+fn write_marker(out: &mut Vec<u8>, marker: u8, size: usize, label: Option<&BStr>) {
+    out.extend(std::iter::repeat(marker).take(size));
+    if let Some(label) = label {
+        out.push(b' ');
+        out.extend_from_slice(label);
+    }
+    out.push(b'\n');
+}
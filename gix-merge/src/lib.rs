@@ -0,0 +1,6 @@
+//! Merge algorithms for use by `gitoxide`, like three-way merges of text files.
+#![deny(rust_2018_idioms, missing_docs)]
+#![forbid(unsafe_code)]
+
+///
+pub mod blob;
@@ -0,0 +1,93 @@
+use bstr::{BStr, ByteSlice};
+use gix_merge::blob::{merge, ConflictStyle, Labels, Options};
+
+const BASE: &str = "a\nb\nc\nd\ne\nf\n";
+
+fn labels() -> Labels<'static> {
+    Labels {
+        ancestor: Some("b".into()),
+        current: Some("o".into()),
+        other: Some("t".into()),
+    }
+}
+
+fn run(ours: &str, theirs: &str, options: Options) -> (String, usize) {
+    let mut out = Vec::new();
+    let outcome = merge(
+        &mut out,
+        BASE.as_bytes(),
+        ours.as_bytes(),
+        theirs.as_bytes(),
+        labels(),
+        options,
+    );
+    (out.to_str().expect("valid UTF8").to_owned(), outcome.conflicts)
+}
+
+#[test]
+fn non_overlapping_changes_merge_cleanly() {
+    let (out, conflicts) = run("a\nB\nc\nd\ne\nf\n", "a\nb\nc\nd\nE\nf\n", Options::default());
+    assert_eq!(out, "a\nB\nc\nd\nE\nf\n");
+    assert_eq!(conflicts, 0);
+}
+
+#[test]
+fn identical_changes_are_taken_once() {
+    let (out, conflicts) = run("a\nB\nc\nd\ne\nf\n", "a\nB\nc\nd\ne\nf\n", Options::default());
+    assert_eq!(out, "a\nB\nc\nd\ne\nf\n");
+    assert_eq!(conflicts, 0);
+}
+
+#[test]
+fn overlapping_changes_conflict() {
+    let (out, conflicts) = run("a\nB\nc\nd\ne\nf\n", "a\nX\nc\nd\ne\nf\n", Options::default());
+    assert_eq!(out, "a\n<<<<<<< o\nB\n=======\nX\n>>>>>>> t\nc\nd\ne\nf\n");
+    assert_eq!(conflicts, 1);
+}
+
+#[test]
+fn adjacent_changes_conflict_like_git() {
+    let (out, conflicts) = run("a\nB\nc\nd\ne\nf\n", "a\nb\nC\nd\ne\nf\n", Options::default());
+    assert_eq!(out, "a\n<<<<<<< o\nB\nc\n=======\nb\nC\n>>>>>>> t\nd\ne\nf\n");
+    assert_eq!(conflicts, 1);
+}
+
+#[test]
+fn diff3_shows_the_ancestor() {
+    let (out, conflicts) = run(
+        "a\nB\nc\nd\ne\nf\n",
+        "a\nX\nc\nd\ne\nf\n",
+        Options {
+            conflict_style: ConflictStyle::Diff3,
+            ..Default::default()
+        },
+    );
+    assert_eq!(out, "a\n<<<<<<< o\nB\n||||||| b\nb\n=======\nX\n>>>>>>> t\nc\nd\ne\nf\n");
+    assert_eq!(conflicts, 1);
+}
+
+#[test]
+fn common_lines_are_moved_out_of_conflicts() {
+    let mut out = Vec::new();
+    let outcome = merge(
+        &mut out,
+        b"a\nb\n",
+        b"a\nsame\nY\nend\n",
+        b"a\nsame\nW\nend\n",
+        Labels::default(),
+        Options {
+            marker_size: 3,
+            ..Default::default()
+        },
+    );
+    assert_eq!(out.as_bstr(), "a\nsame\n<<<\nY\n===\nW\n>>>\nend\n");
+    assert!(outcome.is_conflicted());
+}
+
+#[test]
+fn missing_newlines_are_added_before_markers() {
+    let mut out = Vec::new();
+    let outcome = merge(&mut out, b"a", b"b", b"c", labels(), Options::default());
+    assert_eq!(out.as_bstr(), <&BStr>::from("<<<<<<< o\nb\n=======\nc\n>>>>>>> t\n"));
+    assert_eq!(outcome.conflicts, 1);
+}
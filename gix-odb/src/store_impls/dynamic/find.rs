@@ -306,17 +306,8 @@ where
         }
     }
 
-    pub(crate) fn clear_cache(&self) {
-        self.packed_object_count.borrow_mut().take();
-    }
-}
-
-impl<S> gix_pack::Find for super::Handle<S>
-where
-    S: Deref<Target = super::Store> + Clone,
-{
-    // TODO: probably make this method fallible, but that would mean its own error type.
-    fn contains(&self, id: &gix_hash::oid) -> bool {
+    /// Check if `id` is present in the object database, without ever using the promisor to fetch it.
+    pub(crate) fn contains_inner(&self, id: &gix_hash::oid) -> bool {
         let mut snapshot = self.snapshot.borrow_mut();
         loop {
             for (idx, index) in snapshot.indices.iter().enumerate() {
@@ -345,12 +336,28 @@ where
         }
     }
 
+    pub(crate) fn clear_cache(&self) {
+        self.packed_object_count.borrow_mut().take();
+    }
+}
+
+impl<S> gix_pack::Find for super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    // TODO: probably make this method fallible, but that would mean its own error type.
+    // Like `git`, never fetch missing objects with the promisor here, as existence checks are common, e.g. before each write.
+    fn contains(&self, id: &gix_hash::oid) -> bool {
+        self.contains_inner(id)
+    }
+
     fn try_find_cached<'a>(
         &self,
         id: &gix_hash::oid,
         buffer: &'a mut Vec<u8>,
         pack_cache: &mut dyn DecodeEntry,
     ) -> Result<Option<(gix_object::Data<'a>, Option<gix_pack::data::entry::Location>)>, gix_object::find::Error> {
+        self.fetch_if_missing(id)?;
         let mut snapshot = self.snapshot.borrow_mut();
         let mut inflate = self.inflate.borrow_mut();
         self.try_find_cached_inner(id, buffer, &mut inflate, pack_cache, &mut snapshot, None)
@@ -521,6 +528,7 @@ where
     S: Deref<Target = super::Store> + Clone,
 {
     fn try_header(&self, id: &gix_hash::oid) -> Result<Option<gix_object::Header>, gix_object::find::Error> {
+        self.fetch_if_missing(id)?;
         let mut snapshot = self.snapshot.borrow_mut();
         let mut inflate = self.inflate.borrow_mut();
         self.try_header_inner(id, &mut inflate, &mut snapshot, None)
@@ -185,6 +185,7 @@ where
     S: Deref<Target = super::Store> + Clone,
 {
     fn try_header(&self, id: &oid) -> Result<Option<Header>, gix_object::find::Error> {
+        self.fetch_if_missing(id)?;
         let mut snapshot = self.snapshot.borrow_mut();
         let mut inflate = self.inflate.borrow_mut();
         self.try_header_inner(id, &mut inflate, &mut snapshot, None)
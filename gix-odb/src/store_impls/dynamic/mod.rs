@@ -1,5 +1,5 @@
 //! The standard object store which should fit all needs.
-use std::{cell::RefCell, ops::Deref};
+use std::{cell::RefCell, ops::Deref, sync::Arc};
 
 use gix_features::zlib;
 
@@ -23,6 +23,10 @@ where
 
     /// If true, replacements will not be performed even if these are available.
     pub ignore_replacements: bool,
+    /// If set, objects that can't be found are fetched with it before trying again, as needed for partial clones.
+    ///
+    /// This only happens when objects or their headers are looked up, while checking for their existence never fetches.
+    pub promisor: Option<Arc<dyn promisor::Fetch>>,
 
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
@@ -53,6 +57,9 @@ impl RefreshMode {
 ///
 pub mod find;
 
+///
+pub mod promisor;
+
 ///
 pub mod prefix;
 
@@ -0,0 +1,77 @@
+use std::ops::Deref;
+
+use gix_hash::ObjectId;
+
+use crate::store::RefreshMode;
+
+/// The error returned by [`Fetch::fetch_missing()`].
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A way to obtain objects that aren't present in the object database, typically by fetching them from a *promisor remote*
+/// of a partial clone.
+pub trait Fetch: Send + Sync {
+    /// Make all objects identified by `ids` available in the object database, usually by receiving a pack that contains them.
+    ///
+    /// Note that the object database will check for new packs on disk once this call returns.
+    fn fetch_missing(&self, ids: &[ObjectId]) -> Result<(), Error>;
+}
+
+impl<F> Fetch for F
+where
+    F: Fn(&[ObjectId]) -> Result<(), Error> + Send + Sync,
+{
+    fn fetch_missing(&self, ids: &[ObjectId]) -> Result<(), Error> {
+        self(ids)
+    }
+}
+
+/// Promisor support
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Fetch all objects in `ids` that aren't present in the object database with a single call to the
+    /// [promisor](super::Handle::promisor), and return the amount of objects that were requested.
+    ///
+    /// This is more efficient than relying on lookups to fetch each missing object individually.
+    /// Nothing happens if no promisor is set.
+    pub fn fetch_missing(&self, ids: impl IntoIterator<Item = ObjectId>) -> Result<usize, Error> {
+        let Some(promisor) = self.promisor.as_ref() else {
+            return Ok(0);
+        };
+        let mut missing: Vec<_> = ids.into_iter().filter(|id| !self.contains_inner(id)).collect();
+        missing.sort();
+        missing.dedup();
+        if missing.is_empty() {
+            return Ok(0);
+        }
+        promisor.fetch_missing(&missing)?;
+        self.refresh_after_fetch();
+        Ok(missing.len())
+    }
+
+    /// Fetch `id` using our promisor if it is set and if `id` isn't present in the object database.
+    pub(crate) fn fetch_if_missing(&self, id: &gix_hash::oid) -> Result<(), Error> {
+        let Some(promisor) = self.promisor.as_ref() else {
+            return Ok(());
+        };
+        if self.contains_inner(id) {
+            return Ok(());
+        }
+        promisor.fetch_missing(&[id.to_owned()])?;
+        self.refresh_after_fetch();
+        Ok(())
+    }
+
+    /// Assure new packs are picked up even if our refresh mode doesn't allow it.
+    fn refresh_after_fetch(&self) {
+        let mut snapshot = self.snapshot.borrow_mut();
+        if let Ok(Some(new_snapshot)) = self
+            .store
+            .load_one_index(RefreshMode::AfterAllIndicesLoaded, snapshot.marker)
+        {
+            *snapshot = new_snapshot;
+            self.clear_cache();
+        }
+    }
+}
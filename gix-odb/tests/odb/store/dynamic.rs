@@ -221,6 +221,58 @@ fn write() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn promisor_provides_missing_objects() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let mut handle = gix_odb::at(dir.path())?;
+    let id = hex_to_id("95d09f2b10159347eece71399a7e2e907ea3df4f");
+    let unknown = hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+    assert!(!handle.exists(&id), "nothing to fetch the object with yet");
+
+    let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::<Vec<ObjectId>>::new()));
+    handle.promisor = Some(std::sync::Arc::new({
+        let requests = requests.clone();
+        let loose = gix_odb::loose::Store::at(dir.path(), gix_hash::Kind::Sha1);
+        move |ids: &[ObjectId]| -> Result<(), gix_odb::store::promisor::Error> {
+            requests.lock().expect("not poisoned").push(ids.to_vec());
+            if ids.contains(&id) {
+                loose.write_buf(gix_object::Kind::Blob, b"hello world")?;
+            }
+            Ok(())
+        }
+    }));
+
+    assert!(!handle.exists(&id), "existence checks never fetch");
+    assert!(requests.lock().expect("not poisoned").is_empty());
+
+    let mut buf = Vec::new();
+    assert_eq!(
+        handle.find(&id, &mut buf)?.data,
+        b"hello world",
+        "it's fetched on demand"
+    );
+    assert!(handle.exists(&id), "it's now present locally");
+    assert!(
+        handle.try_header(&unknown)?.is_none(),
+        "the promisor may not provide what we need"
+    );
+    assert_eq!(
+        *requests.lock().expect("not poisoned"),
+        [vec![id], vec![unknown]],
+        "one request per missing object, and none for objects that are present"
+    );
+
+    requests.lock().expect("not poisoned").clear();
+    let other_unknown = hex_to_id("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+    assert_eq!(handle.fetch_missing([other_unknown, id, unknown, unknown])?, 2);
+    assert_eq!(
+        *requests.lock().expect("not poisoned"),
+        [vec![unknown, other_unknown]],
+        "missing objects are requested in a single batch"
+    );
+    Ok(())
+}
+
 #[test]
 fn alternate_dbs_query() -> crate::Result {
     let dir = gix_testtools::scripted_fixture_read_only_standalone("make_alternates_odb.sh")?;
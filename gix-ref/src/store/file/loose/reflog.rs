@@ -83,6 +83,72 @@ impl file::Store {
     }
 }
 
+///
+pub mod rewrite {
+    use std::io::Write;
+
+    use crate::{log::Line, store_impl::file, FullNameRef};
+
+    impl file::Store {
+        /// Replace all entries of the reflog of the reference `name` with `lines`, ordered from oldest to newest,
+        /// after acquiring a lock on it according to `lock_mode`.
+        ///
+        /// This is useful to remove entries, like `git reflog delete` and `git reflog expire` do. If there is no
+        /// reflog yet, it is created. Note that this doesn't change the reference itself.
+        pub fn reflog_rewrite<'a, Name, E>(
+            &self,
+            name: Name,
+            lines: &[Line],
+            lock_mode: gix_lock::acquire::Fail,
+        ) -> Result<(), Error>
+        where
+            Name: TryInto<&'a FullNameRef, Error = E>,
+            crate::name::Error: From<E>,
+        {
+            let name: &FullNameRef = name.try_into().map_err(|err| Error::RefnameValidation(err.into()))?;
+            let (base, rela_path) = self.reflog_base_and_relative_path(name);
+            let path = base.join(rela_path);
+            let mut lock =
+                gix_lock::File::acquire_to_update_resource(&path, lock_mode, base.parent().map(ToOwned::to_owned))?;
+            let mut buf = Vec::new();
+            for line in lines {
+                line.write_to(&mut buf).map_err(|source| Error::Write {
+                    source,
+                    reflog_path: path.clone(),
+                })?;
+            }
+            lock.write_all(&buf).map_err(|source| Error::Write {
+                source,
+                reflog_path: path.clone(),
+            })?;
+            lock.commit().map_err(|err| Error::Commit(err.error))?;
+            Ok(())
+        }
+    }
+
+    mod error {
+        use std::path::PathBuf;
+
+        /// The error returned by [`crate::file::Store::reflog_rewrite()`].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("The reflog name or path is not a valid ref name")]
+            RefnameValidation(#[from] crate::name::Error),
+            #[error("The reflog could not be locked")]
+            Lock(#[from] gix_lock::acquire::Error),
+            #[error("Could not write reflog file at {reflog_path:?}")]
+            Write {
+                source: std::io::Error,
+                reflog_path: PathBuf,
+            },
+            #[error("The rewritten reflog could not be moved into place")]
+            Commit(#[source] std::io::Error),
+        }
+    }
+    pub use error::Error;
+}
+
 ///
 pub mod create_or_update {
     use std::{
@@ -123,5 +123,8 @@ pub mod transaction;
 ///
 pub mod packed;
 
+///
+pub mod pack_refs;
+
 mod raw_ext;
 pub use raw_ext::ReferenceExt;
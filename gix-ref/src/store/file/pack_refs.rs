@@ -0,0 +1,109 @@
+use crate::{
+    file,
+    file::transaction::PackedRefs,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Category, Target,
+};
+
+/// Options for use in [`file::Store::pack_refs()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If `true`, pack all references, like `git pack-refs --all`. Otherwise, only tags and references that are already
+    /// in `packed-refs` are packed.
+    pub all: bool,
+    /// If `true`, delete loose references after they were packed, like `git pack-refs --prune`.
+    pub prune: bool,
+}
+
+/// The result of [`file::Store::pack_refs()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of loose references that were written into `packed-refs`.
+    pub packed: usize,
+}
+
+/// The error returned by [`file::Store::pack_refs()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Loose references could not be traversed")]
+    Iter(#[from] std::io::Error),
+    #[error(transparent)]
+    OpenPacked(#[from] crate::packed::buffer::open::Error),
+    #[error("The object a reference points to could not be looked up")]
+    FindObject(#[from] gix_object::find::Error),
+    #[error(transparent)]
+    Prepare(#[from] file::transaction::prepare::Error),
+    #[error(transparent)]
+    Commit(#[from] file::transaction::commit::Error),
+}
+
+impl file::Store {
+    /// Write loose references into `packed-refs` as configured in `options`, and use `objects` to peel annotated tags.
+    /// All affected references are locked according to `ref_files_lock_fail_mode`, and `packed-refs` is locked according
+    /// to `packed_refs_lock_fail_mode` before it's replaced atomically.
+    ///
+    /// Symbolic references, references that are private to a worktree and references that point to objects not
+    /// contained in `objects` are never packed.
+    pub fn pack_refs(
+        &self,
+        options: Options,
+        objects: impl gix_object::Find,
+        ref_files_lock_fail_mode: gix_lock::acquire::Fail,
+        packed_refs_lock_fail_mode: gix_lock::acquire::Fail,
+    ) -> Result<Outcome, Error> {
+        let packed = self.cached_packed_buffer()?;
+        let mut buf = Vec::new();
+        let mut edits = Vec::new();
+        for reference in self.loose_iter()? {
+            // Just like git, we skip over references that can't be parsed.
+            let Ok(reference) = reference else { continue };
+            let Target::Object(id) = reference.target else {
+                continue;
+            };
+            let name = reference.name.as_ref();
+            let is_eligible = match name.category() {
+                Some(category) if category.is_worktree_private() => false,
+                Some(Category::Tag) => true,
+                _ => options.all || packed.as_ref().map_or(false, |packed| packed.find(name).is_ok()),
+            };
+            if !is_eligible || objects.try_find(&id, &mut buf)?.is_none() {
+                continue;
+            }
+            edits.push(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        log_symbolic_changes: false,
+                        message: "pack-refs".into(),
+                    },
+                    expected: PreviousValue::MustExistAndMatch(reference.target.clone()),
+                    new: reference.target,
+                },
+                name: reference.name,
+                deref: false,
+            });
+        }
+        drop(packed);
+
+        let out = Outcome { packed: edits.len() };
+        if edits.is_empty() {
+            return Ok(out);
+        }
+        // The names are already fully qualified, so our namespace must not be applied to them again.
+        let mut store = self.clone();
+        store.namespace = None;
+        let objects = Box::new(objects);
+        store
+            .transaction()
+            .packed_refs(if options.prune {
+                PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(objects)
+            } else {
+                PackedRefs::DeletionsAndNonSymbolicUpdates(objects)
+            })
+            .prepare(edits, ref_files_lock_fail_mode, packed_refs_lock_fail_mode)?
+            .commit(None)?;
+        Ok(out)
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{
-    store_impl::file::{transaction::PackedRefs, Transaction},
+    store_impl::{
+        file,
+        file::{transaction::PackedRefs, Transaction},
+    },
     transaction::{Change, LogChange, RefEdit, RefLog},
     Target,
 };
@@ -49,17 +52,26 @@ impl<'s, 'p> Transaction<'s, 'p> {
                     };
                     if update_reflog {
                         let log_update = match new {
-                            Target::Symbolic(_) => {
-                                // Special HACK: no reflog for symref changes as there is no OID involved which the reflog needs.
-                                // Unless, the ref is new and we can obtain a peeled id
+                            Target::Symbolic(_) => match expected {
+                                // Special HACK: there is no OID involved which the reflog needs,
+                                // unless the ref is new and we can obtain a peeled id
                                 // identified by the expectation of what could be there, as is the case when cloning.
-                                match expected {
-                                    PreviousValue::ExistingMustMatch(Target::Object(oid)) => {
-                                        Some((Some(gix_hash::ObjectId::null(oid.kind())), oid))
+                                PreviousValue::ExistingMustMatch(Target::Object(oid)) => {
+                                    Some((Some(gix_hash::ObjectId::null(oid.kind())), oid.to_owned(), false))
+                                }
+                                // An existing ref now points to another ref, like `HEAD` when switching branches,
+                                // which is logged with the peeled ids like git does, even if they are the same.
+                                // If the new referent doesn't exist yet, there is nothing to log.
+                                PreviousValue::MustExistAndMatch(previous) if log.log_symbolic_changes => {
+                                    match self.store.peel_target(new)? {
+                                        Some(new_oid) => {
+                                            Some((self.store.peel_target(previous)?, new_oid, previous != new))
+                                        }
+                                        None => None,
                                     }
-                                    _ => None,
                                 }
-                            }
+                                _ => None,
+                            },
                             Target::Object(new_oid) => {
                                 let previous = match expected {
                                     // Here, this means that the ref already existed, and that it will receive (even transitively)
@@ -68,16 +80,16 @@ impl<'s, 'p> Transaction<'s, 'p> {
                                     _ => None,
                                 }
                                 .or(change.leaf_referent_previous_oid);
-                                Some((previous, new_oid))
+                                Some((previous, new_oid.to_owned(), false))
                             }
                         };
-                        if let Some((previous, new_oid)) = log_update {
-                            let do_update = previous.as_ref().map_or(true, |previous| previous != new_oid);
+                        if let Some((previous, new_oid, always)) = log_update {
+                            let do_update = always || previous.as_ref().map_or(true, |previous| *previous != new_oid);
                             if do_update {
                                 self.store.reflog_create_or_append(
                                     change.update.name.as_ref(),
                                     previous,
-                                    new_oid,
+                                    &new_oid,
                                     committer,
                                     log.message.as_ref(),
                                     log.force_create_reflog,
@@ -197,8 +209,29 @@ mod error {
         DeleteReflog { full_name: BString, source: std::io::Error },
         #[error("The reflog could not be created or updated")]
         CreateOrUpdateRefLog(#[from] file::log::create_or_update::Error),
+        #[error("The target of a symbolic reference could not be peeled to log its change")]
+        PeelSymbolicTarget(#[from] file::find::Error),
     }
 }
 pub use error::Error;
 
 use crate::transaction::PreviousValue;
+
+impl file::Store {
+    /// Follow `target` through symbolic references to the id it points to, or return `None` if one of them doesn't exist,
+    /// or if there are more symbolic references than `git` would follow.
+    fn peel_target(&self, target: &Target) -> Result<Option<gix_hash::ObjectId>, file::find::Error> {
+        const MAX_SYMBOLIC_DEPTH: usize = 5;
+        let mut target = target.clone();
+        for _ in 0..=MAX_SYMBOLIC_DEPTH {
+            match target {
+                Target::Object(oid) => return Ok(Some(oid)),
+                Target::Symbolic(name) => match self.try_find(name.as_ref())? {
+                    Some(reference) => target = reference.target,
+                    None => return Ok(None),
+                },
+            }
+        }
+        Ok(None)
+    }
+}
@@ -418,6 +418,14 @@ impl<'s, 'p> Transaction<'s, 'p> {
             .map(|updates| updates.into_iter().map(|u| u.update).collect())
             .unwrap_or_default()
     }
+
+    /// Return the `RefEdits` as they were [prepared](Self::prepare()), or nothing if this transaction isn't prepared yet.
+    ///
+    /// Just like with [`rollback()`](Self::rollback()), they have been split and know about their current state on disk,
+    /// which makes them suitable to inform others about the changes that are about to be committed.
+    pub fn prepared_edits(&self) -> impl Iterator<Item = &RefEdit> + '_ {
+        self.updates.iter().flatten().map(|edit| &edit.update)
+    }
 }
 
 fn possibly_adjust_name_for_prefixes(name: &FullNameRef) -> Option<FullName> {
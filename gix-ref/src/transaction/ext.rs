@@ -98,6 +98,7 @@ where
                                         message: log.message.clone(),
                                         mode: RefLog::Only,
                                         force_create_reflog: log.force_create_reflog,
+                                        log_symbolic_changes: false,
                                     },
                                 );
                                 let next = std::mem::replace(expected, PreviousValue::Any);
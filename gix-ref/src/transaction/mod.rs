@@ -24,6 +24,10 @@ pub struct LogChange {
     /// If set, create a reflog even though it would otherwise not be the case as prohibited by general rules.
     /// Note that ref-log writing might be prohibited in the entire repository which is when this flag has no effect either.
     pub force_create_reflog: bool,
+    /// If set, a change of an existing reference to point to another reference is logged as well, with the ids both
+    /// targets peel to, like `git` does for `HEAD` when switching branches.
+    /// Nothing is logged if the new target can't be peeled, for instance because it is an unborn branch.
+    pub log_symbolic_changes: bool,
     /// The message to put into the reference log. It must be a single line, hence newlines are forbidden.
     /// The string can be empty to indicate there should be no message at all.
     pub message: BString,
@@ -34,6 +38,7 @@ impl Default for LogChange {
         LogChange {
             mode: RefLog::AndReference,
             force_create_reflog: false,
+            log_symbolic_changes: false,
             message: Default::default(),
         }
     }
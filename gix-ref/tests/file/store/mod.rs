@@ -9,6 +9,7 @@ use gix_ref::Target;
 mod access;
 mod find;
 mod iter;
+mod pack_refs;
 mod reflog;
 
 #[test]
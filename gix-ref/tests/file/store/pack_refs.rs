@@ -0,0 +1,72 @@
+use gix_lock::acquire::Fail;
+use gix_ref::{file::pack_refs::Options, Target};
+
+use crate::file::store_writable;
+
+fn all_refs(store: &gix_ref::file::Store) -> crate::Result<Vec<(String, Target)>> {
+    Ok(store
+        .iter()?
+        .all()?
+        .filter_map(Result::ok)
+        .map(|r| (r.name.as_bstr().to_string(), r.target))
+        .collect())
+}
+
+fn loose_names(store: &gix_ref::file::Store) -> crate::Result<Vec<String>> {
+    Ok(store
+        .loose_iter()?
+        .filter_map(Result::ok)
+        .map(|r| r.name.as_bstr().to_string())
+        .collect())
+}
+
+#[test]
+fn tags_only_by_default_and_everything_with_all() -> crate::Result {
+    let (_keep, store) = store_writable("make_ref_repository.sh")?;
+    let odb = gix_odb::at(store.git_dir().join("objects"))?;
+    let before = all_refs(&store)?;
+    let loose_before = loose_names(&store)?;
+    let reflog_len = |store: &gix_ref::file::Store| -> crate::Result<usize> {
+        let mut buf = Vec::new();
+        Ok(store
+            .reflog_iter("refs/heads/main", &mut buf)?
+            .expect("present")
+            .count())
+    };
+    let reflog_before = reflog_len(&store)?;
+
+    let out = store.pack_refs(Options::default(), &odb, Fail::Immediately, Fail::Immediately)?;
+    assert_eq!(out.packed, 2, "only the two tags pointing to objects");
+    let packed = store.open_packed_buffer()?.expect("written");
+    let tag = packed.find("dt1")?;
+    assert!(tag.object.is_some(), "annotated tags are peeled");
+    assert_eq!(loose_names(&store)?, loose_before, "nothing is pruned");
+    assert_eq!(all_refs(&store)?, before);
+
+    let out = store.pack_refs(
+        Options { all: true, prune: true },
+        &odb,
+        Fail::Immediately,
+        Fail::Immediately,
+    )?;
+    assert_eq!(out.packed, 8);
+    assert_eq!(
+        loose_names(&store)?,
+        [
+            "refs/heads/multi-link-target1",
+            "refs/loop-a",
+            "refs/loop-b",
+            "refs/multi-link",
+            "refs/remotes/origin/HEAD",
+            "refs/tags/multi-link-target2"
+        ],
+        "only symbolic refs and those that can't be parsed remain loose"
+    );
+    assert!(store.git_dir().join("refs/broken").is_file());
+    assert_eq!(all_refs(&store)?, before, "all values are retained");
+    assert_eq!(reflog_len(&store)?, reflog_before, "reflogs aren't touched");
+
+    let out = store.pack_refs(Options::default(), &odb, Fail::Immediately, Fail::Immediately)?;
+    assert_eq!(out.packed, 0, "nothing left to pack");
+    Ok(())
+}
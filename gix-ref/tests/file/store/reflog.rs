@@ -67,3 +67,41 @@ mod iter_rev {
         Ok(())
     }
 }
+
+mod rewrite {
+    #[test]
+    fn replaces_all_lines_and_creates_missing_logs() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable_standalone("make_repo_for_reflog.sh")?;
+        let store = crate::file::Store::at(tmp.path().join(".git"), Default::default());
+        let mut buf = Vec::new();
+        let lines: Vec<_> = store
+            .reflog_iter("refs/heads/main", &mut buf)?
+            .expect("exists")
+            .map(|line| line.map(|line| line.to_owned()))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(lines.len(), 5);
+
+        let kept = [lines[0].clone(), lines[4].clone()];
+        store.reflog_rewrite("refs/heads/main", &kept, gix_lock::acquire::Fail::Immediately)?;
+        let rewritten: Vec<_> = store
+            .reflog_iter("refs/heads/main", &mut buf)?
+            .expect("exists")
+            .map(|line| line.map(|line| line.to_owned()))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(rewritten, kept);
+
+        store.reflog_rewrite(
+            "refs/heads/new/branch",
+            &kept[..1],
+            gix_lock::acquire::Fail::Immediately,
+        )?;
+        assert_eq!(
+            store
+                .reflog_iter("refs/heads/new/branch", &mut buf)?
+                .expect("created")
+                .count(),
+            1
+        );
+        Ok(())
+    }
+}
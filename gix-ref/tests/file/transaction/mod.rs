@@ -53,6 +53,7 @@ pub(crate) mod prepare_and_commit {
                 log: LogChange {
                     mode: RefLog::AndReference,
                     force_create_reflog: true,
+                    log_symbolic_changes: false,
                     message: "log peeled".into(),
                 },
                 expected: PreviousValue::MustNotExist,
@@ -54,6 +54,30 @@ fn intermediate_directories_are_removed_on_rollback() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn prepared_edits_are_available_until_commit() -> crate::Result {
+    let (_dir, store) = empty_store()?;
+    let transaction = store.transaction();
+    assert_eq!(transaction.prepared_edits().count(), 0, "nothing is prepared yet");
+
+    let transaction = transaction.prepare(
+        [create_at("refs/heads/a"), create_at("refs/heads/b")],
+        Fail::Immediately,
+        Fail::Immediately,
+    )?;
+    assert_eq!(
+        transaction
+            .prepared_edits()
+            .map(|edit| edit.name.as_bstr().to_owned())
+            .collect::<Vec<_>>(),
+        ["refs/heads/a", "refs/heads/b"]
+    );
+    let prepared: Vec<_> = transaction.prepared_edits().cloned().collect();
+    let edits = transaction.commit(committer().to_ref())?;
+    assert_eq!(edits, prepared, "these edits don't change while committing");
+    Ok(())
+}
+
 #[test]
 fn reference_with_equally_named_empty_or_non_empty_directory_already_in_place_can_potentially_recover() -> crate::Result
 {
@@ -393,6 +417,7 @@ fn symbolic_reference_writes_reflog_if_previous_value_is_set() -> crate::Result
     let log = LogChange {
         mode: RefLog::AndReference,
         force_create_reflog: false,
+        log_symbolic_changes: false,
         message: "message".into(),
     };
     let new_head_value = Target::Symbolic(referent.try_into().unwrap());
@@ -431,6 +456,72 @@ fn symbolic_reference_writes_reflog_if_previous_value_is_set() -> crate::Result
     Ok(())
 }
 
+#[test]
+fn symbolic_reference_change_writes_reflog_with_peeled_ids_if_requested() -> crate::Result {
+    let (_keep, store) = empty_store()?;
+    let (a, b) = (
+        hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242"),
+        hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+    );
+    let update = |name: &str, new: Target, message: &str, log_symbolic_changes: bool| RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: true,
+                log_symbolic_changes,
+                message: message.into(),
+            },
+            expected: PreviousValue::Any,
+            new,
+        },
+        name: name.try_into().expect("valid"),
+        deref: false,
+    };
+    let symbolic = |name: &str| -> crate::Result<Target> { Ok(Target::Symbolic(name.try_into()?)) };
+    store
+        .transaction()
+        .prepare(
+            [
+                update("refs/heads/a", Target::Object(a), "create a", false),
+                update("refs/heads/b", Target::Object(b), "create b", false),
+                update("refs/heads/link", symbolic("refs/heads/b")?, "create link", false),
+                update("HEAD", symbolic("refs/heads/a")?, "new HEAD", true),
+            ],
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    assert!(
+        !store.reflog_exists("HEAD")?,
+        "a new symbolic ref has no previous value, so nothing is logged"
+    );
+
+    for (target, message, log_symbolic_changes) in [
+        ("refs/heads/b", "not logged by default", false),
+        ("refs/heads/a", "b to a", true),
+        ("refs/heads/a", "a to a", true),
+        ("refs/heads/link", "a to b through link", true),
+        ("refs/heads/unborn", "unborn", true),
+    ] {
+        store
+            .transaction()
+            .prepare(
+                Some(update("HEAD", symbolic(target)?, message, log_symbolic_changes)),
+                Fail::Immediately,
+                Fail::Immediately,
+            )?
+            .commit(committer().to_ref())?;
+    }
+
+    assert_eq!(
+        reflog_lines(&store, "HEAD")?,
+        vec![log_line(b, a, "b to a"), log_line(a, b, "a to b through link")],
+        "switching between refs is logged with fully peeled ids only if requested, but nothing is logged \
+         if the target didn't change, or if the new referent doesn't exist"
+    );
+    Ok(())
+}
+
 #[test]
 fn windows_device_name_is_illegal_with_enabled_windows_protections() -> crate::Result {
     let (_keep, mut store) = empty_store()?;
@@ -438,6 +529,7 @@ fn windows_device_name_is_illegal_with_enabled_windows_protections() -> crate::R
     let log_ignored = LogChange {
         mode: RefLog::AndReference,
         force_create_reflog: false,
+        log_symbolic_changes: false,
         message: "ignored".into(),
     };
 
@@ -501,6 +593,7 @@ fn symbolic_head_missing_referent_then_update_referent() -> crate::Result {
         let log_ignored = LogChange {
             mode: RefLog::AndReference,
             force_create_reflog: false,
+            log_symbolic_changes: false,
             message: "ignored".into(),
         };
         let new_head_value = Target::Symbolic(referent.try_into().unwrap());
@@ -555,6 +648,7 @@ fn symbolic_head_missing_referent_then_update_referent() -> crate::Result {
             message: "an actual change".into(),
             mode: RefLog::AndReference,
             force_create_reflog: false,
+            log_symbolic_changes: false,
         };
         let edits = store
             .transaction()
@@ -658,6 +752,7 @@ fn write_reference_to_which_head_points_to_does_not_update_heads_reflog_even_tho
                     log: LogChange {
                         mode: RefLog::AndReference,
                         force_create_reflog: false,
+                        log_symbolic_changes: false,
                         message: "".into(),
                     },
                     expected: PreviousValue::MustExist,
@@ -679,6 +774,7 @@ fn write_reference_to_which_head_points_to_does_not_update_heads_reflog_even_tho
                 log: LogChange {
                     mode: RefLog::AndReference,
                     force_create_reflog: false,
+                    log_symbolic_changes: false,
                     message: "".into(),
                 },
                 expected: PreviousValue::MustExistAndMatch(Target::Object(hex_to_id(
@@ -724,6 +820,7 @@ fn packed_refs_are_looked_up_when_checking_existing_values() -> crate::Result {
                     log: LogChange {
                         mode: RefLog::AndReference,
                         force_create_reflog: false,
+                        log_symbolic_changes: false,
                         message: "for pack".into(),
                     },
                     expected: PreviousValue::MustExistAndMatch(Target::Object(old_id)),
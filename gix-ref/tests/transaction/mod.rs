@@ -202,6 +202,7 @@ mod refedit_ext {
                         log: LogChange {
                             mode: RefLog::AndReference,
                             force_create_reflog: true,
+                            log_symbolic_changes: false,
                             message: "the log message".into(),
                         },
                         new: Target::Object(gix_hash::Kind::Sha1.null()),
@@ -254,6 +255,7 @@ mod refedit_ext {
             let log = LogChange {
                 mode: RefLog::AndReference,
                 force_create_reflog: true,
+                log_symbolic_changes: false,
                 message: "the log message".into(),
             };
             let log_only = {
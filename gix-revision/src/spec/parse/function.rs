@@ -414,7 +414,7 @@ where
     input = {
         if let Some(b'@') = sep {
             let past_sep = input[sep_pos.map_or(input.len(), |pos| pos + 1)..].as_bstr();
-            let (nav, rest, _consumed) = parens(past_sep)?.ok_or_else(|| Error::AtNeedsCurlyBrackets {
+            let (nav, mut rest, _consumed) = parens(past_sep)?.ok_or_else(|| Error::AtNeedsCurlyBrackets {
                 input: input[sep_pos.unwrap_or(input.len())..].into(),
             })?;
             let nav = nav.as_ref();
@@ -424,6 +424,15 @@ where
                         delegate
                             .nth_checked_out_branch(n.unsigned_abs())
                             .ok_or(Error::Delegate)?;
+                        // Like `git`, allow to obtain the upstream or push branch of the prior checkout.
+                        if let Some((kind, sibling_rest)) =
+                            rest.strip_prefix(b"@").map(parens).transpose()?.flatten().and_then(
+                                |(nav, rest, _consumed)| SiblingBranch::parse(nav.as_ref()).map(|kind| (kind, rest)),
+                            )
+                        {
+                            delegate.sibling_branch(kind).ok_or(Error::Delegate)?;
+                            rest = sibling_rest;
+                        }
                     } else {
                         return Err(Error::RefnameNeedsPositiveReflogEntries { nav: nav.into() });
                     }
@@ -185,6 +185,29 @@ fn nth_checked_out_branch() {
     }
 }
 
+#[test]
+fn sibling_branch_of_nth_checked_out_branch() {
+    for (spec, kind_name) in [("@{-1}@{u}", "Upstream"), ("@{-1}@{push}", "Push")] {
+        let rec = parse(spec);
+
+        assert!(rec.kind.is_none());
+        assert_eq!(rec.find_ref[0], None);
+        assert_eq!(rec.nth_checked_out_branch[0], Some(1));
+        assert_eq!(rec.sibling_branch[0].as_deref(), Some(kind_name));
+        assert_eq!(rec.calls, 2);
+    }
+
+    let rec = parse("@{-2}@{u}~1");
+    assert_eq!(rec.sibling_branch[0].as_deref(), Some("Upstream"));
+    assert_eq!(rec.traversal, [spec::parse::delegate::Traversal::NthAncestor(1)]);
+
+    let err = try_parse("@{-1}@{1}").unwrap_err();
+    assert!(
+        matches!(err, spec::parse::Error::UnconsumedInput {input} if input == "@{1}"),
+        "only the upstream and push branches are supported"
+    );
+}
+
 #[test]
 fn numbers_within_braces_cannot_be_negative_zero() {
     let err = try_parse("@{-0}").unwrap_err();
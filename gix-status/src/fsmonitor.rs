@@ -0,0 +1,264 @@
+//! Query filesystem monitors to learn which paths in the worktree changed since they were last asked.
+//!
+//! The result can be [applied](apply_changes()) to an index, so that [`index_as_worktree()`](crate::index_as_worktree())
+//! only has to look at entries that were reported as changed, while an untracked cache only needs to be refreshed for the
+//! directories that actually changed.
+use std::path::Path;
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_index::extension::fs_monitor::Token;
+
+/// The filesystem monitor to query.
+#[derive(Debug, Clone, Copy)]
+pub enum Source<'a> {
+    /// A hook program as configured with `core.fsmonitor`, which is executed with the protocol version and the
+    /// last token as arguments.
+    Hook {
+        /// The command to execute, possibly as shell script.
+        command: &'a BStr,
+        /// The version of the hook protocol to use, as configured with `core.fsmonitorHookVersion`.
+        /// If `None`, version 2 is tried first, with a fallback to version 1.
+        version: Option<HookVersion>,
+    },
+    /// The builtin filesystem monitor, `git fsmonitor--daemon`, which listens on the IPC socket at the given path.
+    Daemon {
+        /// The path to the socket the daemon listens on, typically `$GIT_DIR/fsmonitor--daemon.ipc`.
+        socket: &'a Path,
+    },
+}
+
+/// The version of the protocol to use when invoking a [hook](Source::Hook).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookVersion {
+    /// Pass the time of the last query in nanoseconds since the unix epoch, and receive a list of changed paths.
+    V1,
+    /// Pass an opaque token, and receive a new token along with a list of changed paths.
+    V2,
+}
+
+/// What changed in the worktree according to the filesystem monitor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Changes {
+    /// Everything has to be assumed as changed, which happens if there was no previous token or if the filesystem
+    /// monitor couldn't provide an answer.
+    All,
+    /// Only the given worktree-relative paths changed since the last query.
+    ///
+    /// Paths with a trailing slash are directories, for which everything they contain has to be assumed as changed.
+    Paths(Vec<BString>),
+}
+
+/// The result of [`query()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The token to store in the index to pass to the next query, or `None` if no token could be obtained.
+    pub token: Option<Token>,
+    /// What changed since the token passed to [`query()`] was obtained.
+    pub changes: Changes,
+}
+
+///
+pub mod query {
+    /// The error returned by [`query()`](super::query()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not execute the filesystem monitor hook '{command}'")]
+        SpawnHook { command: String, source: std::io::Error },
+        #[error("Could not communicate with the filesystem monitor daemon at '{}'", socket.display())]
+        Daemon {
+            socket: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("The filesystem monitor daemon is not supported on this platform")]
+        DaemonUnsupported,
+    }
+}
+
+/// Query the filesystem monitor identified by `source` for all changes in the worktree at `worktree` since `token`
+/// was obtained from it, typically from the [fsmonitor extension](gix_index::State::fs_monitor()) of the index.
+///
+/// If `token` is `None`, all paths are considered changed and only a new token is obtained.
+/// Hooks that fail or answer in an unexpected way are treated as if all paths changed, just like `git` does.
+pub fn query(source: Source<'_>, worktree: &Path, token: Option<&Token>) -> Result<Outcome, query::Error> {
+    let _span = gix_features::trace::coarse!("gix_status::fsmonitor::query()");
+    let token = token.map(|token| match token {
+        Token::V1 { nanos_since_1970 } => BString::from(nanos_since_1970.to_string()),
+        Token::V2 { token } => token.clone(),
+    });
+    match source {
+        Source::Hook { command, version } => query_hook(command, version, worktree, token),
+        Source::Daemon { socket } => {
+            let Some(response) = query_daemon(socket, token.as_ref().map(|t| t.as_bstr()))? else {
+                return Ok(Outcome {
+                    token: None,
+                    changes: Changes::All,
+                });
+            };
+            Ok(parse_v2_response(&response).unwrap_or(Outcome {
+                token: None,
+                changes: Changes::All,
+            }))
+        }
+    }
+}
+
+/// Apply `changes` to `index` by removing the [`FSMONITOR_VALID`](gix_index::entry::Flags::FSMONITOR_VALID) flag
+/// from all changed entries, and by invalidating all affected directories in its
+/// [untracked cache](gix_index::State::untracked()).
+///
+/// Note that entries that aren't mentioned in `changes` keep their flags, as they may not have been valid before
+/// either. It's up to the caller to mark entries as valid once they are known to be unchanged.
+pub fn apply_changes(changes: &Changes, index: &mut gix_index::State) {
+    let paths = match changes {
+        Changes::All => {
+            for entry in index.entries_mut() {
+                entry.flags.remove(gix_index::entry::Flags::FSMONITOR_VALID);
+            }
+            return;
+        }
+        Changes::Paths(paths) => paths,
+    };
+
+    for path in paths {
+        let is_dir = path.ends_with(b"/");
+        let path = path.strip_suffix(b"/").unwrap_or(path).as_bstr();
+        let range = if is_dir { None } else { index.entry_range(path) }.or_else(|| {
+            let mut prefix = path.to_owned();
+            prefix.push(b'/');
+            index.prefixed_entries_range(prefix.as_bstr())
+        });
+        if let Some(range) = range {
+            for entry in &mut index.entries_mut()[range] {
+                entry.flags.remove(gix_index::entry::Flags::FSMONITOR_VALID);
+            }
+        }
+
+        if let Some(untracked) = index.untracked_mut() {
+            // Invalidate the parent directory, whose listing may have changed, and the directory itself if it is one.
+            untracked.invalidate_path(path);
+            let mut dir = path.to_owned();
+            dir.push(b'/');
+            untracked.invalidate_path(dir.as_bstr());
+        }
+    }
+}
+
+fn query_hook(
+    command: &BStr,
+    version: Option<HookVersion>,
+    worktree: &Path,
+    token: Option<BString>,
+) -> Result<Outcome, query::Error> {
+    // Changes that happen while the hook runs will be reported next time.
+    let now = Token::V1 {
+        nanos_since_1970: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64),
+    };
+    let Some(token) = token else {
+        return Ok(Outcome {
+            token: Some(now),
+            changes: Changes::All,
+        });
+    };
+
+    if version != Some(HookVersion::V1) {
+        if let Some(out) = run_hook(command, 2, worktree, token.as_bstr())?.and_then(|out| parse_v2_response(&out)) {
+            return Ok(out);
+        }
+        if version == Some(HookVersion::V2) {
+            return Ok(Outcome {
+                token: Some(now),
+                changes: Changes::All,
+            });
+        }
+    }
+
+    let changes = run_hook(command, 1, worktree, token.as_bstr())?.map_or(Changes::All, |out| parse_paths(&out));
+    Ok(Outcome {
+        token: Some(now),
+        changes,
+    })
+}
+
+/// Run the hook and return its output if it succeeded.
+fn run_hook(command: &BStr, version: u8, worktree: &Path, token: &BStr) -> Result<Option<Vec<u8>>, query::Error> {
+    let mut cmd: std::process::Command = gix_command::prepare(gix_path::from_bstr(command).into_owned())
+        .with_shell()
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::inherit())
+        .arg(version.to_string())
+        .arg(gix_path::from_bstr(token).into_owned())
+        .into();
+    cmd.current_dir(worktree);
+    gix_features::trace::debug!(cmd = ?cmd, "Querying filesystem monitor hook");
+    let out = cmd.output().map_err(|err| query::Error::SpawnHook {
+        command: command.to_string(),
+        source: err,
+    })?;
+    Ok(out.status.success().then_some(out.stdout))
+}
+
+/// Send `token` to the daemon listening on `socket` using the simple IPC protocol of `git`, and return its response,
+/// or `None` if the daemon isn't running.
+#[cfg(unix)]
+fn query_daemon(socket: &Path, token: Option<&BStr>) -> Result<Option<Vec<u8>>, query::Error> {
+    use std::io::{Read, Write};
+
+    let err = |source: std::io::Error| query::Error::Daemon {
+        socket: socket.to_owned(),
+        source,
+    };
+    let stream = match std::os::unix::net::UnixStream::connect(socket) {
+        Ok(stream) => stream,
+        Err(err)
+            if matches!(
+                err.kind(),
+                std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            return Ok(None)
+        }
+        Err(source) => return Err(err(source)),
+    };
+
+    let mut out = gix_packetline::Writer::new(&stream);
+    // The daemon answers with a new token and reports everything as changed for tokens it doesn't know.
+    out.write_all(token.map_or(b"builtin:fake".as_slice(), |token| token.as_bytes()))
+        .map_err(err)?;
+    gix_packetline::encode::flush_to_write(out.inner_mut()).map_err(err)?;
+    out.flush().map_err(err)?;
+
+    let mut input = gix_packetline::StreamingPeekableIter::new(&stream, &[gix_packetline::PacketLineRef::Flush], false);
+    let mut response = Vec::new();
+    input.as_read().read_to_end(&mut response).map_err(err)?;
+    Ok(Some(response))
+}
+
+#[cfg(not(unix))]
+fn query_daemon(_socket: &Path, _token: Option<&BStr>) -> Result<Option<Vec<u8>>, query::Error> {
+    Err(query::Error::DaemonUnsupported)
+}
+
+/// Parse `<token>\0<path>\0<path>…`, or return `None` if there is no token.
+fn parse_v2_response(response: &[u8]) -> Option<Outcome> {
+    let (token, paths) = response.split_once_str(b"\0")?;
+    if token.is_empty() {
+        return None;
+    }
+    Some(Outcome {
+        token: Some(Token::V2 { token: token.into() }),
+        changes: parse_paths(paths),
+    })
+}
+
+/// Parse null-separated paths, with `/` as first path indicating that everything changed.
+fn parse_paths(paths: &[u8]) -> Changes {
+    let mut paths = paths.split(|b| *b == 0).filter(|path| !path.is_empty()).peekable();
+    if paths.peek().map_or(false, |path| path.starts_with(b"/")) {
+        return Changes::All;
+    }
+    Changes::Paths(paths.map(Into::into).collect())
+}
@@ -356,6 +356,10 @@ pub struct DirwalkContext<'a> {
     ///
     /// [Read more in `gix-dir`](gix_dir::walk::Context::ignore_case_index_lookup).
     pub ignore_case_index_lookup: Option<&'a gix_index::AccelerateLookup<'a>>,
+    /// The untracked cache to use and update during the directory walk, if any.
+    ///
+    /// [Read more in `gix-dir`](gix_dir::walk::Context::untracked_cache).
+    pub untracked_cache: Option<gix_dir::walk::UntrackedCache<'a>>,
 }
 
 /// Observe the status of an entry by comparing an index entry to the worktree, along
@@ -0,0 +1,199 @@
+use bstr::ByteSlice;
+use gix_index::entry::Flags;
+use gix_index::extension::fs_monitor::Token;
+use gix_status::fsmonitor::{apply_changes, query, Changes, HookVersion, Outcome, Source};
+
+use crate::fixture_path;
+
+#[test]
+fn hook_v2() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let hook = write_hook(tmp.path(), r#"echo "$@" > args; printf 'new-token\0file\0dir/\0'"#)?;
+    let out = query(
+        Source::Hook {
+            command: hook.as_str().into(),
+            version: None,
+        },
+        tmp.path(),
+        Some(&Token::V2 { token: "old".into() }),
+    )?;
+    assert_eq!(
+        out,
+        Outcome {
+            token: Some(Token::V2 {
+                token: "new-token".into()
+            }),
+            changes: Changes::Paths(vec!["file".into(), "dir/".into()]),
+        }
+    );
+    assert_eq!(
+        std::fs::read(tmp.path().join("args"))?.as_bstr(),
+        "2 old\n",
+        "the hook runs in the worktree and receives the version and token"
+    );
+    Ok(())
+}
+
+#[test]
+fn hook_v1_fallback() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let hook = write_hook(
+        tmp.path(),
+        r#"test "$1" = 2 && exit 1; echo "$@" > args; printf 'file\0'"#,
+    )?;
+    let out = query(
+        Source::Hook {
+            command: hook.as_str().into(),
+            version: None,
+        },
+        tmp.path(),
+        Some(&Token::V1 { nanos_since_1970: 42 }),
+    )?;
+    assert!(
+        matches!(out.token, Some(Token::V1 { nanos_since_1970 }) if nanos_since_1970 > 42),
+        "the time of the query is the next token"
+    );
+    assert_eq!(out.changes, Changes::Paths(vec!["file".into()]));
+    assert_eq!(std::fs::read(tmp.path().join("args"))?.as_bstr(), "1 42\n");
+
+    let out = query(
+        Source::Hook {
+            command: hook.as_str().into(),
+            version: Some(HookVersion::V2),
+        },
+        tmp.path(),
+        Some(&Token::V1 { nanos_since_1970: 42 }),
+    )?;
+    assert_eq!(
+        out.changes,
+        Changes::All,
+        "without fallback, a failing hook means everything has to be checked"
+    );
+    Ok(())
+}
+
+#[test]
+fn hook_without_token_or_with_trivial_response() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let hook = write_hook(tmp.path(), r#"touch called; printf 'token\0/\0'"#)?;
+    let source = Source::Hook {
+        command: hook.as_str().into(),
+        version: None,
+    };
+    let out = query(source, tmp.path(), None)?;
+    assert_eq!(out.changes, Changes::All);
+    assert!(out.token.is_some());
+    assert!(
+        !tmp.path().join("called").exists(),
+        "without token, there is nothing to ask for"
+    );
+
+    let out = query(source, tmp.path(), out.token.as_ref())?;
+    assert_eq!(out.changes, Changes::All, "'/' indicates that everything changed");
+    assert!(tmp.path().join("called").exists());
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn daemon() -> crate::Result {
+    use std::io::{Read, Write};
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let socket = tmp.path().join("fsmonitor--daemon.ipc");
+    let listener = std::os::unix::net::UnixListener::bind(&socket)?;
+    let server = std::thread::spawn(move || -> std::io::Result<Vec<u8>> {
+        let (mut stream, _) = listener.accept()?;
+        let mut request = Vec::new();
+        loop {
+            let mut len = [0u8; 4];
+            stream.read_exact(&mut len)?;
+            let len = usize::from_str_radix(std::str::from_utf8(&len).expect("hex"), 16).expect("valid length");
+            if len == 0 {
+                break;
+            }
+            let mut data = vec![0; len - 4];
+            stream.read_exact(&mut data)?;
+            request.extend(data);
+        }
+        let response = b"new-token\0dir/file\0";
+        write!(stream, "{:04x}", response.len() + 4)?;
+        stream.write_all(response)?;
+        stream.write_all(b"0000")?;
+        Ok(request)
+    });
+
+    let out = query(
+        Source::Daemon { socket: &socket },
+        tmp.path(),
+        Some(&Token::V2 { token: "old".into() }),
+    )?;
+    assert_eq!(server.join().expect("no panic")?.as_bstr(), "old");
+    assert_eq!(
+        out,
+        Outcome {
+            token: Some(Token::V2 {
+                token: "new-token".into()
+            }),
+            changes: Changes::Paths(vec!["dir/file".into()]),
+        }
+    );
+
+    drop(tmp);
+    let out = query(Source::Daemon { socket: &socket }, std::path::Path::new("."), None)?;
+    assert_eq!(
+        out,
+        Outcome {
+            token: None,
+            changes: Changes::All
+        },
+        "if the daemon isn't running, everything has to be checked"
+    );
+    Ok(())
+}
+
+#[test]
+fn apply_changes_to_index() -> crate::Result {
+    let mut index = gix_index::File::at(
+        fixture_path("status_unchanged").join(".git/index"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )?;
+    for entry in index.entries_mut() {
+        entry.flags.insert(Flags::FSMONITOR_VALID);
+    }
+
+    apply_changes(
+        &Changes::Paths(vec!["empty".into(), "dir/sub-dir".into(), "does-not-exist".into()]),
+        &mut index,
+    );
+    let invalid: Vec<_> = index
+        .entries()
+        .iter()
+        .filter(|e| !e.flags.contains(Flags::FSMONITOR_VALID))
+        .map(|e| e.path(&index))
+        .collect();
+    assert_eq!(
+        invalid,
+        ["dir/sub-dir/symlink", "empty"],
+        "directories may be reported without trailing slash"
+    );
+
+    apply_changes(&Changes::All, &mut index);
+    assert!(index
+        .entries()
+        .iter()
+        .all(|e| !e.flags.contains(Flags::FSMONITOR_VALID)));
+    Ok(())
+}
+
+fn write_hook(dir: &std::path::Path, script: &str) -> std::io::Result<String> {
+    let path = dir.join("hook.sh");
+    std::fs::write(&path, format!("#!/bin/sh\n{script}\n"))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(path.to_str().expect("valid UTF-8").to_owned())
+}
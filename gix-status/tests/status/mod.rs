@@ -1,3 +1,4 @@
+mod fsmonitor;
 mod index_as_worktree;
 mod index_as_worktree_with_renames;
 
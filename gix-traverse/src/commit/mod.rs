@@ -15,6 +15,8 @@ pub struct Simple<Find, Predicate> {
     state: simple::State,
     parents: Parents,
     sorting: simple::Sorting,
+    changed_paths: Option<simple::ChangedPaths>,
+    shallow_commits: Vec<ObjectId>,
 }
 
 /// Simple ancestors traversal, without the need to keep track of graph-state.
@@ -35,6 +37,7 @@ pub struct Topo<Find, Predicate> {
     topo_queue: topo::iter::Queue,
     parents: Parents,
     min_gen: u32,
+    shallow_commits: Vec<ObjectId>,
     buf: Vec<u8>,
 }
 
@@ -1,6 +1,8 @@
+use gix_commitgraph::bloom;
 use gix_date::SecondsSinceUnixEpoch;
 use gix_hash::ObjectId;
 use gix_hashtable::HashSet;
+use gix_object::bstr::BString;
 use smallvec::SmallVec;
 use std::collections::VecDeque;
 
@@ -72,15 +74,24 @@ pub(super) struct State {
     parent_ids: SmallVec<[(ObjectId, SecondsSinceUnixEpoch); 2]>,
 }
 
+/// The paths a traversal is limited to, see [`Simple::changed_paths()`](super::Simple::changed_paths()).
+pub(super) struct ChangedPaths {
+    /// Paths without leading or trailing slashes, or empty to refer to the whole tree.
+    paths: Vec<BString>,
+    /// The Bloom filter keys of `paths`, for each of the Bloom filter settings encountered so far.
+    keys: Vec<(bloom::Settings, Vec<Vec<bloom::Key>>)>,
+    buf: Vec<u8>,
+}
+
 ///
 mod init {
     use gix_date::SecondsSinceUnixEpoch;
     use gix_hash::{oid, ObjectId};
-    use gix_object::{CommitRefIter, FindExt};
+    use gix_object::{bstr::BString, CommitRefIter, FindExt};
 
     use super::{
         super::{simple::Sorting, Either, Info, ParentIds, Parents, Simple},
-        collect_parents, Error, State,
+        collect_parents, ChangedPaths, Error, State,
     };
 
     impl Default for State {
@@ -147,6 +158,31 @@ mod init {
             self
         }
 
+        /// Only return commits which changed any of `paths` compared to their first parent, or which contain any of them
+        /// if they are root commits, similar to `git log --first-parent -- <paths>`.
+        ///
+        /// `paths` are relative to the repository root and use slashes as separators. They may refer to files or directories,
+        /// with an empty path referring to the whole tree. If there are no `paths`, all commits are returned.
+        /// Note that commits which aren't returned are still traversed, and that merge commits are only compared to
+        /// their first parent, even if all parents are traversed.
+        ///
+        /// If a [commit-graph](Self::commit_graph()) with changed-path Bloom filters is set, commits that definitely
+        /// didn't change any of `paths` are skipped without looking at their trees.
+        pub fn changed_paths(mut self, paths: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+            self.changed_paths = Some(ChangedPaths::new(paths.into_iter().map(Into::into)));
+            self
+        }
+
+        /// Treat `commits` as the *shallow boundary*, that is as commits without parents, as their parents aren't
+        /// available in a shallow repository. This is what `git` does for all commits listed in `.git/shallow`.
+        ///
+        /// Without it, traversing past the shallow boundary fails as the parents of these commits can't be found.
+        pub fn shallow_commits(mut self, commits: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+            self.shallow_commits = commits.into_iter().map(Into::into).collect();
+            self.shallow_commits.sort();
+            self
+        }
+
         /// Set the commitgraph as `cache` to greatly accelerate any traversal.
         ///
         /// The cache will be used if possible, but we will fall-back without error to using the object
@@ -224,6 +260,8 @@ mod init {
                 state,
                 parents: Default::default(),
                 sorting: Default::default(),
+                changed_paths: None,
+                shallow_commits: Vec::new(),
             }
         }
     }
@@ -249,15 +287,28 @@ mod init {
         type Item = Result<Info, Error>;
 
         fn next(&mut self) -> Option<Self::Item> {
-            if matches!(self.parents, Parents::First) {
-                self.next_by_topology()
-            } else {
-                match self.sorting {
-                    Sorting::BreadthFirst => self.next_by_topology(),
-                    Sorting::ByCommitTimeNewestFirst => self.next_by_commit_date(None),
-                    Sorting::ByCommitTimeNewestFirstCutoffOlderThan { seconds } => {
-                        self.next_by_commit_date(seconds.into())
+            loop {
+                let info = if matches!(self.parents, Parents::First) {
+                    self.next_by_topology()
+                } else {
+                    match self.sorting {
+                        Sorting::BreadthFirst => self.next_by_topology(),
+                        Sorting::ByCommitTimeNewestFirst => self.next_by_commit_date(None),
+                        Sorting::ByCommitTimeNewestFirstCutoffOlderThan { seconds } => {
+                            self.next_by_commit_date(seconds.into())
+                        }
                     }
+                };
+                let Some(changed_paths) = self.changed_paths.as_mut() else {
+                    return info;
+                };
+                match info? {
+                    Ok(info) => match changed_paths.changed_by(&info, self.cache.as_ref(), &self.objects) {
+                        Ok(true) => return Some(Ok(info)),
+                        Ok(false) => {}
+                        Err(err) => return Some(Err(err)),
+                    },
+                    Err(err) => return Some(Err(err)),
                 }
             }
         }
@@ -287,6 +338,7 @@ mod init {
 
             let (commit_time, oid) = state.queue.pop()?;
             let mut parents: ParentIds = Default::default();
+            let is_shallow = self.shallow_commits.binary_search(&oid).is_ok();
             match super::super::find(self.cache.as_ref(), &self.objects, &oid, &mut state.buf) {
                 Ok(Either::CachedCommit(commit)) => {
                     if !collect_parents(&mut state.parent_ids, self.cache.as_ref(), commit.iter_parents()) {
@@ -294,6 +346,9 @@ mod init {
                         self.cache = None;
                         return self.next_by_commit_date(cutoff_older_than);
                     }
+                    if is_shallow {
+                        state.parent_ids.clear();
+                    }
                     for (id, parent_commit_time) in state.parent_ids.drain(..) {
                         parents.push(id);
                         let was_inserted = state.seen.insert(id);
@@ -311,6 +366,7 @@ mod init {
                     for token in commit_iter {
                         match token {
                             Ok(gix_object::commit::ref_iter::Token::Tree { .. }) => continue,
+                            Ok(gix_object::commit::ref_iter::Token::Parent { .. }) if is_shallow => break,
                             Ok(gix_object::commit::ref_iter::Token::Parent { id }) => {
                                 parents.push(id);
                                 let was_inserted = state.seen.insert(id);
@@ -353,6 +409,7 @@ mod init {
             let state = &mut self.state;
             let oid = state.next.pop_front()?;
             let mut parents: ParentIds = Default::default();
+            let is_shallow = self.shallow_commits.binary_search(&oid).is_ok();
             match super::super::find(self.cache.as_ref(), &self.objects, &oid, &mut state.buf) {
                 Ok(Either::CachedCommit(commit)) => {
                     if !collect_parents(&mut state.parent_ids, self.cache.as_ref(), commit.iter_parents()) {
@@ -360,6 +417,9 @@ mod init {
                         self.cache = None;
                         return self.next_by_topology();
                     }
+                    if is_shallow {
+                        state.parent_ids.clear();
+                    }
 
                     for (id, _commit_time) in state.parent_ids.drain(..) {
                         parents.push(id);
@@ -376,6 +436,7 @@ mod init {
                     for token in commit_iter {
                         match token {
                             Ok(gix_object::commit::ref_iter::Token::Tree { .. }) => continue,
+                            Ok(gix_object::commit::ref_iter::Token::Parent { .. }) if is_shallow => break,
                             Ok(gix_object::commit::ref_iter::Token::Parent { id }) => {
                                 parents.push(id);
                                 let was_inserted = state.seen.insert(id);
@@ -423,3 +484,128 @@ fn collect_parents(
     }
     true
 }
+
+mod changed_paths {
+    use gix_commitgraph::bloom;
+    use gix_hash::ObjectId;
+    use gix_object::{
+        bstr::{BStr, BString, ByteSlice},
+        tree::EntryMode,
+        FindExt,
+    };
+
+    use super::{super::Info, ChangedPaths, Error};
+
+    impl ChangedPaths {
+        pub(in crate::commit) fn new(paths: impl Iterator<Item = BString>) -> Self {
+            ChangedPaths {
+                paths: paths
+                    .map(|path| {
+                        let path = path.as_bytes();
+                        let start = path.iter().position(|b| *b != b'/').unwrap_or(path.len());
+                        let end = path.iter().rposition(|b| *b != b'/').map_or(start, |pos| pos + 1);
+                        path[start..end].into()
+                    })
+                    .collect(),
+                keys: Vec::new(),
+                buf: Vec::new(),
+            }
+        }
+
+        /// Return `true` if the commit of `info` changed any of our paths compared to its first parent.
+        pub(in crate::commit) fn changed_by(
+            &mut self,
+            info: &Info,
+            cache: Option<&gix_commitgraph::Graph>,
+            objects: &impl gix_object::Find,
+        ) -> Result<bool, Error> {
+            if self.paths.is_empty() {
+                return Ok(true);
+            }
+            let cached = cache.and_then(|cache| cache.commit_by_id(info.id));
+            if let Some(filter) = cached.as_ref().and_then(gix_commitgraph::file::Commit::bloom_filter) {
+                if !self.maybe_changed(&filter) {
+                    return Ok(false);
+                }
+            }
+            let tree = match cached {
+                Some(commit) => commit.root_tree_id().to_owned(),
+                None => objects.find_commit_iter(&info.id, &mut self.buf)?.tree_id()?,
+            };
+            let parent_tree = match info.parent_ids.first() {
+                Some(parent) => Some(match cache.and_then(|cache| cache.commit_by_id(parent)) {
+                    Some(commit) => commit.root_tree_id().to_owned(),
+                    None => objects.find_commit_iter(parent, &mut self.buf)?.tree_id()?,
+                }),
+                None => None,
+            };
+            for path in &self.paths {
+                if path_changed(objects, path.as_ref(), tree, parent_tree, &mut self.buf)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+
+        /// Return `false` if `filter` shows that none of our paths were changed.
+        fn maybe_changed(&mut self, filter: &bloom::Filter<'_>) -> bool {
+            // The empty path refers to the root tree, which isn't part of any filter.
+            if self.paths.iter().any(|path| path.is_empty()) {
+                return true;
+            }
+            let settings = filter.settings();
+            let keys = match self.keys.iter().position(|(s, _)| s == settings) {
+                Some(idx) => &self.keys[idx].1,
+                None => {
+                    let keys = self
+                        .paths
+                        .iter()
+                        .map(|path| bloom::Key::with_leading_directories(path.as_ref(), settings))
+                        .collect();
+                    self.keys.push((*settings, keys));
+                    &self.keys.last().expect("just pushed").1
+                }
+            };
+            keys.iter().any(|keys| filter.contains_all(keys))
+        }
+    }
+
+    /// Return `true` if the entry at `path` differs between the trees `lhs` and `rhs`, with `None` meaning that there is no tree.
+    fn path_changed(
+        objects: &impl gix_object::Find,
+        path: &BStr,
+        lhs: ObjectId,
+        rhs: Option<ObjectId>,
+        buf: &mut Vec<u8>,
+    ) -> Result<bool, Error> {
+        let mut lhs = Some((EntryMode::from(gix_object::tree::EntryKind::Tree), lhs));
+        let mut rhs = rhs.map(|id| (EntryMode::from(gix_object::tree::EntryKind::Tree), id));
+        for component in path.split(|b| *b == b'/').filter(|c| !c.is_empty()) {
+            if lhs == rhs {
+                return Ok(false);
+            }
+            lhs = entry(objects, lhs, component.as_bstr(), buf)?;
+            rhs = entry(objects, rhs, component.as_bstr(), buf)?;
+        }
+        Ok(lhs != rhs)
+    }
+
+    /// Return the entry called `name` in the tree `parent`, if it is a tree.
+    fn entry(
+        objects: &impl gix_object::Find,
+        parent: Option<(EntryMode, ObjectId)>,
+        name: &BStr,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<(EntryMode, ObjectId)>, Error> {
+        let Some((_, id)) = parent.filter(|(mode, _)| mode.is_tree()) else {
+            return Ok(None);
+        };
+        for entry in objects.find_tree_iter(&id, buf)? {
+            let entry = entry?;
+            if entry.filename == name {
+                return Ok(Some((entry.mode, entry.oid.to_owned())));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -14,6 +14,7 @@ pub struct Builder<Find, Predicate> {
     parents: Parents,
     tips: Vec<ObjectId>,
     ends: Vec<ObjectId>,
+    shallow_commits: Vec<ObjectId>,
 }
 
 impl<Find> Builder<Find, fn(&oid) -> bool>
@@ -40,6 +41,7 @@ where
             parents: Default::default(),
             tips,
             ends,
+            shallow_commits: Vec::new(),
             predicate: |_| true,
         }
     }
@@ -59,6 +61,7 @@ where
             parents: self.parents,
             tips: self.tips,
             ends: self.ends,
+            shallow_commits: self.shallow_commits,
             predicate,
         }
     }
@@ -87,6 +90,14 @@ where
         self
     }
 
+    /// Treat `commits` as the *shallow boundary*, that is as commits without parents, as their parents aren't
+    /// available in a shallow repository. This is what `git` does for all commits listed in `.git/shallow`.
+    pub fn shallow_commits(mut self, commits: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+        self.shallow_commits = commits.into_iter().map(Into::into).collect();
+        self.shallow_commits.sort();
+        self
+    }
+
     /// Build a new [`Topo`] instance.
     ///
     /// Note that merely building an instance is currently expensive.
@@ -102,6 +113,7 @@ where
             topo_queue: super::iter::Queue::new(self.sorting),
             parents: self.parents,
             min_gen: gix_commitgraph::GENERATION_NUMBER_INFINITY,
+            shallow_commits: self.shallow_commits,
             buf: vec![],
         };
 
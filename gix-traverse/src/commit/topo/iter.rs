@@ -184,6 +184,7 @@ where
             &self.find,
             id,
             matches!(self.parents, Parents::First),
+            &self.shallow_commits,
             &mut self.buf,
         )
     }
@@ -193,7 +194,14 @@ where
         &mut self,
         id: &oid,
     ) -> Result<SmallVec<[(ObjectId, GenAndCommitTime); 1]>, Error> {
-        collect_parents(&mut self.commit_graph, &self.find, id, false, &mut self.buf)
+        collect_parents(
+            &mut self.commit_graph,
+            &self.find,
+            id,
+            false,
+            &self.shallow_commits,
+            &mut self.buf,
+        )
     }
 
     fn pop_commit(&mut self) -> Option<Result<Info, Error>> {
@@ -240,12 +248,17 @@ fn collect_parents<Find>(
     f: Find,
     id: &oid,
     first_only: bool,
+    shallow_commits: &[ObjectId],
     buf: &mut Vec<u8>,
 ) -> Result<SmallVec<[(ObjectId, GenAndCommitTime); 1]>, Error>
 where
     Find: gix_object::Find,
 {
     let mut parents = SmallVec::<[(ObjectId, GenAndCommitTime); 1]>::new();
+    if shallow_commits.binary_search_by(|c| c.as_ref().cmp(id)).is_ok() {
+        // The parents of commits at the shallow boundary are not available.
+        return Ok(parents);
+    }
     match find(cache.as_ref(), &f, id, buf)? {
         Either::CommitRefIter(c) => {
             for token in c {
@@ -274,7 +287,7 @@ where
                 let Ok(pos) = pos else {
                     // drop corrupt cache and use ODB from now on.
                     *cache = None;
-                    return collect_parents(cache, f, id, first_only, buf);
+                    return collect_parents(cache, f, id, first_only, shallow_commits, buf);
                 };
                 let parent_commit = cache
                     .as_ref()
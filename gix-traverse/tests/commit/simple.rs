@@ -420,3 +420,120 @@ mod adjusted_dates {
         .check()
     }
 }
+
+mod changed_paths {
+    use gix_traverse::commit::{simple::Sorting, Parents, Simple};
+
+    use crate::hex_to_id;
+
+    const C4: &str = "74ff8611b286c943c3aa69a6e43e6738050cf46c";
+    const MERGE: &str = "546b3e55961614f9dda4c0e15e252c59fc8e294c";
+    const B2: &str = "063d6fbbaa62c8a87b4bc02acdadcdf28e2857c6";
+    const B1: &str = "bf5ecbdeb9e9a4912c23abef21bb9ec0a7d963a7";
+    const C2: &str = "c92c9348445d31b7169facc06da498503fb3dcfa";
+    const C1: &str = "317ce52334eaae3a319c2efb610c96ae42374e13";
+
+    /// Assert that traversing from `c4` limited to `paths` yields `expected`, with and without a commit-graph
+    /// that has changed-path Bloom filters.
+    fn assert_changed_paths(paths: &[&str], parents: Parents, expected: &[&str]) -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only_standalone("make_repo_for_changed_paths.sh")?;
+        let store = gix_odb::at(dir.join(".git").join("objects"))?;
+        let graph = gix_commitgraph::at(store.store_ref().path().join("info"))?;
+        assert!(
+            graph.bloom_filter_settings().is_some(),
+            "the commit-graph was written with --changed-paths"
+        );
+        let expected: Vec<_> = expected.iter().copied().map(hex_to_id).collect();
+
+        for graph in [None, Some(graph)] {
+            let actual = Simple::new(Some(hex_to_id(C4)), &store)
+                .sorting(Sorting::ByCommitTimeNewestFirst)?
+                .parents(parents)
+                .commit_graph(graph)
+                .changed_paths(paths.iter().copied())
+                .map(|res| res.map(|info| info.id))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(actual, expected, "{paths:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn first_parent_only_is_like_git_log_first_parent() -> crate::Result {
+        // The expectations are the same as the ones of `git log --first-parent -- <paths>`.
+        assert_changed_paths(&["dir"], Parents::First, &[C4, MERGE, C1])?;
+        assert_changed_paths(&["dir/"], Parents::First, &[C4, MERGE, C1])?;
+        assert_changed_paths(&["a"], Parents::First, &[C2, C1])?;
+        assert_changed_paths(&["dir/sub/c"], Parents::First, &[MERGE])?;
+        assert_changed_paths(&["a", "dir/sub/c"], Parents::First, &[MERGE, C2, C1])?;
+        assert_changed_paths(&["does-not-exist", "dir/b/does-not-exist"], Parents::First, &[])?;
+        Ok(())
+    }
+
+    #[test]
+    fn all_parents_compare_merges_to_their_first_parent() -> crate::Result {
+        assert_changed_paths(&["dir"], Parents::All, &[C4, MERGE, B2, B1, C1])?;
+        assert_changed_paths(&["dir/sub/c"], Parents::All, &[MERGE, B2])?;
+        assert_changed_paths(&[""], Parents::All, &[C4, MERGE, B2, B1, C2, C1])?;
+        Ok(())
+    }
+}
+
+mod shallow {
+    use gix_traverse::commit::{simple::Sorting, Parents, Simple};
+
+    use crate::hex_to_id;
+
+    const C6: &str = "06c1ceda82236c386dfbd3423f8112f4295b3f40";
+    const MERGE: &str = "f0d49f5d5c9dec7f71a3628caa8875bd4fdd3934";
+    const B1C2: &str = "ec2533c2a9cd5d3271d1381bd7131353c810fd7e";
+    const B1C1: &str = "7494c88e51bc23a95c308728a0bec5343959ed7c";
+    const C5: &str = "12173e97a4f83b253a13dad6f88f99a743748817";
+    const C4: &str = "924376e3ea884fc5eded11955de2d6af908aeeec";
+
+    fn assert_traversal(sorting: Sorting, parents: Parents, expected: &[&str]) -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only_standalone("make_repo_for_shallow.sh")?;
+        let store = gix_odb::at(dir.join("shallow").join(".git").join("objects"))?;
+        let expected: Vec<_> = expected.iter().copied().map(hex_to_id).collect();
+
+        let actual = Simple::new(Some(hex_to_id(C6)), &store)
+            .sorting(sorting)?
+            .parents(parents)
+            .shallow_commits([C4, B1C1].map(hex_to_id))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            actual.iter().map(|info| info.id).collect::<Vec<_>>(),
+            expected,
+            "{sorting:?}"
+        );
+        assert!(
+            actual
+                .iter()
+                .filter(|info| info.id == hex_to_id(C4) || info.id == hex_to_id(B1C1))
+                .all(|info| info.parent_ids.is_empty()),
+            "commits at the shallow boundary appear to have no parents"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn traversal_stops_at_the_shallow_boundary() -> crate::Result {
+        assert_traversal(Sorting::BreadthFirst, Parents::All, &[C6, MERGE, C5, B1C2, C4, B1C1])?;
+        assert_traversal(
+            Sorting::ByCommitTimeNewestFirst,
+            Parents::All,
+            &[C6, MERGE, B1C2, C5, B1C1, C4],
+        )?;
+        assert_traversal(Sorting::BreadthFirst, Parents::First, &[C6, MERGE, C5, C4])?;
+        Ok(())
+    }
+
+    #[test]
+    fn traversal_fails_without_knowing_the_shallow_boundary() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only_standalone("make_repo_for_shallow.sh")?;
+        let store = gix_odb::at(dir.join("shallow").join(".git").join("objects"))?;
+        let res = Simple::new(Some(hex_to_id(C6)), &store).collect::<Result<Vec<_>, _>>();
+        assert!(res.is_err(), "the parents of the shallow commits are missing");
+        Ok(())
+    }
+}
@@ -371,3 +371,50 @@ mod date_order {
         Ok(())
     }
 }
+
+mod shallow {
+    use gix_object::bstr::ByteSlice;
+    use gix_traverse::commit::topo;
+
+    use crate::hex_to_id;
+
+    #[test]
+    fn traversal_stops_at_the_shallow_boundary() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only_standalone("make_repo_for_shallow.sh")?;
+        let worktree_dir = dir.join("shallow");
+        let store = gix_odb::at(worktree_dir.join(".git").join("objects"))?;
+        let shallow_commits: Vec<_> = std::fs::read(worktree_dir.join(".git").join("shallow"))?
+            .lines()
+            .map(|hex| hex_to_id(hex.to_str().expect("ascii")))
+            .collect();
+        assert_eq!(shallow_commits.len(), 2, "one commit per branch is at the boundary");
+
+        for (sorting, baseline) in [
+            (topo::Sorting::TopoOrder, "all-commits"),
+            (topo::Sorting::DateOrder, "date-order"),
+        ] {
+            let expected: Vec<_> = std::fs::read(worktree_dir.join(format!("{baseline}.baseline")))?
+                .lines()
+                .map(|hex| hex_to_id(hex.to_str().expect("ascii")))
+                .collect();
+            let tip = expected[0];
+            let actual = topo::Builder::from_iters(&store, [tip], None::<Vec<gix_hash::ObjectId>>)
+                .sorting(sorting)
+                .shallow_commits(shallow_commits.iter().copied())
+                .build()?
+                .map(|res| res.map(|info| info.id))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(actual, expected, "{sorting:?} matches `git rev-list`");
+
+            assert!(
+                topo::Builder::from_iters(&store, [tip], None::<Vec<gix_hash::ObjectId>>)
+                    .sorting(sorting)
+                    .build()
+                    .and_then(|walk| walk.collect::<Result<Vec<_>, _>>())
+                    .is_err(),
+                "without knowing the boundary, the missing parents are an error"
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit() {
+  local message=${1:?first argument is the commit message}
+  tick
+  git add -A
+  git commit -q --allow-empty -m "$message"
+}
+
+git init -q
+git config merge.ff false
+
+git checkout -q -b main
+mkdir dir
+echo a > a && echo b > dir/b
+commit c1
+echo a2 > a
+commit c2
+
+git checkout -q -b branch
+echo b2 > dir/b
+commit b1
+mkdir dir/sub && echo c > dir/sub/c
+commit b2
+
+git checkout -q main
+commit c3
+tick
+git merge -q branch -m merge
+echo b3 > dir/b
+commit c4
+
+git commit-graph write --no-progress --reachable --changed-paths
+git repack -adq
@@ -0,0 +1,51 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit() {
+  local message=${1:?first argument is the commit message}
+  tick
+  git commit --allow-empty -q -m "$message"
+}
+
+mkdir base
+(cd base
+  git init -q
+  git config merge.ff false
+
+  git checkout -q -b main
+  for i in {0..3}; do
+      commit c$i
+  done
+
+  git branch branch1
+  commit c4
+  git checkout -q branch1
+  commit b1c1
+  git checkout -q main
+  commit c5
+  git checkout -q branch1
+  commit b1c2
+
+  git checkout -q main
+  tick
+  git merge -q branch1 -m merge
+  commit c6
+)
+
+git clone -q --depth 4 --no-local file://$PWD/base shallow
+(cd shallow
+  git rev-list --topo-order HEAD > all-commits.baseline
+  git rev-list --date-order HEAD > date-order.baseline
+)
@@ -9,7 +9,8 @@ use crate::checkout::chunk;
 /// respective path in `dir`.
 /// Use `files` to count each fully checked out file, and count the amount written `bytes`. If `should_interrupt` is `true`, the
 /// operation will abort.
-/// `options` provide a lot of context on how to perform the operation.
+/// `options` provide a lot of context on how to perform the operation, and may contain sparse-checkout patterns
+/// to set the [`SKIP_WORKTREE`](gix_index::entry::Flags::SKIP_WORKTREE) flag of entries that shouldn't be checked out.
 ///
 /// ### Handling the return value
 ///
@@ -28,6 +29,9 @@ pub fn checkout<Find>(
 where
     Find: gix_object::Find + Send + Clone,
 {
+    if let Some(patterns) = &options.sparse_checkout {
+        patterns.apply_to_index(index);
+    }
     let paths = index.take_path_backing();
     let res = checkout_inner(index, &paths, dir, objects, files, bytes, should_interrupt, options);
     index.return_path_backing(paths);
@@ -68,6 +68,12 @@ pub struct Options {
     pub filters: gix_filter::Pipeline,
     /// Control how long-running processes may use the 'delay' capability.
     pub filter_process_delay: gix_filter::driver::apply::Delay,
+    /// If set, the patterns of a sparse checkout, typically from `$GIT_DIR/info/sparse-checkout` if `core.sparseCheckout`
+    /// is enabled.
+    ///
+    /// They are applied to the index before the checkout, so that only included entries are written and all others
+    /// receive the [`SKIP_WORKTREE`](gix_index::entry::Flags::SKIP_WORKTREE) flag.
+    pub sparse_checkout: Option<gix_worktree::sparse::Patterns>,
 }
 
 /// The error returned by the [checkout()][crate::checkout()] function.
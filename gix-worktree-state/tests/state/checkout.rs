@@ -267,6 +267,35 @@ fn symlinks_become_files_if_disabled() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn sparse_checkout_patterns_skip_entries() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.sparse_checkout = Some(gix_worktree::sparse::Patterns::Cone(Default::default()));
+    let (_source_tree, destination, index, outcome) =
+        checkout_index_in_tmp_dir(opts, "make_mixed_without_submodules", None)?;
+
+    let worktree_files = dir_structure(&destination);
+    assert_eq!(
+        stripped_prefix(&destination, &worktree_files),
+        paths(["empty", "executable"]),
+        "only files in the root are included in an empty cone"
+    );
+    assert!(destination.path().join(".gitattributes").is_file());
+    assert_eq!(
+        outcome.files_updated,
+        index.entries().len(),
+        "skipped files are counted as well"
+    );
+    for entry in index.entries() {
+        assert_eq!(
+            entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE),
+            entry.path(&index).starts_with(b"dir/"),
+            "entries that aren't checked out are marked as such"
+        );
+    }
+    Ok(())
+}
+
 #[test]
 fn symlinks_to_directories_are_usable() -> crate::Result {
     let opts = opts_from_probe();
@@ -0,0 +1,342 @@
+//! Sparse-checkout patterns as stored in `$GIT_DIR/info/sparse-checkout`, which decide which entries of the index
+//! are present in the worktree.
+//!
+//! Entries that don't match the patterns receive the [`SKIP_WORKTREE`](gix_index::entry::Flags::SKIP_WORKTREE) flag
+//! so that checkouts skip them and `status` doesn't consider them deleted.
+use std::collections::BTreeSet;
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_index::entry::Flags;
+
+/// The patterns of a sparse checkout.
+#[derive(Debug, Clone)]
+pub enum Patterns {
+    /// _Cone mode_, which includes all files in the root of the worktree, all files in the parent directories of
+    /// included directories, and everything in included directories.
+    ///
+    /// This is the mode `git` uses by default, as configured with `core.sparseCheckoutCone`.
+    Cone(Cone),
+    /// _Non-cone mode_, which includes all paths matched by gitignore-style patterns, with negative patterns
+    /// excluding paths again.
+    NonCone(NonCone),
+}
+
+/// The directories of a [cone-mode](Patterns::Cone) sparse checkout.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cone {
+    /// Directories that are included along with everything they contain.
+    recursive: BTreeSet<BString>,
+    /// Directories whose files are included, but not their subdirectories.
+    parents: BTreeSet<BString>,
+}
+
+/// The patterns of a [non-cone mode](Patterns::NonCone) sparse checkout.
+#[derive(Debug, Clone)]
+pub struct NonCone {
+    patterns: Vec<BString>,
+    list: gix_glob::search::pattern::List<gix_ignore::search::Ignore>,
+}
+
+/// The result of [`Patterns::apply_to_index()`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The indices of all entries which received the [`SKIP_WORKTREE`](Flags::SKIP_WORKTREE) flag.
+    pub newly_skipped: Vec<usize>,
+    /// The indices of all entries which lost the [`SKIP_WORKTREE`](Flags::SKIP_WORKTREE) flag.
+    pub newly_included: Vec<usize>,
+}
+
+/// Lifecycle
+impl Patterns {
+    /// Parse the content of a `sparse-checkout` file in `bytes`, in cone mode if `cone` is `true`.
+    ///
+    /// Like `git`, patterns that aren't valid in cone mode cause a fallback to non-cone mode.
+    pub fn from_bytes(bytes: &[u8], cone: bool) -> Self {
+        match cone.then(|| Cone::from_bytes(bytes)).flatten() {
+            Some(cone) => Patterns::Cone(cone),
+            None => Patterns::NonCone(NonCone::from_patterns(
+                bytes
+                    .lines()
+                    .map(ByteSlice::trim_end)
+                    .filter(|line| !line.is_empty() && !line.starts_with(b"#"))
+                    .map(ByteSlice::as_bstr),
+            )),
+        }
+    }
+}
+
+/// Access
+impl Patterns {
+    /// Return `true` if these are [cone-mode](Patterns::Cone) patterns.
+    pub fn is_cone(&self) -> bool {
+        matches!(self, Patterns::Cone(_))
+    }
+
+    /// Return the included directories in cone mode, or the patterns in non-cone mode, which is what
+    /// `git sparse-checkout list` shows.
+    pub fn list(&self) -> Vec<BString> {
+        match self {
+            Patterns::Cone(cone) => cone.directories().map(ToOwned::to_owned).collect(),
+            Patterns::NonCone(non_cone) => non_cone.patterns().to_vec(),
+        }
+    }
+
+    /// Return `true` if the file at the worktree-relative `path` is included in the sparse checkout.
+    pub fn includes_file(&self, path: &BStr) -> bool {
+        match self {
+            Patterns::Cone(cone) => cone.includes_file(path),
+            Patterns::NonCone(non_cone) => non_cone.includes_file(path),
+        }
+    }
+
+    /// Return `true` if nothing in the worktree-relative directory `dir` (without trailing slash) can be included,
+    /// which is when it could be represented by a sparse directory.
+    ///
+    /// This is only ever the case in cone mode.
+    pub fn excludes_directory(&self, dir: &BStr) -> bool {
+        match self {
+            Patterns::Cone(cone) => !cone.parents.contains(dir) && !cone.has_recursive_ancestor_or_self(dir),
+            Patterns::NonCone(_) => false,
+        }
+    }
+
+    /// Set or clear the [`SKIP_WORKTREE`](Flags::SKIP_WORKTREE) flag of all entries in `index` depending on whether
+    /// they are included by these patterns, and return which entries changed.
+    ///
+    /// Conflicting entries are never skipped, and [sparse directories](gix_index::entry::Mode::DIR) are left
+    /// unchanged, so the index should be [expanded](gix_index::State::expand_sparse_directories()) first if the
+    /// patterns changed.
+    pub fn apply_to_index(&self, index: &mut gix_index::State) -> Outcome {
+        let mut out = Outcome::default();
+        for (idx, (entry, path)) in index.entries_mut_with_paths().enumerate() {
+            if entry.mode.is_sparse() {
+                continue;
+            }
+            let is_skipped = entry.flags.contains(Flags::SKIP_WORKTREE);
+            let should_skip = entry.stage_raw() == 0 && !self.includes_file(path);
+            match (is_skipped, should_skip) {
+                (false, true) => {
+                    entry.flags.insert(Flags::SKIP_WORKTREE | Flags::EXTENDED);
+                    out.newly_skipped.push(idx);
+                }
+                (true, false) => {
+                    entry.flags.remove(Flags::SKIP_WORKTREE);
+                    if !entry.flags.contains(Flags::INTENT_TO_ADD) {
+                        entry.flags.remove(Flags::EXTENDED);
+                    }
+                    out.newly_included.push(idx);
+                }
+                _ => {}
+            }
+        }
+        out
+    }
+
+    /// Write these patterns to `out` in the format of the `sparse-checkout` file.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        match self {
+            Patterns::Cone(cone) => cone.write_to(out),
+            Patterns::NonCone(non_cone) => non_cone.write_to(out),
+        }
+    }
+}
+
+impl Cone {
+    /// Create a cone which includes all `directories` recursively, along with the files of their parent directories.
+    pub fn from_directories(directories: impl IntoIterator<Item = impl AsRef<BStr>>) -> Self {
+        let mut cone = Cone::default();
+        for dir in directories {
+            cone.add_directory(dir.as_ref());
+        }
+        cone
+    }
+
+    /// Include `dir` recursively, with leading and trailing slashes being ignored.
+    pub fn add_directory(&mut self, dir: &BStr) {
+        let dir = dir.trim_start_with(|c| c == '/').trim_end_with(|c| c == '/');
+        if dir.is_empty() {
+            return;
+        }
+        self.insert_recursive(dir.as_bstr().to_owned());
+    }
+
+    /// Return all directories that are included recursively, without those that are contained in other
+    /// recursively included directories, in sorted order.
+    pub fn directories(&self) -> impl Iterator<Item = &BStr> + '_ {
+        self.recursive
+            .iter()
+            .map(|dir| dir.as_bstr())
+            .filter(move |dir| !self.has_recursive_ancestor(dir))
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut cone = Cone::default();
+        for line in bytes.lines().map(ByteSlice::trim_end) {
+            if line.is_empty() || line.starts_with(b"#") || line == b"/*" || line == b"!/*/" {
+                continue;
+            }
+            if let Some(dir) = line.strip_prefix(b"!/").and_then(|line| line.strip_suffix(b"/*/")) {
+                // Only directories that were included before can be turned into parents.
+                let dir = unescape(dir)?;
+                if !cone.recursive.remove(&dir) {
+                    return None;
+                }
+                cone.insert_parent(dir);
+            } else if let Some(dir) = line.strip_prefix(b"/").and_then(|line| line.strip_suffix(b"/")) {
+                let dir = unescape(dir)?;
+                if dir.is_empty() {
+                    return None;
+                }
+                cone.insert_recursive(dir);
+            } else {
+                return None;
+            }
+        }
+        Some(cone)
+    }
+
+    fn insert_recursive(&mut self, dir: BString) {
+        if let Some(pos) = dir.rfind_byte(b'/') {
+            self.insert_parent(dir[..pos].into());
+        }
+        self.recursive.insert(dir);
+    }
+
+    fn insert_parent(&mut self, mut dir: BString) {
+        loop {
+            let parent = dir.rfind_byte(b'/').map(|pos| dir[..pos].to_owned());
+            if !self.parents.insert(dir) {
+                break;
+            }
+            match parent {
+                Some(parent) => dir = parent.into(),
+                None => break,
+            }
+        }
+    }
+
+    fn includes_file(&self, path: &BStr) -> bool {
+        match path.rfind_byte(b'/') {
+            Some(pos) => {
+                let parent = path[..pos].as_bstr();
+                self.parents.contains(parent) || self.has_recursive_ancestor_or_self(parent)
+            }
+            None => true,
+        }
+    }
+
+    fn has_recursive_ancestor_or_self(&self, dir: &BStr) -> bool {
+        self.recursive.contains(dir) || self.has_recursive_ancestor(dir)
+    }
+
+    fn has_recursive_ancestor(&self, dir: &BStr) -> bool {
+        dir.iter()
+            .enumerate()
+            .filter(|(_, b)| **b == b'/')
+            .any(|(pos, _)| self.recursive.contains(dir[..pos].as_bstr()))
+    }
+
+    fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        out.write_all(b"/*\n!/*/\n")?;
+        for dir in self
+            .parents
+            .iter()
+            .filter(|dir| !self.has_recursive_ancestor_or_self(dir.as_bstr()))
+        {
+            let dir = escape(dir.as_bstr());
+            writeln!(out, "/{dir}/\n!/{dir}/*/")?;
+        }
+        for dir in self.directories() {
+            writeln!(out, "/{}/", escape(dir))?;
+        }
+        Ok(())
+    }
+}
+
+impl NonCone {
+    /// Create an instance from gitignore-style `patterns`, where later patterns take precedence.
+    pub fn from_patterns(patterns: impl IntoIterator<Item = impl AsRef<BStr>>) -> Self {
+        let patterns: Vec<BString> = patterns.into_iter().map(|p| p.as_ref().to_owned()).collect();
+        let list = Self::parse(&patterns);
+        NonCone { patterns, list }
+    }
+
+    /// Add `pattern` so that it takes precedence over all previous patterns.
+    pub fn add_pattern(&mut self, pattern: &BStr) {
+        self.patterns.push(pattern.to_owned());
+        self.list = Self::parse(&self.patterns);
+    }
+
+    /// Return all patterns in the order in which they were added.
+    pub fn patterns(&self) -> &[BString] {
+        &self.patterns
+    }
+
+    fn parse(patterns: &[BString]) -> gix_glob::search::pattern::List<gix_ignore::search::Ignore> {
+        let mut buf = BString::default();
+        for pattern in patterns {
+            buf.push_str(pattern);
+            buf.push(b'\n');
+        }
+        gix_glob::search::pattern::List::from_bytes(&buf, "sparse-checkout".into(), None)
+    }
+
+    fn includes_file(&self, path: &BStr) -> bool {
+        // Like `git`, try the path itself, then each of its parent directories, until one of them matches.
+        let mut end = path.len();
+        let mut is_dir = false;
+        while end > 0 {
+            let path = path[..end].as_bstr();
+            let basename_pos = path.rfind_byte(b'/').map(|pos| pos + 1);
+            if let Some(m) = gix_ignore::search::pattern_matching_relative_path(
+                &self.list,
+                path,
+                basename_pos,
+                Some(is_dir),
+                gix_glob::pattern::Case::Sensitive,
+            ) {
+                return !m.pattern.is_negative();
+            }
+            end = basename_pos.map_or(0, |pos| pos - 1);
+            is_dir = true;
+        }
+        false
+    }
+
+    fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        for pattern in &self.patterns {
+            out.write_all(pattern)?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+fn is_glob_special(b: u8) -> bool {
+    matches!(b, b'*' | b'?' | b'[' | b'\\')
+}
+
+fn escape(dir: &BStr) -> BString {
+    let mut out = BString::default();
+    for &b in dir.iter() {
+        if is_glob_special(b) {
+            out.push(b'\\');
+        }
+        out.push(b);
+    }
+    out
+}
+
+/// Remove escapes from `dir`, or return `None` if it contains unescaped glob characters, which aren't valid in cone mode.
+fn unescape(dir: &[u8]) -> Option<BString> {
+    let mut out = BString::default();
+    let mut bytes = dir.iter();
+    while let Some(&b) = bytes.next() {
+        match b {
+            b'\\' => out.push(*bytes.next()?),
+            b if is_glob_special(b) => return None,
+            b => out.push(b),
+        }
+    }
+    Some(out)
+}
@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function setup() {
+  git init -q "$1"
+  (cd "$1"
+    mkdir -p a/b/c d/e 'f[x]' g
+    touch root a/file a/b/file a/b/c/file d/file d/e/file 'f[x]/file' g/file g/file.txt g/other.md
+    git add .
+    git commit -q -m "init"
+  )
+}
+
+setup cone
+(cd cone
+  git sparse-checkout set --cone --skip-checks a/b 'f[x]'
+)
+
+setup non-cone
+(cd non-cone
+  git sparse-checkout set --no-cone '/a/' '*.txt' '!/a/b/c/' 'd/e'
+)
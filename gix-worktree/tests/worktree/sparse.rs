@@ -0,0 +1,115 @@
+use bstr::ByteSlice;
+use gix_index::entry::Flags;
+use gix_worktree::sparse::{Cone, Patterns};
+
+fn fixture(name: &str) -> crate::Result<(gix_index::File, Vec<u8>)> {
+    let root = gix_testtools::scripted_fixture_read_only_standalone("make_sparse_checkout.sh")?.join(name);
+    let index = gix_index::File::at(root.join(".git/index"), gix_hash::Kind::Sha1, false, Default::default())?;
+    let patterns = std::fs::read(root.join(".git/info/sparse-checkout"))?;
+    Ok((index, patterns))
+}
+
+fn skipped_paths(index: &gix_index::State) -> Vec<String> {
+    index
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(Flags::SKIP_WORKTREE))
+        .map(|e| e.path(index).to_str_lossy().into_owned())
+        .collect()
+}
+
+fn assert_same_skipped_entries_as_git(index: &mut gix_index::File, patterns: &Patterns) {
+    let expected = skipped_paths(index);
+    for entry in index.entries_mut() {
+        entry.flags.remove(Flags::SKIP_WORKTREE | Flags::EXTENDED);
+    }
+    let out = patterns.apply_to_index(index);
+    assert_eq!(out.newly_skipped.len(), expected.len());
+    assert!(out.newly_included.is_empty());
+    assert_eq!(skipped_paths(index), expected, "we skip the same entries as git");
+
+    let out = patterns.apply_to_index(index);
+    assert!(
+        out.newly_skipped.is_empty() && out.newly_included.is_empty(),
+        "nothing changes when applying patterns again"
+    );
+}
+
+#[test]
+fn cone_mode_baseline() -> crate::Result {
+    let (mut index, expected_file) = fixture("cone")?;
+    let patterns = Patterns::from_bytes(&expected_file, true);
+    assert!(patterns.is_cone());
+    assert_eq!(patterns.list(), ["a/b", "f[x]"]);
+    assert_same_skipped_entries_as_git(&mut index, &patterns);
+
+    let mut buf = Vec::new();
+    Patterns::Cone(Cone::from_directories(["/f[x]/", "a/b", "a/b/c"])).write_to(&mut buf)?;
+    assert_eq!(
+        buf.as_bstr(),
+        expected_file.as_bstr(),
+        "the file is written like git does, without nested directories"
+    );
+
+    for (dir, excluded) in [
+        ("a", false),
+        ("a/b", false),
+        ("a/b/c", false),
+        ("d", true),
+        ("d/e", true),
+    ] {
+        assert_eq!(patterns.excludes_directory(dir.into()), excluded, "{dir}");
+    }
+    Ok(())
+}
+
+#[test]
+fn non_cone_mode_baseline() -> crate::Result {
+    let (mut index, expected_file) = fixture("non-cone")?;
+    let patterns = Patterns::from_bytes(&expected_file, true);
+    assert!(
+        !patterns.is_cone(),
+        "invalid cone patterns cause a fallback to non-cone mode"
+    );
+    assert_eq!(patterns.list(), ["/a/", "*.txt", "!/a/b/c/", "d/e"]);
+    assert_same_skipped_entries_as_git(&mut index, &patterns);
+    assert!(
+        !patterns.excludes_directory("d".into()),
+        "directories are never excluded entirely"
+    );
+
+    let mut buf = Vec::new();
+    patterns.write_to(&mut buf)?;
+    assert_eq!(buf.as_bstr(), expected_file.as_bstr());
+    Ok(())
+}
+
+#[test]
+fn applying_changed_patterns() -> crate::Result {
+    let (mut index, _) = fixture("cone")?;
+    let mut cone = Cone::from_directories(["a/b"]);
+    Patterns::Cone(cone.clone()).apply_to_index(&mut index);
+    assert_eq!(
+        skipped_paths(&index),
+        ["d/e/file", "d/file", "f[x]/file", "g/file", "g/file.txt", "g/other.md"]
+    );
+
+    cone.add_directory("d/e".into());
+    let out = Patterns::Cone(cone).apply_to_index(&mut index);
+    assert!(out.newly_skipped.is_empty());
+    let included: Vec<_> = out
+        .newly_included
+        .iter()
+        .map(|idx| index.entry(*idx).path(&index).to_str_lossy().into_owned())
+        .collect();
+    assert_eq!(
+        included,
+        ["d/e/file", "d/file"],
+        "parent directories contribute their files"
+    );
+    assert!(index
+        .entries()
+        .iter()
+        .all(|e| e.flags.contains(Flags::SKIP_WORKTREE) == e.flags.contains(Flags::EXTENDED)));
+    Ok(())
+}
@@ -0,0 +1,348 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::atomic::AtomicBool,
+};
+
+use gix_apply::mail;
+use gix_hash::ObjectId;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use super::{state::State, Error, Options, Outcome};
+use crate::{
+    apply::{function::Entry, Location},
+    bstr::{BString, ByteSlice},
+    Repository,
+};
+
+/// Applying emails
+impl Repository {
+    /// Apply the patches of all emails in `mbox` as commits on top of `HEAD`, similar to `git am`.
+    ///
+    /// The author, subject and message of each commit are taken from its email, and the patch is applied to the worktree
+    /// and the index. If an email can't be applied, the operation stops with an error that names it. Its state is kept in
+    /// `.git/rebase-apply` so that it can be [continued](Self::apply_mailbox_continue()) after resolving the problem,
+    /// [skipped](Self::apply_mailbox_skip()) or [aborted](Self::apply_mailbox_abort()).
+    ///
+    /// `should_interrupt` is checked while writing files to the worktree.
+    pub fn apply_mailbox(
+        &self,
+        mbox: &[u8],
+        options: Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::apply_mailbox()");
+        let dir = self.apply_mailbox_state_dir();
+        if dir.exists() {
+            return Err(Error::InProgress { path: dir });
+        }
+        let mails: Vec<_> = mail::split(mbox).collect();
+        if mails.is_empty() {
+            return Err(Error::EmptyMailbox);
+        }
+        let head = self.head()?.id().map(crate::Id::detach);
+        let state = State::create(dir, &mails, head, &options)?;
+        let orig_head: FullName = "ORIG_HEAD".try_into().expect("valid");
+        let change = match head {
+            Some(head) => Some(Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    log_symbolic_changes: false,
+                    message: "am".into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(head),
+            }),
+            None => self.try_find_reference(&orig_head)?.map(|_| Change::Delete {
+                expected: PreviousValue::Any,
+                log: RefLog::AndReference,
+            }),
+        };
+        if let Some(change) = change {
+            self.edit_reference(RefEdit {
+                change,
+                name: orig_head,
+                deref: false,
+            })?;
+        }
+        self.apply_mailbox_from(state, options, Outcome::default(), should_interrupt)
+    }
+
+    /// Continue applying a mailbox after the email that stopped the operation was applied manually, and its changes were
+    /// added to the index.
+    pub fn apply_mailbox_continue(&self, should_interrupt: &AtomicBool) -> Result<Outcome, Error> {
+        let (mut state, options) = self.apply_mailbox_state()?;
+        let info = self.parse_mail(&state, options)?;
+        let index = self.open_index()?;
+        let tree = self.write_tree_from_index(&index)?.detach();
+        let head_tree = match self.head()?.id() {
+            Some(id) => self.find_commit(id)?.tree()?.id,
+            None => self.empty_tree().id,
+        };
+        if head_tree == tree {
+            return Err(Error::NoChanges { patch: state.next });
+        }
+        let mut outcome = Outcome::default();
+        outcome.commits.push(self.commit_mail(&state, &info, options, tree)?);
+        state.next += 1;
+        state.write_next()?;
+        self.apply_mailbox_from(state, options, outcome, should_interrupt)
+    }
+
+    /// Skip the email that stopped applying a mailbox, restoring the files it touched to their state in `HEAD`,
+    /// and continue with the next one.
+    pub fn apply_mailbox_skip(&self, should_interrupt: &AtomicBool) -> Result<Outcome, Error> {
+        let (mut state, options) = self.apply_mailbox_state()?;
+        let mut paths = self.unmerged_paths()?;
+        paths.extend(self.patched_paths(&state, options)?);
+        let head = self.head()?.id().map(crate::Id::detach);
+        self.restore_paths(head, paths, should_interrupt)?;
+        state.next += 1;
+        state.write_next()?;
+        self.apply_mailbox_from(state, options, Outcome::default(), should_interrupt)
+    }
+
+    /// Stop applying a mailbox and restore `HEAD`, the index and the worktree to the state before it was started.
+    ///
+    /// `HEAD` is only reset if it still points to the last commit created from an email.
+    pub fn apply_mailbox_abort(&self, should_interrupt: &AtomicBool) -> Result<(), Error> {
+        let (state, options) = self.apply_mailbox_state()?;
+        let head = self.head()?.id().map(crate::Id::detach);
+        let orig_head = self
+            .try_find_reference("ORIG_HEAD")?
+            .map(|mut reference| reference.peel_to_id_in_place())
+            .transpose()?
+            .map(crate::Id::detach);
+        let mut paths = self.unmerged_paths()?;
+        paths.extend(self.patched_paths(&state, options).unwrap_or_default());
+
+        let safety = state.abort_safety()?;
+        let target = if safety.is_none() || safety == head {
+            if let (Some(head), Some(orig_head)) = (head, orig_head) {
+                let (old, new) = (self.find_commit(orig_head)?.tree()?, self.find_commit(head)?.tree()?);
+                old.changes()?.track_path().for_each_to_obtain_tree(&new, |change| {
+                    paths.insert(change.location.to_owned());
+                    Ok::<_, std::convert::Infallible>(crate::object::tree::diff::Action::Continue)
+                })?;
+            }
+            if head != orig_head {
+                self.edit_reference(RefEdit {
+                    change: match orig_head {
+                        Some(orig_head) => Change::Update {
+                            log: LogChange {
+                                mode: RefLog::AndReference,
+                                force_create_reflog: false,
+                                log_symbolic_changes: false,
+                                message: "am --abort".into(),
+                            },
+                            expected: PreviousValue::Any,
+                            new: Target::Object(orig_head),
+                        },
+                        None => Change::Delete {
+                            expected: PreviousValue::Any,
+                            log: RefLog::AndReference,
+                        },
+                    },
+                    name: "HEAD".try_into().expect("valid"),
+                    deref: true,
+                })?;
+            }
+            orig_head
+        } else {
+            head
+        };
+        self.restore_paths(target, paths, should_interrupt)?;
+        state.remove()
+    }
+
+    /// Return the directory in which the state of applying a mailbox is kept.
+    fn apply_mailbox_state_dir(&self) -> std::path::PathBuf {
+        self.path().join("rebase-apply")
+    }
+
+    fn apply_mailbox_state(&self) -> Result<(State, Options), Error> {
+        let state = State::read(self.apply_mailbox_state_dir())?.ok_or(Error::NotInProgress)?;
+        let mut options = self.apply_mailbox_options()?;
+        state.restore_options(&mut options)?;
+        Ok((state, options))
+    }
+
+    /// Apply all emails starting at the next one of `state`, and add the created commits to `outcome`.
+    fn apply_mailbox_from(
+        &self,
+        mut state: State,
+        options: Options,
+        mut outcome: Outcome,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        self.work_dir().ok_or(Error::MissingWorktree)?;
+        while state.next <= state.last {
+            let patch = state.next;
+            let info = self.parse_mail(&state, options)?;
+            let files = gix_apply::parse(&info.patch, Default::default())
+                .map_err(|source| Error::ParsePatch { patch, source })?;
+            if files.is_empty() {
+                return Err(Error::EmptyPatch { patch });
+            }
+            let applied = self
+                .apply(
+                    &files,
+                    crate::apply::Options {
+                        location: Location::WorktreeAndIndex,
+                        ..options.apply
+                    },
+                    should_interrupt,
+                )
+                .map_err(|err| Error::Apply {
+                    patch,
+                    subject: info.subject.clone(),
+                    source: Box::new(err),
+                })?;
+            if applied.has_conflicts() {
+                return Err(Error::Conflicts {
+                    patch,
+                    subject: info.subject.clone(),
+                });
+            }
+            let index = self.open_index()?;
+            let tree = self.write_tree_from_index(&index)?.detach();
+            outcome.commits.push(self.commit_mail(&state, &info, options, tree)?);
+            state.next += 1;
+            state.write_next()?;
+        }
+        state.remove()?;
+        Ok(outcome)
+    }
+
+    /// Parse the email that is to be applied next and record its information in `state`.
+    fn parse_mail(&self, state: &State, options: Options) -> Result<mail::Info, Error> {
+        let patch = state.next;
+        let info =
+            mail::parse(&state.mail(patch)?, options.mail).map_err(|source| Error::ParseMail { patch, source })?;
+        if info.author.email.is_empty() {
+            return Err(Error::MissingAuthor { patch });
+        }
+        state.write_current(&info, commit_message(&info, options).as_ref())?;
+        Ok(info)
+    }
+
+    /// Commit `tree` with the author and message of the email `info` on top of `HEAD`, and return the commit.
+    fn commit_mail(
+        &self,
+        state: &State,
+        info: &mail::Info,
+        options: Options,
+        tree: ObjectId,
+    ) -> Result<ObjectId, Error> {
+        let committer = self.committer().ok_or(Error::CommitterMissing)??;
+        let parent = self.head()?.id().map(crate::Id::detach);
+        let message = commit_message(info, options);
+        let commit = gix_object::Commit {
+            tree,
+            parents: parent.into_iter().collect(),
+            author: gix_actor::Signature {
+                name: info.author.name.clone(),
+                email: info.author.email.clone(),
+                time: info.time().unwrap_or(committer.time),
+            },
+            committer: committer.into(),
+            encoding: options
+                .mail
+                .encoding
+                .filter(|encoding| *encoding != gix_filter::encoding::UTF_8)
+                .map(|encoding| encoding.name().into()),
+            message,
+            extra_headers: Vec::new(),
+        };
+        let mut log_message = BString::from("am: ");
+        log_message.extend_from_slice(&info.subject);
+        #[cfg(feature = "gpg")]
+        let signer = self
+            .configured_signer(&crate::config::tree::Commit::GPG_SIGN)
+            .map_err(crate::commit::Error::from)?;
+        let id = self
+            .write_commit_inner(
+                "HEAD".try_into().expect("valid"),
+                commit,
+                Some(log_message),
+                #[cfg(feature = "gpg")]
+                signer.as_ref().map(|signer| signer as &dyn gix_gpg::Signer),
+                #[cfg(feature = "hooks")]
+                crate::hook::CommitHooks::None,
+            )?
+            .detach();
+        state.write_abort_safety(id)?;
+        Ok(id)
+    }
+
+    /// Return all paths touched by the patch of the next email in `state`.
+    fn patched_paths(&self, state: &State, options: Options) -> Result<BTreeSet<BString>, Error> {
+        let info = mail::parse(&state.mail(state.next)?, options.mail).map_err(|source| Error::ParseMail {
+            patch: state.next,
+            source,
+        })?;
+        let files = gix_apply::parse(&info.patch, Default::default()).map_err(|source| Error::ParsePatch {
+            patch: state.next,
+            source,
+        })?;
+        Ok(files
+            .into_iter()
+            .flat_map(|file| file.old_path.into_iter().chain(file.new_path))
+            .collect())
+    }
+
+    /// Return the paths of all unmerged entries in the index.
+    fn unmerged_paths(&self) -> Result<BTreeSet<BString>, Error> {
+        let index = self.index_or_empty()?;
+        Ok(index
+            .entries()
+            .iter()
+            .filter(|entry| entry.stage() != gix_index::entry::Stage::Unconflicted)
+            .map(|entry| entry.path(&index).to_owned())
+            .collect())
+    }
+
+    /// Restore `paths` in the index and the worktree to their state in the commit `target`, or remove them if there is
+    /// no `target` or if they don't exist in it.
+    fn restore_paths(
+        &self,
+        target: Option<ObjectId>,
+        paths: BTreeSet<BString>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), Error> {
+        let tree = match target {
+            Some(id) => Some(self.find_commit(id)?.tree()?),
+            None => None,
+        };
+        let mut changes = BTreeMap::new();
+        for path in paths {
+            let entry = match &tree {
+                Some(tree) => tree.lookup_entry(path.split_str("/"))?,
+                None => None,
+            };
+            let entry = entry.filter(|entry| !entry.mode().is_tree()).map(|entry| Entry {
+                kind: entry.mode().kind(),
+                id: entry.object_id(),
+                conflict: None,
+            });
+            changes.insert(path, entry);
+        }
+        let index = (**self.index_or_empty()?).clone();
+        self.write_changes(&changes, self.work_dir(), Some(index), should_interrupt)?;
+        Ok(())
+    }
+}
+
+/// Return the commit message for the email `info`, with a `Message-Id` trailer if configured in `options`.
+fn commit_message(info: &mail::Info, options: Options) -> BString {
+    let mut message = info.commit_message();
+    if let Some(id) = info.message_id.as_ref().filter(|_| options.message_id) {
+        message.extend_from_slice(b"\nMessage-Id: ");
+        message.extend_from_slice(id);
+        message.push(b'\n');
+    }
+    message
+}
@@ -0,0 +1,164 @@
+//! Apply patches from emails in a mailbox as commits, similar to `git am`.
+//!
+//! The state of the operation is kept in `.git/rebase-apply` just like `git` does, so it can be resumed, skipped or aborted
+//! by either implementation.
+use std::path::PathBuf;
+
+use crate::bstr::BString;
+use crate::config::cache::util::ApplyLeniency;
+use crate::{apply::Location, config, Repository};
+
+mod function;
+mod state;
+
+/// Options for use in [`Repository::apply_mailbox()`].
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Options {
+    /// How to extract the author, subject and message from each email.
+    pub mail: gix_apply::mail::parse::Options,
+    /// How to apply the patch of each email, which is always applied to the worktree and the index.
+    pub apply: crate::apply::Options,
+    /// If `true`, like `--message-id`, add a `Message-Id` trailer with the id of the email to each commit message.
+    pub message_id: bool,
+}
+
+/// The result of [`Repository::apply_mailbox()`] and its continuations.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The commits that were created in this run, in order.
+    pub commits: Vec<gix_hash::ObjectId>,
+}
+
+/// The error returned by [`Repository::apply_mailbox()`] and its continuations.
+///
+/// Errors that refer to a `patch` stop the operation and keep its state, so it can be
+/// [continued](Repository::apply_mailbox_continue()), [skipped](Repository::apply_mailbox_skip()) or
+/// [aborted](Repository::apply_mailbox_abort()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A mailbox is already being applied as '{}' exists", path.display())]
+    InProgress { path: PathBuf },
+    #[error("No mailbox is being applied")]
+    NotInProgress,
+    #[error("The mailbox didn't contain any email")]
+    EmptyMailbox,
+    #[error("The state file at '{}' is invalid", path.display())]
+    InvalidState { path: PathBuf },
+    #[error("Could not access the state at '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Could not parse email {patch}")]
+    ParseMail {
+        patch: usize,
+        source: gix_apply::mail::parse::Error,
+    },
+    #[error("Email {patch} doesn't have a valid author")]
+    MissingAuthor { patch: usize },
+    #[error("Could not parse the patch in email {patch}")]
+    ParsePatch {
+        patch: usize,
+        source: gix_apply::parse::Error,
+    },
+    #[error("Email {patch} doesn't contain a patch")]
+    EmptyPatch { patch: usize },
+    #[error("Email {patch} with subject '{subject}' couldn't be applied")]
+    Apply {
+        patch: usize,
+        subject: BString,
+        source: Box<crate::apply::Error>,
+    },
+    #[error("Email {patch} with subject '{subject}' was applied with conflicts")]
+    Conflicts { patch: usize, subject: BString },
+    #[error("The index of email {patch} has no changes compared to HEAD, which may be skipped instead")]
+    NoChanges { patch: usize },
+    #[error("The conversion of the message of email {patch} to '{encoding}' failed")]
+    Encoding { patch: usize, encoding: &'static str },
+    #[error("Committer identity is not configured")]
+    CommitterMissing,
+    #[error("A worktree is required to apply a mailbox")]
+    MissingWorktree,
+    #[error(transparent)]
+    ParseTime(#[from] config::time::Error),
+    #[error(transparent)]
+    Options(#[from] options::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    FindObjectWithConversion(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    CommitTree(#[from] crate::object::commit::Error),
+    #[error(transparent)]
+    DiffInit(#[from] crate::diff::new_rewrites::Error),
+    #[error(transparent)]
+    Diff(#[from] crate::object::tree::diff::for_each::Error),
+    #[error(transparent)]
+    Index(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    RestoreFiles(#[from] crate::apply::Error),
+    #[error(transparent)]
+    WriteTree(#[from] crate::repository::write_tree_from_index::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    Commit(#[from] crate::commit::Error),
+}
+
+///
+pub mod options {
+    /// The error returned by [`Repository::apply_mailbox_options()`](crate::Repository::apply_mailbox_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Apply(#[from] crate::apply::options::Error),
+        #[error(transparent)]
+        Boolean(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        Encoding(#[from] crate::config::encoding::Error),
+    }
+}
+
+/// Configuration
+impl Repository {
+    /// Return the options for use in [`apply_mailbox()`](Self::apply_mailbox()) as configured by `am.keepcr`, `am.threeWay`,
+    /// `am.messageId`, `mailinfo.scissors` and `i18n.commitEncoding`, along with the configuration for
+    /// [applying patches](Self::apply_options()).
+    pub fn apply_mailbox_options(&self) -> Result<Options, options::Error> {
+        use config::tree::{Am, I18n, Key, Mailinfo};
+        let config = &self.config.resolved;
+        let boolean = |key: &'static config::tree::keys::Boolean| -> Result<bool, options::Error> {
+            Ok(config
+                .boolean(key)
+                .map(|value| key.enrich_error(value))
+                .transpose()
+                .with_leniency(self.config.lenient_config)?
+                .unwrap_or_default())
+        };
+        let mut opts = Options {
+            apply: self.apply_options(Location::WorktreeAndIndex)?,
+            message_id: boolean(&Am::MESSAGE_ID)?,
+            ..Default::default()
+        };
+        opts.apply.three_way = boolean(&Am::THREE_WAY)?;
+        opts.mail.keep_cr = boolean(&Am::KEEP_CR)?;
+        opts.mail.scissors = boolean(&Mailinfo::SCISSORS)?;
+        if let Some(value) = config.string(I18n::COMMIT_ENCODING) {
+            opts.mail.encoding = Some(
+                gix_filter::encoding::Encoding::for_label(value.as_ref()).ok_or_else(|| config::encoding::Error {
+                    key: I18n::COMMIT_ENCODING.logical_name().into(),
+                    value: value.as_ref().to_owned(),
+                    encoding: value.as_ref().to_owned(),
+                })?,
+            );
+        }
+        Ok(opts)
+    }
+}
@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+
+use gix_apply::mail;
+use gix_hash::ObjectId;
+
+use super::{Error, Options};
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The state of applying a mailbox as stored in `.git/rebase-apply`, compatible with `git am`.
+pub(super) struct State {
+    pub dir: PathBuf,
+    /// The number of the next email to apply, starting at 1.
+    pub next: usize,
+    /// The number of the last email.
+    pub last: usize,
+}
+
+impl State {
+    /// Create the state for applying `mails` on top of `head`, recording `options` so they can be restored.
+    pub fn create(dir: PathBuf, mails: &[&[u8]], head: Option<ObjectId>, options: &Options) -> Result<Self, Error> {
+        std::fs::create_dir_all(&dir).map_err(|source| Error::Io {
+            path: dir.clone(),
+            source,
+        })?;
+        let state = State {
+            dir,
+            next: 1,
+            last: mails.len(),
+        };
+        for (number, mail) in (1..).zip(mails) {
+            state.write(&format!("{number:04}"), mail)?;
+        }
+        let flag = |value: bool| if value { "t" } else { "f" };
+        let keep = if options.mail.keep_subject {
+            "t"
+        } else if options.mail.keep_non_patch_brackets {
+            "b"
+        } else {
+            "f"
+        };
+        for (name, value) in [
+            ("keep", keep),
+            ("keepcr", flag(options.mail.keep_cr)),
+            ("scissors", flag(options.mail.scissors)),
+            ("messageid", flag(options.message_id)),
+            ("threeway", flag(options.apply.three_way)),
+            ("utf8", flag(options.mail.encoding.is_some())),
+            ("quiet", "f"),
+            ("sign", "f"),
+        ] {
+            state.write(name, format!("{value}\n").as_bytes())?;
+        }
+        if let Some(head) = head {
+            state.write_abort_safety(head)?;
+        }
+        state.write("last", format!("{}\n", state.last).as_bytes())?;
+        state.write_next()?;
+        state.write("applying", b"")?;
+        Ok(state)
+    }
+
+    /// Read the state from `dir`, or return `None` if there is none.
+    pub fn read(dir: PathBuf) -> Result<Option<Self>, Error> {
+        if !dir.is_dir() {
+            return Ok(None);
+        }
+        let mut state = State { dir, next: 0, last: 0 };
+        state.next = state.read_number("next")?;
+        state.last = state.read_number("last")?;
+        Ok(Some(state))
+    }
+
+    /// Override `options` with the options that were recorded when the state was created.
+    pub fn restore_options(&self, options: &mut Options) -> Result<(), Error> {
+        let flag = |name: &str| -> Result<Option<bool>, Error> {
+            Ok(match self.try_read_file(name)?.as_ref().map(|value| value.trim()) {
+                Some(b"t") => Some(true),
+                Some(b"f") => Some(false),
+                _ => None,
+            })
+        };
+        match self.try_read_file("keep")?.as_ref().map(|value| value.trim()) {
+            Some(b"t") => options.mail.keep_subject = true,
+            Some(b"b") => options.mail.keep_non_patch_brackets = true,
+            _ => {}
+        }
+        if let Some(value) = flag("keepcr")? {
+            options.mail.keep_cr = value;
+        }
+        if let Some(value) = flag("scissors")? {
+            options.mail.scissors = value;
+        }
+        if let Some(value) = flag("messageid")? {
+            options.message_id = value;
+        }
+        if let Some(value) = flag("threeway")? {
+            options.apply.three_way = value;
+        }
+        if flag("utf8")? == Some(false) {
+            options.mail.encoding = None;
+        }
+        Ok(())
+    }
+
+    /// Return the email with `number`.
+    pub fn mail(&self, number: usize) -> Result<Vec<u8>, Error> {
+        self.read_file(&format!("{number:04}"))
+    }
+
+    /// Record the information about the email that is currently applied, like `git am` does.
+    pub fn write_current(&self, info: &mail::Info, message: &BStr) -> Result<(), Error> {
+        let mut author_script = BString::default();
+        for (name, value) in [
+            ("GIT_AUTHOR_NAME", info.author.name.as_bstr()),
+            ("GIT_AUTHOR_EMAIL", info.author.email.as_bstr()),
+            (
+                "GIT_AUTHOR_DATE",
+                info.date.as_ref().map(|d| d.as_bstr()).unwrap_or_default(),
+            ),
+        ] {
+            author_script.extend_from_slice(name.as_bytes());
+            author_script.extend_from_slice(b"='");
+            author_script.extend_from_slice(&value.replace("'", "'\\''"));
+            author_script.extend_from_slice(b"'\n");
+        }
+        self.write("author-script", &author_script)?;
+        self.write("final-commit", message)?;
+        self.write("patch", &info.patch)
+    }
+
+    /// Record the commit `HEAD` points to after applying an email, so aborting can assure it didn't change since.
+    pub fn write_abort_safety(&self, head: ObjectId) -> Result<(), Error> {
+        self.write("abort-safety", format!("{head}\n").as_bytes())
+    }
+
+    /// Return the commit `HEAD` pointed to after the last email was applied, if known.
+    pub fn abort_safety(&self) -> Result<Option<ObjectId>, Error> {
+        Ok(self
+            .try_read_file("abort-safety")?
+            .and_then(|hex| ObjectId::from_hex(hex.trim()).ok()))
+    }
+
+    pub fn write_next(&self) -> Result<(), Error> {
+        self.write("next", format!("{}\n", self.next).as_bytes())
+    }
+
+    /// Remove the state entirely.
+    pub fn remove(self) -> Result<(), Error> {
+        std::fs::remove_dir_all(&self.dir).map_err(|source| Error::Io { path: self.dir, source })
+    }
+
+    fn read_number(&self, name: &str) -> Result<usize, Error> {
+        let value = self.read_file(name)?;
+        value
+            .trim()
+            .to_str()
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| Error::InvalidState {
+                path: self.dir.join(name),
+            })
+    }
+
+    fn read_file(&self, name: &str) -> Result<Vec<u8>, Error> {
+        let path = self.dir.join(name);
+        std::fs::read(&path).map_err(|source| Error::Io { path, source })
+    }
+
+    fn try_read_file(&self, name: &str) -> Result<Option<Vec<u8>>, Error> {
+        let path = self.dir.join(name);
+        match std::fs::read(&path) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(Error::Io { path, source }),
+        }
+    }
+
+    fn write(&self, name: &str, data: &[u8]) -> Result<(), Error> {
+        let path = self.dir.join(name);
+        std::fs::write(&path, data).map_err(|source| Error::Io { path, source })
+    }
+}
@@ -0,0 +1,461 @@
+use std::{
+    collections::BTreeMap,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use gix_hash::ObjectId;
+use gix_object::tree::EntryKind;
+
+use super::{plumbing, Error, File, Location, Options, Outcome};
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    Repository,
+};
+
+/// The state of a path after all patches that touch it were applied.
+#[derive(Clone, Copy)]
+pub(crate) struct Entry {
+    pub(crate) kind: EntryKind,
+    /// The blob to write to the worktree, which contains conflict markers if `conflict` is set.
+    pub(crate) id: ObjectId,
+    /// The ids of the base, our and their version if the file was merged with conflicts.
+    pub(crate) conflict: Option<[ObjectId; 3]>,
+}
+
+/// Patching
+impl Repository {
+    /// Apply all `patches` to the location configured in `options`, similar to `git apply`.
+    ///
+    /// All patches are applied in memory first, so if one of them fails nothing is written. Patches that touch a path that
+    /// was changed by a previous patch see the result of the previous patch.
+    /// New blobs are always written to the object database, as files are written to the worktree with a
+    /// [checkout](gix_worktree_state::checkout()) which applies all configured filters.
+    ///
+    /// `should_interrupt` is checked while writing files to the worktree.
+    pub fn apply(
+        &self,
+        patches: &[plumbing::File],
+        options: Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::apply()");
+        let workdir = match options.location {
+            Location::Worktree | Location::WorktreeAndIndex => Some(self.work_dir().ok_or(Error::MissingWorktree)?),
+            Location::Index | Location::Tree(_) => None,
+        };
+        let mut source = Source {
+            repo: self,
+            location: options.location,
+            workdir,
+            index: match options.location {
+                Location::Index | Location::WorktreeAndIndex => Some((**self.index_or_empty()?).clone()),
+                Location::Worktree | Location::Tree(_) => None,
+            },
+            pipeline: None,
+            tree: match options.location {
+                Location::Tree(id) => Some(self.find_tree(id)?),
+                _ => None,
+            },
+            executable_bit: workdir.is_some() && self.filesystem_options().map_or(true, |fs| fs.executable_bit),
+            changes: BTreeMap::new(),
+        };
+
+        let mut outcome = Outcome::default();
+        for patch in patches {
+            let path = patch.path().clone();
+            let old = match (patch.operation, &patch.old_path) {
+                (plumbing::Operation::Create, _) | (_, None) => None,
+                (_, Some(old_path)) => Some(
+                    source
+                        .read(old_path.as_ref())?
+                        .ok_or_else(|| Error::DoesNotExist { path: old_path.clone() })?,
+                ),
+            };
+            if matches!(
+                patch.operation,
+                plumbing::Operation::Create | plumbing::Operation::Rename | plumbing::Operation::Copy
+            ) && source.read(path.as_ref())?.is_some()
+            {
+                return Err(Error::AlreadyExists { path });
+            }
+
+            let mut file = File {
+                path: path.clone(),
+                hunks: Vec::new(),
+                whitespace_problems: Vec::new(),
+                conflicts: 0,
+            };
+            let old_kind = old.as_ref().map(|blob| blob.kind);
+            let old_data = old.map(|blob| blob.data).unwrap_or_default();
+            let mut conflict = None;
+            let new_data = match &patch.content {
+                plumbing::Content::Text(hunks) if hunks.is_empty() => old_data,
+                plumbing::Content::Text(hunks) => match plumbing::apply::text(&old_data, hunks, options.hunks) {
+                    Ok(applied) => {
+                        file.hunks = applied.hunks;
+                        file.whitespace_problems = applied.whitespace_problems;
+                        applied.data
+                    }
+                    Err(plumbing::apply::text::Error::HunkMismatch { .. }) if options.three_way => {
+                        let base_id = patch
+                            .old_id
+                            .as_ref()
+                            .and_then(|hex| gix_hash::Prefix::from_hex(hex.to_str().ok()?).ok())
+                            .map(|prefix| self.objects.lookup_prefix(prefix, None))
+                            .transpose()?
+                            .flatten()
+                            .and_then(Result::ok)
+                            .ok_or_else(|| Error::ThreeWayPreimage { path: path.clone() })?;
+                        let base = self.find_object(base_id)?.detach().data;
+                        let theirs = plumbing::apply::text(&base, hunks, options.hunks)
+                            .map_err(|err| Error::Hunks {
+                                path: path.clone(),
+                                source: err,
+                            })?
+                            .data;
+                        let mut merged = Vec::new();
+                        let merge = gix_merge::blob::merge(
+                            &mut merged,
+                            &base,
+                            &old_data,
+                            &theirs,
+                            gix_merge::blob::Labels {
+                                ancestor: Some("base".into()),
+                                current: Some("ours".into()),
+                                other: Some("theirs".into()),
+                            },
+                            options.merge,
+                        );
+                        if merge.is_conflicted() {
+                            if !matches!(options.location, Location::Worktree | Location::WorktreeAndIndex) {
+                                return Err(Error::Conflict { path });
+                            }
+                            file.conflicts = merge.conflicts;
+                            conflict = Some([
+                                base_id,
+                                self.write_blob(&old_data)?.detach(),
+                                self.write_blob(&theirs)?.detach(),
+                            ]);
+                        }
+                        merged
+                    }
+                    Err(err) => return Err(Error::Hunks { path, source: err }),
+                },
+                plumbing::Content::Binary(binary) => {
+                    if !is_expected_id(patch.old_id.as_ref(), &old_data, self.object_hash()) {
+                        return Err(Error::BinaryMismatch { path, preimage: true });
+                    }
+                    let new_data =
+                        plumbing::apply::binary(&old_data, &binary.forward).map_err(|err| Error::Binary {
+                            path: path.clone(),
+                            source: err,
+                        })?;
+                    if !is_expected_id(patch.new_id.as_ref(), &new_data, self.object_hash()) {
+                        return Err(Error::BinaryMismatch { path, preimage: false });
+                    }
+                    new_data
+                }
+                plumbing::Content::BinaryWithoutData => {
+                    match patch
+                        .new_id
+                        .as_ref()
+                        .and_then(|hex| ObjectId::from_hex(hex).ok())
+                        .filter(|id| self.has_object(id))
+                    {
+                        Some(id) => self.find_object(id)?.detach().data,
+                        None => return Err(Error::MissingBinaryData { path }),
+                    }
+                }
+            };
+
+            if let Some(old_path) = patch.old_path.as_ref().filter(|_| {
+                matches!(
+                    patch.operation,
+                    plumbing::Operation::Rename | plumbing::Operation::Delete
+                )
+            }) {
+                source.changes.insert(old_path.clone(), None);
+            }
+            if patch.operation == plumbing::Operation::Delete {
+                if !new_data.is_empty() {
+                    return Err(Error::DeletionLeavesContent { path });
+                }
+            } else {
+                let kind = patch
+                    .new_mode
+                    .map(|mode| mode.kind())
+                    .or(old_kind)
+                    .unwrap_or(EntryKind::Blob);
+                let id = self.write_blob(&new_data)?.detach();
+                source.changes.insert(path, Some(Entry { kind, id, conflict }));
+            }
+            outcome.files.push(file);
+        }
+
+        let Source {
+            changes,
+            index,
+            tree,
+            workdir,
+            ..
+        } = source;
+        if let Some(tree) = tree {
+            let mut editor = tree.edit().map_err(crate::repository::edit_tree::Error::from)?;
+            for (path, entry) in &changes {
+                match entry {
+                    Some(entry) => editor.upsert(path, entry.kind, entry.id)?,
+                    None => editor.remove(path)?,
+                };
+            }
+            outcome.tree = Some(editor.write()?.detach());
+        }
+
+        self.write_changes(&changes, workdir, index, should_interrupt)?;
+        Ok(outcome)
+    }
+
+    /// Write `changes` to the files in `workdir` if set, and to `index` if set, which is written to disk afterwards.
+    ///
+    /// Paths mapped to `None` are deleted, and entries with a conflict are added to `index` with all their stages.
+    pub(crate) fn write_changes(
+        &self,
+        changes: &BTreeMap<BString, Option<Entry>>,
+        workdir: Option<&Path>,
+        index: Option<gix_index::File>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), Error> {
+        let mut stats = BTreeMap::new();
+        if let Some(workdir) = workdir {
+            for path in changes
+                .iter()
+                .filter_map(|(path, entry)| entry.is_none().then_some(path))
+            {
+                remove_file(workdir, path.as_ref())?;
+            }
+            let mut state = gix_index::State::new(self.object_hash());
+            for (path, entry) in changes.iter().filter_map(|(path, entry)| entry.map(|e| (path, e))) {
+                state.dangerously_push_entry(
+                    Default::default(),
+                    entry.id,
+                    gix_index::entry::Flags::empty(),
+                    gix_index::entry::Mode::from(gix_object::tree::EntryMode::from(entry.kind)),
+                    path.as_ref(),
+                );
+            }
+            state.sort_entries();
+            let mut opts = self.config.checkout_options(
+                self,
+                gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )?;
+            opts.overwrite_existing = true;
+            gix_worktree_state::checkout(
+                &mut state,
+                workdir,
+                self.objects.clone().into_arc().map_err(|err| Error::Io {
+                    path: self.objects.store_ref().path().into(),
+                    source: err,
+                })?,
+                &gix_features::progress::Discard,
+                &gix_features::progress::Discard,
+                should_interrupt,
+                opts,
+            )?;
+            for entry in state.entries() {
+                stats.insert(entry.path(&state).to_owned(), entry.stat);
+            }
+        }
+
+        if let Some(mut index) = index {
+            index.remove_entries(|_, path, _| changes.contains_key(path));
+            for (path, entry) in changes.iter().filter_map(|(path, entry)| entry.map(|e| (path, e))) {
+                let mode = gix_index::entry::Mode::from(gix_object::tree::EntryMode::from(entry.kind));
+                match entry.conflict {
+                    Some(ids) => {
+                        for (stage, id) in (1..).zip(ids) {
+                            index.dangerously_push_entry(
+                                Default::default(),
+                                id,
+                                gix_index::entry::Flags::from_bits_retain(stage << 12),
+                                mode,
+                                path.as_ref(),
+                            );
+                        }
+                    }
+                    None => index.dangerously_push_entry(
+                        stats.get(path).copied().unwrap_or_default(),
+                        entry.id,
+                        gix_index::entry::Flags::empty(),
+                        mode,
+                        path.as_ref(),
+                    ),
+                }
+            }
+            index.sort_entries();
+            index.write(Default::default())?;
+        }
+        Ok(())
+    }
+}
+
+/// A file as read from the location patches are applied to.
+struct Blob {
+    data: Vec<u8>,
+    kind: EntryKind,
+}
+
+/// Provide access to the files in the location patches are applied to.
+struct Source<'repo> {
+    repo: &'repo Repository,
+    location: Location,
+    workdir: Option<&'repo Path>,
+    index: Option<gix_index::File>,
+    pipeline: Option<(
+        crate::filter::Pipeline<'repo>,
+        crate::worktree::IndexPersistedOrInMemory,
+    )>,
+    tree: Option<crate::Tree<'repo>>,
+    executable_bit: bool,
+    /// The changes of all patches that were applied so far.
+    changes: BTreeMap<BString, Option<Entry>>,
+}
+
+impl Source<'_> {
+    /// Read the file at `path`, or return `None` if it doesn't exist.
+    fn read(&mut self, path: &BStr) -> Result<Option<Blob>, Error> {
+        if let Some(change) = self.changes.get(path) {
+            return Ok(match change {
+                Some(entry) => Some(Blob {
+                    data: self.repo.find_object(entry.id)?.detach().data,
+                    kind: entry.kind,
+                }),
+                None => None,
+            });
+        }
+        match self.location {
+            Location::Worktree => self.read_from_worktree(path),
+            Location::Index => self.read_from_index(path),
+            Location::WorktreeAndIndex => {
+                let in_index = self.index_entry(path);
+                let Some(blob) = self.read_from_worktree(path)? else {
+                    return match in_index {
+                        Some(_) => Err(Error::DoesNotExist { path: path.to_owned() }),
+                        None => Ok(None),
+                    };
+                };
+                let Some((id, _)) = in_index else {
+                    return Err(Error::DoesNotExist { path: path.to_owned() });
+                };
+                if gix_object::compute_hash(self.repo.object_hash(), gix_object::Kind::Blob, &blob.data) != id {
+                    return Err(Error::WorktreeMismatch { path: path.to_owned() });
+                }
+                Ok(Some(blob))
+            }
+            Location::Tree(_) => {
+                let tree = self.tree.as_ref().expect("set for tree location");
+                let Some(entry) = tree.lookup_entry_by_path(gix_path::from_bstr(path))? else {
+                    return Ok(None);
+                };
+                if !entry.mode().is_blob_or_symlink() {
+                    return Ok(None);
+                }
+                Ok(Some(Blob {
+                    data: entry.object()?.detach().data,
+                    kind: entry.mode().kind(),
+                }))
+            }
+        }
+    }
+
+    fn index_entry(&self, path: &BStr) -> Option<(ObjectId, EntryKind)> {
+        let index = self.index.as_ref()?;
+        let entry = index.entry_by_path_and_stage(path, gix_index::entry::Stage::Unconflicted)?;
+        Some((entry.id, entry.mode.to_tree_entry_mode()?.kind()))
+    }
+
+    fn read_from_index(&mut self, path: &BStr) -> Result<Option<Blob>, Error> {
+        let Some((id, kind)) = self.index_entry(path) else {
+            return Ok(None);
+        };
+        Ok(Some(Blob {
+            data: self.repo.find_object(id)?.detach().data,
+            kind,
+        }))
+    }
+
+    fn read_from_worktree(&mut self, rela_path: &BStr) -> Result<Option<Blob>, Error> {
+        let workdir = self.workdir.expect("set for worktree locations");
+        let path = workdir.join(gix_path::from_bstr(rela_path));
+        let io_err = |source| Error::Io {
+            path: path.clone(),
+            source,
+        };
+        let metadata = match std::fs::symlink_metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(io_err(err)),
+        };
+        if metadata.is_dir() {
+            return Ok(None);
+        }
+        if metadata.is_symlink() {
+            let target = std::fs::read_link(&path).map_err(io_err)?;
+            return Ok(Some(Blob {
+                data: gix_path::into_bstr(target).into_owned().into(),
+                kind: EntryKind::Link,
+            }));
+        }
+
+        let kind = if self.executable_bit {
+            if gix_fs::is_executable(&metadata) {
+                EntryKind::BlobExecutable
+            } else {
+                EntryKind::Blob
+            }
+        } else {
+            self.index_entry(rela_path)
+                .map(|(_, kind)| kind)
+                .filter(|kind| *kind == EntryKind::BlobExecutable)
+                .unwrap_or(EntryKind::Blob)
+        };
+        if self.pipeline.is_none() {
+            self.pipeline = Some(self.repo.filter_pipeline(None)?);
+        }
+        let (pipeline, index) = self.pipeline.as_mut().expect("just set");
+        let file = std::fs::File::open(&path).map_err(io_err)?;
+        let mut data = Vec::new();
+        pipeline
+            .convert_to_git(file, gix_path::from_bstr(rela_path).as_ref(), index)?
+            .read_to_end(&mut data)
+            .map_err(io_err)?;
+        Ok(Some(Blob { data, kind }))
+    }
+}
+
+/// Return `true` if `hex` isn't a full object id, or if it is the id of `data`.
+fn is_expected_id(hex: Option<&BString>, data: &[u8], object_hash: gix_hash::Kind) -> bool {
+    match hex.and_then(|hex| ObjectId::from_hex(hex).ok()) {
+        Some(id) if id.is_null() => data.is_empty(),
+        Some(id) => gix_object::compute_hash(object_hash, gix_object::Kind::Blob, data) == id,
+        None => true,
+    }
+}
+
+/// Remove the file at `rela_path` along with all parent directories that became empty.
+fn remove_file(workdir: &Path, rela_path: &BStr) -> Result<(), Error> {
+    let path: PathBuf = workdir.join(gix_path::from_bstr(rela_path));
+    match std::fs::remove_file(&path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(source) => return Err(Error::Io { path, source }),
+    }
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|dir| *dir != workdir) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+    Ok(())
+}
@@ -0,0 +1,185 @@
+//! Apply patches to the worktree, the index or a tree, similar to `git apply`.
+use crate::bstr::{BString, ByteSlice};
+use crate::config::cache::util::ApplyLeniencyDefault;
+use crate::{config, Repository};
+pub use gix_apply as plumbing;
+
+pub(crate) mod function;
+
+/// Where to apply a patch to.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Location {
+    /// Apply the patch to the files in the worktree only, which is the default of `git apply`.
+    #[default]
+    Worktree,
+    /// Apply the patch to the index only, without touching the worktree, like `git apply --cached`.
+    Index,
+    /// Apply the patch to the worktree and the index, like `git apply --index`.
+    ///
+    /// The files in the worktree must match their index entries.
+    WorktreeAndIndex,
+    /// Apply the patch to the tree with the given id, and write the resulting tree to the object database.
+    Tree(gix_hash::ObjectId),
+}
+
+/// Options for use in [`Repository::apply()`].
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Options {
+    /// Where to apply the patch to.
+    pub location: Location,
+    /// Options to control how hunks are matched, and how whitespace errors are handled.
+    pub hunks: plumbing::apply::Options,
+    /// If `true`, like `--3way`, fall back to a three-way merge if hunks don't apply, which requires the object
+    /// database to contain the version of the file the patch was created from.
+    ///
+    /// Conflicts are recorded in the index as stages if it is written to, and conflict markers are written to the worktree.
+    pub three_way: bool,
+    /// Options for the three-way merge.
+    pub merge: gix_merge::blob::Options,
+}
+
+/// The result of [`Repository::apply()`].
+#[derive(Default, Debug, Clone)]
+pub struct Outcome {
+    /// Information about each patched file, in order of the patches.
+    pub files: Vec<File>,
+    /// The id of the written tree if the patch was applied to [a tree](Location::Tree).
+    pub tree: Option<gix_hash::ObjectId>,
+}
+
+impl Outcome {
+    /// Return `true` if at least one file was merged with conflicts.
+    pub fn has_conflicts(&self) -> bool {
+        self.files.iter().any(|file| file.conflicts != 0)
+    }
+}
+
+/// Information about a single patched file.
+#[derive(Debug, Clone)]
+pub struct File {
+    /// The path of the file after the patch was applied, or the deleted path.
+    pub path: BString,
+    /// Information about where each hunk was applied, in order of the hunks, or empty if the file was merged or binary.
+    pub hunks: Vec<plumbing::apply::HunkOutcome>,
+    /// All whitespace errors found in added lines.
+    pub whitespace_problems: Vec<plumbing::whitespace::Problem>,
+    /// The amount of conflicts if a [three-way merge](Options::three_way) was performed.
+    pub conflicts: usize,
+}
+
+/// The error returned by [`Repository::apply()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A worktree is required to apply patches to it")]
+    MissingWorktree,
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error("Could not access '{}' in the worktree", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    LookupPrefix(#[from] gix_odb::store::prefix::lookup::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    EditTree(#[from] crate::repository::edit_tree::Error),
+    #[error(transparent)]
+    TreeEdit(#[from] gix_object::tree::editor::Error),
+    #[error(transparent)]
+    WriteTree(#[from] crate::object::tree::editor::write::Error),
+    #[error(transparent)]
+    FindTree(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error("'{path}' does not exist")]
+    DoesNotExist { path: BString },
+    #[error("'{path}' already exists")]
+    AlreadyExists { path: BString },
+    #[error("'{path}' does not match the index")]
+    WorktreeMismatch { path: BString },
+    #[error("Patch failed at '{path}'")]
+    Hunks {
+        path: BString,
+        source: plumbing::apply::text::Error,
+    },
+    #[error("Binary patch failed at '{path}'")]
+    Binary {
+        path: BString,
+        source: plumbing::apply::binary::Error,
+    },
+    #[error("The binary patch for '{path}' contains no data, and its new version isn't in the object database")]
+    MissingBinaryData { path: BString },
+    #[error("The binary patch for '{path}' does not apply to its {} version", if *preimage { "old" } else { "new" })]
+    BinaryMismatch { path: BString, preimage: bool },
+    #[error("Removal patch for '{path}' leaves file contents")]
+    DeletionLeavesContent { path: BString },
+    #[error("'{path}' can't be merged as the version it was patched from isn't in the object database")]
+    ThreeWayPreimage { path: BString },
+    #[error("Merging '{path}' resulted in conflicts, which can't be stored in trees or in the index alone")]
+    Conflict { path: BString },
+}
+
+///
+pub mod options {
+    /// The error returned by [`Repository::apply_options()`](crate::Repository::apply_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The value of 'core.whitespace' is invalid")]
+        Whitespace(#[from] super::plumbing::whitespace::from_config::Error),
+        #[error(transparent)]
+        Config(#[from] crate::config::key::GenericErrorWithValue),
+    }
+}
+
+/// Patching
+impl Repository {
+    /// Return options for use in [`apply()`](Self::apply()) that apply patches to `location`, configured with
+    /// `core.whitespace`, `apply.whitespace` and `apply.ignoreWhitespace`.
+    pub fn apply_options(&self, location: Location) -> Result<Options, options::Error> {
+        use config::tree::{Apply, Core};
+        use plumbing::whitespace;
+
+        let config = &self.config.resolved;
+        let mut opts = Options {
+            location,
+            ..Default::default()
+        };
+        if let Some(value) = config.string(Core::WHITESPACE) {
+            opts.hunks.whitespace = whitespace::Rules::from_config(value.as_ref())
+                .map_err(options::Error::from)
+                .with_lenient_default(self.config.lenient_config)?;
+        }
+        if let Some(value) = config.string(Apply::WHITESPACE) {
+            opts.hunks.whitespace_action = whitespace::Action::from_config(value.as_ref())
+                .ok_or_else(|| config::key::GenericErrorWithValue::from_value(&Apply::WHITESPACE, value.into_owned()))
+                .map_err(options::Error::from)
+                .with_lenient_default(self.config.lenient_config)?;
+        }
+        if let Some(value) = config.string(Apply::IGNORE_WHITESPACE) {
+            opts.hunks.ignore_whitespace = match value.as_ref().as_bytes() {
+                b"change" => Ok(true),
+                b"no" | b"none" | b"never" | b"false" => Ok(false),
+                _ => Err(config::key::GenericErrorWithValue::from_value(
+                    &Apply::IGNORE_WHITESPACE,
+                    value.into_owned(),
+                )),
+            }
+            .with_lenient_default(self.config.lenient_config)?;
+        }
+        Ok(opts)
+    }
+}
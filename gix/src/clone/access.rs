@@ -35,6 +35,14 @@ impl PrepareFetch {
         self
     }
 
+    /// Make this a partial clone by asking the remote to omit the objects described by `filter`.
+    ///
+    /// The remote is configured as *promisor remote* from which missing objects are fetched once they are needed.
+    pub fn with_filter(mut self, filter: crate::remote::fetch::Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     /// Apply the given configuration `values` right before readying the actual fetch from the remote.
     /// The configuration is marked with [source API](gix_config::Source::Api), and will not be written back, it's
     /// retained only in memory.
@@ -35,6 +35,10 @@ pub mod main_worktree {
         FindHead(#[from] crate::reference::find::existing::Error),
         #[error("The HEAD reference could not be located")]
         PeelHeadToId(#[from] crate::head::peel::Error),
+        #[error(transparent)]
+        SparseCheckoutPatterns(#[from] crate::sparse_checkout::patterns::Error),
+        #[error("Could not fetch the objects to check out from the promisor remote")]
+        FetchMissingObjects(#[source] gix_odb::store::promisor::Error),
     }
 
     /// The progress ids used in [`PrepareCheckout::main_worktree()`].
@@ -65,6 +69,9 @@ pub mod main_worktree {
         /// Note that this is a no-op if the remote was empty, leaving this repository empty as well. This can be validated by checking
         /// if the `head()` of the returned repository is *not* unborn.
         ///
+        /// The `post-checkout` [hook](crate::hook) runs once the worktree was checked out, but note that hooks can only be set
+        /// up for a clone with [in-process hooks](crate::hook::Hooks::with_hook()), or with `core.hooksPath`.
+        ///
         /// # Panics
         ///
         /// If called after it was successful. The reason here is that it auto-deletes the contained repository,
@@ -122,6 +129,21 @@ pub mod main_worktree {
                 .config
                 .checkout_options(repo, gix_worktree::stack::state::attributes::Source::IdMapping)?;
             opts.destination_is_initially_empty = true;
+            let sparse_checkout = repo.sparse_checkout_patterns()?;
+            opts.sparse_checkout.clone_from(&sparse_checkout);
+            if let Some(patterns) = &sparse_checkout {
+                patterns.apply_to_index(&mut index);
+            }
+            // In partial clones, fetch all objects we are about to check out at once, instead of one by one.
+            repo.objects
+                .fetch_missing(
+                    index
+                        .entries()
+                        .iter()
+                        .filter(|e| !e.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE) && !e.mode.is_submodule())
+                        .map(|e| e.id),
+                )
+                .map_err(Error::FetchMissingObjects)?;
 
             let mut files = progress.add_child_with_id("checkout".to_string(), ProgressId::CheckoutFiles.into());
             let mut bytes = progress.add_child_with_id("writing".to_string(), ProgressId::BytesWritten.into());
@@ -142,7 +164,23 @@ pub mod main_worktree {
             files.show_throughput(start);
             bytes.show_throughput(start);
 
+            if let Some(patterns) = &sparse_checkout {
+                repo.collapse_sparse_directories_if_configured(&mut index, patterns)?;
+            }
             index.write(Default::default())?;
+            #[cfg(feature = "hooks")]
+            repo.run_post_hook(
+                "post-checkout",
+                vec![
+                    repo.object_hash().null().to_string().into(),
+                    root_tree_id
+                        .expect("present if something was checked out")
+                        .to_string()
+                        .into(),
+                    "1".into(),
+                ],
+                &[],
+            );
             Ok((self.repo.take().expect("still present").clone(), outcome))
         }
     }
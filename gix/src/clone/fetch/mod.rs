@@ -30,6 +30,8 @@ pub enum Error {
     SaveConfig(#[from] crate::remote::save::AsError),
     #[error("Failed to write repository configuration to disk")]
     SaveConfigIo(#[from] std::io::Error),
+    #[error("Failed to lock the repository configuration for writing")]
+    LockConfig(#[from] gix_lock::acquire::Error),
     #[error("The remote HEAD points to a reference named {head_ref_name:?} which is invalid.")]
     InvalidHeadRef {
         source: gix_validate::reference::name::Error,
@@ -198,6 +200,10 @@ impl PrepareFetch {
             b.insert_str(0, "clone: from ");
             b
         };
+        let pending_pack = match &self.filter {
+            Some(filter) => pending_pack.with_filter(Some(filter.clone())),
+            None => pending_pack,
+        };
         let outcome = pending_pack
             .with_write_packed_refs_only(true)
             .with_reflog_message(RefLogMessage::Override {
@@ -208,6 +214,10 @@ impl PrepareFetch {
             .await?;
 
         util::append_config_to_repo_config(repo, config);
+        if let Some(filter) = &self.filter {
+            util::write_partial_clone_config(repo, remote_name.as_ref(), filter)?;
+            repo.setup_promisor();
+        }
         util::update_head(
             repo,
             &outcome.ref_map,
@@ -58,6 +58,43 @@ pub fn append_config_to_repo_config(repo: &mut Repository, config: gix_config::F
     repo_config.append(config);
 }
 
+/// Make `repo` a partial clone that fetches objects omitted due to `filter` from the remote named `remote_name`,
+/// both in its configuration file and in memory.
+pub fn write_partial_clone_config(
+    repo: &mut Repository,
+    remote_name: &BStr,
+    filter: &crate::remote::fetch::Filter,
+) -> Result<(), Error> {
+    let filter = filter.to_string();
+    let values: [(&str, Option<&BStr>, &str, &BStr); 4] = [
+        ("core", None, "repositoryFormatVersion", "1".into()),
+        ("extensions", None, "partialClone", remote_name),
+        ("remote", Some(remote_name), "promisor", "true".into()),
+        (
+            "remote",
+            Some(remote_name),
+            "partialCloneFilter",
+            filter.as_str().into(),
+        ),
+    ];
+    let path = local_config_meta(repo).path.expect("local config with path set");
+    let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+    let mut local_config = gix_config::File::from_path_no_includes(path, gix_config::Source::Local)?;
+    let mut config = repo.config_snapshot_mut();
+    for (section, subsection, key, value) in values {
+        local_config
+            .set_raw_value_by(section, subsection, key, value)
+            .expect("statically known keys and validated remote names");
+        config
+            .set_raw_value_by(section, subsection, key, value)
+            .expect("statically known keys and validated remote names");
+    }
+    local_config.write_to(&mut lock)?;
+    lock.commit().map_err(|err| err.error)?;
+    config.commit()?;
+    Ok(())
+}
+
 /// HEAD cannot be written by means of refspec by design, so we have to do it manually here. Also create the pointed-to ref
 /// if we have to, as it might not have been naturally included in the ref-specs.
 /// Lastly, use `ref_name` if it was provided instead, and let `HEAD` point to it.
@@ -100,6 +137,7 @@ pub fn update_head(
     let reflog_message = || LogChange {
         mode: RefLog::AndReference,
         force_create_reflog: false,
+        log_symbolic_changes: false,
         message: reflog_message.to_owned(),
     };
     match head_ref {
@@ -108,7 +146,8 @@ pub fn update_head(
                 head_ref_name: referent.to_owned(),
                 source: err,
             })?;
-            repo.refs
+            let transaction = repo
+                .refs
                 .transaction()
                 .packed_refs(gix_ref::file::transaction::PackedRefs::DeletionsAndNonSymbolicUpdates(
                     Box::new(&repo.objects),
@@ -140,13 +179,8 @@ pub fn update_head(
                     gix_lock::acquire::Fail::Immediately,
                     gix_lock::acquire::Fail::Immediately,
                 )
-                .map_err(crate::reference::edit::Error::from)?
-                .commit(
-                    repo.committer()
-                        .transpose()
-                        .map_err(|err| Error::HeadUpdate(crate::reference::edit::Error::ParseCommitterTime(err)))?,
-                )
                 .map_err(crate::reference::edit::Error::from)?;
+            repo.commit_ref_transaction(transaction)?;
 
             if let Some(head_peeled_id) = head_peeled_id {
                 let mut log = reflog_message();
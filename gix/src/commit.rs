@@ -4,6 +4,13 @@
 /// An empty array of a type usable with the `gix::easy` API to help declaring no parents should be used
 pub const NO_PARENT_IDS: [gix_hash::ObjectId; 0] = [];
 
+/// Options for use in [`Repository::commit_index()`](crate::Repository::commit_index()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If `true`, the `pre-commit` and `commit-msg` hooks aren't run, like `git commit --no-verify`.
+    pub no_verify: bool,
+}
+
 /// The error returned by [`commit(…)`][crate::Repository::commit()].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -20,6 +27,23 @@ pub enum Error {
     WriteObject(#[from] crate::object::write::Error),
     #[error(transparent)]
     ReferenceEdit(#[from] crate::reference::edit::Error),
+    #[cfg(feature = "gpg")]
+    #[error(transparent)]
+    Signer(#[from] crate::repository::signer::Error),
+    #[cfg(feature = "gpg")]
+    #[error("Could not sign the commit")]
+    Sign(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[cfg(feature = "hooks")]
+    #[error(transparent)]
+    Hook(#[from] crate::hook::Error),
+    #[cfg(all(feature = "tree-editor", feature = "index"))]
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[cfg(all(feature = "tree-editor", feature = "index"))]
+    #[error(transparent)]
+    WriteTree(#[from] crate::repository::write_tree_from_index::Error),
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
 }
 
 ///
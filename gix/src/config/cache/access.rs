@@ -330,6 +330,7 @@ impl Cache {
             destination_is_initially_empty: false,
             overwrite_existing: false,
             keep_going: false,
+            sparse_checkout: None,
             stat_options: self.stat_options().map_err(|err| match err {
                 config::stat_options::Error::ConfigCheckStat(err) => {
                     config::checkout_options::Error::ConfigCheckStat(err)
@@ -339,6 +340,15 @@ impl Cache {
         })
     }
 
+    /// Return the path to the user-wide excludes file as configured in `core.excludesFile`, or its default location.
+    #[cfg(feature = "excludes")]
+    pub(crate) fn global_excludes_file(&self) -> Result<Option<PathBuf>, config::exclude_stack::Error> {
+        Ok(match self.excludes_file().transpose()? {
+            Some(user_path) => Some(user_path),
+            None => self.xdg_config_path("ignore")?,
+        })
+    }
+
     #[cfg(feature = "excludes")]
     pub(crate) fn assemble_exclude_globals(
         &self,
@@ -347,13 +357,9 @@ impl Cache {
         source: gix_worktree::stack::state::ignore::Source,
         buf: &mut Vec<u8>,
     ) -> Result<gix_worktree::stack::state::Ignore, config::exclude_stack::Error> {
-        let excludes_file = match self.excludes_file().transpose()? {
-            Some(user_path) => Some(user_path),
-            None => self.xdg_config_path("ignore")?,
-        };
         Ok(gix_worktree::stack::state::Ignore::new(
             overrides.unwrap_or_default(),
-            gix_ignore::Search::from_git_dir(git_dir, excludes_file, buf)?,
+            gix_ignore::Search::from_git_dir(git_dir, self.global_excludes_file()?, buf)?,
             None,
             source,
         ))
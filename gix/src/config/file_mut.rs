@@ -0,0 +1,216 @@
+#![allow(clippy::result_large_err)]
+use std::{
+    fmt::{Debug, Formatter},
+    ops::{Deref, DerefMut},
+    path::Path,
+};
+
+use gix_features::threading::OwnShared;
+
+use crate::{
+    bstr::{BStr, BString},
+    config::{remove_value, set_value, tree::SubSectionRequirement, FileMut},
+    Repository,
+};
+
+/// The error returned by [`Repository::config_file_mut()`](crate::Repository::config_file_mut()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The worktree configuration can only be edited if 'extensions.worktreeConfig' is enabled")]
+    WorktreeConfigDisabled,
+    #[error("The location of the global configuration file could not be determined")]
+    GlobalPathUnknown,
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    Lock(#[from] gix_lock::acquire::Error),
+    #[error("Could not read configuration file at '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Parse(#[from] gix_config::file::init::Error),
+}
+
+///
+pub mod commit {
+    /// The error returned by [`FileMut::commit()`](crate::config::FileMut::commit()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not write configuration file at '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        Apply(#[from] crate::config::Error),
+    }
+}
+
+/// Editing
+impl FileMut<'_> {
+    /// Set the value at `key` to `new_value`, possibly creating the section if it doesn't exist yet, or overriding the most
+    /// recent existing value, which will be returned.
+    pub fn set_value<'b>(
+        &mut self,
+        key: &'static dyn crate::config::tree::Key,
+        new_value: impl Into<&'b BStr>,
+    ) -> Result<Option<BString>, set_value::Error> {
+        set_value::set(&mut self.file, key, None, new_value.into())
+    }
+
+    /// Set the value at `key` to `new_value` in the given `subsection`, possibly creating the section and sub-section if it
+    /// doesn't exist yet, or overriding the most recent existing value, which will be returned.
+    pub fn set_subsection_value<'a, 'b>(
+        &mut self,
+        key: &'static dyn crate::config::tree::Key,
+        subsection: impl Into<&'a BStr>,
+        new_value: impl Into<&'b BStr>,
+    ) -> Result<Option<BString>, set_value::Error> {
+        set_value::set(&mut self.file, key, Some(subsection.into()), new_value.into())
+    }
+
+    /// Remove all values at `key`, like `git config --unset-all`, and return the most recent one, or `None` if there was
+    /// no such value.
+    ///
+    /// Sections that become empty are kept.
+    pub fn remove_value(
+        &mut self,
+        key: &'static dyn crate::config::tree::Key,
+    ) -> Result<Option<BString>, remove_value::Error> {
+        self.remove_value_inner(key, None)
+    }
+
+    /// Remove all values at `key` in the given `subsection`, like `git config --unset-all`, and return the most recent one,
+    /// or `None` if there was no such value.
+    ///
+    /// Sections that become empty are kept.
+    pub fn remove_subsection_value<'a>(
+        &mut self,
+        key: &'static dyn crate::config::tree::Key,
+        subsection: impl Into<&'a BStr>,
+    ) -> Result<Option<BString>, remove_value::Error> {
+        self.remove_value_inner(key, Some(subsection.into()))
+    }
+
+    fn remove_value_inner(
+        &mut self,
+        key: &'static dyn crate::config::tree::Key,
+        subsection: Option<&BStr>,
+    ) -> Result<Option<BString>, remove_value::Error> {
+        match (key.subsection_requirement(), subsection) {
+            (Some(SubSectionRequirement::Parameter(_)), None) => return Err(remove_value::Error::SubSectionRequired),
+            (Some(SubSectionRequirement::Never), Some(_)) => return Err(remove_value::Error::SubSectionForbidden),
+            _ => {}
+        }
+        let (section, subsection, name) = crate::config::key_location(key, subsection);
+        let ids: Vec<_> = self
+            .file
+            .sections_and_ids_by_name(section)
+            .into_iter()
+            .flatten()
+            .filter_map(|(section, id)| (section.header().subsection_name() == subsection).then_some(id))
+            .collect();
+        let mut previous = None;
+        for id in ids {
+            let mut section = self.file.section_mut_by_id(id).expect("id was just obtained");
+            // Values are removed from the last one, so the first one is the most recent in this section.
+            if let Some(value) = section.remove(name) {
+                previous = Some(value.into_owned());
+                while section.remove(name).is_some() {}
+            }
+        }
+        Ok(previous)
+    }
+}
+
+/// Lifecycle
+impl<'repo> FileMut<'repo> {
+    /// Write all changes to disk and apply them to the configuration of the parent repository, which is returned.
+    ///
+    /// The changes are validated before they are written, so nothing is written if the new configuration isn't valid.
+    /// Note that files included by the edited file aren't read again.
+    pub fn commit(self) -> Result<&'repo mut Repository, commit::Error> {
+        let FileMut { repo, file, mut lock } = self;
+        let path = lock.resource_path();
+        let previous = OwnShared::clone(&repo.config.resolved);
+        let permissions = &repo.options.permissions.config;
+        let is_loaded = match file.meta().source {
+            gix_config::Source::User => permissions.user,
+            gix_config::Source::Git => permissions.git,
+            _ => true,
+        };
+        if is_loaded {
+            let config = replace_sections(&previous, &file, &path);
+            repo.reread_values_and_clear_caches_replacing_config(config.into())?;
+        }
+
+        let io_err = |source| commit::Error::Io {
+            path: path.clone(),
+            source,
+        };
+        let res = file
+            .write_to(&mut lock)
+            .map_err(io_err)
+            .and_then(|()| lock.commit().map(|_| ()).map_err(|err| io_err(err.error)));
+        if let Err(err) = res {
+            if is_loaded {
+                repo.reread_values_and_clear_caches_replacing_config(previous)
+                    .expect("the previous configuration was valid");
+            }
+            return Err(err);
+        }
+        Ok(repo)
+    }
+}
+
+/// Return a copy of `config` with all sections of the file at `path` replaced by the sections of `file`, which are placed
+/// where the previous ones were, or according to the precedence of their source if there were none.
+fn replace_sections(
+    config: &gix_config::File<'static>,
+    file: &gix_config::File<'static>,
+    path: &Path,
+) -> gix_config::File<'static> {
+    let source = file.meta().source;
+    let mut out = gix_config::File::new(config.meta_owned());
+    let mut new_sections = Some(file.sections());
+    for section in config.sections() {
+        let meta = section.meta();
+        let is_replaced = meta.source == source && meta.path.as_deref() == Some(path);
+        if is_replaced || meta.source > source {
+            for section in new_sections.take().into_iter().flatten() {
+                out.push_section(section.clone());
+            }
+        }
+        if !is_replaced {
+            out.push_section(section.clone());
+        }
+    }
+    for section in new_sections.into_iter().flatten() {
+        out.push_section(section.clone());
+    }
+    out
+}
+
+impl Debug for FileMut<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.file.to_string())
+    }
+}
+
+impl Deref for FileMut<'_> {
+    type Target = gix_config::File<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.file
+    }
+}
+
+impl DerefMut for FileMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.file
+    }
+}
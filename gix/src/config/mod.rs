@@ -8,12 +8,18 @@ mod snapshot;
 #[cfg(feature = "credentials")]
 pub use snapshot::credential_helpers;
 
+///
+pub mod file_mut;
+
 ///
 pub mod overrides;
 
 pub mod tree;
 pub use tree::root::Tree;
 
+#[cfg(feature = "credentials")]
+pub(crate) mod url_match;
+
 /// A platform to access configuration values as read from disk.
 ///
 /// Note that these values won't update even if the underlying file(s) change.
@@ -27,14 +33,42 @@ pub struct Snapshot<'repo> {
 ///
 /// Note that these values won't update even if the underlying file(s) change.
 ///
-/// Use [`forget()`][Self::forget()] to not apply any of the changes.
-// TODO: make it possible to load snapshots with reloading via .config() and write mutated snapshots back to disk which should be the way
-//       to affect all instances of a repo, probably via `config_mut()` and `config_mut_at()`.
+/// Use [`forget()`][Self::forget()] to not apply any of the changes, and [`Repository::config_file_mut()`] to write changes
+/// to disk.
+// TODO: make it possible to load snapshots with reloading via .config().
 pub struct SnapshotMut<'repo> {
     pub(crate) repo: Option<&'repo mut Repository>,
     pub(crate) config: gix_config::File<'static>,
 }
 
+/// The configuration file to edit with [`Repository::config_file_mut()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// The configuration file of the repository, `$GIT_COMMON_DIR/config`, like `git config --local`.
+    Local,
+    /// The configuration file of the current worktree, `$GIT_DIR/config.worktree`, like `git config --worktree`.
+    ///
+    /// It's only used by `git` if `extensions.worktreeConfig` is enabled, which is why it's an error to edit it otherwise.
+    Worktree,
+    /// The configuration file of the user, like `git config --global`.
+    ///
+    /// This is `~/.gitconfig`, unless it doesn't exist but `$XDG_CONFIG_HOME/git/config` does, or `GIT_CONFIG_GLOBAL` if set.
+    Global,
+}
+
+/// A platform to edit a single configuration file on disk without losing comments, whitespace or the order of values,
+/// created by [`Repository::config_file_mut()`].
+///
+/// The file is locked until the changes are [committed](Self::commit()), which writes them to disk and applies them to the
+/// configuration of the parent repository. Dropping this instance discards all changes instead.
+///
+/// All methods of [`gix_config::File`] are available for editing, including those to remove or rename sections.
+pub struct FileMut<'repo> {
+    pub(crate) repo: &'repo mut Repository,
+    pub(crate) file: gix_config::File<'static>,
+    pub(crate) lock: gix_lock::File,
+}
+
 /// A utility structure created by [`SnapshotMut::commit_auto_rollback()`] that restores the previous configuration on drop.
 pub struct CommitAutoRollback<'repo> {
     pub(crate) repo: Option<&'repo mut Repository>,
@@ -65,6 +99,51 @@ pub mod set_value {
         #[error("The key must not be used with a subsection")]
         SubSectionForbidden,
     }
+
+    /// Set the value at `key` in `subsection` to `value` in `config`, and return the previous value.
+    pub(crate) fn set(
+        config: &mut gix_config::File<'static>,
+        key: &'static dyn crate::config::tree::Key,
+        subsection: Option<&crate::bstr::BStr>,
+        value: &crate::bstr::BStr,
+    ) -> Result<Option<crate::bstr::BString>, Error> {
+        use crate::config::tree::SubSectionRequirement;
+        match (key.subsection_requirement(), subsection) {
+            (Some(SubSectionRequirement::Parameter(_)), None) => return Err(Error::SubSectionRequired),
+            (Some(SubSectionRequirement::Never), Some(_)) => return Err(Error::SubSectionForbidden),
+            _ => {}
+        }
+        key.validate(value)?;
+        let (section, subsection, name) = super::key_location(key, subsection);
+        let current = config.set_raw_value_by(section, subsection, name.to_owned(), value)?;
+        Ok(current.map(std::borrow::Cow::into_owned))
+    }
+}
+
+///
+pub mod remove_value {
+    /// The error produced when calling [`FileMut::remove(_subsection)?_value()`][crate::config::FileMut::remove_value()]
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The key needs a subsection parameter to be valid.")]
+        SubSectionRequired,
+        #[error("The key must not be used with a subsection")]
+        SubSectionForbidden,
+    }
+}
+
+/// Return the section name, subsection name and value name of `key` in `subsection`, which must match the subsection
+/// requirements of `key`.
+pub(crate) fn key_location<'a>(
+    key: &'a dyn tree::Key,
+    subsection: Option<&'a crate::bstr::BStr>,
+) -> (&'a str, Option<&'a crate::bstr::BStr>, &'a str) {
+    let section = key.section();
+    match section.parent() {
+        Some(parent) => (parent.name(), Some(section.name().into()), key.name()),
+        None => (section.name(), subsection, key.name()),
+    }
 }
 
 /// The error returned when failing to initialize the repository configuration.
@@ -415,6 +494,12 @@ pub mod remote {
         /// The error produced when failing to produce a symbolic remote name from configuration.
         pub type Error = super::super::key::Error<crate::remote::name::Error, 'v', 'i'>;
     }
+
+    ///
+    pub mod partial_clone_filter {
+        /// The error produced when failing to parse an object filter specification from configuration.
+        pub type Error = super::super::key::Error<crate::remote::fetch::filter::parse::Error, 'v', 'i'>;
+    }
 }
 
 ///
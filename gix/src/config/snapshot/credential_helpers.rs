@@ -54,6 +54,7 @@ pub(super) mod function {
     use crate::config::credential_helpers::Error;
     use crate::config::tree::gitoxide::Credentials;
     use crate::config::tree::{credential, Core, Credential};
+    use crate::config::url_match::host_matches;
     use std::borrow::Cow;
 
     /// Returns the configuration for all git-credential helpers from trusted configuration that apply
@@ -85,7 +86,6 @@ pub(super) mod function {
     /// - Upper-case scheme and host will be lower-cased automatically when parsing into a url, so prompts differ compared to git.
     /// - A **difference in prompt might affect the matching of getting existing stored credentials**, and it's a question of this being
     ///   a feature or a bug.
-    // TODO: when dealing with `http.*.*` configuration, generalize this algorithm as needed and support precedence.
     pub fn credential_helpers(
         mut url: gix_url::Url,
         config: &gix_config::File<'_>,
@@ -215,23 +215,6 @@ pub(super) mod function {
         ))
     }
 
-    fn host_matches(pattern: Option<&str>, host: Option<&str>) -> bool {
-        match (pattern, host) {
-            (Some(pattern), Some(host)) => {
-                let lfields = pattern.split('.');
-                let rfields = host.split('.');
-                if lfields.clone().count() != rfields.clone().count() {
-                    return false;
-                }
-                lfields.zip(rfields).all(|(pat, value)| {
-                    gix_glob::wildmatch(pat.into(), value.into(), gix_glob::wildmatch::Mode::empty())
-                })
-            }
-            (None, None) => true,
-            (Some(_), None) | (None, Some(_)) => false,
-        }
-    }
-
     fn normalize(url: &mut gix_url::Url) {
         if !url.path_is_root() && url.path.ends_with(b"/") {
             url.path.pop();
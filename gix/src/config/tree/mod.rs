@@ -16,6 +16,12 @@ pub(crate) mod root {
     pub struct Tree;
 
     impl Tree {
+        /// The `apply` section.
+        #[cfg(feature = "apply")]
+        pub const APPLY: sections::Apply = sections::Apply;
+        /// The `am` section.
+        #[cfg(feature = "apply")]
+        pub const AM: sections::Am = sections::Am;
         /// The `author` section.
         pub const AUTHOR: sections::Author = sections::Author;
         /// The `branch` section.
@@ -24,6 +30,11 @@ pub(crate) mod root {
         pub const CHECKOUT: sections::Checkout = sections::Checkout;
         /// The `clone` section.
         pub const CLONE: sections::Clone = sections::Clone;
+        /// The `commit` section.
+        #[cfg(feature = "gpg")]
+        pub const COMMIT: sections::Commit = sections::Commit;
+        /// The `commitGraph` section.
+        pub const COMMIT_GRAPH: sections::CommitGraph = sections::CommitGraph;
         /// The `committer` section.
         pub const COMMITTER: sections::Committer = sections::Committer;
         /// The `core` section.
@@ -37,14 +48,31 @@ pub(crate) mod root {
         pub const EXTENSIONS: sections::Extensions = sections::Extensions;
         /// The `fetch` section.
         pub const FETCH: sections::Fetch = sections::Fetch;
+        /// The `format` section.
+        #[cfg(feature = "apply")]
+        pub const FORMAT: sections::Format = sections::Format;
+        /// The `fsck` section.
+        #[cfg(feature = "fsck")]
+        pub const FSCK: sections::Fsck = sections::Fsck;
+        /// The `gc` section.
+        pub const GC: sections::Gc = sections::Gc;
         /// The `gitoxide` section.
         pub const GITOXIDE: sections::Gitoxide = sections::Gitoxide;
+        /// The `gpg` section.
+        #[cfg(feature = "gpg")]
+        pub const GPG: sections::Gpg = sections::Gpg;
         /// The `http` section.
         pub const HTTP: sections::Http = sections::Http;
+        /// The `i18n` section.
+        #[cfg(feature = "apply")]
+        pub const I18N: sections::I18n = sections::I18n;
         /// The `index` section.
         pub const INDEX: sections::Index = sections::Index;
         /// The `init` section.
         pub const INIT: sections::Init = sections::Init;
+        /// The `mailinfo` section.
+        #[cfg(feature = "apply")]
+        pub const MAILINFO: sections::Mailinfo = sections::Mailinfo;
         /// The `mailmap` section.
         pub const MAILMAP: sections::Mailmap = sections::Mailmap;
         /// The `pack` section.
@@ -62,6 +90,9 @@ pub(crate) mod root {
         /// The `status` section.
         #[cfg(feature = "status")]
         pub const STATUS: sections::Status = sections::Status;
+        /// The `tag` section.
+        #[cfg(feature = "gpg")]
+        pub const TAG: sections::Tag = sections::Tag;
         /// The `user` section.
         pub const USER: sections::User = sections::User;
         /// The `url` section.
@@ -70,10 +101,17 @@ pub(crate) mod root {
         /// List all available sections.
         pub fn sections(&self) -> &[&dyn Section] {
             &[
+                #[cfg(feature = "apply")]
+                &Self::APPLY,
+                #[cfg(feature = "apply")]
+                &Self::AM,
                 &Self::AUTHOR,
                 &Self::BRANCH,
                 &Self::CHECKOUT,
                 &Self::CLONE,
+                #[cfg(feature = "gpg")]
+                &Self::COMMIT,
+                &Self::COMMIT_GRAPH,
                 &Self::COMMITTER,
                 &Self::CORE,
                 &Self::CREDENTIAL,
@@ -81,10 +119,21 @@ pub(crate) mod root {
                 &Self::DIFF,
                 &Self::EXTENSIONS,
                 &Self::FETCH,
+                #[cfg(feature = "apply")]
+                &Self::FORMAT,
+                #[cfg(feature = "fsck")]
+                &Self::FSCK,
+                &Self::GC,
                 &Self::GITOXIDE,
+                #[cfg(feature = "gpg")]
+                &Self::GPG,
                 &Self::HTTP,
+                #[cfg(feature = "apply")]
+                &Self::I18N,
                 &Self::INDEX,
                 &Self::INIT,
+                #[cfg(feature = "apply")]
+                &Self::MAILINFO,
                 &Self::MAILMAP,
                 &Self::PACK,
                 &Self::PROTOCOL,
@@ -94,6 +143,8 @@ pub(crate) mod root {
                 &Self::SSH,
                 #[cfg(feature = "status")]
                 &Self::STATUS,
+                #[cfg(feature = "gpg")]
+                &Self::TAG,
                 &Self::USER,
                 &Self::URL,
             ]
@@ -102,15 +153,21 @@ pub(crate) mod root {
 }
 
 mod sections;
+#[cfg(feature = "fsck")]
+pub use sections::Fsck;
 pub use sections::{
-    branch, checkout, core, credential, extensions, fetch, gitoxide, http, index, protocol, push, remote, ssh, Author,
-    Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init, Mailmap,
-    Pack, Protocol, Push, Remote, Safe, Ssh, Url, User,
+    branch, checkout, core, credential, extensions, fetch, gc, gitoxide, http, index, protocol, push, remote, ssh,
+    Author, Branch, Checkout, Clone, CommitGraph, Committer, Core, Credential, Extensions, Fetch, Gc, Gitoxide, Http,
+    Index, Init, Mailmap, Pack, Protocol, Push, Remote, Safe, Ssh, Url, User,
 };
 #[cfg(feature = "blob-diff")]
 pub use sections::{diff, Diff};
+#[cfg(feature = "gpg")]
+pub use sections::{gpg, Commit, Gpg, Tag};
 #[cfg(feature = "status")]
 pub use sections::{status, Status};
+#[cfg(feature = "apply")]
+pub use sections::{Am, Apply, Format, I18n, Mailinfo};
 
 /// Generic value implementations for static instantiation.
 pub mod keys;
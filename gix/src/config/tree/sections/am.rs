@@ -0,0 +1,23 @@
+use crate::config::{
+    tree::{keys, Am, Key, Section},
+    Tree,
+};
+
+impl Am {
+    /// The `am.keepcr` key.
+    pub const KEEP_CR: keys::Boolean = keys::Boolean::new_boolean("keepcr", &Tree::AM);
+    /// The `am.threeWay` key.
+    pub const THREE_WAY: keys::Boolean = keys::Boolean::new_boolean("threeWay", &Tree::AM);
+    /// The `am.messageId` key.
+    pub const MESSAGE_ID: keys::Boolean = keys::Boolean::new_boolean("messageId", &Tree::AM);
+}
+
+impl Section for Am {
+    fn name(&self) -> &str {
+        "am"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::KEEP_CR, &Self::THREE_WAY, &Self::MESSAGE_ID]
+    }
+}
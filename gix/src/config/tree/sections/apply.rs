@@ -0,0 +1,21 @@
+use crate::config::{
+    tree::{keys, Apply, Key, Section},
+    Tree,
+};
+
+impl Apply {
+    /// The `apply.whitespace` key.
+    pub const WHITESPACE: keys::String = keys::String::new_string("whitespace", &Tree::APPLY);
+    /// The `apply.ignoreWhitespace` key.
+    pub const IGNORE_WHITESPACE: keys::String = keys::String::new_string("ignoreWhitespace", &Tree::APPLY);
+}
+
+impl Section for Apply {
+    fn name(&self) -> &str {
+        "apply"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::WHITESPACE, &Self::IGNORE_WHITESPACE]
+    }
+}
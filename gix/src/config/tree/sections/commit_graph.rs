@@ -0,0 +1,71 @@
+use crate::{
+    config,
+    config::tree::{keys, CommitGraph, Key, Section},
+};
+
+impl CommitGraph {
+    /// The `commitGraph.generationVersion` key.
+    pub const GENERATION_VERSION: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("generationVersion", &config::Tree::COMMIT_GRAPH);
+
+    /// The `commitGraph.changedPathsVersion` key.
+    pub const CHANGED_PATHS_VERSION: ChangedPathsVersion = ChangedPathsVersion::new_with_validate(
+        "changedPathsVersion",
+        &config::Tree::COMMIT_GRAPH,
+        validate::ChangedPathsVersion,
+    );
+}
+
+/// The `commitGraph.changedPathsVersion` key.
+pub type ChangedPathsVersion = keys::Any<validate::ChangedPathsVersion>;
+
+mod changed_paths_version {
+    use crate::{config, config::tree::sections::commit_graph::ChangedPathsVersion};
+
+    impl ChangedPathsVersion {
+        /// Try to interpret an integer value as version of the changed-path Bloom filters to write.
+        ///
+        /// `-1` yields `None` to indicate that the version of existing filters should be used, and `0` yields `Some(0)`
+        /// to indicate that no filters should be read or written.
+        pub fn try_into_changed_paths_version(
+            &'static self,
+            value: Result<i64, gix_config::value::Error>,
+        ) -> Result<Option<u32>, config::key::GenericError> {
+            let value = value.map_err(|err| config::key::GenericError::from(self).with_source(err))?;
+            Ok(match value {
+                -1 => None,
+                0 => Some(0),
+                1 => Some(1),
+                2 => Some(2),
+                _ => return Err(config::key::GenericError::from(self)),
+            })
+        }
+    }
+}
+
+impl Section for CommitGraph {
+    fn name(&self) -> &str {
+        "commitGraph"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::GENERATION_VERSION, &Self::CHANGED_PATHS_VERSION]
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct ChangedPathsVersion;
+    impl keys::Validate for ChangedPathsVersion {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::CommitGraph::CHANGED_PATHS_VERSION.try_into_changed_paths_version(
+                gix_config::Integer::try_from(value).and_then(|int| {
+                    int.to_decimal()
+                        .ok_or_else(|| gix_config::value::Error::new("integer out of range", value))
+                }),
+            )?;
+            Ok(())
+        }
+    }
+}
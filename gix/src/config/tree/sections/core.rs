@@ -24,8 +24,16 @@ impl Core {
         Disambiguate::new_with_validate("disambiguate", &config::Tree::CORE, validate::Disambiguate);
     /// The `core.editor` key.
     pub const EDITOR: keys::Program = keys::Program::new_program("editor", &config::Tree::CORE);
+    /// The `core.fsmonitor` key.
+    pub const FSMONITOR: keys::Any = keys::Any::new("fsmonitor", &config::Tree::CORE)
+        .with_note("a boolean to use the builtin daemon, or the path to a hook program to query for changes");
+    /// The `core.fsmonitorHookVersion` key.
+    pub const FSMONITOR_HOOK_VERSION: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("fsmonitorHookVersion", &config::Tree::CORE);
     /// The `core.fileMode` key.
     pub const FILE_MODE: keys::Boolean = keys::Boolean::new_boolean("fileMode", &config::Tree::CORE);
+    /// The `core.hooksPath` key.
+    pub const HOOKS_PATH: keys::Path = keys::Path::new_path("hooksPath", &config::Tree::CORE);
     /// The `core.ignoreCase` key.
     pub const IGNORE_CASE: keys::Boolean = keys::Boolean::new_boolean("ignoreCase", &config::Tree::CORE);
     /// The `core.filesRefLockTimeout` key.
@@ -51,10 +59,18 @@ impl Core {
     /// The `core.repositoryFormatVersion` key.
     pub const REPOSITORY_FORMAT_VERSION: keys::UnsignedInteger =
         keys::UnsignedInteger::new_unsigned_integer("repositoryFormatVersion", &config::Tree::CORE);
+    /// The `core.sparseCheckout` key.
+    pub const SPARSE_CHECKOUT: keys::Boolean = keys::Boolean::new_boolean("sparseCheckout", &config::Tree::CORE);
+    /// The `core.sparseCheckoutCone` key.
+    pub const SPARSE_CHECKOUT_CONE: keys::Boolean =
+        keys::Boolean::new_boolean("sparseCheckoutCone", &config::Tree::CORE);
     /// The `core.symlinks` key.
     pub const SYMLINKS: keys::Boolean = keys::Boolean::new_boolean("symlinks", &config::Tree::CORE);
     /// The `core.trustCTime` key.
     pub const TRUST_C_TIME: keys::Boolean = keys::Boolean::new_boolean("trustCTime", &config::Tree::CORE);
+    /// The `core.untrackedCache` key.
+    pub const UNTRACKED_CACHE: UntrackedCache =
+        UntrackedCache::new_with_validate("untrackedCache", &config::Tree::CORE, validate::UntrackedCache);
     /// The `core.worktree` key.
     pub const WORKTREE: keys::Any = keys::Any::new("worktree", &config::Tree::CORE)
         .with_environment_override("GIT_WORK_TREE")
@@ -77,6 +93,8 @@ impl Core {
         .with_environment_override("GIT_NO_REPLACE_OBJECTS");
     /// The `core.commitGraph` key.
     pub const COMMIT_GRAPH: keys::Boolean = keys::Boolean::new_boolean("commitGraph", &config::Tree::CORE);
+    /// The `core.whitespace` key.
+    pub const WHITESPACE: keys::String = keys::String::new_string("whitespace", &config::Tree::CORE);
     /// The `core.safecrlf` key.
     #[cfg(feature = "attributes")]
     pub const SAFE_CRLF: SafeCrlf = SafeCrlf::new_with_validate("safecrlf", &config::Tree::CORE, validate::SafeCrlf);
@@ -109,7 +127,10 @@ impl Section for Core {
             &Self::DELTA_BASE_CACHE_LIMIT,
             &Self::DISAMBIGUATE,
             &Self::EDITOR,
+            &Self::FSMONITOR,
+            &Self::FSMONITOR_HOOK_VERSION,
             &Self::FILE_MODE,
+            &Self::HOOKS_PATH,
             &Self::IGNORE_CASE,
             &Self::FILES_REF_LOCK_TIMEOUT,
             &Self::PACKED_REFS_TIMEOUT,
@@ -117,8 +138,11 @@ impl Section for Core {
             &Self::LOG_ALL_REF_UPDATES,
             &Self::PRECOMPOSE_UNICODE,
             &Self::REPOSITORY_FORMAT_VERSION,
+            &Self::SPARSE_CHECKOUT,
+            &Self::SPARSE_CHECKOUT_CONE,
             &Self::SYMLINKS,
             &Self::TRUST_C_TIME,
+            &Self::UNTRACKED_CACHE,
             &Self::WORKTREE,
             &Self::PROTECT_HFS,
             &Self::PROTECT_NTFS,
@@ -128,6 +152,7 @@ impl Section for Core {
             &Self::SSH_COMMAND,
             &Self::USE_REPLACE_REFS,
             &Self::COMMIT_GRAPH,
+            &Self::WHITESPACE,
             #[cfg(feature = "attributes")]
             &Self::SAFE_CRLF,
             #[cfg(feature = "attributes")]
@@ -143,6 +168,9 @@ impl Section for Core {
 /// The `core.checkStat` key.
 pub type CheckStat = keys::Any<validate::CheckStat>;
 
+/// The `core.untrackedCache` key.
+pub type UntrackedCache = keys::Any<validate::UntrackedCache>;
+
 /// The `core.abbrev` key.
 pub type Abbrev = keys::Any<validate::Abbrev>;
 
@@ -383,6 +411,29 @@ mod check_stat {
     }
 }
 
+mod untracked_cache {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::core::UntrackedCache};
+
+    impl UntrackedCache {
+        /// Returns `Some(true)` if the untracked cache should be added to the index, `Some(false)` if it should be removed,
+        /// or `None` if an existing cache should be kept and used.
+        pub fn try_into_untracked_cache(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<Option<bool>, config::key::GenericErrorWithValue> {
+            if value.as_ref() == "keep" {
+                return Ok(None);
+            }
+            let value = gix_config::Boolean::try_from(value.as_ref()).map_err(|err| {
+                config::key::GenericErrorWithValue::from_value(self, value.into_owned()).with_source(err)
+            })?;
+            Ok(Some(value.into()))
+        }
+    }
+}
+
 mod abbrev {
     use std::borrow::Cow;
 
@@ -480,6 +531,14 @@ mod validate {
         }
     }
 
+    pub struct UntrackedCache;
+    impl keys::Validate for UntrackedCache {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Core::UNTRACKED_CACHE.try_into_untracked_cache(value.into())?;
+            Ok(())
+        }
+    }
+
     pub struct Abbrev;
     impl keys::Validate for Abbrev {
         fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
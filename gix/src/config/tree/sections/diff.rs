@@ -32,6 +32,12 @@ impl Diff {
     pub const DRIVER_BINARY: Binary = Binary::new_with_validate("binary", &config::Tree::DIFF, validate::Binary)
         .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
 
+    /// The `diff.<driver>.wordRegex` key.
+    pub const DRIVER_WORD_REGEX: keys::String = keys::String::new_string("wordRegex", &config::Tree::DIFF)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
+    /// The `diff.wordRegex` key.
+    pub const WORD_REGEX: keys::String = keys::String::new_string("wordRegex", &config::Tree::DIFF);
+
     /// The `diff.external` key.
     pub const EXTERNAL: keys::Program =
         keys::Program::new_program("external", &config::Tree::DIFF).with_environment_override("GIT_EXTERNAL_DIFF");
@@ -51,6 +57,8 @@ impl Section for Diff {
             &Self::DRIVER_TEXTCONV,
             &Self::DRIVER_ALGORITHM,
             &Self::DRIVER_BINARY,
+            &Self::DRIVER_WORD_REGEX,
+            &Self::WORD_REGEX,
             &Self::EXTERNAL,
         ]
     }
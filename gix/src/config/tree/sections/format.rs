@@ -0,0 +1,30 @@
+use crate::config::{
+    tree::{keys, Format, Key, Section},
+    Tree,
+};
+
+impl Format {
+    /// The `format.subjectPrefix` key.
+    pub const SUBJECT_PREFIX: keys::String = keys::String::new_string("subjectPrefix", &Tree::FORMAT);
+    /// The `format.signature` key.
+    pub const SIGNATURE: keys::String = keys::String::new_string("signature", &Tree::FORMAT);
+    /// The `format.coverLetter` key.
+    pub const COVER_LETTER: keys::Boolean = keys::Boolean::new_boolean("coverLetter", &Tree::FORMAT);
+    /// The `format.numbered` key.
+    pub const NUMBERED: keys::Any = keys::Any::new("numbered", &Tree::FORMAT);
+}
+
+impl Section for Format {
+    fn name(&self) -> &str {
+        "format"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::SUBJECT_PREFIX,
+            &Self::SIGNATURE,
+            &Self::COVER_LETTER,
+            &Self::NUMBERED,
+        ]
+    }
+}
@@ -0,0 +1,22 @@
+use crate::config::{
+    tree::{keys, Fsck, Key, Section},
+    Tree,
+};
+
+impl Fsck {
+    /// The `fsck.skipList` key, a file with the ids of objects whose problems shouldn't be reported.
+    ///
+    /// Note that all other keys of this section, like `fsck.badDate`, configure the severity of the problem with the
+    /// respective identifier.
+    pub const SKIP_LIST: keys::Path = keys::Path::new_path("skipList", &Tree::FSCK);
+}
+
+impl Section for Fsck {
+    fn name(&self) -> &str {
+        "fsck"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::SKIP_LIST]
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{
+    config,
+    config::tree::{keys, Gc, Key, Section},
+};
+
+impl Gc {
+    /// The `gc.pruneExpire` key.
+    pub const PRUNE_EXPIRE: Expiry = Expiry::new_with_validate("pruneExpire", &config::Tree::GC, validate::Expiry);
+    /// The `gc.reflogExpire` key, which may also be used as `gc.<pattern>.reflogExpire` for references matching `<pattern>`.
+    pub const REFLOG_EXPIRE: Expiry = Expiry::new_with_validate("reflogExpire", &config::Tree::GC, validate::Expiry)
+        .with_subsection_requirement(None);
+    /// The `gc.reflogExpireUnreachable` key, which may also be used as `gc.<pattern>.reflogExpireUnreachable` for references
+    /// matching `<pattern>`.
+    pub const REFLOG_EXPIRE_UNREACHABLE: Expiry =
+        Expiry::new_with_validate("reflogExpireUnreachable", &config::Tree::GC, validate::Expiry)
+            .with_subsection_requirement(None);
+}
+
+/// A key for a point in time before which objects or entries expire, like `2.weeks.ago`, `now` or `never`.
+pub type Expiry = keys::Any<validate::Expiry>;
+
+mod expiry {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::sections::gc::Expiry,
+    };
+
+    impl Expiry {
+        /// Interpret `value` as the point in time in seconds since the Unix epoch before which objects or entries expire,
+        /// with `now` as reference for relative dates.
+        ///
+        /// Return `None` for `never` and `false`, which make sure nothing expires, while `now` and `all` let everything expire.
+        pub fn try_into_expiry(
+            &'static self,
+            value: Cow<'_, BStr>,
+            now: std::time::SystemTime,
+        ) -> Result<Option<gix_date::SecondsSinceUnixEpoch>, config::key::GenericErrorWithValue> {
+            Ok(match value.as_ref().as_bytes() {
+                b"never" | b"false" => None,
+                b"now" | b"all" => Some(gix_date::SecondsSinceUnixEpoch::MAX),
+                _ => Some(
+                    value
+                        .to_str()
+                        .ok()
+                        .and_then(|date| gix_date::parse(date, Some(now)).ok())
+                        .ok_or_else(|| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))?
+                        .seconds,
+                ),
+            })
+        }
+    }
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::PRUNE_EXPIRE,
+            &Self::REFLOG_EXPIRE,
+            &Self::REFLOG_EXPIRE_UNREACHABLE,
+        ]
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Expiry;
+    impl keys::Validate for Expiry {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Gc::PRUNE_EXPIRE.try_into_expiry(value.into(), std::time::SystemTime::now())?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{
+    config,
+    config::tree::{keys, Gpg, Key, Section},
+};
+
+impl Gpg {
+    /// The `gpg.program` key.
+    pub const PROGRAM: keys::Program = keys::Program::new_program("program", &config::Tree::GPG);
+    /// The `gpg.format` key.
+    pub const FORMAT: Format = Format::new_with_validate("format", &config::Tree::GPG, validate::Format);
+    /// The `gpg.minTrustLevel` key.
+    pub const MIN_TRUST_LEVEL: MinTrustLevel =
+        MinTrustLevel::new_with_validate("minTrustLevel", &config::Tree::GPG, validate::MinTrustLevel);
+
+    /// The `gpg.openpgp` section.
+    pub const OPENPGP: OpenPgp = OpenPgp;
+    /// The `gpg.x509` section.
+    pub const X509: X509 = X509;
+    /// The `gpg.ssh` section.
+    pub const SSH: Ssh = Ssh;
+}
+
+impl Section for Gpg {
+    fn name(&self) -> &str {
+        "gpg"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::PROGRAM, &Self::FORMAT, &Self::MIN_TRUST_LEVEL]
+    }
+
+    fn sub_sections(&self) -> &[&dyn Section] {
+        &[&Self::OPENPGP, &Self::X509, &Self::SSH]
+    }
+}
+
+/// The `gpg.format` key.
+pub type Format = keys::Any<validate::Format>;
+
+/// The `gpg.minTrustLevel` key.
+pub type MinTrustLevel = keys::Any<validate::MinTrustLevel>;
+
+mod format {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::sections::gpg::Format};
+
+    impl Format {
+        /// Convert `value` into the format of signatures to create.
+        pub fn try_into_format(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_gpg::Format, config::key::GenericErrorWithValue> {
+            let name: &[u8] = value.as_ref().as_ref();
+            Ok(match name {
+                b"openpgp" => gix_gpg::Format::OpenPgp,
+                b"x509" => gix_gpg::Format::X509,
+                b"ssh" => gix_gpg::Format::Ssh,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+}
+
+mod min_trust_level {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::sections::gpg::MinTrustLevel};
+
+    impl MinTrustLevel {
+        /// Convert `value` into the trust level that keys of signatures need at least to be considered good.
+        pub fn try_into_trust(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_gpg::Trust, config::key::GenericErrorWithValue> {
+            gix_gpg::Trust::from_bytes(value.as_ref())
+                .ok_or_else(|| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
+mod subsections {
+    use crate::config::{
+        tree::{keys, Gpg, Key, Section},
+        Tree,
+    };
+
+    /// The `gpg.openpgp` sub-section.
+    #[derive(Copy, Clone, Default)]
+    pub struct OpenPgp;
+
+    impl OpenPgp {
+        /// The `gpg.openpgp.program` key.
+        pub const PROGRAM: keys::Program = keys::Program::new_program("program", &Gpg::OPENPGP);
+    }
+
+    impl Section for OpenPgp {
+        fn name(&self) -> &str {
+            "openpgp"
+        }
+
+        fn keys(&self) -> &[&dyn Key] {
+            &[&Self::PROGRAM]
+        }
+
+        fn parent(&self) -> Option<&dyn Section> {
+            Some(&Tree::GPG)
+        }
+    }
+
+    /// The `gpg.x509` sub-section.
+    #[derive(Copy, Clone, Default)]
+    pub struct X509;
+
+    impl X509 {
+        /// The `gpg.x509.program` key.
+        pub const PROGRAM: keys::Program = keys::Program::new_program("program", &Gpg::X509);
+    }
+
+    impl Section for X509 {
+        fn name(&self) -> &str {
+            "x509"
+        }
+
+        fn keys(&self) -> &[&dyn Key] {
+            &[&Self::PROGRAM]
+        }
+
+        fn parent(&self) -> Option<&dyn Section> {
+            Some(&Tree::GPG)
+        }
+    }
+
+    /// The `gpg.ssh` sub-section.
+    #[derive(Copy, Clone, Default)]
+    pub struct Ssh;
+
+    impl Ssh {
+        /// The `gpg.ssh.program` key.
+        pub const PROGRAM: keys::Program = keys::Program::new_program("program", &Gpg::SSH)
+            .with_note("Only used for signing, as SSH signatures are verified without running a program");
+        /// The `gpg.ssh.allowedSignersFile` key.
+        pub const ALLOWED_SIGNERS_FILE: keys::Path = keys::Path::new_path("allowedSignersFile", &Gpg::SSH);
+        /// The `gpg.ssh.revocationFile` key.
+        pub const REVOCATION_FILE: keys::Path = keys::Path::new_path("revocationFile", &Gpg::SSH)
+            .with_note("Only files with one public key per line are supported, but not key revocation lists");
+    }
+
+    impl Section for Ssh {
+        fn name(&self) -> &str {
+            "ssh"
+        }
+
+        fn keys(&self) -> &[&dyn Key] {
+            &[&Self::PROGRAM, &Self::ALLOWED_SIGNERS_FILE, &Self::REVOCATION_FILE]
+        }
+
+        fn parent(&self) -> Option<&dyn Section> {
+            Some(&Tree::GPG)
+        }
+    }
+}
+pub use subsections::{OpenPgp, Ssh, X509};
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Gpg},
+    };
+
+    pub struct Format;
+    impl keys::Validate for Format {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Gpg::FORMAT.try_into_format(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct MinTrustLevel;
+    impl keys::Validate for MinTrustLevel {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Gpg::MIN_TRUST_LEVEL.try_into_trust(value.into())?;
+            Ok(())
+        }
+    }
+}
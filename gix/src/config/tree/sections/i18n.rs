@@ -0,0 +1,21 @@
+use crate::config::{
+    tree::{keys, I18n, Key, Section},
+    Tree,
+};
+
+impl I18n {
+    /// The `i18n.commitEncoding` key.
+    pub const COMMIT_ENCODING: keys::String = keys::String::new_string("commitEncoding", &Tree::I18N);
+    /// The `i18n.logOutputEncoding` key.
+    pub const LOG_OUTPUT_ENCODING: keys::String = keys::String::new_string("logOutputEncoding", &Tree::I18N);
+}
+
+impl Section for I18n {
+    fn name(&self) -> &str {
+        "i18n"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::COMMIT_ENCODING, &Self::LOG_OUTPUT_ENCODING]
+    }
+}
@@ -0,0 +1,19 @@
+use crate::config::{
+    tree::{keys, Key, Mailinfo, Section},
+    Tree,
+};
+
+impl Mailinfo {
+    /// The `mailinfo.scissors` key.
+    pub const SCISSORS: keys::Boolean = keys::Boolean::new_boolean("scissors", &Tree::MAILINFO);
+}
+
+impl Section for Mailinfo {
+    fn name(&self) -> &str {
+        "mailinfo"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::SCISSORS]
+    }
+}
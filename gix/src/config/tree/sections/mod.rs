@@ -1,5 +1,19 @@
 #![allow(missing_docs)]
 
+/// The `apply` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "apply")]
+pub struct Apply;
+#[cfg(feature = "apply")]
+mod apply;
+
+/// The `am` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "apply")]
+pub struct Am;
+#[cfg(feature = "apply")]
+mod am;
+
 /// The `author` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Author;
@@ -20,6 +34,18 @@ pub mod checkout;
 pub struct Clone;
 mod clone;
 
+/// The `commit` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "gpg")]
+pub struct Commit;
+#[cfg(feature = "gpg")]
+mod commit;
+
+/// The `commitGraph` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct CommitGraph;
+pub mod commit_graph;
+
 /// The `committer` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Committer;
@@ -52,16 +78,49 @@ pub mod extensions;
 pub struct Fetch;
 pub mod fetch;
 
+/// The `format` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "apply")]
+pub struct Format;
+#[cfg(feature = "apply")]
+mod format;
+
+/// The `fsck` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "fsck")]
+pub struct Fsck;
+#[cfg(feature = "fsck")]
+mod fsck;
+
+/// The `gc` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gc;
+pub mod gc;
+
 /// The `gitoxide` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Gitoxide;
 pub mod gitoxide;
 
+/// The `gpg` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "gpg")]
+pub struct Gpg;
+#[cfg(feature = "gpg")]
+pub mod gpg;
+
 /// The `http` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Http;
 pub mod http;
 
+/// The `i18n` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "apply")]
+pub struct I18n;
+#[cfg(feature = "apply")]
+mod i18n;
+
 /// The `index` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Index;
@@ -72,6 +131,13 @@ pub mod index;
 pub struct Init;
 mod init;
 
+/// The `mailinfo` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "apply")]
+pub struct Mailinfo;
+#[cfg(feature = "apply")]
+mod mailinfo;
+
 #[derive(Copy, Clone, Default)]
 pub struct Mailmap;
 mod mailmap;
@@ -113,6 +179,13 @@ pub struct Status;
 #[cfg(feature = "status")]
 pub mod status;
 
+/// The `tag` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "gpg")]
+pub struct Tag;
+#[cfg(feature = "gpg")]
+mod tag;
+
 /// The `user` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct User;
@@ -31,6 +31,16 @@ impl Remote {
         http::ProxyAuthMethod::new_proxy_auth_method("proxyAuthMethod", &config::Tree::REMOTE)
             .with_subsection_requirement(NAME_PARAMETER)
             .with_deviation("implemented like git, but never actually tried");
+    /// The `remote.<name>.promisor` key
+    pub const PROMISOR: keys::Boolean =
+        keys::Boolean::new_boolean("promisor", &config::Tree::REMOTE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `remote.<name>.partialCloneFilter` key
+    pub const PARTIAL_CLONE_FILTER: PartialCloneFilter = PartialCloneFilter::new_with_validate(
+        "partialCloneFilter",
+        &config::Tree::REMOTE,
+        validate::PartialCloneFilter,
+    )
+    .with_subsection_requirement(NAME_PARAMETER);
 }
 
 impl Section for Remote {
@@ -48,6 +58,8 @@ impl Section for Remote {
             &Self::PUSH,
             &Self::PROXY,
             &Self::PROXY_AUTH_METHOD,
+            &Self::PROMISOR,
+            &Self::PARTIAL_CLONE_FILTER,
         ]
     }
 }
@@ -86,6 +98,27 @@ mod tag_opts {
     }
 }
 
+/// The `remote.<name>.partialCloneFilter` key type.
+pub type PartialCloneFilter = keys::Any<validate::PartialCloneFilter>;
+
+mod partial_clone_filter {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::remote::PartialCloneFilter, remote};
+
+    impl PartialCloneFilter {
+        /// Try to interpret `value` as object filter specification, like `blob:none`.
+        pub fn try_into_filter(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<remote::fetch::Filter, config::remote::partial_clone_filter::Error> {
+            remote::fetch::Filter::from_bytes(value.as_ref()).map_err(|err| {
+                config::remote::partial_clone_filter::Error::from_value(self, value.into_owned()).with_source(err)
+            })
+        }
+    }
+}
+
 pub mod validate {
     use std::{borrow::Cow, error::Error};
 
@@ -98,4 +131,12 @@ pub mod validate {
             Ok(())
         }
     }
+
+    pub struct PartialCloneFilter;
+    impl Validate for PartialCloneFilter {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+            super::Remote::PARTIAL_CLONE_FILTER.try_into_filter(Cow::Borrowed(value))?;
+            Ok(())
+        }
+    }
 }
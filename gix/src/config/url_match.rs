@@ -0,0 +1,195 @@
+//! Match URLs against the `<url>` in `<section>.<url>.<key>` configuration like `git` does, with the precedence rules
+//! described in `git help config` for `http.<url>.*`.
+
+/// Return `true` if the host `pattern` matches `host`, with each of the dot-separated components of `pattern` possibly
+/// containing wildcards.
+pub(crate) fn host_matches(pattern: Option<&str>, host: Option<&str>) -> bool {
+    match (pattern, host) {
+        (Some(pattern), Some(host)) => {
+            let lfields = pattern.split('.');
+            let rfields = host.split('.');
+            if lfields.clone().count() != rfields.clone().count() {
+                return false;
+            }
+            lfields
+                .zip(rfields)
+                .all(|(pat, value)| gix_glob::wildmatch(pat.into(), value.into(), gix_glob::wildmatch::Mode::empty()))
+        }
+        (None, None) => true,
+        (Some(_), None) | (None, Some(_)) => false,
+    }
+}
+
+#[cfg(any(
+    feature = "blocking-http-transport-reqwest",
+    feature = "blocking-http-transport-curl"
+))]
+pub(crate) use values::Values;
+
+#[cfg(any(
+    feature = "blocking-http-transport-reqwest",
+    feature = "blocking-http-transport-curl"
+))]
+mod values {
+    use std::{borrow::Cow, collections::BTreeMap};
+
+    use super::host_matches;
+    use crate::bstr::BStr;
+
+    /// Describes how specifically a URL pattern matched a URL, where greater values take precedence.
+    ///
+    /// Fields are ordered by importance, which is how `git` prioritizes them.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    struct Specificity {
+        /// Values set by environment variables override everything else.
+        is_env_override: bool,
+        /// The length of the host in the pattern, which makes longer hosts win over shorter hosts with wildcards.
+        host_len: usize,
+        /// The host in the pattern doesn't contain wildcards, which makes it win over hosts with wildcards of the same length.
+        exact_host: bool,
+        /// The length of the matching path prefix.
+        path_len: usize,
+        /// The pattern contained a user name, which matched.
+        user_matched: bool,
+    }
+
+    /// Return how specifically `pattern` matches `url`, or `None` if it doesn't match.
+    ///
+    /// Scheme and port must be the same, each dot-separated component of the host in `pattern` may contain `*` wildcards,
+    /// the path in `pattern` must be a prefix of the one in `url` ending at a `/`, and if `pattern` has a user name, it must be
+    /// the one in `url`.
+    fn matches(pattern: &gix_url::Url, url: &gix_url::Url) -> Option<Specificity> {
+        if pattern.scheme != url.scheme
+            || !host_matches(pattern.host(), url.host())
+            || pattern.port_or_default() != url.port_or_default()
+        {
+            return None;
+        }
+        let user_matched = match pattern.user() {
+            Some(user) if url.user() != Some(user) => return None,
+            Some(_) => true,
+            None => false,
+        };
+        Some(Specificity {
+            is_env_override: false,
+            host_len: pattern.host().map_or(0, str::len),
+            exact_host: pattern
+                .host()
+                .map_or(true, |host| !host.contains(['*', '?', '[', '\\'])),
+            path_len: path_prefix_len(url.path.as_ref(), pattern.path.as_ref())?,
+            user_matched,
+        })
+    }
+
+    /// Return the length of the match if `prefix` is a prefix of `path` that ends at a path component, with both being considered
+    /// to end with a `/`, or `None` if it isn't.
+    fn path_prefix_len(path: &BStr, prefix: &BStr) -> Option<usize> {
+        if prefix.is_empty() || prefix == "/" {
+            return (path.is_empty() || path.starts_with(b"/")).then_some(1);
+        }
+        let prefix = prefix.strip_suffix(b"/").unwrap_or(prefix);
+        let rest = path.strip_prefix(prefix)?;
+        (rest.is_empty() || rest.starts_with(b"/")).then_some(prefix.len() + 1)
+    }
+
+    /// Access to the values of a section like `http` as they apply to a URL, where values in `<section>.<url>` subsections
+    /// that match the URL more specifically take precedence over less specific ones, and over those in `<section>`.
+    ///
+    /// Like in `git`, values are applied in order, but values in sections that are less specific than the most specific
+    /// one seen for the same key so far are ignored.
+    pub(crate) struct Values<'a> {
+        sections: Vec<(&'a gix_config::file::Section<'static>, Specificity)>,
+    }
+
+    impl<'a> Values<'a> {
+        /// Collect all sections named `section_name` in `config` that pass `filter` and match `url`.
+        ///
+        /// Each distinct pattern is parsed only once, even if it's used by multiple sections.
+        pub(crate) fn new(
+            config: &'a gix_config::File<'static>,
+            section_name: &'a str,
+            url: &gix_url::Url,
+            filter: &mut gix_config::file::MetadataFilter,
+        ) -> Self {
+            let mut patterns = BTreeMap::<&BStr, Option<Specificity>>::new();
+            let sections = config
+                .sections_by_name(section_name)
+                .into_iter()
+                .flatten()
+                .filter(|section| filter(section.meta()))
+                .filter_map(|section| {
+                    let specificity = match section.header().subsection_name() {
+                        Some(pattern) => {
+                            (*patterns.entry(pattern).or_insert_with(|| {
+                                gix_url::parse(pattern).ok().and_then(|pattern| matches(&pattern, url))
+                            }))?
+                        }
+                        None => Specificity {
+                            is_env_override: section.meta().source == gix_config::Source::EnvOverride,
+                            ..Default::default()
+                        },
+                    };
+                    Some((section, specificity))
+                })
+                .collect();
+            Values { sections }
+        }
+
+        /// Return all sections that contain `key` in the order in which their values are applied.
+        fn applicable<'b>(
+            &'b self,
+            key: &'b str,
+        ) -> impl DoubleEndedIterator<Item = &'a gix_config::file::Section<'static>> + 'b {
+            let mut most_specific = None;
+            self.sections
+                .iter()
+                .filter(|(section, _)| section.body().contains_value_name(key))
+                .filter(move |(_, specificity)| {
+                    if most_specific.map_or(false, |most_specific| *specificity < most_specific) {
+                        return false;
+                    }
+                    most_specific = Some(*specificity);
+                    true
+                })
+                .map(|(section, _)| *section)
+                .collect::<Vec<_>>()
+                .into_iter()
+        }
+
+        /// Like [`gix_config::File::string()`].
+        pub(crate) fn string(&self, key: &str) -> Option<Cow<'a, BStr>> {
+            self.applicable(key).rev().find_map(|section| section.value(key))
+        }
+
+        /// Like [`gix_config::File::strings()`].
+        pub(crate) fn strings(&self, key: &str) -> Option<Vec<Cow<'a, BStr>>> {
+            let values: Vec<_> = self.applicable(key).flat_map(|section| section.values(key)).collect();
+            (!values.is_empty()).then_some(values)
+        }
+
+        /// Like [`gix_config::File::boolean()`].
+        pub(crate) fn boolean(&self, key: &str) -> Option<Result<bool, gix_config::value::Error>> {
+            self.applicable(key)
+                .rev()
+                .find_map(|section| section.value_implicit(key))
+                .map(|value| match value {
+                    None => Ok(true),
+                    Some(value) => gix_config::Boolean::try_from(value).map(|b| b.0),
+                })
+        }
+
+        /// Like [`gix_config::File::integer()`].
+        pub(crate) fn integer(&self, key: &str) -> Option<Result<i64, gix_config::value::Error>> {
+            let value = self.string(key)?;
+            Some(gix_config::Integer::try_from(value.as_ref()).and_then(|int| {
+                int.to_decimal()
+                    .ok_or_else(|| gix_config::value::Error::new("Integer overflow", value.into_owned()))
+            }))
+        }
+
+        /// Like [`gix_config::File::path()`].
+        pub(crate) fn path(&self, key: &str) -> Option<gix_config::Path<'a>> {
+            self.string(key).map(gix_config::Path::from)
+        }
+    }
+}
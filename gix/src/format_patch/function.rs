@@ -0,0 +1,342 @@
+use std::io::Write;
+
+use gix_apply::{mail, BinaryHunk, Content, Operation};
+use gix_hash::ObjectId;
+use gix_object::tree::EntryMode;
+
+use super::{Error, Options, Patch};
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    object::tree::diff::change::EventDetached,
+    Repository,
+};
+
+/// The width of the diffstat in each email, which is what `git format-patch` uses.
+const STAT_WIDTH: usize = 72;
+
+impl Repository {
+    /// Turn all commits reachable from `head` but not from `upstream` into emails, oldest first, similar to
+    /// `git format-patch <upstream>..<head>`.
+    ///
+    /// Merge commits are skipped. Changes to binary files are written as binary patches so they can be applied again,
+    /// and commit messages are converted to UTF-8 if their commit has an encoding.
+    pub fn format_patch(
+        &self,
+        upstream: impl Into<ObjectId>,
+        head: impl Into<ObjectId>,
+        options: Options,
+    ) -> Result<Vec<Patch>, Error> {
+        let _span = gix_trace::coarse!("gix::format_patch()");
+        let hidden = self
+            .rev_walk(Some(upstream.into()))
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<gix_hashtable::HashSet<_>, _>>()?;
+        let mut commits = Vec::new();
+        for info in self
+            .rev_walk(Some(head.into()))
+            .selected(move |id| !hidden.contains(id))?
+        {
+            let info = info?;
+            if info.parent_ids.len() <= 1 {
+                commits.push((info.id, info.parent_ids.first().copied()));
+            }
+        }
+        commits.reverse();
+
+        let total = commits.len();
+        let numbered = options.numbered.unwrap_or(total > 1) || options.cover_letter;
+        let mut out = Vec::with_capacity(total + usize::from(options.cover_letter));
+        let mut summaries = Vec::new();
+        for (index, (id, parent)) in commits.iter().enumerate() {
+            let commit = self.find_commit(*id)?;
+            let commit = commit.decode()?;
+            let message = match commit
+                .encoding
+                .filter(|encoding| !encoding.eq_ignore_ascii_case(b"utf-8"))
+            {
+                Some(label) => {
+                    let encoding = gix_filter::encoding::Encoding::for_label(label).ok_or_else(|| Error::Encoding {
+                        id: *id,
+                        encoding: label.into(),
+                    })?;
+                    let (message, _, _) = encoding.decode(commit.message);
+                    BString::from(message.as_bytes())
+                }
+                None => commit.message.into(),
+            };
+            let message = gix_object::commit::MessageRef::from_bytes(&message);
+            let subject = message.summary();
+            let body = message.body.map(|body| body.trim_end()).unwrap_or_default();
+
+            let old_tree = match parent {
+                Some(parent) => self.find_commit(*parent)?.tree()?,
+                None => self.empty_tree(),
+            };
+            let new_tree = self.find_tree(commit.tree())?;
+            let stats = old_tree.changes()?.file_stats(&new_tree)?;
+
+            let mut data = Vec::new();
+            let prefix = if numbered {
+                format!("{} {}/{}", options.subject_prefix, index + 1, total)
+            } else {
+                options.subject_prefix.to_string()
+            };
+            let mut body_with_newline = BString::from(body);
+            if !body_with_newline.is_empty() {
+                body_with_newline.push(b'\n');
+            }
+            mail::Email {
+                id,
+                author: commit.author,
+                subject_prefix: (!prefix.is_empty()).then(|| prefix.as_str().into()),
+                subject: subject.as_ref(),
+                body: body_with_newline.as_ref(),
+            }
+            .write_to(&mut data)
+            .expect("writing to a vector can't fail");
+            data.extend_from_slice(b"---\n");
+            write_stat(stats, &mut data);
+            data.push(b'\n');
+            for file in self.patch_files(&old_tree, &new_tree, options.context_lines)? {
+                file.write_to(&mut data).expect("writing to a vector can't fail");
+            }
+            if let Some(signature) = &options.signature {
+                mail::write::write_signature(&mut data, signature.as_ref()).expect("writing to a vector can't fail");
+            }
+
+            out.push(Patch {
+                file_name: file_name(index + 1, subject.as_ref()),
+                commit: Some(*id),
+                data,
+            });
+            summaries.push((commit.author.name.to_owned(), subject.into_owned()));
+        }
+
+        if options.cover_letter && total != 0 {
+            out.insert(0, self.cover_letter(&commits, &summaries, &options)?);
+        }
+        Ok(out)
+    }
+
+    /// Produce the email that introduces all `commits`, with `summaries` of the author and subject of each commit.
+    fn cover_letter(
+        &self,
+        commits: &[(ObjectId, Option<ObjectId>)],
+        summaries: &[(BString, BString)],
+        options: &Options,
+    ) -> Result<Patch, Error> {
+        let committer = self.committer().ok_or(Error::CommitterMissing)??;
+        let mut body = BString::from("*** BLURB HERE ***\n\n");
+        let mut authors: Vec<&BString> = summaries.iter().map(|(author, _)| author).collect();
+        authors.sort();
+        authors.dedup();
+        for author in authors {
+            let subjects: Vec<_> = summaries
+                .iter()
+                .filter(|(name, _)| name == author)
+                .map(|(_, subject)| subject)
+                .collect();
+            body.extend_from_slice(format!("{author} ({}):\n", subjects.len()).as_bytes());
+            for subject in subjects {
+                body.extend_from_slice(format!("  {subject}\n").as_bytes());
+            }
+            body.push(b'\n');
+        }
+
+        let (first, last) = (commits.first().expect("non-empty"), commits.last().expect("non-empty"));
+        let old_tree = match first.1 {
+            Some(parent) => self.find_commit(parent)?.tree()?,
+            None => self.empty_tree(),
+        };
+        let new_tree = self.find_commit(last.0)?.tree()?;
+        write_stat(old_tree.changes()?.file_stats(&new_tree)?, &mut *body);
+        body.push(b'\n');
+
+        let null = ObjectId::null(self.object_hash());
+        let prefix = format!("{} 0/{}", options.subject_prefix, commits.len());
+        let mut data = Vec::new();
+        mail::Email {
+            id: &null,
+            author: committer,
+            subject_prefix: Some(prefix.as_str().into()),
+            subject: "*** SUBJECT HERE ***".into(),
+            body: body.as_ref(),
+        }
+        .write_to(&mut data)
+        .expect("writing to a vector can't fail");
+        if let Some(signature) = &options.signature {
+            mail::write::write_signature(&mut data, signature.as_ref()).expect("writing to a vector can't fail");
+        }
+        Ok(Patch {
+            file_name: "0000-cover-letter.patch".into(),
+            commit: None,
+            data,
+        })
+    }
+
+    /// Return the patches that turn `old_tree` into `new_tree`.
+    fn patch_files(
+        &self,
+        old_tree: &crate::Tree<'_>,
+        new_tree: &crate::Tree<'_>,
+        context_lines: u32,
+    ) -> Result<Vec<gix_apply::File>, Error> {
+        let mut changes = Vec::new();
+        old_tree
+            .changes()?
+            .track_path()
+            .for_each_to_obtain_tree(new_tree, |change| {
+                changes.push(change.detach());
+                Ok::<_, std::convert::Infallible>(crate::object::tree::diff::Action::Continue)
+            })?;
+
+        let mut out = Vec::new();
+        for change in changes {
+            let location = change.location;
+            let (operation, old, new, old_path, similarity) = match change.event {
+                EventDetached::Addition { entry_mode, id } => {
+                    (Operation::Create, None, Some((entry_mode, id)), None, None)
+                }
+                EventDetached::Deletion { entry_mode, id } => (
+                    Operation::Delete,
+                    Some((entry_mode, id)),
+                    None,
+                    Some(location.clone()),
+                    None,
+                ),
+                EventDetached::Modification {
+                    previous_entry_mode,
+                    previous_id,
+                    entry_mode,
+                    id,
+                } => (
+                    Operation::Modify,
+                    Some((previous_entry_mode, previous_id)),
+                    Some((entry_mode, id)),
+                    Some(location.clone()),
+                    None,
+                ),
+                EventDetached::Rewrite {
+                    source_location,
+                    source_entry_mode,
+                    source_id,
+                    diff,
+                    entry_mode,
+                    id,
+                    copy,
+                } => (
+                    if copy { Operation::Copy } else { Operation::Rename },
+                    Some((source_entry_mode, source_id)),
+                    Some((entry_mode, id)),
+                    Some(source_location),
+                    Some(diff.map_or(100, |diff| (diff.similarity * 100.0) as u8)),
+                ),
+            };
+            if old.into_iter().chain(new).any(|(mode, _)| mode.is_tree()) {
+                continue;
+            }
+
+            let old_data = old.map(|(mode, id)| self.entry_data(mode, id)).transpose()?;
+            let new_data = new.map(|(mode, id)| self.entry_data(mode, id)).transpose()?;
+            let (old_data, new_data) = (old_data.unwrap_or_default(), new_data.unwrap_or_default());
+            let is_binary = [&old_data, &new_data].iter().any(|data| is_binary(data));
+            let hex_len = if is_binary { self.object_hash().len_in_hex() } else { 7 };
+            let null = ObjectId::null(self.object_hash());
+            let content = if old_data == new_data {
+                Content::Text(Vec::new())
+            } else if is_binary {
+                Content::Binary(gix_apply::Binary {
+                    forward: BinaryHunk::Literal(new_data),
+                    reverse: Some(BinaryHunk::Literal(old_data)),
+                })
+            } else {
+                Content::Text(gix_apply::diff::hunks(
+                    gix_apply::diff::Algorithm::Histogram,
+                    &old_data,
+                    &new_data,
+                    context_lines,
+                ))
+            };
+            out.push(gix_apply::File {
+                old_path,
+                new_path: new.is_some().then_some(location),
+                old_mode: old.map(|(mode, _)| mode),
+                new_mode: new.map(|(mode, _)| mode),
+                old_id: Some(
+                    old.map_or(null, |(_, id)| id)
+                        .to_hex_with_len(hex_len)
+                        .to_string()
+                        .into(),
+                ),
+                new_id: Some(
+                    new.map_or(null, |(_, id)| id)
+                        .to_hex_with_len(hex_len)
+                        .to_string()
+                        .into(),
+                ),
+                operation,
+                similarity,
+                content,
+            });
+        }
+        out.sort_by(|a, b| a.path().cmp(b.path()));
+        Ok(out)
+    }
+
+    /// Return the content of the entry with `mode` and `id`, which is `Subproject commit <id>` for submodules.
+    fn entry_data(&self, mode: EntryMode, id: ObjectId) -> Result<Vec<u8>, Error> {
+        Ok(if mode.is_commit() {
+            format!("Subproject commit {id}\n").into_bytes()
+        } else {
+            self.find_object(id)?.detach().data
+        })
+    }
+}
+
+/// Return `true` if `data` is considered binary, similar to `buffer_is_binary()` in `git`.
+fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(8000)].contains(&0)
+}
+
+/// Write `stats` sorted by path, like `git` does.
+fn write_stat(mut stats: Vec<gix_diff::stat::File>, out: &mut dyn Write) {
+    stats.sort_by(|a, b| a.location.cmp(&b.location));
+    gix_diff::stat::write_stat(
+        &stats,
+        gix_diff::stat::Options {
+            width: STAT_WIDTH,
+            ..Default::default()
+        },
+        out,
+    )
+    .expect("writing to a vector can't fail");
+}
+
+/// Return the name of the file for patch number `number` with `subject`, like `0001-fix-a-bug.patch`.
+///
+/// This is `format_sanitized_subject()` in `git`.
+fn file_name(number: usize, subject: &BStr) -> BString {
+    const MAX_LEN: usize = 64;
+    let mut name = BString::default();
+    let mut pending_dash = false;
+    for byte in subject.iter().copied() {
+        if byte.is_ascii_alphanumeric() || byte == b'.' || byte == b'_' {
+            if pending_dash && !name.is_empty() {
+                name.push(b'-');
+            }
+            pending_dash = false;
+            name.push(byte);
+        } else {
+            pending_dash = true;
+        }
+    }
+    while name.first().map_or(false, |b| *b == b'.') {
+        name.remove(0);
+    }
+    name.truncate(MAX_LEN);
+    while name.last().map_or(false, |b| *b == b'.' || *b == b'-') {
+        name.pop();
+    }
+    format!("{number:04}-{name}.patch").into()
+}
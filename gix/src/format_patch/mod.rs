@@ -0,0 +1,127 @@
+//! Turn commits into emails with patches, similar to `git format-patch`.
+use crate::bstr::BString;
+use crate::config::cache::util::{ApplyLeniency, ApplyLeniencyDefault};
+use crate::{config, Repository};
+
+mod function;
+
+/// Options for use in [`Repository::format_patch()`].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The text in brackets in front of each subject, like `PATCH` or `RFC PATCH`, as configured by `format.subjectPrefix`.
+    pub subject_prefix: BString,
+    /// Whether to number patches like `[PATCH 1/2]`, or `None` to only do so if there is more than one patch, which is the
+    /// default of `format.numbered`.
+    pub numbered: Option<bool>,
+    /// If `true`, like `--cover-letter`, produce an additional email with a summary of all patches which is meant to be edited.
+    pub cover_letter: bool,
+    /// The signature to put at the end of each email, or `None` to not write one, as configured by `format.signature`.
+    pub signature: Option<BString>,
+    /// The amount of lines of context around each change.
+    pub context_lines: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            subject_prefix: "PATCH".into(),
+            numbered: None,
+            cover_letter: false,
+            signature: Some(env!("CARGO_PKG_VERSION").into()),
+            context_lines: 3,
+        }
+    }
+}
+
+/// A single email as produced by [`Repository::format_patch()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    /// The name of the file to write the email to, like `0001-fix-a-bug.patch`.
+    pub file_name: BString,
+    /// The commit the email was created from, or `None` for the cover letter.
+    pub commit: Option<gix_hash::ObjectId>,
+    /// The email in the mailbox format, starting with a `From <id>` line.
+    pub data: Vec<u8>,
+}
+
+/// Return all `patches` as a single mailbox, like `git format-patch --stdout`.
+pub fn to_mbox(patches: &[Patch]) -> Vec<u8> {
+    patches.iter().flat_map(|patch| patch.data.iter().copied()).collect()
+}
+
+/// The error returned by [`Repository::format_patch()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    WalkIter(#[from] crate::revision::walk::iter::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    FindObjectWithConversion(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    CommitTree(#[from] crate::object::commit::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    DiffInit(#[from] crate::diff::new_rewrites::Error),
+    #[error(transparent)]
+    Diff(#[from] crate::object::tree::diff::for_each::Error),
+    #[error(transparent)]
+    Stats(#[from] crate::object::tree::diff::stats::Error),
+    #[error("The commit message of {id} could not be converted from its encoding '{encoding}'")]
+    Encoding { id: gix_hash::ObjectId, encoding: BString },
+    #[error(transparent)]
+    ParseTime(#[from] crate::config::time::Error),
+    #[error("Committer identity is not configured, but is needed for the cover letter")]
+    CommitterMissing,
+}
+
+///
+pub mod options {
+    /// The error returned by [`Repository::format_patch_options()`](crate::Repository::format_patch_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Config(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        Boolean(#[from] crate::config::boolean::Error),
+    }
+}
+
+/// Configuration
+impl Repository {
+    /// Return the options for use in [`format_patch()`](Self::format_patch()) as configured by `format.subjectPrefix`,
+    /// `format.numbered`, `format.coverLetter` and `format.signature`.
+    pub fn format_patch_options(&self) -> Result<Options, options::Error> {
+        use config::tree::Format;
+        let config = &self.config.resolved;
+        let mut opts = Options::default();
+        if let Some(prefix) = config.string(Format::SUBJECT_PREFIX) {
+            opts.subject_prefix = prefix.into_owned();
+        }
+        if let Some(signature) = config.string(Format::SIGNATURE) {
+            opts.signature = (!signature.is_empty()).then(|| signature.into_owned());
+        }
+        if let Some(value) = config.string(Format::NUMBERED) {
+            opts.numbered = if value.as_ref() == "auto" {
+                None
+            } else {
+                gix_config::Boolean::try_from(value.as_ref())
+                    .map(|b| Some(b.0))
+                    .map_err(|_| config::key::GenericErrorWithValue::from_value(&Format::NUMBERED, value.into_owned()))
+                    .with_lenient_default(self.config.lenient_config)?
+            };
+        }
+        opts.cover_letter = config
+            .boolean(Format::COVER_LETTER)
+            .map(|value| Format::COVER_LETTER.enrich_error(value))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+            .unwrap_or_default();
+        Ok(opts)
+    }
+}
@@ -0,0 +1,349 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_features::progress::{Count, Progress};
+use gix_hash::ObjectId;
+use gix_hashtable::{HashMap, HashSet};
+use gix_object::{tree::EntryKind, Find, Kind};
+
+use super::{message, object, Error, Options, Outcome, Problem};
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::{cache::util::ApplyLeniency, tree::Fsck},
+};
+
+impl crate::Repository {
+    /// Verify all objects in the object database, like `git fsck`, and return all problems that were found.
+    ///
+    /// Each object is checked for a matching hash and for valid syntax, with problems reported according to their severity
+    /// as configured in `fsck.<msg-id>`, unless the object is listed in the file at `fsck.skipList`. Blobs that are used
+    /// as `.gitmodules` file are checked for submodule settings that could be used for attacks.
+    ///
+    /// Then all objects reachable from references, their reflogs, and the `HEAD` and index of each worktree are traversed
+    /// to find missing objects, to finally report all objects that aren't reachable, and those among them that aren't
+    /// referred to by any other unreachable object as dangling.
+    /// In a partial clone, objects that are referred to by objects in packs with a `.promisor` file may be missing,
+    /// and are neither fetched nor reported.
+    ///
+    /// ### Deviation
+    ///
+    /// Unlike `git`, all problems are collected instead of being printed, and problems in the references themselves,
+    /// or in the commit-graph and multi-pack index, aren't detected.
+    pub fn fsck(
+        &self,
+        options: Options,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let severities = self.fsck_severities(options.strict)?;
+        let skip_list = self.fsck_skip_list()?;
+        let object_hash = self.object_hash();
+        let mut db = (*self.objects).clone();
+        db.ignore_replacements = true;
+        // We expect to look for objects that don't exist, which shouldn't trigger a refresh of the object database.
+        // Like `git`, never fetch from the promisor remote either, as missing trees and commits are looked up to be reported.
+        db.refresh_never();
+        db.promisor = None;
+
+        let mut out = Outcome::default();
+        let add_problems = |out: &mut Outcome, id: ObjectId, kind: Kind, problems: Vec<object::Problem>| {
+            if skip_list.contains(&id) {
+                return;
+            }
+            for problem in problems {
+                let severity = severities.severity(problem.id);
+                if severity != message::Severity::Ignore {
+                    out.problems.push(Problem {
+                        id,
+                        kind,
+                        severity,
+                        problem,
+                    });
+                }
+            }
+        };
+
+        let mut checking = progress.add_child("checking objects".into());
+        checking.init(None, crate::progress::count("objects"));
+        let mut kinds = HashMap::<ObjectId, Kind>::default();
+        let mut gitmodules = Vec::new();
+        let mut buf = Vec::new();
+        for id in db.iter()? {
+            let id = id?;
+            if kinds.contains_key(&id) {
+                continue;
+            }
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            checking.inc();
+            let object = match db.try_find(&id, &mut buf) {
+                Ok(Some(object)) => object,
+                Ok(None) => continue,
+                Err(_) => {
+                    out.corrupt.push(id);
+                    continue;
+                }
+            };
+            kinds.insert(id, object.kind);
+            if gix_object::compute_hash(object_hash, object.kind, object.data) != id {
+                out.corrupt.push(id);
+                continue;
+            }
+            let checked = object::check(object.kind, object.data, object_hash);
+            gitmodules.extend(checked.gitmodules);
+            add_problems(&mut out, id, object.kind, checked.problems);
+        }
+        out.checked_objects = kinds.len();
+        out.corrupt.sort();
+
+        gitmodules.sort();
+        gitmodules.dedup();
+        for id in gitmodules {
+            if kinds.get(&id) != Some(&Kind::Blob) || out.corrupt.binary_search(&id).is_ok() {
+                continue;
+            }
+            if let Some(object) = db.try_find(&id, &mut buf)? {
+                let problems = object::check_gitmodules(object.data);
+                add_problems(&mut out, id, Kind::Blob, problems);
+            }
+        }
+        out.problems.sort_by_key(|problem| problem.id);
+
+        let tips = self.fsck_tips()?;
+        let promised = self.promisor_objects()?;
+        let mut shallow_parents = HashSet::default();
+        if let Some(shallow) = self.shallow_commits()? {
+            for id in shallow.iter() {
+                if let Some(commit) = db.try_find(id, &mut buf)? {
+                    if let Ok(commit) = gix_object::CommitRef::from_bytes(commit.data) {
+                        shallow_parents.extend(commit.parents());
+                    }
+                }
+            }
+        }
+        let mut missing = BTreeMap::new();
+        let mut connectivity = gix_fsck::Connectivity::new(&db, |id: &ObjectId, kind: Kind| {
+            if !shallow_parents.contains(id) && !promised.contains(id) {
+                missing.insert(*id, kind);
+            }
+        });
+        for (source, id) in tips {
+            match kinds.get(&id) {
+                Some(kind) => connectivity.check_object(&id, *kind)?,
+                None if promised.contains(&id) => {}
+                None => out.broken_links.push((source, id)),
+            }
+        }
+        out.unreachable = kinds
+            .iter()
+            .filter(|(id, _)| !connectivity.seen().contains(*id))
+            .map(|(id, kind)| (*id, *kind))
+            .collect();
+        drop(connectivity);
+        out.missing = missing.into_iter().collect();
+        out.unreachable.sort();
+
+        let mut referenced = HashSet::default();
+        for (id, _kind) in &out.unreachable {
+            let Some(object) = db.try_find(id, &mut buf)? else {
+                continue;
+            };
+            match object.kind {
+                Kind::Commit => {
+                    if let Ok(commit) = gix_object::CommitRef::from_bytes(object.data) {
+                        referenced.insert(commit.tree());
+                        referenced.extend(commit.parents());
+                    }
+                }
+                Kind::Tag => {
+                    if let Ok(tag) = gix_object::TagRef::from_bytes(object.data) {
+                        referenced.insert(tag.target());
+                    }
+                }
+                Kind::Tree => {
+                    for entry in gix_object::TreeRefIter::from_bytes(object.data).map_while(Result::ok) {
+                        if entry.mode.kind() != EntryKind::Commit {
+                            referenced.insert(entry.oid.to_owned());
+                        }
+                    }
+                }
+                Kind::Blob => {}
+            }
+        }
+        out.dangling = out
+            .unreachable
+            .iter()
+            .filter(|(id, _)| !referenced.contains(id))
+            .copied()
+            .collect();
+
+        if options.lost_found {
+            let lost_found = self.common_dir().join("lost-found");
+            for (id, kind) in &out.dangling {
+                let dir = lost_found.join(if *kind == Kind::Commit { "commit" } else { "other" });
+                std::fs::create_dir_all(&dir).map_err(|source| Error::Io {
+                    path: dir.clone(),
+                    source,
+                })?;
+                let path = dir.join(id.to_hex().to_string());
+                let content = match kind {
+                    Kind::Blob => match db.try_find(id, &mut buf)? {
+                        Some(object) => object.data.to_vec(),
+                        None => continue,
+                    },
+                    _ => format!("{id}\n").into_bytes(),
+                };
+                std::fs::write(&path, content).map_err(|source| Error::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+                out.lost_found.push(path);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Return the severities of all problems, as configured with `fsck.<msg-id>`.
+    fn fsck_severities(&self, strict: bool) -> Result<message::Severities, Error> {
+        let mut out = message::Severities {
+            strict,
+            overrides: Default::default(),
+        };
+        let mut filter = self.filter_config_section();
+        let Some(sections) = self.config.resolved.sections_by_name_and_filter("fsck", &mut filter) else {
+            return Ok(out);
+        };
+        for section in sections.filter(|section| section.header().subsection_name().is_none()) {
+            for key in section.value_names() {
+                // Other keys, like `fsck.skipList`, are not message ids.
+                let Some(id) = message::Id::from_name(key) else {
+                    continue;
+                };
+                let value = section.value(key).expect("present");
+                match message::Severity::from_name(value.as_ref()) {
+                    Some(severity) => {
+                        out.overrides.insert(id, severity);
+                    }
+                    None if self.options.lenient_config => {}
+                    None => {
+                        return Err(Error::InvalidSeverity {
+                            key: id.as_str().into(),
+                            value: value.into_owned(),
+                        })
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Return the ids of all objects listed in the file at `fsck.skipList`, whose problems shouldn't be reported.
+    fn fsck_skip_list(&self) -> Result<HashSet<ObjectId>, Error> {
+        let mut out = HashSet::default();
+        let Some(path) = self
+            .config
+            .trusted_file_path(Fsck::SKIP_LIST)
+            .transpose()
+            .with_leniency(self.options.lenient_config)?
+        else {
+            return Ok(out);
+        };
+        let data = std::fs::read(&path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        for line in data.lines() {
+            // Comments, empty lines and surrounding whitespace are ignored.
+            let line = line.split(|b| *b == b'#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let id = ObjectId::from_hex(line).map_err(|_| Error::InvalidSkipListEntry {
+                path: path.to_path_buf(),
+                line: line.into(),
+            })?;
+            out.insert(id);
+        }
+        Ok(out)
+    }
+
+    /// Return all starting points for the reachability check, along with a description of where they were found.
+    fn fsck_tips(&self) -> Result<Vec<(BString, ObjectId)>, Error> {
+        let main = self.main_repo()?;
+        let mut repos = main
+            .worktrees()
+            .map_err(|source| Error::Io {
+                path: main.common_dir().join("worktrees"),
+                source,
+            })?
+            .into_iter()
+            .map(crate::worktree::Proxy::into_repo_with_possibly_inaccessible_worktree)
+            .collect::<Result<Vec<_>, _>>()?;
+        repos.insert(0, main);
+
+        let mut tips = Vec::new();
+        let mut buf = Vec::new();
+        let mut add_reflog = |repo: &crate::Repository,
+                              name: &gix_ref::FullNameRef,
+                              source: &BString,
+                              tips: &mut Vec<(BString, ObjectId)>|
+         -> Result<(), Error> {
+            if let Some(lines) = repo.refs.reflog_iter(name, &mut buf)? {
+                for line in lines {
+                    let line = line?;
+                    for id in [line.previous_oid(), line.new_oid()] {
+                        if !id.is_null() {
+                            tips.push((format!("{source}@{{reflog}}").into(), id));
+                        }
+                    }
+                }
+            }
+            Ok(())
+        };
+        let references = repos[0].refs.iter()?;
+        for reference in references.all().map_err(|source| Error::Io {
+            path: repos[0].common_dir().join("refs"),
+            source,
+        })? {
+            let reference = reference?;
+            let source = reference.name.as_bstr().to_owned();
+            if let gix_ref::Target::Object(id) = reference.target {
+                tips.push((source.clone(), id));
+            }
+            add_reflog(&repos[0], reference.name.as_ref(), &source, &mut tips)?;
+        }
+        let head_name: &gix_ref::FullNameRef = "HEAD".try_into().expect("valid");
+        for repo in &repos {
+            let prefix = worktree_prefix(repo.git_dir(), repos[0].common_dir());
+            let head: BString = format!("{prefix}HEAD").into();
+            if let Some(id) = repo.head()?.id() {
+                tips.push((head.clone(), id.detach()));
+            }
+            add_reflog(repo, head_name, &head, &mut tips)?;
+            let index = repo.index_or_empty()?;
+            for entry in index.entries() {
+                if entry.mode != gix_index::entry::Mode::COMMIT {
+                    tips.push((format!("{prefix}index:{}", entry.path(&index)).into(), entry.id));
+                }
+            }
+        }
+        Ok(tips)
+    }
+}
+
+/// Return `worktrees/<id>/` for the linked worktree with `git_dir`, or an empty string for the main worktree.
+fn worktree_prefix(git_dir: &Path, common_dir: &Path) -> String {
+    match git_dir.strip_prefix(common_dir) {
+        Ok(relative) if !relative.as_os_str().is_empty() => {
+            format!(
+                "{}/",
+                gix_path::to_unix_separators_on_windows(gix_path::into_bstr(relative))
+            )
+        }
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,110 @@
+//! Verification of the objects in the object database, like `git fsck`.
+use std::path::PathBuf;
+
+pub use gix_fsck::{message, object, Connectivity};
+use gix_hash::ObjectId;
+use gix_object::Kind;
+
+use crate::bstr::BString;
+
+mod function;
+
+/// Options for use in [`Repository::fsck()`](crate::Repository::fsck()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If `true`, problems that would be warnings are treated as errors, like `git fsck --strict`.
+    pub strict: bool,
+    /// If `true`, write dangling objects into `lost-found/commit/<id>` or `lost-found/other/<id>` in the `.git`
+    /// directory, like `git fsck --lost-found`. Blobs are written with their content, all other objects as their id.
+    pub lost_found: bool,
+}
+
+/// A problem found in an object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// The id of the object the problem was found in.
+    pub id: ObjectId,
+    /// The kind of object the problem was found in.
+    pub kind: Kind,
+    /// The severity of the problem as configured, which is never [`Ignore`](message::Severity::Ignore).
+    pub severity: message::Severity,
+    /// The problem itself.
+    pub problem: object::Problem,
+}
+
+/// The result of [`Repository::fsck()`](crate::Repository::fsck()).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of objects in the object database that were checked.
+    pub checked_objects: usize,
+    /// Problems found in objects, sorted by object id.
+    pub problems: Vec<Problem>,
+    /// Objects whose content doesn't hash to their id, sorted by id.
+    pub corrupt: Vec<ObjectId>,
+    /// The names of references, or descriptions of other starting points like `HEAD@{reflog}` or `index:<path>`,
+    /// along with the missing object they point to.
+    pub broken_links: Vec<(BString, ObjectId)>,
+    /// Objects that are referred to by reachable objects, but are missing, sorted by id.
+    pub missing: Vec<(ObjectId, Kind)>,
+    /// Objects that aren't reachable from references, reflogs, or the `HEAD` and index of any worktree, sorted by id.
+    pub unreachable: Vec<(ObjectId, Kind)>,
+    /// The [unreachable](Self::unreachable) objects that aren't referred to by any other unreachable object, sorted by id.
+    pub dangling: Vec<(ObjectId, Kind)>,
+    /// The files written into the `lost-found` directory, if [enabled](Options::lost_found).
+    pub lost_found: Vec<PathBuf>,
+}
+
+impl Outcome {
+    /// Return `true` if there is any problem that makes the object database invalid, i.e. problems of severity
+    /// [`Error`](message::Severity::Error), corrupt or missing objects and broken links.
+    ///
+    /// Note that unreachable or dangling objects are not considered an error.
+    pub fn has_errors(&self) -> bool {
+        self.problems
+            .iter()
+            .any(|problem| problem.severity == message::Severity::Error)
+            || !self.corrupt.is_empty()
+            || !self.broken_links.is_empty()
+            || !self.missing.is_empty()
+    }
+}
+
+/// The error returned by [`Repository::fsck()`](crate::Repository::fsck()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The value of 'fsck.{key}={value}' isn't one of 'error', 'warn' or 'ignore'")]
+    InvalidSeverity { key: BString, value: BString },
+    #[error(transparent)]
+    SkipListPath(#[from] gix_config::path::interpolate::Error),
+    #[error("The object id '{line}' in the skip list at '{}' is invalid", path.display())]
+    InvalidSkipListEntry { path: PathBuf, line: BString },
+    #[error("Could not access '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("The operation was interrupted")]
+    Interrupted,
+    #[error(transparent)]
+    LoadIndex(#[from] gix_odb::store::load_index::Error),
+    #[error(transparent)]
+    IterObjects(#[from] gix_odb::loose::iter::Error),
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::Error),
+    #[error(transparent)]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error(transparent)]
+    OpenPackedRefs(#[from] gix_ref::packed::buffer::open::Error),
+    #[error(transparent)]
+    IterReference(#[from] gix_ref::file::iter::loose_then_packed::Error),
+    #[error(transparent)]
+    ReadReflog(#[from] gix_ref::file::log::Error),
+    #[error(transparent)]
+    DecodeReflog(#[from] gix_ref::file::log::iter::decode::Error),
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    Shallow(#[from] crate::shallow::open::Error),
+    #[error(transparent)]
+    PromisorObjects(#[from] crate::repository::promisor_objects::Error),
+}
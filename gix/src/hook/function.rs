@@ -0,0 +1,292 @@
+use std::{
+    ffi::{OsStr, OsString},
+    io::Write,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use gix_ref::{
+    transaction::{Change, RefEdit, RefLog},
+    TargetRef,
+};
+
+use super::{CommitHooks, Error, Hook, Hooks, Invocation};
+use crate::{bstr::BString, config::tree::Core, Repository};
+
+/// A hook that was found and can be run.
+enum Found<'a> {
+    InProcess(&'a dyn Hook),
+    Program(PathBuf),
+}
+
+impl Repository {
+    /// Return the hooks that are run by this instance.
+    pub fn hooks(&self) -> &Hooks {
+        &self.options.hooks
+    }
+
+    /// Set the hooks to be run by this instance to `hooks`, and return the previous ones.
+    pub fn set_hooks(&mut self, hooks: Hooks) -> Hooks {
+        std::mem::replace(&mut self.options.hooks, hooks)
+    }
+
+    /// Return the directory in which hooks are looked up, which is `core.hooksPath` or the `hooks` directory in the
+    /// [common directory](Self::common_dir()).
+    ///
+    /// Just like in `git`, a relative `core.hooksPath` is relative to the directory hooks run in, which is the root of the
+    /// worktree, or the `.git` directory in bare repositories.
+    pub fn hooks_dir(&self) -> Result<PathBuf, gix_config::path::interpolate::Error> {
+        Ok(match self.config.trusted_file_path(&Core::HOOKS_PATH).transpose()? {
+            Some(path) => self.hook_cwd().join(path),
+            None => self.absolute(self.common_dir()).join("hooks"),
+        })
+    }
+
+    /// Run the hook `name`, like `pre-commit`, with `args`, and pass `stdin` on standard input unless it's empty,
+    /// and return `true` if the hook ran successfully, or `false` if there is no such hook.
+    ///
+    /// [In-process hooks](Hooks::with_hook()) take precedence over programs in the [hooks directory](Self::hooks_dir()),
+    /// which are only run if they are executable and if this repository is fully trusted. Programs run in the root of the
+    /// worktree, or in the `.git` directory of bare repositories, with `GIT_DIR` and `GIT_INDEX_FILE` set to the repository
+    /// and its index, and with their output passed through.
+    ///
+    /// A program that exits with a non-zero exit code, or an in-process hook that returns an error, fails the call.
+    pub fn run_hook(
+        &self,
+        name: &str,
+        args: impl IntoIterator<Item = impl Into<OsString>>,
+        stdin: &[u8],
+    ) -> Result<bool, Error> {
+        self.run_hook_with_env(name, args.into_iter().map(Into::into).collect(), stdin, &[])
+    }
+
+    /// Return `true` if the hook `name` would be run.
+    pub(crate) fn has_hook(&self, name: &str) -> Result<bool, Error> {
+        Ok(self.find_hook(name)?.is_some())
+    }
+
+    /// Run the hook `name` like [`run_hook()`](Self::run_hook()), but with the environment variables in `extra_env`
+    /// set as well.
+    pub(crate) fn run_hook_with_env(
+        &self,
+        name: &str,
+        args: Vec<OsString>,
+        stdin: &[u8],
+        extra_env: &[(&str, &OsStr)],
+    ) -> Result<bool, Error> {
+        let Some(hook) = self.find_hook(name)? else {
+            return Ok(false);
+        };
+        let _span = gix_trace::coarse!("gix::hook::run()", name);
+        let cwd = self.hook_cwd();
+        let mut env: Vec<(OsString, OsString)> = vec![("GIT_DIR".into(), self.absolute(self.git_dir()).into())];
+        if self.work_dir().is_some() {
+            env.push(("GIT_INDEX_FILE".into(), self.absolute(&self.index_path()).into()));
+        }
+        env.extend(
+            extra_env
+                .iter()
+                .map(|(key, value)| ((*key).into(), (*value).to_owned())),
+        );
+
+        match hook {
+            Found::InProcess(hook) => hook
+                .run(&Invocation {
+                    name,
+                    args: &args,
+                    stdin,
+                    env: &env,
+                    cwd: &cwd,
+                })
+                .map_err(|source| Error::InProcess {
+                    name: name.into(),
+                    source,
+                })?,
+            Found::Program(path) => {
+                let mut context = self.command_context()?;
+                // We set these ourselves, and like `git`, leave the worktree to be discovered from the working directory.
+                context.git_dir = None;
+                context.worktree_dir = None;
+                let mut cmd: std::process::Command = gix_command::prepare(path.as_os_str())
+                    .with_context(context)
+                    .args(args)
+                    .stdin(if stdin.is_empty() {
+                        Stdio::null()
+                    } else {
+                        Stdio::piped()
+                    })
+                    .stdout(stderr_as_stdio())
+                    .into();
+                cmd.current_dir(&cwd).envs(env);
+                gix_trace::debug!(cmd = ?cmd);
+                let spawn_err = |source| Error::Spawn {
+                    name: name.into(),
+                    path: path.clone(),
+                    source,
+                };
+                let mut child = cmd.spawn().map_err(spawn_err)?;
+                if let Some(mut input) = child.stdin.take() {
+                    // Hooks are free to ignore their input.
+                    match input.write_all(stdin) {
+                        Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => return Err(spawn_err(err)),
+                        _ => {}
+                    }
+                }
+                let status = child.wait().map_err(spawn_err)?;
+                if !status.success() {
+                    return Err(Error::Failed {
+                        name: name.into(),
+                        status,
+                    });
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Run the hook `name` after the operation that invokes it completed, which is why failures can only be logged.
+    pub(crate) fn run_post_hook(&self, name: &str, args: Vec<OsString>, stdin: &[u8]) {
+        if let Err(_err) = self.run_hook_with_env(name, args, stdin, &[]) {
+            gix_trace::warn!("ignoring failure of the '{name}' hook: {_err}");
+        }
+    }
+
+    /// Run the `pre-commit` hook, which may still change the index that is about to be committed.
+    #[cfg(all(feature = "tree-editor", feature = "index"))]
+    pub(crate) fn run_pre_commit_hook(&self) -> Result<(), Error> {
+        self.run_hook_with_env("pre-commit", Vec::new(), &[], &commit_hook_env())?;
+        Ok(())
+    }
+
+    /// Run the `prepare-commit-msg` and `commit-msg` hooks as configured by `hooks` for a commit with `message`, and return
+    /// the message as changed by the hooks, if one of them received it.
+    pub(crate) fn run_commit_msg_hooks(&self, message: &str, hooks: CommitHooks) -> Result<Option<BString>, Error> {
+        let verify = match hooks {
+            CommitHooks::None => return Ok(None),
+            CommitHooks::NoVerify => false,
+            CommitHooks::Verify => true,
+        };
+        let env = commit_hook_env();
+        if !(self.has_hook("prepare-commit-msg")? || verify && self.has_hook("commit-msg")?) {
+            return Ok(None);
+        }
+        let path = self.absolute(self.git_dir()).join("COMMIT_EDITMSG");
+        let io_err = |source| Error::MessageFile {
+            path: path.clone(),
+            source,
+        };
+        std::fs::write(&path, message).map_err(io_err)?;
+        self.run_hook_with_env(
+            "prepare-commit-msg",
+            vec![path.clone().into(), "message".into()],
+            &[],
+            &env,
+        )?;
+        if verify {
+            self.run_hook_with_env("commit-msg", vec![path.clone().into()], &[], &env)?;
+        }
+        Ok(Some(std::fs::read(&path).map_err(io_err)?.into()))
+    }
+
+    /// Run the `reference-transaction` hook in `state`, like `prepared`, `committed` or `aborted`, with `edits` on standard
+    /// input as `<old-value> <new-value> <ref-name>` lines.
+    pub(crate) fn run_reference_transaction_hook<'a>(
+        &self,
+        state: &str,
+        edits: impl IntoIterator<Item = &'a RefEdit>,
+    ) -> Result<(), Error> {
+        if !self.has_hook("reference-transaction")? {
+            return Ok(());
+        }
+        let null = self.object_hash().null();
+        let format = |value: Option<TargetRef<'_>>| match value {
+            Some(TargetRef::Object(id)) => id.to_string(),
+            Some(TargetRef::Symbolic(name)) => format!("ref:{}", name.as_bstr()),
+            None => null.to_string(),
+        };
+        let mut stdin = Vec::new();
+        for edit in edits {
+            // Like in `git`, updates to reflogs only aren't reported.
+            if matches!(&edit.change, Change::Update { log, .. } if log.mode == RefLog::Only) {
+                continue;
+            }
+            writeln!(
+                stdin,
+                "{} {} {}",
+                format(edit.change.previous_value()),
+                format(edit.change.new_value()),
+                edit.name.as_bstr()
+            )
+            .expect("write to memory works");
+        }
+        self.run_hook_with_env("reference-transaction", vec![state.into()], &stdin, &[])?;
+        Ok(())
+    }
+
+    fn find_hook(&self, name: &str) -> Result<Option<Found<'_>>, Error> {
+        let hooks = &self.options.hooks;
+        if let Some(hook) = hooks.in_process(name) {
+            return Ok(Some(Found::InProcess(hook)));
+        }
+        // Anyone could have placed programs into repositories we don't trust, so these are never run.
+        if !hooks.run_programs || self.git_dir_trust() != gix_sec::Trust::Full {
+            return Ok(None);
+        }
+        Ok(find_program(self.hooks_dir()?.join(name)).map(Found::Program))
+    }
+
+    /// The directory hooks run in.
+    fn hook_cwd(&self) -> PathBuf {
+        self.absolute(self.work_dir().unwrap_or(self.git_dir()))
+    }
+
+    fn absolute(&self, path: &Path) -> PathBuf {
+        if path.is_relative() {
+            self.current_dir().join(path)
+        } else {
+            path.to_owned()
+        }
+    }
+}
+
+/// The environment of hooks run while committing, where the message is given so editors must not be started.
+fn commit_hook_env() -> [(&'static str, &'static OsStr); 1] {
+    [("GIT_EDITOR", OsStr::new(":"))]
+}
+
+/// Return `path` if it is an executable file, or the path with `.exe` extension on Windows if that exists.
+fn find_program(path: PathBuf) -> Option<PathBuf> {
+    let is_executable_file = |path: &Path| {
+        std::fs::metadata(path).map_or(false, |meta| {
+            meta.is_file() && (cfg!(not(unix)) || gix_fs::is_executable(&meta))
+        })
+    };
+    if is_executable_file(&path) {
+        return Some(path);
+    }
+    if cfg!(windows) {
+        let mut path = path.into_os_string();
+        path.push(".exe");
+        let path = PathBuf::from(path);
+        return is_executable_file(&path).then_some(path);
+    }
+    None
+}
+
+/// Return our standard error to be used as standard output of hooks, as `git` does so their output doesn't get mixed
+/// into ours, or discard their output if standard error isn't available.
+fn stderr_as_stdio() -> Stdio {
+    #[cfg(unix)]
+    let stderr = {
+        use std::os::unix::io::AsFd;
+        std::io::stderr().as_fd().try_clone_to_owned().map(Stdio::from)
+    };
+    #[cfg(windows)]
+    let stderr = {
+        use std::os::windows::io::AsHandle;
+        std::io::stderr().as_handle().try_clone_to_owned().map(Stdio::from)
+    };
+    #[cfg(not(any(unix, windows)))]
+    let stderr = std::io::Result::<Stdio>::Err(std::io::ErrorKind::Unsupported.into());
+    stderr.unwrap_or_else(|_| Stdio::null())
+}
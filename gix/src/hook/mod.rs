@@ -0,0 +1,149 @@
+//! Run hooks, programs in the hooks directory which are invoked by operations like committing or checking out,
+//! similar to `git hook run`.
+//!
+//! Hooks are looked up in `core.hooksPath`, or in the `hooks` directory of the repository, and can be disabled or replaced
+//! with in-process implementations using [`Hooks`], set with [`open::Options::hooks()`](crate::open::Options::hooks())
+//! or [`Repository::set_hooks()`](crate::Repository::set_hooks()).
+//!
+//! The following hooks are run:
+//!
+//! * `pre-commit`, `prepare-commit-msg`, `commit-msg` and `post-commit` when [committing the index](crate::Repository::commit_index()),
+//!   while commits created with [`Repository::commit()`](crate::Repository::commit()) and similar run no hooks.
+//! * `reference-transaction` whenever references are [edited](crate::Repository::edit_references()), also while fetching.
+//! * `post-checkout` after [switching branches](crate::Repository::switch()), adding worktrees and cloning.
+use std::{collections::BTreeMap, ffi::OsString, path::Path, process::ExitStatus, sync::Arc};
+
+mod function;
+
+/// The error produced by a [`Hook`] to indicate it failed.
+pub type HookError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A hook that runs in-process instead of a program in the hooks directory.
+pub trait Hook: Send + Sync {
+    /// Run the hook as described by `invocation`, and return an error to indicate failure, which aborts the operation
+    /// that invoked it if the hook is allowed to do so.
+    fn run(&self, invocation: &Invocation<'_>) -> Result<(), HookError>;
+}
+
+impl<F> Hook for F
+where
+    F: Fn(&Invocation<'_>) -> Result<(), HookError> + Send + Sync,
+{
+    fn run(&self, invocation: &Invocation<'_>) -> Result<(), HookError> {
+        self(invocation)
+    }
+}
+
+/// Everything a hook receives when it runs.
+#[derive(Debug, Clone, Copy)]
+pub struct Invocation<'a> {
+    /// The name of the hook, like `pre-commit`.
+    pub name: &'a str,
+    /// The arguments passed to the hook.
+    pub args: &'a [OsString],
+    /// The data passed to the hook on standard input, which is empty for hooks that don't receive any.
+    pub stdin: &'a [u8],
+    /// The environment variables that are set for the hook, like `GIT_DIR` or `GIT_INDEX_FILE`.
+    pub env: &'a [(OsString, OsString)],
+    /// The directory the hook runs in, the root of the worktree, or the `.git` directory in bare repositories.
+    pub cwd: &'a Path,
+}
+
+/// Determine which hooks are run.
+///
+/// By default, all programs in the hooks directory are run.
+#[derive(Clone)]
+pub struct Hooks {
+    /// If `false`, programs in the hooks directory are never run, which is like passing `--no-verify` to `git commit`
+    /// but affects all hooks. In-process hooks are still run.
+    pub run_programs: bool,
+    in_process: BTreeMap<String, Arc<dyn Hook>>,
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Hooks {
+            run_programs: true,
+            in_process: Default::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hooks")
+            .field("run_programs", &self.run_programs)
+            .field("in_process", &self.in_process.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// The hooks that are run when creating a commit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum CommitHooks {
+    /// Run no hook, as commits are created by a program and not on behalf of a user.
+    None,
+    /// Run `prepare-commit-msg` and `post-commit`, like `git commit --no-verify`.
+    NoVerify,
+    /// Run `prepare-commit-msg`, `commit-msg` and `post-commit`, with `pre-commit` run by the caller before the tree
+    /// was written.
+    Verify,
+}
+
+/// Lifecycle
+impl Hooks {
+    /// Return an instance that doesn't run any hook.
+    pub fn disabled() -> Self {
+        Hooks {
+            run_programs: false,
+            in_process: Default::default(),
+        }
+    }
+}
+
+/// Builder
+impl Hooks {
+    /// Run `hook` whenever the hook with `name`, like `pre-commit`, is to be run, instead of the program of the same name
+    /// in the hooks directory.
+    pub fn with_hook(mut self, name: impl Into<String>, hook: impl Hook + 'static) -> Self {
+        self.in_process.insert(name.into(), Arc::new(hook));
+        self
+    }
+}
+
+/// Access
+impl Hooks {
+    /// Return the in-process hook for `name`, if there is one.
+    pub fn in_process(&self, name: &str) -> Option<&dyn Hook> {
+        self.in_process.get(name).map(|hook| &**hook as &dyn Hook)
+    }
+}
+
+/// The error returned by [`Repository::run_hook()`](crate::Repository::run_hook()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    HooksPath(#[from] gix_config::path::interpolate::Error),
+    #[error(transparent)]
+    CommandContext(#[from] crate::config::command_context::Error),
+    #[error("Could not run the '{name}' hook at '{}'", path.display())]
+    Spawn {
+        name: String,
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("The '{name}' hook failed with {status}")]
+    Failed { name: String, status: ExitStatus },
+    #[error("The in-process '{name}' hook failed")]
+    InProcess {
+        name: String,
+        #[source]
+        source: HookError,
+    },
+    #[error("Could not write the commit message to '{}' for use by hooks", path.display())]
+    MessageFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
@@ -112,6 +112,8 @@ pub use gix_features::{
 };
 pub use gix_fs as fs;
 pub use gix_glob as glob;
+#[cfg(feature = "gpg")]
+pub use gix_gpg as gpg;
 pub use gix_hash as hash;
 pub use gix_hashtable as hashtable;
 #[cfg(feature = "excludes")]
@@ -120,6 +122,8 @@ pub use gix_ignore as ignore;
 #[cfg(feature = "index")]
 pub use gix_index as index;
 pub use gix_lock as lock;
+#[cfg(any(feature = "apply", feature = "worktree-mutation"))]
+pub use gix_merge as merge;
 #[cfg(feature = "credentials")]
 pub use gix_negotiate as negotiate;
 pub use gix_object as objs;
@@ -175,12 +179,21 @@ pub use types::{
 #[cfg(feature = "attributes")]
 pub use types::{Pathspec, PathspecDetached, Submodule};
 
+///
+#[cfg(feature = "apply")]
+pub mod am;
+///
+#[cfg(feature = "apply")]
+pub mod apply;
 ///
 pub mod clone;
 pub mod commit;
 ///
 #[cfg(feature = "dirwalk")]
 pub mod dirwalk;
+///
+#[cfg(feature = "apply")]
+pub mod format_patch;
 pub mod head;
 pub mod id;
 pub mod object;
@@ -328,6 +341,34 @@ pub mod status;
 ///
 pub mod shallow;
 
+#[cfg(feature = "worktree-mutation")]
+pub mod sparse_checkout;
+
+#[cfg(feature = "worktree-mutation")]
+pub mod reset;
+
+#[cfg(feature = "worktree-mutation")]
+pub mod switch;
+
+#[cfg(feature = "worktree-mutation")]
+pub mod restore;
+#[cfg(all(feature = "worktree-mutation", feature = "revision", feature = "tree-editor"))]
+pub mod sequencer;
+
+#[cfg(all(feature = "worktree-mutation", feature = "dirwalk", feature = "tree-editor"))]
+pub mod stash;
+
+#[cfg(feature = "fsck")]
+pub mod fsck;
+
+#[cfg(feature = "hooks")]
+pub mod hook;
+
+#[cfg(feature = "maintenance")]
+pub mod reflog;
+#[cfg(feature = "maintenance")]
+pub mod repack;
+
 ///
 pub mod discover;
 
@@ -239,6 +239,62 @@ pub mod diff {
                 Operation::SourceOrDestinationIsBinary => Ok(None),
             }
         }
+
+        /// Count the amount of removed and inserted lines, or obtain the sizes of the old and new version if one of them
+        /// is binary, as needed for `git diff --stat` and `git diff --numstat`.
+        pub fn stat_counts(&mut self) -> Result<gix_diff::stat::Counts, gix_diff::blob::platform::prepare_diff::Error> {
+            self.resource_cache.options.skip_internal_diff_if_external_is_configured = false;
+
+            let prep = self.resource_cache.prepare_diff()?;
+            Ok(gix_diff::stat::Counts::from_prepared_diff(&prep).expect("we disabled external commands"))
+        }
+
+        /// Perform a word diff between the old and the new version of a blob like `git diff --word-diff`, splitting words
+        /// with `tokenizer` and writing the result to `out` in the given `mode`.
+        ///
+        /// Nothing is written if one of the resources is binary, which can be determined by introspecting the outcome.
+        /// Use [`Repository::diff_word_tokenizer()`](crate::Repository::diff_word_tokenizer()) to obtain a `tokenizer`
+        /// configured like `git` would.
+        pub fn words(
+            &mut self,
+            tokenizer: &gix_diff::blob::words::Tokenizer,
+            mode: gix_diff::blob::words::Mode,
+            out: impl std::io::Write,
+        ) -> Result<gix_diff::blob::platform::prepare_diff::Outcome<'_>, words::Error> {
+            self.resource_cache.options.skip_internal_diff_if_external_is_configured = false;
+
+            let prep = self.resource_cache.prepare_diff()?;
+            match prep.operation {
+                Operation::InternalDiff { algorithm } => {
+                    gix_diff::blob::words::write(
+                        algorithm,
+                        prep.old.data.as_slice().unwrap_or_default(),
+                        prep.new.data.as_slice().unwrap_or_default(),
+                        tokenizer,
+                        mode,
+                        out,
+                    )?;
+                }
+                Operation::ExternalCommand { .. } => {
+                    unreachable!("we disabled that")
+                }
+                Operation::SourceOrDestinationIsBinary => {}
+            }
+            Ok(prep)
+        }
+    }
+
+    ///
+    pub mod words {
+        /// The error returned by [Platform::words()](super::Platform::words()).
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error(transparent)]
+            PrepareDiff(#[from] gix_diff::blob::platform::prepare_diff::Error),
+            #[error("Could not write word diff")]
+            Io(#[from] std::io::Error),
+        }
     }
 }
 
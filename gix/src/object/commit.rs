@@ -157,14 +157,32 @@ impl<'repo> Commit<'repo> {
         }
     }
 
-    /// Extracts the PGP signature and the data that was used to create the signature, or `None` if it wasn't signed.
-    // TODO: make it possible to verify the signature, probably by wrapping `SignedData`. It's quite some work to do it properly.
+    /// Extracts the signature and the data that was used to create the signature, or `None` if it wasn't signed.
+    ///
+    /// Use [`verify_signature()`](Self::verify_signature()) to verify it.
     pub fn signature(
         &self,
     ) -> Result<Option<(std::borrow::Cow<'_, BStr>, gix_object::commit::SignedData<'_>)>, gix_object::decode::Error>
     {
         gix_object::CommitRefIter::signature(&self.data)
     }
+
+    /// Verify the signature of this commit similar to `git verify-commit`, or return `None` if it isn't signed.
+    ///
+    /// See [`Repository::verify_signature()`](crate::Repository::verify_signature()) for details, with the validity of
+    /// SSH keys being checked at the time of the committer.
+    #[cfg(feature = "gpg")]
+    pub fn verify_signature(&self) -> Result<Option<gix_gpg::Verification>, crate::object::verify_signature::Error> {
+        let Some((signature, signed_data)) = self.signature()? else {
+            return Ok(None);
+        };
+        let time = self.committer()?.time.seconds;
+        Ok(Some(self.repo.verify_signature(
+            signed_data.to_bstring().as_ref(),
+            signature.as_ref(),
+            Some(time),
+        )?))
+    }
 }
 
 impl<'r> std::fmt::Debug for Commit<'r> {
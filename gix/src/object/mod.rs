@@ -20,6 +20,21 @@ mod tag;
 ///
 pub mod tree;
 
+///
+#[cfg(feature = "gpg")]
+pub mod verify_signature {
+    /// The error returned by [Commit::verify_signature()](crate::Commit::verify_signature()) and
+    /// [Tag::verify_signature()](crate::Tag::verify_signature()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The object could not be decoded to obtain its signature")]
+        Decode(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        Verify(#[from] crate::repository::verify_signature::Error),
+    }
+}
+
 ///
 pub mod try_into {
     #[derive(thiserror::Error, Debug)]
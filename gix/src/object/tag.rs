@@ -23,6 +23,26 @@ impl<'repo> Tag<'repo> {
     pub fn tagger(&self) -> Result<Option<gix_actor::SignatureRef<'_>>, gix_object::decode::Error> {
         gix_object::TagRefIter::from_bytes(&self.data).tagger()
     }
+
+    /// Verify the signature at the end of the message of this tag similar to `git verify-tag`, or return `None` if
+    /// it isn't signed.
+    ///
+    /// See [`Repository::verify_signature()`](crate::Repository::verify_signature()) for details, with the validity of
+    /// SSH keys being checked at the time of the tagger.
+    #[cfg(feature = "gpg")]
+    pub fn verify_signature(&self) -> Result<Option<gix_gpg::Verification>, crate::object::verify_signature::Error> {
+        use crate::bstr::ByteSlice;
+        let Some(start) = gix_gpg::signature_start(&self.data) else {
+            return Ok(None);
+        };
+        let time = self.tagger()?.map(|tagger| tagger.time.seconds);
+        let (signed_data, signature) = self.data.split_at(start);
+        Ok(Some(self.repo.verify_signature(
+            signed_data,
+            signature.as_bstr(),
+            time,
+        )?))
+    }
 }
 
 /// Remove Lifetime
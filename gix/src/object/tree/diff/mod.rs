@@ -164,6 +164,40 @@ impl<'a, 'repo> Platform<'a, 'repo> {
             lines_removed,
         })
     }
+
+    /// Obtain the changes of each file in the diff between our current and the `other` tree, as needed to display
+    /// them like `git diff --stat` or `git diff --numstat`, or to aggregate them with [`gix_diff::stat::dirstat`].
+    ///
+    /// Changes to trees and submodules are skipped, and rewrites are listed with their source location
+    /// if [rewrite tracking](Self::track_rewrites()) is enabled.
+    /// Note that [paths are tracked](Self::track_path()) automatically.
+    pub fn file_stats(&mut self, other: &Tree<'_>) -> Result<Vec<gix_diff::stat::File>, stats::Error> {
+        let mut resource_cache = self.lhs.repo.diff_resource_cache_for_tree_diff()?;
+        self.track_path();
+
+        let mut out = Vec::new();
+        self.for_each_to_obtain_tree(other, |change| {
+            let source_location = match change.event {
+                change::Event::Rewrite { source_location, .. } => Some(source_location.to_owned()),
+                _ => None,
+            };
+            if let Some(counts) = change
+                .diff(&mut resource_cache)
+                .ok()
+                .and_then(|mut platform| platform.stat_counts().ok())
+            {
+                out.push(gix_diff::stat::File {
+                    location: change.location.to_owned(),
+                    source_location,
+                    counts,
+                });
+            }
+
+            resource_cache.clear_resource_cache_keep_allocation();
+            Ok::<_, std::convert::Infallible>(Action::Continue)
+        })?;
+        Ok(out)
+    }
 }
 
 ///
@@ -37,6 +37,8 @@ pub struct Options {
     pub(crate) open_path_as_is: bool,
     /// Internal to pass an already obtained CWD on to where it may also be used. This avoids the CWD being queried more than once per repo.
     pub(crate) current_dir: Option<PathBuf>,
+    #[cfg(feature = "hooks")]
+    pub(crate) hooks: crate::hook::Hooks,
 }
 
 /// The error returned by [`crate::open()`].
@@ -17,6 +17,8 @@ impl Default for Options {
             api_config_overrides: Vec::new(),
             cli_config_overrides: Vec::new(),
             current_dir: None,
+            #[cfg(feature = "hooks")]
+            hooks: Default::default(),
         }
     }
 }
@@ -142,6 +144,13 @@ impl Options {
         self
     }
 
+    /// Set the `hooks` to run, which by default are all programs in the hooks directory.
+    #[cfg(feature = "hooks")]
+    pub fn hooks(mut self, hooks: crate::hook::Hooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
     /// Open a repository at `path` with the options set so far.
     #[allow(clippy::result_large_err)]
     pub fn open(self, path: impl Into<PathBuf>) -> Result<ThreadSafeRepository, Error> {
@@ -170,6 +179,8 @@ impl gix_sec::trust::DefaultForLevel for Options {
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
                 current_dir: None,
+                #[cfg(feature = "hooks")]
+                hooks: Default::default(),
             },
             gix_sec::Trust::Reduced => Options {
                 object_store_slots: gix_odb::store::init::Slots::Given(32), // limit resource usage
@@ -183,6 +194,8 @@ impl gix_sec::trust::DefaultForLevel for Options {
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
                 current_dir: None,
+                #[cfg(feature = "hooks")]
+                hooks: Default::default(),
             },
         }
     }
@@ -171,6 +171,8 @@ impl ThreadSafeRepository {
             ref api_config_overrides,
             ref cli_config_overrides,
             ref mut current_dir,
+            #[cfg(feature = "hooks")]
+            hooks: _,
         } = options;
         let git_dir_trust = git_dir_trust.expect("trust must be determined by now");
 
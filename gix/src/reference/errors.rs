@@ -17,6 +17,9 @@ pub mod edit {
         LockTimeoutConfiguration(#[from] config::lock_timeout::Error),
         #[error(transparent)]
         ParseCommitterTime(#[from] crate::config::time::Error),
+        #[cfg(feature = "hooks")]
+        #[error(transparent)]
+        Hook(#[from] crate::hook::Error),
     }
 }
 
@@ -0,0 +1,260 @@
+use std::{borrow::Cow, time::SystemTime};
+
+use gix_date::SecondsSinceUnixEpoch;
+use gix_hash::ObjectId;
+use gix_ref::FullNameRef;
+
+use super::{Error, Expiry, Outcome};
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::{cache::util::ApplyLeniency, tree::gc, tree::Gc},
+};
+
+const DAY: SecondsSinceUnixEpoch = 24 * 60 * 60;
+
+/// Expiry values configured with `gc.<pattern>.reflogExpire*`, where `None` means the value isn't set for the pattern.
+struct PatternExpiry {
+    pattern: BString,
+    total: Option<Option<SecondsSinceUnixEpoch>>,
+    unreachable: Option<Option<SecondsSinceUnixEpoch>>,
+}
+
+type CommitGraph<'find, 'cache> = gix_revwalk::Graph<'find, 'cache, gix_revwalk::graph::Commit<()>>;
+
+impl crate::Repository {
+    /// Return the points in time before which entries in the reference log of `name` expire, relative to `now`.
+    ///
+    /// They are configured with `gc.reflogExpire` and `gc.reflogExpireUnreachable`, which default to 90 and 30 days
+    /// respectively. Values in `gc.<pattern>.reflogExpire*` sections take precedence for references whose full name
+    /// matches `<pattern>`, with the first matching pattern winning.
+    pub fn reflog_expiry(&self, name: &FullNameRef, now: SystemTime) -> Result<Expiry, Error> {
+        let patterns = self.reflog_expiry_patterns(now)?;
+        self.reflog_expiry_inner(name, &patterns, now)
+    }
+
+    /// Remove expired entries from the reference logs of all references and of the `HEAD` of each worktree, like
+    /// `git reflog expire --all`, with expiry times determined by [`reflog_expiry()`](Self::reflog_expiry()) relative to `now`.
+    ///
+    /// Note that the references themselves are never changed, even if all entries of their log are removed.
+    pub fn expire_reflogs(&self, now: SystemTime) -> Result<Outcome, Error> {
+        let patterns = self.reflog_expiry_patterns(now)?;
+        let cache = self.commit_graph_if_enabled()?;
+        let mut graph: CommitGraph<'_, '_> = self.revision_graph(cache.as_ref());
+        let (file_lock_fail, _) = self.config.lock_timeout()?;
+
+        let mut references = Vec::new();
+        let iter = self.refs.iter()?;
+        for reference in iter.all().map_err(|source| Error::Io {
+            path: self.common_dir().join("refs"),
+            source,
+        })? {
+            let reference = reference?;
+            let tip = match reference.target {
+                gix_ref::Target::Object(id) => self.peel_to_commit(id)?,
+                gix_ref::Target::Symbolic(_) => None,
+            };
+            references.push((reference.name, tip));
+        }
+
+        let mut out = Outcome::default();
+        let mut buf = Vec::new();
+        for (name, tip) in &references {
+            let expiry = self.reflog_expiry_inner(name.as_ref(), &patterns, now)?;
+            let removed = expire_reflog(
+                self,
+                name.as_ref(),
+                expiry,
+                tip.iter().copied(),
+                &mut graph,
+                &mut buf,
+                file_lock_fail,
+            )?;
+            out.removed_entries += removed;
+            out.rewritten_reflogs += usize::from(removed != 0);
+        }
+
+        let main = self.main_repo()?;
+        let mut repos = main
+            .worktrees()
+            .map_err(|source| Error::Io {
+                path: main.common_dir().join("worktrees"),
+                source,
+            })?
+            .into_iter()
+            .map(crate::worktree::Proxy::into_repo_with_possibly_inaccessible_worktree)
+            .collect::<Result<Vec<_>, _>>()?;
+        repos.insert(0, main);
+        let head_name: &FullNameRef = "HEAD".try_into().expect("valid");
+        let all_tips: Vec<_> = references.iter().filter_map(|(_, tip)| *tip).collect();
+        for repo in &repos {
+            let expiry = self.reflog_expiry_inner(head_name, &patterns, now)?;
+            let head_tip = repo.head()?.id().map(crate::Id::detach);
+            let head_tip = match head_tip {
+                Some(id) => self.peel_to_commit(id)?,
+                None => None,
+            };
+            let tips = all_tips.iter().copied().chain(head_tip);
+            let removed = expire_reflog(repo, head_name, expiry, tips, &mut graph, &mut buf, file_lock_fail)?;
+            out.removed_entries += removed;
+            out.rewritten_reflogs += usize::from(removed != 0);
+        }
+        Ok(out)
+    }
+
+    fn reflog_expiry_inner(
+        &self,
+        name: &FullNameRef,
+        patterns: &[PatternExpiry],
+        now: SystemTime,
+    ) -> Result<Expiry, Error> {
+        let default = |key: &'static gc::Expiry, days: SecondsSinceUnixEpoch| -> Result<_, Error> {
+            Ok(match self.config.resolved.string(key) {
+                Some(value) => self.parse_expiry(key, value, now)?,
+                None => None,
+            }
+            .unwrap_or(Some(seconds(now) - days * DAY)))
+        };
+        let matching = patterns.iter().find(|entry| {
+            gix_glob::wildmatch(
+                entry.pattern.as_bstr(),
+                name.as_bstr(),
+                gix_glob::wildmatch::Mode::empty(),
+            )
+        });
+        Ok(Expiry {
+            total: match matching.and_then(|entry| entry.total) {
+                Some(total) => total,
+                None => default(&Gc::REFLOG_EXPIRE, 90)?,
+            },
+            unreachable: match matching.and_then(|entry| entry.unreachable) {
+                Some(unreachable) => unreachable,
+                None => default(&Gc::REFLOG_EXPIRE_UNREACHABLE, 30)?,
+            },
+        })
+    }
+
+    /// Return all `gc.<pattern>` sections with expiry settings, in order of their first appearance.
+    fn reflog_expiry_patterns(&self, now: SystemTime) -> Result<Vec<PatternExpiry>, Error> {
+        let mut filter = self.filter_config_section();
+        let mut out = Vec::<PatternExpiry>::new();
+        let Some(sections) = self.config.resolved.sections_by_name_and_filter("gc", &mut filter) else {
+            return Ok(out);
+        };
+        for section in sections {
+            let Some(pattern) = section.header().subsection_name() else {
+                continue;
+            };
+            let total = match section.value(Gc::REFLOG_EXPIRE.name) {
+                Some(value) => self.parse_expiry(&Gc::REFLOG_EXPIRE, value, now)?,
+                None => None,
+            };
+            let unreachable = match section.value(Gc::REFLOG_EXPIRE_UNREACHABLE.name) {
+                Some(value) => self.parse_expiry(&Gc::REFLOG_EXPIRE_UNREACHABLE, value, now)?,
+                None => None,
+            };
+            match out.iter_mut().find(|entry| entry.pattern == pattern) {
+                Some(entry) => {
+                    entry.total = total.or(entry.total);
+                    entry.unreachable = unreachable.or(entry.unreachable);
+                }
+                None => out.push(PatternExpiry {
+                    pattern: pattern.to_owned(),
+                    total,
+                    unreachable,
+                }),
+            }
+        }
+        Ok(out)
+    }
+
+    /// Parse `value`, and return `None` if it's invalid and we are lenient.
+    fn parse_expiry(
+        &self,
+        key: &'static gc::Expiry,
+        value: Cow<'_, BStr>,
+        now: SystemTime,
+    ) -> Result<Option<Option<SecondsSinceUnixEpoch>>, Error> {
+        Ok(key
+            .try_into_expiry(value, now)
+            .map(Some)
+            .with_leniency(self.options.lenient_config)?)
+    }
+
+    fn peel_to_commit(&self, id: ObjectId) -> Result<Option<ObjectId>, Error> {
+        if !self.has_object(id) {
+            return Ok(None);
+        }
+        let object = self.find_object(id)?.peel_tags_to_end()?;
+        Ok((object.kind == gix_object::Kind::Commit).then_some(object.id))
+    }
+}
+
+/// Remove the entries of the reflog of `name` in `repo` that expired according to `expiry`, with `tips` used for
+/// reachability checks. Return the amount of removed entries.
+fn expire_reflog(
+    repo: &crate::Repository,
+    name: &FullNameRef,
+    expiry: Expiry,
+    tips: impl IntoIterator<Item = ObjectId>,
+    graph: &mut CommitGraph<'_, '_>,
+    buf: &mut Vec<u8>,
+    lock_mode: gix_lock::acquire::Fail,
+) -> Result<usize, Error> {
+    let Some(lines) = repo.refs.reflog_iter(name, buf)? else {
+        return Ok(0);
+    };
+    let lines = lines
+        .map(|line| line.map(|line| line.to_owned()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let is_older = |line: &gix_ref::log::Line, expire: Option<SecondsSinceUnixEpoch>| {
+        expire.map_or(false, |expire| line.signature.time.seconds < expire)
+    };
+
+    let mut reachable = None;
+    let mut tips = Some(tips);
+    let mut kept = Vec::with_capacity(lines.len());
+    for line in &lines {
+        let is_expired = if is_older(line, expiry.total) {
+            true
+        } else if is_older(line, expiry.unreachable) && !line.new_oid.is_null() {
+            if reachable.is_none() {
+                reachable = Some(reachable_commits(graph, tips.take().expect("taken once"))?);
+            }
+            !reachable.as_ref().expect("just set").contains(&line.new_oid)
+        } else {
+            false
+        };
+        if !is_expired {
+            kept.push(line.clone());
+        }
+    }
+
+    let removed = lines.len() - kept.len();
+    if removed != 0 {
+        repo.refs.reflog_rewrite(name, &kept, lock_mode)?;
+    }
+    Ok(removed)
+}
+
+/// Return all commits reachable from `tips`.
+fn reachable_commits(
+    graph: &mut CommitGraph<'_, '_>,
+    tips: impl IntoIterator<Item = ObjectId>,
+) -> Result<gix_hashtable::HashSet<ObjectId>, Error> {
+    let mut seen = gix_hashtable::HashSet::default();
+    let mut queue: Vec<_> = tips.into_iter().collect();
+    while let Some(id) = queue.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        if let Some(commit) = graph.get_or_insert_commit(id, |_| {})? {
+            queue.extend(commit.parents.iter().copied());
+        }
+    }
+    Ok(seen)
+}
+
+fn seconds(time: SystemTime) -> SecondsSinceUnixEpoch {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as SecondsSinceUnixEpoch)
+}
@@ -0,0 +1,60 @@
+//! Maintenance of reference logs, like `git reflog expire`.
+use gix_date::SecondsSinceUnixEpoch;
+
+mod function;
+
+/// The points in time before which entries of a reference log expire, as obtained by
+/// [`Repository::reflog_expiry()`](crate::Repository::reflog_expiry()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Expiry {
+    /// Entries older than this time, in seconds since the Unix epoch, are removed, or never if `None`.
+    pub total: Option<SecondsSinceUnixEpoch>,
+    /// Entries older than this time, in seconds since the Unix epoch, are removed if their new value isn't reachable from the
+    /// tip of the reference anymore, or never if `None`. For `HEAD`, reachability from any reference is sufficient.
+    pub unreachable: Option<SecondsSinceUnixEpoch>,
+}
+
+/// The result of [`Repository::expire_reflogs()`](crate::Repository::expire_reflogs()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of reference logs that were rewritten as entries were removed from them.
+    pub rewritten_reflogs: usize,
+    /// The amount of entries that were removed across all reference logs.
+    pub removed_entries: usize,
+}
+
+/// The error returned by [`Repository::expire_reflogs()`](crate::Repository::expire_reflogs()) and
+/// [`Repository::reflog_expiry()`](crate::Repository::reflog_expiry()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Expiry(#[from] crate::config::key::GenericErrorWithValue),
+    #[error(transparent)]
+    LockTimeout(#[from] crate::config::lock_timeout::Error),
+    #[error("Could not access '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error(transparent)]
+    OpenPackedRefs(#[from] gix_ref::packed::buffer::open::Error),
+    #[error(transparent)]
+    IterReference(#[from] gix_ref::file::iter::loose_then_packed::Error),
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    ReadReflog(#[from] gix_ref::file::log::Error),
+    #[error(transparent)]
+    DecodeReflog(#[from] gix_ref::file::log::iter::decode::Error),
+    #[error(transparent)]
+    RewriteReflog(#[from] gix_ref::file::log::rewrite::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    CommitGraph(#[from] crate::repository::commit_graph_if_enabled::Error),
+    #[error(transparent)]
+    FindCommit(#[from] gix_revwalk::graph::get_or_insert_default::Error),
+}
@@ -147,6 +147,10 @@ pub mod prepare {
         MissingRefSpecs,
         #[error(transparent)]
         RefMap(#[from] crate::remote::ref_map::Error),
+        #[error(transparent)]
+        PromisorConfig(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        PartialCloneFilter(#[from] crate::config::remote::partial_clone_filter::Error),
     }
 
     impl gix_protocol::transport::IsSpuriousError for Error {
@@ -186,6 +190,15 @@ where
             return Err(prepare::Error::MissingRefSpecs);
         }
         let ref_map = self.ref_map_inner(progress, options).await?;
+        let repo = self.remote.repo;
+        let promisor = match self.remote.name().and_then(remote::Name::as_symbol) {
+            Some(name) => repo.is_promisor_remote(name.into())?,
+            None => false,
+        };
+        let filter = match self.remote.name().and_then(remote::Name::as_symbol) {
+            Some(name) if promisor => repo.partial_clone_filter(name.into())?,
+            _ => None,
+        };
         Ok(Prepare {
             con: Some(self),
             ref_map,
@@ -193,6 +206,8 @@ where
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
             shallow: Default::default(),
+            filter,
+            promisor,
         })
     }
 }
@@ -224,6 +239,8 @@ where
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
     shallow: remote::fetch::Shallow,
+    filter: Option<remote::fetch::Filter>,
+    promisor: bool,
 }
 
 /// Builder
@@ -265,6 +282,15 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// Ask the remote to omit the objects described by `filter` from the pack, or to send all objects if `None`.
+    ///
+    /// The received pack is marked as coming from a *promisor remote* if a `filter` is set.
+    /// Defaults to the value of `remote.<name>.partialCloneFilter` if the remote is a promisor remote.
+    pub fn with_filter(mut self, filter: Option<remote::fetch::Filter>) -> Self {
+        self.filter = filter;
+        self
+    }
 }
 
 impl<'remote, 'repo, T> Drop for Prepare<'remote, 'repo, T>
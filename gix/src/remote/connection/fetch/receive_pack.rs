@@ -126,6 +126,15 @@ where
             arguments.use_include_tag();
         }
         let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, &self.shallow, repo)?;
+        if let Some(filter) = &self.filter {
+            if !arguments.can_use_filter() {
+                return Err(Error::MissingServerFeature {
+                    feature: "filter",
+                    description: "Partial clones need server support to omit objects from the pack",
+                });
+            }
+            arguments.filter(&filter.to_string());
+        }
 
         if self.ref_map.object_hash != repo.object_hash() {
             return Err(Error::IncompatibleObjectHash {
@@ -151,6 +160,8 @@ where
             let mut r = repo.clone();
             // assure that checking for unknown server refs doesn't trigger ODB refreshes.
             r.objects.refresh = RefreshMode::Never;
+            // objects we don't have must not be fetched from a promisor remote while negotiating.
+            r.objects.promisor = None;
             // we cache everything of importance in the graph and thus don't need an object cache.
             r.objects.unset_object_cache();
             r
@@ -177,7 +188,7 @@ where
                 remote_ref_target_known,
             } => {
                 negotiate::add_wants(
-                    repo,
+                    &graph_repo,
                     &mut arguments,
                     &self.ref_map,
                     remote_ref_target_known,
@@ -308,6 +319,11 @@ where
                     {
                         reader = rd;
                     }
+                    if self.promisor || self.filter.is_some() {
+                        if let Some(data_path) = &res.data_path {
+                            write_promisor_file(&data_path.with_extension("promisor"), &self.ref_map)?;
+                        }
+                    }
                     Some(res)
                 } else {
                     None
@@ -369,6 +385,25 @@ where
     }
 }
 
+/// Like `git`, list the tips we received the pack for in the `.promisor` file at `path`.
+fn write_promisor_file(path: &std::path::Path, ref_map: &fetch::RefMap) -> Result<(), Error> {
+    let mut buf = Vec::new();
+    for mapping in &ref_map.mappings {
+        if let Some(id) = mapping.remote.as_id() {
+            buf.extend_from_slice(id.to_hex().to_string().as_bytes());
+            if let Some(name) = mapping.remote.as_name() {
+                buf.push(b' ');
+                buf.extend_from_slice(name);
+            }
+            buf.push(b'\n');
+        }
+    }
+    std::fs::write(path, buf).map_err(|err| Error::WritePromisorFile {
+        path: path.to_owned(),
+        source: err,
+    })
+}
+
 fn acquire_shallow_lock(repo: &Repository) -> Result<gix_lock::File, Error> {
     gix_lock::File::acquire_to_update_resource(repo.shallow_file(), gix_lock::acquire::Fail::Immediately, None)
         .map_err(Into::into)
@@ -264,6 +264,7 @@ pub(crate) fn update(
                         log: LogChange {
                             mode: RefLog::AndReference,
                             force_create_reflog: false,
+                            log_symbolic_changes: false,
                             message: message.compose(reflog_message),
                         },
                         expected: previous_value,
@@ -322,7 +323,7 @@ pub(crate) fn update(
                 .config
                 .lock_timeout()
                 .map_err(crate::reference::edit::Error::from)?;
-            repo.refs
+            let transaction = repo.refs
                 .transaction()
                 .packed_refs(
                     match write_packed_refs {
@@ -332,9 +333,8 @@ pub(crate) fn update(
                     }
                 )
                 .prepare(edits, file_lock_fail, packed_refs_lock_fail)
-                .map_err(crate::reference::edit::Error::from)?
-                .commit(repo.committer().transpose().map_err(|err| update::Error::EditReferences(crate::reference::edit::Error::ParseCommitterTime(err)))?)
-                .map_err(crate::reference::edit::Error::from)?
+                .map_err(crate::reference::edit::Error::from)?;
+            repo.commit_ref_transaction(transaction)?
         }
         fetch::DryRun::Yes => edits,
     };
@@ -293,6 +293,7 @@ mod update {
                     log: LogChange {
                         mode: RefLog::AndReference,
                         force_create_reflog: false,
+                        log_symbolic_changes: false,
                         message: "action: change unborn ref".into(),
                     },
                     expected: PreviousValue::MustExistAndMatch(Target::Symbolic(
@@ -337,6 +338,7 @@ mod update {
                     log: LogChange {
                         mode: RefLog::AndReference,
                         force_create_reflog: false,
+                        log_symbolic_changes: false,
                         message: "action: change unborn ref".into(),
                     },
                     expected: PreviousValue::MustExistAndMatch(Target::Symbolic(
@@ -385,6 +387,7 @@ mod update {
                     log: LogChange {
                         mode: RefLog::AndReference,
                         force_create_reflog: false,
+                        log_symbolic_changes: false,
                         message: "action: storing head".into(),
                     },
                     expected: PreviousValue::ExistingMustMatch(target.clone()),
@@ -478,6 +481,7 @@ mod update {
                         log: LogChange {
                             mode: RefLog::AndReference,
                             force_create_reflog: false,
+                            log_symbolic_changes: false,
                             message: "action: storing head".into(),
                         },
                         expected: PreviousValue::ExistingMustMatch(Target::Symbolic(
@@ -516,6 +520,7 @@ mod update {
                         log: LogChange {
                             mode: RefLog::AndReference,
                             force_create_reflog: false,
+                            log_symbolic_changes: false,
                             message: "action: no update will be performed".into(),
                         },
                         expected: PreviousValue::MustExistAndMatch(Target::Symbolic(
@@ -541,6 +546,7 @@ mod update {
                         log: LogChange {
                             mode: RefLog::AndReference,
                             force_create_reflog: false,
+                            log_symbolic_changes: false,
                             message: "action: no update will be performed".into(),
                         },
                         expected: PreviousValue::MustExistAndMatch(Target::Object(hex_to_id(
@@ -566,6 +572,7 @@ mod update {
                         log: LogChange {
                             mode: RefLog::AndReference,
                             force_create_reflog: false,
+                            log_symbolic_changes: false,
                             message: "action: no update will be performed".into(),
                         },
                         expected: PreviousValue::MustExistAndMatch(Target::Symbolic(
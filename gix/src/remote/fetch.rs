@@ -108,6 +108,90 @@ impl Shallow {
     }
 }
 
+/// Describe which objects the remote should omit when sending a pack, which makes the receiving repository a *partial clone*.
+///
+/// Objects that were omitted are fetched from the *promisor remote* once they are needed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Filter {
+    /// Omit all blobs, `blob:none`.
+    BlobNone,
+    /// Omit all blobs whose size is at least the given amount of bytes, `blob:limit=<n>`.
+    BlobLimit(u64),
+    /// Omit all trees and blobs whose depth from the root tree is at least the given depth, `tree:<depth>`.
+    ///
+    /// With a depth of `0`, only commits and tags are sent.
+    TreeDepth(u64),
+    /// Omit all blobs that aren't required for a sparse checkout that uses the sparse-checkout patterns
+    /// in the blob the given revision resolves to on the remote, `sparse:oid=<blob-ish>`.
+    SparseOid(crate::bstr::BString),
+}
+
+///
+pub mod filter {
+    ///
+    pub mod parse {
+        use crate::bstr::BString;
+
+        /// The error returned by [`Filter::from_bytes()`](crate::remote::fetch::Filter::from_bytes()).
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("Unknown or unsupported object filter specification: '{spec}'")]
+            Unknown { spec: BString },
+            #[error("The value in object filter specification '{spec}' must be a positive integer with an optional k, m or g suffix")]
+            InvalidNumber { spec: BString },
+        }
+    }
+}
+
+impl Filter {
+    /// Parse `spec` like `blob:none` or `blob:limit=1m` as `git clone --filter` would.
+    pub fn from_bytes(spec: &crate::bstr::BStr) -> Result<Self, filter::parse::Error> {
+        use filter::parse::Error;
+        let number =
+            |value: &[u8]| parse_number_with_unit(value).ok_or_else(|| Error::InvalidNumber { spec: spec.to_owned() });
+        Ok(if spec == "blob:none" {
+            Filter::BlobNone
+        } else if let Some(limit) = spec.strip_prefix(b"blob:limit=") {
+            Filter::BlobLimit(number(limit)?)
+        } else if let Some(depth) = spec.strip_prefix(b"tree:") {
+            Filter::TreeDepth(number(depth)?)
+        } else if let Some(rev) = spec.strip_prefix(b"sparse:oid=").filter(|rev| !rev.is_empty()) {
+            Filter::SparseOid(rev.into())
+        } else {
+            return Err(Error::Unknown { spec: spec.to_owned() });
+        })
+    }
+}
+
+fn parse_number_with_unit(value: &[u8]) -> Option<u64> {
+    let (digits, factor) = match value.last()?.to_ascii_lowercase() {
+        b'k' => (&value[..value.len() - 1], 1024),
+        b'm' => (&value[..value.len() - 1], 1024 * 1024),
+        b'g' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    std::str::from_utf8(digits)
+        .ok()?
+        .parse::<u64>()
+        .ok()?
+        .checked_mul(factor)
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Filter::BlobNone => f.write_str("blob:none"),
+            Filter::BlobLimit(limit) => write!(f, "blob:limit={limit}"),
+            Filter::TreeDepth(depth) => write!(f, "tree:{depth}"),
+            Filter::SparseOid(rev) => write!(f, "sparse:oid={rev}"),
+        }
+    }
+}
+
 /// Information about the relationship between our refspecs, and remote references with their local counterparts.
 #[derive(Default, Debug, Clone)]
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
@@ -0,0 +1,744 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_features::progress::{Count, Progress};
+use gix_hash::ObjectId;
+use gix_object::{tree::EntryKind, FindExt, Kind};
+use gix_pack::{data::output, Find};
+
+use super::{mtimes, Error, Mode, Options, Outcome};
+use crate::config::{
+    cache::util::ApplyLeniency,
+    tree::{Gc, Pack},
+};
+
+/// An existing pack in the object database.
+struct ExistingPack {
+    index: gix_pack::index::File,
+    /// The modification time of the pack data file in seconds since the Unix epoch.
+    mtime: u32,
+    /// If `true`, the pack has a `.keep` file and must not be touched.
+    is_kept: bool,
+    /// If `true`, the pack has a `.promisor` file as its objects were received from a promisor remote.
+    is_promisor: bool,
+    /// The modification times of all objects in index order if this is a cruft pack.
+    mtimes: Option<Vec<u32>>,
+}
+
+impl ExistingPack {
+    fn contains(&self, id: &gix_hash::oid) -> bool {
+        self.index.lookup(id).is_some()
+    }
+}
+
+/// An unreachable object along with the modification time that determines when it expires.
+#[derive(Copy, Clone)]
+struct Unreachable {
+    mtime: u32,
+    is_loose: bool,
+}
+
+impl crate::Repository {
+    /// Perform all maintenance tasks like `git gc --cruft` does, and drop unreachable objects that expired according to
+    /// `gc.pruneExpire`, which defaults to two weeks.
+    ///
+    /// First, expired entries are removed from all reference logs as configured, see
+    /// [`expire_reflogs()`](Self::expire_reflogs()). Then all references are packed into `packed-refs`.
+    /// Finally, all reachable objects are written into a single pack, while unreachable objects that didn't expire yet
+    /// are written into a cruft pack, so that their modification time is retained.
+    /// Packs with a `.keep` file are left alone, and objects from packs with a `.promisor` file are written into
+    /// a new pack that is marked the same way.
+    pub fn gc(
+        &self,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let now = std::time::SystemTime::now();
+        let expire = match self.config.resolved.string(Gc::PRUNE_EXPIRE) {
+            Some(value) => Gc::PRUNE_EXPIRE
+                .try_into_expiry(value, now)
+                .map(Some)
+                .with_leniency(self.options.lenient_config)?
+                .unwrap_or(None),
+            None => Some(seconds(now) - 2 * 7 * 24 * 60 * 60),
+        };
+
+        let reflog = self.expire_reflogs(now)?;
+        let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout()?;
+        let packed_refs = self
+            .refs
+            .pack_refs(
+                gix_ref::file::pack_refs::Options { all: true, prune: true },
+                &self.objects,
+                file_lock_fail,
+                packed_refs_lock_fail,
+            )?
+            .packed;
+
+        let out = self.repack(
+            Options {
+                mode: Mode::All,
+                delete_redundant: true,
+                cruft: true,
+                expire,
+            },
+            progress,
+            should_interrupt,
+        )?;
+        Ok(Outcome {
+            packed_refs,
+            reflog,
+            ..out
+        })
+    }
+
+    /// Pack objects as configured in `options`, and delete what became redundant if [`Options::delete_redundant`] is set.
+    ///
+    /// Objects are reachable if they can be reached from references, their reflogs, and the `HEAD`, private references
+    /// like `refs/bisect/*` and index of each worktree, including the trees the index caches.
+    /// In a partial clone, objects that are missing but promised by the promisor remote are never fetched, as objects
+    /// referred to by objects in packs with a `.promisor` file are expected to be missing, just like `git` does.
+    ///
+    /// ### Deviation
+    ///
+    /// Neither bitmaps nor multi-pack indices are written, and a multi-pack index is removed if packs are deleted.
+    pub fn repack(
+        &self,
+        options: Options,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        if let Mode::Geometric { factor } = options.mode {
+            if factor < 2 {
+                return Err(Error::InvalidGeometricFactor { factor });
+            }
+        }
+        if options.cruft && !(options.mode == Mode::All && options.delete_redundant) {
+            return Err(Error::CruftWithoutAll);
+        }
+
+        let mut db = (*self.objects).clone();
+        db.prevent_pack_unload();
+        db.ignore_replacements = true;
+        let object_hash = self.object_hash();
+        let objects_dir = db.store_ref().path().to_owned();
+        let pack_dir = objects_dir.join("pack");
+        let packs = existing_packs(&pack_dir, object_hash)?;
+        let loose_db = gix_odb::loose::Store::at(&objects_dir, object_hash);
+        let loose = loose_db.iter().collect::<Result<BTreeSet<_>, _>>()?;
+
+        let reachable = if !matches!(options.mode, Mode::Geometric { .. }) {
+            self.reachable_objects(&db, progress, should_interrupt)?
+        } else {
+            Default::default()
+        };
+
+        let is_in_promisor_pack = |id: &gix_hash::oid| packs.iter().any(|pack| pack.is_promisor && pack.contains(id));
+        let (to_pack, replaced): (BTreeSet<ObjectId>, Vec<usize>) = match options.mode {
+            Mode::Loose => (
+                reachable
+                    .iter()
+                    .filter(|id| loose.contains(*id) && !packs.iter().any(|pack| pack.contains(id)))
+                    .copied()
+                    .collect(),
+                Vec::new(),
+            ),
+            Mode::All => (
+                reachable
+                    .iter()
+                    .filter(|id| {
+                        !packs.iter().any(|pack| pack.is_kept && pack.contains(id)) && !is_in_promisor_pack(id)
+                    })
+                    .copied()
+                    .collect(),
+                (0..packs.len()).filter(|&idx| !packs[idx].is_kept).collect(),
+            ),
+            Mode::Geometric { factor } => {
+                let rolled_up = geometric_rollup(&packs, factor);
+                let mut ids = loose.clone();
+                for &idx in &rolled_up {
+                    ids.extend(packs[idx].index.iter().map(|entry| entry.oid));
+                }
+                (ids, rolled_up)
+            }
+        };
+
+        let mut out = Outcome::default();
+        let write_options = self.pack_write_options()?;
+        let mut new_packs = Vec::new();
+        if !to_pack.is_empty() {
+            let bundle = write_pack(
+                &db,
+                to_pack.iter().copied(),
+                &pack_dir,
+                write_options.clone(),
+                progress,
+                should_interrupt,
+            )?;
+            out.packed_objects = to_pack.len();
+            out.pack.clone_from(&bundle.data_path);
+            new_packs.push(bundle);
+        }
+
+        if options.mode == Mode::All {
+            // Like `git`, keep all objects received from promisor remotes in a pack that is marked as such, as what
+            // they refer to is allowed to be missing.
+            let promisor_objects: BTreeSet<_> = packs
+                .iter()
+                .filter(|pack| pack.is_promisor && !pack.is_kept)
+                .flat_map(|pack| pack.index.iter().map(|entry| entry.oid))
+                .filter(|id| !packs.iter().any(|pack| pack.is_kept && pack.contains(id)))
+                .collect();
+            if !promisor_objects.is_empty() {
+                let bundle = write_pack(
+                    &db,
+                    promisor_objects.iter().copied(),
+                    &pack_dir,
+                    write_options.clone(),
+                    progress,
+                    should_interrupt,
+                )?;
+                let promisor_path = bundle
+                    .index_path
+                    .as_ref()
+                    .expect("written to directory")
+                    .with_extension("promisor");
+                std::fs::write(&promisor_path, b"").map_err(|source| Error::Io {
+                    path: promisor_path,
+                    source,
+                })?;
+                out.promisor_objects = promisor_objects.len();
+                out.promisor_pack.clone_from(&bundle.data_path);
+                new_packs.push(bundle);
+            }
+        }
+
+        let mut expired = BTreeSet::new();
+        if options.mode == Mode::All && options.delete_redundant {
+            let mut unreachable = BTreeMap::<ObjectId, Unreachable>::new();
+            let is_retained = |id: &gix_hash::oid| {
+                packs.iter().any(|pack| pack.is_kept && pack.contains(id)) || is_in_promisor_pack(id)
+            };
+            for &idx in &replaced {
+                let pack = &packs[idx];
+                for (entry_idx, entry) in pack.index.iter().enumerate() {
+                    if reachable.contains(&entry.oid) || is_retained(&entry.oid) {
+                        continue;
+                    }
+                    let mtime = pack.mtimes.as_ref().map_or(pack.mtime, |mtimes| mtimes[entry_idx]);
+                    let object = unreachable
+                        .entry(entry.oid)
+                        .or_insert(Unreachable { mtime, is_loose: false });
+                    object.mtime = object.mtime.max(mtime);
+                }
+            }
+            for id in &loose {
+                if reachable.contains(id) || is_retained(id) {
+                    continue;
+                }
+                let path = loose_db.object_path(id);
+                let mtime = std::fs::metadata(&path)
+                    .and_then(|meta| meta.modified())
+                    .map_err(|source| Error::Io { path, source })?;
+                let mtime = seconds(mtime) as u32;
+                let object = unreachable.entry(*id).or_insert(Unreachable { mtime, is_loose: true });
+                object.mtime = object.mtime.max(mtime);
+                object.is_loose = true;
+            }
+
+            let is_expired =
+                |object: &Unreachable| options.expire.map_or(false, |expire| i64::from(object.mtime) < expire);
+            if options.cruft {
+                let (cruft, mtimes): (Vec<_>, Vec<_>) = unreachable
+                    .iter()
+                    .filter(|(_, object)| !is_expired(object))
+                    .map(|(id, object)| (*id, object.mtime))
+                    .unzip();
+                expired.extend(
+                    unreachable
+                        .iter()
+                        .filter(|(_, object)| is_expired(object))
+                        .map(|(id, _)| *id),
+                );
+                out.pruned_objects = expired.len();
+                if !cruft.is_empty() {
+                    let bundle = write_pack(
+                        &db,
+                        cruft.iter().copied(),
+                        &pack_dir,
+                        write_options,
+                        progress,
+                        should_interrupt,
+                    )?;
+                    let index_path = bundle.index_path.clone().expect("written to directory");
+                    let mtimes_path = index_path.with_extension("mtimes");
+                    mtimes::write(&mtimes_path, &mtimes, &bundle.index.data_hash).map_err(|source| Error::Io {
+                        path: mtimes_path,
+                        source,
+                    })?;
+                    out.cruft_objects = cruft.len();
+                    out.cruft_pack.clone_from(&bundle.data_path);
+                    new_packs.push(bundle);
+                }
+            } else {
+                for (id, object) in &unreachable {
+                    if !object.is_loose {
+                        out.pruned_objects += 1;
+                    } else if is_expired(object) {
+                        out.pruned_objects += 1;
+                        expired.insert(*id);
+                    }
+                }
+            }
+        }
+
+        if options.delete_redundant {
+            let new_index_paths: Vec<_> = new_packs
+                .iter()
+                .filter_map(|bundle| bundle.index_path.clone())
+                .collect();
+            let removed: Vec<_> = replaced
+                .iter()
+                .map(|&idx| &packs[idx])
+                .filter(|pack| !new_index_paths.iter().any(|path| path == pack.index.path()))
+                .collect();
+            if !removed.is_empty() {
+                remove_file(&pack_dir.join("multi-pack-index"))?;
+            }
+            for pack in &removed {
+                remove_pack(pack.index.path())?;
+            }
+            out.removed_packs = removed.len();
+
+            let remaining = new_index_paths
+                .iter()
+                .map(|path| gix_pack::index::File::at(path, object_hash))
+                .collect::<Result<Vec<_>, _>>()?;
+            let retained: Vec<_> = packs
+                .iter()
+                .filter(|pack| !removed.iter().any(|removed| removed.index.path() == pack.index.path()))
+                .collect();
+            let mut parent_dirs = BTreeSet::new();
+            for id in &loose {
+                if !(expired.contains(id)
+                    || remaining.iter().any(|index| index.lookup(id).is_some())
+                    || retained.iter().any(|pack| pack.contains(id)))
+                {
+                    continue;
+                }
+                let path = loose_db.object_path(id);
+                remove_file(&path)?;
+                out.removed_loose_objects += 1;
+                parent_dirs.insert(path.parent().expect("fan-out directory").to_owned());
+            }
+            for dir in parent_dirs {
+                // Only succeeds if the directory is empty, which is all we want.
+                std::fs::remove_dir(dir).ok();
+            }
+        }
+
+        for bundle in new_packs {
+            if let Some(keep_path) = bundle.keep_path {
+                remove_file(&keep_path)?;
+            }
+        }
+        Ok(out)
+    }
+
+    fn pack_write_options(&self) -> Result<gix_pack::bundle::write::Options, Error> {
+        let index_version = self
+            .config
+            .resolved
+            .integer(Pack::INDEX_VERSION)
+            .map(|value| Pack::INDEX_VERSION.try_into_index_version(value))
+            .transpose()
+            .with_leniency(self.options.lenient_config)?
+            .unwrap_or(gix_pack::index::Version::V2);
+        let thread_limit = self
+            .config
+            .resolved
+            .integer_filter(Pack::THREADS, &mut self.filter_config_section())
+            .map(|value| Pack::THREADS.try_into_usize(value))
+            .transpose()
+            .with_leniency(self.options.lenient_config)?;
+        Ok(gix_pack::bundle::write::Options {
+            thread_limit,
+            iteration_mode: gix_pack::data::input::Mode::Verify,
+            index_version,
+            object_hash: self.object_hash(),
+        })
+    }
+
+    /// Return all objects that are reachable from the references, reflogs, `HEAD`, private references and index of all
+    /// worktrees and that exist in `db`, which must not fetch missing objects. Missing objects are only acceptable if they
+    /// are promised.
+    fn reachable_objects(
+        &self,
+        db: &gix_odb::Cache<gix_odb::store::Handle<gix_features::threading::OwnShared<gix_odb::Store>>>,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_hashtable::HashSet<ObjectId>, Error> {
+        let main = self.main_repo()?;
+        let mut repos = main
+            .worktrees()
+            .map_err(|source| Error::Io {
+                path: main.common_dir().join("worktrees"),
+                source,
+            })?
+            .into_iter()
+            .map(crate::worktree::Proxy::into_repo_with_possibly_inaccessible_worktree)
+            .collect::<Result<Vec<_>, _>>()?;
+        repos.insert(0, main);
+
+        let mut tips = BTreeSet::new();
+        let mut buf = Vec::new();
+        let mut add_reflog = |repo: &crate::Repository,
+                              name: &gix_ref::FullNameRef,
+                              tips: &mut BTreeSet<ObjectId>|
+         -> Result<(), Error> {
+            if let Some(lines) = repo.refs.reflog_iter(name, &mut buf)? {
+                for line in lines {
+                    let line = line?;
+                    for id in [line.previous_oid(), line.new_oid()] {
+                        if !id.is_null() && db.contains(&id) {
+                            tips.insert(id);
+                        }
+                    }
+                }
+            }
+            Ok(())
+        };
+        let references = repos[0].refs.iter()?;
+        for reference in references.all().map_err(|source| Error::Io {
+            path: repos[0].common_dir().join("refs"),
+            source,
+        })? {
+            let reference = reference?;
+            if let gix_ref::Target::Object(id) = reference.target {
+                tips.insert(id);
+            }
+            add_reflog(&repos[0], reference.name.as_ref(), &mut tips)?;
+        }
+        let head_name: &gix_ref::FullNameRef = "HEAD".try_into().expect("valid");
+        let mut index_ids = Vec::new();
+        for (idx, repo) in repos.iter().enumerate() {
+            if let Some(id) = repo.head()?.id() {
+                tips.insert(id.detach());
+            }
+            add_reflog(repo, head_name, &mut tips)?;
+            // The references of the main worktree were seen above, but those private to linked worktrees, like
+            // `refs/bisect/*` and `refs/worktree/*`, are only visible through their own reference store.
+            if idx != 0 {
+                let references = repo.refs.iter()?;
+                for reference in references.all().map_err(|source| Error::Io {
+                    path: repo.git_dir().join("refs"),
+                    source,
+                })? {
+                    let reference = reference?;
+                    if !reference
+                        .name
+                        .category()
+                        .map_or(false, |category| category.is_worktree_private())
+                    {
+                        continue;
+                    }
+                    if let gix_ref::Target::Object(id) = reference.target {
+                        tips.insert(id);
+                    }
+                    add_reflog(repo, reference.name.as_ref(), &mut tips)?;
+                }
+            }
+            let index = repo.index_or_empty()?;
+            index_ids.extend(
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| entry.mode != gix_index::entry::Mode::COMMIT)
+                    .map(|entry| entry.id),
+            );
+            // Like `git`, keep the trees of the `TREE` extension, which may not be reachable from any commit yet.
+            let mut cache_trees: Vec<_> = index.tree().into_iter().collect();
+            while let Some(tree) = cache_trees.pop() {
+                if tree.num_entries.is_some() && db.contains(&tree.id) {
+                    index_ids.push(tree.id);
+                }
+                cache_trees.extend(&tree.children);
+            }
+        }
+
+        let promised = self.promisor_objects()?;
+        let mut counting = progress.add_child("counting objects".into());
+        counting.init(None, crate::progress::count("objects"));
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut reachable = gix_hashtable::HashSet::default();
+        let mut stack: Vec<_> = tips.into_iter().chain(index_ids).collect();
+        while let Some(id) = stack.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            if !db.contains(&id) {
+                if promised.contains(&id) {
+                    continue;
+                }
+                return Err(gix_object::find::existing::Error::NotFound { oid: id }.into());
+            }
+            let object = db.find(&id, &mut buf)?;
+            match object.kind {
+                Kind::Blob => {}
+                Kind::Tree => {
+                    for entry in gix_object::TreeRefIter::from_bytes(object.data) {
+                        let entry = entry?;
+                        if entry.mode.kind() != EntryKind::Commit {
+                            stack.push(entry.oid.to_owned());
+                        }
+                    }
+                }
+                Kind::Commit => {
+                    let mut commit = gix_object::CommitRefIter::from_bytes(object.data);
+                    stack.push(commit.tree_id()?);
+                    stack.extend(commit.parent_ids());
+                }
+                Kind::Tag => stack.push(gix_object::TagRefIter::from_bytes(object.data).target_id()?),
+            }
+            reachable.insert(id);
+            counting.inc();
+        }
+        Ok(reachable)
+    }
+}
+
+/// Write a pack with all objects in `ids` into `pack_dir`, along with its index and a `.keep` file.
+fn write_pack(
+    db: &gix_odb::Cache<gix_odb::store::Handle<gix_features::threading::OwnShared<gix_odb::Store>>>,
+    ids: impl IntoIterator<Item = ObjectId>,
+    pack_dir: &Path,
+    options: gix_pack::bundle::write::Options,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<gix_pack::bundle::write::Outcome, Error> {
+    use gix_pack::data::output::count::PackLocation;
+
+    let mut buf = Vec::new();
+    let mut counts: Vec<_> = ids
+        .into_iter()
+        .map(|id| output::Count::from_data(id, db.location_by_oid(&id, &mut buf)))
+        .collect();
+    counts.sort_by(
+        |lhs, rhs| match (lhs.entry_pack_location.as_ref(), rhs.entry_pack_location.as_ref()) {
+            (None, None) => std::cmp::Ordering::Equal,
+            (Some(_), None) => std::cmp::Ordering::Greater,
+            (None, Some(_)) => std::cmp::Ordering::Less,
+            (Some(lhs), Some(rhs)) => lhs
+                .pack_id
+                .cmp(&rhs.pack_id)
+                .then(lhs.pack_offset.cmp(&rhs.pack_offset)),
+        },
+    );
+    let mut counts_range_by_pack_id: Vec<(u32, std::ops::Range<usize>)> = Vec::new();
+    let mut pack_start = counts.partition_point(|count| count.entry_pack_location.is_none());
+    let mut slice = &counts[pack_start..];
+    while !slice.is_empty() {
+        let pack_id = slice[0].entry_pack_location.as_ref().expect("packed object").pack_id;
+        let pack_end = slice
+            .partition_point(|count| count.entry_pack_location.as_ref().expect("packed object").pack_id == pack_id);
+        counts_range_by_pack_id.push((pack_id, pack_start..pack_start + pack_end));
+        slice = &slice[pack_end..];
+        pack_start += pack_end;
+    }
+    debug_assert!(counts
+        .iter()
+        .all(|count| !matches!(count.entry_pack_location, PackLocation::NotLookedUp)));
+
+    let entries = counts.iter().map(|count| {
+        let from_pack = count.entry_pack_location.as_ref().and_then(|location| {
+            let pack_entry = db.entry_by_location(location)?;
+            let pack_range = counts_range_by_pack_id[counts_range_by_pack_id
+                .binary_search_by_key(&location.pack_id, |entry| entry.0)
+                .expect("pack-id always present")]
+            .1
+            .clone();
+            output::Entry::from_pack_entry(
+                pack_entry,
+                count,
+                &counts[pack_range.clone()],
+                pack_range.start,
+                None::<fn(u32, u64) -> Option<ObjectId>>,
+                gix_pack::data::Version::V2,
+            )
+        });
+        let entry = match from_pack {
+            Some(entry) => entry?,
+            None => {
+                let (object, _location) = gix_pack::Find::try_find(db, &count.id, &mut buf)
+                    .map_err(output::entry::iter_from_counts::Error::Find)?
+                    .ok_or_else(|| {
+                        output::entry::iter_from_counts::Error::Find(Box::new(
+                            gix_object::find::existing::Error::NotFound { oid: count.id },
+                        ))
+                    })?;
+                output::Entry::from_data(count, &object)?
+            }
+        };
+        Ok::<_, output::entry::iter_from_counts::Error>(vec![entry])
+    });
+
+    let io_err = |path: &Path| {
+        let path = path.to_owned();
+        move |source| Error::Io { path, source }
+    };
+    let mut tempfile = gix_tempfile::new(
+        pack_dir,
+        gix_tempfile::ContainingDirectory::CreateAllRaceProof(Default::default()),
+        gix_tempfile::AutoRemove::Tempfile,
+    )
+    .map_err(io_err(pack_dir))?;
+    let num_entries = u32::try_from(counts.len()).expect("less than 4 billion objects");
+    let mut pack = output::bytes::FromEntriesIter::new(
+        entries,
+        &mut tempfile,
+        num_entries,
+        gix_pack::data::Version::V2,
+        options.object_hash,
+    );
+    for written in pack.by_ref() {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        written?;
+    }
+    drop(pack);
+
+    let tempfile_path = tempfile
+        .with_mut(|file| file.path().to_owned())
+        .map_err(io_err(pack_dir))?;
+    let mut data = std::io::BufReader::new(std::fs::File::open(&tempfile_path).map_err(io_err(&tempfile_path))?);
+    let outcome = gix_pack::Bundle::write_to_directory(
+        &mut data,
+        Some(pack_dir),
+        progress,
+        should_interrupt,
+        None::<gix_object::find::Never>,
+        options,
+    )?;
+    Ok(outcome)
+}
+
+/// Return all packs in `pack_dir`, ordered by path.
+fn existing_packs(pack_dir: &Path, object_hash: gix_hash::Kind) -> Result<Vec<ExistingPack>, Error> {
+    let entries = match std::fs::read_dir(pack_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => {
+            return Err(Error::Io {
+                path: pack_dir.to_owned(),
+                source,
+            })
+        }
+    };
+    let mut packs = Vec::new();
+    for entry in entries {
+        let index_path = entry
+            .map_err(|source| Error::Io {
+                path: pack_dir.to_owned(),
+                source,
+            })?
+            .path();
+        let is_pack_index = index_path.extension().map_or(false, |ext| ext == "idx")
+            && index_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.starts_with("pack-"));
+        if !is_pack_index {
+            continue;
+        }
+        let data_path = index_path.with_extension("pack");
+        let mtime = match std::fs::metadata(&data_path).and_then(|meta| meta.modified()) {
+            Ok(mtime) => seconds(mtime) as u32,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(source) => {
+                return Err(Error::Io {
+                    path: data_path,
+                    source,
+                })
+            }
+        };
+        let index = gix_pack::index::File::at(&index_path, object_hash)?;
+        let is_kept = index_path.with_extension("keep").exists();
+        let is_promisor = index_path.with_extension("promisor").exists();
+        let mtimes_path = index_path.with_extension("mtimes");
+        let mtimes = if mtimes_path.is_file() {
+            Some(
+                mtimes::read(&mtimes_path, index.num_objects(), object_hash)
+                    .map_err(|source| Error::Io {
+                        path: mtimes_path.clone(),
+                        source,
+                    })?
+                    .ok_or(Error::InvalidMtimes { path: mtimes_path })?,
+            )
+        } else {
+            None
+        };
+        packs.push(ExistingPack {
+            index,
+            mtime,
+            is_kept,
+            is_promisor,
+            mtimes,
+        });
+    }
+    packs.sort_by(|lhs, rhs| lhs.index.path().cmp(rhs.index.path()));
+    Ok(packs)
+}
+
+/// Return the indices into `packs` of the packs to combine so that the remaining ones form a geometric progression,
+/// without considering kept packs, promisor packs and cruft packs. This is the algorithm used by `git repack --geometric`.
+fn geometric_rollup(packs: &[ExistingPack], factor: u32) -> Vec<usize> {
+    let mut candidates: Vec<usize> = (0..packs.len())
+        .filter(|&idx| !packs[idx].is_kept && !packs[idx].is_promisor && packs[idx].mtimes.is_none())
+        .collect();
+    candidates.sort_by_key(|&idx| packs[idx].index.num_objects());
+    let weight = |idx: usize| u64::from(packs[candidates[idx]].index.num_objects());
+    let factor = u64::from(factor);
+
+    let mut split = (1..candidates.len())
+        .rev()
+        .find(|&idx| weight(idx) < factor * weight(idx - 1))
+        .map_or(0, |idx| idx + 1);
+    let mut total: u64 = (0..split).map(weight).sum();
+    while split < candidates.len() && weight(split) < factor * total {
+        total += weight(split);
+        split += 1;
+    }
+    candidates.truncate(split);
+    candidates
+}
+
+/// Delete the pack with the given index, starting with the index so that the object database stops seeing it first.
+fn remove_pack(index_path: &Path) -> Result<(), Error> {
+    for extension in ["idx", "pack", "rev", "bitmap", "mtimes", "promisor"] {
+        remove_file(&index_path.with_extension(extension))?;
+    }
+    Ok(())
+}
+
+fn remove_file(path: &Path) -> Result<(), Error> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(source) => Err(Error::Io {
+            path: PathBuf::from(path),
+            source,
+        }),
+    }
+}
+
+fn seconds(time: std::time::SystemTime) -> gix_date::SecondsSinceUnixEpoch {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as gix_date::SecondsSinceUnixEpoch)
+}
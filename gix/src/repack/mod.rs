@@ -0,0 +1,137 @@
+//! Consolidate loose objects and packs into fewer packs and drop unreachable objects, similar to `git repack` and `git gc`.
+//!
+//! New packs are fully written before any of the files they replace are deleted, and pack indices are deleted before
+//! their data files. Together with the object database keeping the packs it already uses mapped, this allows handles
+//! that were opened earlier to keep finding all objects while the operation is in progress.
+use std::path::PathBuf;
+
+mod function;
+mod mtimes;
+
+/// The way objects are selected for packing in [`Repository::repack()`](crate::Repository::repack()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Pack all reachable loose objects into a new pack and leave existing packs alone, like `git repack`.
+    #[default]
+    Loose,
+    /// Pack all reachable objects into a single new pack, like `git repack -a`.
+    All,
+    /// Combine the smallest packs along with all loose objects into a new pack so that the remaining packs form a
+    /// geometric progression, with each pack containing at least `factor` times as many objects as the next smaller one,
+    /// like `git repack --geometric=<factor>`.
+    ///
+    /// Unreachable objects are kept.
+    Geometric {
+        /// The factor by which each pack should be larger than the next smaller one, at least `2`.
+        factor: u32,
+    },
+}
+
+/// Options for use in [`Repository::repack()`](crate::Repository::repack()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// Determine which objects to pack.
+    pub mode: Mode,
+    /// If `true`, delete packs and loose objects whose objects are now contained in other packs, like `-d`.
+    ///
+    /// In [`Mode::All`], all packs except for those with a `.keep` file are replaced, which drops unreachable objects in
+    /// them unless [`cruft`](Self::cruft) is set. Objects in packs with a `.promisor` file are never dropped.
+    pub delete_redundant: bool,
+    /// If `true`, write all unreachable objects that didn't [expire](Self::expire) yet into a cruft pack that records their
+    /// modification times, like `--cruft`, so that they can expire later.
+    ///
+    /// This is only possible in [`Mode::All`] along with [`delete_redundant`](Self::delete_redundant).
+    pub cruft: bool,
+    /// Unreachable objects whose modification time is before this time, in seconds since the Unix epoch, are removed.
+    /// If `None`, unreachable objects never expire.
+    ///
+    /// It's used in [`Mode::All`] along with [`delete_redundant`](Self::delete_redundant), and affects cruft packs and
+    /// loose objects alike.
+    pub expire: Option<gix_date::SecondsSinceUnixEpoch>,
+}
+
+/// The result of [`Repository::repack()`](crate::Repository::repack()) and [`Repository::gc()`](crate::Repository::gc()).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The path to the data file of the newly written pack, if there was anything to pack.
+    pub pack: Option<PathBuf>,
+    /// The path to the data file of the newly written cruft pack, if there were unreachable objects to keep.
+    pub cruft_pack: Option<PathBuf>,
+    /// The amount of objects in the new pack.
+    pub packed_objects: usize,
+    /// The amount of objects in the new cruft pack.
+    pub cruft_objects: usize,
+    /// The path to the data file of the new pack with a `.promisor` file, if there were packs with a `.promisor` file
+    /// in [`Mode::All`].
+    pub promisor_pack: Option<PathBuf>,
+    /// The amount of objects in the new pack with a `.promisor` file.
+    pub promisor_objects: usize,
+    /// The amount of packs that were deleted as they were replaced.
+    pub removed_packs: usize,
+    /// The amount of loose objects that were deleted, either because they were packed or because they expired.
+    pub removed_loose_objects: usize,
+    /// The amount of unreachable objects that were dropped, either because they expired or because they were in a
+    /// deleted pack without being written to a cruft pack.
+    pub pruned_objects: usize,
+    /// The amount of loose references that were written to `packed-refs` and deleted, as done by
+    /// [`Repository::gc()`](crate::Repository::gc()) only.
+    pub packed_refs: usize,
+    /// Information about expired reference log entries, as removed by [`Repository::gc()`](crate::Repository::gc()) only.
+    pub reflog: crate::reflog::Outcome,
+}
+
+/// The error returned by [`Repository::repack()`](crate::Repository::repack()) and [`Repository::gc()`](crate::Repository::gc()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The geometric factor must be at least 2, got {factor}")]
+    InvalidGeometricFactor { factor: u32 },
+    #[error("Cruft packs can only be written when repacking all objects and deleting redundant packs")]
+    CruftWithoutAll,
+    #[error("Interrupted")]
+    Interrupted,
+    #[error("Could not access '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("The file at '{}' doesn't contain valid modification times", path.display())]
+    InvalidMtimes { path: PathBuf },
+    #[error(transparent)]
+    PruneExpire(#[from] crate::config::key::GenericErrorWithValue),
+    #[error("The value to configure pack threads should be 0 to auto-configure or the amount of threads to use")]
+    PackThreads(#[from] crate::config::unsigned_integer::Error),
+    #[error("The value to configure the pack index version should be 1 or 2")]
+    PackIndexVersion(#[from] crate::config::key::GenericError),
+    #[error(transparent)]
+    OpenPackedRefs(#[from] gix_ref::packed::buffer::open::Error),
+    #[error(transparent)]
+    IterReference(#[from] gix_ref::file::iter::loose_then_packed::Error),
+    #[error(transparent)]
+    ReadReflog(#[from] gix_ref::file::log::Error),
+    #[error(transparent)]
+    DecodeReflog(#[from] gix_ref::file::log::iter::decode::Error),
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    DecodeObject(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    PromisorObjects(#[from] crate::repository::promisor_objects::Error),
+    #[error(transparent)]
+    ExpireReflogs(#[from] crate::reflog::Error),
+    #[error(transparent)]
+    LockTimeout(#[from] crate::config::lock_timeout::Error),
+    #[error(transparent)]
+    PackRefs(#[from] gix_ref::file::pack_refs::Error),
+    #[error(transparent)]
+    IterLoose(#[from] gix_odb::loose::iter::Error),
+    #[error(transparent)]
+    OpenPackIndex(#[from] gix_pack::index::init::Error),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error(transparent)]
+    WriteBundle(#[from] gix_pack::bundle::write::Error),
+}
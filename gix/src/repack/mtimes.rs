@@ -0,0 +1,50 @@
+//! Read and write `.mtimes` files, which record the modification time of each object in a cruft pack in index order.
+use std::path::Path;
+
+use gix_hash::oid;
+
+const SIGNATURE: &[u8] = b"MTME";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 12;
+
+/// Read the modification times of `num_objects` objects from the file at `path`, or return `None` if it isn't valid.
+pub(super) fn read(path: &Path, num_objects: u32, object_hash: gix_hash::Kind) -> std::io::Result<Option<Vec<u32>>> {
+    let data = std::fs::read(path)?;
+    let table_len = num_objects as usize * 4;
+    if data.len() != HEADER_LEN + table_len + 2 * object_hash.len_in_bytes()
+        || &data[..4] != SIGNATURE
+        || data[4..8] != VERSION.to_be_bytes()
+        || data[8..12] != hash_id(object_hash).to_be_bytes()
+    {
+        return Ok(None);
+    }
+    Ok(Some(
+        data[HEADER_LEN..][..table_len]
+            .chunks_exact(4)
+            .map(|mtime| u32::from_be_bytes(mtime.try_into().expect("four bytes")))
+            .collect(),
+    ))
+}
+
+/// Write `mtimes`, ordered like the objects in the index of the pack with checksum `pack_hash`, to `path`.
+pub(super) fn write(path: &Path, mtimes: &[u32], pack_hash: &oid) -> std::io::Result<()> {
+    let object_hash = pack_hash.kind();
+    let mut data = Vec::with_capacity(HEADER_LEN + mtimes.len() * 4 + 2 * object_hash.len_in_bytes());
+    data.extend_from_slice(SIGNATURE);
+    data.extend_from_slice(&VERSION.to_be_bytes());
+    data.extend_from_slice(&hash_id(object_hash).to_be_bytes());
+    for mtime in mtimes {
+        data.extend_from_slice(&mtime.to_be_bytes());
+    }
+    data.extend_from_slice(pack_hash.as_bytes());
+    let mut hasher = gix_features::hash::hasher(object_hash);
+    hasher.update(&data);
+    data.extend_from_slice(&hasher.digest());
+    std::fs::write(path, data)
+}
+
+fn hash_id(kind: gix_hash::Kind) -> u32 {
+    match kind {
+        gix_hash::Kind::Sha1 => 1,
+    }
+}
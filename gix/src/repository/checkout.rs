@@ -0,0 +1,433 @@
+//! Shared machinery to bring the index and the worktree to the state of a tree, as used by resets,
+//! switching branches and restoring paths.
+use std::{cmp::Ordering, collections::BTreeMap, io::Read, path::Path, sync::atomic::AtomicBool};
+
+use gix_hash::ObjectId;
+use gix_index::entry::{Flags, Mode, Stat};
+
+use super::sparse_checkout::remove_empty_parent_directories;
+use crate::{
+    bstr::{BStr, BString},
+    Repository,
+};
+
+/// The error returned by the functions in this module, which is converted into the public error of each caller.
+#[derive(Debug)]
+pub(crate) enum Error {
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    IndexFromTree(crate::repository::index_from_tree::Error),
+    FilterPipeline(crate::repository::filter::pipeline::Error),
+    ConvertToGit(crate::filter::pipeline::convert_to_git::Error),
+    CheckoutOptions(crate::config::checkout_options::Error),
+    Checkout(gix_worktree_state::checkout::Error),
+    FetchMissingObjects(gix_odb::store::promisor::Error),
+}
+
+macro_rules! impl_from_checkout_error {
+    ($($error:ty),*) => {
+        $(
+            impl From<Error> for $error {
+                fn from(err: Error) -> Self {
+                    match err {
+                        Error::Io { path, source } => Self::Io { path, source },
+                        Error::IndexFromTree(err) => Self::IndexFromTree(err),
+                        Error::FilterPipeline(err) => Self::FilterPipeline(err),
+                        Error::ConvertToGit(err) => Self::ConvertToGit(err),
+                        Error::CheckoutOptions(err) => Self::CheckoutOptions(err),
+                        Error::Checkout(err) => Self::Checkout(err),
+                        Error::FetchMissingObjects(err) => Self::FetchMissingObjects(err),
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_from_checkout_error!(crate::reset::Error, crate::switch::Error, crate::restore::Error);
+#[cfg(all(feature = "revision", feature = "tree-editor"))]
+impl_from_checkout_error!(crate::sequencer::Error);
+#[cfg(all(feature = "dirwalk", feature = "tree-editor"))]
+impl_from_checkout_error!(crate::stash::push::Error, crate::stash::apply::Error);
+
+/// A path whose entries in the index differ from its entry in the target tree.
+pub(crate) struct Difference {
+    pub(crate) path: BString,
+    /// The index of the unconflicted entry in the index, if present.
+    pub(crate) current: Option<usize>,
+    /// If `true`, the path has conflicting entries in the index.
+    pub(crate) conflicted: bool,
+    /// The index of the entry in the target tree, if present.
+    pub(crate) target: Option<usize>,
+}
+
+/// Return all paths whose entries in `index` differ from the ones in `target` in id or mode, sorted by path.
+pub(crate) fn differences(index: &gix_index::State, target: &gix_index::State) -> Vec<Difference> {
+    let (entries, target_entries) = (index.entries(), target.entries());
+    let (mut cur, mut tgt) = (0, 0);
+    let mut out = Vec::new();
+    loop {
+        let current_path = entries.get(cur).map(|e| e.path(index));
+        let target_path = target_entries.get(tgt).map(|e| e.path(target));
+        let ordering = match (current_path, target_path) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(a), Some(b)) => a.cmp(b),
+        };
+        if ordering == Ordering::Greater {
+            out.push(Difference {
+                path: target_path.expect("present").to_owned(),
+                current: None,
+                conflicted: false,
+                target: Some(tgt),
+            });
+            tgt += 1;
+            continue;
+        }
+
+        let path = current_path.expect("present");
+        let (mut current, mut conflicted) = (None, false);
+        while let Some(entry) = entries.get(cur).filter(|e| e.path(index) == path) {
+            if entry.stage_raw() == 0 {
+                current = Some(cur);
+            } else {
+                conflicted = true;
+            }
+            cur += 1;
+        }
+        let target_idx = (ordering == Ordering::Equal).then_some(tgt);
+        let is_different = conflicted
+            || match (current, target_idx) {
+                (Some(current), Some(target_idx)) => {
+                    let (a, b) = (&entries[current], &target_entries[target_idx]);
+                    a.id != b.id || a.mode != b.mode
+                }
+                _ => true,
+            };
+        if is_different {
+            out.push(Difference {
+                path: path.to_owned(),
+                current,
+                conflicted,
+                target: target_idx,
+            });
+        }
+        if target_idx.is_some() {
+            tgt += 1;
+        }
+    }
+    out
+}
+
+/// How to change the index entries of a path.
+pub(crate) enum IndexChange {
+    /// Remove all entries of the path.
+    Remove,
+    /// Replace all entries of the path with a single unconflicted entry.
+    Set { id: ObjectId, mode: Mode },
+    /// Replace all entries of the path with the stages of a conflict, for the base, our and their version respectively.
+    Conflict { mode: Mode, ids: [Option<ObjectId>; 3] },
+}
+
+/// Return the changes needed to set the entries of all `differences` to their version in `target`.
+pub(crate) fn changes_to_target(
+    target: &gix_index::State,
+    differences: &[Difference],
+) -> BTreeMap<BString, IndexChange> {
+    differences
+        .iter()
+        .map(|d| {
+            let change = match d.target.map(|idx| target.entry(idx)) {
+                Some(entry) => IndexChange::Set {
+                    id: entry.id,
+                    mode: entry.mode,
+                },
+                None => IndexChange::Remove,
+            };
+            (d.path.clone(), change)
+        })
+        .collect()
+}
+
+/// The id and stat of each file that was just checked out, by path.
+pub(crate) type Stats = BTreeMap<BString, (ObjectId, Stat)>;
+
+/// Apply all `changes` to `index`, and use the `stats` of files that were just checked out for all entries of the same id.
+pub(crate) fn apply_index_changes(
+    index: &mut gix_index::State,
+    changes: &BTreeMap<BString, IndexChange>,
+    stats: &Stats,
+) {
+    for (entry, path) in index.entries_mut_with_paths() {
+        if let Some((_, stat)) = stats.get(path).filter(|(id, _)| *id == entry.id) {
+            entry.stat = *stat;
+        }
+    }
+    if changes.is_empty() {
+        return;
+    }
+    index.remove_entries(|_, path, _| changes.contains_key(path));
+    for (path, change) in changes {
+        match change {
+            IndexChange::Remove => {}
+            IndexChange::Set { id, mode } => index.dangerously_push_entry(
+                stats
+                    .get(path)
+                    .filter(|(stat_id, _)| stat_id == id)
+                    .map(|(_, stat)| *stat)
+                    .unwrap_or_default(),
+                *id,
+                Flags::empty(),
+                *mode,
+                path.as_ref(),
+            ),
+            IndexChange::Conflict { mode, ids } => {
+                for (stage, id) in (1..).zip(ids) {
+                    if let Some(id) = id {
+                        index.dangerously_push_entry(
+                            Default::default(),
+                            *id,
+                            Flags::from_bits_retain(stage << 12),
+                            *mode,
+                            path.as_ref(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    index.sort_entries();
+    // The cached trees of the changed paths are outdated, and git would have to recompute them all anyway.
+    index.set_tree(None);
+}
+
+/// Remove the files at all worktree-relative `paths` in `workdir` along with their empty parent directories,
+/// and return the amount of removed files.
+pub(crate) fn remove_files<'a>(workdir: &Path, paths: impl IntoIterator<Item = &'a BStr>) -> Result<usize, Error> {
+    let mut removed = 0;
+    for rela_path in paths {
+        let path = workdir.join(gix_path::from_bstr(rela_path));
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                remove_empty_parent_directories(&path, workdir);
+                removed += 1;
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(source) => return Err(Error::Io { path, source }),
+        }
+    }
+    Ok(removed)
+}
+
+impl Repository {
+    /// Return an index with the entries of the tree `tree_id`, or an empty one if there is no tree or if it's the empty tree,
+    /// which doesn't have to exist in the object database.
+    pub(crate) fn index_from_tree_or_empty(&self, tree_id: Option<ObjectId>) -> Result<gix_index::File, Error> {
+        Ok(match tree_id.filter(|id| !id.is_empty_tree()) {
+            Some(tree_id) => self.index_from_tree(&tree_id).map_err(Error::IndexFromTree)?,
+            None => gix_index::File::from_state(gix_index::State::new(self.object_hash()), self.index_path()),
+        })
+    }
+
+    /// Write the blobs of `entries`, a list of `(path, id, mode)`, to `workdir`, overwriting existing files,
+    /// and return the amount of written files along with the id and stat of each written file by path.
+    pub(crate) fn checkout_entries(
+        &self,
+        workdir: &Path,
+        entries: &[(BString, ObjectId, Mode)],
+        should_interrupt: &AtomicBool,
+    ) -> Result<(usize, Stats), Error> {
+        let mut stats = BTreeMap::new();
+        if entries.is_empty() {
+            return Ok((0, stats));
+        }
+        // In partial clones, fetch all objects we are about to check out at once, instead of one by one.
+        self.objects
+            .fetch_missing(
+                entries
+                    .iter()
+                    .filter(|(_, _, mode)| !mode.is_submodule())
+                    .map(|(_, id, _)| *id),
+            )
+            .map_err(Error::FetchMissingObjects)?;
+        let mut state = gix_index::State::new(self.object_hash());
+        for (path, id, mode) in entries {
+            state.dangerously_push_entry(Default::default(), *id, Flags::empty(), *mode, path.as_ref());
+        }
+        state.sort_entries();
+        let mut opts = self
+            .config
+            .checkout_options(
+                self,
+                gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )
+            .map_err(Error::CheckoutOptions)?;
+        opts.overwrite_existing = true;
+        let outcome = gix_worktree_state::checkout(
+            &mut state,
+            workdir,
+            self.objects.clone().into_arc().map_err(|err| Error::Io {
+                path: self.objects.store_ref().path().into(),
+                source: err,
+            })?,
+            &gix_features::progress::Discard,
+            &gix_features::progress::Discard,
+            should_interrupt,
+            opts,
+        )
+        .map_err(Error::Checkout)?;
+        for entry in state.entries() {
+            stats.insert(entry.path(&state).to_owned(), (entry.id, entry.stat));
+        }
+        Ok((outcome.files_updated, stats))
+    }
+}
+
+/// Access to files in the worktree to learn if they are modified compared to their index entry.
+pub(crate) struct Worktree<'repo> {
+    repo: &'repo Repository,
+    root: &'repo Path,
+    stat_options: gix_index::entry::stat::Options,
+    executable_bit: bool,
+    pipeline: Option<(
+        crate::filter::Pipeline<'repo>,
+        crate::worktree::IndexPersistedOrInMemory,
+    )>,
+    buf: Vec<u8>,
+}
+
+impl<'repo> Worktree<'repo> {
+    pub(crate) fn new(repo: &'repo Repository, root: &'repo Path) -> Result<Self, crate::config::stat_options::Error> {
+        Ok(Worktree {
+            repo,
+            root,
+            stat_options: repo.config.stat_options()?,
+            executable_bit: repo.filesystem_options().map_or(true, |fs| fs.executable_bit),
+            pipeline: None,
+            buf: Vec::new(),
+        })
+    }
+
+    /// Return `true` if the file at `rela_path` doesn't match `entry`, either because it doesn't exist, its kind or
+    /// executable bit changed, or because it has different content after applying all filters.
+    ///
+    /// Submodules are never considered modified.
+    pub(crate) fn is_modified(&mut self, entry: &gix_index::Entry, rela_path: &BStr) -> Result<bool, Error> {
+        if entry.mode == Mode::COMMIT {
+            return Ok(false);
+        }
+        let path = self.root.join(gix_path::from_bstr(rela_path));
+        let metadata = match gix_index::fs::Metadata::from_path_no_follow(&path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(true),
+            Err(source) => return Err(Error::Io { path, source }),
+        };
+        if metadata.is_dir() || metadata.is_symlink() != (entry.mode == Mode::SYMLINK) {
+            return Ok(true);
+        }
+        if self.executable_bit
+            && !metadata.is_symlink()
+            && metadata.is_executable() != (entry.mode == Mode::FILE_EXECUTABLE)
+        {
+            return Ok(true);
+        }
+        if Stat::from_fs(&metadata).map_or(false, |stat| entry.stat.matches(&stat, self.stat_options)) {
+            return Ok(false);
+        }
+        let id = gix_object::compute_hash(
+            self.repo.object_hash(),
+            gix_object::Kind::Blob,
+            self.read(rela_path, metadata.is_symlink())?,
+        );
+        Ok(id != entry.id)
+    }
+
+    /// Read the file at `rela_path` and convert it to what would be stored in git, or read the target of the symlink
+    /// at `rela_path` if `is_symlink` is `true`.
+    pub(crate) fn read(&mut self, rela_path: &BStr, is_symlink: bool) -> Result<&[u8], Error> {
+        let path = self.root.join(gix_path::from_bstr(rela_path));
+        let io_err = |source| Error::Io {
+            path: path.clone(),
+            source,
+        };
+        self.buf.clear();
+        if is_symlink {
+            let target = std::fs::read_link(&path).map_err(io_err)?;
+            self.buf.extend_from_slice(&gix_path::into_bstr(target));
+        } else {
+            if self.pipeline.is_none() {
+                self.pipeline = Some(self.repo.filter_pipeline(None).map_err(Error::FilterPipeline)?);
+            }
+            let (pipeline, index) = self.pipeline.as_mut().expect("just set");
+            let file = std::fs::File::open(&path).map_err(io_err)?;
+            pipeline
+                .convert_to_git(file, gix_path::from_bstr(rela_path).as_ref(), index)
+                .map_err(Error::ConvertToGit)?
+                .read_to_end(&mut self.buf)
+                .map_err(io_err)?;
+        }
+        Ok(&self.buf)
+    }
+
+    /// Return `true` if anything exists at `rela_path`.
+    pub(crate) fn exists(&self, rela_path: &BStr) -> bool {
+        self.root
+            .join(gix_path::from_bstr(rela_path))
+            .symlink_metadata()
+            .is_ok()
+    }
+
+    /// Return the mode of the file at `rela_path` as it would be stored in the index, or `None` if there is no file.
+    ///
+    /// `previous` is the mode of the file in the index, which is kept for files if the executable bit isn't trusted.
+    #[cfg(all(feature = "dirwalk", feature = "tree-editor"))]
+    pub(crate) fn mode(&self, rela_path: &BStr, previous: Option<Mode>) -> Result<Option<Mode>, Error> {
+        let path = self.root.join(gix_path::from_bstr(rela_path));
+        let metadata = match gix_index::fs::Metadata::from_path_no_follow(&path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(Error::Io { path, source }),
+        };
+        if metadata.is_dir() {
+            return Ok(None);
+        }
+        Ok(Some(if metadata.is_symlink() {
+            Mode::SYMLINK
+        } else if !self.executable_bit {
+            previous
+                .filter(|mode| *mode == Mode::FILE_EXECUTABLE)
+                .unwrap_or(Mode::FILE)
+        } else if metadata.is_executable() {
+            Mode::FILE_EXECUTABLE
+        } else {
+            Mode::FILE
+        }))
+    }
+
+    /// Return the local change that would be lost if the file at `rela_path` was overwritten or removed, with `index` being
+    /// the index that is checked out.
+    #[cfg(all(feature = "tree-editor", any(feature = "dirwalk", feature = "revision")))]
+    pub(crate) fn obstruction(
+        &mut self,
+        index: &gix_index::State,
+        rela_path: &BStr,
+    ) -> Result<Option<Obstruction>, Error> {
+        Ok(match index.entry_by_path(rela_path).filter(|e| e.stage_raw() == 0) {
+            Some(entry) => (!entry.flags.contains(Flags::SKIP_WORKTREE) && self.is_modified(entry, rela_path)?)
+                .then_some(Obstruction::LocalChanges),
+            None => self.exists(rela_path).then_some(Obstruction::Untracked),
+        })
+    }
+}
+
+/// A local change that would be lost when writing a path to the worktree.
+#[cfg(all(feature = "tree-editor", any(feature = "dirwalk", feature = "revision")))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Obstruction {
+    /// The tracked file was modified.
+    LocalChanges,
+    /// An untracked file is in the way.
+    Untracked,
+}
@@ -22,6 +22,60 @@ impl crate::Repository {
         }
     }
 
+    /// Lock the configuration file of `scope` and return a platform to edit it without losing comments, whitespace or the
+    /// order of values, which writes all changes to disk and applies them to this instance once
+    /// [committed](config::FileMut::commit()).
+    ///
+    /// The file is read again after it was locked, so it's fine if it changed on disk since the repository was opened, and it
+    /// will be created if it doesn't exist yet. Note that the lock is held until the returned instance is committed or dropped.
+    pub fn config_file_mut(&mut self, scope: config::Scope) -> Result<config::FileMut<'_>, config::file_mut::Error> {
+        use config::file_mut::Error;
+        let (source, path, trust) = match scope {
+            config::Scope::Local => (
+                gix_config::Source::Local,
+                self.common_dir().join("config"),
+                self.git_dir_trust(),
+            ),
+            config::Scope::Worktree => {
+                let key = &config::tree::Extensions::WORKTREE_CONFIG;
+                let enabled = self
+                    .config
+                    .apply_leniency(self.config.resolved.boolean(key).map(|res| key.enrich_error(res)))?
+                    .unwrap_or(false);
+                if !enabled {
+                    return Err(Error::WorktreeConfigDisabled);
+                }
+                (
+                    gix_config::Source::Worktree,
+                    self.git_dir().join("config.worktree"),
+                    self.git_dir_trust(),
+                )
+            }
+            config::Scope::Global => {
+                let mut env = config::Cache::make_source_env(self.options.permissions.env);
+                let user = gix_config::Source::User.storage_location(&mut env);
+                let xdg = gix_config::Source::Git.storage_location(&mut env);
+                // Like `git`, only use the XDG location if it's the only one that exists.
+                let (source, path) = match (user, xdg) {
+                    (Some(user), Some(xdg)) if !user.is_file() && xdg.is_file() => (gix_config::Source::Git, xdg),
+                    (Some(user), _) => (gix_config::Source::User, user),
+                    (None, Some(xdg)) => (gix_config::Source::Git, xdg),
+                    (None, None) => return Err(Error::GlobalPathUnknown),
+                };
+                (source, path.into_owned(), gix_sec::Trust::Full)
+            }
+        };
+
+        let lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        let meta = gix_config::file::Metadata::from(source).at(&path).with(trust);
+        let file = match std::fs::read(&path) {
+            Ok(mut buf) => gix_config::File::from_bytes_owned(&mut buf, meta, Default::default())?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => gix_config::File::new(meta),
+            Err(err) => return Err(Error::Io { path, source: err }),
+        };
+        Ok(config::FileMut { repo: self, file, lock })
+    }
+
     /// Return filesystem options as retrieved from the repository configuration.
     ///
     /// Note that these values have not been [probed](gix_fs::Capabilities::probe()).
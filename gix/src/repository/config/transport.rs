@@ -9,6 +9,9 @@ impl crate::Repository {
     /// `None` is returned if there is no known configuration. If `remote_name` is not `None`, the remote's name may contribute to
     /// configuration overrides, typically for the HTTP transport.
     ///
+    /// For HTTP, values in `http.<url>.*` sections override those in `http.*` if `<url>` matches `url`, with more specific
+    /// matches taking precedence as described in `git help config`.
+    ///
     /// Note that the caller may cast the instance themselves to modify it before passing it on.
     ///
     /// For transports that support proxy authentication, the
@@ -98,19 +101,11 @@ impl crate::Repository {
                     }
 
                     fn ssl_version(
-                        config: &gix_config::File<'static>,
-                        key_str: &'static str,
+                        value: Option<Cow<'_, BStr>>,
                         key: &'static config::tree::http::SslVersion,
-                        mut filter: fn(&gix_config::file::Metadata) -> bool,
                         lenient: bool,
                     ) -> Result<Option<SslVersion>, config::transport::Error> {
-                        debug_assert_eq!(
-                            key_str,
-                            key.logical_name(),
-                            "BUG: hardcoded and generated key names must match"
-                        );
-                        config
-                            .string_filter(key_str, &mut filter)
+                        value
                             .filter(|v| !v.is_empty())
                             .map(|v| {
                                 key.try_into_ssl_version(v)
@@ -142,11 +137,12 @@ impl crate::Repository {
                     let config = &self.config.resolved;
                     let mut trusted_only = self.filter_config_section();
                     let lenient = self.config.lenient_config;
+                    // `http.<url>.<key>` takes precedence over `http.<key>`, depending on how specifically `<url>` matches.
+                    let http = config::url_match::Values::new(config, "http", &url, &mut trusted_only);
                     opts.extra_headers = {
                         let key = "http.extraHeader";
                         debug_assert_eq!(key, &config::tree::Http::EXTRA_HEADER.logical_name());
-                        config
-                            .strings_filter(key, &mut trusted_only)
+                        http.strings(config::tree::Http::EXTRA_HEADER.name)
                             .map(|values| config::tree::Http::EXTRA_HEADER.try_into_extra_header(values))
                             .transpose()
                             .map_err(|err| config::transport::Error::IllformedUtf8 {
@@ -157,30 +153,22 @@ impl crate::Repository {
                     };
 
                     opts.follow_redirects = {
-                        let key = "http.followRedirects";
-
-                        config::tree::Http::FOLLOW_REDIRECTS
-                            .try_into_follow_redirects(
-                                config.string_filter(key, &mut trusted_only).unwrap_or_default(),
-                                || {
-                                    config
-                                        .boolean_filter(key, &mut trusted_only)
-                                        .transpose()
-                                        .with_leniency(lenient)
-                                },
-                            )
-                            .map_err(config::transport::http::Error::InvalidFollowRedirects)?
+                        let key = &config::tree::Http::FOLLOW_REDIRECTS;
+                        key.try_into_follow_redirects(http.string(key.name).unwrap_or_default(), || {
+                            http.boolean(key.name).transpose().with_leniency(lenient)
+                        })
+                        .map_err(config::transport::http::Error::InvalidFollowRedirects)?
                     };
 
-                    opts.low_speed_time_seconds = config
-                        .integer_filter("http.lowSpeedTime", &mut trusted_only)
+                    opts.low_speed_time_seconds = http
+                        .integer(config::tree::Http::LOW_SPEED_TIME.name)
                         .map(|value| config::tree::Http::LOW_SPEED_TIME.try_into_u64(value))
                         .transpose()
                         .with_leniency(lenient)
                         .map_err(config::transport::http::Error::from)?
                         .unwrap_or_default();
-                    opts.low_speed_limit_bytes_per_second = config
-                        .integer_filter("http.lowSpeedLimit", &mut trusted_only)
+                    opts.low_speed_limit_bytes_per_second = http
+                        .integer(config::tree::Http::LOW_SPEED_LIMIT.name)
                         .map(|value| config::tree::Http::LOW_SPEED_LIMIT.try_into_u32(value))
                         .transpose()
                         .with_leniency(lenient)
@@ -196,8 +184,8 @@ impl crate::Repository {
                             .or_else(|| {
                                 let key = "http.proxy";
                                 debug_assert_eq!(key, config::tree::Http::PROXY.logical_name());
-                                let http_proxy = config
-                                    .string_filter(key, &mut trusted_only)
+                                let http_proxy = http
+                                    .string(config::tree::Http::PROXY.name)
                                     .map(|v| (v, cow_bstr(key), &config::tree::Http::PROXY))
                                     .or_else(|| {
                                         let key = "gitoxide.http.proxy";
@@ -260,7 +248,7 @@ impl crate::Repository {
                                     .or_else(|| {
                                         let key = "http.proxyAuthMethod";
                                         debug_assert_eq!(key, config::tree::Http::PROXY_AUTH_METHOD.logical_name());
-                                        config.string_filter(key, &mut trusted_only).map(|v| {
+                                        http.string(config::tree::Http::PROXY_AUTH_METHOD.name).map(|v| {
                                             (v, Cow::Borrowed(key.into()), &config::tree::Http::PROXY_AUTH_METHOD)
                                         })
                                     })
@@ -298,8 +286,8 @@ impl crate::Repository {
                     };
                     {
                         let key = "http.userAgent";
-                        opts.user_agent = config
-                            .string_filter(key, &mut trusted_only)
+                        opts.user_agent = http
+                            .string(config::tree::Http::USER_AGENT.name)
                             .and_then(|v| {
                                 try_cow_to_string(
                                     v,
@@ -314,9 +302,8 @@ impl crate::Repository {
                     }
 
                     {
-                        let key = "http.version";
-                        opts.http_version = config
-                            .string_filter(key, &mut trusted_only)
+                        opts.http_version = http
+                            .string(config::tree::Http::VERSION.name)
                             .map(|v| {
                                 config::tree::Http::VERSION
                                     .try_into_http_version(v)
@@ -333,9 +320,7 @@ impl crate::Repository {
                     }
 
                     let may_use_cainfo = {
-                        let key = "http.schannelUseSSLCAInfo";
-                        config
-                            .boolean_filter(key, &mut trusted_only)
+                        http.boolean(config::tree::Http::SCHANNEL_USE_SSL_CA_INFO.name)
                             .map(|value| config::tree::Http::SCHANNEL_USE_SSL_CA_INFO.enrich_error(value))
                             .transpose()
                             .with_leniency(lenient)
@@ -346,8 +331,8 @@ impl crate::Repository {
                     if may_use_cainfo {
                         let key = "http.sslCAInfo";
                         debug_assert_eq!(key, config::tree::Http::SSL_CA_INFO.logical_name());
-                        opts.ssl_ca_info = config
-                            .path_filter(key, &mut trusted_only)
+                        opts.ssl_ca_info = http
+                            .path(config::tree::Http::SSL_CA_INFO.name)
                             .map(|p| {
                                 use crate::config::cache::interpolate_context;
                                 p.interpolate(interpolate_context(
@@ -363,26 +348,20 @@ impl crate::Repository {
 
                     {
                         opts.ssl_version = ssl_version(
-                            config,
-                            "http.sslVersion",
+                            http.string(config::tree::Http::SSL_VERSION.name),
                             &config::tree::Http::SSL_VERSION,
-                            trusted_only,
                             lenient,
                         )?
                         .map(|v| SslVersionRangeInclusive { min: v, max: v });
                         let min_max = ssl_version(
-                            config,
-                            "gitoxide.http.sslVersionMin",
+                            config.string_filter(&gitoxide::Http::SSL_VERSION_MIN, &mut trusted_only),
                             &gitoxide::Http::SSL_VERSION_MIN,
-                            trusted_only,
                             lenient,
                         )
                         .and_then(|min| {
                             ssl_version(
-                                config,
-                                "gitoxide.http.sslVersionMax",
+                                config.string_filter(&gitoxide::Http::SSL_VERSION_MAX, &mut trusted_only),
                                 &gitoxide::Http::SSL_VERSION_MAX,
-                                trusted_only,
                                 lenient,
                             )
                             .map(|max| min.and_then(|min| max.map(|max| (min, max))))
@@ -410,9 +389,8 @@ impl crate::Repository {
                         if ssl_no_verify {
                             opts.ssl_verify = false;
                         } else {
-                            let key = "http.sslVerify";
-                            opts.ssl_verify = config
-                                .boolean_filter(key, &mut trusted_only)
+                            opts.ssl_verify = http
+                                .boolean(config::tree::Http::SSL_VERIFY.name)
                                 .map(|value| config::tree::Http::SSL_VERIFY.enrich_error(value))
                                 .transpose()
                                 .with_leniency(lenient)
@@ -423,9 +401,8 @@ impl crate::Repository {
 
                     #[cfg(feature = "blocking-http-transport-curl")]
                     {
-                        let key = "http.schannelCheckRevoke";
-                        let schannel_check_revoke = config
-                            .boolean_filter(key, &mut trusted_only)
+                        let schannel_check_revoke = http
+                            .boolean(config::tree::Http::SCHANNEL_CHECK_REVOKE.name)
                             .map(|value| config::tree::Http::SCHANNEL_CHECK_REVOKE.enrich_error(value))
                             .transpose()
                             .with_leniency(lenient)
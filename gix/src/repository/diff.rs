@@ -15,6 +15,21 @@ pub mod resource_cache {
     }
 }
 
+///
+pub mod word_tokenizer {
+    /// The error returned by [Repository::diff_word_tokenizer()](super::Repository::diff_word_tokenizer()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The regular expression in '{key}' could not be compiled")]
+        #[cfg(feature = "blob-diff-word-regex")]
+        Regex { key: String, source: regex::Error },
+        #[error("'{key}' is set, but word regular expressions are not supported as the 'blob-diff-word-regex' feature is disabled")]
+        #[cfg(not(feature = "blob-diff-word-regex"))]
+        Unsupported { key: String },
+    }
+}
+
 /// Diff-utilities
 impl Repository {
     /// Create a resource cache for diffable objects, and configured with everything it needs to know to perform diffs
@@ -58,4 +73,42 @@ impl Repository {
             gix_diff::blob::pipeline::WorktreeRoots::default(),
         )
     }
+
+    /// Return the tokenizer to split text into words for use in [word diffs](crate::object::blob::diff::Platform::words()),
+    /// as configured by `diff.<driver>.wordRegex` if the diff `driver` is known, or `diff.wordRegex` otherwise.
+    ///
+    /// Without configuration, words are separated by whitespace.
+    pub fn diff_word_tokenizer(
+        &self,
+        driver: Option<&crate::bstr::BStr>,
+    ) -> Result<gix_diff::blob::words::Tokenizer, word_tokenizer::Error> {
+        use crate::config::tree::{Diff, Key, Section};
+
+        let config = &self.config.resolved;
+        let (key, pattern) = match driver
+            .and_then(|driver| {
+                config
+                    .string_by(Diff.name(), Some(driver), Diff::DRIVER_WORD_REGEX.name())
+                    .map(|pattern| (format!("diff.{driver}.{}", Diff::DRIVER_WORD_REGEX.name()), pattern))
+            })
+            .or_else(|| {
+                config
+                    .string(&Diff::WORD_REGEX)
+                    .map(|pattern| (Diff::WORD_REGEX.logical_name(), pattern))
+            }) {
+            Some(key_and_pattern) => key_and_pattern,
+            None => return Ok(gix_diff::blob::words::Tokenizer::Whitespace),
+        };
+
+        #[cfg(feature = "blob-diff-word-regex")]
+        {
+            gix_diff::blob::words::Tokenizer::from_word_regex(&pattern.to_string())
+                .map_err(|source| word_tokenizer::Error::Regex { key, source })
+        }
+        #[cfg(not(feature = "blob-diff-word-regex"))]
+        {
+            drop(pattern);
+            Err(word_tokenizer::Error::Unsupported { key })
+        }
+    }
 }
@@ -97,6 +97,7 @@ impl Repository {
                 excludes: Some(&mut excludes.inner),
                 objects: &self.objects,
                 explicit_traversal_root: (!options.empty_patterns_match_prefix).then_some(workdir),
+                untracked_cache: None,
             },
             opts,
             delegate,
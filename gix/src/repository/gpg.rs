@@ -0,0 +1,154 @@
+use std::borrow::Cow;
+
+use crate::{
+    bstr::BStr,
+    config,
+    config::{
+        cache::util::ApplyLeniencyDefault,
+        tree::{gpg, keys, Gpg, User},
+    },
+    repository::{signer, verify_signature},
+    Repository,
+};
+
+impl Repository {
+    /// Verify `signature` of `data` according to the `gpg.*` configuration, with the format of the signature determined
+    /// by its first line.
+    ///
+    /// SSH signatures are verified natively against the keys in `gpg.ssh.allowedSignersFile`, checking their validity
+    /// at `time` in seconds since the Unix epoch if it is known, and fail with keys listed in `gpg.ssh.revocationFile`.
+    /// OpenPGP signatures are verified by running `gpg.openpgp.program`, `gpg.program` or `gpg`, and X.509 signatures
+    /// by running `gpg.x509.program` or `gpgsm`.
+    ///
+    /// Note that a signature that is not good is not an error, use [`Verification::is_good()`](gix_gpg::Verification::is_good())
+    /// with the [configured trust level](Self::signature_min_trust_level()) to see if it should be accepted.
+    pub fn verify_signature(
+        &self,
+        data: &[u8],
+        signature: &BStr,
+        time: Option<gix_date::SecondsSinceUnixEpoch>,
+    ) -> Result<gix_gpg::Verification, verify_signature::Error> {
+        let format = gix_gpg::Format::from_signature(signature).ok_or(verify_signature::Error::UnknownFormat)?;
+        Ok(match format {
+            gix_gpg::Format::OpenPgp | gix_gpg::Format::X509 => {
+                gix_gpg::program::verify(&*self.signature_program(format), format, data, signature)?
+            }
+            gix_gpg::Format::Ssh => {
+                let allowed_signers_file = self
+                    .config
+                    .trusted_file_path(&gpg::Ssh::ALLOWED_SIGNERS_FILE)
+                    .transpose()?
+                    .ok_or(verify_signature::Error::MissingAllowedSignersFile)?;
+                let allowed_signers = read_file(&allowed_signers_file)?;
+                let allowed_signers = gix_gpg::ssh::allowed_signers::parse(&allowed_signers)?;
+                let revoked_keys = match self.config.trusted_file_path(&gpg::Ssh::REVOCATION_FILE).transpose()? {
+                    Some(path) => gix_gpg::ssh::parse_revoked_keys(&read_file(&path)?).map_err(|source| {
+                        verify_signature::Error::RevocationFile {
+                            path: path.into_owned(),
+                            source,
+                        }
+                    })?,
+                    None => Vec::new(),
+                };
+                gix_gpg::ssh::verify(
+                    data,
+                    signature,
+                    gix_gpg::ssh::Options {
+                        allowed_signers: &allowed_signers,
+                        revoked_keys: &revoked_keys,
+                        time,
+                    },
+                )?
+            }
+        })
+    }
+
+    /// Return a signer that runs `gpg.<format>.program` to create signatures in the format configured with `gpg.format`,
+    /// with the key configured in `user.signingKey`.
+    ///
+    /// For OpenPGP and X.509 signatures, the committer identity is used as key if `user.signingKey` isn't set.
+    /// For SSH signatures, `user.signingKey` is either the path to a private key, or a public key that is used with
+    /// the `ssh-agent` if it starts with `key::` or `ssh-`.
+    pub fn signer(&self) -> Result<gix_gpg::program::Signer, signer::Error> {
+        let format = self
+            .config
+            .resolved
+            .string(&Gpg::FORMAT)
+            .map(|value| Gpg::FORMAT.try_into_format(value))
+            .transpose()
+            .with_lenient_default(self.config.lenient_config)?
+            .unwrap_or_default();
+        let key = match self.config.resolved.string(&User::SIGNING_KEY) {
+            Some(key) if format == gix_gpg::Format::Ssh && !key.starts_with(b"key::") && !key.starts_with(b"ssh-") => {
+                let path = self
+                    .config
+                    .trusted_file_path(&User::SIGNING_KEY)
+                    .transpose()?
+                    .ok_or(signer::Error::MissingSshSigningKey)?;
+                gix_path::into_bstr(path).into_owned()
+            }
+            Some(key) => key.into_owned(),
+            None if format == gix_gpg::Format::Ssh => return Err(signer::Error::MissingSshSigningKey),
+            None => {
+                let committer = self.committer().ok_or(signer::Error::CommitterMissing)??;
+                format!("{} <{}>", committer.name, committer.email).into()
+            }
+        };
+        Ok(gix_gpg::program::Signer {
+            program: self.signature_program(format).into_owned().into(),
+            format,
+            key,
+        })
+    }
+
+    /// Return the [configured signer](Self::signer()) if the boolean `key`, like `commit.gpgSign`, is `true`.
+    pub(crate) fn configured_signer(
+        &self,
+        key: &'static keys::Boolean,
+    ) -> Result<Option<gix_gpg::program::Signer>, signer::Error> {
+        let enabled = self
+            .config
+            .resolved
+            .boolean(key)
+            .map(|res| key.enrich_error(res))
+            .transpose()
+            .with_lenient_default(self.config.lenient_config)?
+            .unwrap_or_default();
+        enabled.then(|| self.signer()).transpose()
+    }
+
+    /// Return the program to run for creating or verifying signatures in `format`.
+    fn signature_program(&self, format: gix_gpg::Format) -> Cow<'_, std::path::Path> {
+        let keys: &[&keys::Program] = match format {
+            gix_gpg::Format::OpenPgp => &[&gpg::OpenPgp::PROGRAM, &Gpg::PROGRAM],
+            gix_gpg::Format::X509 => &[&gpg::X509::PROGRAM],
+            gix_gpg::Format::Ssh => &[&gpg::Ssh::PROGRAM],
+        };
+        keys.iter()
+            .find_map(|key| self.config.resolved.string(*key))
+            .and_then(|program| match program {
+                Cow::Borrowed(program) => gix_path::try_from_bstr(program).ok(),
+                Cow::Owned(program) => gix_path::try_from_bstring(program).ok().map(Cow::Owned),
+            })
+            .unwrap_or_else(|| Cow::Borrowed(format.default_program().as_ref()))
+    }
+
+    /// Return the trust level that the key of a signature needs at least for it to be considered good, as configured with
+    /// `gpg.minTrustLevel`. It defaults to [undefined](gix_gpg::Trust::Undefined) which accepts all good signatures.
+    pub fn signature_min_trust_level(&self) -> Result<gix_gpg::Trust, config::key::GenericErrorWithValue> {
+        self.config
+            .resolved
+            .string(&Gpg::MIN_TRUST_LEVEL)
+            .map(|value| Gpg::MIN_TRUST_LEVEL.try_into_trust(value))
+            .transpose()
+            .with_lenient_default(self.config.lenient_config)
+            .map(Option::unwrap_or_default)
+    }
+}
+
+fn read_file(path: &std::path::Path) -> Result<Vec<u8>, verify_signature::Error> {
+    std::fs::read(path).map_err(|source| verify_signature::Error::ReadFile {
+        path: path.to_owned(),
+        source,
+    })
+}
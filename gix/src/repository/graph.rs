@@ -1,3 +1,5 @@
+use crate::config::{cache::util::ApplyLeniency, tree::CommitGraph};
+
 impl crate::Repository {
     /// Create a graph data-structure capable of accelerating graph traversals and storing state of type `T` with each commit
     /// it encountered.
@@ -42,4 +44,148 @@ impl crate::Repository {
                 _ => Err(err),
             })?)
     }
+
+    /// Write a commit-graph with all commits reachable from `tips` into the object database, similar to
+    /// `git commit-graph write --stdin-commits`, or to `--reachable` if `tips` are the peeled tips of all references.
+    ///
+    /// If `split` is set, only commits that aren't yet part of the existing commit-graph are written into a new layer of
+    /// a split commit-graph chain, otherwise a single file with all reachable commits is written.
+    /// Generation numbers v2 are written unless `commitGraph.generationVersion` is set to another value than 2.
+    ///
+    /// If `changed_paths` is `Some(true)`, changed-path Bloom filters are computed and written like `--changed-paths` would,
+    /// which accelerates [path-limited traversals](crate::revision::walk::Platform::with_changed_paths()).
+    /// With `None`, they are written only if the existing commit-graph has them already.
+    /// Their version is controlled by `commitGraph.changedPathsVersion`, defaulting to the one of existing filters or 1,
+    /// and setting it to 0 disables them entirely.
+    pub fn write_commit_graph(
+        &self,
+        tips: impl IntoIterator<Item = impl Into<gix_hash::ObjectId>>,
+        split: Option<gix_commitgraph::write::Split>,
+        changed_paths: Option<bool>,
+    ) -> Result<gix_commitgraph::write::Outcome, super::write_commit_graph::Error> {
+        let generation_version = match self
+            .config
+            .resolved
+            .integer(&CommitGraph::GENERATION_VERSION)
+            .map(|res| CommitGraph::GENERATION_VERSION.try_into_usize(res))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+        {
+            Some(2) | None => gix_commitgraph::write::GenerationVersion::V2,
+            Some(_) => gix_commitgraph::write::GenerationVersion::V1,
+        };
+        let existing = self.commit_graph().ok();
+        let existing_bloom_settings = existing
+            .as_ref()
+            .and_then(|graph| graph.bloom_filter_settings().copied());
+        let bloom_settings = match self
+            .config
+            .resolved
+            .integer(&CommitGraph::CHANGED_PATHS_VERSION)
+            .map(|res| CommitGraph::CHANGED_PATHS_VERSION.try_into_changed_paths_version(res))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+            .flatten()
+        {
+            Some(0) => None,
+            Some(hash_version) => Some(gix_commitgraph::bloom::Settings {
+                hash_version,
+                ..Default::default()
+            }),
+            None => Some(existing_bloom_settings.unwrap_or_default()),
+        }
+        .filter(|_| changed_paths.unwrap_or(existing_bloom_settings.is_some()));
+
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut queue: Vec<_> = tips.into_iter().map(Into::into).collect();
+        let mut commits = Vec::new();
+        while let Some(id) = queue.pop() {
+            let existing_commit = existing.as_ref().and_then(|graph| graph.commit_by_id(id));
+            if !seen.insert(id) || (split.is_some() && existing_commit.is_some()) {
+                continue;
+            }
+            let commit = self.find_commit(id)?;
+            let commit = commit.decode()?;
+            let parents: Vec<_> = commit.parents().collect();
+            queue.extend(parents.iter().copied());
+            commits.push(gix_commitgraph::write::Commit {
+                id,
+                tree: commit.tree(),
+                parents,
+                committer_timestamp: commit.time().seconds.try_into().unwrap_or_default(),
+                bloom_filter: existing_commit
+                    .and_then(|commit| commit.bloom_filter())
+                    .filter(|filter| Some(filter.settings()) == bloom_settings.as_ref())
+                    .map(|filter| filter.as_bytes().to_owned()),
+            });
+        }
+
+        if let Some(settings) = bloom_settings {
+            let trees: gix_hashtable::HashMap<_, _> = commits.iter().map(|c| (c.id, c.tree)).collect();
+            let mut state = gix_diff::tree::State::default();
+            for commit in commits.iter_mut().filter(|c| c.bloom_filter.is_none()) {
+                let parent_tree = match commit.parents.first() {
+                    Some(parent) => Some(match trees.get(parent) {
+                        Some(tree) => *tree,
+                        None => match existing.as_ref().and_then(|graph| graph.commit_by_id(parent)) {
+                            Some(parent) => parent.root_tree_id().to_owned(),
+                            None => self.find_commit(*parent)?.tree_id()?.detach(),
+                        },
+                    }),
+                    None => None,
+                };
+                commit.bloom_filter =
+                    Some(self.changed_paths_bloom_filter(parent_tree, commit.tree, &settings, &mut state)?);
+            }
+        }
+        drop(existing);
+
+        Ok(gix_commitgraph::Graph::write_to_info_dir(
+            &self.objects.store_ref().path().join("info"),
+            commits,
+            gix_commitgraph::write::Options {
+                object_hash: self.object_hash(),
+                generation_version,
+                split,
+                changed_paths: bloom_settings,
+            },
+        )?)
+    }
+
+    /// Compute the changed-path Bloom filter for the changes from `parent_tree`, or the empty tree, to `tree`.
+    fn changed_paths_bloom_filter(
+        &self,
+        parent_tree: Option<gix_hash::ObjectId>,
+        tree: gix_hash::ObjectId,
+        settings: &gix_commitgraph::bloom::Settings,
+        state: &mut gix_diff::tree::State,
+    ) -> Result<Vec<u8>, super::write_commit_graph::Error> {
+        use gix_diff::tree::recorder::Change;
+
+        let parent_tree = match parent_tree {
+            Some(id) => self.find_object(id)?.detach().data,
+            None => Vec::new(),
+        };
+        let tree = self.find_object(tree)?;
+        let mut recorder = gix_diff::tree::Recorder::default();
+        gix_diff::tree::Changes::from(gix_object::TreeRefIter::from_bytes(&parent_tree)).needed_to_obtain(
+            gix_object::TreeRefIter::from_bytes(&tree.data),
+            state,
+            &self.objects,
+            &mut recorder,
+        )?;
+        // Like git, only consider files and submodules, whose leading directories are added to the filter automatically.
+        let paths = recorder.records.iter().filter_map(|change| match change {
+            Change::Addition { entry_mode, path, .. } | Change::Deletion { entry_mode, path, .. } => {
+                (!entry_mode.is_tree()).then_some(path.as_ref())
+            }
+            Change::Modification {
+                previous_entry_mode,
+                entry_mode,
+                path,
+                ..
+            } => (!(previous_entry_mode.is_tree() && entry_mode.is_tree())).then_some(path.as_ref()),
+        });
+        Ok(gix_commitgraph::bloom::compute(paths, settings))
+    }
 }
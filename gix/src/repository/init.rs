@@ -14,6 +14,10 @@ impl crate::Repository {
         #[cfg(feature = "attributes")] modules: crate::submodule::ModulesFileStorage,
     ) -> Self {
         setup_objects(&mut objects, &config);
+        #[cfg(feature = "blocking-network-client")]
+        {
+            objects.promisor = super::partial_clone::promisor(refs.git_dir(), &config, &linked_worktree_options);
+        }
         crate::Repository {
             bufs: RefCell::new(Vec::with_capacity(4)),
             work_tree,
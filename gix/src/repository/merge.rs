@@ -0,0 +1,150 @@
+//! A path-based three-way merge of trees, as used to apply stashes and to cherry-pick or revert commits.
+use std::collections::BTreeMap;
+
+use gix_hash::ObjectId;
+use gix_index::entry::Mode;
+
+use super::checkout::IndexChange;
+use crate::{
+    bstr::{BStr, BString},
+    Repository,
+};
+
+/// The merged version of a path that differs from our version.
+pub(crate) enum Merged {
+    /// Use the given entry, or remove the path if `None`.
+    Resolved(Option<(ObjectId, Mode)>),
+    /// Both sides changed the path in incompatible ways.
+    Conflict {
+        mode: Mode,
+        /// The ids of the base, our and their version.
+        ids: [Option<ObjectId>; 3],
+        /// The blob to write to the worktree, which contains conflict markers if both sides are files.
+        worktree: Option<ObjectId>,
+    },
+}
+
+/// The labels to use for conflict markers in [`Repository::merge_trees()`].
+pub(crate) struct Labels<'a> {
+    pub(crate) base: &'a BStr,
+    pub(crate) ours: &'a BStr,
+    pub(crate) theirs: &'a BStr,
+}
+
+impl Repository {
+    /// Merge the changes from `base` to `theirs` into `ours`, all of which are flattened trees, and return the merged
+    /// version of each path whose version differs from `ours`.
+    ///
+    /// Paths are merged independently without detecting renames. Files changed on both sides are merged line by line,
+    /// and everything else that changed on both sides in different ways is a conflict.
+    pub(crate) fn merge_trees<E>(
+        &self,
+        base: &gix_index::State,
+        ours: &gix_index::State,
+        theirs: &gix_index::State,
+        labels: Labels<'_>,
+    ) -> Result<BTreeMap<BString, Merged>, E>
+    where
+        E: From<crate::object::find::existing::Error> + From<crate::object::write::Error>,
+    {
+        let mut versions = BTreeMap::<&BStr, [Option<(ObjectId, Mode)>; 3]>::new();
+        for (stage, state) in [base, ours, theirs].into_iter().enumerate() {
+            for entry in state.entries() {
+                versions.entry(entry.path(state)).or_default()[stage] = Some((entry.id, entry.mode));
+            }
+        }
+
+        let mut out = BTreeMap::new();
+        for (path, [b, o, t]) in versions {
+            if o == t || b == t {
+                continue;
+            }
+            let merged = if b == o {
+                Merged::Resolved(t)
+            } else {
+                match (o, t) {
+                    (Some(o), Some(t)) if is_blob(o.1) && is_blob(t.1) && b.map_or(true, |b| is_blob(b.1)) => {
+                        let mode = if o.1 == t.1 || b.map_or(false, |b| b.1 == t.1) {
+                            o.1
+                        } else {
+                            t.1
+                        };
+                        let base_data = match b {
+                            Some(b) => self.find_object(b.0)?.detach().data,
+                            None => Vec::new(),
+                        };
+                        let mut merged = Vec::new();
+                        let outcome = gix_merge::blob::merge(
+                            &mut merged,
+                            &base_data,
+                            &self.find_object(o.0)?.data,
+                            &self.find_object(t.0)?.data,
+                            gix_merge::blob::Labels {
+                                ancestor: Some(labels.base),
+                                current: Some(labels.ours),
+                                other: Some(labels.theirs),
+                            },
+                            Default::default(),
+                        );
+                        let id = self.write_blob(&merged)?.detach();
+                        if outcome.is_conflicted() {
+                            Merged::Conflict {
+                                mode,
+                                ids: [b.map(|b| b.0), Some(o.0), Some(t.0)],
+                                worktree: Some(id),
+                            }
+                        } else {
+                            Merged::Resolved(Some((id, mode)))
+                        }
+                    }
+                    _ => {
+                        let present = o.or(t).expect("at least one side has the path as they differ");
+                        Merged::Conflict {
+                            mode: present.1,
+                            ids: [b.map(|b| b.0), o.map(|o| o.0), t.map(|t| t.0)],
+                            worktree: Some(present.0),
+                        }
+                    }
+                }
+            };
+            out.insert(path.to_owned(), merged);
+        }
+        Ok(out)
+    }
+}
+
+/// The changes needed to bring the index and the worktree from our version to the merged version of each path.
+#[derive(Default)]
+pub(crate) struct Changes {
+    pub(crate) index: BTreeMap<BString, IndexChange>,
+    pub(crate) to_checkout: Vec<(BString, ObjectId, Mode)>,
+    pub(crate) to_remove: Vec<BString>,
+    pub(crate) conflicts: Vec<BString>,
+}
+
+/// Return the changes to apply to the index and the worktree for all `merged` paths, as obtained by
+/// [`Repository::merge_trees()`].
+pub(crate) fn changes(merged: &BTreeMap<BString, Merged>) -> Changes {
+    let mut out = Changes::default();
+    for (path, merged) in merged {
+        let (change, worktree) = match *merged {
+            Merged::Resolved(Some((id, mode))) => (IndexChange::Set { id, mode }, Some((id, mode))),
+            Merged::Resolved(None) => (IndexChange::Remove, None),
+            Merged::Conflict { mode, ids, worktree } => {
+                out.conflicts.push(path.clone());
+                (IndexChange::Conflict { mode, ids }, worktree.map(|id| (id, mode)))
+            }
+        };
+        match worktree {
+            Some((_, Mode::COMMIT)) => {}
+            Some((id, mode)) => out.to_checkout.push((path.clone(), id, mode)),
+            None => out.to_remove.push(path.clone()),
+        }
+        out.index.insert(path.clone(), change);
+    }
+    out
+}
+
+fn is_blob(mode: Mode) -> bool {
+    matches!(mode, Mode::FILE | Mode::FILE_EXECUTABLE)
+}
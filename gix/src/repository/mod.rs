@@ -39,6 +39,8 @@ impl crate::Repository {
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
 mod cache;
+#[cfg(feature = "worktree-mutation")]
+pub(crate) mod checkout;
 mod config;
 ///
 #[cfg(feature = "blob-diff")]
@@ -49,6 +51,8 @@ mod dirwalk;
 ///
 #[cfg(feature = "attributes")]
 pub mod filter;
+#[cfg(feature = "gpg")]
+mod gpg;
 mod graph;
 pub(crate) mod identity;
 mod impls;
@@ -59,16 +63,31 @@ mod kind;
 mod location;
 #[cfg(feature = "mailmap")]
 mod mailmap;
+#[cfg(all(
+    feature = "worktree-mutation",
+    feature = "tree-editor",
+    any(feature = "dirwalk", feature = "revision")
+))]
+pub(crate) mod merge;
 mod object;
+mod partial_clone;
 #[cfg(feature = "attributes")]
 mod pathspec;
 mod reference;
 mod remote;
+#[cfg(feature = "worktree-mutation")]
+mod reset;
+#[cfg(feature = "worktree-mutation")]
+mod restore;
 mod revision;
 mod shallow;
+#[cfg(feature = "worktree-mutation")]
+mod sparse_checkout;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
+#[cfg(feature = "worktree-mutation")]
+mod switch;
 mod thread_safe;
 mod worktree;
 
@@ -86,6 +105,97 @@ pub mod edit_tree {
     }
 }
 
+///
+#[cfg(all(feature = "tree-editor", feature = "index"))]
+pub mod write_tree_from_index {
+    /// The error returned by [Repository::write_tree_from_index()](crate::Repository::write_tree_from_index()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The index entry at '{path}' is unmerged")]
+        Unmerged { path: crate::bstr::BString },
+        #[error(transparent)]
+        EditTree(#[from] super::edit_tree::Error),
+        #[error(transparent)]
+        TreeEdit(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+    }
+}
+
+///
+#[cfg(feature = "gpg")]
+pub mod signer {
+    /// The error returned by [Repository::signer()](crate::Repository::signer()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        ConfigValue(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        ParseTime(#[from] crate::config::time::Error),
+        #[error("Committer identity is not configured, and it's needed as signing key if user.signingKey isn't set")]
+        CommitterMissing,
+        #[error("user.signingKey needs to be configured for SSH signing")]
+        MissingSshSigningKey,
+        #[error(transparent)]
+        InterpolatePath(#[from] gix_config::path::interpolate::Error),
+    }
+}
+
+///
+#[cfg(feature = "gpg")]
+pub mod verify_signature {
+    use std::path::PathBuf;
+
+    /// The error returned by [Repository::verify_signature()](crate::Repository::verify_signature()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The format of the signature could not be determined")]
+        UnknownFormat,
+        #[error("gpg.ssh.allowedSignersFile needs to be configured for SSH signature verification")]
+        MissingAllowedSignersFile,
+        #[error(transparent)]
+        InterpolatePath(#[from] gix_config::path::interpolate::Error),
+        #[error("Could not read '{}'", path.display())]
+        ReadFile { path: PathBuf, source: std::io::Error },
+        #[error(transparent)]
+        AllowedSigners(#[from] gix_gpg::ssh::allowed_signers::Error),
+        #[error("Could not parse the keys in the revocation file at '{}'", path.display())]
+        RevocationFile {
+            path: PathBuf,
+            source: gix_gpg::ssh::ssh_key::Error,
+        },
+        #[error(transparent)]
+        Ssh(#[from] gix_gpg::ssh::verify::Error),
+        #[error(transparent)]
+        Program(#[from] gix_gpg::program::verify::Error),
+    }
+}
+
+///
+pub mod promisor_objects {
+    /// The error returned by [Repository::promisor_objects()](crate::Repository::promisor_objects()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the pack directory at '{}'", path.display())]
+        ReadPackDirectory {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        OpenPackIndex(#[from] gix_pack::index::init::Error),
+        #[error(transparent)]
+        FindObject(#[from] gix_object::find::existing::Error),
+        #[error(transparent)]
+        Decode(#[from] gix_object::decode::Error),
+    }
+}
+
 ///
 #[cfg(feature = "revision")]
 pub mod merge_base {
@@ -135,6 +245,29 @@ pub mod commit_graph_if_enabled {
     }
 }
 
+///
+pub mod write_commit_graph {
+    /// The error returned by [Repository::write_commit_graph()](crate::Repository::write_commit_graph()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        GenerationVersion(#[from] crate::config::unsigned_integer::Error),
+        #[error(transparent)]
+        ChangedPathsVersion(#[from] crate::config::key::GenericError),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        FindTree(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        DiffTree(#[from] gix_diff::tree::changes::Error),
+        #[error(transparent)]
+        Write(#[from] gix_commitgraph::write::Error),
+    }
+}
+
 ///
 #[cfg(feature = "index")]
 pub mod index_from_tree {
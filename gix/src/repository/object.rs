@@ -10,7 +10,7 @@ use gix_ref::{
 };
 use smallvec::SmallVec;
 
-use crate::{commit, ext::ObjectIdExt, object, tag, Blob, Commit, Id, Object, Reference, Tag, Tree};
+use crate::{bstr::BString, commit, ext::ObjectIdExt, object, tag, Blob, Commit, Id, Object, Reference, Tag, Tree};
 
 /// Tree editing
 #[cfg(feature = "tree-editor")]
@@ -26,6 +26,31 @@ impl crate::Repository {
         let tree = self.find_tree(id)?;
         Ok(tree.edit()?)
     }
+
+    /// Write a tree with all entries of `index` to the object database and return its id, similar to `git write-tree`.
+    ///
+    /// It fails if `index` contains unmerged entries.
+    #[cfg(feature = "index")]
+    pub fn write_tree_from_index(
+        &self,
+        index: &gix_index::State,
+    ) -> Result<Id<'_>, crate::repository::write_tree_from_index::Error> {
+        use crate::repository::write_tree_from_index::Error;
+        let mut editor = self
+            .empty_tree()
+            .edit()
+            .map_err(crate::repository::edit_tree::Error::from)?;
+        for entry in index.entries() {
+            let path = entry.path(index);
+            if entry.stage() != gix_index::entry::Stage::Unconflicted {
+                return Err(Error::Unmerged { path: path.to_owned() });
+            }
+            if let Some(mode) = entry.mode.to_tree_entry_mode() {
+                editor.upsert(path, mode.kind(), entry.id)?;
+            }
+        }
+        Ok(editor.write()?)
+    }
 }
 
 /// Find objects of various kins
@@ -245,6 +270,8 @@ impl crate::Repository {
     ///
     /// It will be created with `constraint` which is most commonly to [only create it][PreviousValue::MustNotExist]
     /// or to [force overwriting a possibly existing tag](PreviousValue::Any).
+    ///
+    /// If `tag.gpgSign` is `true`, the tag object is signed with the [configured signer](Self::signer()).
     pub fn tag(
         &self,
         name: impl AsRef<str>,
@@ -254,14 +281,80 @@ impl crate::Repository {
         message: impl AsRef<str>,
         constraint: PreviousValue,
     ) -> Result<Reference<'_>, tag::Error> {
-        let tag = gix_object::Tag {
-            target: target.as_ref().into(),
+        #[cfg(feature = "gpg")]
+        let signer = self.configured_signer(&crate::config::tree::Tag::GPG_SIGN)?;
+        self.tag_inner(
+            name.as_ref(),
+            target.as_ref(),
+            target_kind,
+            tagger,
+            message.as_ref(),
+            constraint,
+            #[cfg(feature = "gpg")]
+            signer.as_ref().map(|signer| signer as &dyn gix_gpg::Signer),
+        )
+    }
+
+    /// Like [`tag(…)`](Self::tag()), but signs the tag object with `signer`, independently of `tag.gpgSign`.
+    ///
+    /// The signature is appended to the tag message just like `git tag -s` does.
+    #[cfg(feature = "gpg")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn tag_signed(
+        &self,
+        name: impl AsRef<str>,
+        target: impl AsRef<gix_hash::oid>,
+        target_kind: gix_object::Kind,
+        tagger: Option<gix_actor::SignatureRef<'_>>,
+        message: impl AsRef<str>,
+        constraint: PreviousValue,
+        signer: &dyn gix_gpg::Signer,
+    ) -> Result<Reference<'_>, tag::Error> {
+        self.tag_inner(
+            name.as_ref(),
+            target.as_ref(),
+            target_kind,
+            tagger,
+            message.as_ref(),
+            constraint,
+            Some(signer),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn tag_inner(
+        &self,
+        name: &str,
+        target: &gix_hash::oid,
+        target_kind: gix_object::Kind,
+        tagger: Option<gix_actor::SignatureRef<'_>>,
+        message: &str,
+        constraint: PreviousValue,
+        #[cfg(feature = "gpg")] signer: Option<&dyn gix_gpg::Signer>,
+    ) -> Result<Reference<'_>, tag::Error> {
+        #[allow(unused_mut)]
+        let mut tag = gix_object::Tag {
+            target: target.into(),
             target_kind,
-            name: name.as_ref().into(),
+            name: name.into(),
             tagger: tagger.map(|t| t.to_owned()),
-            message: message.as_ref().into(),
+            message: message.into(),
             pgp_signature: None,
         };
+        #[cfg(feature = "gpg")]
+        if let Some(signer) = signer {
+            use gix_object::WriteTo;
+            // The signature must start on its own line.
+            if !tag.message.is_empty() && !tag.message.ends_with(b"\n") {
+                tag.message.push(b'\n');
+            }
+            let mut buf = self.shared_empty_buf();
+            tag.write_to(buf.deref_mut()).expect("write to memory works");
+            let signature = signer.sign(&buf).map_err(tag::Error::Sign)?;
+            buf.extend_from_slice(&signature);
+            let tag_id = self.write_object_inner(&buf, gix_object::Kind::Tag)?;
+            return self.tag_reference(name, tag_id, constraint).map_err(Into::into);
+        }
         let tag_id = self.write_object(&tag)?;
         self.tag_reference(name, tag_id, constraint).map_err(Into::into)
     }
@@ -269,6 +362,10 @@ impl crate::Repository {
     /// Similar to [`commit(…)`][crate::Repository::commit()], but allows to create the commit with `committer` and `author` specified.
     ///
     /// This forces setting the commit time and author time by hand. Note that typically, committer and author are the same.
+    ///
+    /// If `commit.gpgSign` is `true`, the commit is signed with the [configured signer](Self::signer()).
+    ///
+    /// No commit [hooks](crate::hook) are run, use [`commit_index()`](Self::commit_index()) to commit like `git commit` does.
     pub fn commit_as<'a, 'c, Name, E>(
         &self,
         committer: impl Into<gix_actor::SignatureRef<'c>>,
@@ -282,6 +379,8 @@ impl crate::Repository {
         Name: TryInto<FullName, Error = E>,
         commit::Error: From<E>,
     {
+        #[cfg(feature = "gpg")]
+        let signer = self.configured_signer(&crate::config::tree::Commit::GPG_SIGN)?;
         self.commit_as_inner(
             committer.into(),
             author.into(),
@@ -289,10 +388,49 @@ impl crate::Repository {
             message.as_ref(),
             tree.into(),
             parents.into_iter().map(Into::into).collect(),
+            #[cfg(feature = "gpg")]
+            signer.as_ref().map(|signer| signer as &dyn gix_gpg::Signer),
+            #[cfg(feature = "hooks")]
+            crate::hook::CommitHooks::None,
         )
     }
 
-    fn commit_as_inner(
+    /// Like [`commit_as(…)`](Self::commit_as()), but signs the commit with `signer`, independently of `commit.gpgSign`.
+    ///
+    /// The signature is stored in the `gpgsig` header just like `git commit -S` does.
+    #[cfg(feature = "gpg")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn commit_as_signed<'a, 'c, Name, E>(
+        &self,
+        committer: impl Into<gix_actor::SignatureRef<'c>>,
+        author: impl Into<gix_actor::SignatureRef<'a>>,
+        reference: Name,
+        message: impl AsRef<str>,
+        tree: impl Into<ObjectId>,
+        parents: impl IntoIterator<Item = impl Into<ObjectId>>,
+        signer: &dyn gix_gpg::Signer,
+    ) -> Result<Id<'_>, commit::Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        commit::Error: From<E>,
+    {
+        self.commit_as_inner(
+            committer.into(),
+            author.into(),
+            reference.try_into()?,
+            message.as_ref(),
+            tree.into(),
+            parents.into_iter().map(Into::into).collect(),
+            Some(signer),
+            #[cfg(feature = "hooks")]
+            crate::hook::CommitHooks::None,
+        )
+    }
+
+    /// Write a commit and update `reference` to point to it, running the commit message `hooks` before and `post-commit`
+    /// after unless no hooks should run.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn commit_as_inner(
         &self,
         committer: gix_actor::SignatureRef<'_>,
         author: gix_actor::SignatureRef<'_>,
@@ -300,12 +438,9 @@ impl crate::Repository {
         message: &str,
         tree: ObjectId,
         parents: SmallVec<[ObjectId; 1]>,
+        #[cfg(feature = "gpg")] signer: Option<&dyn gix_gpg::Signer>,
+        #[cfg(feature = "hooks")] hooks: crate::hook::CommitHooks,
     ) -> Result<Id<'_>, commit::Error> {
-        use gix_ref::{
-            transaction::{Change, RefEdit},
-            Target,
-        };
-
         // TODO: possibly use CommitRef to save a few allocations (but will have to allocate for object ids anyway.
         //       This can be made vastly more efficient though if we wanted to, so we lie in the API
         let commit = gix_object::Commit {
@@ -317,6 +452,54 @@ impl crate::Repository {
             parents,
             extra_headers: Default::default(),
         };
+        self.write_commit_inner(
+            reference,
+            commit,
+            None,
+            #[cfg(feature = "gpg")]
+            signer,
+            #[cfg(feature = "hooks")]
+            hooks,
+        )
+    }
+
+    /// Write `commit`, signed with `signer` if set, and update `reference` to point to it with `log_message` in the reflog,
+    /// or with a message like the one of `git commit` if `None`.
+    ///
+    /// The commit message `hooks` run before the commit is written and may change its message, and `post-commit` runs
+    /// after unless no hooks should run.
+    pub(crate) fn write_commit_inner(
+        &self,
+        reference: FullName,
+        #[allow(unused_mut)] mut commit: gix_object::Commit,
+        log_message: Option<BString>,
+        #[cfg(feature = "gpg")] signer: Option<&dyn gix_gpg::Signer>,
+        #[cfg(feature = "hooks")] hooks: crate::hook::CommitHooks,
+    ) -> Result<Id<'_>, commit::Error> {
+        use gix_ref::{
+            transaction::{Change, RefEdit},
+            Target,
+        };
+
+        #[cfg(feature = "hooks")]
+        {
+            use crate::bstr::ByteSlice;
+            if let Some(message) = self.run_commit_msg_hooks(&commit.message.to_str_lossy(), hooks)? {
+                commit.message = message;
+            }
+        }
+        #[cfg(feature = "gpg")]
+        if let Some(signer) = signer {
+            use gix_object::WriteTo;
+            let mut buf = self.shared_empty_buf();
+            commit.write_to(buf.deref_mut()).expect("write to memory works");
+            let mut signature = signer.sign(&buf).map_err(commit::Error::Sign)?;
+            // Multi-line header values are written with a trailing newline already.
+            if signature.last() == Some(&b'\n') {
+                signature.pop();
+            }
+            commit.extra_headers.push(("gpgsig".into(), signature));
+        }
 
         let commit_id = self.write_object(&commit)?;
         self.edit_reference(RefEdit {
@@ -324,7 +507,10 @@ impl crate::Repository {
                 log: LogChange {
                     mode: RefLog::AndReference,
                     force_create_reflog: false,
-                    message: crate::reference::log::message("commit", commit.message.as_ref(), commit.parents.len()),
+                    log_symbolic_changes: false,
+                    message: log_message.unwrap_or_else(|| {
+                        crate::reference::log::message("commit", commit.message.as_ref(), commit.parents.len())
+                    }),
                 },
                 expected: match commit.parents.first().map(|p| Target::Object(*p)) {
                     Some(previous) => {
@@ -341,6 +527,10 @@ impl crate::Repository {
             name: reference,
             deref: true,
         })?;
+        #[cfg(feature = "hooks")]
+        if hooks != crate::hook::CommitHooks::None {
+            self.run_post_hook("post-commit", Vec::new(), &[]);
+        }
         Ok(commit_id)
     }
 
@@ -373,6 +563,44 @@ impl crate::Repository {
         self.commit_as(committer, author, reference, message, tree, parents)
     }
 
+    /// Commit the tree of the current index with `message` on top of `HEAD`, like `git commit` does, with the author
+    /// and committer taken from the configuration, and sign it if `commit.gpgSign` is `true`.
+    ///
+    /// Unlike [`commit()`](Self::commit()), this runs the `pre-commit`, `prepare-commit-msg`, `commit-msg` and
+    /// `post-commit` [hooks](crate::hook), with `pre-commit` and `commit-msg` skipped if
+    /// [`no_verify`](commit::Options::no_verify) is set. As `pre-commit` may change the index, it is read after the
+    /// hook ran.
+    #[cfg(all(feature = "tree-editor", feature = "index"))]
+    #[cfg_attr(not(feature = "hooks"), allow(unused_variables))]
+    pub fn commit_index(&self, message: impl AsRef<str>, options: commit::Options) -> Result<Id<'_>, commit::Error> {
+        let author = self.author().ok_or(commit::Error::AuthorMissing)??;
+        let committer = self.committer().ok_or(commit::Error::CommitterMissing)??;
+        #[cfg(feature = "hooks")]
+        if !options.no_verify {
+            self.run_pre_commit_hook()?;
+        }
+        let tree = self.write_tree_from_index(&*self.index_or_empty()?)?.detach();
+        let parents = self.head()?.id().map(crate::Id::detach).into_iter().collect();
+        #[cfg(feature = "gpg")]
+        let signer = self.configured_signer(&crate::config::tree::Commit::GPG_SIGN)?;
+        self.commit_as_inner(
+            committer,
+            author,
+            "HEAD".try_into().expect("valid"),
+            message.as_ref(),
+            tree,
+            parents,
+            #[cfg(feature = "gpg")]
+            signer.as_ref().map(|signer| signer as &dyn gix_gpg::Signer),
+            #[cfg(feature = "hooks")]
+            if options.no_verify {
+                crate::hook::CommitHooks::NoVerify
+            } else {
+                crate::hook::CommitHooks::Verify
+            },
+        )
+    }
+
     /// Return an empty tree object, suitable for [getting changes](Tree::changes()).
     ///
     /// Note that the returned object is special and doesn't necessarily physically exist in the object database.
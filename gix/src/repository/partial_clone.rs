@@ -0,0 +1,181 @@
+use crate::{
+    bstr::BStr,
+    config::tree::{Extensions, Key, Remote},
+    remote,
+};
+
+/// Partial clones
+impl crate::Repository {
+    /// Return the name of the *promisor remote* from which objects that are missing locally are fetched, as configured
+    /// in `extensions.partialClone`, or `None` if this repository isn't a partial clone.
+    pub fn partial_clone_remote_name(&self) -> Option<std::borrow::Cow<'_, BStr>> {
+        self.config.resolved.string(Extensions::PARTIAL_CLONE)
+    }
+
+    /// Return `true` if the remote named `remote_name` is a *promisor remote*, i.e. if objects received from it
+    /// may be incomplete.
+    ///
+    /// This is the case if `remote.<name>.promisor` is `true` or if it is the [partial clone remote](Self::partial_clone_remote_name()).
+    pub fn is_promisor_remote(&self, remote_name: &BStr) -> Result<bool, crate::config::boolean::Error> {
+        if self
+            .partial_clone_remote_name()
+            .map_or(false, |name| name.as_ref() == remote_name)
+        {
+            return Ok(true);
+        }
+        Ok(self
+            .config
+            .apply_leniency(
+                self.config
+                    .resolved
+                    .boolean_by("remote", Some(remote_name), Remote::PROMISOR.name())
+                    .map(|res| Remote::PROMISOR.enrich_error(res)),
+            )?
+            .unwrap_or(false))
+    }
+
+    /// Return the object filter to use when fetching from the remote named `remote_name`, as configured in
+    /// `remote.<name>.partialCloneFilter`.
+    pub fn partial_clone_filter(
+        &self,
+        remote_name: &BStr,
+    ) -> Result<Option<remote::fetch::Filter>, crate::config::remote::partial_clone_filter::Error> {
+        self.config.apply_leniency(
+            self.config
+                .resolved
+                .string_by("remote", Some(remote_name), Remote::PARTIAL_CLONE_FILTER.name())
+                .map(|value| Remote::PARTIAL_CLONE_FILTER.try_into_filter(value)),
+        )
+    }
+
+    /// Return all objects in packs that were received from a promisor remote along with all objects they refer to.
+    ///
+    /// These are the *promised* objects, which may be missing locally in a partial clone without the repository being
+    /// corrupt, determined like `git` does. Nothing is fetched, and the set is empty if there are no such packs.
+    pub fn promisor_objects(
+        &self,
+    ) -> Result<gix_hashtable::HashSet<gix_hash::ObjectId>, crate::repository::promisor_objects::Error> {
+        use gix_object::{FindExt, Kind};
+
+        use crate::repository::promisor_objects::Error;
+
+        let pack_dir = self.objects.store_ref().path().join("pack");
+        let mut out = gix_hashtable::HashSet::default();
+        let entries = match std::fs::read_dir(&pack_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(out),
+            Err(source) => return Err(Error::ReadPackDirectory { path: pack_dir, source }),
+        };
+        let mut buf = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|source| Error::ReadPackDirectory {
+                    path: pack_dir.clone(),
+                    source,
+                })?
+                .path();
+            if path.extension().map_or(true, |ext| ext != "promisor") {
+                continue;
+            }
+            let index = gix_pack::index::File::at(path.with_extension("idx"), self.object_hash())?;
+            for entry in index.iter() {
+                out.insert(entry.oid);
+                let object = self.objects.find(&entry.oid, &mut buf)?;
+                match object.kind {
+                    Kind::Blob => {}
+                    Kind::Tree => {
+                        for entry in gix_object::TreeRefIter::from_bytes(object.data) {
+                            out.insert(entry?.oid.to_owned());
+                        }
+                    }
+                    Kind::Commit => {
+                        let mut commit = gix_object::CommitRefIter::from_bytes(object.data);
+                        out.insert(commit.tree_id()?);
+                        out.extend(commit.parent_ids());
+                    }
+                    Kind::Tag => {
+                        out.insert(gix_object::TagRefIter::from_bytes(object.data).target_id()?);
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(feature = "blocking-network-client")]
+mod promisor {
+    use std::{path::Path, sync::atomic::AtomicBool};
+
+    use gix_hash::ObjectId;
+
+    use crate::{
+        bstr::{BStr, BString},
+        config::tree::Extensions,
+        remote,
+    };
+
+    /// Return a way to fetch missing objects from the promisor remote if the repository at `git_dir`, opened with `options`,
+    /// is a partial clone according to `config`.
+    pub(crate) fn promisor(
+        git_dir: &Path,
+        config: &crate::config::Cache,
+        options: &crate::open::Options,
+    ) -> Option<std::sync::Arc<dyn gix_odb::store::promisor::Fetch>> {
+        let remote_name = config
+            .resolved
+            .string(Extensions::PARTIAL_CLONE)
+            .and_then(|name| Extensions::PARTIAL_CLONE.try_into_symbolic_name(name).ok())?;
+        let git_dir = git_dir.to_owned();
+        let mut options = options.clone();
+        // Like `git`, don't send any `have` as these could cause the objects we want to be excluded.
+        options
+            .api_config_overrides
+            .push("fetch.negotiationAlgorithm=noop".into());
+        Some(std::sync::Arc::new(move |ids: &[ObjectId]| {
+            fetch_missing(&git_dir, options.clone(), remote_name.as_ref(), ids)
+        }))
+    }
+
+    fn fetch_missing(
+        git_dir: &Path,
+        options: crate::open::Options,
+        remote_name: &BStr,
+        ids: &[ObjectId],
+    ) -> Result<(), gix_odb::store::promisor::Error> {
+        let _span = gix_trace::coarse!("gix::partial_clone::fetch_missing()", num_objects = ids.len());
+        let mut repo = crate::ThreadSafeRepository::open_opts(git_dir, options)?.to_thread_local();
+        // Objects that are still missing must not be fetched recursively.
+        repo.objects.promisor = None;
+        let mut remote = repo
+            .find_remote(remote_name)?
+            .with_fetch_tags(remote::fetch::Tags::None);
+        remote.replace_refspecs(
+            ids.iter().map(|id| BString::from(id.to_string())),
+            remote::Direction::Fetch,
+        )?;
+        let outcome = remote
+            .connect(remote::Direction::Fetch)?
+            .prepare_fetch(gix_features::progress::Discard, Default::default())?
+            .with_filter(Some(remote::fetch::Filter::BlobNone))
+            .receive(gix_features::progress::Discard, &AtomicBool::default())?;
+        // No ref was updated, so the pack is protected by nothing but its `.keep` file, which we don't need.
+        if let remote::fetch::Status::Change { write_pack_bundle, .. } = outcome.status {
+            if let Some(keep_path) = write_pack_bundle.keep_path {
+                std::fs::remove_file(keep_path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "blocking-network-client")]
+pub(crate) use promisor::promisor;
+
+#[cfg(feature = "blocking-network-client")]
+impl crate::Repository {
+    /// Fetch missing objects from the promisor remote on demand if this repository is a partial clone.
+    pub(crate) fn setup_promisor(&mut self) {
+        self.objects.promisor = promisor(self.git_dir(), &self.config, &self.options);
+    }
+}
@@ -103,6 +103,7 @@ impl crate::Repository {
                 log: LogChange {
                     mode: RefLog::AndReference,
                     force_create_reflog: false,
+                    log_symbolic_changes: false,
                     message: log_message,
                 },
                 expected: constraint,
@@ -144,11 +145,34 @@ impl crate::Repository {
         edits: impl IntoIterator<Item = RefEdit>,
     ) -> Result<Vec<RefEdit>, reference::edit::Error> {
         let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout()?;
-        self.refs
+        let transaction = self
+            .refs
             .transaction()
-            .prepare(edits, file_lock_fail, packed_refs_lock_fail)?
-            .commit(self.committer().transpose()?)
-            .map_err(Into::into)
+            .prepare(edits, file_lock_fail, packed_refs_lock_fail)?;
+        self.commit_ref_transaction(transaction)
+    }
+
+    /// Commit the prepared `transaction` with the committer in reference logs, and run the `reference-transaction` hook
+    /// before and after, which may abort the transaction.
+    pub(crate) fn commit_ref_transaction(
+        &self,
+        transaction: gix_ref::file::Transaction<'_, '_>,
+    ) -> Result<Vec<RefEdit>, reference::edit::Error> {
+        let committer = self.committer().transpose()?;
+        #[cfg(feature = "hooks")]
+        if let Err(err) = self.run_reference_transaction_hook("prepared", transaction.prepared_edits()) {
+            let edits = transaction.rollback();
+            if let Err(_err) = self.run_reference_transaction_hook("aborted", &edits) {
+                gix_trace::warn!("ignoring failure of the 'reference-transaction' hook: {_err}");
+            }
+            return Err(err.into());
+        }
+        let edits = transaction.commit(committer)?;
+        #[cfg(feature = "hooks")]
+        if let Err(_err) = self.run_reference_transaction_hook("committed", &edits) {
+            gix_trace::warn!("ignoring failure of the 'reference-transaction' hook: {_err}");
+        }
+        Ok(edits)
     }
 
     /// Return the repository head, an abstraction to help dealing with the `HEAD` reference.
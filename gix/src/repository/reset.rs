@@ -0,0 +1,330 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix_hash::ObjectId;
+use gix_index::entry::Flags;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use super::checkout::{apply_index_changes, changes_to_target, differences, remove_files, Worktree};
+use crate::{
+    bstr::{BStr, ByteSlice},
+    reset::{Error, Mode, Outcome},
+    Repository,
+};
+
+/// Resetting
+impl Repository {
+    /// Move `HEAD`, or the branch it points to, to the commit `target` peels to, and update the index and the worktree
+    /// according to `mode`, similar to `git reset --<mode> <target>`.
+    ///
+    /// Only the index entries that differ from the tree of `target` are changed, and only the files that need an update
+    /// are [checked out](gix_worktree_state::checkout()), which applies all configured filters.
+    /// Files that are excluded from a sparse checkout are never written.
+    ///
+    /// Like `git`, the previous `HEAD` is stored in `ORIG_HEAD`, the reflog message is `reset: moving to <target>`, and
+    /// the state of an in-progress merge, cherry-pick or revert is removed.
+    /// `should_interrupt` is checked while writing files to the worktree.
+    ///
+    /// Use [`reset_to_spec()`](Self::reset_to_spec()) to have the revision appear in the reflog as spelled by the user.
+    pub fn reset(
+        &self,
+        target: impl Into<ObjectId>,
+        mode: Mode,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::reset()");
+        let target = target.into();
+        self.reset_inner(target, target.to_string().as_str().into(), mode, should_interrupt)
+    }
+
+    /// Like [`reset()`](Self::reset()), but resolve the revision `spec` to the target commit and use it as is in the
+    /// reflog message, like `reset: moving to HEAD~1`.
+    #[cfg(feature = "revision")]
+    pub fn reset_to_spec(
+        &self,
+        spec: impl AsRef<BStr>,
+        mode: Mode,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::reset_to_spec()");
+        let spec = spec.as_ref();
+        let target = self.rev_parse_single(spec)?.detach();
+        self.reset_inner(target, spec, mode, should_interrupt)
+    }
+
+    fn reset_inner(
+        &self,
+        target: ObjectId,
+        spec: &BStr,
+        mode: Mode,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let commit = self
+            .find_object(target)?
+            .peel_to_kind(gix_object::Kind::Commit)?
+            .into_commit();
+        let head_id = self.head()?.id().map(crate::Id::detach);
+
+        let outcome = match mode {
+            Mode::Soft => {
+                if self.path().join("MERGE_HEAD").is_file()
+                    || self.index_or_empty()?.entries().iter().any(|e| e.stage_raw() != 0)
+                {
+                    return Err(Error::SoftResetDuringMerge);
+                }
+                Outcome::default()
+            }
+            Mode::Mixed | Mode::Hard | Mode::Merge | Mode::Keep => {
+                let workdir = self.work_dir().ok_or(Error::BareRepository { mode })?;
+                let head_tree = head_id
+                    .map(|id| -> Result<_, Error> {
+                        Ok(self
+                            .find_object(id)?
+                            .peel_to_kind(gix_object::Kind::Commit)?
+                            .into_commit()
+                            .tree_id()?
+                            .detach())
+                    })
+                    .transpose()?;
+                self.reset_index_and_worktree(workdir, commit.tree_id()?.detach(), head_tree, mode, should_interrupt)?
+            }
+        };
+
+        let orig_head: FullName = "ORIG_HEAD".try_into().expect("valid");
+        let change = match head_id {
+            Some(head_id) => Some(Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    log_symbolic_changes: false,
+                    message: "updating ORIG_HEAD".into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(head_id),
+            }),
+            None => self.try_find_reference(&orig_head)?.map(|_| Change::Delete {
+                expected: PreviousValue::Any,
+                log: RefLog::AndReference,
+            }),
+        };
+        if let Some(change) = change {
+            self.edit_reference(RefEdit {
+                change,
+                name: orig_head,
+                deref: false,
+            })?;
+        }
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    log_symbolic_changes: false,
+                    message: format!("reset: moving to {spec}").into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(commit.id),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: true,
+        })?;
+        self.remove_branch_state();
+        Ok(outcome)
+    }
+
+    /// Set all index entries matching `patterns` to their version in the tree `tree_ish` peels to, or remove them if they
+    /// aren't in it, similar to `git reset <tree-ish> -- <patterns>`.
+    ///
+    /// Neither `HEAD` nor the worktree are changed. If `patterns` is empty, all entries are reset.
+    /// To reset paths in a repository with an unborn `HEAD`, use the [empty tree](ObjectId::empty_tree()).
+    pub fn reset_paths(
+        &self,
+        tree_ish: impl Into<ObjectId>,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::reset_paths()");
+        let tree_id = self.find_object(tree_ish.into())?.peel_to_tree()?.id;
+        let mut index = (**self.index_or_empty()?).clone();
+        index.expand_sparse_directories(&self.objects)?;
+        let target = self.index_from_tree_or_empty(Some(tree_id))?;
+        let mut pathspec = self.pathspec(
+            true,
+            patterns,
+            true,
+            &index,
+            gix_worktree::stack::state::attributes::Source::IdMapping,
+        )?;
+        let differences: Vec<_> = differences(&index, &target)
+            .into_iter()
+            .filter(|d| pathspec.is_included(d.path.as_bstr(), Some(false)))
+            .collect();
+        apply_index_changes(
+            &mut index,
+            &changes_to_target(&target, &differences),
+            &Default::default(),
+        );
+        if let Some(patterns) = self.sparse_checkout_patterns()? {
+            patterns.apply_to_index(&mut index);
+            self.collapse_sparse_directories_if_configured(&mut index, &patterns)?;
+        }
+        index.write(Default::default())?;
+        Ok(Outcome {
+            index_entries_changed: differences.len(),
+            ..Default::default()
+        })
+    }
+
+    /// Reset the index to the tree `tree_id` and update the worktree according to `mode`, with `head_tree` being the tree
+    /// of the current `HEAD` commit if it's born.
+    fn reset_index_and_worktree(
+        &self,
+        workdir: &Path,
+        tree_id: ObjectId,
+        head_tree: Option<ObjectId>,
+        mode: Mode,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let mut index = (**self.index_or_empty()?).clone();
+        index.expand_sparse_directories(&self.objects)?;
+        let sparse = self.sparse_checkout_patterns()?;
+        let target = self.index_from_tree_or_empty(Some(tree_id))?;
+        let head = match mode {
+            Mode::Keep => Some(self.index_from_tree_or_empty(head_tree)?),
+            _ => None,
+        };
+        let differences = differences(&index, &target);
+
+        let mut worktree = Worktree::new(self, workdir)?;
+        let is_skipped = |idx: usize| index.entry(idx).flags.contains(Flags::SKIP_WORKTREE);
+        let mut to_checkout = Vec::new();
+        let mut to_remove = Vec::new();
+        for d in &differences {
+            let path = d.path.as_bstr();
+            let may_write = match mode {
+                Mode::Soft | Mode::Mixed => false,
+                Mode::Hard => true,
+                Mode::Merge => {
+                    if !d.conflicted {
+                        match d.current {
+                            Some(idx) => {
+                                if !is_skipped(idx) && worktree.is_modified(index.entry(idx), path)? {
+                                    return Err(Error::NotUpToDate { path: path.to_owned() });
+                                }
+                            }
+                            None => {
+                                if worktree.exists(path) {
+                                    return Err(Error::UntrackedWouldBeOverwritten { path: path.to_owned() });
+                                }
+                            }
+                        }
+                    }
+                    true
+                }
+                Mode::Keep => {
+                    let head = head.as_ref().expect("set in keep mode");
+                    let head_entry = head.entry_by_path(path).map(|e| (e.id, e.mode));
+                    let target_entry = d.target.map(|idx| {
+                        let e = target.entry(idx);
+                        (e.id, e.mode)
+                    });
+                    if head_entry == target_entry {
+                        false
+                    } else {
+                        if d.conflicted {
+                            return Err(Error::Unmerged { path: path.to_owned() });
+                        }
+                        let current_entry = d.current.map(|idx| {
+                            let e = index.entry(idx);
+                            (e.id, e.mode)
+                        });
+                        if current_entry != head_entry {
+                            return Err(Error::NotUpToDate { path: path.to_owned() });
+                        }
+                        match d.current {
+                            Some(idx) => {
+                                if !is_skipped(idx) && worktree.is_modified(index.entry(idx), path)? {
+                                    return Err(Error::NotUpToDate { path: path.to_owned() });
+                                }
+                            }
+                            None => {
+                                if worktree.exists(path) {
+                                    return Err(Error::UntrackedWouldBeOverwritten { path: path.to_owned() });
+                                }
+                            }
+                        }
+                        true
+                    }
+                }
+            };
+            if !may_write {
+                continue;
+            }
+            match d.target.map(|idx| target.entry(idx)) {
+                Some(entry) => {
+                    if entry.mode != gix_index::entry::Mode::COMMIT
+                        && sparse.as_ref().map_or(true, |patterns| patterns.includes_file(path))
+                    {
+                        to_checkout.push((path.to_owned(), entry.id, entry.mode));
+                    }
+                }
+                None => {
+                    let is_file = d
+                        .current
+                        .map_or(true, |idx| index.entry(idx).mode != gix_index::entry::Mode::COMMIT);
+                    if is_file && !d.current.map_or(false, is_skipped) {
+                        to_remove.push(path.to_owned());
+                    }
+                }
+            }
+        }
+        if mode == Mode::Hard {
+            for (idx, entry) in index.entries().iter().enumerate() {
+                let path = entry.path(&index);
+                if entry.stage_raw() != 0
+                    || is_skipped(idx)
+                    || differences.binary_search_by(|d| d.path.as_bstr().cmp(path)).is_ok()
+                {
+                    continue;
+                }
+                if worktree.is_modified(entry, path)? {
+                    to_checkout.push((path.to_owned(), entry.id, entry.mode));
+                }
+            }
+            to_checkout.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+
+        let mut out = Outcome {
+            index_entries_changed: differences.len(),
+            ..Default::default()
+        };
+        out.files_removed = remove_files(workdir, to_remove.iter().map(AsRef::as_ref))?;
+        let (files_checked_out, stats) = self.checkout_entries(workdir, &to_checkout, should_interrupt)?;
+        out.files_checked_out = files_checked_out;
+
+        apply_index_changes(&mut index, &changes_to_target(&target, &differences), &stats);
+        if let Some(patterns) = sparse {
+            patterns.apply_to_index(&mut index);
+            self.collapse_sparse_directories_if_configured(&mut index, &patterns)?;
+        }
+        index.write(Default::default())?;
+        Ok(out)
+    }
+
+    /// Remove all files that keep the state of an in-progress merge, cherry-pick or revert, ignoring errors like `git` does.
+    pub(crate) fn remove_branch_state(&self) {
+        for name in [
+            "MERGE_HEAD",
+            "MERGE_RR",
+            "MERGE_MSG",
+            "MERGE_MODE",
+            "AUTO_MERGE",
+            "SQUASH_MSG",
+            "CHERRY_PICK_HEAD",
+            "REVERT_HEAD",
+        ] {
+            std::fs::remove_file(self.path().join(name)).ok();
+        }
+    }
+}
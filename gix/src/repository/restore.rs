@@ -0,0 +1,150 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_index::entry::{Flags, Mode};
+
+use super::checkout::{apply_index_changes, changes_to_target, differences, remove_files, Worktree};
+use crate::{
+    bstr::{BStr, ByteSlice},
+    restore::{Error, Options, Outcome},
+    Repository,
+};
+
+/// Restoring paths
+impl Repository {
+    /// Restore the index entries and tracked files matching `patterns` from the tree-ish or the index as configured by
+    /// `options`, similar to `git restore [--source=<tree-ish>] [--staged] [--worktree] -- <patterns>`.
+    ///
+    /// If `patterns` is empty, all paths are restored. Files are written with all configured filters applied and only if
+    /// they differ from the source, and tracked files that aren't in the source are removed.
+    /// Files that are excluded from a sparse checkout are never written.
+    /// Restoring the worktree from the index fails for paths with conflicts.
+    /// `should_interrupt` is checked while writing files to the worktree.
+    pub fn restore(
+        &self,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::restore()");
+        let workdir = if options.worktree {
+            Some(self.work_dir().ok_or(Error::BareRepository)?)
+        } else {
+            None
+        };
+        let source_tree = match options.source {
+            Some(tree_ish) => Some(self.find_object(tree_ish)?.peel_to_tree()?.id),
+            None if options.staged => match self.head()?.id() {
+                Some(head_id) => Some(self.find_object(head_id)?.peel_to_tree()?.id),
+                None => Some(gix_hash::ObjectId::empty_tree(self.object_hash())),
+            },
+            None => None,
+        };
+        let source = source_tree
+            .map(|tree_id| self.index_from_tree_or_empty(Some(tree_id)))
+            .transpose()?;
+
+        let mut index = (**self.index_or_empty()?).clone();
+        index.expand_sparse_directories(&self.objects)?;
+        let sparse = self.sparse_checkout_patterns()?;
+        let mut pathspec = self.pathspec(
+            true,
+            patterns,
+            true,
+            &index,
+            gix_worktree::stack::state::attributes::Source::IdMapping,
+        )?;
+
+        let changes = match source.as_ref().filter(|_| options.staged) {
+            Some(source) => {
+                let differences: Vec<_> = differences(&index, source)
+                    .into_iter()
+                    .filter(|d| pathspec.is_included(d.path.as_bstr(), Some(false)))
+                    .collect();
+                changes_to_target(source, &differences)
+            }
+            None => Default::default(),
+        };
+
+        let mut out = Outcome {
+            index_entries_changed: changes.len(),
+            ..Default::default()
+        };
+        let mut stats = Default::default();
+        if let Some(workdir) = workdir {
+            let mut worktree = Worktree::new(self, workdir)?;
+            let is_excluded = |path: &BStr| {
+                index
+                    .entry_by_path(path)
+                    .map_or(false, |e| e.flags.contains(Flags::SKIP_WORKTREE))
+                    || sparse.as_ref().map_or(false, |patterns| !patterns.includes_file(path))
+            };
+            let mut to_checkout = Vec::new();
+            let mut to_remove = Vec::new();
+            match source.as_ref() {
+                Some(source) => {
+                    for entry in source.entries() {
+                        let path = entry.path(source);
+                        if entry.mode == Mode::COMMIT || !pathspec.is_included(path, Some(false)) || is_excluded(path) {
+                            continue;
+                        }
+                        // Prefer the index entry if it's the same to be able to use its stat information.
+                        let current = index
+                            .entry_by_path(path)
+                            .filter(|e| e.stage_raw() == 0 && e.id == entry.id && e.mode == entry.mode)
+                            .unwrap_or(entry);
+                        if worktree.is_modified(current, path)? {
+                            to_checkout.push((path.to_owned(), entry.id, entry.mode));
+                        }
+                    }
+                    let mut previous_path = None;
+                    for entry in index.entries() {
+                        let path = entry.path(&index);
+                        if previous_path == Some(path)
+                            || entry.mode == Mode::COMMIT
+                            || entry.flags.contains(Flags::SKIP_WORKTREE)
+                            || source.entry_by_path(path).is_some()
+                            || !pathspec.is_included(path, Some(false))
+                        {
+                            continue;
+                        }
+                        previous_path = Some(path);
+                        to_remove.push(path.to_owned());
+                    }
+                }
+                None => {
+                    for entry in index.entries() {
+                        let path = entry.path(&index);
+                        if entry.mode == Mode::COMMIT
+                            || entry.flags.contains(Flags::SKIP_WORKTREE)
+                            || !pathspec.is_included(path, Some(false))
+                        {
+                            continue;
+                        }
+                        if entry.stage_raw() != 0 {
+                            return Err(Error::Unmerged { path: path.to_owned() });
+                        }
+                        if worktree.is_modified(entry, path)? {
+                            to_checkout.push((path.to_owned(), entry.id, entry.mode));
+                        }
+                    }
+                }
+            }
+            out.files_removed = remove_files(workdir, to_remove.iter().map(AsRef::as_ref))?;
+            let (files_checked_out, checked_out_stats) =
+                self.checkout_entries(workdir, &to_checkout, should_interrupt)?;
+            out.files_checked_out = files_checked_out;
+            stats = checked_out_stats;
+        }
+
+        if changes.is_empty() && stats.is_empty() {
+            return Ok(out);
+        }
+        apply_index_changes(&mut index, &changes, &stats);
+        if let Some(patterns) = sparse {
+            patterns.apply_to_index(&mut index);
+            self.collapse_sparse_directories_if_configured(&mut index, &patterns)?;
+        }
+        index.write(Default::default())?;
+        Ok(out)
+    }
+}
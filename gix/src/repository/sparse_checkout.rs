@@ -0,0 +1,271 @@
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use gix_index::entry::Flags;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::tree::{Core, Index, Key},
+    sparse_checkout::{patterns, update, Cone, NonCone, Outcome, Patterns},
+};
+
+/// Sparse checkouts
+impl crate::Repository {
+    /// Return the path to the file with the sparse-checkout patterns of the current worktree.
+    pub fn sparse_checkout_file(&self) -> std::path::PathBuf {
+        self.git_dir().join("info").join("sparse-checkout")
+    }
+
+    /// Return the sparse-checkout patterns if `core.sparseCheckout` is enabled and the
+    /// [sparse-checkout file](Self::sparse_checkout_file()) exists, or `None` if everything is checked out.
+    ///
+    /// Cone mode is used unless `core.sparseCheckoutCone` is `false`, or if the patterns aren't valid in cone mode.
+    pub fn sparse_checkout_patterns(&self) -> Result<Option<Patterns>, patterns::Error> {
+        if !self.config_boolean(&Core::SPARSE_CHECKOUT, false)? {
+            return Ok(None);
+        }
+        let cone = self.config_boolean(&Core::SPARSE_CHECKOUT_CONE, true)?;
+        let path = self.sparse_checkout_file();
+        match std::fs::read(&path) {
+            Ok(bytes) => Ok(Some(Patterns::from_bytes(&bytes, cone))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(patterns::Error::Io { path, source: err }),
+        }
+    }
+
+    /// List the directories of a sparse checkout in cone mode, or its patterns in non-cone mode, or return an empty list
+    /// if sparse checkout isn't enabled.
+    pub fn sparse_checkout_list(&self) -> Result<Vec<BString>, patterns::Error> {
+        Ok(self
+            .sparse_checkout_patterns()?
+            .map(|patterns| patterns.list())
+            .unwrap_or_default())
+    }
+
+    /// Enable sparse checkout and only include `patterns` in the worktree, which are directories if `cone` is `true`, or
+    /// gitignore-style patterns otherwise.
+    ///
+    /// This persists `core.sparseCheckout` and `core.sparseCheckoutCone` in the repository configuration, writes the
+    /// [sparse-checkout file](Self::sparse_checkout_file()) and [updates the worktree](Self::sparse_checkout_reapply()).
+    pub fn sparse_checkout_set(
+        &mut self,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        cone: bool,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, update::Error> {
+        let patterns = if cone {
+            Patterns::Cone(Cone::from_directories(patterns))
+        } else {
+            Patterns::NonCone(NonCone::from_patterns(patterns))
+        };
+        self.persist_sparse_checkout_config(cone)?;
+        self.write_sparse_checkout_file(&patterns)?;
+        self.sparse_checkout_reapply(should_interrupt)
+    }
+
+    /// Add `patterns` to the existing sparse-checkout patterns, which are directories in cone mode, and
+    /// [update the worktree](Self::sparse_checkout_reapply()).
+    ///
+    /// Fails if sparse checkout isn't enabled.
+    pub fn sparse_checkout_add(
+        &mut self,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, update::Error> {
+        let mut current = self.sparse_checkout_patterns()?.ok_or(update::Error::NotEnabled)?;
+        for pattern in patterns {
+            match &mut current {
+                Patterns::Cone(cone) => cone.add_directory(pattern.as_ref()),
+                Patterns::NonCone(non_cone) => non_cone.add_pattern(pattern.as_ref()),
+            }
+        }
+        self.write_sparse_checkout_file(&current)?;
+        self.sparse_checkout_reapply(should_interrupt)
+    }
+
+    /// Apply the current sparse-checkout patterns to the index and the worktree.
+    ///
+    /// Files that are now excluded are removed from the worktree unless they are modified, in which case they stay
+    /// included. Files that are now included are checked out.
+    /// With `index.sparse` enabled in cone mode, excluded directories are collapsed into sparse directories when
+    /// writing the index.
+    ///
+    /// Fails if sparse checkout isn't enabled.
+    pub fn sparse_checkout_reapply(&self, should_interrupt: &AtomicBool) -> Result<Outcome, update::Error> {
+        let _span = gix_trace::coarse!("gix::sparse_checkout_reapply()");
+        let patterns = self.sparse_checkout_patterns()?.ok_or(update::Error::NotEnabled)?;
+        let workdir = self.work_dir().ok_or(update::Error::BareRepository)?;
+        let mut index = self.open_index()?;
+        index.expand_sparse_directories(&self.objects)?;
+
+        let changes = patterns.apply_to_index(&mut index);
+        let mut out = Outcome::default();
+        let stat_options = self.config.stat_options()?;
+        for idx in changes.newly_skipped {
+            let entry = index.entry(idx);
+            let rela_path = entry.path(&index).to_owned();
+            let path = workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+            let io_err = |source| update::Error::Io {
+                path: path.clone(),
+                source,
+            };
+            match is_unmodified(entry, &path, self.object_hash(), stat_options).map_err(io_err)? {
+                None => {}
+                Some(true) => {
+                    std::fs::remove_file(&path).map_err(io_err)?;
+                    remove_empty_parent_directories(&path, workdir);
+                    out.files_removed += 1;
+                }
+                Some(false) => {
+                    let entry = &mut index.entries_mut()[idx];
+                    entry.flags.remove(Flags::SKIP_WORKTREE);
+                    if !entry.flags.contains(Flags::INTENT_TO_ADD) {
+                        entry.flags.remove(Flags::EXTENDED);
+                    }
+                    out.kept_modified.push(rela_path);
+                }
+            }
+        }
+
+        // Files that are already present, maybe because they were kept, aren't overwritten.
+        let mut to_checkout = gix_index::State::new(self.object_hash());
+        for idx in changes.newly_included {
+            let entry = index.entry(idx);
+            let rela_path = entry.path(&index);
+            if workdir.join(gix_path::from_bstr(rela_path)).symlink_metadata().is_ok() {
+                continue;
+            }
+            to_checkout.dangerously_push_entry(Default::default(), entry.id, Flags::empty(), entry.mode, rela_path);
+        }
+        if !to_checkout.entries().is_empty() {
+            let opts = self.config.checkout_options(
+                self,
+                gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )?;
+            let outcome = gix_worktree_state::checkout(
+                &mut to_checkout,
+                workdir,
+                self.objects.clone().into_arc().map_err(|err| update::Error::Io {
+                    path: self.objects.store_ref().path().into(),
+                    source: err,
+                })?,
+                &gix_features::progress::Discard,
+                &gix_features::progress::Discard,
+                should_interrupt,
+                opts,
+            )?;
+            out.files_checked_out = outcome.files_updated;
+            for checked_out in to_checkout.entries() {
+                if let Some(entry) = index
+                    .entry_mut_by_path_and_stage(checked_out.path(&to_checkout), gix_index::entry::Stage::Unconflicted)
+                {
+                    entry.stat = checked_out.stat;
+                }
+            }
+        }
+
+        out.sparse_directories = self.collapse_sparse_directories_if_configured(&mut index, &patterns)?;
+        index.write(Default::default())?;
+        Ok(out)
+    }
+
+    /// Collapse all directories excluded by `patterns` in `index` if `index.sparse` is enabled, and return the amount
+    /// of sparse directories in `index`.
+    pub(crate) fn collapse_sparse_directories_if_configured(
+        &self,
+        index: &mut gix_index::State,
+        patterns: &Patterns,
+    ) -> Result<usize, crate::config::boolean::Error> {
+        if patterns.is_cone() && self.config_boolean(&Index::SPARSE, false)? {
+            index.collapse_sparse_directories(|dir| patterns.excludes_directory(dir), &self.objects);
+        }
+        Ok(index.entries().iter().filter(|e| e.mode.is_sparse()).count())
+    }
+
+    fn config_boolean(
+        &self,
+        key: &'static crate::config::tree::keys::Boolean,
+        default: bool,
+    ) -> Result<bool, crate::config::boolean::Error> {
+        Ok(self
+            .config
+            .apply_leniency(self.config.resolved.boolean(key).map(|res| key.enrich_error(res)))?
+            .unwrap_or(default))
+    }
+
+    /// Enable sparse checkout in the repository configuration, both on disk and in memory.
+    fn persist_sparse_checkout_config(&mut self, cone: bool) -> Result<(), update::Error> {
+        let cone = if cone { "true" } else { "false" };
+        let path = self.common_dir().join("config");
+        let mut file = gix_config::File::from_path_no_includes(path.clone(), gix_config::Source::Local)?;
+        for (key, value) in [(&Core::SPARSE_CHECKOUT, "true"), (&Core::SPARSE_CHECKOUT_CONE, cone)] {
+            file.set_raw_value_by("core", None, key.name(), value)
+                .map_err(crate::config::set_value::Error::from)?;
+        }
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        file.write_to(&mut lock).map_err(|err| update::Error::Io {
+            path: path.clone(),
+            source: err,
+        })?;
+        lock.commit().map_err(|err| update::Error::Io {
+            path,
+            source: err.error,
+        })?;
+
+        let mut config = self.config_snapshot_mut();
+        config.set_value(&Core::SPARSE_CHECKOUT, "true")?;
+        config.set_value(&Core::SPARSE_CHECKOUT_CONE, cone)?;
+        config.commit()?;
+        Ok(())
+    }
+
+    fn write_sparse_checkout_file(&self, patterns: &Patterns) -> Result<(), update::Error> {
+        let path = self.sparse_checkout_file();
+        let io_err = |source| update::Error::Io {
+            path: path.clone(),
+            source,
+        };
+        std::fs::create_dir_all(path.parent().expect("file in directory")).map_err(io_err)?;
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        patterns.write_to(&mut lock).map_err(io_err)?;
+        lock.commit().map_err(|err| io_err(err.error))?;
+        Ok(())
+    }
+}
+
+/// Return `None` if there is no file at `path`, or `true` if it matches `entry`.
+fn is_unmodified(
+    entry: &gix_index::Entry,
+    path: &Path,
+    object_hash: gix_hash::Kind,
+    options: gix_index::entry::stat::Options,
+) -> std::io::Result<Option<bool>> {
+    let metadata = match gix_index::fs::Metadata::from_path_no_follow(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    if metadata.is_dir() {
+        return Ok(None);
+    }
+    if gix_index::entry::Stat::from_fs(&metadata).map_or(false, |stat| entry.stat.matches(&stat, options)) {
+        return Ok(Some(true));
+    }
+    let data = if metadata.is_symlink() {
+        gix_path::into_bstr(std::fs::read_link(path)?).into_owned().into()
+    } else {
+        std::fs::read(path)?
+    };
+    Ok(Some(
+        gix_object::compute_hash(object_hash, gix_object::Kind::Blob, &data) == entry.id,
+    ))
+}
+
+/// Remove the parent directories of `path` up to `workdir` for as long as they are empty.
+pub(crate) fn remove_empty_parent_directories(path: &Path, workdir: &Path) {
+    for dir in path.ancestors().skip(1).take_while(|dir| *dir != workdir) {
+        if std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
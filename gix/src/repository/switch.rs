@@ -0,0 +1,220 @@
+use std::{collections::BTreeMap, sync::atomic::AtomicBool};
+
+use gix_index::entry::{Flags, Mode};
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target as RefTarget,
+};
+
+use super::checkout::{apply_index_changes, differences, remove_files, IndexChange, Worktree};
+use crate::{
+    bstr::{BString, ByteSlice},
+    switch::{Error, Options, Outcome, Target},
+    Repository,
+};
+
+/// Switching branches
+impl Repository {
+    /// Make `HEAD` point to `target` and update the index and the worktree from the tree of the current `HEAD` commit
+    /// to the one of the target commit, similar to `git switch` and `git checkout <branch>`.
+    ///
+    /// Like `git`, this is a two-way merge between the index and the target tree, which carries over local changes in the
+    /// index and the worktree to paths that are the same in `HEAD` and the target, and refuses to switch if local changes
+    /// to any other path would be lost, unless [`force`](Options::force) or [`merge`](Options::merge) is set.
+    /// Files are written with all configured filters applied, and files that are excluded from a sparse checkout are never written.
+    ///
+    /// The reflog message is `checkout: moving from <old> to <new>`, and the state of an in-progress merge, cherry-pick
+    /// or revert is removed. `should_interrupt` is checked while writing files to the worktree.
+    /// The `post-checkout` [hook](crate::hook) runs once `HEAD` was changed.
+    pub fn switch(&self, target: Target, options: Options, should_interrupt: &AtomicBool) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::switch()");
+        let workdir = self.work_dir().ok_or(Error::BareRepository)?;
+        let target_id = match &target {
+            Target::Branch(name) => self.find_reference(name.as_ref())?.peel_to_id_in_place()?.detach(),
+            Target::Detached(id) => *id,
+        };
+        let commit = self
+            .find_object(target_id)?
+            .peel_to_kind(gix_object::Kind::Commit)?
+            .into_commit();
+        let head = self.head()?;
+        let head_tree = match head.id() {
+            Some(id) => Some(
+                self.find_object(id)?
+                    .peel_to_kind(gix_object::Kind::Commit)?
+                    .into_commit()
+                    .tree_id()?
+                    .detach(),
+            ),
+            None => None,
+        };
+
+        let mut index = (**self.index_or_empty()?).clone();
+        index.expand_sparse_directories(&self.objects)?;
+        let sparse = self.sparse_checkout_patterns()?;
+        let head_index = self.index_from_tree_or_empty(head_tree)?;
+        let target_index = self.index_from_tree_or_empty(Some(commit.tree_id()?.detach()))?;
+        let differences = differences(&index, &target_index);
+
+        let mut worktree = Worktree::new(self, workdir)?;
+        let is_skipped = |idx: usize| index.entry(idx).flags.contains(Flags::SKIP_WORKTREE);
+        let old_name = match head.referent_name() {
+            Some(name) => name.shorten().to_owned(),
+            None => head.id().map(|id| id.to_string().into()).unwrap_or_default(),
+        };
+        let new_name: BString = match &target {
+            Target::Branch(name) => name.shorten().to_owned(),
+            Target::Detached(id) => id.to_string().into(),
+        };
+
+        let mut changes = BTreeMap::new();
+        let mut to_checkout = Vec::new();
+        let mut to_remove = Vec::new();
+        let mut out = Outcome::default();
+        for d in &differences {
+            let path = d.path.as_bstr();
+            let head_entry = head_index.entry_by_path(path).map(|e| (e.id, e.mode));
+            let target_entry = d.target.map(|idx| {
+                let e = target_index.entry(idx);
+                (e.id, e.mode)
+            });
+            let current_entry = d.current.map(|idx| {
+                let e = index.entry(idx);
+                (e.id, e.mode)
+            });
+            if !options.force {
+                if d.conflicted {
+                    return Err(Error::Unmerged { path: path.to_owned() });
+                }
+                if head_entry == target_entry {
+                    // The local changes are carried over.
+                    continue;
+                }
+                if current_entry != head_entry {
+                    return Err(Error::LocalChangesWouldBeOverwritten { path: path.to_owned() });
+                }
+            }
+
+            let mut change = match target_entry {
+                Some((id, mode)) => IndexChange::Set { id, mode },
+                None => IndexChange::Remove,
+            };
+            let mut worktree_id = target_entry.map(|(id, _)| id);
+            if !options.force {
+                match d.current {
+                    Some(idx) => {
+                        if !is_skipped(idx) && worktree.is_modified(index.entry(idx), path)? {
+                            let (base, ours) = match (head_entry, target_entry) {
+                                (Some(base), Some(ours)) if options.merge && is_blob(base.1) && is_blob(ours.1) => {
+                                    (base, ours)
+                                }
+                                _ => return Err(Error::LocalChangesWouldBeOverwritten { path: path.to_owned() }),
+                            };
+                            let theirs = worktree.read(path, false)?.to_owned();
+                            let mut merged = Vec::new();
+                            let merge = gix_merge::blob::merge(
+                                &mut merged,
+                                &self.find_object(base.0)?.data,
+                                &self.find_object(ours.0)?.data,
+                                &theirs,
+                                gix_merge::blob::Labels {
+                                    ancestor: Some(old_name.as_ref()),
+                                    current: Some(new_name.as_ref()),
+                                    other: Some("local".into()),
+                                },
+                                Default::default(),
+                            );
+                            worktree_id = Some(self.write_blob(&merged)?.detach());
+                            if merge.is_conflicted() {
+                                change = IndexChange::Conflict {
+                                    mode: ours.1,
+                                    ids: [Some(base.0), Some(ours.0), Some(self.write_blob(&theirs)?.detach())],
+                                };
+                                out.conflicts.push(path.to_owned());
+                            }
+                        }
+                    }
+                    None => {
+                        if target_entry.is_some() && worktree.exists(path) {
+                            return Err(Error::UntrackedWouldBeOverwritten { path: path.to_owned() });
+                        }
+                    }
+                }
+            }
+
+            match (target_entry, worktree_id) {
+                (Some((_, mode)), Some(id)) => {
+                    if mode != Mode::COMMIT && sparse.as_ref().map_or(true, |patterns| patterns.includes_file(path)) {
+                        to_checkout.push((path.to_owned(), id, mode));
+                    }
+                }
+                _ => {
+                    let is_file = d.current.map_or(true, |idx| index.entry(idx).mode != Mode::COMMIT);
+                    if is_file && !d.current.map_or(false, is_skipped) {
+                        to_remove.push(path.to_owned());
+                    }
+                }
+            }
+            changes.insert(path.to_owned(), change);
+        }
+        if options.force {
+            for (idx, entry) in index.entries().iter().enumerate() {
+                let path = entry.path(&index);
+                if entry.stage_raw() != 0 || is_skipped(idx) || changes.contains_key(path) {
+                    continue;
+                }
+                if worktree.is_modified(entry, path)? {
+                    to_checkout.push((path.to_owned(), entry.id, entry.mode));
+                }
+            }
+            to_checkout.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+
+        out.files_removed = remove_files(workdir, to_remove.iter().map(AsRef::as_ref))?;
+        let (files_checked_out, stats) = self.checkout_entries(workdir, &to_checkout, should_interrupt)?;
+        out.files_checked_out = files_checked_out;
+        apply_index_changes(&mut index, &changes, &stats);
+        if let Some(patterns) = sparse {
+            patterns.apply_to_index(&mut index);
+            self.collapse_sparse_directories_if_configured(&mut index, &patterns)?;
+        }
+        index.write(Default::default())?;
+
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    log_symbolic_changes: true,
+                    message: format!("checkout: moving from {old_name} to {new_name}").into(),
+                },
+                expected: PreviousValue::Any,
+                new: match target {
+                    Target::Branch(name) => RefTarget::Symbolic(name),
+                    Target::Detached(_) => RefTarget::Object(commit.id),
+                },
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+        self.remove_branch_state();
+        #[cfg(feature = "hooks")]
+        self.run_post_hook(
+            "post-checkout",
+            vec![
+                head.id()
+                    .map_or_else(|| self.object_hash().null(), crate::Id::detach)
+                    .to_string()
+                    .into(),
+                commit.id.to_string().into(),
+                "1".into(),
+            ],
+            &[],
+        );
+        Ok(out)
+    }
+}
+
+fn is_blob(mode: Mode) -> bool {
+    matches!(mode, Mode::FILE | Mode::FILE_EXECUTABLE)
+}
@@ -25,6 +25,49 @@ impl crate::Repository {
         res.sort_by(|a, b| a.git_dir.cmp(&b.git_dir));
         Ok(res)
     }
+
+    /// Repair the linked worktree whose checkout is at `base` after it was moved manually, by pointing its private git directory
+    /// back to it, and by assuring its `.git` file points to the private git directory, like `git worktree repair <path>`.
+    ///
+    /// Returns `true` if anything had to be repaired.
+    pub fn worktree_repair_at(
+        &self,
+        base: impl AsRef<std::path::Path>,
+    ) -> Result<bool, worktree::proxy::repair::Error> {
+        use worktree::proxy::repair::Error;
+        let base =
+            gix_path::realpath(base.as_ref()).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        let dot_git = base.join(gix_discover::DOT_GIT_DIR);
+        if !dot_git.is_file() {
+            return Err(Error::DotGitIsNotAFile { path: dot_git });
+        }
+        let private_git_dir = gix_discover::path::from_gitdir_file(&dot_git).map_err(|err| Error::ReadGitDirFile {
+            path: dot_git.clone(),
+            source: err,
+        })?;
+        // The `.git` file might point to a private git directory that was moved along with the main repository, so only trust its name.
+        let worktrees_dir = gix_path::realpath(self.common_dir().join("worktrees"))
+            .map_err(|_| Error::UnknownWorktree { path: base.clone() })?;
+        let expected_git_dir = private_git_dir
+            .file_name()
+            .map(|id| worktrees_dir.join(id))
+            .filter(|dir| dir.is_dir())
+            .ok_or_else(|| Error::UnknownWorktree { path: base.clone() })?;
+
+        let mut repaired = false;
+        if gix_path::realpath(&private_git_dir).ok().as_ref() != Some(&expected_git_dir) {
+            worktree::proxy::write_dot_git_file(&dot_git, &expected_git_dir)?;
+            repaired = true;
+        }
+        let gitdir_file = expected_git_dir.join("gitdir");
+        let recorded_dot_git = gix_discover::path::from_plain_file(&gitdir_file).and_then(Result::ok);
+        if recorded_dot_git.as_ref() != Some(&dot_git) {
+            worktree::proxy::write_gitdir_file(&expected_git_dir, &base)?;
+            repaired = true;
+        }
+        Ok(repaired)
+    }
+
     /// Return the repository owning the main worktree, typically from a linked worktree.
     ///
     /// Note that it might be the one that is currently open if this repository doesn't point to a linked worktree.
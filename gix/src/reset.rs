@@ -0,0 +1,113 @@
+//! Reset `HEAD`, the index and the worktree to a commit, similar to `git reset`.
+//!
+//! See [`Repository::reset()`](crate::Repository::reset()) or
+//! [`Repository::reset_to_spec()`](crate::Repository::reset_to_spec()) to move `HEAD` and update the index and the worktree
+//! according to a [`Mode`], and [`Repository::reset_paths()`](crate::Repository::reset_paths()) to only reset some index entries.
+use crate::bstr::BString;
+
+/// The way [`Repository::reset()`](crate::Repository::reset()) affects the index and the worktree after moving `HEAD`.
+///
+/// Given a file whose version in the worktree, the index, `HEAD` and the target commit are `W`, `I`, `H` and `T`
+/// respectively, the modes differ in what they keep and when they refuse to proceed, exactly like described in the
+/// documentation of `git reset`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Only move `HEAD`, and leave the index and the worktree untouched, like `--soft`.
+    ///
+    /// This isn't possible while a merge is in progress.
+    Soft,
+    /// Move `HEAD` and reset the index, but leave the worktree untouched, like `--mixed`.
+    #[default]
+    Mixed,
+    /// Move `HEAD` and reset the index and all tracked files in the worktree, discarding all changes, like `--hard`.
+    ///
+    /// Tracked files that aren't in the target commit are removed, untracked files are left alone unless they are
+    /// in the way of a file that is checked out.
+    Hard,
+    /// Move `HEAD`, reset the index and update the files that differ between the target commit and the index, but keep
+    /// changes in all other files, like `--merge`.
+    ///
+    /// Fails if a file that would be updated has changes that aren't in the index, which makes this suitable to abort
+    /// a merge with conflicts.
+    Merge,
+    /// Move `HEAD`, reset the index and update the files that differ between the target commit and `HEAD`, but keep
+    /// changes in all other files, like `--keep`.
+    ///
+    /// Fails if a file that would be updated has any local changes, in the index or the worktree.
+    Keep,
+}
+
+/// The result of [`Repository::reset()`](crate::Repository::reset()) and
+/// [`Repository::reset_paths()`](crate::Repository::reset_paths()).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of index entries that were added, changed or removed, with all stages of a conflict counting as one.
+    pub index_entries_changed: usize,
+    /// The amount of files that were written to the worktree.
+    pub files_checked_out: usize,
+    /// The amount of files that were removed from the worktree as they aren't tracked anymore.
+    pub files_removed: usize,
+}
+
+/// The error returned by [`Repository::reset()`](crate::Repository::reset()) and
+/// [`Repository::reset_paths()`](crate::Repository::reset_paths()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A {mode:?} reset is not possible in a bare repository")]
+    BareRepository { mode: Mode },
+    #[error("Cannot do a soft reset in the middle of a merge")]
+    SoftResetDuringMerge,
+    #[error("Entry '{path}' not uptodate. Cannot merge.")]
+    NotUpToDate { path: BString },
+    #[error("Untracked working tree file '{path}' would be overwritten by merge.")]
+    UntrackedWouldBeOverwritten { path: BString },
+    #[error("Entry '{path}' would be overwritten by merge. Cannot merge.")]
+    Unmerged { path: BString },
+    #[error(transparent)]
+    #[cfg(feature = "revision")]
+    RevParse(#[from] crate::revision::spec::parse::single::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    Peel(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    ExpandIndex(#[from] gix_index::sparse::expand::Error),
+    #[error(transparent)]
+    SparseCheckoutPatterns(#[from] crate::sparse_checkout::patterns::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error("Could not fetch the objects to check out from the promisor remote")]
+    FetchMissingObjects(#[source] gix_odb::store::promisor::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error("Could not access '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
@@ -0,0 +1,88 @@
+//! Restore paths in the index or the worktree from a tree or the index, similar to `git restore`.
+//!
+//! See [`Repository::restore()`](crate::Repository::restore()) for details.
+use gix_hash::ObjectId;
+
+use crate::bstr::BString;
+
+/// Options for use in [`Repository::restore()`](crate::Repository::restore()).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The tree-ish to restore from, like `--source`.
+    ///
+    /// If `None`, the worktree is restored from the index, and the index is restored from `HEAD`.
+    pub source: Option<ObjectId>,
+    /// If `true`, restore the index, like `--staged`.
+    pub staged: bool,
+    /// If `true`, restore the files in the worktree, like `--worktree`.
+    pub worktree: bool,
+}
+
+impl Default for Options {
+    /// Restore the worktree from the index, just like `git restore` does without any flags.
+    fn default() -> Self {
+        Options {
+            source: None,
+            staged: false,
+            worktree: true,
+        }
+    }
+}
+
+/// The result of [`Repository::restore()`](crate::Repository::restore()).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of index entries that were added, changed or removed, with all stages of a conflict counting as one.
+    pub index_entries_changed: usize,
+    /// The amount of files that were written to the worktree.
+    pub files_checked_out: usize,
+    /// The amount of files that were removed from the worktree as they aren't in the source.
+    pub files_removed: usize,
+}
+
+/// The error returned by [`Repository::restore()`](crate::Repository::restore()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot restore the worktree of a bare repository")]
+    BareRepository,
+    #[error("Path '{path}' is unmerged")]
+    Unmerged { path: BString },
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    Peel(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    ExpandIndex(#[from] gix_index::sparse::expand::Error),
+    #[error(transparent)]
+    SparseCheckoutPatterns(#[from] crate::sparse_checkout::patterns::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error("Could not fetch the objects to check out from the promisor remote")]
+    FetchMissingObjects(#[source] gix_odb::store::promisor::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error("Could not access '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
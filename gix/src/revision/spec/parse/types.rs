@@ -66,6 +66,8 @@ pub enum Error {
     UnbornHeadsHaveNoRefLog,
     #[error("Unborn heads cannot have push or upstream tracking branches")]
     UnbornHeadForSibling,
+    #[error("The prior checkout isn't a branch and cannot have push or upstream tracking branches")]
+    PriorCheckoutIsNoBranch,
     #[error("Branch named {name} does not have a {} tracking branch configured", direction.as_str())]
     NoTrackingBranch {
         name: gix_ref::FullName,
@@ -81,6 +83,8 @@ pub enum Error {
     Planned { dependency: &'static str },
     #[error("Reference {reference:?} does not have a reference log, cannot {action}")]
     MissingRefLog { reference: BString, action: &'static str },
+    #[error("Reference {reference:?} has no ref-log entries, cannot {action}")]
+    EmptyRefLog { reference: BString, action: &'static str },
     #[error("HEAD has {available} prior checkouts and checkout number {desired} is out of range")]
     PriorCheckoutOutOfRange { desired: usize, available: usize },
     #[error("Reference {:?} has {available} ref-log entries and entry number {desired} is out of range", reference.name.as_bstr())]
@@ -1,7 +1,6 @@
 use gix_hash::ObjectId;
-use gix_object::FindExt;
 
-use crate::{ext::ObjectIdExt, revision, Repository};
+use crate::{bstr::BString, ext::ObjectIdExt, revision, Repository};
 
 /// The error returned by [`Platform::all()`] and [`Platform::selected()`].
 #[derive(Debug, thiserror::Error)]
@@ -155,6 +154,7 @@ pub struct Platform<'repo> {
     pub(crate) parents: gix_traverse::commit::Parents,
     pub(crate) use_commit_graph: Option<bool>,
     pub(crate) commit_graph: Option<gix_commitgraph::Graph>,
+    pub(crate) changed_paths: Vec<BString>,
 }
 
 impl<'repo> Platform<'repo> {
@@ -167,6 +167,7 @@ impl<'repo> Platform<'repo> {
             use_commit_graph: None,
             commit_graph: None,
             prune: Vec::new(),
+            changed_paths: Vec::new(),
         }
     }
 }
@@ -205,6 +206,18 @@ impl<'repo> Platform<'repo> {
         self
     }
 
+    /// Only return commits which changed any of `paths` compared to their first parent, or which contain any of them if they
+    /// are root commits, similar to `git log --first-parent -- <paths>`.
+    ///
+    /// `paths` are relative to the root of the repository and may refer to files or directories. Commits which aren't returned
+    /// are still traversed, and merge commits are only compared to their first parent.
+    /// If the commit-graph has changed-path Bloom filters, as written by [`Repository::write_commit_graph()`], these are used
+    /// to skip commits that definitely didn't change any of `paths` without diffing their trees.
+    pub fn with_changed_paths(mut self, paths: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        self.changed_paths.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
     /// Prune the commit with the given `ids` such that they won't be returned, and such that none of their ancestors is returned either.
     ///
     /// Note that this forces the [sorting](Self::sorting) to
@@ -255,43 +268,20 @@ impl<'repo> Platform<'repo> {
             use_commit_graph,
             commit_graph,
             mut prune,
+            changed_paths,
         } = self;
         prune.sort();
         Ok(revision::Walk {
             repo,
             inner: Box::new(
-                gix_traverse::commit::Simple::filtered(tips, &repo.objects, {
-                    // Note that specific shallow handling for commit-graphs isn't needed as these contain
-                    // all information there is, and exclude shallow parents to be structurally consistent.
-                    let shallow_commits = repo.shallow_commits()?;
-                    let mut grafted_parents_to_skip = Vec::new();
-                    let mut buf = Vec::new();
-                    move |id| {
-                        if !filter(id) {
-                            return false;
-                        }
-                        let id = id.to_owned();
-                        if prune.binary_search(&id).is_ok() {
-                            return false;
-                        }
-                        match shallow_commits.as_ref() {
-                            Some(commits) => {
-                                if let Ok(idx) = grafted_parents_to_skip.binary_search(&id) {
-                                    grafted_parents_to_skip.remove(idx);
-                                    return false;
-                                };
-                                if commits.binary_search(&id).is_ok() {
-                                    if let Ok(commit) = repo.objects.find_commit_iter(&id, &mut buf) {
-                                        grafted_parents_to_skip.extend(commit.parent_ids());
-                                        grafted_parents_to_skip.sort();
-                                    }
-                                };
-                                true
-                            }
-                            None => true,
-                        }
-                    }
+                gix_traverse::commit::Simple::filtered(tips, &repo.objects, move |id| {
+                    filter(id) && prune.binary_search(&id.to_owned()).is_err()
                 })
+                .shallow_commits(
+                    repo.shallow_commits()?
+                        .map(|commits| commits.iter().copied().collect::<Vec<_>>())
+                        .unwrap_or_default(),
+                )
                 .sorting(sorting.into_simple().expect("for now there is nothing else"))?
                 .parents(parents)
                 .commit_graph(
@@ -300,6 +290,7 @@ impl<'repo> Platform<'repo> {
                         .then(|| self.repo.commit_graph().ok())
                         .flatten()),
                 )
+                .changed_paths(changed_paths)
                 .map(|res| res.map_err(iter::Error::from)),
             ),
         })
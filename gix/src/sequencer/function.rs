@@ -0,0 +1,483 @@
+use std::{fmt::Write, sync::atomic::AtomicBool};
+
+use gix_hash::ObjectId;
+
+use super::{
+    state::{State, Todo},
+    Action, Commits, Error, Options, Outcome,
+};
+use crate::{
+    bstr::{BString, ByteSlice},
+    repository::{
+        checkout::{apply_index_changes, remove_files, Obstruction, Worktree},
+        merge::{self, Labels},
+    },
+    Repository,
+};
+
+/// Cherry-picking and reverting
+impl Repository {
+    /// Apply the changes of `commits` on top of `HEAD` and commit each of them with its original author and message,
+    /// similar to `git cherry-pick`.
+    ///
+    /// The changes of each commit are merged into the index and the worktree, which must not have changes compared to
+    /// `HEAD` unless [`no_commit`](Options::no_commit) is set. If a commit can't be applied cleanly, the operation stops
+    /// with an error that names it, leaving the conflicts in the index and the worktree. The state is kept so that it can
+    /// be [continued](Self::sequencer_continue()) after resolving them, [skipped](Self::sequencer_skip()) or
+    /// [aborted](Self::sequencer_abort()).
+    ///
+    /// `should_interrupt` is checked while writing files to the worktree.
+    pub fn cherry_pick(
+        &self,
+        commits: impl Into<Commits>,
+        options: Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::cherry_pick()");
+        self.start_sequence(Action::Pick, commits.into(), options, should_interrupt)
+    }
+
+    /// Undo the changes of `commits` on top of `HEAD` and commit each of them with a message like `Revert "<subject>"`,
+    /// similar to `git revert`.
+    ///
+    /// Everything else works just like [`cherry_pick()`](Self::cherry_pick()).
+    pub fn revert(
+        &self,
+        commits: impl Into<Commits>,
+        options: Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::revert()");
+        self.start_sequence(Action::Revert, commits.into(), options, should_interrupt)
+    }
+
+    /// Continue the cherry-pick or revert that is in progress after resolving the conflicts of the commit that stopped it,
+    /// and adding the result to the index.
+    ///
+    /// The resolved commit is created with the message in `MERGE_MSG`, and all commits that remain are picked.
+    pub fn sequencer_continue(&self, should_interrupt: &AtomicBool) -> Result<Outcome, Error> {
+        let state = State::read(self.sequencer_dir());
+        let picked = self.picked_commit()?;
+        if state.is_none() && picked.is_none() {
+            return Err(Error::NotInProgress);
+        }
+        let options = state.as_ref().map(State::options).transpose()?.unwrap_or_default();
+
+        let index = self.index_or_empty()?;
+        if let Some(entry) = index.entries().iter().find(|e| e.stage_raw() != 0) {
+            return Err(Error::Unmerged {
+                path: entry.path(&index).to_owned(),
+            });
+        }
+        let mut outcome = Outcome::default();
+        if !options.no_commit {
+            let head = self.head()?.id().ok_or(Error::UnbornHead)?.detach();
+            let head_tree = self.find_commit(head)?.tree_id()?.detach();
+            let tree = self.write_tree_from_index(&index)?.detach();
+            match picked {
+                Some((action, commit)) => {
+                    if tree == head_tree {
+                        return Err(Error::Empty { commit });
+                    }
+                    let message = match self.read_git_file("MERGE_MSG")? {
+                        Some(message) => strip_comments(&message),
+                        None => self.sequencer_message(action, commit, options)?,
+                    };
+                    let id = self.commit_picked(action, commit, message, tree, head)?;
+                    outcome.commits.push(id);
+                    if let Some(state) = &state {
+                        state.write_abort_safety(id)?;
+                    }
+                }
+                None if tree != head_tree => return Err(Error::DirtyIndex),
+                None => {}
+            }
+        }
+        self.remove_branch_state();
+
+        match state {
+            Some(state) => {
+                let mut todo = state.todo()?;
+                if !todo.is_empty() {
+                    todo.remove(0);
+                }
+                self.pick_from(state, todo, options, outcome, should_interrupt)
+            }
+            None => Ok(outcome),
+        }
+    }
+
+    /// Discard the changes of the commit that stopped the cherry-pick or revert in progress, and continue with the next one.
+    pub fn sequencer_skip(&self, should_interrupt: &AtomicBool) -> Result<Outcome, Error> {
+        let state = State::read(self.sequencer_dir());
+        if state.is_none() && self.picked_commit()?.is_none() {
+            return Err(Error::NotInProgress);
+        }
+        let head = self.head()?.id().ok_or(Error::UnbornHead)?.detach();
+        self.reset(head, crate::reset::Mode::Merge, should_interrupt)?;
+        match state {
+            Some(state) => {
+                let options = state.options()?;
+                let mut todo = state.todo()?;
+                if !todo.is_empty() {
+                    todo.remove(0);
+                }
+                self.pick_from(state, todo, options, Outcome::default(), should_interrupt)
+            }
+            None => Ok(Outcome::default()),
+        }
+    }
+
+    /// Stop the cherry-pick or revert in progress and restore `HEAD`, the index and the worktree to the state before it was
+    /// started.
+    ///
+    /// `HEAD` is only reset if it still points to the last commit that was created by the operation, otherwise only its
+    /// state is removed.
+    pub fn sequencer_abort(&self, should_interrupt: &AtomicBool) -> Result<(), Error> {
+        let state = State::read(self.sequencer_dir());
+        if state.is_none() && self.picked_commit()?.is_none() {
+            return Err(Error::NotInProgress);
+        }
+        let head = self.head()?.id().ok_or(Error::UnbornHead)?.detach();
+        match state {
+            Some(state) => {
+                if state.abort_safety()? == Some(head) {
+                    self.reset(state.head()?, crate::reset::Mode::Merge, should_interrupt)?;
+                }
+                state.remove()
+            }
+            None => {
+                self.reset(head, crate::reset::Mode::Merge, should_interrupt)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Return the directory in which the state of picking multiple commits is kept.
+    fn sequencer_dir(&self) -> std::path::PathBuf {
+        self.path().join("sequencer")
+    }
+
+    fn start_sequence(
+        &self,
+        action: Action,
+        commits: Commits,
+        options: Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        self.work_dir().ok_or(Error::MissingWorktree)?;
+        let dir = self.sequencer_dir();
+        if dir.exists() || self.picked_commit()?.is_some() {
+            return Err(Error::InProgress);
+        }
+        let ids = match commits {
+            Commits::List(ids) if ids.len() == 1 => {
+                let commit = self.pick_commit(action, ids[0], options, should_interrupt)?;
+                return Ok(Outcome {
+                    commits: commit.into_iter().collect(),
+                });
+            }
+            Commits::List(ids) => ids,
+            Commits::Range { from, to } => {
+                let hidden = self
+                    .rev_walk(Some(from))
+                    .all()?
+                    .map(|info| info.map(|info| info.id))
+                    .collect::<Result<gix_hashtable::HashSet<_>, _>>()?;
+                let mut ids = self
+                    .rev_walk(Some(to))
+                    .selected(move |id| !hidden.contains(id))?
+                    .map(|info| info.map(|info| info.id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if action == Action::Pick {
+                    ids.reverse();
+                }
+                ids
+            }
+        };
+        if ids.is_empty() {
+            return Err(Error::NoCommits);
+        }
+
+        let head = self.head()?.id().ok_or(Error::UnbornHead)?.detach();
+        let mut todo = Vec::with_capacity(ids.len());
+        for id in ids {
+            let commit = self.find_commit(id)?;
+            let short = commit.id().shorten_or_id().to_string();
+            todo.push(Todo {
+                action,
+                line: format!("{} {short} {}", action.command(), commit.message()?.summary()).into(),
+                commit: short.into(),
+            });
+        }
+        let state = State::create(dir, head, &options)?;
+        self.pick_from(state, todo, options, Outcome::default(), should_interrupt)
+    }
+
+    /// Pick all commits in `todo` in order while keeping them in `state`, and add the created commits to `outcome`.
+    fn pick_from(
+        &self,
+        state: State,
+        todo: Vec<Todo>,
+        options: Options,
+        mut outcome: Outcome,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        for position in 0..todo.len() {
+            state.write_todo(&todo[position..])?;
+            let item = &todo[position];
+            let commit = self.rev_parse_single(item.commit.as_bstr())?.detach();
+            if let Some(id) = self.pick_commit(item.action, commit, options, should_interrupt)? {
+                state.write_abort_safety(id)?;
+                outcome.commits.push(id);
+            }
+        }
+        state.remove()?;
+        Ok(outcome)
+    }
+
+    /// Merge the changes of `commit` into the index and the worktree according to `action`, and commit them unless
+    /// `options` says otherwise, returning the created commit.
+    fn pick_commit(
+        &self,
+        action: Action,
+        commit: ObjectId,
+        options: Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Option<ObjectId>, Error> {
+        let workdir = self.work_dir().ok_or(Error::MissingWorktree)?;
+        let head = self.head()?.id().ok_or(Error::UnbornHead)?.detach();
+        let head_tree = self.find_commit(head)?.tree_id()?.detach();
+        let mut index = (**self.index_or_empty()?).clone();
+        index.expand_sparse_directories(&self.objects)?;
+        if let Some(entry) = index.entries().iter().find(|e| e.stage_raw() != 0) {
+            return Err(Error::Unmerged {
+                path: entry.path(&index).to_owned(),
+            });
+        }
+        if !options.no_commit && self.write_tree_from_index(&index)? != head_tree {
+            return Err(Error::DirtyIndex);
+        }
+
+        let picked = self.find_commit(commit)?;
+        let parent = self.mainline_parent(commit, options)?;
+        let label = format!("{} ({})", picked.id().shorten_or_id(), picked.message()?.summary());
+        let parent_label = format!("parent of {label}");
+        let commit_tree = Some(picked.tree_id()?.detach());
+        let parent_tree = match parent {
+            Some(parent) => Some(self.find_commit(parent)?.tree_id()?.detach()),
+            None => None,
+        };
+        let (base, theirs, base_label, theirs_label) = match action {
+            Action::Pick => (parent_tree, commit_tree, parent_label, label),
+            Action::Revert => (commit_tree, parent_tree, label, parent_label),
+        };
+        let (base, theirs) = (
+            self.index_from_tree_or_empty(base)?,
+            self.index_from_tree_or_empty(theirs)?,
+        );
+        let merged = self.merge_trees::<Error>(
+            &base,
+            &index,
+            &theirs,
+            Labels {
+                base: base_label.as_str().into(),
+                ours: "HEAD".into(),
+                theirs: theirs_label.as_str().into(),
+            },
+        )?;
+        let changes = merge::changes(&merged);
+
+        let mut worktree = Worktree::new(self, workdir)?;
+        for path in changes.index.keys() {
+            match worktree.obstruction(&index, path.as_bstr())? {
+                Some(Obstruction::LocalChanges) => {
+                    return Err(Error::LocalChangesWouldBeOverwritten { path: path.clone() })
+                }
+                Some(Obstruction::Untracked) => return Err(Error::UntrackedWouldBeOverwritten { path: path.clone() }),
+                None => {}
+            }
+        }
+        remove_files(workdir, changes.to_remove.iter().map(AsRef::as_ref))?;
+        let (_, stats) = self.checkout_entries(workdir, &changes.to_checkout, should_interrupt)?;
+        apply_index_changes(&mut index, &changes.index, &stats);
+        if let Some(patterns) = self.sparse_checkout_patterns()? {
+            patterns.apply_to_index(&mut index);
+            self.collapse_sparse_directories_if_configured(&mut index, &patterns)?;
+        }
+        index.write(Default::default())?;
+
+        let message = self.sequencer_message(action, commit, options)?;
+        if !changes.conflicts.is_empty() {
+            let mut merge_message = message;
+            merge_message.extend_from_slice(b"\n# Conflicts:\n");
+            for path in &changes.conflicts {
+                merge_message.extend_from_slice(b"#\t");
+                merge_message.extend_from_slice(path);
+                merge_message.push(b'\n');
+            }
+            self.write_git_file("MERGE_MSG", &merge_message)?;
+            if !(action == Action::Pick && options.no_commit) {
+                self.write_git_file(action.head_file(), format!("{commit}\n").as_bytes())?;
+            }
+            return Err(Error::Conflicts {
+                commit,
+                subject: picked.message()?.summary().into_owned(),
+                paths: changes.conflicts,
+            });
+        }
+        if options.no_commit {
+            self.write_git_file("MERGE_MSG", &message)?;
+            if action == Action::Revert {
+                self.write_git_file(action.head_file(), format!("{commit}\n").as_bytes())?;
+            }
+            return Ok(None);
+        }
+
+        let tree = self.write_tree_from_index(&index)?.detach();
+        if tree == head_tree {
+            self.write_git_file("MERGE_MSG", &message)?;
+            self.write_git_file(action.head_file(), format!("{commit}\n").as_bytes())?;
+            return Err(Error::Empty { commit });
+        }
+        Ok(Some(self.commit_picked(action, commit, message, tree, head)?))
+    }
+
+    /// Return the parent of `commit` relative to which its changes are determined, or `None` if it's a root commit.
+    fn mainline_parent(&self, commit: ObjectId, options: Options) -> Result<Option<ObjectId>, Error> {
+        let parents: Vec<_> = self.find_commit(commit)?.parent_ids().map(crate::Id::detach).collect();
+        Ok(match (parents.len(), options.mainline) {
+            (0, None) => None,
+            (1, None) => Some(parents[0]),
+            (_, None) => return Err(Error::MergeWithoutMainline { commit }),
+            (_, Some(mainline)) => Some(
+                *mainline
+                    .checked_sub(1)
+                    .and_then(|index| parents.get(index))
+                    .ok_or(Error::NoSuchParent { commit, mainline })?,
+            ),
+        })
+    }
+
+    /// Return the message of the commit that applies `action` to `commit`.
+    fn sequencer_message(&self, action: Action, commit: ObjectId, options: Options) -> Result<BString, Error> {
+        let picked = self.find_commit(commit)?;
+        Ok(match action {
+            Action::Pick => {
+                let mut message = BString::from(picked.message_raw()?);
+                if !message.ends_with(b"\n") {
+                    message.push(b'\n');
+                }
+                if options.record_origin {
+                    if !ends_with_trailers(message.as_ref()) {
+                        message.push(b'\n');
+                    }
+                    message.extend_from_slice(format!("(cherry picked from commit {commit})\n").as_bytes());
+                }
+                message
+            }
+            Action::Revert => {
+                let mut message = format!(
+                    "Revert \"{}\"\n\nThis reverts commit {commit}",
+                    picked.message()?.summary()
+                );
+                if picked.parent_ids().count() > 1 {
+                    let parent = self.mainline_parent(commit, options)?.expect("merges have parents");
+                    write!(message, ", reversing\nchanges made to {parent}").expect("writing to a string never fails");
+                }
+                message.push_str(".\n");
+                message.into()
+            }
+        })
+    }
+
+    /// Commit `tree` on top of `head` with `message` as the result of applying `action` to `commit`, and return the new commit.
+    fn commit_picked(
+        &self,
+        action: Action,
+        commit: ObjectId,
+        message: BString,
+        tree: ObjectId,
+        head: ObjectId,
+    ) -> Result<ObjectId, Error> {
+        let committer = self.committer().ok_or(Error::CommitterMissing)??;
+        let picked = self.find_commit(commit)?;
+        let author = match action {
+            Action::Pick => picked.author()?,
+            Action::Revert => self.author().ok_or(Error::AuthorMissing)??,
+        };
+        #[cfg(feature = "gpg")]
+        let signer = self
+            .configured_signer(&crate::config::tree::Commit::GPG_SIGN)
+            .map_err(crate::commit::Error::from)?;
+        // Like `git`, only run the hooks that don't verify the message, as it was verified when it was first committed.
+        Ok(self
+            .commit_as_inner(
+                committer,
+                author,
+                "HEAD".try_into().expect("valid"),
+                &message.to_str_lossy(),
+                tree,
+                Some(head).into_iter().collect(),
+                #[cfg(feature = "gpg")]
+                signer.as_ref().map(|signer| signer as &dyn gix_gpg::Signer),
+                #[cfg(feature = "hooks")]
+                crate::hook::CommitHooks::NoVerify,
+            )?
+            .detach())
+    }
+
+    /// Return the commit recorded in `CHERRY_PICK_HEAD` or `REVERT_HEAD`, along with what is done to it.
+    fn picked_commit(&self) -> Result<Option<(Action, ObjectId)>, Error> {
+        for action in [Action::Pick, Action::Revert] {
+            if let Some(hex) = self.read_git_file(action.head_file())? {
+                let id = ObjectId::from_hex(hex.trim()).map_err(|_| Error::InvalidState {
+                    path: self.path().join(action.head_file()),
+                })?;
+                return Ok(Some((action, id)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn read_git_file(&self, name: &str) -> Result<Option<Vec<u8>>, Error> {
+        let path = self.path().join(name);
+        match std::fs::read(&path) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(Error::Io { path, source }),
+        }
+    }
+
+    fn write_git_file(&self, name: &str, data: &[u8]) -> Result<(), Error> {
+        let path = self.path().join(name);
+        std::fs::write(&path, data).map_err(|source| Error::Io { path, source })
+    }
+}
+
+/// Return `true` if the last paragraph of `message` consists only of trailers like `Signed-off-by: …`, or of lines added by `-x`.
+fn ends_with_trailers(message: &[u8]) -> bool {
+    let Some(paragraph) = message.trim_end().rsplit_str("\n\n").next() else {
+        return false;
+    };
+    message.trim_end().contains_str("\n\n")
+        && paragraph.lines().all(|line| {
+            line.starts_with(b"(cherry picked from commit ")
+                || line.find_byte(b':').map_or(false, |colon| {
+                    colon > 0 && line[..colon].iter().all(|b| b.is_ascii_alphanumeric() || *b == b'-')
+                })
+        })
+}
+
+/// Remove all comment lines from `message`, along with trailing empty lines.
+fn strip_comments(message: &[u8]) -> BString {
+    let mut out = BString::default();
+    for line in message.lines().filter(|line| !line.starts_with(b"#")) {
+        out.extend_from_slice(line);
+        out.push(b'\n');
+    }
+    while out.ends_with(b"\n\n") {
+        out.pop();
+    }
+    out
+}
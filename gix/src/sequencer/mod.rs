@@ -0,0 +1,194 @@
+//! Apply or undo the changes of existing commits as new commits, similar to `git cherry-pick` and `git revert`.
+//!
+//! When picking more than one commit, the state of the operation is kept in `.git/sequencer` just like `git` does,
+//! and a commit that can't be applied cleanly is recorded in `CHERRY_PICK_HEAD` or `REVERT_HEAD`. This way the operation
+//! can be [continued](crate::Repository::sequencer_continue()), [skipped](crate::Repository::sequencer_skip())
+//! or [aborted](crate::Repository::sequencer_abort()) by either implementation.
+use std::path::PathBuf;
+
+use gix_hash::ObjectId;
+
+use crate::bstr::BString;
+
+mod function;
+mod state;
+
+/// The commits to cherry-pick or revert with [`Repository::cherry_pick()`](crate::Repository::cherry_pick())
+/// and [`Repository::revert()`](crate::Repository::revert()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Commits {
+    /// The given commits in the given order, like `git cherry-pick <commit>…`.
+    List(Vec<ObjectId>),
+    /// All commits reachable from `to` but not from `from`, like `git cherry-pick <from>..<to>`.
+    ///
+    /// Commits are cherry-picked oldest first, and reverted newest first.
+    Range {
+        /// The commit whose ancestry isn't picked.
+        from: ObjectId,
+        /// The last commit to pick.
+        to: ObjectId,
+    },
+}
+
+impl From<ObjectId> for Commits {
+    fn from(id: ObjectId) -> Self {
+        Commits::List(vec![id])
+    }
+}
+
+impl From<Vec<ObjectId>> for Commits {
+    fn from(ids: Vec<ObjectId>) -> Self {
+        Commits::List(ids)
+    }
+}
+
+/// Options for use in [`Repository::cherry_pick()`](crate::Repository::cherry_pick()) and
+/// [`Repository::revert()`](crate::Repository::revert()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The number of the parent, starting at 1, relative to which the changes of merge commits are determined,
+    /// like `--mainline <parent>`.
+    ///
+    /// It must be set to pick merge commits, and may only be `1` for commits that aren't merges.
+    pub mainline: Option<usize>,
+    /// If `true`, add a line like `(cherry picked from commit <id>)` to the message of each cherry-picked commit, like `-x`.
+    ///
+    /// It has no effect when reverting.
+    pub record_origin: bool,
+    /// If `true`, only apply the changes to the index and the worktree without creating commits, like `--no-commit`.
+    pub no_commit: bool,
+}
+
+/// The result of [`Repository::cherry_pick()`](crate::Repository::cherry_pick()), [`Repository::revert()`](crate::Repository::revert())
+/// and their continuations.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The commits that were created in this run, in order.
+    pub commits: Vec<ObjectId>,
+}
+
+/// The error returned by [`Repository::cherry_pick()`](crate::Repository::cherry_pick()),
+/// [`Repository::revert()`](crate::Repository::revert()) and their continuations.
+///
+/// Errors that refer to a `commit` stop the operation and keep its state, so it can be
+/// [continued](crate::Repository::sequencer_continue()), [skipped](crate::Repository::sequencer_skip()) or
+/// [aborted](crate::Repository::sequencer_abort()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A cherry-pick or revert is already in progress")]
+    InProgress,
+    #[error("No cherry-pick or revert is in progress")]
+    NotInProgress,
+    #[error("There are no commits to pick")]
+    NoCommits,
+    #[error("A worktree is required to cherry-pick or revert")]
+    MissingWorktree,
+    #[error("Cannot cherry-pick or revert on top of an unborn branch")]
+    UnbornHead,
+    #[error("The state file at '{}' is invalid", path.display())]
+    InvalidState { path: PathBuf },
+    #[error("Commit {commit} is a merge but no mainline was given")]
+    MergeWithoutMainline { commit: ObjectId },
+    #[error("Commit {commit} does not have parent {mainline}")]
+    NoSuchParent { commit: ObjectId, mainline: usize },
+    #[error("'{path}' needs merge")]
+    Unmerged { path: BString },
+    #[error("Your local changes would be overwritten by cherry-pick or revert, commit or stash them to proceed")]
+    DirtyIndex,
+    #[error("Your local changes to '{path}' would be overwritten by merge")]
+    LocalChangesWouldBeOverwritten { path: BString },
+    #[error("Untracked working tree file '{path}' would be overwritten by merge")]
+    UntrackedWouldBeOverwritten { path: BString },
+    #[error("Could not apply {commit}: {subject}")]
+    Conflicts {
+        commit: ObjectId,
+        subject: BString,
+        paths: Vec<BString>,
+    },
+    #[error("Applying {commit} resulted in no changes, which may be skipped")]
+    Empty { commit: ObjectId },
+    #[error("Could not access '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Author identity is not configured")]
+    AuthorMissing,
+    #[error("Committer identity is not configured")]
+    CommitterMissing,
+    #[error(transparent)]
+    ParseTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    ParseOptions(#[from] gix_config::file::init::Error),
+    #[error(transparent)]
+    RevParse(#[from] crate::revision::spec::parse::single::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    WalkIter(#[from] crate::revision::walk::iter::Error),
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    Commit(#[from] crate::commit::Error),
+    #[error(transparent)]
+    Reset(#[from] crate::reset::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    ExpandIndex(#[from] gix_index::sparse::expand::Error),
+    #[error(transparent)]
+    WriteTree(#[from] crate::repository::write_tree_from_index::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    SparseCheckoutPatterns(#[from] crate::sparse_checkout::patterns::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error("Could not fetch the objects to check out from the promisor remote")]
+    FetchMissingObjects(#[source] gix_odb::store::promisor::Error),
+}
+
+/// What to do with a commit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Action {
+    /// Apply the changes of the commit.
+    Pick,
+    /// Undo the changes of the commit.
+    Revert,
+}
+
+impl Action {
+    /// The name of the command in the todo list of `git`.
+    fn command(self) -> &'static str {
+        match self {
+            Action::Pick => "pick",
+            Action::Revert => "revert",
+        }
+    }
+
+    /// The file in the `.git` directory that records the commit that is currently applied.
+    fn head_file(self) -> &'static str {
+        match self {
+            Action::Pick => "CHERRY_PICK_HEAD",
+            Action::Revert => "REVERT_HEAD",
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::{fmt::Write, path::PathBuf};
+
+use gix_hash::ObjectId;
+
+use super::{Action, Error, Options};
+use crate::bstr::{BString, ByteSlice};
+
+/// The state of picking multiple commits as stored in `.git/sequencer`, compatible with `git cherry-pick` and `git revert`.
+pub(super) struct State {
+    pub dir: PathBuf,
+}
+
+/// A line of the todo list, which names a commit as it's written by `git`, typically abbreviated.
+pub(super) struct Todo {
+    pub action: Action,
+    pub commit: BString,
+    /// The line as it was read or written.
+    pub line: BString,
+}
+
+impl State {
+    /// Create the state for picking commits on top of `head`, recording `options` so they can be restored.
+    pub fn create(dir: PathBuf, head: ObjectId, options: &Options) -> Result<Self, Error> {
+        std::fs::create_dir_all(&dir).map_err(|source| Error::Io {
+            path: dir.clone(),
+            source,
+        })?;
+        let state = State { dir };
+        state.write("head", format!("{head}\n").as_bytes())?;
+        state.write_abort_safety(head)?;
+        let mut opts = String::new();
+        if options.no_commit {
+            opts.push_str("\tno-commit = true\n");
+        }
+        if options.record_origin {
+            opts.push_str("\trecord-origin = true\n");
+        }
+        if let Some(mainline) = options.mainline {
+            writeln!(opts, "\tmainline = {mainline}").expect("writing to a string never fails");
+        }
+        if !opts.is_empty() {
+            state.write("opts", format!("[options]\n{opts}").as_bytes())?;
+        }
+        Ok(state)
+    }
+
+    /// Read the state from `dir`, or return `None` if there is none.
+    pub fn read(dir: PathBuf) -> Option<Self> {
+        dir.join("todo").is_file().then_some(State { dir })
+    }
+
+    /// Return the options that were recorded when the state was created.
+    pub fn options(&self) -> Result<Options, Error> {
+        let Some(data) = self.try_read_file("opts")? else {
+            return Ok(Options::default());
+        };
+        let path = self.dir.join("opts");
+        let config = gix_config::File::from_bytes_no_includes(
+            &data,
+            gix_config::file::Metadata::from(gix_config::Source::Api),
+            Default::default(),
+        )?;
+        let invalid = || Error::InvalidState { path: path.clone() };
+        let flag = |key: &str| -> Result<bool, Error> {
+            config
+                .boolean(key)
+                .transpose()
+                .map_err(|_| invalid())
+                .map(Option::unwrap_or_default)
+        };
+        Ok(Options {
+            mainline: config
+                .integer("options.mainline")
+                .transpose()
+                .map_err(|_| invalid())?
+                .map(|value| usize::try_from(value).map_err(|_| invalid()))
+                .transpose()?,
+            record_origin: flag("options.record-origin")?,
+            no_commit: flag("options.no-commit")?,
+        })
+    }
+
+    /// Return the commit `HEAD` pointed to before the operation started.
+    pub fn head(&self) -> Result<ObjectId, Error> {
+        self.read_id("head")?.ok_or_else(|| Error::InvalidState {
+            path: self.dir.join("head"),
+        })
+    }
+
+    /// Record the commit `HEAD` points to after a commit was created, so aborting can assure it didn't change since.
+    pub fn write_abort_safety(&self, head: ObjectId) -> Result<(), Error> {
+        self.write("abort-safety", format!("{head}\n").as_bytes())
+    }
+
+    /// Return the commit `HEAD` pointed to after the last commit was created, if known.
+    pub fn abort_safety(&self) -> Result<Option<ObjectId>, Error> {
+        self.read_id("abort-safety")
+    }
+
+    /// Return all commits that remain to be picked, starting with the one that is currently picked.
+    pub fn todo(&self) -> Result<Vec<Todo>, Error> {
+        let data = self.try_read_file("todo")?.unwrap_or_default();
+        let mut out = Vec::new();
+        for line in data.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with(b"#") {
+                continue;
+            }
+            let mut tokens = trimmed.fields();
+            let action = match tokens.next() {
+                Some(b"pick" | b"p") => Action::Pick,
+                Some(b"revert") => Action::Revert,
+                _ => {
+                    return Err(Error::InvalidState {
+                        path: self.dir.join("todo"),
+                    })
+                }
+            };
+            let commit = tokens.next().ok_or_else(|| Error::InvalidState {
+                path: self.dir.join("todo"),
+            })?;
+            out.push(Todo {
+                action,
+                commit: commit.into(),
+                line: line.into(),
+            });
+        }
+        Ok(out)
+    }
+
+    /// Record `todo` as the commits that remain to be picked.
+    pub fn write_todo(&self, todo: &[Todo]) -> Result<(), Error> {
+        let mut data = BString::default();
+        for item in todo {
+            data.extend_from_slice(&item.line);
+            data.push(b'\n');
+        }
+        self.write("todo", &data)
+    }
+
+    /// Remove the state entirely.
+    pub fn remove(self) -> Result<(), Error> {
+        std::fs::remove_dir_all(&self.dir).map_err(|source| Error::Io { path: self.dir, source })
+    }
+
+    fn read_id(&self, name: &str) -> Result<Option<ObjectId>, Error> {
+        Ok(self
+            .try_read_file(name)?
+            .and_then(|hex| ObjectId::from_hex(hex.trim()).ok()))
+    }
+
+    fn try_read_file(&self, name: &str) -> Result<Option<Vec<u8>>, Error> {
+        let path = self.dir.join(name);
+        match std::fs::read(&path) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(Error::Io { path, source }),
+        }
+    }
+
+    fn write(&self, name: &str, data: &[u8]) -> Result<(), Error> {
+        let path = self.dir.join(name);
+        std::fs::write(&path, data).map_err(|source| Error::Io { path, source })
+    }
+}
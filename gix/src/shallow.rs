@@ -17,7 +17,8 @@ pub mod write {
         /// Write the previously obtained (possibly non-existing) `shallow_commits` to the shallow `file`
         /// after applying all `updates`.
         ///
-        /// If this leaves the list of shallow commits empty, the file is removed.
+        /// If this leaves the list of shallow commits empty, the file is removed, which is what happens when unshallowing
+        /// a repository as the remote sends an `unshallow` update for each of our shallow commits.
         ///
         /// ### Deviation
         ///
@@ -36,28 +37,26 @@ pub mod write {
                     ShallowUpdate::Unshallow(id) => shallow_commits.retain(|oid| oid != id),
                 }
             }
-            if shallow_commits.is_empty() {
-                std::fs::remove_file(file.resource_path())?;
-                drop(file);
-                return Ok(());
-            }
-
             if shallow_commits.is_empty() {
                 if let Err(err) = std::fs::remove_file(file.resource_path()) {
                     if err.kind() != std::io::ErrorKind::NotFound {
                         return Err(err.into());
                     }
                 }
-            } else {
-                shallow_commits.sort();
-                let mut buf = Vec::<u8>::new();
-                for commit in shallow_commits {
-                    commit.write_hex_to(&mut buf).map_err(Error::Io)?;
-                    buf.push(b'\n');
-                }
-                file.write_all(&buf).map_err(Error::Io)?;
-                file.flush()?;
+                // Dropping the lock removes it without creating an empty shallow file.
+                drop(file);
+                return Ok(());
+            }
+
+            shallow_commits.sort();
+            shallow_commits.dedup();
+            let mut buf = Vec::<u8>::new();
+            for commit in shallow_commits {
+                commit.write_hex_to(&mut buf).map_err(Error::Io)?;
+                buf.push(b'\n');
             }
+            file.write_all(&buf).map_err(Error::Io)?;
+            file.flush()?;
             file.commit()?;
             Ok(())
         }
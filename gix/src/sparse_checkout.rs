@@ -0,0 +1,81 @@
+//! Sparse checkouts, which only populate the worktree with the parts of the index that match the patterns in
+//! `$GIT_DIR/info/sparse-checkout`.
+//!
+//! They are enabled with `core.sparseCheckout`, and `core.sparseCheckoutCone` controls if the patterns are
+//! directories (_cone mode_, the default) or gitignore-style patterns. With `index.sparse`, directories outside
+//! of the cone are stored as single entries in the index.
+use crate::bstr::BString;
+pub use gix_worktree::sparse::{Cone, NonCone, Patterns};
+
+/// The result of [`Repository::sparse_checkout_reapply()`](crate::Repository::sparse_checkout_reapply()) and
+/// related methods.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of files that were written to the worktree as they are now included.
+    pub files_checked_out: usize,
+    /// The amount of files that were removed from the worktree as they are now excluded.
+    pub files_removed: usize,
+    /// The paths to excluded files that were kept in the worktree as they were modified, and which thus remain included.
+    pub kept_modified: Vec<BString>,
+    /// The amount of sparse directories in the index after it was written.
+    pub sparse_directories: usize,
+}
+
+///
+pub mod patterns {
+    /// The error returned by [`Repository::sparse_checkout_patterns()`](crate::Repository::sparse_checkout_patterns()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error("Could not read the sparse-checkout patterns at '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+
+///
+pub mod update {
+    /// The error returned by [`Repository::sparse_checkout_reapply()`](crate::Repository::sparse_checkout_reapply()) and
+    /// related methods.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Sparse checkout is not enabled")]
+        NotEnabled,
+        #[error("Cannot update the worktree of a bare repository")]
+        BareRepository,
+        #[error(transparent)]
+        Patterns(#[from] super::patterns::Error),
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        ExpandIndex(#[from] gix_index::sparse::expand::Error),
+        #[error(transparent)]
+        StatOptions(#[from] crate::config::stat_options::Error),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        ReadConfigFile(#[from] gix_config::file::init::from_paths::Error),
+        #[error(transparent)]
+        SetConfig(#[from] crate::config::set_value::Error),
+        #[error(transparent)]
+        CommitConfig(#[from] crate::config::Error),
+        #[error("Could not write '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        Lock(#[from] gix_lock::acquire::Error),
+    }
+}
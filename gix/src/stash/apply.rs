@@ -0,0 +1,223 @@
+use crate::bstr::BString;
+
+/// Options for use in [`Repository::stash_apply()`](crate::Repository::stash_apply()) and
+/// [`Repository::stash_pop()`](crate::Repository::stash_pop()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If `true`, also restore the changes that were in the index when stashing, like `--index`.
+    ///
+    /// Otherwise, all changes are only applied to the worktree, and only files added in the stash are added to the index.
+    pub index: bool,
+}
+
+/// The result of [`Repository::stash_apply()`](crate::Repository::stash_apply()) and
+/// [`Repository::stash_pop()`](crate::Repository::stash_pop()).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of files that were written to the worktree.
+    pub files_checked_out: usize,
+    /// The amount of files that were removed from the worktree as they were deleted in the stash.
+    pub files_removed: usize,
+    /// The paths that couldn't be merged without conflicts, which are left unmerged in the index.
+    ///
+    /// If there are conflicts, [`Repository::stash_pop()`](crate::Repository::stash_pop()) keeps the stash.
+    pub conflicts: Vec<BString>,
+}
+
+/// The error returned by [`Repository::stash_apply()`](crate::Repository::stash_apply()) and
+/// [`Repository::stash_pop()`](crate::Repository::stash_pop()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot apply a stash in a bare repository")]
+    BareRepository,
+    #[error("Cannot apply a stash in the middle of a merge as '{path}' needs merge")]
+    Unmerged { path: BString },
+    #[error("Conflicts in index. Try without restoring the index.")]
+    IndexConflicts,
+    #[error("Your local changes to '{path}' would be overwritten by merge")]
+    LocalChangesWouldBeOverwritten { path: BString },
+    #[error("Untracked working tree file '{path}' would be overwritten by merge")]
+    UntrackedWouldBeOverwritten { path: BString },
+    #[error(transparent)]
+    Stash(#[from] super::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    ExpandIndex(#[from] gix_index::sparse::expand::Error),
+    #[error(transparent)]
+    SparseCheckoutPatterns(#[from] crate::sparse_checkout::patterns::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error("Could not fetch the objects to check out from the promisor remote")]
+    FetchMissingObjects(#[source] gix_odb::store::promisor::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error("Could not access '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+pub(crate) mod function {
+    use std::{collections::BTreeMap, sync::atomic::AtomicBool};
+
+    use super::{Error, Options, Outcome};
+    use crate::{
+        bstr::ByteSlice,
+        repository::{
+            checkout::{
+                apply_index_changes, changes_to_target, differences, remove_files, IndexChange, Obstruction, Worktree,
+            },
+            merge::{self, Labels, Merged},
+        },
+        Repository,
+    };
+
+    impl Repository {
+        /// Apply the changes of the stash at `index` to the worktree, with a three-way merge between the commit the stash
+        /// was created on, the current index and the stash, like `git stash apply stash@{<index>}`.
+        ///
+        /// Untracked files in the stash are restored as well. The operation fails if a file that would be changed has
+        /// local changes, or if an untracked file is in the way. Paths that can't be merged are left unmerged in the index,
+        /// with conflict markers in the worktree.
+        /// `should_interrupt` is checked while writing files to the worktree.
+        pub fn stash_apply(
+            &self,
+            index: usize,
+            options: Options,
+            should_interrupt: &AtomicBool,
+        ) -> Result<Outcome, Error> {
+            let _span = gix_trace::coarse!("gix::stash_apply()");
+            let workdir = self.work_dir().ok_or(Error::BareRepository)?;
+            let stash = self.stash_commit(index)?;
+            let tree_of = |id| -> Result<_, Error> {
+                Ok(self.index_from_tree_or_empty(Some(self.find_commit(id)?.tree_id()?.detach()))?)
+            };
+            let base = tree_of(stash.parents[0])?;
+            let theirs = self.index_from_tree_or_empty(Some(stash.tree))?;
+
+            let mut current = (**self.index_or_empty()?).clone();
+            current.expand_sparse_directories(&self.objects)?;
+            if let Some(entry) = current.entries().iter().find(|e| e.stage_raw() != 0) {
+                return Err(Error::Unmerged {
+                    path: entry.path(&current).to_owned(),
+                });
+            }
+            let labels = || Labels {
+                base: "Stash base".into(),
+                ours: "Updated upstream".into(),
+                theirs: "Stashed changes".into(),
+            };
+            let restored_index = if options.index {
+                let stashed_index = tree_of(stash.parents[1])?;
+                let merged = self.merge_trees::<Error>(&base, &current, &stashed_index, labels())?;
+                if merged.values().any(|m| matches!(m, Merged::Conflict { .. })) {
+                    return Err(Error::IndexConflicts);
+                }
+                let mut restored = current.clone();
+                apply_index_changes(&mut restored, &merge::changes(&merged).index, &Default::default());
+                Some(restored)
+            } else {
+                None
+            };
+
+            let mut worktree = Worktree::new(self, workdir)?;
+            let untracked = match stash.parents.get(2) {
+                Some(id) => Some(tree_of(*id)?),
+                None => None,
+            };
+            let mut to_checkout = Vec::new();
+            for (state, entry) in untracked
+                .iter()
+                .flat_map(|state| state.entries().iter().map(move |e| (state, e)))
+            {
+                let path = entry.path(state);
+                if worktree.exists(path) {
+                    return Err(Error::UntrackedWouldBeOverwritten { path: path.to_owned() });
+                }
+                to_checkout.push((path.to_owned(), entry.id, entry.mode));
+            }
+
+            let merged = self.merge_trees::<Error>(&base, &current, &theirs, labels())?;
+            let changes = merge::changes(&merged);
+            for path in changes.index.keys() {
+                match worktree.obstruction(&current, path.as_bstr())? {
+                    Some(Obstruction::LocalChanges) => {
+                        return Err(Error::LocalChangesWouldBeOverwritten { path: path.clone() })
+                    }
+                    Some(Obstruction::Untracked) => {
+                        return Err(Error::UntrackedWouldBeOverwritten { path: path.clone() })
+                    }
+                    None => {}
+                }
+            }
+
+            let mut out = Outcome {
+                conflicts: changes.conflicts,
+                ..Default::default()
+            };
+            out.files_removed = remove_files(workdir, changes.to_remove.iter().map(AsRef::as_ref))?;
+            to_checkout.extend(changes.to_checkout);
+            let (files_checked_out, stats) = self.checkout_entries(workdir, &to_checkout, should_interrupt)?;
+            out.files_checked_out = files_checked_out;
+
+            let index_changes = if !out.conflicts.is_empty() {
+                changes.index
+            } else if let Some(restored) = restored_index {
+                changes_to_target(&restored, &differences(&current, &restored))
+            } else {
+                changes
+                    .index
+                    .into_iter()
+                    .filter(|(path, change)| {
+                        matches!(change, IndexChange::Set { .. }) && current.entry_by_path(path.as_bstr()).is_none()
+                    })
+                    .collect::<BTreeMap<_, _>>()
+            };
+            apply_index_changes(&mut current, &index_changes, &stats);
+            if let Some(patterns) = self.sparse_checkout_patterns()? {
+                patterns.apply_to_index(&mut current);
+                self.collapse_sparse_directories_if_configured(&mut current, &patterns)?;
+            }
+            current.write(Default::default())?;
+            Ok(out)
+        }
+
+        /// Like [`stash_apply()`](Self::stash_apply()), but also remove the stash at `index` if it was applied without
+        /// conflicts, like `git stash pop stash@{<index>}`.
+        pub fn stash_pop(
+            &self,
+            index: usize,
+            options: Options,
+            should_interrupt: &AtomicBool,
+        ) -> Result<Outcome, Error> {
+            let out = self.stash_apply(index, options, should_interrupt)?;
+            if out.conflicts.is_empty() {
+                self.stash_drop(index)?;
+            }
+            Ok(out)
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Save local changes to the index and the worktree in stash commits, and apply them again, similar to `git stash`.
+//!
+//! Stashes use the same commit layout as `git`: a commit with the state of the worktree whose parents are the commit `HEAD`
+//! pointed to, a commit with the state of the index and, optionally, a commit with all untracked files.
+//! The worktree commit is stored in `refs/stash`, and each entry of its reflog is one entry of the stack of stashes,
+//! with the most recent entry at index `0`. This makes them interoperable with `git stash`.
+use gix_hash::ObjectId;
+
+use crate::bstr::BString;
+
+///
+pub mod apply;
+///
+pub mod push;
+
+/// The name of the reference that points to the most recent stash.
+pub(crate) const REF_NAME: &str = "refs/stash";
+
+/// An entry in the stack of stashes, as returned by [`Repository::stash_list()`](crate::Repository::stash_list()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The id of the stash commit.
+    pub id: ObjectId,
+    /// The message of the entry, like `WIP on main: 1234567 subject`.
+    pub message: BString,
+}
+
+/// A path that differs between a stash and the commit it was created on, as returned by
+/// [`Repository::stash_show()`](crate::Repository::stash_show()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The worktree-relative path.
+    pub path: BString,
+    /// The blob in the commit the stash was created on, or `None` if it was added.
+    pub previous_id: Option<ObjectId>,
+    /// The blob in the stash, or `None` if it was deleted.
+    pub id: Option<ObjectId>,
+}
+
+/// The error returned by [`Repository::stash_list()`](crate::Repository::stash_list()),
+/// [`Repository::stash_show()`](crate::Repository::stash_show()), [`Repository::stash_drop()`](crate::Repository::stash_drop())
+/// and [`Repository::stash_clear()`](crate::Repository::stash_clear()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("There is no stash entry at index {index}")]
+    NoSuchEntry { index: usize },
+    #[error("'{id}' is not a stash commit")]
+    NotAStash { id: ObjectId },
+    #[error(transparent)]
+    ReadReflog(#[from] gix_ref::file::log::Error),
+    #[error(transparent)]
+    DecodeReflog(#[from] gix_ref::file::log::iter::decode::Error),
+    #[error(transparent)]
+    RewriteReflog(#[from] gix_ref::file::log::rewrite::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    LockTimeout(#[from] crate::config::lock_timeout::Error),
+}
+
+pub(crate) mod function {
+    use gix_ref::{
+        transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+        Target,
+    };
+
+    use super::{Entry, Error, REF_NAME};
+    use crate::{repository::checkout::differences, Repository};
+
+    /// Stashing
+    impl Repository {
+        /// Return all entries of the stack of stashes, with the most recent one first, like `git stash list`.
+        pub fn stash_list(&self) -> Result<Vec<Entry>, Error> {
+            Ok(self
+                .stash_reflog()?
+                .into_iter()
+                .rev()
+                .map(|line| Entry {
+                    id: line.new_oid,
+                    message: line.message,
+                })
+                .collect())
+        }
+
+        /// Return all paths that differ between the stash at `index` and the commit it was created on, like
+        /// `git stash show --name-status`, including untracked files if `include_untracked` is `true`.
+        pub fn stash_show(&self, index: usize, include_untracked: bool) -> Result<Vec<super::Change>, Error> {
+            let commit = self.stash_commit(index)?;
+            let base = self.index_from_tree(&self.find_commit(commit.parents[0])?.tree_id()?)?;
+            let stash = self.index_from_tree(&commit.tree)?;
+            let mut out: Vec<_> = differences(&base, &stash)
+                .into_iter()
+                .map(|d| super::Change {
+                    previous_id: d.current.map(|idx| base.entry(idx).id),
+                    id: d.target.map(|idx| stash.entry(idx).id),
+                    path: d.path,
+                })
+                .collect();
+            if let Some(untracked) = commit.parents.get(2).filter(|_| include_untracked) {
+                let untracked = self.index_from_tree(&self.find_commit(*untracked)?.tree_id()?)?;
+                out.extend(untracked.entries().iter().map(|entry| super::Change {
+                    path: entry.path(&untracked).to_owned(),
+                    previous_id: None,
+                    id: Some(entry.id),
+                }));
+                out.sort_by(|a, b| a.path.cmp(&b.path));
+            }
+            Ok(out)
+        }
+
+        /// Remove the stash at `index` from the stack of stashes and return its id, like `git stash drop stash@{<index>}`.
+        ///
+        /// If it was the last stash, `refs/stash` is removed.
+        pub fn stash_drop(&self, index: usize) -> Result<gix_hash::ObjectId, Error> {
+            let mut lines = self.stash_reflog()?;
+            let position = lines.len().checked_sub(index + 1).ok_or(Error::NoSuchEntry { index })?;
+            let dropped = lines.remove(position);
+            let Some(top) = lines.last().map(|line| line.new_oid) else {
+                self.stash_clear()?;
+                return Ok(dropped.new_oid);
+            };
+            // Keep the chain of ids intact, just like `git reflog delete --rewrite`.
+            let previous_oid = match position.checked_sub(1) {
+                Some(previous) => lines[previous].new_oid,
+                None => gix_hash::ObjectId::null(self.object_hash()),
+            };
+            if let Some(next) = lines.get_mut(position) {
+                next.previous_oid = previous_oid;
+            }
+            if index == 0 {
+                self.edit_reference(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: true,
+                            log_symbolic_changes: false,
+                            message: Default::default(),
+                        },
+                        expected: PreviousValue::MustExistAndMatch(Target::Object(dropped.new_oid)),
+                        new: Target::Object(top),
+                    },
+                    name: REF_NAME.try_into().expect("valid"),
+                    deref: false,
+                })?;
+            }
+            let (file_lock_fail, _) = self.config.lock_timeout()?;
+            self.refs.reflog_rewrite(REF_NAME, &lines, file_lock_fail)?;
+            Ok(dropped.new_oid)
+        }
+
+        /// Remove all stashes, like `git stash clear`.
+        pub fn stash_clear(&self) -> Result<(), Error> {
+            if self.try_find_reference(REF_NAME)?.is_some() {
+                self.edit_reference(RefEdit {
+                    change: Change::Delete {
+                        expected: PreviousValue::Any,
+                        log: RefLog::AndReference,
+                    },
+                    name: REF_NAME.try_into().expect("valid"),
+                    deref: false,
+                })?;
+            }
+            Ok(())
+        }
+
+        /// Return the stash commit at `index`, making sure it has the parents of a stash.
+        pub(crate) fn stash_commit(&self, index: usize) -> Result<gix_object::Commit, Error> {
+            let lines = self.stash_reflog()?;
+            let id = lines
+                .len()
+                .checked_sub(index + 1)
+                .map(|position| lines[position].new_oid)
+                .ok_or(Error::NoSuchEntry { index })?;
+            let commit: gix_object::Commit = self.find_commit(id)?.decode()?.into();
+            if !(2..=3).contains(&commit.parents.len()) {
+                return Err(Error::NotAStash { id });
+            }
+            Ok(commit)
+        }
+
+        /// Return all lines of the reflog of `refs/stash`, with the oldest one first.
+        fn stash_reflog(&self) -> Result<Vec<gix_ref::log::Line>, Error> {
+            let mut buf = Vec::new();
+            let Some(lines) = self.refs.reflog_iter(REF_NAME, &mut buf)? else {
+                return Ok(Vec::new());
+            };
+            Ok(lines
+                .map(|line| line.map(|line| line.to_owned()))
+                .collect::<Result<_, _>>()?)
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use crate::bstr::BString;
+
+/// Options for use in [`Repository::stash_push()`](crate::Repository::stash_push()).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The message of the stash, like `--message`, or `None` to use `WIP on <branch>: <commit>`.
+    pub message: Option<BString>,
+    /// If `true`, keep all changes in the index, and in the files in the worktree, after stashing them, like `--keep-index`.
+    pub keep_index: bool,
+    /// If `true`, also stash all untracked files which aren't ignored and remove them from the worktree,
+    /// like `--include-untracked`.
+    pub include_untracked: bool,
+}
+
+/// The error returned by [`Repository::stash_push()`](crate::Repository::stash_push()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot stash in a bare repository")]
+    BareRepository,
+    #[error("You do not have the initial commit yet")]
+    UnbornHead,
+    #[error("'{path}' needs merge")]
+    Unmerged { path: BString },
+    #[error("Committer identity is not configured")]
+    CommitterMissing,
+    #[error(transparent)]
+    ParseTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    ExpandIndex(#[from] gix_index::sparse::expand::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    DirwalkOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    Dirwalk(#[from] crate::dirwalk::Error),
+    #[error(transparent)]
+    WriteTree(#[from] crate::repository::write_tree_from_index::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error("Could not remove the stashed changes")]
+    Restore(#[from] crate::restore::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error("Could not fetch the objects to check out from the promisor remote")]
+    FetchMissingObjects(#[source] gix_odb::store::promisor::Error),
+    #[error("Could not access '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+pub(crate) mod function {
+    use std::{collections::BTreeMap, sync::atomic::AtomicBool};
+
+    use gix_hash::ObjectId;
+    use gix_index::entry::{Flags, Mode};
+    use gix_ref::{
+        transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+        Target,
+    };
+
+    use super::{Error, Options};
+    use crate::{
+        bstr::{BStr, BString},
+        repository::checkout::{apply_index_changes, remove_files, IndexChange, Worktree},
+        stash::REF_NAME,
+        Repository,
+    };
+
+    impl Repository {
+        /// Save the changes in the index and in all tracked files matching `patterns` in a new stash, and remove them from
+        /// the index and the worktree, like `git stash push [-- <patterns>]`.
+        ///
+        /// If `patterns` is empty, all paths are stashed.
+        /// Return the id of the new stash, or `None` if there were no local changes to save.
+        /// `should_interrupt` is checked while writing files to the worktree.
+        pub fn stash_push(
+            &self,
+            patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+            options: Options,
+            should_interrupt: &AtomicBool,
+        ) -> Result<Option<ObjectId>, Error> {
+            let _span = gix_trace::coarse!("gix::stash_push()");
+            let workdir = self.work_dir().ok_or(Error::BareRepository)?;
+            let patterns: Vec<BString> = patterns.into_iter().map(|p| p.as_ref().to_owned()).collect();
+            let head = self.head()?;
+            let head_id = head.id().ok_or(Error::UnbornHead)?.detach();
+            let head_commit = self.find_commit(head_id)?;
+            let head_tree = head_commit.tree_id()?.detach();
+
+            let mut index = (**self.index_or_empty()?).clone();
+            index.expand_sparse_directories(&self.objects)?;
+            if let Some(entry) = index.entries().iter().find(|e| e.stage_raw() != 0) {
+                return Err(Error::Unmerged {
+                    path: entry.path(&index).to_owned(),
+                });
+            }
+            let index_tree = self.write_tree_from_index(&index)?.detach();
+
+            let mut worktree = Worktree::new(self, workdir)?;
+            let mut changes = BTreeMap::new();
+            {
+                let mut pathspec = self.pathspec(
+                    true,
+                    &patterns,
+                    true,
+                    &index,
+                    gix_worktree::stack::state::attributes::Source::IdMapping,
+                )?;
+                for entry in index.entries() {
+                    let path = entry.path(&index);
+                    if entry.mode == Mode::COMMIT
+                        || entry.flags.contains(Flags::SKIP_WORKTREE)
+                        || !pathspec.is_included(path, Some(false))
+                        || !worktree.is_modified(entry, path)?
+                    {
+                        continue;
+                    }
+                    let change = match worktree.mode(path, Some(entry.mode))? {
+                        Some(mode) => {
+                            let id = self.write_blob(worktree.read(path, mode == Mode::SYMLINK)?)?.detach();
+                            IndexChange::Set { id, mode }
+                        }
+                        None => IndexChange::Remove,
+                    };
+                    changes.insert(path.to_owned(), change);
+                }
+            }
+            let mut worktree_index = index.clone();
+            apply_index_changes(&mut worktree_index, &changes, &Default::default());
+            let worktree_tree = self.write_tree_from_index(&worktree_index)?.detach();
+
+            let mut untracked = Vec::new();
+            if options.include_untracked {
+                let mut collect = gix_dir::walk::delegate::Collect::default();
+                self.dirwalk(
+                    &index,
+                    &patterns,
+                    should_interrupt,
+                    self.dirwalk_options()?
+                        .emit_untracked(gix_dir::walk::EmissionMode::Matching)
+                        .emit_ignored(None),
+                    &mut collect,
+                )?;
+                for (entry, _) in collect.into_entries_by_path() {
+                    if entry.status != gix_dir::entry::Status::Untracked
+                        || !matches!(
+                            entry.disk_kind,
+                            Some(gix_dir::entry::Kind::File | gix_dir::entry::Kind::Symlink)
+                        )
+                    {
+                        continue;
+                    }
+                    untracked.push(entry.rela_path);
+                }
+            }
+            let untracked_tree = if untracked.is_empty() {
+                None
+            } else {
+                let mut state = gix_index::State::new(self.object_hash());
+                for path in &untracked {
+                    let Some(mode) = worktree.mode(path.as_ref(), None)? else {
+                        continue;
+                    };
+                    let id = self.write_blob(worktree.read(path.as_ref(), mode == Mode::SYMLINK)?)?;
+                    state.dangerously_push_entry(Default::default(), id.detach(), Flags::empty(), mode, path.as_ref());
+                }
+                state.sort_entries();
+                Some(self.write_tree_from_index(&state)?.detach())
+            };
+
+            if index_tree == head_tree && worktree_tree == index_tree && untracked_tree.is_none() {
+                return Ok(None);
+            }
+
+            let branch = match head.referent_name() {
+                Some(name) => name.shorten().to_owned(),
+                None => "(no branch)".into(),
+            };
+            let base_message = format!(
+                "{branch}: {} {}",
+                head_commit.id().shorten_or_id(),
+                head_commit.message()?.summary()
+            );
+            let index_commit =
+                self.write_stash_commit(index_tree, vec![head_id], format!("index on {base_message}\n"))?;
+            let mut parents = vec![head_id, index_commit];
+            if let Some(tree) = untracked_tree {
+                parents.push(self.write_stash_commit(
+                    tree,
+                    Vec::new(),
+                    format!("untracked files on {base_message}\n"),
+                )?);
+            }
+            let message = match &options.message {
+                Some(message) => format!("On {branch}: {message}"),
+                None => format!("WIP on {base_message}"),
+            };
+            let stash = self.write_stash_commit(worktree_tree, parents, format!("{message}\n"))?;
+            self.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: true,
+                        log_symbolic_changes: false,
+                        message: message.into(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: Target::Object(stash),
+                },
+                name: REF_NAME.try_into().expect("valid"),
+                deref: false,
+            })?;
+
+            self.restore(
+                &patterns,
+                crate::restore::Options {
+                    source: Some(head_tree),
+                    staged: true,
+                    worktree: true,
+                },
+                should_interrupt,
+            )?;
+            remove_files(workdir, untracked.iter().map(AsRef::as_ref))?;
+            if options.keep_index {
+                self.restore(
+                    &patterns,
+                    crate::restore::Options {
+                        source: Some(index_tree),
+                        staged: true,
+                        worktree: true,
+                    },
+                    should_interrupt,
+                )?;
+            }
+            Ok(Some(stash))
+        }
+
+        /// Write a commit of `tree` with `parents` and `message` as it's used for stashes, and return its id.
+        fn write_stash_commit(
+            &self,
+            tree: ObjectId,
+            parents: Vec<ObjectId>,
+            message: String,
+        ) -> Result<ObjectId, Error> {
+            let committer: gix_actor::Signature = self.committer().ok_or(Error::CommitterMissing)??.into();
+            let commit = gix_object::Commit {
+                tree,
+                parents: parents.into(),
+                author: committer.clone(),
+                committer,
+                encoding: None,
+                message: message.into(),
+                extra_headers: Vec::new(),
+            };
+            Ok(self.write_object(&commit)?.detach())
+        }
+    }
+}
@@ -23,6 +23,8 @@ pub enum Error {
     StatOptions(#[from] config::stat_options::Error),
     #[error(transparent)]
     ResourceCache(#[from] crate::diff::resource_cache::Error),
+    #[error(transparent)]
+    ExcludesFile(#[from] config::exclude_stack::Error),
 }
 
 /// Options for use with [Repository::index_worktree_status()].
@@ -97,6 +99,48 @@ impl Repository {
         should_interrupt: &AtomicBool,
         options: Options,
     ) -> Result<gix_status::index_as_worktree_with_renames::Outcome, Error>
+    where
+        T: Send + Clone,
+        U: Send + Clone,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        self.index_worktree_status_with_untracked_cache(
+            index,
+            patterns,
+            delegate,
+            compare,
+            submodule,
+            progress,
+            should_interrupt,
+            options,
+            None,
+            false,
+        )
+    }
+
+    /// Like [`index_worktree_status()`](Self::index_worktree_status()), but use and update `untracked_cache` during
+    /// the directory walk if possible.
+    ///
+    /// If `use_fsmonitor` is `true`, the changes reported by the filesystem monitor were applied to `untracked_cache`,
+    /// so its valid directories don't have to be checked on disk.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn index_worktree_status_with_untracked_cache<'index, T, U, E>(
+        &self,
+        index: &'index gix_index::State,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        delegate: &mut impl gix_status::index_as_worktree_with_renames::VisitEntry<
+            'index,
+            ContentChange = T,
+            SubmoduleStatus = U,
+        >,
+        compare: impl CompareBlobs<Output = T> + Send + Clone,
+        submodule: impl SubmoduleStatus<Output = U, Error = E> + Send + Clone,
+        progress: &mut dyn gix_features::progress::Progress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+        untracked_cache: Option<&mut gix_index::extension::UntrackedCache>,
+        use_fsmonitor: bool,
+    ) -> Result<gix_status::index_as_worktree_with_renames::Outcome, Error>
     where
         T: Send + Clone,
         U: Send + Clone,
@@ -135,6 +179,13 @@ impl Repository {
             },
         )?;
 
+        let stat_options = self.stat_options()?;
+        let info_exclude = self.git_dir().join("info").join("exclude");
+        let excludes_file = match untracked_cache {
+            Some(_) => self.config.global_excludes_file()?,
+            None => None,
+        };
+
         let out = gix_status::index_as_worktree_with_renames(
             index,
             workdir,
@@ -151,6 +202,13 @@ impl Repository {
                     git_dir_realpath: git_dir_realpath.as_path(),
                     current_dir: cwd,
                     ignore_case_index_lookup: accelerate_lookup.as_ref(),
+                    untracked_cache: untracked_cache.map(|cache| gix_dir::walk::UntrackedCache {
+                        cache,
+                        info_exclude: Some(&info_exclude),
+                        excludes_file: excludes_file.as_deref(),
+                        stat_options,
+                        use_fsmonitor,
+                    }),
                 },
             },
             gix_status::index_as_worktree_with_renames::Options {
@@ -159,7 +217,7 @@ impl Repository {
                 tracked_file_modifications: gix_status::index_as_worktree::Options {
                     fs: fs_caps,
                     thread_limit: options.thread_limit,
-                    stat: self.stat_options()?,
+                    stat: stat_options,
                 },
                 dirwalk: options.dirwalk_options.map(Into::into),
                 rewrites: options.rewrites,
@@ -328,6 +386,20 @@ pub mod iter {
         pub index: crate::worktree::IndexPersistedOrInMemory,
         skip_hash: bool,
         changes: Option<Vec<(usize, iter::ApplyChange)>>,
+        /// If `Some(cache)`, the untracked cache to store in the index when writing changes.
+        untracked_cache: Option<Option<gix_index::extension::UntrackedCache>>,
+        fs_monitor: Option<FsMonitorUpdate>,
+    }
+
+    /// How to update the fsmonitor extension of the index after a status.
+    pub(super) struct FsMonitorUpdate {
+        /// The token to store, or `None` to remove the extension.
+        token: Option<gix_index::extension::fs_monitor::Token>,
+        /// If `true`, the changes reported by the filesystem monitor were applied to the untracked cache.
+        use_fsmonitor: bool,
+        /// The indices of all entries that were found to be changed, or `None` if entries that weren't checked
+        /// can't be told apart from unchanged ones.
+        changed_entries: Option<Vec<usize>>,
     }
 
     impl Outcome {
@@ -337,17 +409,35 @@ pub mod iter {
         /// additional work can be prevented by writing the changes back to the index.
         pub fn has_changes(&self) -> bool {
             self.changes.as_ref().map_or(false, |changes| !changes.is_empty())
+                || self.untracked_cache.is_some()
+                || self.fs_monitor.is_some()
         }
 
         /// Write the changes if there are any back to the index file.
         /// This can only be done once as the changes are consumed in the process, if there were any.
+        ///
+        /// This includes changes to the untracked cache, which is added, updated or removed according to `core.untrackedCache`,
+        /// and the fsmonitor extension, which stores the latest token of the filesystem monitor configured in `core.fsmonitor`
+        /// along with all entries that are known to be unchanged.
         pub fn write_changes(&mut self) -> Option<Result<(), gix_index::file::write::Error>> {
             let _span = gix_features::trace::coarse!("gix::status::index_worktree::iter::Outcome::write_changes()");
-            let changes = self.changes.take()?;
+            let untracked_cache = self.untracked_cache.take();
+            let fs_monitor = self.fs_monitor.take();
+            let changes = match self.changes.take() {
+                Some(changes) => changes,
+                None if untracked_cache.is_some() || fs_monitor.is_some() => Vec::new(),
+                None => return None,
+            };
             let mut index = match &self.index {
                 IndexPersistedOrInMemory::Persisted(persisted) => (***persisted).clone(),
                 IndexPersistedOrInMemory::InMemory(index) => index.clone(),
             };
+            if let Some(untracked_cache) = untracked_cache {
+                index.set_untracked(untracked_cache);
+            }
+            if let Some(fs_monitor) = fs_monitor {
+                fs_monitor.apply_to(&mut index);
+            }
 
             let entries = index.entries_mut();
             for (entry_index, change) in changes {
@@ -546,6 +636,118 @@ pub mod iter {
                 Item::Rewrite { dirwalk_entry, .. } => dirwalk_entry.rela_path.as_ref(),
             }
         }
+
+        /// Compute the changes between the index and the worktree version of this item, as needed to display it
+        /// like `git diff --stat` or `git diff --numstat`.
+        ///
+        /// `resource_cache` must read the new version of resources from the worktree, which is what
+        /// [`Repository::diff_resource_cache()`](crate::Repository::diff_resource_cache()) provides if
+        /// [`new_root`](gix_diff::blob::pipeline::WorktreeRoots::new_root) is set to the worktree directory.
+        /// `objects` are used to read the version of the item stored in the index.
+        ///
+        /// Return `None` if the item doesn't represent a change to the content of a file, as is the case for conflicts,
+        /// submodules, directories or ignored files. Untracked files are considered additions, and so are copies
+        /// whose source is untracked as well.
+        pub fn file_stat(
+            &self,
+            resource_cache: &mut gix_diff::blob::Platform,
+            objects: &impl gix_object::FindObjectOrHeader,
+        ) -> Result<Option<gix_diff::stat::File>, file_stat::Error> {
+            use gix_diff::blob::ResourceKind;
+
+            // Worktree resources are identified by path, so the kind of hash doesn't matter here.
+            let null = gix_hash::Kind::default().null();
+            let (old, new_kind, location, source_location) = match self {
+                Item::Modification {
+                    entry,
+                    rela_path,
+                    status,
+                    ..
+                } => {
+                    let kind = match entry.mode.to_tree_entry_mode() {
+                        Some(mode) if mode.is_blob_or_symlink() => mode.kind(),
+                        _ => return Ok(None),
+                    };
+                    let old_id = match status {
+                        EntryStatus::Change(Change::Modification { .. } | Change::Type | Change::Removed) => entry.id,
+                        EntryStatus::IntentToAdd => entry.id.kind().null(),
+                        EntryStatus::Change(Change::SubmoduleModification(_))
+                        | EntryStatus::Conflict(_)
+                        | EntryStatus::NeedsUpdate(_) => return Ok(None),
+                    };
+                    (Some((old_id, kind, rela_path.as_ref())), kind, rela_path, None)
+                }
+                Item::DirectoryContents { entry, .. } => {
+                    if entry.status != gix_dir::entry::Status::Untracked {
+                        return Ok(None);
+                    }
+                    match disk_kind_to_entry_kind(entry.disk_kind) {
+                        Some(kind) => (None, kind, &entry.rela_path, None),
+                        None => return Ok(None),
+                    }
+                }
+                Item::Rewrite {
+                    source, dirwalk_entry, ..
+                } => {
+                    let Some(kind) = disk_kind_to_entry_kind(dirwalk_entry.disk_kind) else {
+                        return Ok(None);
+                    };
+                    match source {
+                        RewriteSource::RewriteFromIndex {
+                            source_entry,
+                            source_rela_path,
+                            ..
+                        } => {
+                            let Some(source_kind) = source_entry
+                                .mode
+                                .to_tree_entry_mode()
+                                .filter(gix_object::tree::EntryMode::is_blob_or_symlink)
+                                .map(|mode| mode.kind())
+                            else {
+                                return Ok(None);
+                            };
+                            (
+                                Some((source_entry.id, source_kind, source_rela_path.as_ref())),
+                                kind,
+                                &dirwalk_entry.rela_path,
+                                Some(source_rela_path.clone()),
+                            )
+                        }
+                        RewriteSource::CopyFromDirectoryEntry { .. } => (None, kind, &dirwalk_entry.rela_path, None),
+                    }
+                }
+            };
+
+            match old {
+                Some((id, kind, rela_path)) => {
+                    resource_cache.set_resource(id, kind, rela_path, ResourceKind::OldOrSource, objects)?;
+                }
+                None => {
+                    resource_cache.set_resource(
+                        null,
+                        new_kind,
+                        location.as_ref(),
+                        ResourceKind::OldOrSource,
+                        &gix_object::find::Never,
+                    )?;
+                }
+            }
+            resource_cache.set_resource(
+                null,
+                new_kind,
+                location.as_ref(),
+                ResourceKind::NewOrDestination,
+                objects,
+            )?;
+            let prep = resource_cache.prepare_diff()?;
+            Ok(
+                gix_diff::stat::Counts::from_prepared_diff(&prep).map(|counts| gix_diff::stat::File {
+                    location: location.clone(),
+                    source_location,
+                    counts,
+                }),
+            )
+        }
     }
 
     impl<'index> From<gix_status::index_as_worktree_with_renames::Entry<'index, (), SubmoduleStatus>> for Item {
@@ -589,6 +791,39 @@ pub mod iter {
         }
     }
 
+    fn disk_kind_to_entry_kind(kind: Option<gix_dir::entry::Kind>) -> Option<gix_object::tree::EntryKind> {
+        Some(match kind? {
+            gix_dir::entry::Kind::File => gix_object::tree::EntryKind::Blob,
+            gix_dir::entry::Kind::Symlink => gix_object::tree::EntryKind::Link,
+            gix_dir::entry::Kind::Directory | gix_dir::entry::Kind::Repository => return None,
+        })
+    }
+
+    ///
+    pub mod file_stat {
+        /// The error returned by [Item::file_stat()](super::Item::file_stat()).
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error(transparent)]
+            SetResource(Box<gix_diff::blob::platform::set_resource::Error>),
+            #[error(transparent)]
+            PrepareDiff(Box<gix_diff::blob::platform::prepare_diff::Error>),
+        }
+
+        impl From<gix_diff::blob::platform::set_resource::Error> for Error {
+            fn from(err: gix_diff::blob::platform::set_resource::Error) -> Self {
+                Error::SetResource(Box::new(err))
+            }
+        }
+
+        impl From<gix_diff::blob::platform::prepare_diff::Error> for Error {
+            fn from(err: gix_diff::blob::platform::prepare_diff::Error) -> Self {
+                Error::PrepareDiff(Box::new(err))
+            }
+        }
+    }
+
     type SubmoduleStatus = crate::submodule::Status;
 
     /// The error returned by [Platform::into_index_worktree_iter()](crate::status::Platform::into_index_worktree_iter()).
@@ -606,6 +841,12 @@ pub mod iter {
         #[error(transparent)]
         ConfigSkipHash(#[from] crate::config::boolean::Error),
         #[error(transparent)]
+        ConfigUntrackedCache(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        ConfigFsMonitorHookVersion(#[from] crate::config::unsigned_integer::Error),
+        #[error(transparent)]
+        FsMonitor(#[from] gix_status::fsmonitor::query::Error),
+        #[error(transparent)]
         PrepareSubmodules(#[from] crate::submodule::modules::Error),
     }
 
@@ -637,13 +878,21 @@ pub mod iter {
                 .transpose()
                 .with_lenient_default(self.repo.config.lenient_config)?
                 .unwrap_or_default();
+            let (index, fs_monitor) = fs_monitor_refresh(self.repo, index)?;
+            let use_fsmonitor = fs_monitor.as_ref().map_or(false, |update| update.use_fsmonitor);
+            let (untracked_cache_mode, mut untracked_cache) = untracked_cache_to_use(self.repo, &index)?;
             let should_interrupt = self.should_interrupt.clone().unwrap_or_default();
             let submodule = BuiltinSubmoduleStatus::new(self.repo.clone().into_sync(), self.submodules)?;
+            let patterns: Vec<_> = patterns.into_iter().collect();
+            // Only if all entries were checked can those that weren't reported be considered unchanged.
+            let all_entries_checked = patterns.is_empty();
             #[cfg(feature = "parallel")]
             {
                 let (tx, rx) = std::sync::mpsc::channel();
-                let mut collect = Collect { tx };
-                let patterns: Vec<_> = patterns.into_iter().collect();
+                let mut collect = Collect {
+                    tx,
+                    changed_entries: Vec::new(),
+                };
                 let join = std::thread::Builder::new()
                     .name("gix::status::index_worktree::iter::producer".into())
                     .spawn({
@@ -653,7 +902,7 @@ pub mod iter {
                         let mut progress = self.progress;
                         move || -> Result<_, crate::status::index_worktree::Error> {
                             let repo = repo.to_thread_local();
-                            let out = repo.index_worktree_status(
+                            let out = repo.index_worktree_status_with_untracked_cache(
                                 &index,
                                 patterns,
                                 &mut collect,
@@ -662,9 +911,15 @@ pub mod iter {
                                 &mut progress,
                                 &should_interrupt,
                                 options,
+                                untracked_cache.as_mut(),
+                                use_fsmonitor,
                             )?;
                             Ok(Outcome {
                                 index_worktree: out,
+                                untracked_cache: untracked_cache_update(untracked_cache_mode, &index, untracked_cache),
+                                fs_monitor: fs_monitor.map(|update| {
+                                    update.with_changed_entries(all_entries_checked.then_some(collect.changed_entries))
+                                }),
                                 index,
                                 changes: None,
                                 skip_hash,
@@ -682,13 +937,16 @@ pub mod iter {
             }
             #[cfg(not(feature = "parallel"))]
             {
-                let mut collect = Collect { items: Vec::new() };
+                let mut collect = Collect {
+                    items: Vec::new(),
+                    changed_entries: Vec::new(),
+                };
 
                 let repo = self.repo.clone().into_sync();
                 let options = self.index_worktree_options;
                 let mut progress = self.progress;
                 let repo = repo.to_thread_local();
-                let out = repo.index_worktree_status(
+                let out = repo.index_worktree_status_with_untracked_cache(
                     &index,
                     patterns,
                     &mut collect,
@@ -697,9 +955,15 @@ pub mod iter {
                     &mut progress,
                     &should_interrupt,
                     options,
+                    untracked_cache.as_mut(),
+                    use_fsmonitor,
                 )?;
                 let mut out = Outcome {
                     index_worktree: out,
+                    untracked_cache: untracked_cache_update(untracked_cache_mode, &index, untracked_cache),
+                    fs_monitor: fs_monitor.map(|update| {
+                        update.with_changed_entries(all_entries_checked.then_some(collect.changed_entries))
+                    }),
                     index,
                     changes: None,
                     skip_hash,
@@ -722,6 +986,181 @@ pub mod iter {
         }
     }
 
+    /// Query the filesystem monitor configured in `core.fsmonitor` and apply the changes it reports to a copy of `index`,
+    /// or remove the fsmonitor extension from a copy of `index` if there is no filesystem monitor anymore.
+    ///
+    /// Return the index to use for the status along with the update to apply to the fsmonitor extension, if any.
+    fn fs_monitor_refresh(
+        repo: &crate::Repository,
+        index: IndexPersistedOrInMemory,
+    ) -> Result<(IndexPersistedOrInMemory, Option<FsMonitorUpdate>), Error> {
+        use crate::config::tree::Core;
+        use gix_status::fsmonitor::{Changes, HookVersion, Source};
+
+        let Some(workdir) = repo.work_dir() else {
+            return Ok((index, None));
+        };
+        let command = match repo.config.resolved.boolean(Core::FSMONITOR) {
+            None | Some(Ok(false)) => None,
+            Some(Ok(true)) => Some(None),
+            Some(Err(_)) => repo.config.resolved.string(Core::FSMONITOR).map(Some),
+        };
+        let Some(command) = command else {
+            if index.fs_monitor().is_none() {
+                return Ok((index, None));
+            }
+            // Without a filesystem monitor to vouch for them, no entry can be trusted anymore.
+            let mut index = into_owned(index);
+            index.set_fs_monitor(None);
+            return Ok((
+                index.into(),
+                Some(FsMonitorUpdate {
+                    token: None,
+                    use_fsmonitor: false,
+                    changed_entries: None,
+                }),
+            ));
+        };
+
+        let socket = repo.git_dir().join("fsmonitor--daemon.ipc");
+        let source = match command.as_deref() {
+            None => Source::Daemon { socket: &socket },
+            Some(command) => Source::Hook {
+                command,
+                version: repo
+                    .config
+                    .resolved
+                    .integer(Core::FSMONITOR_HOOK_VERSION)
+                    .map(|version| Core::FSMONITOR_HOOK_VERSION.try_into_u64(version))
+                    .transpose()
+                    .with_lenient_default(repo.config.lenient_config)?
+                    .and_then(|version| match version {
+                        1 => Some(HookVersion::V1),
+                        2 => Some(HookVersion::V2),
+                        _ => None,
+                    }),
+            },
+        };
+        let out =
+            gix_status::fsmonitor::query(source, workdir, index.fs_monitor().map(|fs_monitor| &fs_monitor.token))?;
+        let mut index = into_owned(index);
+        gix_status::fsmonitor::apply_changes(&out.changes, &mut index);
+        let use_fsmonitor = out.token.is_some() && matches!(out.changes, Changes::Paths(_));
+        Ok((
+            index.into(),
+            Some(FsMonitorUpdate {
+                token: out.token,
+                use_fsmonitor,
+                changed_entries: None,
+            }),
+        ))
+    }
+
+    fn into_owned(index: IndexPersistedOrInMemory) -> gix_index::File {
+        match index {
+            IndexPersistedOrInMemory::Persisted(persisted) => (**persisted).clone(),
+            IndexPersistedOrInMemory::InMemory(index) => index,
+        }
+    }
+
+    impl FsMonitorUpdate {
+        fn with_changed_entries(mut self, changed_entries: Option<Vec<usize>>) -> Self {
+            self.changed_entries = changed_entries;
+            self
+        }
+
+        /// Store the token in `index`, and mark all entries that are known to be unchanged as valid so they
+        /// are skipped until the filesystem monitor reports them as changed.
+        fn apply_to(self, index: &mut gix_index::State) {
+            let Some(token) = self.token else {
+                index.set_fs_monitor(None);
+                return;
+            };
+            if let Some(changed_entries) = self.changed_entries {
+                use gix_index::entry::{Flags, Mode};
+                let entries = index.entries_mut();
+                for entry in entries.iter_mut() {
+                    // Submodules can change without the filesystem monitor noticing.
+                    if !entry.flags.contains(Flags::SKIP_WORKTREE) && entry.mode != Mode::COMMIT {
+                        entry.flags.insert(Flags::FSMONITOR_VALID);
+                    }
+                }
+                for entry_index in changed_entries {
+                    entries[entry_index].flags.remove(Flags::FSMONITOR_VALID);
+                }
+            }
+            index.set_fs_monitor(Some(gix_index::extension::FsMonitor {
+                token,
+                entry_dirty: Default::default(),
+            }));
+        }
+    }
+
+    /// Return the `core.untrackedCache` setting along with the untracked cache to use for a status of `index`, if any.
+    fn untracked_cache_to_use(
+        repo: &crate::Repository,
+        index: &gix_index::State,
+    ) -> Result<(Option<bool>, Option<gix_index::extension::UntrackedCache>), Error> {
+        let mode = repo
+            .config
+            .resolved
+            .string(crate::config::tree::Core::UNTRACKED_CACHE)
+            .map(|value| crate::config::tree::Core::UNTRACKED_CACHE.try_into_untracked_cache(value))
+            .transpose()
+            .with_lenient_default(repo.config.lenient_config)?
+            .flatten();
+        let Some(identifier) = repo.work_dir().map(|workdir| untracked_cache_identifier(repo, workdir)) else {
+            return Ok((mode, None));
+        };
+        let existing = index
+            .untracked()
+            .filter(|cache| cache.identifier == identifier)
+            .cloned();
+        let cache = match mode {
+            Some(false) => None,
+            Some(true) => Some(existing.unwrap_or_else(|| gix_index::extension::UntrackedCache::new(identifier, 0))),
+            None => existing,
+        };
+        Ok((mode, cache))
+    }
+
+    /// Return the untracked cache that should be stored in `index` after it was updated during the status,
+    /// or `None` if the index should remain unchanged.
+    fn untracked_cache_update(
+        mode: Option<bool>,
+        index: &gix_index::State,
+        cache: Option<gix_index::extension::UntrackedCache>,
+    ) -> Option<Option<gix_index::extension::UntrackedCache>> {
+        match mode {
+            Some(false) => index.untracked().is_some().then_some(None),
+            Some(true) | None => cache.filter(|cache| index.untracked() != Some(cache)).map(Some),
+        }
+    }
+
+    /// Identify the worktree and system like git does, so caches from other locations or systems aren't used.
+    fn untracked_cache_identifier(repo: &crate::Repository, workdir: &std::path::Path) -> BString {
+        let workdir = gix_path::realpath_opts(workdir, repo.current_dir(), gix_path::realpath::MAX_SYMLINKS)
+            .unwrap_or_else(|_| workdir.to_owned());
+        let system = match std::env::consts::OS {
+            "linux" | "android" => "Linux",
+            "macos" | "ios" => "Darwin",
+            "windows" => "Windows",
+            "freebsd" => "FreeBSD",
+            "netbsd" => "NetBSD",
+            "openbsd" => "OpenBSD",
+            "dragonfly" => "DragonFly",
+            "solaris" | "illumos" => "SunOS",
+            other => other,
+        };
+        let mut identifier = BString::from("Location ");
+        identifier.extend_from_slice(&gix_path::into_bstr(workdir));
+        identifier.extend_from_slice(b", system ");
+        identifier.extend_from_slice(system.as_bytes());
+        // git stores the trailing null-byte as well.
+        identifier.push(0);
+        identifier
+    }
+
     impl Iterator for super::Iter {
         type Item = Result<Item, index_worktree::Error>;
 
@@ -807,6 +1246,8 @@ pub mod iter {
         tx: std::sync::mpsc::Sender<Item>,
         #[cfg(not(feature = "parallel"))]
         items: Vec<Item>,
+        /// The indices of all index entries that were reported as changed.
+        changed_entries: Vec<usize>,
     }
 
     impl<'index> gix_status::index_as_worktree_with_renames::VisitEntry<'index> for Collect {
@@ -822,6 +1263,27 @@ pub mod iter {
                 Self::SubmoduleStatus,
             >,
         ) {
+            use gix_status::index_as_worktree_with_renames::{Entry, RewriteSource};
+            match &entry {
+                Entry::Modification {
+                    status: EntryStatus::NeedsUpdate(_),
+                    ..
+                }
+                | Entry::DirectoryContents { .. }
+                | Entry::Rewrite {
+                    source: RewriteSource::CopyFromDirectoryEntry { .. },
+                    ..
+                } => {}
+                Entry::Modification { entry_index, .. }
+                | Entry::Rewrite {
+                    source:
+                        RewriteSource::RewriteFromIndex {
+                            source_entry_index: entry_index,
+                            ..
+                        },
+                    ..
+                } => self.changed_entries.push(*entry_index),
+            }
             // NOTE: we assume that the receiver triggers interruption so the operation will stop if the receiver is down.
             #[cfg(feature = "parallel")]
             self.tx.send(entry.into()).ok();
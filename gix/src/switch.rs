@@ -0,0 +1,98 @@
+//! Switch `HEAD` to another branch or commit and update the index and the worktree accordingly, similar to `git switch`
+//! and `git checkout <branch>`.
+//!
+//! See [`Repository::switch()`](crate::Repository::switch()) for details.
+use gix_hash::ObjectId;
+use gix_ref::FullName;
+
+use crate::bstr::BString;
+
+/// What [`Repository::switch()`](crate::Repository::switch()) should make `HEAD` point to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// Attach `HEAD` to the branch with the given name, which must exist.
+    Branch(FullName),
+    /// Detach `HEAD` at the commit the given object peels to.
+    Detached(ObjectId),
+}
+
+/// Options for use in [`Repository::switch()`](crate::Repository::switch()).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If `true`, discard all local changes in the index and in tracked files, and overwrite untracked files that are in
+    /// the way, like `--force`.
+    pub force: bool,
+    /// If `true`, merge local changes to files that differ between `HEAD` and the target into the version of the target,
+    /// instead of refusing to switch, like `--merge`.
+    ///
+    /// Paths that can't be merged without conflicts are left unmerged in the index, with conflict markers in the worktree.
+    pub merge: bool,
+}
+
+/// The result of [`Repository::switch()`](crate::Repository::switch()).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of files that were written to the worktree.
+    pub files_checked_out: usize,
+    /// The amount of files that were removed from the worktree as they aren't tracked anymore.
+    pub files_removed: usize,
+    /// The paths whose local changes couldn't be merged without conflicts, only possible with [`Options::merge`].
+    pub conflicts: Vec<BString>,
+}
+
+/// The error returned by [`Repository::switch()`](crate::Repository::switch()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot switch branches in a bare repository")]
+    BareRepository,
+    #[error("Your local changes to '{path}' would be overwritten by checkout")]
+    LocalChangesWouldBeOverwritten { path: BString },
+    #[error("Untracked working tree file '{path}' would be overwritten by checkout")]
+    UntrackedWouldBeOverwritten { path: BString },
+    #[error("'{path}' needs merge")]
+    Unmerged { path: BString },
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    Peel(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    WriteBlob(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    ExpandIndex(#[from] gix_index::sparse::expand::Error),
+    #[error(transparent)]
+    SparseCheckoutPatterns(#[from] crate::sparse_checkout::patterns::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error("Could not fetch the objects to check out from the promisor remote")]
+    FetchMissingObjects(#[source] gix_odb::store::promisor::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error("Could not access '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
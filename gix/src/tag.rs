@@ -12,6 +12,12 @@ mod error {
         WriteObject(#[from] crate::object::write::Error),
         #[error(transparent)]
         ReferenceEdit(#[from] crate::reference::edit::Error),
+        #[cfg(feature = "gpg")]
+        #[error(transparent)]
+        Signer(#[from] crate::repository::signer::Error),
+        #[cfg(feature = "gpg")]
+        #[error("Could not sign the tag")]
+        Sign(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
     }
 }
 pub use error::Error;
@@ -0,0 +1,425 @@
+use std::path::PathBuf;
+
+use crate::bstr::BString;
+
+/// Determine what to check out in a new worktree, see [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+#[derive(Debug, Clone)]
+pub enum Target {
+    /// Create the branch `name` at `start`, or at the commit `HEAD` points to if `None`, and check it out,
+    /// like `git worktree add -b <name> <path> [<start>]`.
+    NewBranch {
+        /// The full name of the branch to create, like `refs/heads/feature`.
+        name: gix_ref::FullName,
+        /// The commit the new branch should point to, or `None` to use the commit `HEAD` points to.
+        start: Option<gix_hash::ObjectId>,
+    },
+    /// Check out the existing branch with the given full name, like `git worktree add <path> <branch>`.
+    Branch(gix_ref::FullName),
+    /// Detach `HEAD` at the given commit, like `git worktree add --detach <path> <commit>`.
+    Detached(gix_hash::ObjectId),
+}
+
+/// Options for use in [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The name of the worktree, which is also the name of its private directory in `$GIT_COMMON_DIR/worktrees`.
+    ///
+    /// If `None`, it's derived from the last component of the worktree path, with a number appended in case it's already taken.
+    pub id: Option<BString>,
+    /// If `true`, the worktree is checked out and its index is written. Otherwise, only `HEAD` is set, like `--no-checkout`.
+    pub checkout: bool,
+    /// If `Some(reason)`, lock the new worktree with the given, possibly empty, reason, like `--lock --reason <reason>`.
+    pub lock_reason: Option<BString>,
+    /// If `true`, check out a branch even though it's already checked out in another worktree.
+    pub force: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            id: None,
+            checkout: true,
+            lock_reason: None,
+            force: false,
+        }
+    }
+}
+
+/// The error returned by [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("'{}' already exists", path.display())]
+    PathExists { path: PathBuf },
+    #[error("The worktree name '{id}' can't be used as it's not a valid directory name")]
+    InvalidId { id: BString },
+    #[error("A worktree named '{id}' already exists")]
+    IdExists { id: BString },
+    #[error("A branch named '{}' already exists", name.as_bstr())]
+    BranchExists { name: gix_ref::FullName },
+    #[error("'{}' is already checked out at '{}'", name.as_bstr(), path.display())]
+    BranchCheckedOut { name: gix_ref::FullName, path: PathBuf },
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindExistingReference(#[from] crate::reference::find::Error),
+    #[error("Could not peel a reference to the commit to check out")]
+    PeelReference(#[from] crate::reference::peel::to_kind::Error),
+    #[error("Could not obtain the commit HEAD points to")]
+    HeadCommit(#[from] crate::reference::head_commit::Error),
+    #[error("Could not create branch '{}'", name.as_bstr())]
+    CreateBranch {
+        name: gix_ref::FullName,
+        source: crate::reference::edit::Error,
+    },
+    #[error("Could not set HEAD of the new worktree")]
+    SetHead(#[source] crate::reference::edit::Error),
+    #[error("Could not list existing worktrees")]
+    ListWorktrees(#[source] std::io::Error),
+    #[error("Could not write '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Could not open the new worktree")]
+    Open(#[from] crate::open::Error),
+    #[error("The commit to check out could not be found")]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error("The commit to check out could not be decoded")]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error("Could not fetch the objects to check out from the promisor remote")]
+    FetchMissingObjects(#[source] gix_odb::store::promisor::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+    OpenArcOdb(#[source] std::io::Error),
+}
+
+pub(crate) mod function {
+    use std::{
+        path::{Path, PathBuf},
+        sync::atomic::AtomicBool,
+    };
+
+    use gix_ref::{
+        transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+        FullName, Target as RefTarget,
+    };
+
+    use super::{Error, Options, Target};
+    use crate::{
+        bstr::{BString, ByteSlice},
+        Repository,
+    };
+
+    impl Repository {
+        /// Create a new linked worktree at `path` which must not exist or be an empty directory, check out `target` there
+        /// according to `options`, and return the repository of the new worktree.
+        ///
+        /// This writes the same files `git worktree add` writes, i.e. the private git directory of the worktree in
+        /// `$GIT_COMMON_DIR/worktrees/<id>` with its `gitdir`, `commondir` and `HEAD` files, along with the `.git` file in
+        /// the new worktree that points back to it.
+        /// If anything fails, all files and directories that were created are removed again.
+        /// The `post-checkout` [hook](crate::hook) runs in the new worktree once it was checked out.
+        ///
+        /// ### Deviation
+        ///
+        /// Sparse-checkout patterns aren't applied to the new worktree, and per-worktree configuration isn't set up.
+        pub fn worktree_add(
+            &self,
+            path: impl AsRef<Path>,
+            target: Target,
+            options: Options,
+            should_interrupt: &AtomicBool,
+        ) -> Result<Repository, Error> {
+            let _span = gix_trace::coarse!("gix::Repository::worktree_add()");
+            let path = path.as_ref();
+            let path = if path.is_absolute() {
+                path.to_owned()
+            } else {
+                std::env::current_dir()
+                    .map_err(|err| Error::Io {
+                        path: path.to_owned(),
+                        source: err,
+                    })?
+                    .join(path)
+            };
+            let path_existed = match std::fs::read_dir(&path) {
+                Ok(mut entries) => {
+                    if entries.next().is_some() {
+                        return Err(Error::PathExists { path });
+                    }
+                    true
+                }
+                Err(_) if path.exists() => return Err(Error::PathExists { path }),
+                Err(_) => false,
+            };
+
+            let (commit_id, branch) = self.resolve_worktree_target(&target, options.force)?;
+            let worktrees_dir = self.common_dir().join("worktrees");
+            let id = match &options.id {
+                Some(id) => {
+                    if !is_valid_id(id.as_ref()) {
+                        return Err(Error::InvalidId { id: id.clone() });
+                    }
+                    if worktrees_dir.join(gix_path::from_bstr(id.as_bstr())).exists() {
+                        return Err(Error::IdExists { id: id.clone() });
+                    }
+                    id.clone()
+                }
+                None => unique_id(&worktrees_dir, &path),
+            };
+            let private_git_dir = worktrees_dir.join(gix_path::from_bstr(id.as_bstr()));
+
+            let res = (|| {
+                create_dir_all(&path)?;
+                create_dir_all(&worktrees_dir)?;
+                std::fs::create_dir(&private_git_dir).map_err(|err| Error::Io {
+                    path: private_git_dir.clone(),
+                    source: err,
+                })?;
+                // Like git, lock the worktree while it's being set up so it isn't pruned in the meantime.
+                write(&private_git_dir.join("locked"), "initializing\n")?;
+
+                let worktree_dot_git = gix_path::realpath(&path)
+                    .map_err(|err| Error::Io {
+                        path: path.clone(),
+                        source: std::io::Error::new(std::io::ErrorKind::Other, err),
+                    })?
+                    .join(gix_discover::DOT_GIT_DIR);
+                let private_git_dir_abs = gix_path::realpath(&private_git_dir).map_err(|err| Error::Io {
+                    path: private_git_dir.clone(),
+                    source: std::io::Error::new(std::io::ErrorKind::Other, err),
+                })?;
+                write(
+                    &private_git_dir.join("gitdir"),
+                    format!("{}\n", gix_path::into_bstr(&worktree_dot_git)),
+                )?;
+                write(&private_git_dir.join("commondir"), "../..\n")?;
+                // A placeholder to make the private git directory look like one, just like git does.
+                write(
+                    &private_git_dir.join("HEAD"),
+                    format!("{}\n", gix_hash::ObjectId::null(self.object_hash())),
+                )?;
+                write(
+                    &worktree_dot_git,
+                    format!("gitdir: {}\n", gix_path::into_bstr(&private_git_dir_abs)),
+                )?;
+
+                if let Target::NewBranch { name, .. } = &target {
+                    self.reference(
+                        name.clone(),
+                        commit_id,
+                        PreviousValue::MustNotExist,
+                        format!("branch: Created from {commit_id}"),
+                    )
+                    .map_err(|err| Error::CreateBranch {
+                        name: name.clone(),
+                        source: err,
+                    })?;
+                }
+
+                let repo: Repository =
+                    crate::ThreadSafeRepository::open_opts(&path, self.options.clone())?.to_thread_local();
+                repo.edit_reference(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            log_symbolic_changes: false,
+                            message: "".into(),
+                        },
+                        expected: PreviousValue::Any,
+                        new: match &branch {
+                            Some(name) => RefTarget::Symbolic(name.clone()),
+                            None => RefTarget::Object(commit_id),
+                        },
+                    },
+                    name: "HEAD".try_into().expect("valid"),
+                    deref: false,
+                })
+                .map_err(Error::SetHead)?;
+
+                if options.checkout {
+                    checkout(&repo, commit_id, should_interrupt)?;
+                }
+
+                let locked = private_git_dir.join("locked");
+                match &options.lock_reason {
+                    Some(reason) if reason.is_empty() => write(&locked, "")?,
+                    Some(reason) => write(&locked, format!("{reason}\n"))?,
+                    None => std::fs::remove_file(&locked).map_err(|err| Error::Io {
+                        path: locked.clone(),
+                        source: err,
+                    })?,
+                }
+                #[cfg(feature = "hooks")]
+                if options.checkout {
+                    repo.run_post_hook(
+                        "post-checkout",
+                        vec![
+                            self.object_hash().null().to_string().into(),
+                            commit_id.to_string().into(),
+                            "1".into(),
+                        ],
+                        &[],
+                    );
+                }
+                Ok(repo)
+            })();
+
+            if res.is_err() {
+                std::fs::remove_dir_all(&private_git_dir).ok();
+                if path_existed {
+                    if let Ok(entries) = std::fs::read_dir(&path) {
+                        for entry in entries.flatten() {
+                            let entry_path = entry.path();
+                            if entry_path.is_dir() {
+                                std::fs::remove_dir_all(entry_path).ok();
+                            } else {
+                                std::fs::remove_file(entry_path).ok();
+                            }
+                        }
+                    }
+                } else {
+                    std::fs::remove_dir_all(&path).ok();
+                }
+                std::fs::remove_dir(&worktrees_dir).ok();
+            }
+            res
+        }
+
+        /// Return the commit to check out for `target` and the name of the branch `HEAD` should point to, if any.
+        fn resolve_worktree_target(
+            &self,
+            target: &Target,
+            force: bool,
+        ) -> Result<(gix_hash::ObjectId, Option<FullName>), Error> {
+            Ok(match target {
+                Target::NewBranch { name, start } => {
+                    if self.try_find_reference(name.as_ref())?.is_some() {
+                        return Err(Error::BranchExists { name: name.clone() });
+                    }
+                    let id = match start {
+                        Some(id) => self.find_commit(*id)?.id,
+                        None => self.head_commit()?.id,
+                    };
+                    (id, Some(name.clone()))
+                }
+                Target::Branch(name) => {
+                    let id = self.find_reference(name.as_ref())?.peel_to_commit()?.id;
+                    if !force {
+                        if let Some(path) = self.branch_checked_out_at(name)? {
+                            return Err(Error::BranchCheckedOut {
+                                name: name.clone(),
+                                path,
+                            });
+                        }
+                    }
+                    (id, Some(name.clone()))
+                }
+                Target::Detached(id) => (self.find_commit(*id)?.id, None),
+            })
+        }
+
+        /// Return the path to the non-bare worktree that has the branch `name` checked out, if there is one.
+        fn branch_checked_out_at(&self, name: &FullName) -> Result<Option<PathBuf>, Error> {
+            let main = self.main_repo()?;
+            if let Some(work_dir) = main.work_dir() {
+                if main.head_name()?.as_ref() == Some(name) {
+                    return Ok(Some(work_dir.to_owned()));
+                }
+            }
+            for proxy in self.worktrees().map_err(Error::ListWorktrees)? {
+                let base = proxy.base().ok();
+                let repo = proxy.into_repo_with_possibly_inaccessible_worktree()?;
+                if repo.head_name()?.as_ref() == Some(name) {
+                    return Ok(Some(base.unwrap_or_else(|| repo.git_dir().to_owned())));
+                }
+            }
+            Ok(None)
+        }
+    }
+
+    fn checkout(repo: &Repository, commit_id: gix_hash::ObjectId, should_interrupt: &AtomicBool) -> Result<(), Error> {
+        let workdir = repo.work_dir().expect("linked worktrees are never bare");
+        let tree_id = repo.find_commit(commit_id)?.tree_id()?;
+        let mut index = repo.index_from_tree(&tree_id)?;
+        repo.objects
+            .fetch_missing(index.entries().iter().filter(|e| !e.mode.is_submodule()).map(|e| e.id))
+            .map_err(Error::FetchMissingObjects)?;
+
+        let mut opts = repo
+            .config
+            .checkout_options(repo, gix_worktree::stack::state::attributes::Source::IdMapping)?;
+        opts.destination_is_initially_empty = true;
+        gix_worktree_state::checkout(
+            &mut index,
+            workdir,
+            repo.objects.clone().into_arc().map_err(Error::OpenArcOdb)?,
+            &gix_features::progress::Discard,
+            &gix_features::progress::Discard,
+            should_interrupt,
+            opts,
+        )?;
+        index.write(Default::default())?;
+        Ok(())
+    }
+
+    fn create_dir_all(path: &Path) -> Result<(), Error> {
+        std::fs::create_dir_all(path).map_err(|err| Error::Io {
+            path: path.to_owned(),
+            source: err,
+        })
+    }
+
+    fn write(path: &Path, content: impl AsRef<[u8]>) -> Result<(), Error> {
+        std::fs::write(path, content).map_err(|err| Error::Io {
+            path: path.to_owned(),
+            source: err,
+        })
+    }
+
+    /// Like git, only accept names that are valid as single path component and won't be mistaken for a special directory.
+    fn is_valid_id(id: &crate::bstr::BStr) -> bool {
+        !id.is_empty() && id != "." && id != ".." && !id.contains(&b'/') && !id.contains(&b'\\')
+    }
+
+    /// Derive the worktree id from the last component of `path`, and append a number until it's unique, like git does.
+    fn unique_id(worktrees_dir: &Path, path: &Path) -> BString {
+        let name = path
+            .file_name()
+            .map(|name| {
+                gix_path::os_str_into_bstr(name)
+                    .map_or_else(|_| name.to_string_lossy().into_owned().into(), ToOwned::to_owned)
+            })
+            .unwrap_or_default();
+        let mut base: BString = name
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_alphanumeric() || b"-_.".contains(&b) {
+                    b
+                } else {
+                    b'-'
+                }
+            })
+            .collect::<Vec<_>>()
+            .into();
+        while base.first() == Some(&b'.') {
+            base.remove(0);
+        }
+        if base.is_empty() {
+            base = "worktree".into();
+        }
+        let mut id = base.clone();
+        let mut counter = 1;
+        while worktrees_dir.join(gix_path::from_bstr(id.as_bstr())).exists() {
+            id = format!("{base}{counter}").into();
+            counter += 1;
+        }
+        id
+    }
+}
@@ -24,6 +24,105 @@ pub mod into_repo {
     }
 }
 
+///
+pub mod lock {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Proxy::lock()`][super::Proxy::lock()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is already locked{}", reason.as_ref().filter(|r| !r.is_empty()).map(|r| format!(", reason: {r}")).unwrap_or_default())]
+        AlreadyLocked { id: BString, reason: Option<BString> },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+pub mod move_to {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// The error returned by [`Proxy::move_to()`][super::Proxy::move_to()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is locked and can't be moved")]
+        Locked { id: BString },
+        #[error("Worktree at '{}' is inaccessible", .base.display())]
+        MissingWorktree { base: PathBuf },
+        #[error("'{}' already exists", .destination.display())]
+        DestinationExists { destination: PathBuf },
+        #[error("Could not move worktree from '{}' to '{}'", .source_dir.display(), .destination.display())]
+        Rename {
+            source_dir: PathBuf,
+            destination: PathBuf,
+            source: std::io::Error,
+        },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+pub mod repair {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Proxy::repair()`][super::Proxy::repair()] and
+    /// [`Repository::worktree_repair_at()`][crate::Repository::worktree_repair_at()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("'{}' is not a file, so the worktree isn't linked", .path.display())]
+        DotGitIsNotAFile { path: PathBuf },
+        #[error("Could not read the private git directory of the worktree at '{}'", .path.display())]
+        ReadGitDirFile {
+            path: PathBuf,
+            source: gix_discover::path::from_gitdir_file::Error,
+        },
+        #[error("The worktree at '{}' doesn't belong to this repository", .path.display())]
+        UnknownWorktree { path: PathBuf },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+#[cfg(feature = "status")]
+pub mod remove {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is locked and can't be removed")]
+        Locked { id: BString },
+        #[error("Worktree '{id}' contains modified or untracked files, use force to delete it anyway")]
+        Dirty { id: BString },
+        #[error(transparent)]
+        IntoRepo(#[from] super::into_repo::Error),
+        #[error(transparent)]
+        StatusPlatform(#[from] crate::status::Error),
+        #[error(transparent)]
+        CreateStatusIterator(#[from] crate::status::index_worktree::iter::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::index_worktree::Error),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        HeadTree(#[from] crate::reference::head_tree_id::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
 impl<'repo> Proxy<'repo> {
     pub(crate) fn new(parent: &'repo Repository, git_dir: impl Into<PathBuf>) -> Self {
         Proxy {
@@ -99,3 +198,160 @@ impl<'repo> Proxy<'repo> {
         Ok(repo.into())
     }
 }
+
+/// Mutation
+impl Proxy<'_> {
+    /// Lock this worktree with an optional `reason` so it can't be pruned, moved or removed, like `git worktree lock`.
+    pub fn lock(&self, reason: Option<&BStr>) -> Result<(), lock::Error> {
+        if self.is_locked() {
+            return Err(lock::Error::AlreadyLocked {
+                id: self.id().to_owned(),
+                reason: self.lock_reason(),
+            });
+        }
+        let mut content = reason.map(ToOwned::to_owned).unwrap_or_default();
+        if !content.is_empty() && !content.ends_with(b"\n") {
+            content.push(b'\n');
+        }
+        std::fs::write(self.git_dir.join("locked"), content)?;
+        Ok(())
+    }
+
+    /// Unlock this worktree, like `git worktree unlock`, and return `true` if it was locked.
+    pub fn unlock(&self) -> std::io::Result<bool> {
+        match std::fs::remove_file(self.git_dir.join("locked")) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Move the checkout of this worktree to `destination`, or into it if it's an existing directory, like `git worktree move`,
+    /// and return the new location of the checkout.
+    ///
+    /// Note that locked worktrees can't be moved until they are [unlocked](Self::unlock()).
+    pub fn move_to(&self, destination: impl AsRef<Path>) -> Result<PathBuf, move_to::Error> {
+        if self.is_locked() {
+            return Err(move_to::Error::Locked {
+                id: self.id().to_owned(),
+            });
+        }
+        let base = self.base()?;
+        if !base.is_dir() {
+            return Err(move_to::Error::MissingWorktree { base });
+        }
+        let mut destination = destination.as_ref().to_owned();
+        if destination.is_dir() {
+            destination.push(base.file_name().expect("worktrees have a name"));
+        }
+        if destination.exists() {
+            return Err(move_to::Error::DestinationExists { destination });
+        }
+        std::fs::rename(&base, &destination).map_err(|err| move_to::Error::Rename {
+            source_dir: base,
+            destination: destination.clone(),
+            source: err,
+        })?;
+        let destination =
+            gix_path::realpath(&destination).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        write_gitdir_file(&self.git_dir, &destination)?;
+        Ok(destination)
+    }
+
+    /// Assure the `.git` file in the checkout of this worktree points to its private git directory, and rewrite it if it doesn't,
+    /// like `git worktree repair`.
+    ///
+    /// Returns `true` if the `.git` file was rewritten, or `false` if it was correct or if the checkout doesn't exist.
+    /// To repair worktrees whose checkout was moved manually, use [`Repository::worktree_repair_at()`].
+    pub fn repair(&self) -> Result<bool, repair::Error> {
+        let base = self.base()?;
+        if !base.is_dir() {
+            return Ok(false);
+        }
+        let dot_git = base.join(gix_discover::DOT_GIT_DIR);
+        if dot_git.is_dir() {
+            return Err(repair::Error::DotGitIsNotAFile { path: dot_git });
+        }
+        let private_git_dir =
+            gix_path::realpath(&self.git_dir).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        if gix_discover::path::from_gitdir_file(&dot_git)
+            .ok()
+            .and_then(|path| gix_path::realpath(path).ok())
+            .map_or(false, |path| path == private_git_dir)
+        {
+            return Ok(false);
+        }
+        write_dot_git_file(&dot_git, &private_git_dir)?;
+        Ok(true)
+    }
+
+    /// Delete the checkout of this worktree along with its private git directory, like `git worktree remove`.
+    ///
+    /// Unless `force` is `true`, this fails if the checkout contains modified or untracked files, or changes that are staged for commit.
+    /// Note that locked worktrees can't be removed until they are [unlocked](Self::unlock()).
+    #[cfg(feature = "status")]
+    pub fn remove(self, force: bool) -> Result<(), remove::Error> {
+        if self.is_locked() {
+            return Err(remove::Error::Locked {
+                id: self.id().to_owned(),
+            });
+        }
+        let id = self.id().to_owned();
+        let git_dir = self.git_dir.clone();
+        let base = self.base().ok().filter(|base| base.is_dir());
+        if let Some(base) = base {
+            if !force {
+                let repo = self.into_repo()?;
+                if is_dirty(&repo)? {
+                    return Err(remove::Error::Dirty { id });
+                }
+            }
+            std::fs::remove_dir_all(base)?;
+        }
+        std::fs::remove_dir_all(&git_dir)?;
+        if let Some(worktrees_dir) = git_dir.parent() {
+            // Like git, remove the directory if it's empty, and ignore failure if it isn't.
+            std::fs::remove_dir(worktrees_dir).ok();
+        }
+        Ok(())
+    }
+}
+
+/// Return `true` if the worktree of `repo` has modified or untracked files, or if its index differs from `HEAD`.
+#[cfg(feature = "status")]
+fn is_dirty(repo: &Repository) -> Result<bool, remove::Error> {
+    if let Some(item) = repo
+        .status(gix_features::progress::Discard)?
+        .index_worktree_rewrites(None)
+        .into_index_worktree_iter(Vec::new())?
+        .next()
+    {
+        item?;
+        return Ok(true);
+    }
+
+    let index = repo.index_or_empty()?;
+    if repo.head()?.is_unborn() {
+        return Ok(!index.entries().is_empty());
+    }
+    let head_index = repo.index_from_tree(&repo.head_tree_id()?)?;
+    Ok(index.entries().len() != head_index.entries().len()
+        || index
+            .entries()
+            .iter()
+            .zip(head_index.entries())
+            .any(|(a, b)| a.id != b.id || a.mode != b.mode || a.path(&index) != b.path(&head_index)))
+}
+
+/// Write the `gitdir` file into the `private_git_dir` of a worktree to point to the `.git` file in its checkout at `base`.
+pub(crate) fn write_gitdir_file(private_git_dir: &Path, base: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        private_git_dir.join("gitdir"),
+        format!("{}\n", gix_path::into_bstr(base.join(gix_discover::DOT_GIT_DIR))),
+    )
+}
+
+/// Write the `.git` file at `dot_git` to point to the `private_git_dir` of its worktree.
+pub(crate) fn write_dot_git_file(dot_git: &Path, private_git_dir: &Path) -> std::io::Result<()> {
+    std::fs::write(dot_git, format!("gitdir: {}\n", gix_path::into_bstr(private_git_dir)))
+}
@@ -0,0 +1,122 @@
+use crate::bstr::BString;
+
+/// Options for use in [`Repository::worktrees_prune()`](crate::Repository::worktrees_prune()).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// If `Some(time)`, only prune worktrees whose checkout is missing if their `gitdir` file wasn't modified after `time`,
+    /// like `git worktree prune --expire <time>`. If `None`, they are pruned no matter how old they are.
+    ///
+    /// Note that worktrees with an unusable private git directory are always pruned.
+    pub expire: Option<std::time::SystemTime>,
+    /// If `true`, don't actually delete anything, but only report what would be pruned.
+    pub dry_run: bool,
+}
+
+/// The reason for a worktree to be pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The entry in `$GIT_COMMON_DIR/worktrees` isn't a directory.
+    NotADirectory,
+    /// The `gitdir` file in the private git directory doesn't exist.
+    MissingGitDirFile,
+    /// The `gitdir` file in the private git directory couldn't be read or is empty.
+    InvalidGitDirFile,
+    /// The `gitdir` file points to a location that doesn't exist anymore as the worktree was deleted.
+    GitDirPointsToNonExistingLocation,
+}
+
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Reason::NotADirectory => "not a valid directory",
+            Reason::MissingGitDirFile => "gitdir file does not exist",
+            Reason::InvalidGitDirFile => "invalid gitdir file",
+            Reason::GitDirPointsToNonExistingLocation => "gitdir file points to non-existent location",
+        })
+    }
+}
+
+/// A worktree that was pruned by [`Repository::worktrees_prune()`](crate::Repository::worktrees_prune()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pruned {
+    /// The name of the worktree, i.e. the name of its private directory in `$GIT_COMMON_DIR/worktrees`.
+    pub id: BString,
+    /// The reason for it to be pruned.
+    pub reason: Reason,
+}
+
+pub(crate) mod function {
+    use std::path::Path;
+
+    use super::{Options, Pruned, Reason};
+    use crate::Repository;
+
+    impl Repository {
+        /// Remove the private git directories of all linked worktrees that aren't usable anymore, typically because
+        /// their checkout was deleted, and return information about each of them, like `git worktree prune`.
+        ///
+        /// Locked worktrees are never pruned.
+        pub fn worktrees_prune(&self, options: Options) -> std::io::Result<Vec<Pruned>> {
+            let mut res = Vec::new();
+            let worktrees_dir = self.common_dir().join("worktrees");
+            let iter = match std::fs::read_dir(&worktrees_dir) {
+                Ok(iter) => iter,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(res),
+                Err(err) => return Err(err),
+            };
+            for entry in iter {
+                let entry = entry?;
+                let path = entry.path();
+                let Some(reason) = prune_reason(&path, options.expire) else {
+                    continue;
+                };
+                if !options.dry_run {
+                    if path.is_dir() {
+                        std::fs::remove_dir_all(&path)?;
+                    } else {
+                        std::fs::remove_file(&path)?;
+                    }
+                }
+                res.push(Pruned {
+                    id: gix_path::os_str_into_bstr(&entry.file_name()).map_or_else(
+                        |_| entry.file_name().to_string_lossy().into_owned().into(),
+                        ToOwned::to_owned,
+                    ),
+                    reason,
+                });
+            }
+            if !options.dry_run {
+                // Like git, remove the directory if it's empty, and ignore failure if it isn't.
+                std::fs::remove_dir(&worktrees_dir).ok();
+            }
+            res.sort_by(|a, b| a.id.cmp(&b.id));
+            Ok(res)
+        }
+    }
+
+    /// Return the reason for the private git directory of a worktree at `git_dir` to be pruned, or `None` if it should be kept.
+    fn prune_reason(git_dir: &Path, expire: Option<std::time::SystemTime>) -> Option<Reason> {
+        if !git_dir.is_dir() {
+            return Some(Reason::NotADirectory);
+        }
+        if git_dir.join("locked").exists() {
+            return None;
+        }
+        let gitdir_file = git_dir.join("gitdir");
+        let dot_git = match gix_discover::path::from_plain_file(&gitdir_file) {
+            None => return Some(Reason::MissingGitDirFile),
+            Some(Err(_)) => return Some(Reason::InvalidGitDirFile),
+            Some(Ok(path)) if path.as_os_str().is_empty() => return Some(Reason::InvalidGitDirFile),
+            Some(Ok(path)) => git_dir.join(path),
+        };
+        if dot_git.exists() {
+            return None;
+        }
+        let is_expired = expire.map_or(true, |expire| {
+            std::fs::metadata(&gitdir_file)
+                .and_then(|md| md.modified())
+                .map_or(true, |modified| modified <= expire)
+        });
+        is_expired.then_some(Reason::GitDirPointsToNonExistingLocation)
+    }
+}
@@ -117,8 +117,8 @@ mod blocking_io {
             vec![
                 hex_to_id("2d9d136fb0765f2e24c44a0f91984318d580d03b"),
                 hex_to_id("dfd0954dabef3b64f458321ef15571cc1a46d552"),
-                hex_to_id("dfd0954dabef3b64f458321ef15571cc1a46d552"),
-            ]
+            ],
+            "shallow commits the remote mentions more than once are only written once"
         );
         assert_eq!(
             repo.config_snapshot().boolean("my.marker"),
@@ -694,6 +694,115 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn partial_clone_fetches_missing_objects_from_promisor_remote() -> crate::Result {
+        use gix::remote::fetch::Filter;
+        use gix_object::Exists;
+        let remote_repo = gix_testtools::scripted_fixture_read_only("make_partial_clone_remote.sh")?.join("base");
+        for version in [
+            gix::protocol::transport::Protocol::V1,
+            gix::protocol::transport::Protocol::V2,
+        ] {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let mut prepare = gix::clone::PrepareFetch::new(
+                remote_repo.as_path(),
+                tmp.path(),
+                gix::create::Kind::WithWorktree,
+                Default::default(),
+                restricted().config_overrides(Some(format!("protocol.version={}", version as u8))),
+            )?
+            .with_filter(Filter::BlobNone);
+            let (mut checkout, _out) = prepare.fetch_then_checkout(gix::progress::Discard, &AtomicBool::default())?;
+            let (repo, _) = checkout.main_worktree(gix::progress::Discard, &AtomicBool::default())?;
+
+            let config = repo.config_snapshot();
+            assert_eq!(
+                config.string("extensions.partialClone").expect("set").as_ref(),
+                "origin"
+            );
+            assert_eq!(config.boolean("remote.origin.promisor"), Some(true));
+            assert_eq!(
+                config.string("remote.origin.partialCloneFilter").expect("set").as_ref(),
+                "blob:none"
+            );
+            assert_eq!(config.integer("core.repositoryFormatVersion"), Some(1));
+            let on_disk = gix::open_opts(repo.git_dir(), restricted())?;
+            assert_eq!(
+                on_disk.partial_clone_remote_name().expect("persisted").as_ref(),
+                "origin",
+                "the configuration is also written to disk"
+            );
+            assert!(
+                !repo.git_dir().join("config.lock").exists(),
+                "it's written through a lock that was committed"
+            );
+            assert_eq!(on_disk.partial_clone_filter("origin".into())?, Some(Filter::BlobNone));
+            assert!(on_disk.is_promisor_remote("origin".into())?);
+            assert!(
+                on_disk.objects.promisor.is_some(),
+                "it's set up when opening a partial clone"
+            );
+
+            let pack_dir = repo.objects.store_ref().path().join("pack");
+            let num_promisor_files = std::fs::read_dir(&pack_dir)?
+                .filter(|e| {
+                    e.as_ref()
+                        .map_or(false, |e| e.path().extension() == Some("promisor".as_ref()))
+                })
+                .count();
+            assert_eq!(
+                num_promisor_files, 2,
+                "the initial pack and the one with checked out blobs"
+            );
+
+            let work_dir = repo.work_dir().expect("non-bare");
+            assert_eq!(std::fs::read(work_dir.join("file"))?, b"second\n");
+            assert_eq!(std::fs::read(work_dir.join("dir").join("file"))?, b"nested\n");
+
+            let first_blob = repo.rev_parse_single("HEAD~1:file")?.detach();
+            assert!(
+                !repo.objects.exists(&first_blob),
+                "the blob of the previous commit wasn't fetched, and existence checks don't fetch it either"
+            );
+            assert_eq!(repo.find_object(first_blob)?.data, b"first\n", "it's fetched on demand");
+            assert!(repo.objects.exists(&first_blob), "and now available locally");
+            assert_eq!(
+                std::fs::read_dir(&pack_dir)?
+                    .filter(|e| e
+                        .as_ref()
+                        .map_or(false, |e| e.path().extension() == Some("keep".as_ref())))
+                    .count(),
+                0,
+                "no pack is left with a .keep file"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn partial_clone_with_tree_filter_fetches_trees_on_demand() -> crate::Result {
+        use gix_object::Exists;
+        let remote_repo = gix_testtools::scripted_fixture_read_only("make_partial_clone_remote.sh")?.join("base");
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, _out) = gix::clone::PrepareFetch::new(
+            remote_repo.as_path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .with_filter(gix::remote::fetch::Filter::TreeDepth(0))
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+
+        let head = repo.head_commit()?;
+        let tree_id = head.tree_id()?.detach();
+        assert!(!repo.objects.exists(&tree_id), "trees are omitted");
+
+        let tree = repo.find_tree(tree_id)?;
+        assert_eq!(tree.decode()?.entries.len(), 2, "the tree is fetched on demand");
+        Ok(())
+    }
+
     #[test]
     fn fetch_only_without_configuration() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
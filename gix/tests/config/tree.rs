@@ -145,6 +145,55 @@ mod ssh {
     }
 }
 
+#[cfg(feature = "gpg")]
+mod gpg {
+    use gix::config::tree::{gpg, Gpg, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn format() -> crate::Result {
+        for (actual, expected) in [
+            ("openpgp", gix::gpg::Format::OpenPgp),
+            ("x509", gix::gpg::Format::X509),
+            ("ssh", gix::gpg::Format::Ssh),
+        ] {
+            assert_eq!(Gpg::FORMAT.try_into_format(bcow(actual))?, expected);
+            assert!(Gpg::FORMAT.validate(actual.into()).is_ok());
+        }
+        assert_eq!(
+            Gpg::FORMAT.try_into_format(bcow("gpg")).unwrap_err().to_string(),
+            "The key \"gpg.format=gpg\" was invalid"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn min_trust_level() -> crate::Result {
+        for (actual, expected) in [
+            ("undefined", gix::gpg::Trust::Undefined),
+            ("never", gix::gpg::Trust::Never),
+            ("Marginal", gix::gpg::Trust::Marginal),
+            ("FULLY", gix::gpg::Trust::Fully),
+            ("ultimate", gix::gpg::Trust::Ultimate),
+        ] {
+            assert_eq!(Gpg::MIN_TRUST_LEVEL.try_into_trust(bcow(actual))?, expected);
+        }
+        assert!(Gpg::MIN_TRUST_LEVEL.validate("trusted".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn subsections() {
+        assert_eq!(gpg::OpenPgp::PROGRAM.logical_name(), "gpg.openpgp.program");
+        assert_eq!(gpg::X509::PROGRAM.logical_name(), "gpg.x509.program");
+        assert_eq!(
+            gpg::Ssh::ALLOWED_SIGNERS_FILE.logical_name(),
+            "gpg.ssh.allowedSignersFile"
+        );
+    }
+}
+
 #[cfg(feature = "status")]
 mod status {
     use crate::config::tree::bcow;
@@ -270,6 +319,32 @@ mod fetch {
 }
 
 #[cfg(feature = "blob-diff")]
+mod commit_graph {
+    use gix::config::tree::{CommitGraph, Key};
+
+    #[test]
+    fn changed_paths_version() -> crate::Result {
+        for (value, expected) in [(-1, None), (0, Some(0)), (1, Some(1)), (2, Some(2))] {
+            assert_eq!(
+                CommitGraph::CHANGED_PATHS_VERSION.try_into_changed_paths_version(Ok(value))?,
+                expected
+            );
+            assert!(CommitGraph::CHANGED_PATHS_VERSION
+                .validate(value.to_string().as_str().into())
+                .is_ok());
+        }
+        assert_eq!(
+            CommitGraph::CHANGED_PATHS_VERSION
+                .try_into_changed_paths_version(Ok(3))
+                .unwrap_err()
+                .to_string(),
+            "The value of key \"commitGraph.changedPathsVersion\" was invalid"
+        );
+        assert!(CommitGraph::CHANGED_PATHS_VERSION.validate("-2".into()).is_err());
+        Ok(())
+    }
+}
+
 mod diff {
     use gix::{
         config::tree::{Diff, Key},
@@ -365,6 +440,53 @@ mod diff {
     }
 }
 
+mod gc {
+    use gix::config::tree::{Gc, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn prune_expire() -> crate::Result {
+        let now = std::time::UNIX_EPOCH + std::time::Duration::from_secs(100 * 24 * 60 * 60);
+        for value in ["never", "false"] {
+            assert_eq!(Gc::PRUNE_EXPIRE.try_into_expiry(bcow(value), now)?, None);
+        }
+        for value in ["now", "all"] {
+            assert_eq!(
+                Gc::PRUNE_EXPIRE.try_into_expiry(bcow(value), now)?,
+                Some(gix::date::SecondsSinceUnixEpoch::MAX)
+            );
+        }
+        assert_eq!(
+            Gc::PRUNE_EXPIRE.try_into_expiry(bcow("2.weeks.ago"), now)?,
+            Some(86 * 24 * 60 * 60)
+        );
+        assert!(Gc::PRUNE_EXPIRE.validate("2.weeks.ago".into()).is_ok());
+        assert_eq!(
+            Gc::PRUNE_EXPIRE
+                .try_into_expiry(bcow("sometime"), now)
+                .unwrap_err()
+                .to_string(),
+            "The key \"gc.pruneExpire=sometime\" was invalid"
+        );
+        assert!(Gc::PRUNE_EXPIRE.validate("sometime".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reflog_expire() -> crate::Result {
+        for key in [&Gc::REFLOG_EXPIRE, &Gc::REFLOG_EXPIRE_UNREACHABLE] {
+            assert!(key.validate("90.days.ago".into()).is_ok());
+            assert!(key.full_name(None).is_ok());
+            assert!(
+                key.full_name(Some("refs/remotes/*".into())).is_ok(),
+                "per-pattern values are supported as well"
+            );
+        }
+        Ok(())
+    }
+}
+
 mod core {
     use std::time::Duration;
 
@@ -530,6 +652,31 @@ mod core {
         Ok(())
     }
 
+    #[test]
+    fn untracked_cache() -> crate::Result {
+        assert_eq!(Core::UNTRACKED_CACHE.try_into_untracked_cache(bcow("keep"))?, None);
+        assert_eq!(
+            Core::UNTRACKED_CACHE.try_into_untracked_cache(bcow("true"))?,
+            Some(true)
+        );
+        assert_eq!(
+            Core::UNTRACKED_CACHE.try_into_untracked_cache(bcow("false"))?,
+            Some(false)
+        );
+        assert_eq!(
+            Core::UNTRACKED_CACHE
+                .try_into_untracked_cache(bcow("sometimes"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"core.untrackedCache=sometimes\" was invalid"
+        );
+
+        assert!(Core::UNTRACKED_CACHE.validate("keep".into()).is_ok());
+        assert!(Core::UNTRACKED_CACHE.validate("yes".into()).is_ok());
+        assert!(Core::UNTRACKED_CACHE.validate("foo".into()).is_err());
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "attributes")]
     fn safecrlf() -> crate::Result {
@@ -1069,6 +1216,53 @@ mod remote {
         Ok(())
     }
 
+    #[test]
+    fn partial_clone_filter() -> crate::Result {
+        use remote::fetch::Filter;
+        for (spec, expected, normalized) in [
+            ("blob:none", Filter::BlobNone, "blob:none"),
+            ("blob:limit=0", Filter::BlobLimit(0), "blob:limit=0"),
+            ("blob:limit=2k", Filter::BlobLimit(2048), "blob:limit=2048"),
+            ("blob:limit=1M", Filter::BlobLimit(1024 * 1024), "blob:limit=1048576"),
+            ("tree:0", Filter::TreeDepth(0), "tree:0"),
+            (
+                "sparse:oid=main:sparse",
+                Filter::SparseOid("main:sparse".into()),
+                "sparse:oid=main:sparse",
+            ),
+        ] {
+            let filter = Remote::PARTIAL_CLONE_FILTER.try_into_filter(bcow(spec))?;
+            assert_eq!(filter, expected);
+            assert_eq!(
+                filter.to_string(),
+                normalized,
+                "it round-trips into a spec that git understands"
+            );
+            assert!(Remote::PARTIAL_CLONE_FILTER.validate(spec.into()).is_ok());
+        }
+
+        for invalid in [
+            "blob:limit=",
+            "blob:limit=x",
+            "tree:-1",
+            "sparse:oid=",
+            "combine:blob:none+tree:0",
+        ] {
+            assert!(
+                Remote::PARTIAL_CLONE_FILTER.validate(invalid.into()).is_err(),
+                "{invalid}"
+            );
+        }
+        assert_eq!(
+            Remote::PARTIAL_CLONE_FILTER
+                .try_into_filter(bcow("blob:some"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"remote.<name>.partialCloneFilter=blob:some\" was invalid"
+        );
+        Ok(())
+    }
+
     #[test]
     fn url_and_push_url() {
         assert!(Remote::URL.try_into_url(bcow("http://example.org")).is_ok());
@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config merge.ff false
+
+git checkout -q -b main
+mkdir -p dir/sub
+echo a > a && echo b > dir/b && echo c > dir/sub/c
+git add . && git commit -q -m c1
+echo a2 > a
+git commit -q -am c2
+
+git checkout -q -b branch
+echo b2 > dir/b
+git commit -q -am b1
+echo ü > "dir/sub/ümlaut" && echo é > "é"
+git add . && git commit -q -m b2
+
+git checkout -q main
+git commit -q --allow-empty -m c3
+git merge -q branch -m merge
+git rm -q dir/b && mkdir dir/b && echo e > dir/b/e
+git add . && git commit -q -m file-becomes-directory
+echo c2 > dir/sub/c
+git commit -q -am c4
+
+git commit-graph write --no-progress --reachable --changed-paths
+cp .git/objects/info/commit-graph commit-graph-written-by-git
+
+for path in a dir dir/b dir/sub dir/sub/c "é" does-not-exist; do
+  git log --first-parent --format=%H -- "$path" > "first-parent-log-of-${path//\//-}"
+done
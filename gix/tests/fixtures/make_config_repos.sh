@@ -175,3 +175,23 @@ git init ssl-no-verify-enabled
   git config http.sslVerify true
   git config gitoxide.http.sslNoVerify true
 )
+
+git init http-url-specific
+(cd http-url-specific
+  git config http.sslVerify false
+  git config http.lowSpeedLimit 1
+  git config http.proxy http://general:9090
+  git config http.extraHeader "General: value"
+
+  git config http.https://a.example.com.proxy http://exact-same-length:9090
+  git config http.https://*.example.com.proxy http://wildcard:9090
+  git config http.https://*.example.com.lowSpeedLimit 2
+  git config http.https://git.example.com.proxy http://exact-host:9090
+  git config http.https://git.example.com/org/repo.lowSpeedLimit 3
+  git config http.https://git.example.com/org.lowSpeedLimit 4
+  git config http.https://git.example.com/org.extraHeader "Org: value"
+  git config http.https://user@git.example.com.followRedirects false
+  git config http.https://git.example.com:8443.sslVerify true
+  git config http.http://git.example.com.userAgent insecure-agent
+  git config http.https://git.example.com/org/rep.userAgent not-a-path-component-prefix
+)
@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config gc.auto 0
+
+echo c1 >file
+git add file
+git commit -q -m c1
+git tag -a -m "annotated" v1
+echo c2 >file
+git commit -q -am c2
+echo staged >staged
+git add staged
+
+# An unreachable commit with a tree and blob that are only referenced by it, so only the commit is dangling.
+blob=$(echo unreachable | git hash-object -w --stdin)
+tree=$(printf '100644 blob %s\tunreachable\n' "$blob" | git mktree)
+git commit-tree -m "dangling" "$tree" >/dev/null
+
+echo dangling | git hash-object -w --stdin >/dev/null
+
+# A dangling commit with an invalid author email, written without validation.
+printf 'tree %s\nauthor A U Thor <author@example.com 946684800 +0000\ncommitter C O Mitter <committer@example.com> 946771200 +0000\n\nbad\n' \
+  "$(git rev-parse HEAD^{tree})" | git hash-object -t commit --literally -w --stdin >/dev/null
+
+# A dangling tree with a .gitmodules file that tries to escape the modules directory and to inject options.
+modules=$(printf '[submodule "../../escape"]\n\tpath = sub\n\turl = --upload-pack=touch\n' | git hash-object -w --stdin)
+printf '100644 blob %s\t.gitmodules\n' "$modules" | git mktree >/dev/null
@@ -0,0 +1,18 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  git config uploadpack.allowFilter true
+  git config uploadpack.allowAnySHA1InWant true
+
+  mkdir dir
+  echo "first" > file
+  echo "nested" > dir/file
+  git add . && git commit -q -m "c1"
+
+  echo "second" > file
+  git commit -q -am "c2"
+)
+
+git clone -q --filter=blob:none "file://$PWD/base" partial
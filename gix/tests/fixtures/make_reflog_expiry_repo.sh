@@ -0,0 +1,18 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function commit_at() {
+  GIT_COMMITTER_DATE="$1" git commit -q --allow-empty -m "$2"
+}
+
+git init -q
+git config gc.auto 0
+git config "gc.refs/heads/keep*.reflogExpire" never
+
+commit_at "2000-01-01 00:00:00 +0000" c1
+commit_at "2000-02-01 00:00:00 +0000" c2
+commit_at "2000-03-01 00:00:00 +0000" unreachable
+GIT_COMMITTER_DATE="2000-03-02 00:00:00 +0000" git reset -q --hard HEAD~1
+commit_at "2000-04-01 00:00:00 +0000" c3
+
+GIT_COMMITTER_DATE="2000-01-05 00:00:00 +0000" git branch keep-me HEAD~1
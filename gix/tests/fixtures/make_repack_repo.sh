@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config gc.auto 0
+
+for n in 1 2 3; do
+  echo $n > f$n
+  git add f$n
+  git commit -q -m "c$n"
+done
+git repack -q -d
+
+echo 4 > f4
+git add f4
+git commit -q -m c4
+git repack -q -d
+
+echo 5 > f5
+git add f5
+git commit -q -m c5
+
+echo reflog-only > r
+git add r
+git commit -q -m reflog-only
+git reset -q --hard HEAD~1
+
+echo staged > s
+git add s
+
+echo unreachable | git hash-object -w --stdin >/dev/null
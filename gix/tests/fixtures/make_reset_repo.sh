@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  mkdir dir
+  echo a > a
+  echo b > b
+  echo c > dir/c
+  printf 'crlf\n' > crlf
+  echo "crlf text eol=crlf" > .gitattributes
+  git add .
+  git commit -q -m c1
+
+  echo a2 > a
+  git rm -q b
+  echo d > dir/d
+  echo crlf2 > crlf
+  git add .
+  git commit -q -m c2
+)
+
+git init -q conflict
+(cd conflict
+  echo base > file
+  echo unrelated > unrelated
+  git add .
+  git commit -q -m base
+
+  git checkout -q -b other
+  echo other > file
+  git commit -q -am other
+
+  git checkout -q main
+  echo main > file
+  git commit -q -am main
+
+  git merge other || true
+)
+git clone -q --bare repo bare.git
@@ -405,6 +405,9 @@ EOF
   baseline main@{push}
   baseline main@{upstream}
   baseline @{upstream}
+  baseline "@{-2}@{upstream}"
+  baseline "@{-2}@{push}"
+  baseline "@{-1}@{upstream}"
 
   baseline "^"
   baseline "^!"
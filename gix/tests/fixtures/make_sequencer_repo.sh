@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+printf '1\n2\n3\n' > shared
+git add .
+git commit -q -m base
+
+git checkout -q -b topic
+echo a > a
+git add a
+git commit -q -m "add a"
+printf 'one\n2\n3\n' > shared
+git commit -q -am "change shared"
+echo b > b
+git add b
+git commit -q -m "add b" -m "Signed-off-by: author <author@example.com>"
+
+git checkout -q -b side main
+echo c > c
+git add c
+git commit -q -m "add c"
+git checkout -q -b with-merge main
+git merge -q --no-ff -m "merge side" side
+
+git checkout -q main
+printf 'uno\n2\n3\n' > shared
+git commit -q -am "conflicting change"
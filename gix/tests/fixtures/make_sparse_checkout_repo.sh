@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir -p a/b d/e
+echo root > root
+echo a > a/file
+echo b > a/b/file
+echo d > d/file
+echo e > d/e/file
+git add .
+git commit -q -m "init"
@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+ssh-keygen -q -t ed25519 -N "" -C signer -f signing-key
+ssh-keygen -q -t ed25519 -N "" -C unknown -f unknown-key
+echo "signer@example.com namespaces=\"git\" $(cat signing-key.pub)" > allowed_signers
+
+git init -q repo
+(cd repo
+  git config gpg.format ssh
+  git config user.signingKey ../signing-key
+
+  git commit -q --allow-empty -m unsigned
+  git tag -a -m "unsigned tag" unsigned-tag
+  git commit -q --allow-empty -S -m signed
+  git tag -s -m "signed tag" signed-tag
+  git -c user.signingKey=../unknown-key commit -q --allow-empty -S -m "unknown signer"
+
+  git cat-file commit HEAD~1 | sed 's/^signed$/tampered/' | git hash-object -w -t commit --stdin > ../tampered-commit-id
+  git cat-file tag signed-tag | sed 's/^signed tag$/tampered tag/' | git hash-object -w -t tag --stdin > ../tampered-tag-id
+)
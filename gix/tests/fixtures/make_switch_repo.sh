@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+mkdir dir
+echo a > a
+echo b > b
+echo c > dir/c
+printf '1\n2\n3\n4\n5\n' > shared
+printf 'crlf\n' > crlf
+echo "crlf text eol=crlf" > .gitattributes
+git add .
+git commit -q -m c1
+
+git checkout -q -b other
+echo a-other > a
+git rm -q b
+echo d > dir/d
+printf '1\n2\n3\n4\nfive\n' > shared
+echo crlf-other > crlf
+git add .
+git commit -q -m other
+
+git checkout -q -b conflicting main
+printf 'one\n2\n3\n4\n5\n' > shared
+git commit -q -am conflicting
+
+git checkout -q main
@@ -154,6 +154,37 @@ mod ancestors {
         Ok(())
     }
 
+    #[test]
+    fn with_changed_paths_is_like_git_log_first_parent() -> crate::Result {
+        let repo = crate::named_repo("make_changed_paths_repo.sh")?;
+        let head = repo.head()?.into_peeled_id()?;
+        assert!(
+            repo.commit_graph()?.bloom_filter_settings().is_some(),
+            "changed-path Bloom filters are used if present"
+        );
+
+        for path in ["a", "dir", "dir/b", "dir/sub", "dir/sub/c", "é", "does-not-exist"] {
+            let log = std::fs::read_to_string(
+                repo.work_dir()
+                    .expect("non-bare")
+                    .join(format!("first-parent-log-of-{}", path.replace('/', "-"))),
+            )?;
+            let expected: Vec<_> = log.lines().map(hex_to_id).collect();
+            for use_commit_graph in [false, true] {
+                let actual = head
+                    .ancestors()
+                    .first_parent_only()
+                    .use_commit_graph(use_commit_graph)
+                    .with_changed_paths(Some(path))
+                    .all()?
+                    .map(|c| c.map(|c| c.id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(actual, expected, "{path}");
+            }
+        }
+        Ok(())
+    }
+
     #[test]
     fn filtered() -> crate::Result {
         let repo = crate::repo("make_repo_with_fork_and_dates.sh")?.to_thread_local();
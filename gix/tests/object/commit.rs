@@ -43,3 +43,97 @@ fn decode() -> crate::Result {
     assert_eq!(commit.decode()?.message, "c2\n");
     Ok(())
 }
+
+#[cfg(feature = "gpg")]
+pub(crate) mod verify_signature {
+    use gix::{
+        config::tree::gpg,
+        gpg::{Format, Status, Trust},
+    };
+
+    /// Return a repository with SSH-signed commits and tags whose allowed signers file is configured, along with the fixture directory.
+    pub(crate) fn ssh_signed_repo() -> crate::Result<(gix::Repository, std::path::PathBuf)> {
+        let dir = gix_testtools::scripted_fixture_read_only("make_ssh_signed_repo.sh")?;
+        let mut repo = gix::open_opts(dir.join("repo"), crate::restricted())?;
+        repo.config_snapshot_mut().set_value(
+            &gpg::Ssh::ALLOWED_SIGNERS_FILE,
+            gix::path::into_bstr(dir.join("allowed_signers")).as_ref(),
+        )?;
+        Ok((repo, dir))
+    }
+
+    #[test]
+    fn ssh() -> crate::Result {
+        let (repo, dir) = ssh_signed_repo()?;
+        let min_trust = repo.signature_min_trust_level()?;
+        assert_eq!(min_trust, Trust::Undefined, "the default");
+
+        let verification = repo
+            .rev_parse_single("HEAD~1")?
+            .object()?
+            .into_commit()
+            .verify_signature()?
+            .expect("signed");
+        assert_eq!(verification.format, Format::Ssh);
+        assert_eq!(verification.status, Status::Good);
+        assert_eq!(verification.trust, Trust::Fully);
+        assert_eq!(
+            verification.signer.as_ref().map(AsRef::as_ref),
+            Some("signer@example.com".as_bytes())
+        );
+        assert!(verification.is_good(min_trust));
+
+        let verification = repo.head_commit()?.verify_signature()?.expect("signed");
+        assert_eq!(
+            verification.status,
+            Status::UnknownValidity,
+            "the key isn't in the allowed signers file"
+        );
+        assert_eq!(verification.signer, None);
+        assert!(!verification.is_good(min_trust));
+
+        let tampered_id = gix::ObjectId::from_hex(
+            std::fs::read_to_string(dir.join("tampered-commit-id"))?
+                .trim()
+                .as_bytes(),
+        )?;
+        let verification = repo.find_commit(tampered_id)?.verify_signature()?.expect("signed");
+        assert_eq!(verification.status, Status::Bad);
+        assert!(!verification.is_good(min_trust));
+
+        let unsigned = repo.rev_parse_single("HEAD~2")?.object()?.into_commit();
+        assert!(unsigned.verify_signature()?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn ssh_without_allowed_signers_file_is_an_error() -> crate::Result {
+        let repo = crate::named_subrepo_opts("make_ssh_signed_repo.sh", "repo", crate::restricted())?;
+        let err = repo
+            .head_commit()?
+            .verify_signature()
+            .expect_err("the allowed signers file is required");
+        assert_eq!(
+            err.to_string(),
+            "gpg.ssh.allowedSignersFile needs to be configured for SSH signature verification"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn min_trust_level() -> crate::Result {
+        let (mut repo, _dir) = ssh_signed_repo()?;
+        repo.config_snapshot_mut()
+            .set_value(&gix::config::tree::Gpg::MIN_TRUST_LEVEL, "Ultimate")?;
+        let min_trust = repo.signature_min_trust_level()?;
+        assert_eq!(min_trust, Trust::Ultimate);
+        let verification = repo
+            .rev_parse_single("HEAD~1")?
+            .object()?
+            .into_commit()
+            .verify_signature()?
+            .expect("signed");
+        assert!(!verification.is_good(min_trust), "SSH keys are fully trusted at most");
+        Ok(())
+    }
+}
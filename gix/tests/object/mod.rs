@@ -1,5 +1,6 @@
 mod blob;
 mod commit;
+mod tag;
 mod tree;
 
 #[test]
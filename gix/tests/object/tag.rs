@@ -0,0 +1,32 @@
+#[cfg(feature = "gpg")]
+#[test]
+fn verify_signature() -> crate::Result {
+    use gix::gpg::{Format, Status};
+
+    let (repo, dir) = crate::object::commit::verify_signature::ssh_signed_repo()?;
+    let verification = repo
+        .find_reference("signed-tag")?
+        .id()
+        .object()?
+        .into_tag()
+        .verify_signature()?
+        .expect("signed");
+    assert_eq!(verification.format, Format::Ssh);
+    assert_eq!(verification.status, Status::Good);
+    assert_eq!(
+        verification.signer.as_ref().map(AsRef::as_ref),
+        Some("signer@example.com".as_bytes())
+    );
+
+    let tampered_id = gix::ObjectId::from_hex(std::fs::read_to_string(dir.join("tampered-tag-id"))?.trim().as_bytes())?;
+    let verification = repo
+        .find_object(tampered_id)?
+        .into_tag()
+        .verify_signature()?
+        .expect("signed");
+    assert_eq!(verification.status, Status::Bad);
+
+    let unsigned = repo.find_reference("unsigned-tag")?.id().object()?.into_tag();
+    assert!(unsigned.verify_signature()?.is_none());
+    Ok(())
+}
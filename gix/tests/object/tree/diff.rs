@@ -83,6 +83,29 @@ fn changes_against_tree_modified() -> crate::Result {
         "two files with one added line each"
     );
 
+    assert_eq!(
+        from.changes()?.file_stats(&to)?,
+        [
+            gix_diff::stat::File {
+                location: "a".into(),
+                source_location: None,
+                counts: gix_diff::stat::Counts::Lines {
+                    insertions: 1,
+                    removals: 0
+                },
+            },
+            gix_diff::stat::File {
+                location: "dir/c".into(),
+                source_location: None,
+                counts: gix_diff::stat::Counts::Lines {
+                    insertions: 1,
+                    removals: 0
+                },
+            }
+        ],
+        "trees are skipped, and paths are always tracked"
+    );
+
     Ok(())
 }
 
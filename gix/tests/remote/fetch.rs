@@ -112,6 +112,42 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_unshallow_of_existing_shallow_clone() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable_with_args_single_archive(
+            "make_complex_shallow_repo.sh",
+            Some(base_repo_path()),
+            gix_testtools::Creation::ExecuteScript,
+        )?;
+        for name in ["shallow", "shallow.git"] {
+            let repo = gix::open_opts(tmp.path().join(name), crate::restricted())?;
+            assert!(repo.is_shallow(), "{name}: git created a shallow clone");
+            let shallow_commit_count = repo.head_id()?.ancestors().all()?.count();
+
+            let mut remote = repo.find_default_remote(Fetch).expect("present")?;
+            // Bare clones have no fetch refspecs.
+            remote.replace_refspecs(Some("+refs/heads/*:refs/remotes/origin/*"), Fetch)?;
+            remote
+                .connect(Fetch)?
+                .prepare_fetch(gix::progress::Discard, Default::default())?
+                .with_shallow(fetch::Shallow::undo())
+                .receive(gix::progress::Discard, &AtomicBool::default())?;
+
+            assert!(
+                !repo.shallow_file().exists(),
+                "{name}: the shallow file is removed once all history is present"
+            );
+            assert!(!repo.is_shallow(), "{name}");
+            assert!(repo.shallow_commits()?.is_none(), "{name}");
+            assert!(
+                repo.head_id()?.ancestors().all()?.count() > shallow_commit_count,
+                "{name}: the rest of the history was fetched"
+            );
+        }
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "blocking-network-client")]
     fn fetch_with_alternates_adds_tips_from_alternates() -> crate::Result<()> {
@@ -0,0 +1,119 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::apply::{plumbing, Location};
+
+const PATCH: &str = "diff --git a/this b/this
+index ce01362..cc628cc 100644
+--- a/this
++++ b/this
+@@ -1 +1 @@
+-hello
++world
+diff --git a/new b/new
+new file mode 100755
+index 0000000..257cc56
+--- /dev/null
++++ b/new
+@@ -0,0 +1 @@
++foo
+";
+
+fn patches() -> Vec<plumbing::File> {
+    plumbing::parse(PATCH.as_bytes(), Default::default()).expect("valid patch")
+}
+
+#[test]
+fn worktree_and_index() -> crate::Result {
+    let (repo, _tmp) = crate::util::basic_rw_repo()?;
+    let outcome = repo.apply(
+        &patches(),
+        repo.apply_options(Location::WorktreeAndIndex)?,
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(outcome.files.len(), 2);
+    assert!(!outcome.has_conflicts());
+    assert_eq!(outcome.tree, None);
+
+    let workdir = repo.work_dir().expect("non-bare");
+    assert_eq!(std::fs::read(workdir.join("this"))?, b"world\n");
+    assert_eq!(std::fs::read(workdir.join("new"))?, b"foo\n");
+
+    let index = repo.open_index()?;
+    let entry = index.entry_by_path("this".into()).expect("present");
+    assert_eq!(entry.id, repo.write_blob("world\n")?.detach());
+    assert_ne!(entry.stat, Default::default(), "stats are taken from the written file");
+    let entry = index.entry_by_path("new".into()).expect("present");
+    assert_eq!(entry.mode, gix::index::entry::Mode::FILE_EXECUTABLE);
+
+    let err = repo
+        .apply(
+            &patches()[1..],
+            repo.apply_options(Location::WorktreeAndIndex)?,
+            &AtomicBool::default(),
+        )
+        .unwrap_err();
+    assert!(
+        matches!(err, gix::apply::Error::AlreadyExists { .. }),
+        "files can't be created twice"
+    );
+    Ok(())
+}
+
+#[test]
+fn index_only() -> crate::Result {
+    let (repo, _tmp) = crate::util::basic_rw_repo()?;
+    repo.apply(&patches(), repo.apply_options(Location::Index)?, &AtomicBool::default())?;
+
+    let workdir = repo.work_dir().expect("non-bare");
+    assert_eq!(
+        std::fs::read(workdir.join("this"))?,
+        b"hello\n",
+        "worktree is untouched"
+    );
+    assert!(!workdir.join("new").exists());
+    let index = repo.open_index()?;
+    assert_eq!(
+        index.entry_by_path("this".into()).expect("present").id,
+        repo.write_blob("world\n")?.detach()
+    );
+    assert!(index.entry_by_path("new".into()).is_some());
+    Ok(())
+}
+
+#[test]
+fn tree() -> crate::Result {
+    let repo = crate::named_repo("make_basic_repo.sh")?;
+    let head_tree = repo.head_tree_id()?.detach();
+    let outcome = repo.apply(
+        &patches(),
+        repo.apply_options(Location::Tree(head_tree))?,
+        &AtomicBool::default(),
+    )?;
+    let tree = repo.find_tree(outcome.tree.expect("tree was written"))?;
+    let entry = tree.find_entry("this").expect("present");
+    assert_eq!(entry.object_id(), repo.write_blob("world\n")?.detach());
+    let entry = tree.find_entry("new").expect("present");
+    assert!(entry.mode().is_executable());
+    Ok(())
+}
+
+#[test]
+fn three_way_with_conflicts() -> crate::Result {
+    let (repo, _tmp) = crate::util::basic_rw_repo()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("this"), "hello\nmore\n")?;
+
+    let patches = &patches()[..1];
+    let mut options = repo.apply_options(Location::Worktree)?;
+    let err = repo.apply(patches, options, &AtomicBool::default()).unwrap_err();
+    assert!(matches!(err, gix::apply::Error::Hunks { .. }));
+
+    options.three_way = true;
+    let outcome = repo.apply(patches, options, &AtomicBool::default())?;
+    assert!(outcome.has_conflicts());
+    assert_eq!(
+        std::fs::read(workdir.join("this"))?.as_slice(),
+        b"<<<<<<< ours\nhello\nmore\n=======\nworld\n>>>>>>> theirs\n".as_slice()
+    );
+    Ok(())
+}
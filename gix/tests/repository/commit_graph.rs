@@ -0,0 +1,102 @@
+use gix::commitgraph::{write::Split, Position};
+
+use crate::util::repo_rw;
+
+fn reachable_commits(repo: &gix::Repository) -> crate::Result<Vec<gix::ObjectId>> {
+    let references = repo.references()?;
+    let tips = references
+        .all()?
+        .filter_map(Result::ok)
+        .filter_map(|mut r| r.peel_to_id_in_place().ok().map(gix::Id::detach));
+    Ok(repo
+        .rev_walk(tips)
+        .all()?
+        .map(|info| info.map(|info| info.id))
+        .collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn write_all_reachable_commits_into_a_single_file() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repo_with_fork_and_dates.sh")?;
+    let commits = reachable_commits(&repo)?;
+    let outcome = repo.write_commit_graph(commits.iter().copied(), None, None)?;
+    assert_eq!(outcome.checksums.len(), 1);
+    assert_eq!(outcome.num_commits as usize, commits.len());
+
+    let graph = repo.commit_graph()?;
+    graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    for id in &commits {
+        let commit = graph.commit_by_id(id).expect("all commits are present");
+        assert!(
+            commit.corrected_commit_date().is_some(),
+            "generation data is written by default"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn write_new_commits_into_split_chain() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_repo_with_fork_and_dates.sh")?;
+    let num_commits_before = repo.commit_graph()?.num_commits();
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::CommitGraph::GENERATION_VERSION, "1")?;
+
+    let commits = reachable_commits(&repo)?;
+    let split = Split {
+        strategy: gix::commitgraph::write::SplitStrategy::NoMerge,
+        ..Default::default()
+    };
+    let outcome = repo.write_commit_graph(commits.iter().copied(), Some(split), None)?;
+    assert_eq!(outcome.checksums.len(), 2, "the previous graph became the base layer");
+    assert_eq!(outcome.num_written_commits, outcome.num_commits - num_commits_before);
+    assert_eq!(outcome.num_commits as usize, commits.len());
+
+    let graph = repo.commit_graph()?;
+    graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    for pos in num_commits_before..graph.num_commits() {
+        assert_eq!(
+            graph.commit_at(Position(pos)).corrected_commit_date(),
+            None,
+            "with generation version 1, no generation data is written"
+        );
+    }
+
+    let outcome = repo.write_commit_graph(commits, Some(split), None)?;
+    assert_eq!(outcome.num_written_commits, 0, "nothing to do");
+    Ok(())
+}
+
+#[test]
+fn write_changed_path_bloom_filters_like_git() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_changed_paths_repo.sh")?;
+    let info_dir = repo.objects.store_ref().path().join("info");
+    std::fs::remove_file(info_dir.join("commit-graph"))?;
+    let commits = reachable_commits(&repo)?;
+
+    repo.write_commit_graph(commits.iter().copied(), None, None)?;
+    assert_eq!(
+        repo.commit_graph()?.bloom_filter_settings(),
+        None,
+        "filters are only written on request if there were none before"
+    );
+
+    repo.write_commit_graph(commits.iter().copied(), None, Some(true))?;
+    let expected = std::fs::read(repo.work_dir().expect("non-bare").join("commit-graph-written-by-git"))?;
+    assert_eq!(
+        std::fs::read(info_dir.join("commit-graph"))?,
+        expected,
+        "filters of typechanges, merges and non-ASCII paths are the same as the ones written by `git commit-graph write --changed-paths`"
+    );
+
+    repo.write_commit_graph(commits.iter().copied(), None, None)?;
+    assert_eq!(
+        std::fs::read(info_dir.join("commit-graph"))?,
+        expected,
+        "existing filters are reused and written again by default"
+    );
+
+    repo.write_commit_graph(commits, None, Some(false))?;
+    assert_eq!(repo.commit_graph()?.bloom_filter_settings(), None);
+    Ok(())
+}
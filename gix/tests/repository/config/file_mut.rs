@@ -0,0 +1,164 @@
+use gix::config::{
+    tree::{Core, Extensions, Remote},
+    Scope,
+};
+
+const CONFIG: &str = r#"# a comment that is kept
+[core]
+	repositoryformatversion = 0
+	bare = false
+	abbrev = 8 ; which is long enough
+[remote "origin"]
+	url = https://example.com/repo
+	fetch = +refs/heads/*:refs/remotes/origin/*
+[old]
+	value = 1
+[gone]
+	value = 2
+"#;
+
+fn repo_with_config(opts: gix::open::Options) -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let (repo, tmp) = crate::repo_rw("make_basic_repo.sh")?;
+    std::fs::write(repo.common_dir().join("config"), CONFIG)?;
+    Ok((gix::open_opts(repo.git_dir(), opts)?, tmp))
+}
+
+#[test]
+fn edits_are_written_losslessly_and_applied() -> crate::Result {
+    let (mut repo, _tmp) = repo_with_config(crate::restricted())?;
+    let mut file = repo.config_file_mut(Scope::Local)?;
+    assert_eq!(file.set_value(&Core::ABBREV, "5")?, Some("8".into()));
+    assert_eq!(
+        file.set_subsection_value(&Remote::URL, "upstream", "https://example.com/upstream")?,
+        None
+    );
+    assert_eq!(
+        file.remove_subsection_value(&Remote::FETCH, "origin")?,
+        Some("+refs/heads/*:refs/remotes/origin/*".into())
+    );
+    assert_eq!(
+        file.remove_value(&Core::IGNORE_CASE)?,
+        None,
+        "removing missing values is fine"
+    );
+    assert!(matches!(
+        file.remove_value(&Remote::URL),
+        Err(gix::config::remove_value::Error::SubSectionRequired)
+    ));
+    file.rename_section("old", None, "new", None)?;
+    assert!(file.remove_section("gone", None).is_some());
+    let repo = file.commit()?;
+
+    assert_eq!(
+        std::fs::read_to_string(repo.common_dir().join("config"))?,
+        r#"# a comment that is kept
+[core]
+	repositoryformatversion = 0
+	bare = false
+	abbrev = 5 ; which is long enough
+[remote "origin"]
+	url = https://example.com/repo
+[new]
+	value = 1
+[remote "upstream"]
+	url = https://example.com/upstream
+"#
+    );
+    assert!(!repo.common_dir().join("config.lock").exists(), "the lock is released");
+
+    let config = repo.config_snapshot();
+    assert_eq!(config.integer("core.abbrev"), Some(5));
+    assert_eq!(
+        config.string("remote.upstream.url").as_deref(),
+        Some("https://example.com/upstream".into())
+    );
+    assert_eq!(config.string("remote.origin.fetch"), None);
+    assert_eq!(config.integer("new.value"), Some(1));
+    assert_eq!(config.integer("old.value"), None);
+    assert_eq!(config.integer("gone.value"), None);
+    assert_eq!(
+        repo.head_id()?.shorten()?.to_string().len(),
+        5,
+        "cached values are read again"
+    );
+    Ok(())
+}
+
+#[test]
+fn changes_are_discarded_on_drop_and_not_written_if_invalid() -> crate::Result {
+    let (mut repo, _tmp) = repo_with_config(crate::restricted().strict_config(true))?;
+    let path = repo.common_dir().join("config");
+    {
+        let mut file = repo.config_file_mut(Scope::Local)?;
+        file.set_value(&Core::ABBREV, "5")?;
+        assert!(
+            path.with_file_name("config.lock").is_file(),
+            "the file is locked while it's edited"
+        );
+    }
+    assert_eq!(std::fs::read_to_string(&path)?, CONFIG);
+    assert_eq!(repo.config_snapshot().integer("core.abbrev"), Some(8));
+
+    let mut file = repo.config_file_mut(Scope::Local)?;
+    file.set_raw_value(&Core::ABBREV, "invalid")?;
+    assert!(matches!(
+        file.commit(),
+        Err(gix::config::file_mut::commit::Error::Apply(_))
+    ));
+    assert_eq!(std::fs::read_to_string(&path)?, CONFIG);
+    assert_eq!(repo.config_snapshot().integer("core.abbrev"), Some(8));
+    Ok(())
+}
+
+#[test]
+fn worktree_configuration_requires_the_extension_and_overrides_the_local_one() -> crate::Result {
+    let (mut repo, _tmp) = repo_with_config(crate::restricted())?;
+    assert!(matches!(
+        repo.config_file_mut(Scope::Worktree),
+        Err(gix::config::file_mut::Error::WorktreeConfigDisabled)
+    ));
+
+    let mut file = repo.config_file_mut(Scope::Local)?;
+    file.set_value(&Extensions::WORKTREE_CONFIG, "true")?;
+    let repo = file.commit()?;
+
+    let mut file = repo.config_file_mut(Scope::Worktree)?;
+    file.set_value(&Core::ABBREV, "6")?;
+    let repo = file.commit()?;
+    assert_eq!(
+        std::fs::read_to_string(repo.git_dir().join("config.worktree"))?,
+        "[core]\n\tabbrev = 6\n",
+        "the file is created"
+    );
+    assert_eq!(repo.config_snapshot().integer("core.abbrev"), Some(6));
+
+    let mut file = repo.config_file_mut(Scope::Local)?;
+    file.set_value(&Core::ABBREV, "7")?;
+    let repo = file.commit()?;
+    assert_eq!(
+        repo.config_snapshot().integer("core.abbrev"),
+        Some(6),
+        "the worktree configuration still has precedence"
+    );
+    Ok(())
+}
+
+#[test]
+#[serial_test::serial]
+fn global_configuration_is_written_to_its_configured_location() -> crate::Result {
+    let (mut repo, tmp) = repo_with_config(crate::restricted_and_git())?;
+    let global = tmp.path().join("global-config");
+    let _env = gix_testtools::Env::new().set("GIT_CONFIG_GLOBAL", global.to_str().expect("valid UTF-8"));
+
+    let mut file = repo.config_file_mut(Scope::Global)?;
+    assert_eq!(file.meta().path.as_deref(), Some(global.as_path()));
+    file.set_value(&Core::ABBREV, "9")?;
+    let repo = file.commit()?;
+    assert_eq!(std::fs::read_to_string(&global)?, "[core]\n\tabbrev = 9\n");
+    assert_eq!(
+        repo.config_snapshot().integer("core.abbrev"),
+        Some(8),
+        "global configuration isn't loaded by this instance, so it's not applied either"
+    );
+    Ok(())
+}
@@ -1,4 +1,5 @@
 mod config_snapshot;
+mod file_mut;
 mod identity;
 mod remote;
 
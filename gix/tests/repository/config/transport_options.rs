@@ -336,4 +336,77 @@ mod http {
             "even with `http.sslVerify` enabled, `gitoxide.http.sslNoVerify` takes precedence`"
         );
     }
+
+    #[test]
+    fn url_specific_configuration() {
+        let repo = repo("http-url-specific");
+
+        let opts = http_options(&repo, None, "https://example.org/repo");
+        assert_eq!(opts.proxy.as_deref(), Some("http://general:9090"), "nothing matches");
+        assert_eq!(opts.low_speed_limit_bytes_per_second, 1);
+        assert_eq!(opts.extra_headers, &["General: value"]);
+        assert_eq!(opts.follow_redirects, FollowRedirects::Initial);
+        assert!(!opts.ssl_verify);
+
+        let opts = http_options(&repo, None, "https://other.example.com/repo");
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://wildcard:9090"),
+            "wildcards match a single host component"
+        );
+        assert_eq!(opts.low_speed_limit_bytes_per_second, 2);
+
+        let opts = http_options(&repo, None, "https://a.example.com/repo");
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://exact-same-length:9090"),
+            "exact hosts win over wildcards of the same length, even if these come later"
+        );
+
+        let opts = http_options(&repo, None, "https://git.example.com/other");
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://exact-host:9090"),
+            "exact hosts are more specific than wildcards"
+        );
+        assert_eq!(opts.low_speed_limit_bytes_per_second, 2);
+        assert!(!opts.ssl_verify, "the port has to match as well");
+        assert_eq!(opts.follow_redirects, FollowRedirects::Initial, "the user has to match");
+
+        let opts = http_options(&repo, None, "https://git.example.com/org/repo.git");
+        assert_eq!(
+            opts.low_speed_limit_bytes_per_second, 4,
+            "the path has to be a prefix ending at a path component"
+        );
+        assert_eq!(
+            opts.extra_headers,
+            &["General: value", "Org: value"],
+            "values of multi-value keys accumulate unless less specific ones come later"
+        );
+
+        let opts = http_options(&repo, None, "https://git.example.com/org/repo");
+        assert_eq!(
+            opts.low_speed_limit_bytes_per_second, 3,
+            "the longer path is more specific, so the later value of a less specific section is ignored"
+        );
+        assert_ne!(
+            opts.user_agent.as_deref(),
+            Some("not-a-path-component-prefix"),
+            "the path has to end at a path component"
+        );
+
+        let opts = http_options(&repo, None, "https://user@git.example.com/org/repo");
+        assert_eq!(opts.follow_redirects, FollowRedirects::None, "the user matches");
+
+        let opts = http_options(&repo, None, "https://git.example.com:8443/repo");
+        assert!(opts.ssl_verify, "the port matches");
+        assert_eq!(opts.proxy.as_deref(), Some("http://general:9090"));
+
+        let opts = http_options(&repo, None, "http://git.example.com/org/repo");
+        assert_eq!(
+            opts.user_agent.as_deref(),
+            Some("insecure-agent"),
+            "the scheme has to match"
+        );
+    }
 }
@@ -0,0 +1,213 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use crate::util::repo_rw;
+use gix::{
+    fsck::{message::Id, message::Severity, Error, Options, Outcome},
+    objs::Kind,
+    ObjectId,
+};
+
+fn fsck(repo: &gix::Repository, options: Options) -> Result<Outcome, Error> {
+    repo.fsck(options, &mut gix::progress::Discard, &AtomicBool::default())
+}
+
+fn problems(out: &Outcome) -> Vec<(Kind, Id, Severity)> {
+    let mut problems: Vec<_> = out
+        .problems
+        .iter()
+        .map(|problem| (problem.kind, problem.problem.id, problem.severity))
+        .collect();
+    problems.sort_by_key(|(kind, id, _)| (*kind, *id));
+    problems
+}
+
+fn kinds(objects: &[(ObjectId, Kind)]) -> Vec<Kind> {
+    let mut kinds: Vec<_> = objects.iter().map(|(_, kind)| *kind).collect();
+    kinds.sort();
+    kinds
+}
+
+fn loose_path(repo: &gix::Repository, id: ObjectId) -> PathBuf {
+    let hex = id.to_string();
+    repo.objects.store_ref().path().join(&hex[..2]).join(&hex[2..])
+}
+
+#[test]
+fn problems_and_dangling_objects() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_fsck_repo.sh")?;
+    let out = fsck(&repo, Options::default())?;
+    assert_eq!(out.checked_objects, 15);
+    assert_eq!(
+        problems(&out),
+        [
+            (Kind::Blob, Id::GitmodulesName, Severity::Error),
+            (Kind::Blob, Id::GitmodulesUrl, Severity::Error),
+            (Kind::Commit, Id::BadEmail, Severity::Error),
+        ],
+        "object syntax is validated, even for unreachable objects"
+    );
+    assert!(out.corrupt.is_empty());
+    assert!(out.missing.is_empty());
+    assert!(out.broken_links.is_empty());
+    assert_eq!(
+        kinds(&out.unreachable),
+        [
+            Kind::Tree,
+            Kind::Tree,
+            Kind::Blob,
+            Kind::Blob,
+            Kind::Blob,
+            Kind::Commit,
+            Kind::Commit
+        ]
+    );
+    assert_eq!(
+        kinds(&out.dangling),
+        [Kind::Tree, Kind::Blob, Kind::Commit, Kind::Commit],
+        "unreachable objects referenced by other unreachable objects aren't dangling"
+    );
+    assert!(out.has_errors());
+    assert!(out.lost_found.is_empty(), "nothing is written by default");
+    Ok(())
+}
+
+#[test]
+fn severities_and_skip_list_are_configurable() -> crate::Result {
+    let (mut repo, tmp) = repo_rw("make_fsck_repo.sh")?;
+    let bad_commit = fsck(&repo, Options::default())?
+        .problems
+        .iter()
+        .find(|problem| problem.kind == Kind::Commit)
+        .expect("present")
+        .id;
+    let skip_list = tmp.path().join("skip-list");
+    std::fs::write(
+        &skip_list,
+        format!("# a comment\n\n  {bad_commit}  # trailing comment\n"),
+    )?;
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value(&"fsck.GITMODULESURL", "warn")?;
+        config.set_raw_value(&"fsck.gitmodulesName", "ignore")?;
+        config.set_raw_value(&"fsck.skipList", gix::path::into_bstr(skip_list).as_ref())?;
+    }
+    let out = fsck(&repo, Options::default())?;
+    assert_eq!(
+        problems(&out),
+        [(Kind::Blob, Id::GitmodulesUrl, Severity::Warn)],
+        "message ids are case-insensitive, and the skipped commit isn't reported"
+    );
+    assert!(!out.has_errors(), "warnings aren't errors");
+
+    let out = fsck(
+        &repo,
+        Options {
+            strict: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(problems(&out), [(Kind::Blob, Id::GitmodulesUrl, Severity::Error)]);
+
+    let mut repo = gix::open_opts(tmp.path(), crate::restricted().strict_config(true))?;
+    repo.config_snapshot_mut().set_raw_value(&"fsck.badDate", "sometimes")?;
+    assert_eq!(
+        fsck(&repo, Options::default()).unwrap_err().to_string(),
+        "The value of 'fsck.badDate=sometimes' isn't one of 'error', 'warn' or 'ignore'"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_and_corrupt_objects() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_fsck_repo.sh")?;
+    let head = repo.head_commit()?;
+    let first = head.parent_ids().next().expect("parent").detach();
+    let staged = repo.index()?.entry_by_path("staged".into()).expect("present").id;
+    let file = head.tree()?.find_entry("file").expect("present").object_id();
+    let dangling_blob = repo.objects.iter()?.find_map(|id| {
+        let id = id.ok()?;
+        let object = repo.find_object(id).ok()?;
+        (object.kind == Kind::Blob && &*object.data == b"dangling\n").then_some(id)
+    });
+
+    std::fs::remove_file(loose_path(&repo, first))?;
+    std::fs::remove_file(loose_path(&repo, staged))?;
+    std::fs::remove_file(loose_path(&repo, file))?;
+    std::fs::rename(
+        loose_path(&repo, dangling_blob.expect("present")),
+        loose_path(&repo, file),
+    )?;
+
+    let out = fsck(&repo, Options::default())?;
+    assert_eq!(out.corrupt, [file], "the content doesn't match the id");
+    assert_eq!(
+        out.missing,
+        [(first, Kind::Commit)],
+        "the parent of HEAD and the target of the tag is missing"
+    );
+    let broken: Vec<_> = out
+        .broken_links
+        .iter()
+        .filter(|(_, id)| *id != first)
+        .map(|(source, _)| source.to_string())
+        .collect();
+    assert_eq!(broken, ["index:staged"]);
+    assert!(
+        out.broken_links
+            .iter()
+            .any(|(source, id)| *id == first && source == "HEAD@{reflog}"),
+        "reflogs are starting points as well"
+    );
+    assert!(out.has_errors());
+    Ok(())
+}
+
+#[test]
+fn promised_objects_may_be_missing_in_partial_clones() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_partial_clone_remote.sh")?;
+    let repo = gix::open_opts(tmp.path().join("partial"), crate::restricted())?;
+    let promised_blob = gix::objs::compute_hash(repo.object_hash(), gix::objs::Kind::Blob, b"first\n");
+
+    let out = fsck(&repo, Options::default())?;
+    assert_eq!(out.missing, []);
+    assert!(!out.has_errors());
+    assert!(!repo.has_object(promised_blob), "promised objects aren't fetched");
+
+    for entry in repo.path().join("objects/pack").read_dir()? {
+        let path = entry?.path();
+        if path.extension() == Some("promisor".as_ref()) {
+            std::fs::remove_file(path)?;
+        }
+    }
+    let out = fsck(&repo, Options::default())?;
+    assert_eq!(
+        out.missing,
+        [(promised_blob, Kind::Blob)],
+        "without promisor packs, nothing is promised"
+    );
+    Ok(())
+}
+
+#[test]
+fn lost_found() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_fsck_repo.sh")?;
+    let out = fsck(
+        &repo,
+        Options {
+            lost_found: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(out.lost_found.len(), out.dangling.len());
+    let lost_found = repo.path().join("lost-found");
+    for (id, kind) in &out.dangling {
+        let dir = if *kind == Kind::Commit { "commit" } else { "other" };
+        let content = std::fs::read(lost_found.join(dir).join(id.to_string()))?;
+        if *kind == Kind::Blob {
+            assert_eq!(content, b"dangling\n", "blobs are written with their content");
+        } else {
+            assert_eq!(content, format!("{id}\n").into_bytes());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,285 @@
+use std::sync::{Arc, Mutex};
+
+use gix::{
+    bstr::ByteSlice,
+    hook::{HookError, Hooks, Invocation},
+};
+
+use crate::util::{freeze_time, restricted_and_git};
+
+/// The label, arguments and standard input of each hook invocation.
+type Calls = Arc<Mutex<Vec<(String, Vec<String>, String)>>>;
+
+/// Return hooks that record their invocations for each of `names`, which all succeed except for the one labelled `failing`.
+fn recording_hooks(names: &[&str], failing: Option<&'static str>) -> (Hooks, Calls) {
+    let calls = Calls::default();
+    let mut hooks = Hooks::disabled();
+    for name in names {
+        let calls = calls.clone();
+        hooks = hooks.with_hook(*name, move |invocation: &Invocation<'_>| -> Result<(), HookError> {
+            let args: Vec<_> = invocation
+                .args
+                .iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect();
+            let label = match invocation.name {
+                "reference-transaction" => format!("{}:{}", invocation.name, args[0]),
+                name => name.to_owned(),
+            };
+            let fail = failing == Some(label.as_str());
+            calls
+                .lock()
+                .unwrap()
+                .push((label, args, invocation.stdin.to_str_lossy().into_owned()));
+            if fail {
+                return Err("refused".into());
+            }
+            if invocation.name == "commit-msg" {
+                let path = &invocation.args[0];
+                let mut message = std::fs::read(path)?;
+                message.extend_from_slice(b"\nSigned-off-by: hook\n");
+                std::fs::write(path, message)?;
+            }
+            Ok(())
+        });
+    }
+    (hooks, calls)
+}
+
+fn labels(calls: &Calls) -> Vec<String> {
+    calls
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(label, _, _)| label.clone())
+        .collect()
+}
+
+#[test]
+#[serial_test::serial]
+fn commits_run_hooks_which_can_change_the_message() -> crate::Result {
+    let _env = freeze_time();
+    let (mut repo, _tmp) = crate::repo_rw_opts("make_basic_repo.sh", restricted_and_git())?;
+    let (hooks, calls) = recording_hooks(
+        &[
+            "pre-commit",
+            "prepare-commit-msg",
+            "commit-msg",
+            "post-commit",
+            "reference-transaction",
+        ],
+        None,
+    );
+    repo.set_hooks(hooks);
+
+    let head = repo.head_commit()?;
+    let id = repo.commit_index("message", Default::default())?;
+    assert_eq!(
+        labels(&calls),
+        [
+            "pre-commit",
+            "prepare-commit-msg",
+            "commit-msg",
+            "reference-transaction:prepared",
+            "reference-transaction:committed",
+            "post-commit"
+        ]
+    );
+    assert_eq!(
+        id.object()?.into_commit().message_raw()?,
+        "message\nSigned-off-by: hook\n",
+        "the message is read back after the hooks ran"
+    );
+    let calls = calls.lock().unwrap();
+    let message_file = repo.git_dir().join("COMMIT_EDITMSG");
+    assert_eq!(
+        calls[1].1,
+        [message_file.to_string_lossy().into_owned(), "message".into()],
+        "prepare-commit-msg receives the file and the source of the message"
+    );
+    assert_eq!(
+        calls[3].2,
+        format!("{} {} refs/heads/main\n", head.id, id),
+        "the branch is changed, but not the reflog of HEAD"
+    );
+    Ok(())
+}
+
+#[test]
+#[serial_test::serial]
+fn failing_hooks_abort_commits() -> crate::Result {
+    let _env = freeze_time();
+    let (mut repo, _tmp) = crate::repo_rw_opts("make_basic_repo.sh", restricted_and_git())?;
+    let (head_id, tree) = {
+        let head = repo.head_commit()?;
+        (head.id, head.tree_id()?.detach())
+    };
+    for failing in ["pre-commit", "commit-msg", "reference-transaction:prepared"] {
+        let (hooks, calls) = recording_hooks(
+            &["pre-commit", "commit-msg", "post-commit", "reference-transaction"],
+            Some(failing),
+        );
+        repo.set_hooks(hooks);
+        let err = repo.commit_index("message", Default::default()).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                gix::commit::Error::Hook(gix::hook::Error::InProcess { .. })
+                    | gix::commit::Error::ReferenceEdit(gix::reference::edit::Error::Hook(
+                        gix::hook::Error::InProcess { .. }
+                    ))
+            ),
+            "{failing}: {err:?}"
+        );
+        assert_eq!(repo.head_id()?, head_id, "{failing}: HEAD wasn't changed");
+        assert!(
+            !labels(&calls).contains(&"post-commit".to_string()),
+            "{failing}: the commit wasn't made"
+        );
+        if failing == "reference-transaction:prepared" {
+            assert_eq!(
+                labels(&calls)[2..],
+                ["reference-transaction:prepared", "reference-transaction:aborted"]
+            );
+        }
+    }
+
+    repo.set_hooks(Hooks::disabled());
+    let id = repo.commit_index("message", Default::default())?;
+    assert_eq!(id.object()?.into_commit().tree_id()?, tree, "the index matches HEAD");
+    Ok(())
+}
+
+#[test]
+#[serial_test::serial]
+fn commits_not_made_from_the_index_run_no_hooks() -> crate::Result {
+    let _env = freeze_time();
+    let (mut repo, _tmp) = crate::repo_rw_opts("make_basic_repo.sh", restricted_and_git())?;
+    let names = ["pre-commit", "prepare-commit-msg", "commit-msg", "post-commit"];
+    let (hooks, calls) = recording_hooks(&names, Some("pre-commit"));
+    repo.set_hooks(hooks);
+
+    let message_file = repo.git_dir().join("COMMIT_EDITMSG");
+    std::fs::remove_file(&message_file)?;
+    let head = repo.head_commit()?;
+    let id = repo.commit("HEAD", "message", head.tree_id()?, Some(head.id))?;
+    assert_eq!(id.object()?.into_commit().message_raw()?, "message");
+    assert!(labels(&calls).is_empty());
+    assert!(!message_file.exists(), "nothing is written for hooks");
+
+    repo.commit_index("message", gix::commit::Options { no_verify: true })?;
+    assert_eq!(
+        labels(&calls),
+        ["prepare-commit-msg", "post-commit"],
+        "hooks that verify aren't run with `no_verify`"
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+mod programs {
+    use std::os::unix::fs::PermissionsExt;
+
+    use gix::bstr::ByteSlice;
+
+    fn write_hook(dir: &std::path::Path, name: &str, script: &str, executable: bool) -> crate::Result {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{script}"))?;
+        std::fs::set_permissions(
+            &path,
+            std::fs::Permissions::from_mode(if executable { 0o755 } else { 0o644 }),
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn run_in_the_worktree_with_git_environment_and_input() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_basic_repo.sh")?;
+        let hooks_dir = repo.hooks_dir()?;
+        assert_eq!(hooks_dir, repo.git_dir().join("hooks"));
+        write_hook(
+            &hooks_dir,
+            "custom",
+            r#"{ echo "$@"; pwd; echo "$GIT_DIR"; echo "$GIT_INDEX_FILE"; cat; } > out"#,
+            true,
+        )?;
+
+        assert!(repo.run_hook("custom", ["a", "b"], b"input\n")?);
+        let workdir = gix_path::realpath(repo.work_dir().expect("non-bare"))?;
+        let out = std::fs::read(workdir.join("out"))?;
+        let git_dir = repo.git_dir();
+        assert_eq!(
+            out.as_bstr(),
+            format!(
+                "a b\n{}\n{}\n{}\ninput\n",
+                workdir.display(),
+                git_dir.display(),
+                git_dir.join("index").display()
+            )
+        );
+
+        assert!(
+            !repo.run_hook("missing", None::<&str>, &[])?,
+            "missing hooks are ignored"
+        );
+        write_hook(&hooks_dir, "not-executable", "exit 0", false)?;
+        assert!(
+            !repo.run_hook("not-executable", None::<&str>, &[])?,
+            "hooks that aren't executable are ignored, like in git"
+        );
+        write_hook(&hooks_dir, "failing", "exit 3", true)?;
+        let err = repo.run_hook("failing", None::<&str>, &[]).unwrap_err();
+        assert!(
+            matches!(&err, gix::hook::Error::Failed { name, status } if name == "failing" && status.code() == Some(3)),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn output_goes_to_standard_error() -> crate::Result {
+        if std::env::var_os("GIX_TEST_RUN_HOOK").is_some() {
+            let (repo, _tmp) = crate::repo_rw("make_basic_repo.sh")?;
+            write_hook(&repo.hooks_dir()?, "custom", "echo from-hook", true)?;
+            assert!(repo.run_hook("custom", None::<&str>, &[])?);
+            return Ok(());
+        }
+        // Run this test in a separate process to see where the output of the hook ends up.
+        let (_crate_name, module) = module_path!().split_once("::").expect("in a module");
+        let out = std::process::Command::new(std::env::current_exe()?)
+            .args(["--exact", "--nocapture"])
+            .arg(format!("{module}::output_goes_to_standard_error"))
+            .env("GIX_TEST_RUN_HOOK", "1")
+            .output()?;
+        assert!(out.status.success(), "{}", out.stderr.as_bstr());
+        assert!(out.stderr.contains_str("from-hook"), "{}", out.stderr.as_bstr());
+        assert!(
+            !out.stdout.contains_str("from-hook"),
+            "like in git, the caller's standard output isn't used by hooks"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_path_is_relative_to_the_worktree_and_can_be_disabled() -> crate::Result {
+        let (mut repo, _tmp) = crate::repo_rw("make_basic_repo.sh")?;
+        let workdir = repo.work_dir().expect("non-bare").to_owned();
+        repo.config_snapshot_mut()
+            .set_value(&gix::config::tree::Core::HOOKS_PATH, "custom-hooks")?;
+        assert_eq!(repo.hooks_dir()?, workdir.join("custom-hooks"));
+        write_hook(
+            &workdir.join("custom-hooks"),
+            "post-checkout",
+            "echo \"$@\" > out",
+            true,
+        )?;
+
+        assert!(repo.run_hook("post-checkout", ["x"], &[])?);
+        assert_eq!(std::fs::read(workdir.join("out"))?.as_bstr(), "x\n");
+
+        repo.set_hooks(gix::hook::Hooks::disabled());
+        assert!(!repo.run_hook("post-checkout", ["x"], &[])?);
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{bstr::ByteSlice, refs::transaction::PreviousValue};
+
+/// Create two commits on top of `HEAD` which change `this`, and add a text and a binary file.
+fn commit_changes(repo: &gix::Repository) -> crate::Result<(gix::ObjectId, gix::ObjectId)> {
+    let base = repo.head_id()?.detach();
+    let mut editor = repo.edit_tree(repo.head_tree_id()?)?;
+    editor.upsert("this", gix::object::tree::EntryKind::Blob, repo.write_blob("world\n")?)?;
+    let first = repo.commit("HEAD", "first change", editor.write()?, Some(base))?;
+
+    let mut editor = repo.edit_tree(first.object()?.peel_to_tree()?.id)?;
+    editor.upsert(
+        "new",
+        gix::object::tree::EntryKind::BlobExecutable,
+        repo.write_blob("foo\n")?,
+    )?;
+    editor.upsert(
+        "bin",
+        gix::object::tree::EntryKind::Blob,
+        repo.write_blob(b"\0binary\0")?,
+    )?;
+    let second = repo.commit(
+        "HEAD",
+        "second change\n\nWith a body\nthat explains it.\n",
+        editor.write()?,
+        Some(first),
+    )?;
+    Ok((base, second.detach()))
+}
+
+fn reset_head(repo: &gix::Repository, id: gix::ObjectId) -> crate::Result {
+    let branch = repo.head_name()?.expect("not detached");
+    repo.reference(branch, id, PreviousValue::Any, "reset for test")?;
+    Ok(())
+}
+
+#[test]
+fn format_patch_and_apply_mailbox_round_trip() -> crate::Result {
+    let (repo, _tmp) = crate::util::basic_rw_repo()?;
+    let (base, head) = commit_changes(&repo)?;
+
+    let patches = repo.format_patch(base, head, repo.format_patch_options()?)?;
+    assert_eq!(patches.len(), 2);
+    assert_eq!(patches[0].file_name, "0001-first-change.patch");
+    assert_eq!(patches[1].file_name, "0002-second-change.patch");
+    assert_eq!(patches[1].commit, Some(head));
+    let second = patches[1].data.as_bstr();
+    assert!(second.starts_with_str(format!("From {head} Mon Sep 17 00:00:00 2001\n")));
+    assert!(second.contains_str("Subject: [PATCH 2/2] second change\n\nWith a body\nthat explains it.\n---\n"));
+    assert!(second.contains_str(" 2 files changed, 1 insertion(+)"));
+    assert!(second.contains_str("diff --git a/new b/new\nnew file mode 100755\n"));
+    assert!(second.contains_str("GIT binary patch\nliteral 8\n"));
+
+    reset_head(&repo, base)?;
+    let mbox = gix::format_patch::to_mbox(&patches);
+    let outcome = repo.apply_mailbox(&mbox, repo.apply_mailbox_options()?, &AtomicBool::default())?;
+    assert_eq!(outcome.commits.len(), 2);
+    assert_eq!(repo.state(), None, "the state is removed when done");
+    assert_eq!(repo.find_reference("ORIG_HEAD")?.id(), base);
+
+    let new_head = repo.head_commit()?;
+    assert_eq!(new_head.id, outcome.commits[1]);
+    assert_eq!(
+        new_head.tree_id()?,
+        repo.find_commit(head)?.tree_id()?,
+        "the same tree is recreated"
+    );
+    let original = repo.find_commit(head)?;
+    assert_eq!(new_head.message_raw()?, original.message_raw()?);
+    assert_eq!(new_head.author()?, original.author()?);
+
+    let workdir = repo.work_dir().expect("non-bare");
+    assert_eq!(std::fs::read(workdir.join("this"))?, b"world\n");
+    assert_eq!(std::fs::read(workdir.join("bin"))?, b"\0binary\0");
+    let reflog_message = repo
+        .head()?
+        .log_iter()
+        .rev()?
+        .expect("reflog exists")
+        .next()
+        .expect("one entry")?
+        .message
+        .to_owned();
+    assert_eq!(reflog_message, "am: second change");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "gpg")]
+fn apply_mailbox_signs_commits_with_commit_gpg_sign() -> crate::Result {
+    let (mut repo, _tmp) = super::object::sign::ssh_signing_repo()?;
+    let (base, head) = commit_changes(&repo)?;
+    let patches = repo.format_patch(base, head, repo.format_patch_options()?)?;
+    reset_head(&repo, base)?;
+
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::Commit::GPG_SIGN, "true")?;
+    let mbox = gix::format_patch::to_mbox(&patches);
+    let outcome = repo.apply_mailbox(&mbox, repo.apply_mailbox_options()?, &AtomicBool::default())?;
+    assert_eq!(outcome.commits.len(), 2);
+    for id in outcome.commits {
+        let commit = repo.find_commit(id)?;
+        assert!(commit.signature()?.is_some(), "{id} is signed");
+    }
+    Ok(())
+}
+
+#[test]
+fn format_patch_with_cover_letter() -> crate::Result {
+    let (repo, _tmp) = crate::util::basic_rw_repo()?;
+    let (base, head) = commit_changes(&repo)?;
+    let patches = repo.format_patch(
+        base,
+        head,
+        gix::format_patch::Options {
+            cover_letter: true,
+            subject_prefix: "RFC PATCH".into(),
+            signature: None,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(patches.len(), 3);
+    assert_eq!(patches[0].file_name, "0000-cover-letter.patch");
+    assert_eq!(patches[0].commit, None);
+    let cover = patches[0].data.as_bstr();
+    assert!(cover.contains_str("Subject: [RFC PATCH 0/2] *** SUBJECT HERE ***\n"));
+    assert!(cover.contains_str("*** BLURB HERE ***\n\ngitoxide (2):\n  first change\n  second change\n"));
+    assert!(cover.contains_str(" bin  | Bin 0 -> 8 bytes\n new  |   1 +\n this |   2 +-\n"));
+    assert!(patches[1]
+        .data
+        .as_bstr()
+        .contains_str("Subject: [RFC PATCH 1/2] first change\n"));
+    assert!(!patches[1].data.as_bstr().contains_str("\n-- \n"), "no signature");
+    Ok(())
+}
+
+#[test]
+fn apply_mailbox_stops_and_can_be_skipped() -> crate::Result {
+    let (repo, _tmp) = crate::util::basic_rw_repo()?;
+    let (base, head) = commit_changes(&repo)?;
+    let mut patches = repo.format_patch(base, head, Default::default())?;
+    reset_head(&repo, base)?;
+
+    let mut bad = patches[0].data.clone();
+    bad = bad.replace("-hello\n", "-something else\n");
+    patches[0].data = bad;
+    let err = repo
+        .apply_mailbox(
+            &gix::format_patch::to_mbox(&patches),
+            repo.apply_mailbox_options()?,
+            &AtomicBool::default(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, gix::am::Error::Apply { patch: 1, .. }), "{err:?}");
+    assert_eq!(repo.state(), Some(gix::state::InProgress::ApplyMailbox));
+    assert!(matches!(
+        repo.apply_mailbox(b"", Default::default(), &AtomicBool::default()),
+        Err(gix::am::Error::InProgress { .. })
+    ));
+
+    let outcome = repo.apply_mailbox_skip(&AtomicBool::default())?;
+    assert_eq!(outcome.commits.len(), 1, "only the second patch was applied");
+    assert_eq!(repo.state(), None);
+    let workdir = repo.work_dir().expect("non-bare");
+    assert_eq!(
+        std::fs::read(workdir.join("this"))?,
+        b"hello\n",
+        "the first patch was skipped"
+    );
+    assert_eq!(std::fs::read(workdir.join("new"))?, b"foo\n");
+    Ok(())
+}
+
+#[test]
+fn apply_mailbox_abort_after_git_am_conflict() -> crate::Result {
+    let (repo, _tmp) = crate::util::repo_rw("make_am_repo.sh")?;
+    assert_eq!(repo.state(), Some(gix::state::InProgress::ApplyMailbox));
+    let head = repo.head_id()?.detach();
+
+    assert!(matches!(
+        repo.apply_mailbox_continue(&AtomicBool::default()),
+        Err(gix::am::Error::NoChanges { patch: 1 })
+    ));
+    repo.apply_mailbox_abort(&AtomicBool::default())?;
+    assert_eq!(repo.state(), None);
+    assert_eq!(repo.head_id()?, head, "no commit was created, so HEAD stays");
+    let workdir = repo.work_dir().expect("non-bare");
+    assert_eq!(std::fs::read(workdir.join("file"))?, b"file.main.update\n");
+    assert!(matches!(
+        repo.apply_mailbox_abort(&AtomicBool::default()),
+        Err(gix::am::Error::NotInProgress)
+    ));
+    Ok(())
+}
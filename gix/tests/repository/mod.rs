@@ -1,20 +1,45 @@
 use gix::Repository;
 
+#[cfg(feature = "apply")]
+mod apply;
+mod commit_graph;
 mod config;
 #[cfg(feature = "excludes")]
 mod excludes;
 #[cfg(feature = "attributes")]
 mod filter;
+#[cfg(feature = "fsck")]
+mod fsck;
+#[cfg(feature = "hooks")]
+mod hook;
+#[cfg(feature = "apply")]
+mod mailbox;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
 mod reference;
+#[cfg(feature = "maintenance")]
+mod reflog;
 mod remote;
+#[cfg(feature = "maintenance")]
+mod repack;
+#[cfg(feature = "worktree-mutation")]
+mod reset;
+#[cfg(feature = "worktree-mutation")]
+mod restore;
+#[cfg(all(feature = "worktree-mutation", feature = "revision", feature = "tree-editor"))]
+mod sequencer;
 mod shallow;
+#[cfg(feature = "worktree-mutation")]
+mod sparse_checkout;
+#[cfg(all(feature = "worktree-mutation", feature = "dirwalk", feature = "tree-editor"))]
+mod stash;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
+#[cfg(feature = "worktree-mutation")]
+mod switch;
 mod worktree;
 
 #[cfg(feature = "dirwalk")]
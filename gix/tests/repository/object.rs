@@ -486,6 +486,152 @@ mod commit_as {
     }
 }
 
+#[cfg(feature = "gpg")]
+pub(crate) mod sign {
+    use gix::{
+        bstr::{BString, ByteSlice},
+        config::tree::{gpg, Commit, Gpg, Tag, User},
+    };
+    use gix_ref::transaction::PreviousValue;
+
+    /// Return a writable copy of a repository with SSH-signed commits and tags, configured to sign with the key that `git` used.
+    pub(crate) fn ssh_signing_repo() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable("make_ssh_signed_repo.sh")?;
+        let key = gix_testtools::scripted_fixture_read_only("make_ssh_signed_repo.sh")?.join("signing-key");
+        let mut repo = gix::open_opts(tmp.path().join("repo"), crate::restricted())?;
+        {
+            let mut config = repo.config_snapshot_mut();
+            config.set_value(&Gpg::FORMAT, "ssh")?;
+            config.set_value(&User::SIGNING_KEY, gix::path::into_bstr(key).as_ref())?;
+        }
+        Ok((repo, tmp))
+    }
+
+    #[test]
+    fn commit_as_with_commit_gpg_sign_is_byte_identical_to_git() -> crate::Result {
+        let (mut repo, _tmp) = ssh_signing_repo()?;
+        repo.config_snapshot_mut().set_value(&Commit::GPG_SIGN, "true")?;
+        let expected = repo.rev_parse_single("HEAD~1")?.object()?.into_commit();
+        let parent = repo.rev_parse_single("HEAD~2")?.detach();
+        let decoded = expected.decode()?;
+
+        let id = repo.commit_as(
+            decoded.committer,
+            decoded.author,
+            "refs/heads/new",
+            decoded.message.to_str()?,
+            decoded.tree(),
+            Some(parent),
+        )?;
+        assert_eq!(
+            id, expected.id,
+            "the signature is deterministic and stored like git does"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tag_with_tag_gpg_sign_is_byte_identical_to_git() -> crate::Result {
+        let (mut repo, _tmp) = ssh_signing_repo()?;
+        repo.config_snapshot_mut().set_value(&Tag::GPG_SIGN, "true")?;
+        let expected = repo.find_reference("signed-tag")?.id().object()?.into_tag();
+        let decoded = expected.decode()?;
+
+        let tag_ref = repo.tag(
+            "signed-tag",
+            decoded.target(),
+            decoded.target_kind,
+            decoded.tagger,
+            "signed tag",
+            PreviousValue::Any,
+        )?;
+        assert_eq!(
+            tag_ref.id(),
+            expected.id,
+            "a newline is added to the message, and the signature is appended to it"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn custom_signer() -> crate::Result {
+        struct Fake;
+        impl gix::gpg::Signer for Fake {
+            fn sign(&self, data: &[u8]) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>> {
+                Ok(format!(
+                    "-----BEGIN PGP SIGNATURE-----\n\n{} bytes\n-----END PGP SIGNATURE-----\n",
+                    data.len()
+                )
+                .into())
+            }
+        }
+        let (repo, _tmp) = ssh_signing_repo()?;
+        let head = repo.head_commit()?;
+        let id = repo.commit_as_signed(
+            head.committer()?,
+            head.author()?,
+            "HEAD",
+            "custom",
+            head.tree_id()?,
+            Some(head.id),
+            &Fake,
+        )?;
+        let commit = id.object()?.into_commit();
+        let (signature, signed_data) = commit.signature()?.expect("signed");
+        assert_eq!(
+            signature.to_str()?,
+            format!(
+                "-----BEGIN PGP SIGNATURE-----\n\n{} bytes\n-----END PGP SIGNATURE-----",
+                signed_data.to_bstring().len()
+            )
+        );
+        assert!(
+            commit.data.ends_with(b"-----END PGP SIGNATURE-----\n\ncustom"),
+            "the signature is a header"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn commits_are_unsigned_by_default() -> crate::Result {
+        let (repo, _tmp) = ssh_signing_repo()?;
+        let head = repo.head_commit()?;
+        let id = repo.commit("HEAD", "unsigned", head.tree_id()?, Some(head.id))?;
+        assert!(id.object()?.into_commit().signature()?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn ssh_signing_needs_signing_key() -> crate::Result {
+        let (_tmp, mut repo) = super::empty_bare_repo()?;
+        repo.config_snapshot_mut().set_value(&Gpg::FORMAT, "ssh")?;
+        assert!(matches!(
+            repo.signer(),
+            Err(gix::repository::signer::Error::MissingSshSigningKey)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn signer_defaults() -> crate::Result {
+        let (mut repo, _tmp) = ssh_signing_repo()?;
+        {
+            let mut config = repo.config_snapshot_mut();
+            config.set_value(&Gpg::FORMAT, "openpgp")?;
+            config.set_value(&gpg::OpenPgp::PROGRAM, "my-gpg")?;
+        }
+        let mut signer = repo.signer()?;
+        assert_eq!(signer.program, "my-gpg");
+        assert_eq!(signer.format, gix::gpg::Format::OpenPgp);
+
+        repo.config_snapshot_mut().set_value(&Gpg::FORMAT, "x509")?;
+        signer = repo.signer()?;
+        assert_eq!(signer.program, "gpgsm", "the program of another format isn't used");
+        assert_eq!(signer.format, gix::gpg::Format::X509);
+        Ok(())
+    }
+}
+
 mod commit {
     use gix_testtools::tempfile;
 
@@ -0,0 +1,74 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::util::repo_rw;
+use gix::reflog::Expiry;
+
+const DAY: i64 = 24 * 60 * 60;
+
+fn at(date: &str) -> crate::Result<SystemTime> {
+    let seconds = gix::date::parse(date, None)?.seconds;
+    Ok(UNIX_EPOCH + Duration::from_secs(seconds as u64))
+}
+
+fn reflog_messages(repo: &gix::Repository, name: &str) -> crate::Result<Vec<String>> {
+    let mut buf = Vec::new();
+    Ok(repo
+        .refs
+        .reflog_iter(name, &mut buf)?
+        .expect("present")
+        .map(|line| line.map(|line| line.message.to_string()))
+        .collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn expiry_is_configured_per_pattern() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_reflog_expiry_repo.sh")?;
+    let now = at("2000-04-15 00:00:00 +0000")?;
+    let now_seconds = gix::date::parse("2000-04-15 00:00:00 +0000", None)?.seconds;
+    assert_eq!(
+        repo.reflog_expiry("refs/heads/main".try_into()?, now)?,
+        Expiry {
+            total: Some(now_seconds - 90 * DAY),
+            unreachable: Some(now_seconds - 30 * DAY),
+        },
+        "defaults"
+    );
+    assert_eq!(
+        repo.reflog_expiry("refs/heads/keep-me".try_into()?, now)?,
+        Expiry {
+            total: None,
+            unreachable: Some(now_seconds - 30 * DAY),
+        },
+        "per-pattern values override only what they set"
+    );
+    Ok(())
+}
+
+#[test]
+fn expire_removes_old_and_unreachable_entries() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_reflog_expiry_repo.sh")?;
+    let out = repo.expire_reflogs(at("2000-04-15 00:00:00 +0000")?)?;
+    assert_eq!(out.rewritten_reflogs, 2);
+    assert_eq!(out.removed_entries, 4);
+    let expected = ["commit: c2", "reset: moving to HEAD~1", "commit: c3"];
+    assert_eq!(
+        reflog_messages(&repo, "refs/heads/main")?,
+        expected,
+        "the oldest entry expired, and so did the unreachable one, while the reachable one remains"
+    );
+    assert_eq!(reflog_messages(&repo, "HEAD")?, expected);
+    assert_eq!(
+        reflog_messages(&repo, "refs/heads/keep-me")?.len(),
+        1,
+        "the pattern prevents old entries from expiring"
+    );
+    assert_eq!(
+        repo.head_id()?,
+        repo.rev_parse_single("main")?,
+        "references are unchanged"
+    );
+
+    let out = repo.expire_reflogs(at("2000-04-15 00:00:00 +0000")?)?;
+    assert_eq!(out.removed_entries, 0, "nothing else expired");
+    Ok(())
+}
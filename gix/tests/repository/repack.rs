@@ -0,0 +1,363 @@
+use std::{collections::BTreeMap, path::Path, sync::atomic::AtomicBool};
+
+use crate::util::{git, git_output, repo_rw};
+use gix::{
+    bstr::ByteSlice,
+    repack::{Error, Mode, Options},
+    ObjectId,
+};
+
+/// Return the output of `git count-objects -v` as map, after asserting that `git fsck` is happy.
+fn count_objects(workdir: &Path) -> crate::Result<BTreeMap<String, u64>> {
+    let out = git_output(workdir, &["fsck", "--no-dangling"])?;
+    assert!(out.status.success(), "{}", out.stderr.as_bstr());
+    let out = git_output(workdir, &["count-objects", "-v"])?;
+    Ok(out
+        .stdout
+        .to_str()?
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .map(|(key, value)| (key.to_owned(), value.parse().unwrap_or(0)))
+        .collect())
+}
+
+fn exists(workdir: &Path, id: ObjectId) -> crate::Result<bool> {
+    Ok(git_output(workdir, &["cat-file", "-e", &id.to_string()])?
+        .status
+        .success())
+}
+
+fn unreachable_blob(repo: &gix::Repository) -> ObjectId {
+    gix::objs::compute_hash(repo.object_hash(), gix::objs::Kind::Blob, b"unreachable\n")
+}
+
+fn reflog_only_commit(repo: &gix::Repository) -> crate::Result<ObjectId> {
+    Ok(repo.rev_parse_single("HEAD@{1}")?.detach())
+}
+
+fn repack(repo: &gix::Repository, options: Options) -> Result<gix::repack::Outcome, Error> {
+    repo.repack(options, &mut gix::progress::Discard, &AtomicBool::default())
+}
+
+#[test]
+fn loose_objects_are_packed() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repack_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let before = count_objects(&workdir)?;
+    assert_eq!(before["packs"], 2);
+
+    let out = repack(
+        &repo,
+        Options {
+            delete_redundant: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        out.packed_objects,
+        3 + 3 + 1,
+        "two commits with their trees and blobs, and the staged blob"
+    );
+    assert_eq!(out.removed_loose_objects, out.packed_objects);
+    assert_eq!(out.removed_packs, 0);
+    assert!(out.pack.as_ref().expect("written").is_file());
+    assert_eq!(out.cruft_pack, None);
+
+    let after = count_objects(&workdir)?;
+    assert_eq!(after["packs"], 3);
+    assert_eq!(after["count"], 1, "the unreachable blob stays loose");
+    assert!(exists(&workdir, unreachable_blob(&repo))?);
+    assert!(!repo
+        .path()
+        .join("objects/pack")
+        .read_dir()?
+        .any(|entry| entry.expect("valid").path().extension() == Some("keep".as_ref())));
+    Ok(())
+}
+
+#[test]
+fn all_objects_with_cruft_pack_retains_unreachable_objects_until_they_expire() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repack_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let head_tree = repo.head_commit()?.tree_id()?.detach();
+    let options = Options {
+        mode: Mode::All,
+        delete_redundant: true,
+        cruft: true,
+        expire: None,
+    };
+
+    let out = repack(&repo, options)?;
+    assert_eq!(out.removed_packs, 2);
+    assert_eq!(out.cruft_objects, 1);
+    assert_eq!(out.pruned_objects, 0);
+    let cruft_pack = out.cruft_pack.expect("written");
+    assert!(cruft_pack.with_extension("mtimes").is_file());
+    let after = count_objects(&workdir)?;
+    assert_eq!(after["packs"], 2);
+    assert_eq!(after["count"], 0, "all loose objects are packed");
+    assert!(exists(&workdir, unreachable_blob(&repo))?);
+    assert!(
+        exists(&workdir, reflog_only_commit(&repo)?)?,
+        "objects in reflogs are reachable"
+    );
+    assert!(
+        repo.find_object(head_tree).is_ok(),
+        "the repository handle still finds objects after their packs were replaced"
+    );
+
+    let out = git_output(
+        &workdir,
+        &["repack", "-q", "-a", "-d", "--cruft", "--cruft-expiration=never"],
+    )?;
+    assert!(
+        out.status.success(),
+        "git can read our cruft pack: {}",
+        out.stderr.as_bstr()
+    );
+    assert!(exists(&workdir, unreachable_blob(&repo))?);
+
+    let out = repack(
+        &repo,
+        Options {
+            expire: Some(gix::date::SecondsSinceUnixEpoch::MAX),
+            ..options
+        },
+    )?;
+    assert_eq!(out.pruned_objects, 1);
+    assert_eq!(out.cruft_pack, None);
+    assert_eq!(count_objects(&workdir)?["packs"], 1);
+    assert!(
+        !exists(&workdir, unreachable_blob(&repo))?,
+        "the expired object is gone"
+    );
+    assert!(exists(&workdir, reflog_only_commit(&repo)?)?);
+    Ok(())
+}
+
+#[test]
+fn all_objects_without_cruft_pack_drops_unreachable_packed_objects() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repack_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let options = Options {
+        mode: Mode::All,
+        delete_redundant: true,
+        ..Default::default()
+    };
+    let out = repack(&repo, options)?;
+    assert_eq!(
+        out.pruned_objects, 0,
+        "the unreachable object is loose and never expires"
+    );
+    assert_eq!(count_objects(&workdir)?["count"], 1);
+
+    let out = repack(
+        &repo,
+        Options {
+            expire: Some(gix::date::SecondsSinceUnixEpoch::MAX),
+            ..options
+        },
+    )?;
+    assert_eq!(out.pruned_objects, 1);
+    let after = count_objects(&workdir)?;
+    assert_eq!((after["packs"], after["count"]), (1, 0));
+    assert!(!exists(&workdir, unreachable_blob(&repo))?);
+    Ok(())
+}
+
+#[test]
+fn private_references_of_linked_worktrees_and_cached_trees_are_reachable() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repack_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    git(&workdir, &["worktree", "add", "-q", "--detach", "wt"])?;
+    let linked_workdir = workdir.join("wt");
+    let mut private_commits = Vec::new();
+    for (message, name) in [("worktree", "refs/worktree/keep"), ("bisect", "refs/bisect/bad")] {
+        let id = git(&workdir, &["commit-tree", "HEAD^{tree}", "-m", message])?;
+        git(&linked_workdir, &["update-ref", name, id.trim()])?;
+        private_commits.push(ObjectId::from_hex(id.trim().as_bytes())?);
+    }
+    let cached_tree = ObjectId::from_hex(git(&workdir, &["write-tree"])?.trim().as_bytes())?;
+
+    let repo = gix::open_opts(&workdir, crate::restricted())?;
+    let out = repack(
+        &repo,
+        Options {
+            mode: Mode::All,
+            delete_redundant: true,
+            expire: Some(gix::date::SecondsSinceUnixEpoch::MAX),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(out.pruned_objects, 1, "only the unreachable blob is removed");
+    for id in private_commits {
+        assert!(
+            exists(&workdir, id)?,
+            "{id} is only reachable from a reference private to the linked worktree"
+        );
+    }
+    assert!(
+        exists(&workdir, cached_tree)?,
+        "the tree of the staged changes is only reachable from the `TREE` extension of the index"
+    );
+    Ok(())
+}
+
+#[test]
+fn kept_packs_are_left_alone() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repack_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let pack_dir = repo.path().join("objects/pack");
+    let mut indices: Vec<_> = pack_dir
+        .read_dir()?
+        .map(|entry| entry.expect("valid").path())
+        .filter(|path| path.extension() == Some("idx".as_ref()))
+        .collect();
+    indices.sort();
+    let kept = indices[0].with_extension("keep");
+    std::fs::write(&kept, "")?;
+
+    let out = repack(
+        &repo,
+        Options {
+            mode: Mode::All,
+            delete_redundant: true,
+            cruft: true,
+            expire: None,
+        },
+    )?;
+    assert_eq!(out.removed_packs, 1);
+    assert!(kept.is_file());
+    assert!(indices[0].is_file());
+    assert_eq!(count_objects(&workdir)?["packs"], 3, "kept, new and cruft pack");
+    Ok(())
+}
+
+#[test]
+fn promised_objects_are_not_fetched_and_promisor_packs_stay_marked() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_partial_clone_remote.sh")?;
+    let repo = gix::open_opts(tmp.path().join("partial"), crate::restricted())?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let promised_blob = gix::objs::compute_hash(repo.object_hash(), gix::objs::Kind::Blob, b"first\n");
+    assert!(
+        !repo.has_object(promised_blob),
+        "the blob of the first commit was filtered"
+    );
+    assert!(repo.promisor_objects()?.contains(&promised_blob));
+
+    let out = repack(
+        &repo,
+        Options {
+            mode: Mode::All,
+            delete_redundant: true,
+            cruft: true,
+            expire: None,
+        },
+    )?;
+    assert_eq!(out.pack, None, "all objects were received from the promisor remote");
+    assert_eq!(
+        out.promisor_objects,
+        2 + 3 + 2,
+        "two commits, their root trees and the shared subtree, and the blobs of the checkout"
+    );
+    let promisor_pack = out.promisor_pack.expect("written");
+    assert!(promisor_pack.with_extension("promisor").is_file());
+    assert!(!repo.has_object(promised_blob), "promised objects aren't fetched");
+
+    let after = count_objects(&workdir)?;
+    assert_eq!((after["packs"], after["count"]), (1, 0));
+    let promisor_files = repo
+        .path()
+        .join("objects/pack")
+        .read_dir()?
+        .filter(|entry| entry.as_ref().expect("valid").path().extension() == Some("promisor".as_ref()))
+        .count();
+    assert_eq!(promisor_files, 1, "the markers of replaced packs are removed");
+    Ok(())
+}
+
+#[test]
+fn geometric_combines_the_smallest_packs() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repack_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    assert!(matches!(
+        repack(
+            &repo,
+            Options {
+                mode: Mode::Geometric { factor: 1 },
+                ..Default::default()
+            }
+        ),
+        Err(Error::InvalidGeometricFactor { factor: 1 })
+    ));
+
+    let out = repack(
+        &repo,
+        Options {
+            mode: Mode::Geometric { factor: 2 },
+            delete_redundant: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        out.removed_packs, 0,
+        "packs with 3 and 9 objects already form a progression"
+    );
+    assert_eq!(out.packed_objects, 8, "all loose objects are packed, reachable or not");
+    let after = count_objects(&workdir)?;
+    assert_eq!((after["packs"], after["count"]), (3, 0));
+
+    let out = repack(
+        &repo,
+        Options {
+            mode: Mode::Geometric { factor: 4 },
+            delete_redundant: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(out.removed_packs, 3);
+    assert_eq!(out.packed_objects, 9 + 3 + 8);
+    assert_eq!(count_objects(&workdir)?["packs"], 1);
+    assert!(exists(&workdir, unreachable_blob(&repo))?);
+    Ok(())
+}
+
+#[test]
+fn gc_prunes_according_to_configuration() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repack_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let reflog_only_commit = reflog_only_commit(&repo)?;
+    for key in ["gc.reflogExpire", "gc.reflogExpireUnreachable"] {
+        assert!(git_output(&workdir, &["config", key, "never"])?.status.success());
+    }
+    let repo = gix::open_opts(&workdir, crate::restricted())?;
+    let out = repo.gc(&mut gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(out.cruft_objects, 1, "the unreachable object is recent");
+    assert_eq!(out.reflog.removed_entries, 0);
+    assert_eq!(out.packed_refs, 1, "the only branch is packed");
+    assert!(!repo.path().join("refs/heads/main").exists());
+    assert!(exists(&workdir, reflog_only_commit)?);
+
+    assert!(git_output(&workdir, &["config", "--unset-all", "gc.reflogExpire"])?
+        .status
+        .success());
+    assert!(git_output(&workdir, &["config", "gc.pruneExpire", "now"])?
+        .status
+        .success());
+    let repo = gix::open_opts(&workdir, crate::restricted())?;
+    let out = repo.gc(&mut gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(
+        out.reflog.rewritten_reflogs, 2,
+        "all entries of the fixture are old enough to expire, in the reflogs of main and HEAD"
+    );
+    assert_eq!(out.packed_refs, 0);
+    assert_eq!(
+        (out.cruft_objects, out.pruned_objects),
+        (0, 1 + 3),
+        "the unreachable blob and the commit that was only reachable through the reflog"
+    );
+    let after = count_objects(&workdir)?;
+    assert_eq!((after["packs"], after["count"]), (1, 0));
+    assert!(!exists(&workdir, reflog_only_commit)?);
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{
+    bstr::{BString, ByteSlice},
+    reset::{Error, Mode},
+};
+
+use super::switch::git_status;
+
+fn repo_rw(name: &str) -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let tmp = gix_testtools::scripted_fixture_writable("make_reset_repo.sh")?;
+    let repo = gix::open_opts(tmp.path().join(name), crate::restricted())?;
+    Ok((repo, tmp))
+}
+
+fn first_commit(repo: &gix::Repository) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single("HEAD~1")?.detach())
+}
+
+/// Return `(path, id)` of all index entries, with conflicting entries marked by their stage.
+fn index_entries(repo: &gix::Repository) -> crate::Result<Vec<(BString, gix::ObjectId)>> {
+    let index = repo.open_index()?;
+    Ok(index
+        .entries()
+        .iter()
+        .map(|e| {
+            let mut path = e.path(&index).to_owned();
+            if e.stage_raw() != 0 {
+                path.extend_from_slice(format!(":{}", e.stage_raw()).as_bytes());
+            }
+            (path, e.id)
+        })
+        .collect())
+}
+
+fn tree_entries(repo: &gix::Repository, commit: gix::ObjectId) -> crate::Result<Vec<(BString, gix::ObjectId)>> {
+    let index = repo.index_from_tree(&repo.find_commit(commit)?.tree_id()?)?;
+    Ok(index
+        .entries()
+        .iter()
+        .map(|e| (e.path(&index).to_owned(), e.id))
+        .collect())
+}
+
+fn last_reflog_message(repo: &gix::Repository, name: &str) -> crate::Result<BString> {
+    let reference = repo.find_reference(name)?;
+    let mut buf = Vec::new();
+    let last = reference
+        .log_iter()
+        .rev()?
+        .expect("log present")
+        .next()
+        .expect("at least one line")?;
+    buf.extend_from_slice(&last.message);
+    Ok(buf.into())
+}
+
+#[test]
+fn soft_only_moves_head() -> crate::Result {
+    let (repo, _tmp) = repo_rw("repo")?;
+    let previous_head = repo.head_id()?.detach();
+    let previous_index = index_entries(&repo)?;
+    let target = first_commit(&repo)?;
+
+    let out = repo.reset(target, Mode::Soft, &AtomicBool::default())?;
+    assert_eq!(out, Default::default());
+    assert_eq!(repo.head_id()?, target);
+    assert_eq!(
+        repo.head_name()?.expect("still on a branch").as_bstr(),
+        "refs/heads/main"
+    );
+    assert_eq!(index_entries(&repo)?, previous_index, "the index wasn't touched");
+    assert_eq!(repo.find_reference("ORIG_HEAD")?.id(), previous_head);
+    let message = format!("reset: moving to {target}");
+    assert_eq!(last_reflog_message(&repo, "HEAD")?, message);
+    assert_eq!(last_reflog_message(&repo, "refs/heads/main")?, message);
+    Ok(())
+}
+
+#[test]
+fn reset_to_spec_keeps_the_spelling_of_the_revision_in_the_reflog() -> crate::Result {
+    let (repo, _tmp) = repo_rw("repo")?;
+    let target = first_commit(&repo)?;
+
+    repo.reset_to_spec("HEAD~1", Mode::Soft, &AtomicBool::default())?;
+    assert_eq!(repo.head_id()?, target);
+    assert_eq!(last_reflog_message(&repo, "HEAD")?, "reset: moving to HEAD~1");
+    assert_eq!(
+        last_reflog_message(&repo, "refs/heads/main")?,
+        "reset: moving to HEAD~1"
+    );
+    Ok(())
+}
+
+#[test]
+fn mixed_resets_the_index_but_not_the_worktree() -> crate::Result {
+    let (repo, _tmp) = repo_rw("repo")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let stat_of_unchanged = repo.open_index()?.entry_by_path("dir/c".into()).expect("present").stat;
+    let target = first_commit(&repo)?;
+
+    let out = repo.reset(target, Mode::Mixed, &AtomicBool::default())?;
+    assert_eq!(out.index_entries_changed, 4, "a, b, crlf and dir/d");
+    assert_eq!(out.files_checked_out + out.files_removed, 0);
+    assert_eq!(index_entries(&repo)?, tree_entries(&repo, target)?);
+    assert_eq!(
+        repo.open_index()?.entry_by_path("dir/c".into()).expect("present").stat,
+        stat_of_unchanged,
+        "unchanged entries are kept as they are"
+    );
+    assert_eq!(std::fs::read(workdir.join("a"))?.as_bstr(), "a2\n");
+    assert!(workdir.join("dir/d").is_file());
+    assert_eq!(git_status(&workdir)?, " M a\n D b\n M crlf\n?? dir/d\n");
+    Ok(())
+}
+
+#[test]
+fn hard_resets_the_index_and_the_worktree() -> crate::Result {
+    let (repo, _tmp) = repo_rw("repo")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("dir/c"), "modified")?;
+    std::fs::write(workdir.join("untracked"), "untracked")?;
+    let target = first_commit(&repo)?;
+
+    let out = repo.reset(target, Mode::Hard, &AtomicBool::default())?;
+    assert_eq!(out.index_entries_changed, 4);
+    assert_eq!(out.files_checked_out, 4, "a, b, crlf and the modified dir/c");
+    assert_eq!(out.files_removed, 1, "dir/d");
+    assert_eq!(repo.head_id()?, target);
+    assert_eq!(index_entries(&repo)?, tree_entries(&repo, target)?);
+    assert_eq!(std::fs::read(workdir.join("a"))?.as_bstr(), "a\n");
+    assert_eq!(std::fs::read(workdir.join("dir/c"))?.as_bstr(), "c\n");
+    assert_eq!(
+        std::fs::read(workdir.join("crlf"))?.as_bstr(),
+        "crlf\r\n",
+        "filters are applied"
+    );
+    assert!(!workdir.join("dir/d").exists());
+    assert_eq!(git_status(&workdir)?, "?? untracked\n", "untracked files are kept");
+
+    let out = repo.reset(target, Mode::Hard, &AtomicBool::default())?;
+    assert_eq!(
+        out,
+        Default::default(),
+        "files with filters applied aren't considered modified"
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_keeps_local_changes_in_untouched_files() -> crate::Result {
+    let (repo, _tmp) = repo_rw("repo")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let target = first_commit(&repo)?;
+    std::fs::write(workdir.join("a"), "modified")?;
+    let err = repo.reset(target, Mode::Merge, &AtomicBool::default()).unwrap_err();
+    assert!(matches!(err, Error::NotUpToDate { ref path } if path == "a"), "{err:?}");
+    assert_ne!(repo.head_id()?, target, "nothing changed");
+
+    std::fs::write(workdir.join("a"), "a2\n")?;
+    std::fs::write(workdir.join("dir/c"), "modified")?;
+    let out = repo.reset(target, Mode::Merge, &AtomicBool::default())?;
+    assert_eq!(out.files_checked_out, 3);
+    assert_eq!(out.files_removed, 1);
+    assert_eq!(std::fs::read(workdir.join("dir/c"))?.as_bstr(), "modified");
+    assert_eq!(git_status(&workdir)?, " M dir/c\n");
+    Ok(())
+}
+
+#[test]
+fn merge_aborts_a_conflicting_merge() -> crate::Result {
+    let (repo, _tmp) = repo_rw("conflict")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    assert_eq!(repo.state(), Some(gix::state::InProgress::Merge));
+    let err = repo
+        .reset(repo.head_id()?, Mode::Soft, &AtomicBool::default())
+        .unwrap_err();
+    assert!(matches!(err, Error::SoftResetDuringMerge), "{err:?}");
+
+    std::fs::write(workdir.join("unrelated"), "modified")?;
+    let out = repo.reset(repo.head_id()?, Mode::Merge, &AtomicBool::default())?;
+    assert_eq!(out.index_entries_changed, 1);
+    assert_eq!(out.files_checked_out, 1);
+    assert_eq!(repo.state(), None, "the merge state was removed");
+    assert_eq!(std::fs::read(workdir.join("file"))?.as_bstr(), "main\n");
+    assert_eq!(git_status(&workdir)?, " M unrelated\n");
+    Ok(())
+}
+
+#[test]
+fn keep_fails_if_files_to_update_have_local_changes() -> crate::Result {
+    let (repo, _tmp) = repo_rw("repo")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let target = first_commit(&repo)?;
+    std::fs::write(workdir.join("a"), "modified")?;
+    let err = repo.reset(target, Mode::Keep, &AtomicBool::default()).unwrap_err();
+    assert!(matches!(err, Error::NotUpToDate { ref path } if path == "a"), "{err:?}");
+
+    std::fs::write(workdir.join("a"), "a2\n")?;
+    std::fs::write(workdir.join("dir/c"), "modified")?;
+    let out = repo.reset(target, Mode::Keep, &AtomicBool::default())?;
+    assert_eq!(out.files_checked_out, 3);
+    assert_eq!(out.files_removed, 1);
+    assert_eq!(git_status(&workdir)?, " M dir/c\n");
+    assert_eq!(index_entries(&repo)?, tree_entries(&repo, target)?);
+    Ok(())
+}
+
+#[test]
+fn paths_only_reset_matching_index_entries() -> crate::Result {
+    let (repo, _tmp) = repo_rw("repo")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let head = repo.head_id()?.detach();
+    let target = first_commit(&repo)?;
+
+    let out = repo.reset_paths(target, ["a", "dir/"])?;
+    assert_eq!(out.index_entries_changed, 2, "a and dir/d");
+    assert_eq!(repo.head_id()?, head, "HEAD doesn't change");
+    assert_eq!(
+        std::fs::read(workdir.join("a"))?.as_bstr(),
+        "a2\n",
+        "the worktree isn't touched"
+    );
+    assert_eq!(git_status(&workdir)?, "MM a\nD  dir/d\n?? dir/d\n");
+
+    let out = repo.reset_paths(head, None::<&str>)?;
+    assert_eq!(out.index_entries_changed, 2, "all paths can be reset");
+    assert_eq!(git_status(&workdir)?, "");
+
+    let out = repo.reset_paths(gix::ObjectId::empty_tree(repo.object_hash()), ["dir"])?;
+    assert_eq!(out.index_entries_changed, 2, "paths not in the tree are removed");
+    assert_eq!(git_status(&workdir)?, "D  dir/c\nD  dir/d\n?? dir/\n");
+    Ok(())
+}
+
+#[test]
+fn bare_repositories_only_support_soft_resets() -> crate::Result {
+    let repo = crate::util::named_subrepo_opts("make_reset_repo.sh", "bare.git", crate::restricted())?;
+    let err = repo
+        .reset(repo.head_id()?, Mode::Mixed, &AtomicBool::default())
+        .unwrap_err();
+    assert!(matches!(err, Error::BareRepository { mode: Mode::Mixed }), "{err:?}");
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{
+    bstr::ByteSlice,
+    restore::{Error, Options},
+};
+
+use super::switch::{git_status, repo_rw};
+
+#[test]
+fn worktree_from_the_index() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("a"), "modified")?;
+    std::fs::remove_file(workdir.join("dir/c"))?;
+    std::fs::remove_file(workdir.join("crlf"))?;
+
+    let out = repo.restore(["a"], Options::default(), &AtomicBool::default())?;
+    assert_eq!(out.files_checked_out, 1);
+    assert_eq!(out.index_entries_changed, 0);
+    assert_eq!(git_status(&workdir)?, " D crlf\n D dir/c\n");
+
+    let out = repo.restore(None::<&str>, Options::default(), &AtomicBool::default())?;
+    assert_eq!(out.files_checked_out, 2, "all paths are restored without patterns");
+    assert_eq!(
+        std::fs::read(workdir.join("crlf"))?.as_bstr(),
+        "crlf\r\n",
+        "filters are applied"
+    );
+    assert_eq!(git_status(&workdir)?, "");
+    Ok(())
+}
+
+#[test]
+fn staged_from_head() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("a"), "modified")?;
+    std::fs::write(workdir.join("b"), "modified")?;
+    crate::util::git(&workdir, &["add", "a", "b"])?;
+
+    let out = repo.restore(
+        ["a"],
+        Options {
+            staged: true,
+            worktree: false,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.index_entries_changed, 1);
+    assert_eq!(out.files_checked_out, 0, "the worktree isn't touched");
+    assert_eq!(git_status(&workdir)?, " M a\nM  b\n");
+    Ok(())
+}
+
+#[test]
+fn index_and_worktree_from_source() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let other = repo.rev_parse_single("other")?.detach();
+
+    let out = repo.restore(
+        ["dir"],
+        Options {
+            source: Some(other),
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.files_checked_out, 1);
+    assert_eq!(git_status(&workdir)?, "?? dir/d\n", "only the worktree was changed");
+
+    let out = repo.restore(
+        None::<&str>,
+        Options {
+            source: Some(other),
+            staged: true,
+            worktree: true,
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.index_entries_changed, 5, "a, b, crlf, dir/d and shared");
+    assert_eq!(out.files_checked_out, 3, "a, crlf and shared");
+    assert_eq!(out.files_removed, 1, "b");
+    assert_eq!(git_status(&workdir)?, "M  a\nD  b\nM  crlf\nA  dir/d\nM  shared\n");
+    Ok(())
+}
+
+#[test]
+fn worktree_from_the_index_fails_for_unmerged_paths() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("shared"), "ONE\n2\n3\n4\n5\n")?;
+    let out = repo.switch(
+        gix::switch::Target::Branch("refs/heads/conflicting".try_into()?),
+        gix::switch::Options {
+            merge: true,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.conflicts.len(), 1);
+
+    let err = repo
+        .restore(["shared"], Options::default(), &AtomicBool::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::Unmerged { ref path } if path == "shared"),
+        "{err:?}"
+    );
+
+    let out = repo.restore(
+        ["shared"],
+        Options {
+            staged: true,
+            worktree: true,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(
+        out.index_entries_changed, 1,
+        "restoring from HEAD resolves the conflict"
+    );
+    assert_eq!(git_status(&workdir)?, "");
+    Ok(())
+}
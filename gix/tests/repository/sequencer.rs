@@ -0,0 +1,249 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix::{
+    bstr::{BString, ByteSlice},
+    sequencer::{Commits, Error, Options},
+    state::InProgress,
+    ObjectId,
+};
+
+use super::switch::git_status;
+use crate::util::{git, repo_rw};
+
+fn id(repo: &gix::Repository, spec: &str) -> crate::Result<ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+fn message(repo: &gix::Repository, id: ObjectId) -> crate::Result<BString> {
+    Ok(repo.find_commit(id)?.message_raw()?.to_owned())
+}
+
+fn subjects(workdir: &Path, range: &str) -> crate::Result<String> {
+    git(workdir, &["log", "--format=%s", range])
+}
+
+#[test]
+fn cherry_pick_range_in_order_with_record_origin() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_sequencer_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let out = repo.cherry_pick(
+        vec![id(&repo, "topic~2")?, id(&repo, "topic")?],
+        Options {
+            record_origin: true,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.commits.len(), 2);
+    assert_eq!(subjects(&workdir, "main~2..")?, "add b\nadd a\n");
+    assert_eq!(
+        message(&repo, out.commits[0])?,
+        format!("add a\n\n(cherry picked from commit {})\n", id(&repo, "topic~2")?)
+    );
+    assert_eq!(
+        message(&repo, out.commits[1])?,
+        format!(
+            "add b\n\nSigned-off-by: author <author@example.com>\n(cherry picked from commit {})\n",
+            id(&repo, "topic")?
+        ),
+        "the line is added to existing trailers"
+    );
+    let picked = repo.find_commit(out.commits[1])?;
+    assert_eq!(
+        picked.author()?,
+        repo.find_commit(id(&repo, "topic")?)?.author()?,
+        "the author is kept"
+    );
+    assert_eq!(git_status(&workdir)?, "");
+    assert_eq!(repo.state(), None);
+    assert!(!repo.path().join("sequencer").exists());
+    Ok(())
+}
+
+#[test]
+fn conflicts_stop_the_sequence_which_can_be_continued() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_sequencer_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let head = id(&repo, "HEAD")?;
+    let range = Commits::Range {
+        from: id(&repo, "main~1")?,
+        to: id(&repo, "topic")?,
+    };
+    let err = repo
+        .cherry_pick(range, Options::default(), &AtomicBool::default())
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::Conflicts { paths, subject, .. } if paths == &["shared"] && subject == "change shared"),
+        "{err:?}"
+    );
+    assert_eq!(repo.state(), Some(InProgress::CherryPickSequence));
+    assert_eq!(git_status(&workdir)?, "UU shared\n");
+    assert_eq!(
+        subjects(&workdir, &format!("{head}~1.."))?,
+        "add a\nconflicting change\n"
+    );
+    assert_eq!(
+        std::fs::read(repo.path().join("MERGE_MSG"))?.as_bstr(),
+        "change shared\n\n# Conflicts:\n#\tshared\n"
+    );
+    assert!(matches!(
+        repo.cherry_pick(id(&repo, "side")?, Options::default(), &AtomicBool::default()),
+        Err(Error::InProgress)
+    ));
+    assert!(matches!(
+        repo.sequencer_continue(&AtomicBool::default()),
+        Err(Error::Unmerged { .. })
+    ));
+
+    std::fs::write(workdir.join("shared"), "one\n2\n3\n")?;
+    git(&workdir, &["add", "shared"])?;
+    let out = repo.sequencer_continue(&AtomicBool::default())?;
+    assert_eq!(out.commits.len(), 2, "the resolved commit and the one that remained");
+    assert_eq!(
+        subjects(&workdir, &format!("{head}.."))?,
+        "add b\nchange shared\nadd a\n",
+        "the message of the conflicting commit is kept, without comments"
+    );
+    assert_eq!(message(&repo, out.commits[0])?, "change shared\n");
+    assert_eq!(git_status(&workdir)?, "");
+    assert_eq!(repo.state(), None);
+    assert!(!repo.path().join("sequencer").exists());
+    Ok(())
+}
+
+#[test]
+fn git_can_continue_a_sequence_that_was_stopped() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_sequencer_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let head = id(&repo, "HEAD")?;
+    let range = Commits::Range {
+        from: id(&repo, "main~1")?,
+        to: id(&repo, "topic")?,
+    };
+    repo.cherry_pick(range, Options::default(), &AtomicBool::default())
+        .unwrap_err();
+    std::fs::write(workdir.join("shared"), "one\n2\n3\n")?;
+    git(&workdir, &["add", "shared"])?;
+    git(&workdir, &["cherry-pick", "--continue"])?;
+    assert_eq!(
+        subjects(&workdir, &format!("{head}.."))?,
+        "add b\nchange shared\nadd a\n"
+    );
+    assert_eq!(repo.state(), None);
+    Ok(())
+}
+
+#[test]
+fn skip_and_abort() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_sequencer_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let head = id(&repo, "HEAD")?;
+    let range = Commits::Range {
+        from: id(&repo, "main~1")?,
+        to: id(&repo, "topic")?,
+    };
+
+    repo.cherry_pick(range.clone(), Options::default(), &AtomicBool::default())
+        .unwrap_err();
+    let out = repo.sequencer_skip(&AtomicBool::default())?;
+    assert_eq!(out.commits.len(), 1);
+    assert_eq!(subjects(&workdir, &format!("{head}.."))?, "add b\nadd a\n");
+    assert_eq!(git_status(&workdir)?, "");
+    assert_eq!(repo.state(), None);
+
+    git(&workdir, &["reset", "-q", "--hard", &head.to_string()])?;
+    repo.cherry_pick(range, Options::default(), &AtomicBool::default())
+        .unwrap_err();
+    repo.sequencer_abort(&AtomicBool::default())?;
+    assert_eq!(id(&repo, "HEAD")?, head, "HEAD is back where it was");
+    assert_eq!(git_status(&workdir)?, "");
+    assert_eq!(repo.state(), None);
+    assert!(!repo.path().join("sequencer").exists());
+    assert!(matches!(
+        repo.sequencer_abort(&AtomicBool::default()),
+        Err(Error::NotInProgress)
+    ));
+    Ok(())
+}
+
+#[test]
+fn revert_and_merges_with_mainline() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_sequencer_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let reverted = id(&repo, "HEAD")?;
+    let out = repo.revert(reverted, Options::default(), &AtomicBool::default())?;
+    assert_eq!(
+        message(&repo, out.commits[0])?,
+        format!("Revert \"conflicting change\"\n\nThis reverts commit {reverted}.\n")
+    );
+    assert_eq!(std::fs::read(workdir.join("shared"))?.as_bstr(), "1\n2\n3\n");
+
+    let merge = id(&repo, "with-merge")?;
+    assert!(matches!(
+        repo.cherry_pick(merge, Options::default(), &AtomicBool::default()),
+        Err(Error::MergeWithoutMainline { commit }) if commit == merge
+    ));
+    assert!(matches!(
+        repo.cherry_pick(
+            merge,
+            Options {
+                mainline: Some(3),
+                ..Default::default()
+            },
+            &AtomicBool::default()
+        ),
+        Err(Error::NoSuchParent { mainline: 3, .. })
+    ));
+    repo.cherry_pick(
+        merge,
+        Options {
+            mainline: Some(1),
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(std::fs::read(workdir.join("c"))?.as_bstr(), "c\n");
+    assert_eq!(git_status(&workdir)?, "");
+
+    let out = repo.revert(
+        id(&repo, "HEAD")?,
+        Options {
+            no_commit: true,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert!(out.commits.is_empty());
+    assert_eq!(git_status(&workdir)?, "D  c\n");
+    assert_eq!(
+        repo.state(),
+        Some(InProgress::Revert),
+        "just like git, REVERT_HEAD is kept"
+    );
+    Ok(())
+}
+
+#[test]
+fn continue_a_sequence_stopped_by_git() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_cherry_pick_sequence_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("f2"), "resolved\n")?;
+    git(&workdir, &["add", "f2"])?;
+    let out = repo.sequencer_continue(&AtomicBool::default())?;
+    assert_eq!(out.commits.len(), 2);
+    assert_eq!(subjects(&workdir, "-3")?, "f3\nf2.other-branch\nf2.main\n");
+    assert_eq!(repo.state(), None);
+
+    let (repo, _tmp) = repo_rw("make_revert_sequence_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("1"), "1.0\n")?;
+    git(&workdir, &["add", "1"])?;
+    let out = repo.sequencer_continue(&AtomicBool::default())?;
+    assert!(
+        out.commits.is_empty(),
+        "the options of git are used, which say to not commit"
+    );
+    assert_eq!(git_status(&workdir)?, "M  1\nD  2\n");
+    assert_eq!(repo.state(), None);
+    Ok(())
+}
@@ -127,4 +127,31 @@ mod traverse {
         }
         Ok(())
     }
+
+    #[test]
+    #[parallel]
+    #[cfg(feature = "revision")]
+    fn merge_base_does_not_fail_on_grafted_commits() -> crate::Result {
+        let base = gix_path::realpath(gix_testtools::scripted_fixture_read_only("make_remote_repos.sh")?.join("base"))?;
+        let shallow_base = gix_testtools::scripted_fixture_read_only_with_args_single_archive(
+            "make_complex_shallow_repo.sh",
+            Some(base.to_string_lossy()),
+        )?;
+        for name in ["shallow.git", "shallow"] {
+            let repo = gix::open_opts(shallow_base.join(name), crate::restricted())?;
+            let c = hex_to_id("2d9d136fb0765f2e24c44a0f91984318d580d03b");
+            let b = hex_to_id("dfd0954dabef3b64f458321ef15571cc1a46d552");
+            let f = hex_to_id("27e71576a6335294aa6073ab767f8b36bdba81d0");
+            let e = hex_to_id("b5152869aedeb21e55696bb81de71ea1bb880c85");
+            assert_eq!(repo.merge_base(c, b)?, f, "the grafted commit F is reachable from both");
+            assert!(
+                matches!(
+                    repo.merge_base(c, e),
+                    Err(gix::repository::merge_base::Error::NotFound { .. })
+                ),
+                "history beyond the shallow boundary is unknown, so there is no merge-base, but no error either"
+            );
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,125 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::bstr::ByteSlice;
+use gix_index::entry::Flags;
+
+fn skipped_paths(index: &gix_index::File) -> Vec<String> {
+    index
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(Flags::SKIP_WORKTREE))
+        .map(|e| e.path(index).to_str_lossy().into_owned())
+        .collect()
+}
+
+fn status_count(repo: &gix::Repository) -> crate::Result<usize> {
+    Ok(repo
+        .status(gix::progress::Discard)?
+        .into_index_worktree_iter(Vec::new())?
+        .filter_map(Result::ok)
+        .count())
+}
+
+#[test]
+fn set_list_add_and_reapply_in_cone_mode() -> crate::Result {
+    let (mut repo, _tmp) = crate::util::repo_rw("make_sparse_checkout_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    assert!(
+        repo.sparse_checkout_list()?.is_empty(),
+        "sparse checkout is disabled by default"
+    );
+    assert!(matches!(
+        repo.sparse_checkout_reapply(&AtomicBool::default()),
+        Err(gix::sparse_checkout::update::Error::NotEnabled)
+    ));
+
+    let out = repo.sparse_checkout_set(["a/b"], true, &AtomicBool::default())?;
+    assert_eq!(out.files_removed, 2);
+    assert_eq!(out.files_checked_out, 0);
+    assert!(out.kept_modified.is_empty());
+    assert_eq!(repo.sparse_checkout_list()?, ["a/b"]);
+    assert!(!workdir.join("d").exists(), "empty directories are removed as well");
+    assert!(workdir.join("a/file").is_file() && workdir.join("a/b/file").is_file());
+    assert_eq!(skipped_paths(&repo.open_index()?), ["d/e/file", "d/file"]);
+    assert_eq!(status_count(&repo)?, 0, "skipped files aren't considered deleted");
+
+    let config = std::fs::read(repo.common_dir().join("config"))?;
+    assert!(config.contains_str("sparseCheckout = true") && config.contains_str("sparseCheckoutCone = true"));
+    assert_eq!(
+        std::fs::read(repo.sparse_checkout_file())?.as_bstr(),
+        "/*\n!/*/\n/a/\n!/a/*/\n/a/b/\n"
+    );
+
+    let out = repo.sparse_checkout_add(["d/e"], &AtomicBool::default())?;
+    assert_eq!(
+        out.files_checked_out, 2,
+        "the files of parent directories are included as well"
+    );
+    assert_eq!(repo.sparse_checkout_list()?, ["a/b", "d/e"]);
+    assert_eq!(std::fs::read(workdir.join("d/e/file"))?, b"e\n");
+    assert!(skipped_paths(&repo.open_index()?).is_empty());
+    assert_eq!(status_count(&repo)?, 0, "checked out files are up-to-date");
+
+    let out = repo.sparse_checkout_reapply(&AtomicBool::default())?;
+    assert_eq!(out, Default::default(), "nothing changes if the patterns stay the same");
+    Ok(())
+}
+
+#[test]
+fn modified_files_are_kept() -> crate::Result {
+    let (mut repo, _tmp) = crate::util::repo_rw("make_sparse_checkout_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("d/file"), "modified")?;
+
+    let out = repo.sparse_checkout_set(["a"], true, &AtomicBool::default())?;
+    assert_eq!(out.files_removed, 1);
+    assert_eq!(out.kept_modified, ["d/file"]);
+    assert!(workdir.join("d/file").is_file());
+    assert!(!workdir.join("d/e").exists());
+    assert_eq!(skipped_paths(&repo.open_index()?), ["d/e/file"]);
+    assert_eq!(status_count(&repo)?, 1, "the kept file is still reported as modified");
+    Ok(())
+}
+
+#[test]
+fn non_cone_mode() -> crate::Result {
+    let (mut repo, _tmp) = crate::util::repo_rw("make_sparse_checkout_repo.sh")?;
+    let out = repo.sparse_checkout_set(["/*", "!/*/", "d/e/"], false, &AtomicBool::default())?;
+    assert_eq!(out.files_removed, 3);
+    assert_eq!(repo.sparse_checkout_list()?, ["/*", "!/*/", "d/e/"]);
+    assert_eq!(skipped_paths(&repo.open_index()?), ["a/b/file", "a/file", "d/file"]);
+    assert_eq!(status_count(&repo)?, 0);
+    Ok(())
+}
+
+#[test]
+fn sparse_index() -> crate::Result {
+    let (mut repo, tmp) = crate::util::repo_rw("make_sparse_checkout_repo.sh")?;
+    let mut config = std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.common_dir().join("config"))?;
+    std::io::Write::write_all(&mut config, b"[index]\n\tsparse = true\n")?;
+    repo = gix::open_opts(tmp.path(), crate::util::restricted())?;
+
+    let out = repo.sparse_checkout_set(["a/b"], true, &AtomicBool::default())?;
+    assert_eq!(out.sparse_directories, 1);
+    let index = repo.open_index()?;
+    assert!(index.is_sparse());
+    let sparse_dirs: Vec<_> = index
+        .entries()
+        .iter()
+        .filter(|e| e.mode.is_sparse())
+        .map(|e| e.path(&index).to_owned())
+        .collect();
+    assert_eq!(sparse_dirs, ["d/"], "excluded directories are collapsed");
+    assert_eq!(status_count(&repo)?, 0, "sparse directories aren't considered deleted");
+
+    let out = repo.sparse_checkout_add(["d"], &AtomicBool::default())?;
+    assert_eq!(out.files_checked_out, 2);
+    assert_eq!(out.sparse_directories, 0);
+    let index = repo.open_index()?;
+    assert!(!index.is_sparse(), "sparse directories are expanded when needed");
+    assert_eq!(index.entries().len(), 5);
+    assert_eq!(status_count(&repo)?, 0);
+    Ok(())
+}
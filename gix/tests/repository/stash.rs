@@ -0,0 +1,160 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{bstr::ByteSlice, stash};
+
+use super::switch::{git_status, repo_rw};
+use crate::util::git;
+
+#[test]
+fn push_and_pop_with_index_and_untracked_files() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("a"), "a-modified\n")?;
+    std::fs::write(workdir.join("b"), "b-staged\n")?;
+    git(&workdir, &["add", "b"])?;
+    std::fs::remove_file(workdir.join("dir/c"))?;
+    std::fs::write(workdir.join("untracked"), "u\n")?;
+    let status_before = git_status(&workdir)?;
+    assert_eq!(status_before, " M a\nM  b\n D dir/c\n?? untracked\n");
+
+    let id = repo
+        .stash_push(
+            None::<&str>,
+            stash::push::Options {
+                include_untracked: true,
+                ..Default::default()
+            },
+            &AtomicBool::default(),
+        )?
+        .expect("there are changes");
+    assert_eq!(git_status(&workdir)?, "", "all changes were removed");
+    let list = repo.stash_list()?;
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].id, id);
+    assert!(list[0].message.starts_with(b"WIP on main: "));
+    assert_eq!(
+        git(&workdir, &["stash", "list", "--format=%gd %gs"])?,
+        format!("stash@{{0}} {}\n", list[0].message),
+        "git sees the same stack"
+    );
+    assert_eq!(
+        repo.stash_show(0, true)?
+            .into_iter()
+            .map(|change| change.path.to_string())
+            .collect::<Vec<_>>(),
+        ["a", "b", "dir/c", "untracked"]
+    );
+
+    let out = repo.stash_pop(0, stash::apply::Options { index: true }, &AtomicBool::default())?;
+    assert!(out.conflicts.is_empty());
+    assert_eq!(out.files_removed, 1, "dir/c");
+    assert_eq!(git_status(&workdir)?, status_before, "the index was restored as well");
+    assert!(repo.stash_list()?.is_empty(), "the stash was dropped");
+    assert!(repo.try_find_reference("refs/stash")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn push_with_pathspec_and_keep_index_can_be_applied_by_git() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("a"), "a-staged\n")?;
+    git(&workdir, &["add", "a"])?;
+    std::fs::write(workdir.join("b"), "b-modified\n")?;
+    std::fs::write(workdir.join("dir/c"), "c-modified\n")?;
+
+    repo.stash_push(
+        ["a", "b"],
+        stash::push::Options {
+            message: Some("custom".into()),
+            keep_index: true,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?
+    .expect("there are changes");
+    assert_eq!(
+        git_status(&workdir)?,
+        "M  a\n M dir/c\n",
+        "paths outside of the pathspec are untouched, and the index is kept"
+    );
+    assert_eq!(git(&workdir, &["stash", "list", "--format=%gs"])?, "On main: custom\n");
+
+    git(&workdir, &["checkout", "--", "."])?;
+    git(&workdir, &["reset", "-q", "--hard"])?;
+    git(&workdir, &["stash", "apply", "--index"])?;
+    assert_eq!(git_status(&workdir)?, "M  a\n M b\n");
+    assert_eq!(std::fs::read(workdir.join("b"))?.as_bstr(), "b-modified\n");
+    Ok(())
+}
+
+#[test]
+fn apply_merges_with_changes_made_since_and_keeps_conflicting_stashes() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(workdir.join("shared"), "1\n2\n3\n4\nfive\n")?;
+    git(&workdir, &["stash", "push", "-q"])?;
+    git(&workdir, &["merge", "-q", "--ff-only", "conflicting"])?;
+
+    let out = repo.stash_apply(0, Default::default(), &AtomicBool::default())?;
+    assert!(out.conflicts.is_empty());
+    assert_eq!(std::fs::read(workdir.join("shared"))?.as_bstr(), "one\n2\n3\n4\nfive\n");
+    assert_eq!(git_status(&workdir)?, " M shared\n", "the index isn't changed");
+
+    let err = repo
+        .stash_apply(0, Default::default(), &AtomicBool::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, stash::apply::Error::LocalChangesWouldBeOverwritten { ref path } if path == "shared"),
+        "{err:?}"
+    );
+
+    git(&workdir, &["checkout", "--", "shared"])?;
+    std::fs::write(workdir.join("shared"), "one\n2\n3\n4\nFIVE\n")?;
+    git(&workdir, &["commit", "-q", "-am", "conflict"])?;
+    let out = repo.stash_pop(0, Default::default(), &AtomicBool::default())?;
+    assert_eq!(out.conflicts, ["shared"]);
+    assert_eq!(git_status(&workdir)?, "UU shared\n");
+    assert_eq!(repo.stash_list()?.len(), 1, "conflicting stashes are kept");
+    Ok(())
+}
+
+#[test]
+fn drop_and_clear() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    for content in ["one", "two", "three"] {
+        std::fs::write(workdir.join("a"), content)?;
+        git(&workdir, &["stash", "push", "-q", "-m", content])?;
+    }
+    let messages = |repo: &gix::Repository| -> crate::Result<Vec<String>> {
+        Ok(repo.stash_list()?.into_iter().map(|e| e.message.to_string()).collect())
+    };
+    assert_eq!(messages(&repo)?, ["On main: three", "On main: two", "On main: one"]);
+    let top = repo.stash_list()?[0].id;
+
+    repo.stash_drop(1)?;
+    assert_eq!(messages(&repo)?, ["On main: three", "On main: one"]);
+    assert_eq!(
+        git(&workdir, &["stash", "list", "--format=%gs"])?,
+        "On main: three\nOn main: one\n"
+    );
+    assert_eq!(repo.find_reference("refs/stash")?.id(), top);
+
+    repo.stash_drop(0)?;
+    assert_eq!(messages(&repo)?, ["On main: one"]);
+    assert_eq!(
+        git(&workdir, &["rev-parse", "stash@{0}"])?,
+        format!("{}\n", repo.stash_list()?[0].id),
+        "the ref points to the new top"
+    );
+    assert!(matches!(
+        repo.stash_drop(1),
+        Err(stash::Error::NoSuchEntry { index: 1 })
+    ));
+
+    repo.stash_clear()?;
+    assert!(repo.stash_list()?.is_empty());
+    assert_eq!(git(&workdir, &["stash", "list"])?, "");
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix::{
+    bstr::{BString, ByteSlice},
+    switch::{Error, Options, Target},
+};
+
+pub(super) fn repo_rw() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    crate::util::repo_rw("make_switch_repo.sh")
+}
+
+pub(super) fn git_status(workdir: &Path) -> crate::Result<BString> {
+    Ok(crate::util::git(workdir, &["status", "--porcelain"])?.into())
+}
+
+fn branch(name: &str) -> Target {
+    Target::Branch(name.try_into().expect("valid"))
+}
+
+fn last_reflog_message(repo: &gix::Repository) -> crate::Result<BString> {
+    let reference = repo.find_reference("HEAD")?;
+    let last = reference
+        .log_iter()
+        .rev()?
+        .expect("log present")
+        .next()
+        .expect("at least one line")?;
+    Ok(last.message.to_owned())
+}
+
+#[test]
+fn to_branch_updates_the_index_the_worktree_and_head() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+
+    let out = repo.switch(branch("refs/heads/other"), Options::default(), &AtomicBool::default())?;
+    assert_eq!(out.files_checked_out, 4, "a, crlf, dir/d and shared");
+    assert_eq!(out.files_removed, 1, "b");
+    assert!(out.conflicts.is_empty());
+    assert_eq!(repo.head_name()?.expect("on a branch").as_bstr(), "refs/heads/other");
+    assert_eq!(last_reflog_message(&repo)?, "checkout: moving from main to other");
+    assert_eq!(std::fs::read(workdir.join("a"))?.as_bstr(), "a-other\n");
+    assert_eq!(
+        std::fs::read(workdir.join("crlf"))?.as_bstr(),
+        "crlf-other\r\n",
+        "filters are applied"
+    );
+    assert!(!workdir.join("b").exists());
+    assert_eq!(git_status(&workdir)?, "");
+    Ok(())
+}
+
+#[test]
+fn detached_points_head_to_the_commit() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let target = repo.rev_parse_single("other")?.detach();
+
+    repo.switch(Target::Detached(target), Options::default(), &AtomicBool::default())?;
+    assert!(repo.head()?.is_detached());
+    assert_eq!(repo.head_id()?, target);
+    assert_eq!(
+        last_reflog_message(&repo)?,
+        format!("checkout: moving from main to {target}")
+    );
+    assert_eq!(git_status(&workdir)?, "");
+
+    repo.switch(branch("refs/heads/main"), Options::default(), &AtomicBool::default())?;
+    assert_eq!(
+        last_reflog_message(&repo)?,
+        format!("checkout: moving from {target} to main")
+    );
+    assert_eq!(git_status(&workdir)?, "");
+    Ok(())
+}
+
+#[test]
+fn local_changes_are_carried_over_if_safe_and_refused_otherwise() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let main = repo.head_id()?.detach();
+
+    std::fs::write(workdir.join("a"), "modified")?;
+    let err = repo
+        .switch(branch("refs/heads/other"), Options::default(), &AtomicBool::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::LocalChangesWouldBeOverwritten { ref path } if path == "a"),
+        "{err:?}"
+    );
+    assert_eq!(repo.head_id()?, main, "nothing changed");
+
+    std::fs::write(workdir.join("a"), "a\n")?;
+    std::fs::write(workdir.join("dir/d"), "untracked")?;
+    let err = repo
+        .switch(branch("refs/heads/other"), Options::default(), &AtomicBool::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::UntrackedWouldBeOverwritten { ref path } if path == "dir/d"),
+        "{err:?}"
+    );
+
+    std::fs::remove_file(workdir.join("dir/d"))?;
+    std::fs::write(workdir.join("dir/c"), "modified")?;
+    repo.switch(branch("refs/heads/other"), Options::default(), &AtomicBool::default())?;
+    assert_eq!(
+        git_status(&workdir)?,
+        " M dir/c\n",
+        "changes to files that are the same in both commits are carried over"
+    );
+
+    std::fs::write(workdir.join("a"), "modified")?;
+    let out = repo.switch(
+        branch("refs/heads/main"),
+        Options {
+            force: true,
+            ..Default::default()
+        },
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.files_checked_out, 5, "a, b, crlf, shared and the modified dir/c");
+    assert_eq!(out.files_removed, 1, "dir/d");
+    assert_eq!(git_status(&workdir)?, "", "force discards all local changes");
+    Ok(())
+}
+
+#[test]
+fn merge_carries_local_changes_into_the_target() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let workdir = repo.work_dir().expect("non-bare").to_owned();
+    let merge = Options {
+        merge: true,
+        ..Default::default()
+    };
+
+    std::fs::write(workdir.join("shared"), "ONE\n2\n3\n4\n5\n")?;
+    let out = repo.switch(branch("refs/heads/other"), merge, &AtomicBool::default())?;
+    assert!(out.conflicts.is_empty());
+    assert_eq!(std::fs::read(workdir.join("shared"))?.as_bstr(), "ONE\n2\n3\n4\nfive\n");
+    assert_eq!(git_status(&workdir)?, " M shared\n");
+
+    let out = repo.switch(branch("refs/heads/conflicting"), merge, &AtomicBool::default())?;
+    assert_eq!(out.conflicts, ["shared"]);
+    assert_eq!(
+        std::fs::read(workdir.join("shared"))?.as_bstr(),
+        "<<<<<<< conflicting\none\n=======\nONE\n>>>>>>> local\n2\n3\n4\n5\n"
+    );
+    assert_eq!(git_status(&workdir)?, "UU shared\n");
+    assert_eq!(
+        repo.head_name()?.expect("on a branch").as_bstr(),
+        "refs/heads/conflicting"
+    );
+
+    let err = repo
+        .switch(branch("refs/heads/main"), Options::default(), &AtomicBool::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::Unmerged { ref path } if path == "shared"),
+        "{err:?}"
+    );
+    Ok(())
+}
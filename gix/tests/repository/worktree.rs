@@ -336,3 +336,375 @@ fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
         );
     }
 }
+
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+mod mutation {
+    use std::{path::Path, sync::atomic::AtomicBool};
+
+    use gix::{
+        bstr::{BString, ByteSlice},
+        worktree::{add, prune},
+    };
+
+    fn git_worktree_list(repo_dir: &Path) -> crate::Result<Vec<super::baseline::Worktree>> {
+        let out = std::process::Command::new("git")
+            .args(["worktree", "list", "--porcelain"])
+            .current_dir(repo_dir)
+            .output()?;
+        assert!(out.status.success(), "{}", out.stderr.as_bstr());
+        let list = repo_dir.join("worktree-list.baseline");
+        std::fs::write(&list, out.stdout)?;
+        let res = super::Baseline::collect(repo_dir)?;
+        std::fs::remove_file(list)?;
+        Ok(res)
+    }
+
+    fn git_status_is_clean(work_dir: &Path) -> crate::Result<bool> {
+        let out = std::process::Command::new("git")
+            .args(["status", "--porcelain"])
+            .current_dir(work_dir)
+            .output()?;
+        assert!(out.status.success(), "{}", out.stderr.as_bstr());
+        Ok(out.stdout.is_empty())
+    }
+
+    fn writable_repo() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        // Worktrees refer to each other by absolute path, so they can't be copied from the read-only fixture.
+        let tmp = gix_testtools::scripted_fixture_writable_with_args(
+            "make_worktree_repo.sh",
+            None::<String>,
+            gix_testtools::Creation::ExecuteScript,
+        )?;
+        let repo = gix::open_opts(tmp.path().join("repo"), crate::restricted())?;
+        Ok((repo, tmp))
+    }
+
+    fn is_listed(list: &[super::baseline::Worktree], path: &Path) -> crate::Result<bool> {
+        let path = gix_path::realpath(path)?;
+        Ok(list.iter().any(|wt| wt.root == path))
+    }
+
+    #[test]
+    fn add_with_new_branch_is_compatible_with_git() -> crate::Result {
+        if gix_testtools::should_skip_as_git_version_is_smaller_than(2, 31, 0) {
+            return Ok(());
+        }
+        let (repo, tmp) = writable_repo()?;
+        let path = tmp.path().join("wt-new");
+        let wt_repo = repo.worktree_add(
+            &path,
+            add::Target::NewBranch {
+                name: "refs/heads/new".try_into()?,
+                start: None,
+            },
+            add::Options::default(),
+            &AtomicBool::default(),
+        )?;
+
+        assert_eq!(wt_repo.head_name()?.expect("on branch").as_bstr(), "refs/heads/new");
+        assert_eq!(wt_repo.head_id()?, repo.head_id()?);
+        assert_eq!(
+            repo.find_reference("refs/heads/new")?.id(),
+            repo.head_id()?,
+            "the branch was created"
+        );
+        for file in ["a", "b", "dir/c"] {
+            assert!(path.join(file).is_file(), "{file} was checked out");
+        }
+
+        let private_dir = repo.common_dir().join("worktrees").join("wt-new");
+        assert_eq!(std::fs::read(private_dir.join("commondir"))?.as_bstr(), "../..\n");
+        assert_eq!(
+            gix_discover::path::from_plain_file(&private_dir.join("gitdir")).expect("present")?,
+            gix_path::realpath(&path)?.join(".git")
+        );
+        assert!(
+            !private_dir.join("locked").exists(),
+            "the lock is only held during creation"
+        );
+
+        let list = git_worktree_list(repo.work_dir().expect("non-bare"))?;
+        let entry = list
+            .iter()
+            .find(|wt| wt.root == gix_path::realpath(&path).expect("valid"))
+            .expect("git sees the new worktree");
+        assert_eq!(entry.branch.as_ref().expect("set").as_bstr(), "refs/heads/new");
+        assert!(git_status_is_clean(&path)?, "git agrees with our index");
+
+        let err = repo
+            .worktree_add(
+                tmp.path().join("other"),
+                add::Target::NewBranch {
+                    name: "refs/heads/new".try_into()?,
+                    start: None,
+                },
+                add::Options::default(),
+                &AtomicBool::default(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, add::Error::BranchExists { .. }), "{err:?}");
+
+        let err = repo
+            .worktree_add(
+                &path,
+                add::Target::Detached(repo.head_id()?.detach()),
+                add::Options::default(),
+                &AtomicBool::default(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, add::Error::PathExists { .. }), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn add_existing_branch_fails_if_it_is_checked_out_unless_forced() -> crate::Result {
+        let (repo, tmp) = writable_repo()?;
+        let path = tmp.path().join("wt-main");
+        let err = repo
+            .worktree_add(
+                &path,
+                add::Target::Branch("refs/heads/main".try_into()?),
+                add::Options::default(),
+                &AtomicBool::default(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, add::Error::BranchCheckedOut { .. }), "{err:?}");
+        assert!(!path.exists(), "nothing was created");
+        assert!(
+            !repo.common_dir().join("worktrees").join("wt-main").exists(),
+            "nothing was created in the common dir either"
+        );
+
+        let wt_repo = repo.worktree_add(
+            &path,
+            add::Target::Branch("refs/heads/main".try_into()?),
+            add::Options {
+                force: true,
+                ..Default::default()
+            },
+            &AtomicBool::default(),
+        )?;
+        assert_eq!(wt_repo.head_name()?.expect("on branch").as_bstr(), "refs/heads/main");
+        assert!(path.join("dir/c").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn add_detached_without_checkout_and_locked() -> crate::Result {
+        if gix_testtools::should_skip_as_git_version_is_smaller_than(2, 31, 0) {
+            return Ok(());
+        }
+        let (repo, tmp) = writable_repo()?;
+        let path = tmp.path().join("wt-detached");
+        let first_commit = repo.rev_parse_single("HEAD~1")?.detach();
+        let wt_repo = repo.worktree_add(
+            &path,
+            add::Target::Detached(first_commit),
+            add::Options {
+                id: Some("custom".into()),
+                checkout: false,
+                lock_reason: Some("on removable media".into()),
+                force: false,
+            },
+            &AtomicBool::default(),
+        )?;
+        assert!(wt_repo.head()?.is_detached());
+        assert_eq!(wt_repo.head_id()?, first_commit);
+        assert!(!path.join("a").exists(), "nothing was checked out");
+        assert!(!wt_repo.index_path().exists(), "no index was written");
+
+        let proxy = repo
+            .worktrees()?
+            .into_iter()
+            .find(|wt| wt.id() == "custom")
+            .expect("present");
+        assert_eq!(proxy.lock_reason().expect("locked"), "on removable media");
+
+        let list = git_worktree_list(repo.work_dir().expect("non-bare"))?;
+        let entry = list
+            .iter()
+            .find(|wt| wt.root == gix_path::realpath(&path).expect("valid"))
+            .expect("git sees the new worktree");
+        assert_eq!(entry.locked.as_ref().expect("locked").as_bstr(), "on removable media");
+        assert_eq!(entry.peeled, first_commit);
+        assert_eq!(entry.branch, None);
+
+        let err = repo
+            .worktree_add(
+                tmp.path().join("other"),
+                add::Target::Detached(first_commit),
+                add::Options {
+                    id: Some("custom".into()),
+                    ..Default::default()
+                },
+                &AtomicBool::default(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, add::Error::IdExists { .. }), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn lock_and_unlock() -> crate::Result {
+        let (repo, _tmp) = writable_repo()?;
+        let worktrees = repo.worktrees()?;
+        let locked = worktrees.iter().find(|wt| wt.id() == "wt-c-locked").expect("present");
+        let err = locked.lock(None).unwrap_err();
+        assert!(
+            matches!(err, gix::worktree::proxy::lock::Error::AlreadyLocked { .. }),
+            "{err:?}"
+        );
+        assert!(locked.unlock()?);
+        assert!(!locked.is_locked());
+        assert!(!locked.unlock()?, "it's not locked anymore");
+
+        let wt = worktrees.iter().find(|wt| wt.id() == "wt-b").expect("present");
+        wt.lock(Some("reason".into()))?;
+        assert_eq!(wt.lock_reason(), Some(BString::from("reason")));
+        assert_eq!(std::fs::read(wt.git_dir().join("locked"))?.as_bstr(), "reason\n");
+        assert!(wt.unlock()?);
+        wt.lock(None)?;
+        assert!(wt.is_locked());
+        assert_eq!(wt.lock_reason(), Some(BString::default()), "no reason is an empty one");
+        Ok(())
+    }
+
+    #[test]
+    fn move_to_updates_administrative_files() -> crate::Result {
+        if gix_testtools::should_skip_as_git_version_is_smaller_than(2, 31, 0) {
+            return Ok(());
+        }
+        let (repo, tmp) = writable_repo()?;
+        let worktrees = repo.worktrees()?;
+        let wt = worktrees.iter().find(|wt| wt.id() == "wt-b").expect("present");
+        let destination = tmp.path().join("moved");
+        std::fs::create_dir(&destination)?;
+
+        let new_base = wt.move_to(&destination)?;
+        assert_eq!(
+            new_base,
+            destination.join("wt-b"),
+            "existing directories are moved into"
+        );
+        assert!(!tmp.path().join("wt-b").exists());
+        assert_eq!(wt.base()?, gix_path::realpath(&new_base)?);
+
+        let list = git_worktree_list(repo.work_dir().expect("non-bare"))?;
+        assert!(
+            is_listed(&list, &new_base)?,
+            "git sees the worktree at its new location"
+        );
+        assert!(git_status_is_clean(&new_base)?);
+
+        let locked = worktrees.iter().find(|wt| wt.id() == "wt-c-locked").expect("present");
+        let err = locked.move_to(tmp.path().join("somewhere")).unwrap_err();
+        assert!(
+            matches!(err, gix::worktree::proxy::move_to::Error::Locked { .. }),
+            "{err:?}"
+        );
+
+        std::fs::create_dir(destination.join("wt-a"))?;
+        let other = worktrees.iter().find(|wt| wt.id() == "wt-a").expect("present");
+        let err = other.move_to(&destination).unwrap_err();
+        assert!(
+            matches!(err, gix::worktree::proxy::move_to::Error::DestinationExists { .. }),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn repair_after_manual_move() -> crate::Result {
+        if gix_testtools::should_skip_as_git_version_is_smaller_than(2, 31, 0) {
+            return Ok(());
+        }
+        let (repo, tmp) = writable_repo()?;
+        let new_base = tmp.path().join("wt-b-moved-manually");
+        std::fs::rename(tmp.path().join("wt-b"), &new_base)?;
+
+        assert!(repo.worktree_repair_at(&new_base)?, "the gitdir file needed an update");
+        assert!(!repo.worktree_repair_at(&new_base)?, "nothing to do anymore");
+        let list = git_worktree_list(repo.work_dir().expect("non-bare"))?;
+        assert!(is_listed(&list, &new_base)?);
+        assert!(git_status_is_clean(&new_base)?);
+
+        let worktrees = repo.worktrees()?;
+        let wt = worktrees.iter().find(|wt| wt.id() == "wt-a1").expect("present");
+        let dot_git = wt.base()?.join(".git");
+        std::fs::write(&dot_git, "gitdir: /does/not/exist\n")?;
+        assert!(wt.repair()?, "the .git file was rewritten");
+        assert!(!wt.repair()?);
+        assert!(git_status_is_clean(&wt.base()?)?);
+        Ok(())
+    }
+
+    #[test]
+    fn remove_refuses_dirty_worktrees_unless_forced() -> crate::Result {
+        let (repo, tmp) = writable_repo()?;
+        let find = |id: &str| {
+            repo.worktrees()
+                .expect("valid")
+                .into_iter()
+                .find(|wt| wt.id() == id)
+                .expect("present")
+        };
+
+        let base = tmp.path().join("wt-b");
+        std::fs::write(base.join("a"), "changed")?;
+        let err = find("wt-b").remove(false).unwrap_err();
+        assert!(
+            matches!(err, gix::worktree::proxy::remove::Error::Dirty { .. }),
+            "{err:?}"
+        );
+        assert!(base.is_dir(), "nothing was removed");
+
+        find("wt-b").remove(true)?;
+        assert!(!base.exists());
+        assert!(!repo.common_dir().join("worktrees/wt-b").exists());
+
+        let err = find("wt-a").remove(false).unwrap_err();
+        assert!(
+            matches!(err, gix::worktree::proxy::remove::Error::Dirty { .. }),
+            "untracked files, like the nested worktree, make it dirty as well"
+        );
+
+        find("wt-a1").remove(false)?;
+        assert!(!tmp.path().join("prev/wt-a").exists(), "clean worktrees are removed");
+
+        let err = find("wt-c-locked").remove(true).unwrap_err();
+        assert!(
+            matches!(err, gix::worktree::proxy::remove::Error::Locked { .. }),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn prune_removes_worktrees_whose_checkout_is_gone() -> crate::Result {
+        let (repo, tmp) = writable_repo()?;
+        let expected = vec![prune::Pruned {
+            id: "wt-deleted".into(),
+            reason: prune::Reason::GitDirPointsToNonExistingLocation,
+        }];
+
+        let pruned = repo.worktrees_prune(prune::Options {
+            expire: Some(std::time::UNIX_EPOCH),
+            dry_run: false,
+        })?;
+        assert!(pruned.is_empty(), "the gitdir file is more recent than the expiry time");
+
+        std::fs::remove_dir_all(tmp.path().join("wt-c-locked"))?;
+        let pruned = repo.worktrees_prune(prune::Options {
+            expire: None,
+            dry_run: true,
+        })?;
+        assert_eq!(pruned, expected, "locked worktrees are never pruned");
+        assert!(repo.common_dir().join("worktrees/wt-deleted").is_dir(), "dry-run");
+
+        let pruned = repo.worktrees_prune(prune::Options::default())?;
+        assert_eq!(pruned, expected);
+        assert!(!repo.common_dir().join("worktrees/wt-deleted").exists());
+        assert_eq!(repo.worktrees()?.len(), 5, "all other worktrees are still present");
+        Ok(())
+    }
+}
@@ -13,7 +13,7 @@ mod traverse;
 mod peel;
 
 mod sibling_branch {
-    use crate::revision::spec::from_bytes::{parse_spec, repo};
+    use crate::revision::spec::from_bytes::{parse_spec, repo, rev_parse};
     use crate::util::hex_to_id;
 
     #[test]
@@ -35,6 +35,30 @@ mod sibling_branch {
         }
         Ok(())
     }
+
+    #[test]
+    fn of_prior_checkout() -> crate::Result {
+        let repo = repo("complex_graph").unwrap();
+        for op in ["upstream", "push"] {
+            let spec = format!("@{{-2}}@{{{op}}}");
+            let actual = rev_parse(&spec, &repo)?;
+            assert_eq!(
+                actual.first_reference().expect("set").name.as_bstr(),
+                "refs/remotes/origin/main",
+                "{spec}: the tracking branch of the prior checkout 'main' is used"
+            );
+            assert_eq!(
+                actual.single().expect("just one"),
+                hex_to_id("55e825ebe8fd2ff78cad3826afb696b96b576a7e")
+            );
+        }
+
+        assert_eq!(
+            rev_parse("@{-1}@{upstream}", &repo).unwrap_err().to_string(),
+            "Branch named refs/heads/i does not have a fetch tracking branch configured"
+        );
+        Ok(())
+    }
 }
 
 mod index {
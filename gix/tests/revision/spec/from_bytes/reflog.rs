@@ -4,12 +4,12 @@ use gix::{
 };
 
 use crate::{
-    revision::spec::from_bytes::{parse_spec, parse_spec_no_baseline, repo},
+    revision::spec::from_bytes::{parse_spec, parse_spec_no_baseline, repo, rev_parse},
     util::hex_to_id,
 };
 
 #[test]
-fn nth_prior_checkout() {
+fn nth_prior_checkout() -> crate::Result {
     let repo = repo("complex_graph").unwrap();
 
     for (spec, prior_branch) in [
@@ -22,12 +22,18 @@ fn nth_prior_checkout() {
         let parsed = parse_spec(spec, &repo).unwrap_or_else(|_| panic!("{spec} to be parsed successfully"));
         assert_eq!(parsed.first_reference().expect("present").name.as_bstr(), prior_branch);
         assert_eq!(parsed.second_reference(), None);
+        assert_eq!(
+            rev_parse(spec, &repo)?,
+            parsed,
+            "{spec}: the repository resolves it the same way"
+        );
     }
 
     assert_eq!(
         parse_spec("@{-6}", &repo).unwrap_err().to_string(),
         "HEAD has 5 prior checkouts and checkout number 6 is out of range"
     );
+    Ok(())
 }
 
 #[test]
@@ -78,10 +84,51 @@ fn by_index() {
 }
 
 #[test]
-fn by_date_is_planned_until_git_date_crate_is_implements_parsing() {
-    let repo = repo("complex_graph").unwrap();
+fn by_date() {
+    let repo = &repo("complex_graph").unwrap();
+    for (spec, expected) in [
+        (
+            "main@{2005-04-07 22:15:13 +0000}",
+            "a8fbd8e0c8753ef07ccdbfa93da030d53b68f0bc",
+        ),
+        ("main@{1112912200 +0000}", "a8fbd8e0c8753ef07ccdbfa93da030d53b68f0bc"),
+        ("main@{now}", "55e825ebe8fd2ff78cad3826afb696b96b576a7e"),
+        ("main@{2.weeks.ago}", "55e825ebe8fd2ff78cad3826afb696b96b576a7e"),
+        ("@{1 year ago}", "55e825ebe8fd2ff78cad3826afb696b96b576a7e"),
+        ("HEAD@{1112912053 -0700}", "0270e757e023fedde198947489215e34bdaf1502"),
+    ] {
+        let parsed = parse_spec_no_baseline(spec, repo).unwrap_or_else(|err| panic!("{spec}: {err}"));
+        assert_eq!(parsed, Spec::from_id(hex_to_id(expected).attach(repo)), "{spec}");
+    }
+
+    assert_eq!(
+        parse_spec_no_baseline("main@{1112911000 +0000}", repo).unwrap(),
+        Spec::from_id(hex_to_id("9f9eac6bd1cd4b4cc6a494f044b28c985a22972b").attach(repo)),
+        "dates before the first entry resolve to the first entry if the reference was created by it"
+    );
     assert!(matches!(
-        parse_spec_no_baseline("main@{1979-02-26 18:30:00}", &repo).unwrap_err(),
-        Error::Planned { .. }
+        parse_spec_no_baseline("main@{not a date}", repo).unwrap_err(),
+        Error::Parse(_)
     ));
 }
+
+#[test]
+fn by_date_without_entries() -> crate::Result {
+    let (repo, _tmp) = crate::util::repo_rw("make_basic_repo.sh")?;
+    std::fs::write(repo.path().join("logs/HEAD"), "")?;
+    assert_eq!(
+        parse_spec_no_baseline("HEAD@{yesterday}", &repo)
+            .unwrap_err()
+            .to_string(),
+        "Reference \"HEAD\" has no ref-log entries, cannot lookup entry by date"
+    );
+
+    std::fs::remove_file(repo.path().join("logs/HEAD"))?;
+    assert_eq!(
+        parse_spec_no_baseline("HEAD@{yesterday}", &repo)
+            .unwrap_err()
+            .to_string(),
+        "Reference \"HEAD\" does not have a reference log, cannot lookup entry by date"
+    );
+    Ok(())
+}
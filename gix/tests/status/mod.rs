@@ -46,6 +46,54 @@ mod index_worktree {
             Ok(())
         }
 
+        #[test]
+        fn file_stats() -> crate::Result {
+            let repo = submodule_repo("modified-untracked-and-submodule-head-changed-and-modified")?;
+            let status = repo
+                .status(gix::progress::Discard)?
+                .index_worktree_options_mut(|opts| {
+                    opts.sorting =
+                        Some(gix::status::plumbing::index_as_worktree_with_renames::Sorting::ByPathCaseSensitive);
+                })
+                .into_index_worktree_iter(Vec::new())?;
+            let mut cache = repo.diff_resource_cache(
+                gix_diff::blob::pipeline::Mode::ToGit,
+                gix_diff::blob::pipeline::WorktreeRoots {
+                    old_root: None,
+                    new_root: repo.work_dir().map(ToOwned::to_owned),
+                },
+            )?;
+            let mut stats = Vec::new();
+            for item in status {
+                if let Some(stat) = item?.file_stat(&mut cache, &repo.objects)? {
+                    stats.push(stat);
+                }
+            }
+            assert_eq!(
+                stats,
+                [
+                    gix_diff::stat::File {
+                        location: "this".into(),
+                        source_location: None,
+                        counts: gix_diff::stat::Counts::Lines {
+                            insertions: 1,
+                            removals: 0
+                        },
+                    },
+                    gix_diff::stat::File {
+                        location: "untracked".into(),
+                        source_location: None,
+                        counts: gix_diff::stat::Counts::Lines {
+                            insertions: 0,
+                            removals: 0
+                        },
+                    }
+                ],
+                "the submodule modification isn't a change to file contents, and the untracked file is empty"
+            );
+            Ok(())
+        }
+
         #[test]
         fn untracked_files_collapse_by_default() -> crate::Result {
             let repo = repo("untracked-only")?;
@@ -89,6 +137,166 @@ mod index_worktree {
             Ok(())
         }
 
+        #[test]
+        fn untracked_cache_is_written_and_used() -> crate::Result {
+            let tmp = gix_testtools::scripted_fixture_writable("make_status_repos.sh")?;
+            let repo = gix::open_opts(
+                tmp.path().join("untracked-only"),
+                gix::open::Options::isolated()
+                    .config_overrides(["core.untrackedCache=true", "gitoxide.core.useNsec=true"]),
+            )?;
+            let status = |repo: &gix::Repository| -> crate::Result<_> {
+                let mut status = repo
+                    .status(gix::progress::Discard)?
+                    .index_worktree_options_mut(|opts| {
+                        opts.sorting =
+                            Some(gix::status::plumbing::index_as_worktree_with_renames::Sorting::ByPathCaseSensitive);
+                    })
+                    .into_index_worktree_iter(Vec::new())?;
+                let items: Vec<_> = status.by_ref().filter_map(Result::ok).collect();
+                Ok((items, status.into_outcome().expect("iteration done")))
+            };
+
+            let (expected_items, mut out) = status(&repo)?;
+            assert_eq!(expected_items.len(), 2);
+            assert!(out.has_changes(), "the untracked cache was created");
+            out.write_changes().expect("changes are present")?;
+
+            let index = repo.open_index()?;
+            let cache = index.untracked().expect("cache was written");
+            assert_eq!(
+                cache.dir_flags,
+                gix::index::extension::untracked_cache::dir_flags::SHOW_OTHER_DIRECTORIES
+                    | gix::index::extension::untracked_cache::dir_flags::HIDE_EMPTY_DIRECTORIES
+            );
+            assert!(cache.identifier.starts_with(b"Location "));
+
+            let (items, out) = status(&repo)?;
+            assert_eq!(items, expected_items, "the cache doesn't change the result");
+            assert_eq!(
+                out.index_worktree
+                    .dirwalk
+                    .as_ref()
+                    .expect("dirwalk performed")
+                    .read_dir_calls,
+                0,
+                "all directories are known to be unchanged"
+            );
+            assert!(!out.has_changes(), "the cache is still up-to-date");
+
+            let repo = gix::open_opts(
+                repo.work_dir().expect("non-bare"),
+                gix::open::Options::isolated().config_overrides(["core.untrackedCache=false"]),
+            )?;
+            let (items, mut out) = status(&repo)?;
+            assert_eq!(items, expected_items);
+            assert!(out.has_changes(), "the cache is removed");
+            out.write_changes().expect("changes are present")?;
+            assert!(repo.open_index()?.untracked().is_none());
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn fsmonitor_hook_limits_work_to_reported_paths() -> crate::Result {
+            use std::os::unix::fs::PermissionsExt;
+            let tmp = gix_testtools::scripted_fixture_writable("make_status_repos.sh")?;
+            let reported = tmp.path().join("reported");
+            std::fs::write(&reported, "")?;
+            let hook = tmp.path().join("fsmonitor-hook");
+            std::fs::write(
+                &hook,
+                format!("#!/bin/sh\nprintf 'token\\0'\ncat '{}'\n", reported.display()),
+            )?;
+            std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755))?;
+            let repo = gix::open_opts(
+                tmp.path().join("untracked-only"),
+                gix::open::Options::isolated().config_overrides([
+                    format!("core.fsmonitor={}", hook.display()),
+                    "core.untrackedCache=true".into(),
+                    "gitoxide.core.useNsec=true".into(),
+                ]),
+            )?;
+            let status = |repo: &gix::Repository| -> crate::Result<_> {
+                let mut status = repo
+                    .status(gix::progress::Discard)?
+                    .index_worktree_options_mut(|opts| {
+                        opts.sorting =
+                            Some(gix::status::plumbing::index_as_worktree_with_renames::Sorting::ByPathCaseSensitive);
+                    })
+                    .into_index_worktree_iter(Vec::new())?;
+                let items: Vec<_> = status.by_ref().filter_map(Result::ok).collect();
+                let mut out = status.into_outcome().expect("iteration done");
+                assert!(out.has_changes(), "the token changes with each query");
+                out.write_changes().expect("changes are present")?;
+                Ok((items, out))
+            };
+
+            let (expected_items, out) = status(&repo)?;
+            assert_eq!(expected_items.len(), 2, "two untracked entries");
+            assert_eq!(
+                out.index_worktree
+                    .tracked_file_modification
+                    .entries_skipped_by_entry_flags,
+                0,
+                "without token, everything is checked"
+            );
+            assert!(repo.open_index()?.fs_monitor().is_some(), "the token was stored");
+
+            let (items, out) = status(&repo)?;
+            assert_eq!(items, expected_items);
+            let tracked = &out.index_worktree.tracked_file_modification;
+            assert_eq!(
+                tracked.entries_skipped_by_entry_flags, tracked.entries_to_process,
+                "nothing was reported, so no entry has to be checked"
+            );
+            assert_eq!(
+                out.index_worktree
+                    .dirwalk
+                    .as_ref()
+                    .expect("dirwalk performed")
+                    .read_dir_calls,
+                0,
+                "and no directory has to be read"
+            );
+
+            let this = repo.work_dir().expect("non-bare").join("this");
+            std::fs::write(&this, "changed")?;
+            let (items, _out) = status(&repo)?;
+            assert_eq!(
+                items, expected_items,
+                "changes that aren't reported by the filesystem monitor aren't seen"
+            );
+
+            std::fs::write(&reported, "this\0")?;
+            let (items, out) = status(&repo)?;
+            assert_eq!(items.len(), 3, "the reported modification shows up");
+            assert_eq!(items[2].rela_path(), "this");
+            assert_eq!(
+                out.index_worktree
+                    .tracked_file_modification
+                    .entries_skipped_by_entry_flags,
+                out.index_worktree.tracked_file_modification.entries_to_process - 1,
+            );
+
+            std::fs::write(&reported, "")?;
+            let (items, _out) = status(&repo)?;
+            assert_eq!(
+                items.len(),
+                3,
+                "modified entries stay modified even if they aren't reported again"
+            );
+
+            let repo = gix::open_opts(repo.work_dir().expect("non-bare"), gix::open::Options::isolated())?;
+            let (items, _out) = status(&repo)?;
+            assert_eq!(items.len(), 3, "without fsmonitor, everything is checked");
+            assert!(
+                repo.open_index()?.fs_monitor().is_none(),
+                "and the extension is removed"
+            );
+            Ok(())
+        }
+
         #[test]
         fn untracked_files_settings_none() -> crate::Result {
             let mut repo = repo("untracked-only")?;
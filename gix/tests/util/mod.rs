@@ -1,5 +1,7 @@
 #![allow(clippy::result_large_err)]
-use gix::{open, Repository, ThreadSafeRepository};
+use std::path::Path;
+
+use gix::{bstr::ByteSlice, open, Repository, ThreadSafeRepository};
 use gix_testtools::tempfile;
 pub use gix_testtools::Result;
 
@@ -80,3 +82,20 @@ pub fn basic_repo() -> Result<Repository> {
 pub fn basic_rw_repo() -> Result<(Repository, tempfile::TempDir)> {
     repo_rw("make_basic_repo.sh")
 }
+
+/// Run `git` with `args` in `workdir` with a fixed identity and an editor that accepts everything, and return its output.
+pub fn git_output(workdir: &Path, args: &[&str]) -> Result<std::process::Output> {
+    Ok(std::process::Command::new("git")
+        .args(["-c", "user.name=author", "-c", "user.email=author@example.com"])
+        .args(args)
+        .env("GIT_EDITOR", "true")
+        .current_dir(workdir)
+        .output()?)
+}
+
+/// Like [`git_output()`], but assert that `git` succeeds and return its standard output.
+pub fn git(workdir: &Path, args: &[&str]) -> Result<String> {
+    let out = git_output(workdir, args)?;
+    assert!(out.status.success(), "{:?}: {}", args, out.stderr.as_bstr());
+    Ok(out.stdout.to_str()?.to_owned())
+}
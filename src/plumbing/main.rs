@@ -16,8 +16,8 @@ use gix::bstr::{io::BufReadExt, BString};
 
 use crate::plumbing::{
     options::{
-        attributes, commit, commitgraph, config, credential, exclude, free, fsck, index, mailmap, odb, revision, tree,
-        Args, Subcommands,
+        attributes, commit, commitgraph, config, credential, exclude, free, fsck, index, mailmap, odb, revision, tag,
+        tree, Args, Subcommands,
     },
     show_progress,
 };
@@ -395,6 +395,50 @@ pub fn main() -> Result<()> {
                 },
             )
             .map(|_| ()),
+            commitgraph::Subcommands::Write {
+                reachable: _,
+                stdin_commits,
+                split,
+                size_multiple,
+                max_commits,
+                changed_paths,
+                no_changed_paths,
+            } => prepare_and_run(
+                "commitgraph-write",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, err| {
+                    use gix::commitgraph::write::{Split, SplitStrategy};
+                    let split = split.map(|strategy| Split {
+                        strategy: match strategy {
+                            commitgraph::SplitStrategy::Merge => SplitStrategy::Merge,
+                            commitgraph::SplitStrategy::NoMerge => SplitStrategy::NoMerge,
+                            commitgraph::SplitStrategy::Replace => SplitStrategy::Replace,
+                        },
+                        size_multiple,
+                        max_commits,
+                    });
+                    core::repository::commitgraph::write(
+                        repository(Mode::Lenient)?,
+                        stdin_commits
+                            .then(|| {
+                                stdin_or_bail().map(|stdin| {
+                                    stdin.byte_lines().filter_map(Result::ok).map(gix::bstr::BString::from)
+                                })
+                            })
+                            .transpose()?,
+                        split,
+                        (changed_paths || no_changed_paths).then_some(changed_paths),
+                        out,
+                        err,
+                        format,
+                    )
+                },
+            )
+            .map(|_| ()),
             commitgraph::Subcommands::Verify { statistics } => prepare_and_run(
                 "commitgraph-verify",
                 trace,
@@ -1080,6 +1124,19 @@ pub fn main() -> Result<()> {
                 },
             ),
         },
+        Subcommands::Tag(cmd) => match cmd {
+            tag::Subcommands::Verify { rev_spec } => prepare_and_run(
+                "tag-verify",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, err| {
+                    core::repository::tag::verify(repository(Mode::Lenient)?, &rev_spec, out, err)
+                },
+            ),
+        },
         Subcommands::Commit(cmd) => match cmd {
             commit::Subcommands::Verify { rev_spec } => prepare_and_run(
                 "commit-verify",
@@ -1088,8 +1145,8 @@ pub fn main() -> Result<()> {
                 progress,
                 progress_keep_open,
                 None,
-                move |_progress, _out, _err| {
-                    core::repository::commit::verify(repository(Mode::Lenient)?, rev_spec.as_deref())
+                move |_progress, out, err| {
+                    core::repository::commit::verify(repository(Mode::Lenient)?, rev_spec.as_deref(), out, err)
                 },
             ),
             commit::Subcommands::Describe {
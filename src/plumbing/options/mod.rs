@@ -97,6 +97,9 @@ pub enum Subcommands {
     /// Interact with commit objects.
     #[clap(subcommand)]
     Commit(commit::Subcommands),
+    /// Interact with tag objects.
+    #[clap(subcommand)]
+    Tag(tag::Subcommands),
     /// Verify the integrity of the entire repository
     Verify {
         #[clap(flatten)]
@@ -671,6 +674,17 @@ pub mod tree {
     }
 }
 
+pub mod tag {
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Verify the signature of a tag.
+        Verify {
+            /// A specification of the tag object to verify, like the name of the tag.
+            rev_spec: String,
+        },
+    }
+}
+
 pub mod commit {
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
@@ -750,6 +764,42 @@ pub mod commitgraph {
             #[clap(default_value = "@")]
             spec: std::ffi::OsString,
         },
+        /// Write a commit-graph file or a new layer of a split commit-graph chain.
+        Write {
+            /// Write all commits reachable from references.
+            #[clap(long, conflicts_with = "stdin_commits")]
+            reachable: bool,
+            /// Write all commits reachable from the commits whose hexadecimal ids are read from stdin, one per line.
+            #[clap(long)]
+            stdin_commits: bool,
+            /// Write a new layer of a split commit-graph chain with all commits that aren't yet in the commit-graph.
+            #[clap(long, value_enum, num_args = 0..=1, require_equals = true, default_missing_value = "merge")]
+            split: Option<SplitStrategy>,
+            /// Merge the topmost layer into the new one unless it has at least this many times more commits.
+            #[clap(long, default_value_t = 2, requires = "split")]
+            size_multiple: u32,
+            /// Keep merging layers into the new one while it contains more than this amount of commits.
+            #[clap(long, requires = "split")]
+            max_commits: Option<u32>,
+            /// Compute and write changed-path Bloom filters to accelerate path-limited history traversals.
+            ///
+            /// By default, they are only written if the existing commit-graph has them.
+            #[clap(long, conflicts_with = "no_changed_paths")]
+            changed_paths: bool,
+            /// Don't write changed-path Bloom filters, even if the existing commit-graph has them.
+            #[clap(long)]
+            no_changed_paths: bool,
+        },
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+    pub enum SplitStrategy {
+        /// Merge existing layers into the new one according to `--size-multiple` and `--max-commits`.
+        Merge,
+        /// Never merge existing layers.
+        NoMerge,
+        /// Merge all existing layers into a single one.
+        Replace,
     }
 }
 
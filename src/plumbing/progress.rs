@@ -94,14 +94,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "core.looseCompression",
         usage: Planned("")
     },
-    Record {
-        config: "core.sparseCheckout",
-        usage: Planned("We want to support huge repos and be the fastest in doing so")
-    },
-    Record {
-        config: "core.sparseCheckoutCone",
-        usage: Planned("This is a nice improvement over 'sparseCheckout' alone and should one day be available too")
-    },
     Record {
         config: "core.gitProxy",
         usage: NotPlanned("The transport mechanism works differently enough to not support it for now, but of course it's possible to add support if there is demand")
@@ -110,10 +102,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "checkout.defaultRemote",
         usage: Planned("Needed for correct checkout behaviour, similar to what git does")
     },
-    Record {
-        config: "core.untrackedCache",
-        usage: Planned("Needed for fast worktree operation")
-    },
     Record {
         config: "checkout.guess",
         usage: Planned("")
@@ -142,10 +130,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "commitGraph.readChangedPaths",
         usage: NotPlanned("Can be considered when the underlying feature is actually used or needed")
     },
-    Record {
-        config: "index.sparse",
-        usage: Planned("We can read sparse indices and support for it will be added early on")
-    },
     Record {
         config: "merge.renormalize",
         usage: Planned("Once merging is being implemented, renormalization should be respected")
@@ -205,7 +189,7 @@ static GIT_CONFIG: &[Record] = &[
     },
     Record {
         config: "clone.filterSubmodules,",
-        usage: Planned("Currently object filtering isn't supported, a prerequisite for this, see '--filter=blob:none' for more"),
+        usage: Planned("Object filters are supported, but submodules aren't cloned yet"),
 
     },
     Record {
@@ -270,14 +254,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "advice.updateSparsePath",
         usage: NotApplicable("gitoxide does not yet have an 'advice' system")
     },
-    Record {
-        config: "core.sparseCheckout",
-        usage: Planned("Together with 'index.sparse' and 'core.sparseCheckoutCone', configures if the index should be written sparse or not")
-    },
-    Record {
-        config: "core.sparseCheckoutCone",
-        usage: Planned("Non-cone mode is deprecated but should still fail gracefully if encountered")
-    },
     Record {
         config: "core.splitIndex",
         usage: NotPlanned("We don't want to be able to create split indices, but we will read them. It's (somewhat) superseded by sparse indices")
@@ -290,10 +266,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "splitIndex.sharedIndexExpire",
         usage: NotPlanned("Seems like it's superseded by sparse indices")
     },
-    Record {
-        config: "index.sparse",
-        usage: Planned("Together with 'core.sparseCheckout' and 'core.sparseCheckoutCone', configures if the index should be written sparse or not")
-    },
     Record {
         config: "index.version",
         usage: Planned("Once V4 indices can be written, we need to be able to set a desired version. For now we write the smallest possible index version only.")
@@ -406,14 +378,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "sparse.expectFilesOutsideOfPatterns",
         usage: NotPlanned("TODO")
     },
-    Record {
-        config: "remote.<name>.promisor",
-        usage: Planned("Required for big monorepos, and typically used in conjunction with sparse indices")
-    },
-    Record {
-        config: "remote.<name>.partialCloneFilter",
-        usage: Planned("Required for big monorepos, and typically used in conjunction with sparse indices")
-    },
     Record {
         config: "merge.renameLimit",
         usage: Planned("The same as 'diff.renameLimit'")
@@ -155,6 +155,28 @@ title "gix (with repository)"
       )
     )
   )
+
+  title "gix revision resolve"
+  (when "running 'revision resolve'"
+    snapshot="$snapshot/revision/resolve"
+    (small-repo-in-sandbox
+      {
+        git checkout dev
+        git checkout main
+        git remote add origin .
+        git fetch origin
+        git branch --set-upstream-to=origin/dev main
+        git branch --set-upstream-to=origin/main dev
+        git config push.default current
+      } &>/dev/null
+      (with "prior checkouts and their tracking branches"
+        it "resolves them like git" && {
+          WITH_SNAPSHOT="$snapshot/prior-checkout-and-tracking-branches" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose revision resolve -r @{-1} @{upstream} @{push} @{-1}@{upstream} @{-1}@{push}
+        }
+      )
+    )
+  )
 )
 
 title "gix attributes"